@@ -6,6 +6,15 @@
 //!
 //! This parses `target/criterion/**/new/estimates.json` (Criterion output) and computes
 //! Ghost-vs-stdlib ratios within the same run.
+//!
+//! Baseline gate:
+//! 1) `cargo bench -- --save-baseline main` (e.g. on the main branch)
+//! 2) `cargo bench` (on the change under test)
+//! 3) `cargo run --example bench_report -- --baseline main --threshold 1.10`
+//!
+//! With `--baseline NAME`, every benchmark present in both the current run and the
+//! saved baseline is compared instead, and the report exits non-zero if any of them
+//! got slower than `threshold` times its baseline.
 
 use std::{
     collections::BTreeMap,
@@ -33,6 +42,7 @@ fn main() {
     let mut criterion_dir: Option<PathBuf> = None;
     let mut threshold: f64 = 1.05;
     let mut stat: Stat = Stat::Mean;
+    let mut baseline: Option<String> = None;
 
     while let Some(arg) = args.next() {
         match arg.as_str() {
@@ -60,6 +70,15 @@ fn main() {
                     _ => usage_exit("invalid value for --stat (expected: mean|median)"),
                 };
             }
+            "--baseline" => {
+                let v = args
+                    .next()
+                    .unwrap_or_else(|| usage_exit("missing value for --baseline"));
+                if v == "new" {
+                    usage_exit("--baseline must name a saved baseline, not `new`");
+                }
+                baseline = Some(v);
+            }
             "--help" | "-h" => {
                 usage();
                 return;
@@ -73,11 +92,20 @@ fn main() {
         usage_exit("--threshold must be a finite float >= 1.0");
     }
 
-    let estimates = read_all_estimates(&criterion_dir).unwrap_or_else(|e| {
+    let estimates = read_all_estimates(&criterion_dir, "new").unwrap_or_else(|e| {
         eprintln!("error: failed to read criterion output: {e}");
         process::exit(2);
     });
 
+    if let Some(baseline) = baseline {
+        let saved = read_all_estimates(&criterion_dir, &baseline).unwrap_or_else(|e| {
+            eprintln!("error: failed to read criterion output: {e}");
+            process::exit(2);
+        });
+        baseline_gate(&criterion_dir, &baseline, &saved, &estimates, threshold, stat);
+        return;
+    }
+
     // Pairs: (ghost_key, std_key, label)
     let comparisons: &[(&str, &str, &str)] = &[
         (
@@ -260,13 +288,94 @@ fn main() {
     );
 }
 
+/// Compares the current run against a saved baseline, exiting with status 1 if any
+/// benchmark regressed beyond `threshold`.
+fn baseline_gate(
+    criterion_dir: &Path,
+    baseline: &str,
+    saved: &BTreeMap<String, Estimate>,
+    current: &BTreeMap<String, Estimate>,
+    threshold: f64,
+    stat: Stat,
+) {
+    let pick = |e: &Estimate| match stat {
+        Stat::Mean => e.mean_point_estimate_ns,
+        Stat::Median => e.median_point_estimate_ns,
+    };
+
+    let common: Vec<&String> = current.keys().filter(|k| saved.contains_key(*k)).collect();
+    if common.is_empty() {
+        eprintln!(
+            "error: no benchmark in `{}` has both current results and baseline `{baseline}`",
+            criterion_dir.display()
+        );
+        eprintln!(
+            "\nTip: save one with `cargo bench -- --save-baseline {baseline}`, then re-run `cargo bench`."
+        );
+        process::exit(2);
+    }
+
+    println!("Criterion dir: {}", criterion_dir.display());
+    println!("Baseline:      {baseline}");
+    println!(
+        "Threshold:     {:.4} (current/baseline must be <= threshold)\n",
+        threshold
+    );
+
+    println!(
+        "{:<58} {:>12} {:>12} {:>10}",
+        "benchmark", "base(ns)", "new(ns)", "ratio"
+    );
+    println!("{:-<96}", "");
+
+    let mut regressions = Vec::new();
+    for name in &common {
+        let b = pick(&saved[*name]);
+        let n = pick(&current[*name]);
+        let ratio = n / b;
+        let flag = if ratio.is_nan() || ratio > threshold {
+            regressions.push((*name, ratio));
+            "  <-- regression"
+        } else {
+            ""
+        };
+        println!("{:<58} {:>12.6} {:>12.6} {:>10.4}{flag}", name, b, n, ratio);
+    }
+
+    let unmatched = current.len() + saved.len() - 2 * common.len();
+    if unmatched > 0 {
+        println!("\n({unmatched} benchmark(s) present in only one of the two runs were skipped)");
+    }
+
+    if !regressions.is_empty() {
+        eprintln!(
+            "\nFAIL: {} benchmark(s) regressed beyond {:.4}x baseline `{baseline}`:",
+            regressions.len(),
+            threshold
+        );
+        for (name, ratio) in regressions {
+            eprintln!("  - {name} ({ratio:.4}x)");
+        }
+        process::exit(1);
+    }
+
+    println!(
+        "\nOK: all {} benchmarks are within {:.4}x baseline `{baseline}`.",
+        common.len(),
+        threshold
+    );
+}
+
 fn usage() {
     eprintln!(
-        "Usage: cargo run --example bench_report -- [--criterion-dir PATH] [--threshold FLOAT] [--stat mean|median]\n\
+        "Usage: cargo run --example bench_report -- [--criterion-dir PATH] [--threshold FLOAT] [--stat mean|median] [--baseline NAME]\n\
          \n\
          Defaults:\n\
          - criterion dir: target/criterion\n\
-         - threshold:     1.05\n"
+         - threshold:     1.05\n\
+         \n\
+         --baseline NAME compares the current run against `cargo bench -- --save-baseline NAME`\n\
+         instead of checking the Ghost/std ratio pairs.\n"
     );
 }
 
@@ -276,7 +385,9 @@ fn usage_exit(msg: &str) -> ! {
     process::exit(2)
 }
 
-fn read_all_estimates(root: &Path) -> Result<BTreeMap<String, Estimate>, String> {
+/// Reads `<bench>/<run>/estimates.json` for every benchmark under `root`, where `run`
+/// is `new` for the latest results or the name of a saved baseline.
+fn read_all_estimates(root: &Path, run: &str) -> Result<BTreeMap<String, Estimate>, String> {
     let mut out = BTreeMap::new();
     if !root.exists() {
         return Err(format!(
@@ -298,13 +409,13 @@ fn read_all_estimates(root: &Path) -> Result<BTreeMap<String, Estimate>, String>
             if path.file_name() != Some(OsStr::new("estimates.json")) {
                 continue;
             }
-            // Criterion layout is typically: <bench>/new/estimates.json
-            // We'll accept any .../new/estimates.json and name it by the parent-of-`new`.
+            // Criterion layout is typically: <bench>/<run>/estimates.json
+            // We'll accept any .../<run>/estimates.json and name it by the parent-of-`<run>`.
             let parent = match path.parent() {
                 Some(p) => p,
                 None => continue,
             };
-            if parent.file_name() != Some(OsStr::new("new")) {
+            if parent.file_name() != Some(OsStr::new(run)) {
                 continue;
             }
             let bench_dir = match parent.parent() {
@@ -0,0 +1,68 @@
+//! A durable key-value store on halo's allocator, written to by several threads.
+//!
+//! Run with `cargo run --example kv_store`.
+
+use halo::alloc::HaloAllocator;
+use halo::kv::KvStore;
+use halo::GhostToken;
+use std::fs::{self, File, OpenOptions};
+use std::io::{BufReader, BufWriter};
+
+#[global_allocator]
+static ALLOC: HaloAllocator = HaloAllocator;
+
+fn main() -> std::io::Result<()> {
+    let dir = std::env::temp_dir().join("halo_kv_example");
+    fs::create_dir_all(&dir)?;
+    let log_path = dir.join("wal.log");
+    let snapshot_path = dir.join("snapshot.bin");
+    let _ = fs::remove_file(&log_path);
+    let _ = fs::remove_file(&snapshot_path);
+
+    GhostToken::new(|token| -> std::io::Result<()> {
+        let store = KvStore::new(token, BufWriter::new(File::create(&log_path)?));
+
+        std::thread::scope(|s| {
+            for t in 0..4 {
+                let store = &store;
+                s.spawn(move || {
+                    for i in 0..10_000u32 {
+                        let key = format!("sensor:{t}:{}", i % 100);
+                        store
+                            .put(key.into_bytes(), i.to_le_bytes().to_vec())
+                            .unwrap();
+                    }
+                });
+            }
+        });
+        println!("wrote {} keys", store.len());
+
+        // Compact: snapshot the state and start a fresh log.
+        let next_log = dir.join("wal.next");
+        let old = store.checkpoint(
+            BufWriter::new(File::create(&snapshot_path)?),
+            BufWriter::new(File::create(&next_log)?),
+        )?;
+        drop(old);
+        fs::rename(&next_log, &log_path)?;
+
+        store.put(&b"sensor:0:0"[..], &b"after checkpoint"[..])?;
+        store.flush()?;
+        Ok(())
+    })?;
+
+    GhostToken::new(|token| -> std::io::Result<()> {
+        let store = KvStore::recover(
+            token,
+            Some(BufReader::new(File::open(&snapshot_path)?)),
+            BufReader::new(File::open(&log_path)?),
+            BufWriter::new(OpenOptions::new().append(true).open(&log_path)?),
+        )?;
+        println!("recovered {} keys", store.len());
+        let value = store.get(b"sensor:0:0").expect("key survives recovery");
+        println!("sensor:0:0 = {:?}", String::from_utf8_lossy(&value));
+        Ok(())
+    })?;
+
+    fs::remove_dir_all(&dir)
+}
@@ -703,6 +703,57 @@ impl<'brand, T, const CHUNK: usize> BrandedArena<'brand, T, CHUNK> {
         }
     }
 
+    /// Iterates over every allocated value with its key, nursery generation first.
+    pub fn iter<'a>(
+        &'a self,
+        token: &'a GhostToken<'brand>,
+    ) -> impl Iterator<Item = (BrandedArenaKey<'brand>, &'a T)> + use<'a, 'brand, T, CHUNK> {
+        let state = self.state.borrow(token);
+        let nursery = state
+            .nursery
+            .iter(token)
+            .enumerate()
+            .map(|(i, value)| (BrandedArenaKey::new(i | (1 << 63)), value));
+        let mature = state
+            .mature
+            .iter(token)
+            .enumerate()
+            .map(|(i, value)| (BrandedArenaKey::new(i), value));
+        nursery.chain(mature)
+    }
+
+    /// Iterates over every allocated value with its key by exclusive reference,
+    /// nursery generation first.
+    pub fn iter_mut<'a>(
+        &'a self,
+        token: &'a mut GhostToken<'brand>,
+    ) -> impl Iterator<Item = (BrandedArenaKey<'brand>, &'a mut T)> + use<'a, 'brand, T, CHUNK>
+    {
+        let state = self.state.borrow_mut(token);
+        let nursery = state
+            .nursery
+            .iter_mut_exclusive()
+            .enumerate()
+            .map(|(i, value)| (BrandedArenaKey::new(i | (1 << 63)), value));
+        let mature = state
+            .mature
+            .iter_mut_exclusive()
+            .enumerate()
+            .map(|(i, value)| (BrandedArenaKey::new(i), value));
+        nursery.chain(mature)
+    }
+
+    /// Drops every value and releases all chunks.
+    ///
+    /// The generation threshold and allocation epoch are kept. Keys issued before the
+    /// call no longer refer to their values: they may resolve to values allocated
+    /// afterwards, or make [`get_key`](Self::get_key) panic.
+    pub fn clear(&self, token: &mut GhostToken<'brand>) {
+        let state = self.state.borrow_mut(token);
+        state.nursery.clear();
+        state.mature.clear();
+    }
+
     /// Bulk operation: applies `f` to all values in the arena.
     ///
     /// Processes nursery generation first (short-lived objects) then mature generation
@@ -762,4 +813,37 @@ mod tests {
             assert_eq!(*arena.get_key(&token, k1), 15);
         });
     }
+    #[test]
+    fn branded_arena_iter_and_clear() {
+        GhostToken::new(|mut token| {
+            let arena: BrandedArena<'_, String, 2> = BrandedArena::with_generation_threshold(3);
+            let keys: Vec<_> = (0..5)
+                .map(|i| arena.alloc(&mut token, i.to_string()))
+                .collect();
+            assert_eq!(arena.nursery_len(&token), 3);
+            assert_eq!(arena.len(&token), 5);
+
+            // Every key comes back exactly once, paired with its value.
+            let mut seen: Vec<_> = arena
+                .iter(&token)
+                .map(|(key, value)| (key, value.clone()))
+                .collect();
+            seen.sort_by_key(|(_, value)| value.clone());
+            for (i, (key, value)) in seen.iter().enumerate() {
+                assert_eq!(*key, keys[i]);
+                assert_eq!(arena.get_key(&token, *key), value);
+            }
+
+            for (_, value) in arena.iter_mut(&mut token) {
+                value.push('!');
+            }
+            assert_eq!(arena.get_key(&token, keys[4]), "4!");
+
+            arena.clear(&mut token);
+            assert!(arena.is_empty(&token));
+            assert_eq!(arena.iter(&token).count(), 0);
+            arena.alloc(&mut token, "new".to_string());
+            assert_eq!(arena.len(&token), 1);
+        });
+    }
 }
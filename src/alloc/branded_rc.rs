@@ -4,10 +4,10 @@
 //! allowing for token-gated Copy-On-Write (COW) semantics.
 
 use crate::token::InvariantLifetime;
-use std::borrow::Borrow;
-use std::hash::{Hash, Hasher};
-use std::ops::Deref;
-use std::rc::Rc;
+use core::borrow::Borrow;
+use core::hash::{Hash, Hasher};
+use core::ops::Deref;
+use alloc_crate::rc::Rc;
 
 /// A branded reference-counted pointer.
 ///
@@ -1,29 +1,48 @@
+// Only `allocator`, `pool` and `branded_rc` are needed by the collections that build
+// without `std`; everything else here relies on `std`.
 pub mod allocator;
+#[cfg(feature = "std")]
 pub mod arena;
+#[cfg(feature = "std")]
 pub mod bump;
 pub mod pool;
+#[cfg(feature = "std")]
 pub mod global;
+#[cfg(feature = "std")]
 pub mod slab;
+#[cfg(feature = "std")]
 pub mod system;
 
 pub use allocator::{AllocError, GhostAlloc};
+#[cfg(feature = "std")]
 pub use arena::BrandedArena;
+#[cfg(feature = "std")]
 pub use bump::BrandedBumpAllocator;
 pub use pool::BrandedPool;
+#[cfg(feature = "std")]
 pub use global::{DispatchGlobalAlloc, with_global_allocator};
+#[cfg(feature = "std")]
 pub use slab::{BrandedSlab, init_slab_page};
 
+#[cfg(feature = "std")]
 pub mod page;
+#[cfg(feature = "std")]
 pub use page::{PageAlloc, GlobalPageAlloc, SyscallPageAlloc};
 
+#[cfg(feature = "std")]
 pub mod branded_box;
 pub mod branded_rc;
+#[cfg(feature = "std")]
 pub mod static_rc;
+#[cfg(feature = "std")]
 pub mod segregated;
 
+#[cfg(feature = "std")]
 pub use branded_box::BrandedBox;
 pub use branded_rc::BrandedRc;
+#[cfg(feature = "std")]
 pub use static_rc::StaticRc;
+#[cfg(feature = "std")]
 pub use system::HaloAllocator;
 
 // # Benchmark Comparison
@@ -17,6 +17,8 @@ use crate::GhostCell;
 // use crate::GhostToken;
 // use crate::token::traits::{GhostBorrow, GhostBorrowMut};
 use core::mem::ManuallyDrop;
+#[cfg(not(feature = "std"))]
+use alloc_crate::vec::Vec;
 
 /// A slot in the pool.
 ///
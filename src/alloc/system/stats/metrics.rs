@@ -1,29 +1,33 @@
-use core::sync::atomic::{AtomicUsize, Ordering};
+use crate::concurrency::atomic::GhostShardedCounter;
 
+/// Allocation statistics of `HaloAllocator`.
+///
+/// The counters are sharded per thread, so recording an allocation does not contend
+/// with other threads; read a total with `sum()`.
 pub struct AllocatorMetrics {
-    pub allocated_bytes: AtomicUsize,
-    pub allocated_count: AtomicUsize,
-    pub deallocated_bytes: AtomicUsize,
-    pub deallocated_count: AtomicUsize,
+    pub allocated_bytes: GhostShardedCounter<'static>,
+    pub allocated_count: GhostShardedCounter<'static>,
+    pub deallocated_bytes: GhostShardedCounter<'static>,
+    pub deallocated_count: GhostShardedCounter<'static>,
 }
 
 pub static METRICS: AllocatorMetrics = AllocatorMetrics {
-    allocated_bytes: AtomicUsize::new(0),
-    allocated_count: AtomicUsize::new(0),
-    deallocated_bytes: AtomicUsize::new(0),
-    deallocated_count: AtomicUsize::new(0),
+    allocated_bytes: GhostShardedCounter::new(),
+    allocated_count: GhostShardedCounter::new(),
+    deallocated_bytes: GhostShardedCounter::new(),
+    deallocated_count: GhostShardedCounter::new(),
 };
 
 impl AllocatorMetrics {
     #[inline(always)]
     pub fn on_alloc(&self, size: usize) {
-        self.allocated_count.fetch_add(1, Ordering::Relaxed);
-        self.allocated_bytes.fetch_add(size, Ordering::Relaxed);
+        self.allocated_count.increment();
+        self.allocated_bytes.add(size as u64);
     }
 
     #[inline(always)]
     pub fn on_dealloc(&self, size: usize) {
-        self.deallocated_count.fetch_add(1, Ordering::Relaxed);
-        self.deallocated_bytes.fetch_add(size, Ordering::Relaxed);
+        self.deallocated_count.increment();
+        self.deallocated_bytes.add(size as u64);
     }
 }
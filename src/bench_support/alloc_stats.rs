@@ -0,0 +1,176 @@
+//! Allocation accounting through a counting `GlobalAlloc` wrapper.
+//!
+//! Install [`CountingAllocator`] as the global allocator of a benchmark binary,
+//! wrapping `std::alloc::System` or any other allocator, then use
+//! [`CountingAllocator::measure`] to see what a piece of code allocated. Counters are
+//! process-wide relaxed atomics, so concurrent threads are included in a
+//! measurement; measure single-threaded sections for exact attribution.
+
+use core::alloc::{GlobalAlloc, Layout};
+use core::sync::atomic::{AtomicUsize, Ordering};
+
+/// A `GlobalAlloc` that forwards to `A` and counts what passes through it.
+pub struct CountingAllocator<A> {
+    inner: A,
+    allocations: AtomicUsize,
+    deallocations: AtomicUsize,
+    reallocations: AtomicUsize,
+    allocated_bytes: AtomicUsize,
+    freed_bytes: AtomicUsize,
+    live_bytes: AtomicUsize,
+    peak_bytes: AtomicUsize,
+}
+
+/// Allocator activity over an interval, as reported by [`CountingAllocator`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct AllocStats {
+    /// Calls to `alloc`/`alloc_zeroed`.
+    pub allocations: usize,
+    /// Calls to `dealloc`.
+    pub deallocations: usize,
+    /// Calls to `realloc`.
+    pub reallocations: usize,
+    /// Bytes requested, counting the new size of each reallocation.
+    pub allocated_bytes: usize,
+    /// Bytes released, counting the old size of each reallocation.
+    pub freed_bytes: usize,
+    /// Highest number of live bytes reached, relative to the start of the interval.
+    pub peak_bytes: usize,
+}
+
+impl AllocStats {
+    /// Bytes still live at the end of the interval that were not live at its start.
+    pub fn net_bytes(&self) -> isize {
+        self.allocated_bytes as isize - self.freed_bytes as isize
+    }
+}
+
+impl<A> CountingAllocator<A> {
+    /// Wraps `inner`.
+    pub const fn new(inner: A) -> Self {
+        Self {
+            inner,
+            allocations: AtomicUsize::new(0),
+            deallocations: AtomicUsize::new(0),
+            reallocations: AtomicUsize::new(0),
+            allocated_bytes: AtomicUsize::new(0),
+            freed_bytes: AtomicUsize::new(0),
+            live_bytes: AtomicUsize::new(0),
+            peak_bytes: AtomicUsize::new(0),
+        }
+    }
+
+    /// Returns the counters accumulated since the process started.
+    pub fn totals(&self) -> AllocStats {
+        AllocStats {
+            allocations: self.allocations.load(Ordering::Relaxed),
+            deallocations: self.deallocations.load(Ordering::Relaxed),
+            reallocations: self.reallocations.load(Ordering::Relaxed),
+            allocated_bytes: self.allocated_bytes.load(Ordering::Relaxed),
+            freed_bytes: self.freed_bytes.load(Ordering::Relaxed),
+            peak_bytes: self.peak_bytes.load(Ordering::Relaxed),
+        }
+    }
+
+    /// Returns the number of bytes currently allocated.
+    pub fn live_bytes(&self) -> usize {
+        self.live_bytes.load(Ordering::Relaxed)
+    }
+
+    /// Runs `f` and returns its result together with the allocator activity during
+    /// the call. `peak_bytes` is measured from the live size at the start of the call.
+    pub fn measure<R>(&self, f: impl FnOnce() -> R) -> (R, AllocStats) {
+        let before = self.totals();
+        let live_before = self.live_bytes();
+        // Restart peak tracking from the current live size.
+        self.peak_bytes.store(live_before, Ordering::Relaxed);
+        let result = f();
+        let after = self.totals();
+        let stats = AllocStats {
+            allocations: after.allocations - before.allocations,
+            deallocations: after.deallocations - before.deallocations,
+            reallocations: after.reallocations - before.reallocations,
+            allocated_bytes: after.allocated_bytes - before.allocated_bytes,
+            freed_bytes: after.freed_bytes - before.freed_bytes,
+            peak_bytes: after.peak_bytes.saturating_sub(live_before),
+        };
+        // Leave the process-wide peak at least as high as it was before the call.
+        self.peak_bytes
+            .fetch_max(before.peak_bytes, Ordering::Relaxed);
+        (result, stats)
+    }
+
+    fn record_alloc(&self, size: usize) {
+        self.allocated_bytes.fetch_add(size, Ordering::Relaxed);
+        let live = self.live_bytes.fetch_add(size, Ordering::Relaxed) + size;
+        self.peak_bytes.fetch_max(live, Ordering::Relaxed);
+    }
+
+    fn record_free(&self, size: usize) {
+        self.freed_bytes.fetch_add(size, Ordering::Relaxed);
+        self.live_bytes.fetch_sub(size, Ordering::Relaxed);
+    }
+}
+
+// SAFETY: every call is forwarded unchanged to `inner`; the counters are side effects.
+unsafe impl<A: GlobalAlloc> GlobalAlloc for CountingAllocator<A> {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = self.inner.alloc(layout);
+        if !ptr.is_null() {
+            self.allocations.fetch_add(1, Ordering::Relaxed);
+            self.record_alloc(layout.size());
+        }
+        ptr
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        let ptr = self.inner.alloc_zeroed(layout);
+        if !ptr.is_null() {
+            self.allocations.fetch_add(1, Ordering::Relaxed);
+            self.record_alloc(layout.size());
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        self.inner.dealloc(ptr, layout);
+        self.deallocations.fetch_add(1, Ordering::Relaxed);
+        self.record_free(layout.size());
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let new = self.inner.realloc(ptr, layout, new_size);
+        if !new.is_null() {
+            self.reallocations.fetch_add(1, Ordering::Relaxed);
+            self.record_free(layout.size());
+            self.record_alloc(new_size);
+        }
+        new
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::alloc::System;
+
+    #[test]
+    fn counts_forwarded_calls() {
+        let alloc = CountingAllocator::new(System);
+        let layout = Layout::from_size_align(64, 8).unwrap();
+        let (_, stats) = alloc.measure(|| unsafe {
+            let a = alloc.alloc(layout);
+            let b = alloc.alloc_zeroed(layout);
+            let b = alloc.realloc(b, layout, 256);
+            alloc.dealloc(a, layout);
+            alloc.dealloc(b, Layout::from_size_align(256, 8).unwrap());
+        });
+        assert_eq!(stats.allocations, 2);
+        assert_eq!(stats.reallocations, 1);
+        assert_eq!(stats.deallocations, 2);
+        assert_eq!(stats.allocated_bytes, 64 + 64 + 256);
+        assert_eq!(stats.net_bytes(), 0);
+        assert_eq!(stats.peak_bytes, 64 + 256);
+        assert_eq!(alloc.live_bytes(), 0);
+    }
+}
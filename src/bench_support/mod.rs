@@ -0,0 +1,44 @@
+//! Benchmark support for measuring halo structures on your own data.
+//!
+//! Enabled by the `bench-support` feature. The pieces are independent:
+//!
+//! - [`workload`] — seeded generators for key streams (uniform, sequential, Zipf),
+//!   mixed read/write operation streams, strings and graphs (random, power-law, DAG),
+//!   so runs are repeatable and comparable across structures.
+//! - [`timing`] — warm-up plus repeated sampling with robust summary statistics, for
+//!   quick measurements outside a Criterion harness.
+//! - [`alloc_stats`] — a counting `GlobalAlloc` wrapper that records allocation
+//!   counts, bytes and peak usage around a closure.
+//!
+//! ```no_run
+//! use halo::bench_support::{alloc_stats::CountingAllocator, timing, workload};
+//! use halo::{BrandedHashMap, GhostToken};
+//! use std::alloc::System;
+//!
+//! #[global_allocator]
+//! static ALLOC: CountingAllocator<System> = CountingAllocator::new(System);
+//!
+//! let keys = workload::zipf_keys(100_000, 10_000, 1.1, 42);
+//! GhostToken::new(|token| {
+//!     let (map, allocs) = ALLOC.measure(|| {
+//!         let mut map = BrandedHashMap::new();
+//!         for &k in &keys {
+//!             map.insert(k, k);
+//!         }
+//!         map
+//!     });
+//!     println!("{} allocations, peak {} bytes", allocs.allocations, allocs.peak_bytes);
+//!
+//!     let summary = timing::measure(&timing::Config::default(), || {
+//!         keys.iter().filter(|k| map.get(&token, *k).is_some()).count()
+//!     });
+//!     println!("{summary}");
+//! });
+//! ```
+
+pub mod alloc_stats;
+pub mod timing;
+pub mod workload;
+
+pub use alloc_stats::{AllocStats, CountingAllocator};
+pub use timing::{measure, Config, Summary};
@@ -0,0 +1,175 @@
+//! Lightweight timing with warm-up and robust summaries.
+//!
+//! [`measure`] follows the same shape as the Criterion suite: warm up, size batches
+//! so each sample is long enough to time reliably, then report per-iteration times.
+//! Results pass through [`core::hint::black_box`] so the measured work is not
+//! optimized away.
+
+use core::fmt;
+use core::hint::black_box;
+use std::time::{Duration, Instant};
+
+/// Runs `f` once and returns its result together with the elapsed wall time.
+pub fn time<R>(f: impl FnOnce() -> R) -> (R, Duration) {
+    let start = Instant::now();
+    let result = black_box(f());
+    (result, start.elapsed())
+}
+
+/// Sampling parameters for [`measure`].
+#[derive(Debug, Clone)]
+pub struct Config {
+    /// How long to run the routine before sampling.
+    pub warm_up: Duration,
+    /// Number of timed samples.
+    pub samples: usize,
+    /// Minimum duration of one sample; fast routines are batched to reach it.
+    pub min_sample_time: Duration,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            warm_up: Duration::from_millis(500),
+            samples: 50,
+            min_sample_time: Duration::from_millis(2),
+        }
+    }
+}
+
+/// Per-iteration timing statistics, in nanoseconds.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Summary {
+    /// Iterations run in each sample.
+    pub iterations_per_sample: u64,
+    /// Fastest sample.
+    pub min_ns: f64,
+    /// Median sample; the most robust single figure.
+    pub median_ns: f64,
+    /// Mean of all samples.
+    pub mean_ns: f64,
+    /// 95th-percentile sample.
+    pub p95_ns: f64,
+    /// Slowest sample.
+    pub max_ns: f64,
+    /// Sample standard deviation.
+    pub std_dev_ns: f64,
+}
+
+impl Summary {
+    /// Summarizes per-iteration sample times.
+    ///
+    /// # Panics
+    /// Panics if `samples_ns` is empty.
+    pub fn from_samples(iterations_per_sample: u64, samples_ns: &[f64]) -> Self {
+        assert!(!samples_ns.is_empty(), "need at least one sample");
+        let mut sorted = samples_ns.to_vec();
+        sorted.sort_by(f64::total_cmp);
+        let n = sorted.len();
+        let percentile = |p: f64| sorted[((n - 1) as f64 * p).round() as usize];
+        let mean = sorted.iter().sum::<f64>() / n as f64;
+        let variance = if n > 1 {
+            sorted.iter().map(|s| (s - mean).powi(2)).sum::<f64>() / (n - 1) as f64
+        } else {
+            0.0
+        };
+        Self {
+            iterations_per_sample,
+            min_ns: sorted[0],
+            median_ns: percentile(0.5),
+            mean_ns: mean,
+            p95_ns: percentile(0.95),
+            max_ns: sorted[n - 1],
+            std_dev_ns: variance.sqrt(),
+        }
+    }
+
+    /// Returns the median throughput in iterations per second.
+    pub fn per_second(&self) -> f64 {
+        1e9 / self.median_ns
+    }
+}
+
+impl fmt::Display for Summary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "median {:.1} ns (mean {:.1} ± {:.1}, min {:.1}, p95 {:.1}) over {} iters/sample",
+            self.median_ns,
+            self.mean_ns,
+            self.std_dev_ns,
+            self.min_ns,
+            self.p95_ns,
+            self.iterations_per_sample
+        )
+    }
+}
+
+/// Times `routine` according to `config` and summarizes the time per call.
+///
+/// # Panics
+/// Panics if `config.samples` is zero.
+pub fn measure<R>(config: &Config, mut routine: impl FnMut() -> R) -> Summary {
+    assert!(config.samples > 0, "need at least one sample");
+
+    // Warm up, and estimate the cost of one call while doing so.
+    let start = Instant::now();
+    let mut warm_iters = 0u64;
+    loop {
+        black_box(routine());
+        warm_iters += 1;
+        if start.elapsed() >= config.warm_up {
+            break;
+        }
+    }
+    let per_iter = start.elapsed().as_nanos() as f64 / warm_iters as f64;
+    let iterations =
+        ((config.min_sample_time.as_nanos() as f64 / per_iter.max(1.0)).ceil() as u64).max(1);
+
+    let samples: Vec<f64> = (0..config.samples)
+        .map(|_| {
+            let start = Instant::now();
+            for _ in 0..iterations {
+                black_box(routine());
+            }
+            start.elapsed().as_nanos() as f64 / iterations as f64
+        })
+        .collect();
+    Summary::from_samples(iterations, &samples)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn summary_statistics() {
+        let s = Summary::from_samples(10, &[4.0, 1.0, 3.0, 2.0, 100.0]);
+        assert_eq!(s.min_ns, 1.0);
+        assert_eq!(s.median_ns, 3.0);
+        assert_eq!(s.max_ns, 100.0);
+        assert_eq!(s.p95_ns, 100.0);
+        assert_eq!(s.mean_ns, 22.0);
+        assert!(s.to_string().starts_with("median 3.0 ns"));
+    }
+
+    #[test]
+    fn measure_batches_fast_routines() {
+        let config = Config {
+            warm_up: Duration::from_millis(5),
+            samples: 5,
+            min_sample_time: Duration::from_micros(200),
+        };
+        let mut calls = 0u64;
+        let summary = measure(&config, || {
+            calls += 1;
+            calls
+        });
+        assert!(summary.iterations_per_sample > 1);
+        assert!(summary.median_ns > 0.0);
+
+        let (value, elapsed) = time(|| 6 * 7);
+        assert_eq!(value, 42);
+        assert!(elapsed < Duration::from_secs(1));
+    }
+}
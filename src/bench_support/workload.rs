@@ -0,0 +1,258 @@
+//! Seeded workload generators.
+//!
+//! Every generator takes an explicit `seed`, so the same call always produces the
+//! same data.
+
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::{Rng, SeedableRng};
+
+fn rng(seed: u64) -> StdRng {
+    StdRng::seed_from_u64(seed)
+}
+
+/// Returns `0..n` as keys, in order.
+pub fn sequential_keys(n: usize) -> Vec<u64> {
+    (0..n as u64).collect()
+}
+
+/// Returns `0..n` as keys in a random order.
+pub fn shuffled_keys(n: usize, seed: u64) -> Vec<u64> {
+    let mut keys = sequential_keys(n);
+    keys.shuffle(&mut rng(seed));
+    keys
+}
+
+/// Returns `n` keys drawn uniformly from `0..universe`.
+///
+/// # Panics
+/// Panics if `universe` is zero.
+pub fn uniform_keys(n: usize, universe: u64, seed: u64) -> Vec<u64> {
+    assert!(universe > 0, "universe must be non-empty");
+    let mut rng = rng(seed);
+    (0..n).map(|_| rng.gen_range(0..universe)).collect()
+}
+
+/// Returns `n` keys from `0..universe` following a Zipf distribution with the given
+/// `exponent`: key `k` is drawn with probability proportional to `1 / (k + 1)^exponent`.
+///
+/// Skewed key streams like this model caches and hot-key lookups.
+///
+/// # Panics
+/// Panics if `universe` is zero or `exponent` is negative.
+pub fn zipf_keys(n: usize, universe: u64, exponent: f64, seed: u64) -> Vec<u64> {
+    assert!(universe > 0, "universe must be non-empty");
+    assert!(exponent >= 0.0, "exponent must be non-negative");
+    let mut cdf = Vec::with_capacity(universe as usize);
+    let mut total = 0.0;
+    for k in 0..universe {
+        total += 1.0 / ((k + 1) as f64).powf(exponent);
+        cdf.push(total);
+    }
+    let mut rng = rng(seed);
+    (0..n)
+        .map(|_| {
+            let target = rng.gen::<f64>() * total;
+            cdf.partition_point(|&c| c <= target).min(cdf.len() - 1) as u64
+        })
+        .collect()
+}
+
+/// One operation of a mixed map workload.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Op {
+    /// Insert or overwrite the key.
+    Insert(u64),
+    /// Look the key up.
+    Get(u64),
+    /// Remove the key.
+    Remove(u64),
+}
+
+/// Returns `n` operations on keys from `keys`, chosen with the given fractions of
+/// reads and removals; the rest are inserts.
+///
+/// # Panics
+/// Panics if `keys` is empty or the fractions are negative or sum to more than 1.
+pub fn mixed_ops(
+    n: usize,
+    keys: &[u64],
+    read_fraction: f64,
+    remove_fraction: f64,
+    seed: u64,
+) -> Vec<Op> {
+    assert!(!keys.is_empty(), "keys must not be empty");
+    assert!(
+        read_fraction >= 0.0 && remove_fraction >= 0.0 && read_fraction + remove_fraction <= 1.0,
+        "fractions must be non-negative and sum to at most 1"
+    );
+    let mut rng = rng(seed);
+    (0..n)
+        .map(|_| {
+            let key = keys[rng.gen_range(0..keys.len())];
+            let roll = rng.gen::<f64>();
+            if roll < read_fraction {
+                Op::Get(key)
+            } else if roll < read_fraction + remove_fraction {
+                Op::Remove(key)
+            } else {
+                Op::Insert(key)
+            }
+        })
+        .collect()
+}
+
+/// Returns `n` ASCII alphanumeric strings with lengths drawn from `len`.
+pub fn random_strings(n: usize, len: core::ops::RangeInclusive<usize>, seed: u64) -> Vec<String> {
+    let mut rng = rng(seed);
+    (0..n)
+        .map(|_| {
+            let l = rng.gen_range(len.clone());
+            (&mut rng)
+                .sample_iter(rand::distributions::Alphanumeric)
+                .take(l)
+                .map(char::from)
+                .collect()
+        })
+        .collect()
+}
+
+/// Returns a directed graph on `n` nodes as adjacency lists, with each node getting
+/// `out_degree` distinct random successors (no self-loops).
+///
+/// # Panics
+/// Panics if `out_degree >= n` for `n > 0`.
+pub fn random_graph(n: usize, out_degree: usize, seed: u64) -> Vec<Vec<usize>> {
+    assert!(
+        n == 0 || out_degree < n,
+        "out_degree must be below the node count"
+    );
+    let mut rng = rng(seed);
+    (0..n)
+        .map(|u| {
+            let mut targets = Vec::with_capacity(out_degree);
+            while targets.len() < out_degree {
+                let v = rng.gen_range(0..n);
+                if v != u && !targets.contains(&v) {
+                    targets.push(v);
+                }
+            }
+            targets
+        })
+        .collect()
+}
+
+/// Returns an undirected power-law graph on `n` nodes (Barabási–Albert preferential
+/// attachment, `m` edges per new node) as symmetric adjacency lists.
+///
+/// Hub-heavy graphs like this stress load balancing in traversal kernels.
+///
+/// # Panics
+/// Panics if `m` is zero or `m >= n` for `n > 0`.
+pub fn power_law_graph(n: usize, m: usize, seed: u64) -> Vec<Vec<usize>> {
+    let mut adj = vec![Vec::new(); n];
+    if n == 0 {
+        return adj;
+    }
+    assert!(m > 0 && m < n, "m must be in 1..n");
+    let mut rng = rng(seed);
+    // Every edge endpoint, so a uniform pick is degree-proportional.
+    let mut endpoints = Vec::new();
+    // Seed with a clique on the first m + 1 nodes.
+    for u in 0..=m {
+        for v in 0..u {
+            adj[u].push(v);
+            adj[v].push(u);
+            endpoints.extend([u, v]);
+        }
+    }
+    for u in m + 1..n {
+        let mut targets: Vec<usize> = Vec::with_capacity(m);
+        while targets.len() < m {
+            let v = endpoints[rng.gen_range(0..endpoints.len())];
+            if !targets.contains(&v) {
+                targets.push(v);
+            }
+        }
+        for v in targets {
+            adj[u].push(v);
+            adj[v].push(u);
+            endpoints.extend([u, v]);
+        }
+    }
+    adj
+}
+
+/// Returns a random DAG on `n` nodes as adjacency lists; every edge goes from a
+/// lower to a higher node index, so `0..n` is a topological order.
+///
+/// Each of the `n * (n - 1) / 2` possible forward edges is included with
+/// probability `edge_probability`.
+pub fn random_dag(n: usize, edge_probability: f64, seed: u64) -> Vec<Vec<usize>> {
+    let mut rng = rng(seed);
+    (0..n)
+        .map(|u| {
+            (u + 1..n)
+                .filter(|_| rng.gen_bool(edge_probability))
+                .collect()
+        })
+        .collect()
+}
+
+/// Flattens adjacency lists into `(from, to)` edge pairs.
+pub fn edge_list(adj: &[Vec<usize>]) -> Vec<(usize, usize)> {
+    adj.iter()
+        .enumerate()
+        .flat_map(|(u, targets)| targets.iter().map(move |&v| (u, v)))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn generators_are_deterministic() {
+        assert_eq!(uniform_keys(100, 50, 1), uniform_keys(100, 50, 1));
+        assert_ne!(uniform_keys(100, 50, 1), uniform_keys(100, 50, 2));
+        assert_eq!(random_strings(5, 1..=8, 3), random_strings(5, 1..=8, 3));
+
+        let mut shuffled = shuffled_keys(100, 9);
+        shuffled.sort_unstable();
+        assert_eq!(shuffled, sequential_keys(100));
+    }
+
+    #[test]
+    fn zipf_is_skewed_toward_small_keys() {
+        let keys = zipf_keys(10_000, 1000, 1.2, 7);
+        assert!(keys.iter().all(|&k| k < 1000));
+        let zeros = keys.iter().filter(|&&k| k == 0).count();
+        let tail = keys.iter().filter(|&&k| k >= 500).count();
+        assert!(zeros > tail, "{zeros} vs {tail}");
+    }
+
+    #[test]
+    fn mixed_ops_respects_fractions() {
+        let ops = mixed_ops(10_000, &sequential_keys(10), 0.8, 0.1, 5);
+        let reads = ops.iter().filter(|op| matches!(op, Op::Get(_))).count();
+        assert!((7_500..8_500).contains(&reads));
+    }
+
+    #[test]
+    fn graph_shapes() {
+        let g = random_graph(50, 3, 1);
+        assert!(g
+            .iter()
+            .enumerate()
+            .all(|(u, t)| t.len() == 3 && !t.contains(&u)));
+
+        let g = power_law_graph(500, 2, 1);
+        let edges = edge_list(&g).len() / 2;
+        assert_eq!(edges, 3 + (500 - 3) * 2);
+        let max_degree = g.iter().map(Vec::len).max().unwrap();
+        assert!(max_degree > 20, "expected hubs, max degree {max_degree}");
+
+        let dag = random_dag(40, 0.2, 1);
+        assert!(edge_list(&dag).iter().all(|&(u, v)| u < v));
+    }
+}
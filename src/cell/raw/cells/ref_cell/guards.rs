@@ -10,6 +10,22 @@ pub struct Ref<'brand, 'cell, T> {
     pub(super) cell: &'cell GhostRefCell<'brand, T>,
 }
 
+impl<'brand, 'cell, T> Ref<'brand, 'cell, T> {
+    /// Converts the guard into a reference with the full lifetime of the cell borrow.
+    ///
+    /// The reader count is never decremented, so the cell stays immutably borrowed
+    /// until [`GhostRefCell::unpoison`] is called on it.
+    #[inline]
+    pub fn leak(orig: Self) -> &'cell T {
+        let cell = orig.cell;
+        core::mem::forget(orig);
+        // SAFETY: the reader count stays incremented, so no writer can be created
+        // until `unpoison`, which requires `&mut GhostRefCell` and hence outlives `'cell`.
+        let slot: *mut MaybeUninit<T> = unsafe { guc::as_mut_ptr_unchecked(&cell.value) };
+        unsafe { mu::assume_init_ref(&*slot) }
+    }
+}
+
 impl<'brand, 'cell, T> core::ops::Deref for Ref<'brand, 'cell, T> {
     type Target = T;
 
@@ -40,6 +56,22 @@ pub struct RefMut<'brand, 'cell, T> {
     pub(super) cell: &'cell GhostRefCell<'brand, T>,
 }
 
+impl<'brand, 'cell, T> RefMut<'brand, 'cell, T> {
+    /// Converts the guard into a mutable reference without releasing the writer flag.
+    ///
+    /// The cell stays exclusively borrowed (every further `borrow`/`borrow_mut` fails)
+    /// until [`GhostRefCell::unpoison`] is called on it.
+    #[inline]
+    pub fn forget_release(orig: Self) -> &'cell mut T {
+        let cell = orig.cell;
+        core::mem::forget(orig);
+        // SAFETY: the writer flag stays set, so no other guard can be created until
+        // `unpoison`, which requires `&mut GhostRefCell` and hence outlives `'cell`.
+        let slot: *mut MaybeUninit<T> = unsafe { guc::as_mut_ptr_unchecked(&cell.value) };
+        unsafe { mu::assume_init_mut(&mut *slot) }
+    }
+}
+
 impl<'brand, 'cell, T> core::ops::Deref for RefMut<'brand, 'cell, T> {
     type Target = T;
 
@@ -3,6 +3,10 @@
 //! This is the raw (foundational) branded ref-cell primitive. Its only interior
 //! mutation storage is [`GhostUnsafeCell`], and all low-level `MaybeUninit`/pointer
 //! operations are centralized through `cell::raw::access`.
+//!
+//! With the `metrics` feature, each cell also counts borrow attempts that failed
+//! because of a conflicting borrow (see [`GhostRefCell::metrics`]), which points at
+//! the cells that are contention hot-spots in a real workload.
 
 mod guards;
 
@@ -13,6 +17,8 @@ use core::{
     ptr,
     sync::atomic::{AtomicIsize, Ordering},
 };
+#[cfg(feature = "metrics")]
+use core::sync::atomic::AtomicUsize;
 
 use crate::cell::raw::access::ghost_unsafe_cell as guc;
 use crate::cell::raw::access::maybe_uninit as mu;
@@ -24,6 +30,21 @@ pub struct GhostRefCell<'brand, T> {
     // Atomic borrow count: negative = writing, positive = reading, zero = free.
     pub(super) borrow: AtomicIsize,
     pub(super) value: GhostUnsafeCell<'brand, MaybeUninit<T>>,
+    #[cfg(feature = "metrics")]
+    failed_borrows: AtomicUsize,
+    #[cfg(feature = "metrics")]
+    failed_borrow_muts: AtomicUsize,
+}
+
+/// Contention counters of a [`GhostRefCell`], available with the `metrics` feature.
+#[cfg(feature = "metrics")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct BorrowMetrics {
+    /// Shared borrows refused because the cell was mutably borrowed.
+    pub failed_borrows: usize,
+    /// Exclusive borrows (including `replace`, `take` and `swap`) refused because the
+    /// cell was already borrowed.
+    pub failed_borrow_muts: usize,
 }
 
 impl<'brand, T> GhostRefCell<'brand, T> {
@@ -33,15 +54,78 @@ impl<'brand, T> GhostRefCell<'brand, T> {
         Self {
             borrow: AtomicIsize::new(0),
             value: GhostUnsafeCell::new(MaybeUninit::new(value)),
+            #[cfg(feature = "metrics")]
+            failed_borrows: AtomicUsize::new(0),
+            #[cfg(feature = "metrics")]
+            failed_borrow_muts: AtomicUsize::new(0),
         }
     }
 
+    #[inline(always)]
+    fn record_failed_borrow(&self) {
+        #[cfg(feature = "metrics")]
+        self.failed_borrows.fetch_add(1, Ordering::Relaxed);
+    }
+
+    #[inline(always)]
+    fn record_failed_borrow_mut(&self) {
+        #[cfg(feature = "metrics")]
+        self.failed_borrow_muts.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Returns a mutable reference to the wrapped value.
+    ///
+    /// Exclusive access to the cell statically rules out outstanding guards, so
+    /// neither a token nor the atomic borrow flag is needed.
+    #[inline]
+    pub fn get_mut(&mut self) -> &mut T {
+        // SAFETY: `new` initializes the slot, and `&mut self` guarantees exclusivity.
+        unsafe { mu::assume_init_mut(&mut *guc::as_mut_ptr_unchecked(&self.value)) }
+    }
+
+    /// Resets the borrow flag left behind by [`Ref::leak`] or
+    /// [`RefMut::forget_release`], returning a mutable reference to the value.
+    ///
+    /// Taking `&mut self` guarantees every leaked reference has expired.
+    #[inline]
+    pub fn unpoison(&mut self) -> &mut T {
+        *self.borrow.get_mut() = 0;
+        self.get_mut()
+    }
+
     /// Returns `true` if the cell is currently borrowed.
     #[inline(always)]
     pub fn is_borrowed(&self, _token: &GhostToken<'brand>) -> bool {
         self.borrow.load(Ordering::Relaxed) != 0
     }
 
+    /// Returns the number of live shared borrows, including leaked ones.
+    ///
+    /// Returns `0` while the cell is mutably borrowed.
+    #[inline]
+    pub fn reader_count(&self, _token: &GhostToken<'brand>) -> usize {
+        usize::try_from(self.borrow.load(Ordering::Relaxed)).unwrap_or(0)
+    }
+
+    /// Returns the contention counters accumulated since creation or the last
+    /// [`reset_metrics`](Self::reset_metrics).
+    #[cfg(feature = "metrics")]
+    #[inline]
+    pub fn metrics(&self) -> BorrowMetrics {
+        BorrowMetrics {
+            failed_borrows: self.failed_borrows.load(Ordering::Relaxed),
+            failed_borrow_muts: self.failed_borrow_muts.load(Ordering::Relaxed),
+        }
+    }
+
+    /// Resets the contention counters to zero.
+    #[cfg(feature = "metrics")]
+    #[inline]
+    pub fn reset_metrics(&self) {
+        self.failed_borrows.store(0, Ordering::Relaxed);
+        self.failed_borrow_muts.store(0, Ordering::Relaxed);
+    }
+
     /// Immutably borrows the wrapped value.
     ///
     /// # Panics
@@ -51,6 +135,7 @@ impl<'brand, T> GhostRefCell<'brand, T> {
         let mut current = self.borrow.load(Ordering::Acquire);
         loop {
             if current < 0 {
+                self.record_failed_borrow();
                 panic!("already mutably borrowed");
             }
             match self.borrow.compare_exchange_weak(
@@ -77,7 +162,10 @@ impl<'brand, T> GhostRefCell<'brand, T> {
             .compare_exchange(0, -1, Ordering::AcqRel, Ordering::Acquire)
         {
             Ok(_) => RefMut { cell: self },
-            Err(_) => panic!("already borrowed"),
+            Err(_) => {
+                self.record_failed_borrow_mut();
+                panic!("already borrowed")
+            }
         }
     }
 
@@ -87,6 +175,7 @@ impl<'brand, T> GhostRefCell<'brand, T> {
         let mut current = self.borrow.load(Ordering::Acquire);
         loop {
             if current < 0 {
+                self.record_failed_borrow();
                 return None;
             }
             match self.borrow.compare_exchange_weak(
@@ -112,7 +201,10 @@ impl<'brand, T> GhostRefCell<'brand, T> {
             .compare_exchange(0, -1, Ordering::AcqRel, Ordering::Acquire)
         {
             Ok(_) => Some(RefMut { cell: self }),
-            Err(_) => None,
+            Err(_) => {
+                self.record_failed_borrow_mut();
+                None
+            }
         }
     }
 
@@ -133,7 +225,10 @@ impl<'brand, T> GhostRefCell<'brand, T> {
                 self.borrow.store(0, Ordering::Release);
                 old
             }
-            Err(_) => panic!("already borrowed"),
+            Err(_) => {
+                self.record_failed_borrow_mut();
+                panic!("already borrowed")
+            }
         }
     }
 
@@ -161,7 +256,10 @@ impl<'brand, T> GhostRefCell<'brand, T> {
                 self.borrow.store(0, Ordering::Release);
                 old
             }
-            Err(_) => panic!("already borrowed"),
+            Err(_) => {
+                self.record_failed_borrow_mut();
+                panic!("already borrowed")
+            }
         }
     }
 
@@ -171,13 +269,20 @@ impl<'brand, T> GhostRefCell<'brand, T> {
     /// Panics if either value is currently borrowed.
     #[inline(always)]
     pub fn swap(&self, _token: &mut GhostToken<'brand>, other: &Self) {
-        match (
+        let results = (
             self.borrow
                 .compare_exchange(0, -1, Ordering::AcqRel, Ordering::Acquire),
             other
                 .borrow
                 .compare_exchange(0, -1, Ordering::AcqRel, Ordering::Acquire),
-        ) {
+        );
+        if results.0.is_err() {
+            self.record_failed_borrow_mut();
+        }
+        if results.1.is_err() {
+            other.record_failed_borrow_mut();
+        }
+        match results {
             (Ok(_), Ok(_)) => {
                 let a = unsafe { guc::as_mut_ptr_unchecked(&self.value) };
                 let b = unsafe { guc::as_mut_ptr_unchecked(&other.value) };
@@ -209,7 +314,10 @@ impl<'brand, T> GhostRefCell<'brand, T> {
                 self.borrow.store(0, Ordering::Release);
                 old
             }
-            Err(_) => panic!("already borrowed"),
+            Err(_) => {
+                self.record_failed_borrow_mut();
+                panic!("already borrowed")
+            }
         }
     }
 }
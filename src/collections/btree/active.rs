@@ -6,7 +6,7 @@
 
 use super::{BrandedBTreeMap, BrandedBTreeSet};
 use crate::token::traits::GhostBorrowMut;
-use std::borrow::Borrow;
+use core::borrow::Borrow;
 
 /// A wrapper around a mutable reference to a `BrandedBTreeMap` and a mutable reference to a generic Token.
 pub struct ActiveBTreeMap<'a, 'brand, K, V, Token>
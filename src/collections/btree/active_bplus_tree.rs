@@ -51,6 +51,23 @@ where
     pub fn iter<'b>(&'b self) -> impl Iterator<Item = (&'b K, &'b V)> + use<'b, 'brand, K, V, Token> {
         self.tree.iter(self.token)
     }
+
+    pub fn range_scan<'b, Q, R>(&'b self, range: R) -> impl Iterator<Item = (&'b K, &'b V)> + use<'b, 'brand, K, V, Q, R, Token>
+    where
+        K: core::borrow::Borrow<Q>,
+        Q: Ord + ?Sized,
+        R: core::ops::RangeBounds<Q>,
+    {
+        self.tree.range_scan(self.token, range)
+    }
+
+    pub fn scan_prefix<'b, P>(&'b self, prefix: P) -> impl Iterator<Item = (&'b K, &'b V)> + use<'b, 'brand, K, V, P, Token>
+    where
+        K: Ord + AsRef<[u8]>,
+        P: AsRef<[u8]>,
+    {
+        self.tree.scan_prefix(self.token, prefix)
+    }
 }
 
 pub trait ActivateBPlusTree<'brand, K, V> {
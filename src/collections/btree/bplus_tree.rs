@@ -3,8 +3,10 @@
 use crate::alloc::BrandedPool;
 use crate::collections::ZeroCopyMapOps;
 use crate::GhostCell;
+use core::borrow::Borrow;
 // use crate::{GhostCell, GhostToken};
 use core::mem::MaybeUninit;
+use core::ops::{Bound, RangeBounds};
 use core::ptr;
 // Removed unused import: use std::borrow::Borrow;
 
@@ -214,13 +216,13 @@ impl<'brand, K, V> BrandedBPlusTree<'brand, K, V> {
                         while idx < l {
                             let k = unsafe { keys.get_unchecked(idx).assume_init_ref() };
                             match key.cmp(k) {
-                                std::cmp::Ordering::Equal => {
+                                core::cmp::Ordering::Equal => {
                                     return Some(unsafe {
                                         vals.get_unchecked(idx).assume_init_ref().borrow(token)
                                     });
                                 }
-                                std::cmp::Ordering::Greater => idx += 1,
-                                std::cmp::Ordering::Less => return None,
+                                core::cmp::Ordering::Greater => idx += 1,
+                                core::cmp::Ordering::Less => return None,
                             }
                         }
                         return None;
@@ -269,7 +271,7 @@ impl<'brand, K, V> BrandedBPlusTree<'brand, K, V> {
                         while idx < l {
                             let k = unsafe { keys.get_unchecked(idx).assume_init_ref() };
                             match key.cmp(k) {
-                                std::cmp::Ordering::Equal => {
+                                core::cmp::Ordering::Equal => {
                                     // We have exclusive access to the node (via get_node_mut which used the token),
                                     // so we have exclusive access to the GhostCell.
                                     // We can skip the token for inner access.
@@ -277,8 +279,8 @@ impl<'brand, K, V> BrandedBPlusTree<'brand, K, V> {
                                         vals.get_unchecked_mut(idx).assume_init_mut().get_mut()
                                     });
                                 }
-                                std::cmp::Ordering::Greater => idx += 1,
-                                std::cmp::Ordering::Less => return None,
+                                core::cmp::Ordering::Greater => idx += 1,
+                                core::cmp::Ordering::Less => return None,
                             }
                         }
                         return None;
@@ -340,6 +342,91 @@ impl<'brand, K, V> BrandedBPlusTree<'brand, K, V> {
         }
     }
 
+    /// Descends to the first entry whose key is not `below` the scan start.
+    ///
+    /// `below` must be monotone over the key order (true for a prefix of the keys,
+    /// false afterwards). Separators equal to the first key of their right subtree, so
+    /// the subtree to descend into is the one after the last separator still below.
+    /// The returned position may be one past the end of its leaf, in which case the
+    /// first entry is at the start of the next leaf.
+    fn seek<Token>(&self, token: &Token, below: impl Fn(&K) -> bool) -> (Option<usize>, usize)
+    where
+        Token: crate::token::traits::GhostBorrow<'brand>,
+    {
+        let Some(mut idx) = self.root else {
+            return (None, 0);
+        };
+        loop {
+            let node = self.get_node(token, idx);
+            let l = node.len();
+            let pos = (0..l)
+                .position(|i| !below(node.key_at(i)))
+                .unwrap_or(l);
+            match node {
+                Node::Leaf { .. } => return (Some(idx), pos),
+                Node::Internal { children, .. } => idx = children[pos],
+            }
+        }
+    }
+
+    /// Iterates over the entries whose keys fall in `range`, in key order.
+    ///
+    /// The tree is descended once to the first key in the range; the scan then follows
+    /// the linked leaf level, so each further entry costs \(O(1)\) amortized and no
+    /// internal node is revisited.
+    pub fn range_scan<'a, Q, R, Token>(
+        &'a self,
+        token: &'a Token,
+        range: R,
+    ) -> impl Iterator<Item = (&'a K, &'a V)> + use<'a, 'brand, K, V, Q, R, Token>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+        R: RangeBounds<Q>,
+        Token: crate::token::traits::GhostBorrow<'brand>,
+    {
+        let (leaf_idx, key_idx) = self.seek(token, |k| match range.start_bound() {
+            Bound::Included(start) => k.borrow() < start,
+            Bound::Excluded(start) => k.borrow() <= start,
+            Bound::Unbounded => false,
+        });
+        Iter {
+            tree: self,
+            token,
+            leaf_idx,
+            key_idx,
+        }
+        .take_while(move |(k, _)| match range.end_bound() {
+            Bound::Included(end) => (*k).borrow() <= end,
+            Bound::Excluded(end) => (*k).borrow() < end,
+            Bound::Unbounded => true,
+        })
+    }
+
+    /// Iterates over the entries whose keys start with `prefix`, in key order.
+    ///
+    /// Works for any key type whose order is the lexicographic order of its bytes,
+    /// such as `String`, `Vec<u8>` or `&str`.
+    pub fn scan_prefix<'a, P, Token>(
+        &'a self,
+        token: &'a Token,
+        prefix: P,
+    ) -> impl Iterator<Item = (&'a K, &'a V)> + use<'a, 'brand, K, V, P, Token>
+    where
+        K: Ord + AsRef<[u8]>,
+        P: AsRef<[u8]>,
+        Token: crate::token::traits::GhostBorrow<'brand>,
+    {
+        let (leaf_idx, key_idx) = self.seek(token, |k| k.as_ref() < prefix.as_ref());
+        Iter {
+            tree: self,
+            token,
+            leaf_idx,
+            key_idx,
+        }
+        .take_while(move |(k, _)| k.as_ref().starts_with(prefix.as_ref()))
+    }
+
     pub fn insert<Token>(&mut self, token: &mut Token, key: K, value: V) -> Option<V>
     where
         K: Ord + Clone,
@@ -503,15 +590,15 @@ impl<'brand, K, V> BrandedBPlusTree<'brand, K, V> {
                 while idx < l {
                     let k = unsafe { keys.get_unchecked(idx).assume_init_ref() };
                     match key.cmp(k) {
-                        std::cmp::Ordering::Greater => idx += 1,
-                        std::cmp::Ordering::Equal => {
+                        core::cmp::Ordering::Greater => idx += 1,
+                        core::cmp::Ordering::Equal => {
                             let cell = unsafe { vals.get_unchecked_mut(idx).assume_init_mut() };
                             // We have exclusive access, so use get_mut() to swap without token
                             let val_mut = cell.get_mut();
-                            let old = std::mem::replace(val_mut, value);
+                            let old = core::mem::replace(val_mut, value);
                             return Some(old);
                         }
-                        std::cmp::Ordering::Less => break,
+                        core::cmp::Ordering::Less => break,
                     }
                 }
                 node.leaf_insert(idx, key, GhostCell::new(value));
@@ -677,4 +764,54 @@ mod tests {
             assert_eq!(count, 100);
         });
     }
+
+    #[test]
+    fn test_range_scan_follows_leaf_chain() {
+        GhostToken::new(|mut token| {
+            let mut tree = BrandedBPlusTree::new();
+            for i in (0..500).map(|i| i * 2) {
+                tree.insert(&mut token, i, i * 10);
+            }
+
+            let keys = |it: &mut dyn Iterator<Item = (&i32, &i32)>| -> Vec<i32> {
+                it.map(|(k, _)| *k).collect()
+            };
+            assert_eq!(keys(&mut tree.range_scan(&token, 10..20)), [10, 12, 14, 16, 18]);
+            assert_eq!(keys(&mut tree.range_scan(&token, 11..=20)), [12, 14, 16, 18, 20]);
+            assert_eq!(keys(&mut tree.range_scan(&token, 995..)), [996, 998]);
+            assert_eq!(tree.range_scan(&token, ..).count(), 500);
+            assert_eq!(tree.range_scan(&token, 2000..).count(), 0);
+            assert_eq!(tree.range_scan(&token, 7..7).count(), 0);
+            assert_eq!(
+                tree.range_scan(&token, (Bound::Excluded(100), Bound::Included(104)))
+                    .collect::<Vec<_>>(),
+                [(&102, &1020), (&104, &1040)]
+            );
+
+            // Ranges starting on, and between, every key, across leaf boundaries.
+            for start in 0..1000 {
+                let expected = (start..(start + 40).min(1000)).filter(|k| k % 2 == 0);
+                assert!(tree.range_scan(&token, start..start + 40).map(|(k, _)| *k).eq(expected));
+            }
+        });
+    }
+
+    #[test]
+    fn test_scan_prefix() {
+        GhostToken::new(|mut token| {
+            let mut tree = BrandedBPlusTree::new();
+            for user in 0..50 {
+                tree.insert(&mut token, format!("user:{user:02}"), user);
+                tree.insert(&mut token, format!("order:{user:02}"), user);
+            }
+            tree.insert(&mut token, "user".to_string(), -1);
+
+            let users: Vec<i32> = tree.scan_prefix(&token, "user:").map(|(_, v)| *v).collect();
+            assert_eq!(users, (0..50).collect::<Vec<_>>());
+            assert_eq!(tree.scan_prefix(&token, "user:1").count(), 10);
+            assert_eq!(tree.scan_prefix(&token, "order").count(), 50);
+            assert_eq!(tree.scan_prefix(&token, "zzz").count(), 0);
+            assert_eq!(tree.scan_prefix(&token, "").count(), 101);
+        });
+    }
 }
@@ -5,12 +5,15 @@
 //! Values are stored inline in the nodes, protected by the `BrandedVec`'s token mechanism.
 
 use crate::collections::BrandedCollection;
-use crate::{BrandedVec, GhostToken};
+use crate::collections::BrandedVec;
+use crate::GhostToken;
 use crate::token::traits::{GhostBorrow, GhostBorrowMut};
-use std::borrow::Borrow;
+use core::borrow::Borrow;
 // use std::cmp::Ordering;
-use std::marker::PhantomData;
-use std::mem::MaybeUninit;
+use core::marker::PhantomData;
+use core::mem::MaybeUninit;
+#[cfg(not(feature = "std"))]
+use alloc_crate::vec::Vec;
 
 // B-Tree order parameters.
 // B = 6 roughly corresponds to std::collections::BTreeMap logic but simplified.
@@ -56,6 +59,8 @@ struct NodeData<'brand, K, V> {
     len: u16,
     is_leaf: bool,
     next_free: NodeIdx<'brand>, // For free list
+    /// Number of entries in the subtree rooted here (order-statistics augmentation).
+    size: usize,
 }
 
 impl<'brand, K, V> NodeData<'brand, K, V> {
@@ -71,6 +76,7 @@ impl<'brand, K, V> NodeData<'brand, K, V> {
             len: 0,
             is_leaf,
             next_free: NodeIdx::NONE,
+            size: 0,
         }
     }
 
@@ -108,7 +114,7 @@ impl<'brand, K, V> NodeData<'brand, K, V> {
     {
         // Binary search
         let len = self.len as usize;
-        let slice = unsafe { std::slice::from_raw_parts(self.keys.as_ptr() as *const K, len) };
+        let slice = unsafe { core::slice::from_raw_parts(self.keys.as_ptr().cast::<K>(), len) };
         slice.binary_search_by(|k| k.borrow().cmp(key))
     }
 }
@@ -183,6 +189,28 @@ impl<'brand, K, V> BrandedBTreeMap<'brand, K, V> {
         }
         self.free_head = idx;
     }
+
+    #[inline]
+    fn node_mut(&mut self, idx: NodeIdx<'brand>) -> &mut NodeData<'brand, K, V> {
+        &mut self.nodes.as_mut_slice_exclusive()[idx.index()]
+    }
+
+    /// Recomputes the subtree size of `idx` from its own length and its children's sizes.
+    ///
+    /// Every structural mutation calls this bottom-up on the nodes it touched, which keeps
+    /// `select`/`rank` at O(log n) for an O(B) overhead per visited node.
+    fn recompute_size(&mut self, idx: NodeIdx<'brand>) {
+        let nodes = self.nodes.as_mut_slice_exclusive();
+        let node = &nodes[idx.index()];
+        let len = node.len as usize;
+        let mut size = len;
+        if !node.is_leaf {
+            for child in &node.children[..=len] {
+                size += nodes[child.index()].size;
+            }
+        }
+        nodes[idx.index()].size = size;
+    }
 }
 
 impl<'brand, K, V> BrandedBTreeMap<'brand, K, V>
@@ -272,6 +300,7 @@ where
                 root.keys[0].write(key);
                 root.vals[0].write(value);
                 root.len = 1;
+                root.size = 1;
             }
             self.len += 1;
             return None;
@@ -301,7 +330,7 @@ where
     }
 
     fn split_child(&mut self, parent_idx: NodeIdx<'brand>, child_index: usize) {
-        unsafe {
+        let (child_idx, new_child_idx) = unsafe {
             let nodes_ptr = self.nodes.as_mut_slice_exclusive().as_mut_ptr();
 
             let parent = &mut *nodes_ptr.add(parent_idx.index());
@@ -319,12 +348,12 @@ where
 
             let move_count = MAX_LEN - split_idx;
 
-            std::ptr::copy_nonoverlapping(
+            core::ptr::copy_nonoverlapping(
                 child.keys.as_ptr().add(split_idx),
                 new_child.keys.as_mut_ptr(),
                 move_count,
             );
-            std::ptr::copy_nonoverlapping(
+            core::ptr::copy_nonoverlapping(
                 child.vals.as_ptr().add(split_idx),
                 new_child.vals.as_mut_ptr(),
                 move_count,
@@ -332,7 +361,7 @@ where
 
             if !child.is_leaf {
                 let children_move_count = MAX_CHILDREN - B;
-                std::ptr::copy_nonoverlapping(
+                core::ptr::copy_nonoverlapping(
                     child.children.as_ptr().add(B),
                     new_child.children.as_mut_ptr(),
                     children_move_count,
@@ -344,29 +373,29 @@ where
 
             let p_len = parent.len as usize;
             if p_len > child_index {
-                std::ptr::copy(
+                core::ptr::copy(
                     parent.keys.as_ptr().add(child_index),
                     parent.keys.as_mut_ptr().add(child_index + 1),
                     p_len - child_index,
                 );
-                std::ptr::copy(
+                core::ptr::copy(
                     parent.vals.as_ptr().add(child_index),
                     parent.vals.as_mut_ptr().add(child_index + 1),
                     p_len - child_index,
                 );
-                std::ptr::copy(
+                core::ptr::copy(
                     parent.children.as_ptr().add(child_index + 1),
                     parent.children.as_mut_ptr().add(child_index + 2),
                     p_len - child_index,
                 );
             }
 
-            std::ptr::copy_nonoverlapping(
+            core::ptr::copy_nonoverlapping(
                 child.keys.as_ptr().add(mid_idx),
                 parent.keys.as_mut_ptr().add(child_index),
                 1,
             );
-            std::ptr::copy_nonoverlapping(
+            core::ptr::copy_nonoverlapping(
                 child.vals.as_ptr().add(mid_idx),
                 parent.vals.as_mut_ptr().add(child_index),
                 1,
@@ -374,10 +403,20 @@ where
 
             parent.children[child_index + 1] = new_child_idx;
             parent.len += 1;
-        }
+            (child_idx, new_child_idx)
+        };
+        // The parent's total is unchanged; only the two halves need new sizes.
+        self.recompute_size(child_idx);
+        self.recompute_size(new_child_idx);
     }
 
     fn insert_non_full(&mut self, node_idx: NodeIdx<'brand>, key: K, value: V) -> Option<V> {
+        let res = self.insert_into_node(node_idx, key, value);
+        self.recompute_size(node_idx);
+        res
+    }
+
+    fn insert_into_node(&mut self, node_idx: NodeIdx<'brand>, key: K, value: V) -> Option<V> {
         unsafe {
             let nodes_ptr = self.nodes.as_mut_slice_exclusive().as_mut_ptr();
             let node = &mut *nodes_ptr.add(node_idx.index());
@@ -388,7 +427,7 @@ where
                 while i > 0 {
                     let k = node.key_at(i - 1);
                     if k == &key {
-                        let old = std::mem::replace(node.val_at_mut(i - 1), value);
+                        let old = core::mem::replace(node.val_at_mut(i - 1), value);
                         return Some(old);
                     }
                     if k < &key {
@@ -398,12 +437,12 @@ where
                 }
 
                 if i < node.len as usize {
-                    std::ptr::copy(
+                    core::ptr::copy(
                         node.keys.as_ptr().add(i),
                         node.keys.as_mut_ptr().add(i + 1),
                         node.len as usize - i,
                     );
-                    std::ptr::copy(
+                    core::ptr::copy(
                         node.vals.as_ptr().add(i),
                         node.vals.as_mut_ptr().add(i + 1),
                         node.len as usize - i,
@@ -419,7 +458,7 @@ where
                 while i > 0 {
                     let k = node.key_at(i - 1);
                     if k == &key {
-                        let old = std::mem::replace(node.val_at_mut(i - 1), value);
+                        let old = core::mem::replace(node.val_at_mut(i - 1), value);
                         return Some(old);
                     }
                     if k < &key {
@@ -442,7 +481,7 @@ where
                     } else if node.key_at(i) > &key {
                         self.insert_non_full(node.children[i], key, value)
                     } else {
-                        let old = std::mem::replace(
+                        let old = core::mem::replace(
                             self.nodes
                                 .get_unchecked_mut_exclusive(node_idx.index())
                                 .val_at_mut(i),
@@ -457,6 +496,450 @@ where
         }
     }
 
+    /// Moves all entries of `other` into `self`, leaving `other` empty.
+    ///
+    /// When every key of one map is below every key of the other, the trees are
+    /// joined node by node: the smaller tree's nodes move into the larger's arena and
+    /// it is grafted onto the larger's spine at its own height, in \(O(\log n)\) plus
+    /// one node copy per moved node. Otherwise (including on duplicate keys, where the
+    /// value from `other` wins) both maps are drained in order, merged, and bulk-loaded
+    /// back into `self`'s node arena in O(n + m).
+    pub fn append(&mut self, other: &mut Self) {
+        if other.is_empty() {
+            return;
+        }
+        if self.is_empty() {
+            core::mem::swap(self, other);
+            return;
+        }
+        if self.edge_key(true) < other.edge_key(false) {
+            self.join(other, false);
+            return;
+        }
+        if other.edge_key(true) < self.edge_key(false) {
+            self.join(other, true);
+            return;
+        }
+
+        let left = self.take_entries();
+        let right = other.take_entries();
+        let mut merged = Vec::with_capacity(left.len() + right.len());
+        let mut left = left.into_iter().peekable();
+        let mut right = right.into_iter().peekable();
+        loop {
+            let take_left = match (left.peek(), right.peek()) {
+                (Some((l, _)), Some((r, _))) => match l.cmp(r) {
+                    core::cmp::Ordering::Less => true,
+                    core::cmp::Ordering::Greater => false,
+                    core::cmp::Ordering::Equal => {
+                        left.next();
+                        false
+                    }
+                },
+                (Some(_), None) => true,
+                (None, Some(_)) => false,
+                (None, None) => break,
+            };
+            let entry = if take_left { left.next() } else { right.next() };
+            merged.extend(entry);
+        }
+        self.build_from_sorted(merged);
+    }
+
+    /// Splits the map in two at `key`: `self` keeps the entries below `key` and the
+    /// returned map holds `key` and everything above it.
+    ///
+    /// Every node on the search path is cut in two and the underfull nodes along both
+    /// cuts are repaired from their siblings, in \(O(\log n)\). The larger half keeps
+    /// the node arena; the smaller half's nodes are then copied into a fresh one, which
+    /// costs one node copy per moved node but never touches entries individually.
+    #[must_use]
+    pub fn split_off<Q: ?Sized>(&mut self, key: &Q) -> Self
+    where
+        K: Borrow<Q>,
+        Q: Ord,
+    {
+        let mut other = Self::new();
+        if self.root.is_none() {
+            return other;
+        }
+        let upper = self.cut(self.root, key);
+        let lower = self.fix_border(self.root, true);
+        let upper = self.fix_border(upper, false);
+        let lower_len = if lower.is_some() { self.node_mut(lower).size } else { 0 };
+        let upper_len = self.len - lower_len;
+
+        if upper_len <= lower_len {
+            if upper.is_some() {
+                other.root = self.move_subtree(upper, &mut other);
+            }
+            (self.root, self.len) = (lower, lower_len);
+            other.len = upper_len;
+        } else {
+            if lower.is_some() {
+                other.root = self.move_subtree(lower, &mut other);
+            }
+            (self.root, self.len) = (upper, upper_len);
+            other.len = lower_len;
+            core::mem::swap(self, &mut other);
+        }
+        other
+    }
+
+    /// Returns the smallest key, or the largest if `last`, of a non-empty map.
+    fn edge_key(&mut self, last: bool) -> &K {
+        let mut idx = self.root;
+        loop {
+            let node = self.node_mut(idx);
+            let len = node.len as usize;
+            if node.is_leaf {
+                let at = if last { len - 1 } else { 0 };
+                // SAFETY: `at < len`, as the nodes of a non-empty map are non-empty.
+                return unsafe { self.node_mut(idx).key_at(at) };
+            }
+            idx = node.children[if last { len } else { 0 }];
+        }
+    }
+
+    /// Returns the height of the subtree at `idx` (leaves are height 0).
+    fn height(&mut self, mut idx: NodeIdx<'brand>) -> usize {
+        let mut height = 0;
+        while !self.node_mut(idx).is_leaf {
+            idx = self.node_mut(idx).children[0];
+            height += 1;
+        }
+        height
+    }
+
+    /// Moves the subtree at `idx` out of this arena and into `dest`'s, returning its
+    /// root there. Nodes are copied whole; entries are not touched.
+    fn move_subtree(&mut self, idx: NodeIdx<'brand>, dest: &mut Self) -> NodeIdx<'brand> {
+        let node = self.node_mut(idx);
+        // SAFETY: the bitwise copy takes over the node's entries, and zeroing the
+        // source's length keeps them from being dropped twice.
+        let mut data = unsafe { core::ptr::read(node) };
+        node.len = 0;
+        self.free_node(idx);
+        if !data.is_leaf {
+            for i in 0..=data.len as usize {
+                data.children[i] = self.move_subtree(data.children[i], dest);
+            }
+        }
+        let moved = dest.alloc_node(data.is_leaf);
+        // The freshly allocated node is empty, so overwriting it drops nothing.
+        *dest.node_mut(moved) = data;
+        moved
+    }
+
+    /// Cuts the subtree at `idx` along the search path for `key`: the entries below
+    /// `key` stay in place and the rest move to a new subtree of the same height, whose
+    /// root is returned.
+    ///
+    /// Nodes along the cut, on the right border of the lower tree and the left border
+    /// of the upper one, may be left underfull or even empty; see [`Self::fix_border`].
+    fn cut<Q: ?Sized>(&mut self, idx: NodeIdx<'brand>, key: &Q) -> NodeIdx<'brand>
+    where
+        K: Borrow<Q>,
+        Q: Ord,
+    {
+        let is_leaf = self.node_mut(idx).is_leaf;
+        let upper = self.alloc_node(is_leaf);
+        let at = unsafe {
+            let nodes_ptr = self.nodes.as_mut_slice_exclusive().as_mut_ptr();
+            let node = &mut *nodes_ptr.add(idx.index());
+            let upper = &mut *nodes_ptr.add(upper.index());
+            let len = node.len as usize;
+            let (Ok(at) | Err(at)) = node.search_key(key);
+
+            core::ptr::copy_nonoverlapping(
+                node.keys.as_ptr().add(at),
+                upper.keys.as_mut_ptr(),
+                len - at,
+            );
+            core::ptr::copy_nonoverlapping(
+                node.vals.as_ptr().add(at),
+                upper.vals.as_mut_ptr(),
+                len - at,
+            );
+            if !is_leaf {
+                // Child `at` straddles `key`; both halves keep a slot for its parts.
+                core::ptr::copy_nonoverlapping(
+                    node.children.as_ptr().add(at),
+                    upper.children.as_mut_ptr(),
+                    len - at + 1,
+                );
+            }
+            upper.len = u16::try_from(len - at).expect("node length fits in u16");
+            node.len = u16::try_from(at).expect("node length fits in u16");
+            at
+        };
+        if !is_leaf {
+            let child = self.node_mut(idx).children[at];
+            let child_upper = self.cut(child, key);
+            self.node_mut(upper).children[0] = child_upper;
+        }
+        self.recompute_size(idx);
+        self.recompute_size(upper);
+        upper
+    }
+
+    /// Frees empty roots until the root holds a key, returning it (`NONE` if the tree
+    /// turned out to be empty).
+    fn trim_root(&mut self, mut root: NodeIdx<'brand>) -> NodeIdx<'brand> {
+        while root.is_some() {
+            let node = self.node_mut(root);
+            if node.len > 0 {
+                break;
+            }
+            let next = if node.is_leaf { NodeIdx::NONE } else { node.children[0] };
+            self.free_node(root);
+            root = next;
+        }
+        root
+    }
+
+    /// Repairs a tree left by [`Self::cut`], whose nodes on its right (or left) border
+    /// may be underfull, and returns its new root.
+    ///
+    /// Empty roots are trimmed first. Then, top-down, every border node is topped up to
+    /// at least `B` keys from its sibling, or merged with it; a merge costs the parent
+    /// one key, which it can spare because it was topped up in the step before.
+    fn fix_border(&mut self, root: NodeIdx<'brand>, right: bool) -> NodeIdx<'brand> {
+        let root = self.trim_root(root);
+        if root.is_none() {
+            return root;
+        }
+        let mut path = Vec::new();
+        let mut parent = root;
+        while !self.node_mut(parent).is_leaf {
+            path.push(parent);
+            let edge = if right { self.node_mut(parent).len as usize } else { 0 };
+            self.top_up_border_child(parent, edge, right);
+            let node = self.node_mut(parent);
+            parent = node.children[if right { node.len as usize } else { 0 }];
+        }
+        path.push(parent);
+        for &idx in path.iter().rev() {
+            self.recompute_size(idx);
+        }
+        self.trim_root(root)
+    }
+
+    /// Brings child `edge` of `parent`, its last child if `right` and otherwise its
+    /// first, up to at least `B` keys by rotating keys in from its one neighbor, or
+    /// merges the two when the neighbor cannot spare enough.
+    fn top_up_border_child(&mut self, parent: NodeIdx<'brand>, edge: usize, right: bool) {
+        let sibling_pos = if right { edge - 1 } else { 1 };
+        loop {
+            let node = self.node_mut(parent);
+            let (child, sibling) = (node.children[edge], node.children[sibling_pos]);
+            if self.node_mut(child).len as usize >= B {
+                return;
+            }
+            if self.node_mut(sibling).len as usize > MIN_LEN {
+                if right {
+                    self.rotate_right(parent, edge);
+                } else {
+                    self.rotate_left(parent, edge);
+                }
+            } else {
+                // The child has fewer than `B` keys and the sibling at most `MIN_LEN`,
+                // so together with the separator they fit in one node.
+                self.merge_children(parent, edge.min(sibling_pos));
+                return;
+            }
+        }
+    }
+
+    /// Joins `other` into `self`, where every key of `other` is above every key of
+    /// `self`, or below if `other_first`.
+    fn join(&mut self, other: &mut Self, mut other_first: bool) {
+        // The larger tree keeps its arena and the smaller one moves into it.
+        if other.len > self.len {
+            core::mem::swap(self, other);
+            other_first = !other_first;
+        }
+        let total = self.len + other.len;
+        let moved = other.move_subtree(other.root, self);
+        *other = Self::new();
+
+        // The separator comes out of the smaller tree, next to the larger one.
+        let (key, value) = if other_first {
+            self.pop_max(moved)
+        } else {
+            self.pop_min(moved)
+        };
+        let moved = self.trim_root(moved);
+        if moved.is_none() {
+            self.len = total - 1;
+            self.insert(key, value);
+            return;
+        }
+        let (lower, upper) = if other_first {
+            (moved, self.root)
+        } else {
+            (self.root, moved)
+        };
+        self.root = self.join_trees(lower, (key, value), upper);
+        self.len = total;
+    }
+
+    /// Joins two trees of this arena, every key of `lower` being below `separator` and
+    /// every key of `upper` above it, and returns the root of the result.
+    ///
+    /// The shorter tree becomes the last (or first) child of the node at its height on
+    /// the taller tree's right (or left) spine. Full nodes on the way down are split
+    /// first so that node can take the separator, as in [`Self::insert`].
+    fn join_trees(
+        &mut self,
+        lower: NodeIdx<'brand>,
+        separator: (K, V),
+        upper: NodeIdx<'brand>,
+    ) -> NodeIdx<'brand> {
+        let (lower_height, upper_height) = (self.height(lower), self.height(upper));
+        if lower_height == upper_height {
+            return self.join_siblings(lower, separator, upper);
+        }
+        let graft_right = lower_height > upper_height;
+        let (mut root, graft, target) = if graft_right {
+            (lower, upper, upper_height + 1)
+        } else {
+            (upper, lower, lower_height + 1)
+        };
+        let mut height = lower_height.max(upper_height);
+
+        if self.node_mut(root).is_full() {
+            let new_root = self.alloc_node(false);
+            self.node_mut(new_root).children[0] = root;
+            self.split_child(new_root, 0);
+            root = new_root;
+            height += 1;
+        }
+        let mut path = Vec::from([root]);
+        let mut node = root;
+        while height > target {
+            let edge = if graft_right { self.node_mut(node).len as usize } else { 0 };
+            let child = self.node_mut(node).children[edge];
+            if self.node_mut(child).is_full() {
+                self.split_child(node, edge);
+            }
+            let parent = self.node_mut(node);
+            node = parent.children[if graft_right { parent.len as usize } else { 0 }];
+            height -= 1;
+            path.push(node);
+        }
+
+        // `node` is not full; add the separator and the shorter tree at its edge.
+        let (key, value) = separator;
+        let parent = self.node_mut(node);
+        let len = parent.len as usize;
+        let edge = if graft_right {
+            parent.keys[len].write(key);
+            parent.vals[len].write(value);
+            parent.children[len + 1] = graft;
+            len + 1
+        } else {
+            // SAFETY: the node is not full, so shifting its `len` entries and `len + 1`
+            // children up by one stays in bounds.
+            unsafe {
+                core::ptr::copy(parent.keys.as_ptr(), parent.keys.as_mut_ptr().add(1), len);
+                core::ptr::copy(parent.vals.as_ptr(), parent.vals.as_mut_ptr().add(1), len);
+                core::ptr::copy(
+                    parent.children.as_ptr(),
+                    parent.children.as_mut_ptr().add(1),
+                    len + 1,
+                );
+            }
+            parent.keys[0].write(key);
+            parent.vals[0].write(value);
+            parent.children[0] = graft;
+            0
+        };
+        parent.len += 1;
+        // The grafted root may hold fewer than `MIN_LEN` keys; its neighbor is a
+        // regular node with at least `MIN_LEN`.
+        let sibling_pos = if graft_right { edge - 1 } else { 1 };
+        while (self.node_mut(graft).len as usize) < MIN_LEN {
+            let sibling = self.node_mut(node).children[sibling_pos];
+            if self.node_mut(sibling).len as usize > MIN_LEN {
+                if graft_right {
+                    self.rotate_right(node, edge);
+                } else {
+                    self.rotate_left(node, edge);
+                }
+            } else {
+                self.merge_children(node, edge.min(sibling_pos));
+                break;
+            }
+        }
+        for &idx in path.iter().rev() {
+            self.recompute_size(idx);
+        }
+        root
+    }
+
+    /// Joins two trees of the same height around `separator`: into one node if they
+    /// fit, and otherwise under a new root, evening out an underfull side.
+    fn join_siblings(
+        &mut self,
+        lower: NodeIdx<'brand>,
+        separator: (K, V),
+        upper: NodeIdx<'brand>,
+    ) -> NodeIdx<'brand> {
+        let (key, value) = separator;
+        let lower_len = self.node_mut(lower).len as usize;
+        let upper_len = self.node_mut(upper).len as usize;
+        if lower_len + 1 + upper_len <= MAX_LEN {
+            unsafe {
+                let nodes_ptr = self.nodes.as_mut_slice_exclusive().as_mut_ptr();
+                let left = &mut *nodes_ptr.add(lower.index());
+                let right = &mut *nodes_ptr.add(upper.index());
+                left.keys[lower_len].write(key);
+                left.vals[lower_len].write(value);
+                core::ptr::copy_nonoverlapping(
+                    right.keys.as_ptr(),
+                    left.keys.as_mut_ptr().add(lower_len + 1),
+                    upper_len,
+                );
+                core::ptr::copy_nonoverlapping(
+                    right.vals.as_ptr(),
+                    left.vals.as_mut_ptr().add(lower_len + 1),
+                    upper_len,
+                );
+                if !left.is_leaf {
+                    core::ptr::copy_nonoverlapping(
+                        right.children.as_ptr(),
+                        left.children.as_mut_ptr().add(lower_len + 1),
+                        upper_len + 1,
+                    );
+                }
+                left.len += 1 + right.len;
+                right.len = 0;
+            }
+            self.free_node(upper);
+            self.recompute_size(lower);
+            return lower;
+        }
+
+        let root = self.alloc_node(false);
+        let node = self.node_mut(root);
+        node.keys[0].write(key);
+        node.vals[0].write(value);
+        node.children[0] = lower;
+        node.children[1] = upper;
+        node.len = 1;
+        // The children hold at least `MAX_LEN` keys between them, enough for both to
+        // reach `MIN_LEN`.
+        while (self.node_mut(lower).len as usize) < MIN_LEN {
+            self.rotate_left(root, 0);
+        }
+        while (self.node_mut(upper).len as usize) < MIN_LEN {
+            self.rotate_right(root, 1);
+        }
+        self.recompute_size(root);
+        root
+    }
+
     /// Removes a key from the map.
     pub fn remove<Q: ?Sized>(&mut self, key: &Q) -> Option<V>
     where
@@ -472,24 +955,35 @@ where
 
         if res.is_some() {
             self.len -= 1;
-            // Check if root became empty
-            unsafe {
-                let root = self.nodes.get_unchecked_mut_exclusive(self.root.index());
-                if root.len == 0 {
-                    let old_root_idx = self.root;
-                    if root.is_leaf {
-                        self.root = NodeIdx::NONE;
-                    } else {
-                        self.root = root.children[0];
-                    }
-                    self.free_node(old_root_idx);
+        }
+        // Check if root became empty. Merges happen on the way down, so this can
+        // occur even when the key was absent.
+        unsafe {
+            let root = self.nodes.get_unchecked_mut_exclusive(self.root.index());
+            if root.len == 0 {
+                let old_root_idx = self.root;
+                if root.is_leaf {
+                    self.root = NodeIdx::NONE;
+                } else {
+                    self.root = root.children[0];
                 }
+                self.free_node(old_root_idx);
             }
         }
         res
     }
 
     fn remove_from_node<Q: ?Sized>(&mut self, node_idx: NodeIdx<'brand>, key: &Q) -> Option<V>
+    where
+        K: Borrow<Q> + Ord,
+        Q: Ord,
+    {
+        let res = self.remove_in_node(node_idx, key);
+        self.recompute_size(node_idx);
+        res
+    }
+
+    fn remove_in_node<Q: ?Sized>(&mut self, node_idx: NodeIdx<'brand>, key: &Q) -> Option<V>
     where
         K: Borrow<Q> + Ord,
         Q: Ord,
@@ -527,17 +1021,17 @@ where
 
             // Found at idx.
             if node.is_leaf {
-                let val = std::ptr::read(node.val_at(idx));
-                let _k = std::ptr::read(node.key_at(idx)); // Drop key
+                let val = core::ptr::read(node.val_at(idx));
+                let _k = core::ptr::read(node.key_at(idx)); // Drop key
 
                 // Shift
                 if idx < node.len as usize - 1 {
-                    std::ptr::copy(
+                    core::ptr::copy(
                         node.keys.as_ptr().add(idx + 1),
                         node.keys.as_mut_ptr().add(idx),
                         node.len as usize - 1 - idx,
                     );
-                    std::ptr::copy(
+                    core::ptr::copy(
                         node.vals.as_ptr().add(idx + 1),
                         node.vals.as_mut_ptr().add(idx),
                         node.len as usize - 1 - idx,
@@ -554,8 +1048,8 @@ where
                 if (left_child.len as usize) >= B {
                     let (pred_key, pred_val) = self.pop_max(left_child_idx);
                     // Replace key/val at idx with pred
-                    let old_val = std::mem::replace(node.val_at_mut(idx), pred_val);
-                    let _old_key = std::mem::replace(node.key_at_mut(idx), pred_key);
+                    let old_val = core::mem::replace(node.val_at_mut(idx), pred_val);
+                    let _old_key = core::mem::replace(node.key_at_mut(idx), pred_key);
                     return Some(old_val);
                 }
 
@@ -565,8 +1059,8 @@ where
 
                 if (right_child.len as usize) >= B {
                     let (succ_key, succ_val) = self.pop_min(right_child_idx);
-                    let old_val = std::mem::replace(node.val_at_mut(idx), succ_val);
-                    let _old_key = std::mem::replace(node.key_at_mut(idx), succ_key);
+                    let old_val = core::mem::replace(node.val_at_mut(idx), succ_val);
+                    let _old_key = core::mem::replace(node.key_at_mut(idx), succ_key);
                     return Some(old_val);
                 }
 
@@ -620,14 +1114,20 @@ where
     }
 
     fn pop_max(&mut self, node_idx: NodeIdx<'brand>) -> (K, V) {
+        let res = self.pop_max_in(node_idx);
+        self.recompute_size(node_idx);
+        res
+    }
+
+    fn pop_max_in(&mut self, node_idx: NodeIdx<'brand>) -> (K, V) {
         unsafe {
             let nodes_ptr = self.nodes.as_mut_slice_exclusive().as_mut_ptr();
             let node = &mut *nodes_ptr.add(node_idx.index());
 
             if node.is_leaf {
                 let idx = node.len as usize - 1;
-                let key = std::ptr::read(node.key_at(idx));
-                let val = std::ptr::read(node.val_at(idx));
+                let key = core::ptr::read(node.key_at(idx));
+                let val = core::ptr::read(node.val_at(idx));
                 node.len -= 1;
                 return (key, val);
             } else {
@@ -645,21 +1145,27 @@ where
     }
 
     fn pop_min(&mut self, node_idx: NodeIdx<'brand>) -> (K, V) {
+        let res = self.pop_min_in(node_idx);
+        self.recompute_size(node_idx);
+        res
+    }
+
+    fn pop_min_in(&mut self, node_idx: NodeIdx<'brand>) -> (K, V) {
         unsafe {
             let nodes_ptr = self.nodes.as_mut_slice_exclusive().as_mut_ptr();
             let node = &mut *nodes_ptr.add(node_idx.index());
 
             if node.is_leaf {
-                let key = std::ptr::read(node.key_at(0));
-                let val = std::ptr::read(node.val_at(0));
+                let key = core::ptr::read(node.key_at(0));
+                let val = core::ptr::read(node.val_at(0));
 
                 // Shift
-                std::ptr::copy(
+                core::ptr::copy(
                     node.keys.as_ptr().add(1),
                     node.keys.as_mut_ptr(),
                     node.len as usize - 1,
                 );
-                std::ptr::copy(
+                core::ptr::copy(
                     node.vals.as_ptr().add(1),
                     node.vals.as_mut_ptr(),
                     node.len as usize - 1,
@@ -686,7 +1192,7 @@ where
                 .children[idx + 1]
         };
 
-        unsafe {
+        let left_idx = unsafe {
             let nodes_ptr = self.nodes.as_mut_slice_exclusive().as_mut_ptr();
             let parent = &mut *nodes_ptr.add(parent_idx.index());
 
@@ -697,26 +1203,26 @@ where
             let right = &mut *nodes_ptr.add(right_idx_to_free.index());
 
             // Move separator from parent to left
-            let sep_key = std::ptr::read(parent.key_at(idx));
-            let sep_val = std::ptr::read(parent.val_at(idx));
+            let sep_key = core::ptr::read(parent.key_at(idx));
+            let sep_val = core::ptr::read(parent.val_at(idx));
 
             left.keys[left.len as usize].write(sep_key);
             left.vals[left.len as usize].write(sep_val);
 
             // Move right to left
-            std::ptr::copy_nonoverlapping(
+            core::ptr::copy_nonoverlapping(
                 right.keys.as_ptr(),
                 left.keys.as_mut_ptr().add(left.len as usize + 1),
                 right.len as usize,
             );
-            std::ptr::copy_nonoverlapping(
+            core::ptr::copy_nonoverlapping(
                 right.vals.as_ptr(),
                 left.vals.as_mut_ptr().add(left.len as usize + 1),
                 right.len as usize,
             );
 
             if !left.is_leaf {
-                std::ptr::copy_nonoverlapping(
+                core::ptr::copy_nonoverlapping(
                     right.children.as_ptr(),
                     left.children.as_mut_ptr().add(left.len as usize + 1),
                     right.len as usize + 1,
@@ -726,17 +1232,17 @@ where
             left.len += 1 + right.len;
 
             // Shift parent
-            std::ptr::copy(
+            core::ptr::copy(
                 parent.keys.as_ptr().add(idx + 1),
                 parent.keys.as_mut_ptr().add(idx),
                 parent.len as usize - 1 - idx,
             );
-            std::ptr::copy(
+            core::ptr::copy(
                 parent.vals.as_ptr().add(idx + 1),
                 parent.vals.as_mut_ptr().add(idx),
                 parent.len as usize - 1 - idx,
             );
-            std::ptr::copy(
+            core::ptr::copy(
                 parent.children.as_ptr().add(idx + 2),
                 parent.children.as_mut_ptr().add(idx + 1),
                 parent.len as usize - 1 - idx,
@@ -745,32 +1251,36 @@ where
 
             // right node is logically empty now (contents moved)
             right.len = 0;
-        }
+            left_idx
+        };
 
         self.free_node(right_idx_to_free);
+        self.recompute_size(left_idx);
     }
 
     fn rotate_right(&mut self, parent_idx: NodeIdx<'brand>, child_idx: usize) {
         // Move from left sibling to child
-        unsafe {
+        let (child_node, sibling_node) = unsafe {
             let nodes_ptr = self.nodes.as_mut_slice_exclusive().as_mut_ptr();
             let parent = &mut *nodes_ptr.add(parent_idx.index());
-            let child = &mut *nodes_ptr.add(parent.children[child_idx].index());
-            let sibling = &mut *nodes_ptr.add(parent.children[child_idx - 1].index());
+            let child_node = parent.children[child_idx];
+            let sibling_node = parent.children[child_idx - 1];
+            let child = &mut *nodes_ptr.add(child_node.index());
+            let sibling = &mut *nodes_ptr.add(sibling_node.index());
 
             // Make room in child
-            std::ptr::copy(
+            core::ptr::copy(
                 child.keys.as_ptr(),
                 child.keys.as_mut_ptr().add(1),
                 child.len as usize,
             );
-            std::ptr::copy(
+            core::ptr::copy(
                 child.vals.as_ptr(),
                 child.vals.as_mut_ptr().add(1),
                 child.len as usize,
             );
             if !child.is_leaf {
-                std::ptr::copy(
+                core::ptr::copy(
                     child.children.as_ptr(),
                     child.children.as_mut_ptr().add(1),
                     child.len as usize + 1,
@@ -778,13 +1288,13 @@ where
             }
 
             // Move parent separator to child
-            child.keys[0].write(std::ptr::read(parent.key_at(child_idx - 1)));
-            child.vals[0].write(std::ptr::read(parent.val_at(child_idx - 1)));
+            child.keys[0].write(core::ptr::read(parent.key_at(child_idx - 1)));
+            child.vals[0].write(core::ptr::read(parent.val_at(child_idx - 1)));
 
             // Move sibling's last to parent
             let sib_last = sibling.len as usize - 1;
-            parent.keys[child_idx - 1].write(std::ptr::read(sibling.key_at(sib_last)));
-            parent.vals[child_idx - 1].write(std::ptr::read(sibling.val_at(sib_last)));
+            parent.keys[child_idx - 1].write(core::ptr::read(sibling.key_at(sib_last)));
+            parent.vals[child_idx - 1].write(core::ptr::read(sibling.val_at(sib_last)));
 
             // Move sibling's last child to child's first
             if !child.is_leaf {
@@ -793,23 +1303,28 @@ where
 
             child.len += 1;
             sibling.len -= 1;
-        }
+            (child_node, sibling_node)
+        };
+        self.recompute_size(child_node);
+        self.recompute_size(sibling_node);
     }
 
     fn rotate_left(&mut self, parent_idx: NodeIdx<'brand>, child_idx: usize) {
-        unsafe {
+        let (child_node, sibling_node) = unsafe {
             let nodes_ptr = self.nodes.as_mut_slice_exclusive().as_mut_ptr();
             let parent = &mut *nodes_ptr.add(parent_idx.index());
-            let child = &mut *nodes_ptr.add(parent.children[child_idx].index());
-            let sibling = &mut *nodes_ptr.add(parent.children[child_idx + 1].index());
+            let child_node = parent.children[child_idx];
+            let sibling_node = parent.children[child_idx + 1];
+            let child = &mut *nodes_ptr.add(child_node.index());
+            let sibling = &mut *nodes_ptr.add(sibling_node.index());
 
             // Move parent separator to child end
-            child.keys[child.len as usize].write(std::ptr::read(parent.key_at(child_idx)));
-            child.vals[child.len as usize].write(std::ptr::read(parent.val_at(child_idx)));
+            child.keys[child.len as usize].write(core::ptr::read(parent.key_at(child_idx)));
+            child.vals[child.len as usize].write(core::ptr::read(parent.val_at(child_idx)));
 
             // Move sibling first to parent
-            parent.keys[child_idx].write(std::ptr::read(sibling.key_at(0)));
-            parent.vals[child_idx].write(std::ptr::read(sibling.val_at(0)));
+            parent.keys[child_idx].write(core::ptr::read(sibling.key_at(0)));
+            parent.vals[child_idx].write(core::ptr::read(sibling.val_at(0)));
 
             // Move sibling first child to child end
             if !child.is_leaf {
@@ -819,25 +1334,203 @@ where
             child.len += 1;
 
             // Shift sibling
-            std::ptr::copy(
+            core::ptr::copy(
                 sibling.keys.as_ptr().add(1),
                 sibling.keys.as_mut_ptr(),
                 sibling.len as usize - 1,
             );
-            std::ptr::copy(
+            core::ptr::copy(
                 sibling.vals.as_ptr().add(1),
                 sibling.vals.as_mut_ptr(),
                 sibling.len as usize - 1,
             );
             if !sibling.is_leaf {
-                std::ptr::copy(
+                core::ptr::copy(
                     sibling.children.as_ptr().add(1),
                     sibling.children.as_mut_ptr(),
                     sibling.len as usize,
                 );
             }
             sibling.len -= 1;
+            (child_node, sibling_node)
+        };
+        self.recompute_size(child_node);
+        self.recompute_size(sibling_node);
+    }
+
+    /// Returns the `k`-th smallest entry (0-based), or `None` if `k >= len()`.
+    ///
+    /// **Time complexity**: \(O(B \log n)\) using the subtree-size augmentation.
+    pub fn select<'a, Token>(&'a self, token: &'a Token, mut k: usize) -> Option<(&'a K, &'a V)>
+    where
+        Token: GhostBorrow<'brand>,
+    {
+        if k >= self.len {
+            return None;
+        }
+        let mut curr = self.root;
+        while curr.is_some() {
+            unsafe {
+                let node = self.nodes.get_unchecked(token, curr.index());
+                let len = node.len as usize;
+                if node.is_leaf {
+                    return Some((node.key_at(k), node.val_at(k)));
+                }
+                let mut next = node.children[len];
+                for i in 0..len {
+                    let child_size = self
+                        .nodes
+                        .get_unchecked(token, node.children[i].index())
+                        .size;
+                    if k < child_size {
+                        next = node.children[i];
+                        break;
+                    }
+                    k -= child_size;
+                    if k == 0 {
+                        return Some((node.key_at(i), node.val_at(i)));
+                    }
+                    k -= 1;
+                }
+                curr = next;
+            }
+        }
+        None
+    }
+
+    /// Returns the number of keys strictly less than `key`.
+    ///
+    /// If `key` is present, this is its 0-based position in iteration order.
+    ///
+    /// **Time complexity**: \(O(B \log n)\) using the subtree-size augmentation.
+    pub fn rank<Q: ?Sized, Token>(&self, token: &Token, key: &Q) -> usize
+    where
+        K: Borrow<Q>,
+        Q: Ord,
+        Token: GhostBorrow<'brand>,
+    {
+        let mut rank = 0;
+        let mut curr = self.root;
+        while curr.is_some() {
+            unsafe {
+                let node = self.nodes.get_unchecked(token, curr.index());
+                let (idx, found) = match node.search_key(key) {
+                    Ok(i) => (i, true),
+                    Err(i) => (i, false),
+                };
+                rank += idx;
+                if node.is_leaf {
+                    return rank;
+                }
+                for child in &node.children[..idx] {
+                    rank += self.nodes.get_unchecked(token, child.index()).size;
+                }
+                if found {
+                    return rank
+                        + self
+                            .nodes
+                            .get_unchecked(token, node.children[idx].index())
+                            .size;
+                }
+                curr = node.children[idx];
+            }
+        }
+        rank
+    }
+
+    /// Checks the structural invariants of the tree, returning the first violation.
+    ///
+    /// Verifies node fill bounds, strict key ordering (within nodes and against the
+    /// parent separators), uniform leaf depth, and that the subtree-size augmentation
+    /// and `len()` agree with the actual entry counts. Intended for debugging; see
+    /// [`debug_assert_valid!`](crate::debug_assert_valid).
+    ///
+    /// **Time complexity**: \(O(n)\)
+    ///
+    /// # Errors
+    /// Returns a description of the first invariant found violated.
+    pub fn validate_invariants<Token>(&self, token: &Token) -> Result<(), &'static str>
+    where
+        Token: GhostBorrow<'brand>,
+    {
+        if self.root.is_none() {
+            return if self.len == 0 {
+                Ok(())
+            } else {
+                Err("empty tree reports non-zero len")
+            };
         }
+        let mut leaf_depth = None;
+        let size = self.validate_node(token, self.root, None, None, 0, &mut leaf_depth)?;
+        if size != self.len {
+            return Err("len does not match the number of entries");
+        }
+        Ok(())
+    }
+
+    /// Validates the subtree at `idx`, whose keys must lie strictly between `lower`
+    /// and `upper`, and returns its entry count.
+    fn validate_node<Token>(
+        &self,
+        token: &Token,
+        idx: NodeIdx<'brand>,
+        lower: Option<&K>,
+        upper: Option<&K>,
+        depth: usize,
+        leaf_depth: &mut Option<usize>,
+    ) -> Result<usize, &'static str>
+    where
+        Token: GhostBorrow<'brand>,
+    {
+        // A valid tree of at most `u32::MAX` nodes is far shallower than this.
+        if depth > 64 {
+            return Err("tree is too deep (cycle in child links?)");
+        }
+        let node = self.nodes.get(token, idx.index()).ok_or("child index out of bounds")?;
+        let len = node.len as usize;
+        if len > MAX_LEN {
+            return Err("node holds more than MAX_LEN keys");
+        }
+        if idx == self.root {
+            if len == 0 {
+                return Err("non-empty tree has an empty root");
+            }
+        } else if len < MIN_LEN {
+            return Err("non-root node holds fewer than MIN_LEN keys");
+        }
+
+        // SAFETY: the first `len` keys of a node are initialized.
+        let keys = unsafe { core::slice::from_raw_parts(node.keys.as_ptr().cast::<K>(), len) };
+        if keys.windows(2).any(|w| w[0] >= w[1]) {
+            return Err("keys within a node are not strictly increasing");
+        }
+        if lower.is_some_and(|lo| keys[0] <= *lo) || upper.is_some_and(|hi| keys[len - 1] >= *hi)
+        {
+            return Err("key lies outside its parent separator range");
+        }
+
+        let mut size = len;
+        if node.is_leaf {
+            match *leaf_depth {
+                None => *leaf_depth = Some(depth),
+                Some(d) if d != depth => return Err("leaves are at different depths"),
+                Some(_) => {}
+            }
+        } else {
+            for i in 0..=len {
+                let child = node.children[i];
+                if child.is_none() {
+                    return Err("internal node is missing a child");
+                }
+                let lo = if i == 0 { lower } else { Some(&keys[i - 1]) };
+                let hi = if i == len { upper } else { Some(&keys[i]) };
+                size += self.validate_node(token, child, lo, hi, depth + 1, leaf_depth)?;
+            }
+        }
+        if node.size != size {
+            return Err("subtree size augmentation is stale");
+        }
+        Ok(size)
     }
 
     /// Returns an iterator over the map.
@@ -1069,7 +1762,7 @@ where
 }
 
 pub struct IntoIter<'brand, K, V> {
-    vec: std::vec::IntoIter<(K, V)>,
+    vec: alloc_crate::vec::IntoIter<(K, V)>,
     phantom: PhantomData<&'brand ()>,
 }
 
@@ -1085,20 +1778,25 @@ impl<'brand, K, V> IntoIterator for BrandedBTreeMap<'brand, K, V> {
     type Item = (K, V);
     type IntoIter = IntoIter<'brand, K, V>;
 
-    fn into_iter(mut self) -> Self::IntoIter {
-        // Collect all items efficiently
-        let mut vec = Vec::with_capacity(self.len);
-        if self.root.is_some() {
-            Self::collect(self.root, &mut self.nodes, &mut vec);
-        }
+    fn into_iter(self) -> Self::IntoIter {
         IntoIter {
-            vec: vec.into_iter(),
+            vec: self.into_vec().into_iter(),
             phantom: PhantomData,
         }
     }
 }
 
 impl<'brand, K, V> BrandedBTreeMap<'brand, K, V> {
+    /// Consumes the map, returning its entries in ascending key order as a `Vec` that
+    /// no longer carries the brand and can leave the token scope.
+    pub fn into_vec(mut self) -> Vec<(K, V)> {
+        let mut vec = Vec::with_capacity(self.len);
+        if self.root.is_some() {
+            Self::collect(self.root, &mut self.nodes, &mut vec);
+        }
+        vec
+    }
+
     fn collect(
         node_idx: NodeIdx<'brand>,
         nodes: &mut BrandedVec<'brand, NodeData<'brand, K, V>>,
@@ -1124,10 +1822,10 @@ impl<'brand, K, V> BrandedBTreeMap<'brand, K, V> {
             let mut vals: [MaybeUninit<V>; MAX_LEN] = MaybeUninit::uninit().assume_init();
             let mut children: [NodeIdx<'brand>; MAX_CHILDREN] = [NodeIdx::NONE; MAX_CHILDREN];
 
-            std::ptr::copy_nonoverlapping(node.keys.as_ptr(), keys.as_mut_ptr(), len);
-            std::ptr::copy_nonoverlapping(node.vals.as_ptr(), vals.as_mut_ptr(), len);
+            core::ptr::copy_nonoverlapping(node.keys.as_ptr(), keys.as_mut_ptr(), len);
+            core::ptr::copy_nonoverlapping(node.vals.as_ptr(), vals.as_mut_ptr(), len);
             if !is_leaf {
-                std::ptr::copy_nonoverlapping(
+                core::ptr::copy_nonoverlapping(
                     node.children.as_ptr(),
                     children.as_mut_ptr(),
                     len + 1,
@@ -1148,6 +1846,90 @@ impl<'brand, K, V> BrandedBTreeMap<'brand, K, V> {
             }
         }
     }
+
+    /// Moves every entry out in key order and resets the map, keeping the node
+    /// arena's allocation for reuse.
+    fn take_entries(&mut self) -> Vec<(K, V)> {
+        let mut vec = Vec::with_capacity(self.len);
+        if self.root.is_some() {
+            Self::collect(self.root, &mut self.nodes, &mut vec);
+        }
+        // Every node now has `len == 0`, so clearing drops nothing twice.
+        self.nodes.clear();
+        self.root = NodeIdx::NONE;
+        self.free_head = NodeIdx::NONE;
+        self.len = 0;
+        vec
+    }
+
+    /// Bulk-loads an empty map from entries already sorted by strictly increasing key.
+    ///
+    /// The tree is built top-down in O(n) with all leaves at the same depth, avoiding
+    /// per-element descent, splits, and rebalancing.
+    fn build_from_sorted(&mut self, entries: Vec<(K, V)>) {
+        debug_assert!(self.root.is_none());
+        let n = entries.len();
+        if n == 0 {
+            return;
+        }
+        let mut height = 0;
+        while max_subtree_len(height) < n {
+            height += 1;
+        }
+        let mut iter = entries.into_iter();
+        self.root = self.build_subtree(&mut iter, n, height, true);
+        self.len = n;
+    }
+
+    fn build_subtree(
+        &mut self,
+        iter: &mut alloc_crate::vec::IntoIter<(K, V)>,
+        count: usize,
+        height: u32,
+        is_root: bool,
+    ) -> NodeIdx<'brand> {
+        let idx = self.alloc_node(height == 0);
+        if height == 0 {
+            let node = unsafe { self.nodes.get_unchecked_mut_exclusive(idx.index()) };
+            for (i, (k, v)) in iter.take(count).enumerate() {
+                node.keys[i].write(k);
+                node.vals[i].write(v);
+            }
+            node.len = u16::try_from(count).expect("node length fits in u16");
+            node.size = count;
+            return idx;
+        }
+
+        // Fewest children whose subtrees can hold the remaining entries, but at least
+        // the per-node minimum (non-root nodes need `MIN_LEN` keys).
+        let child_cap = max_subtree_len(height - 1) + 1;
+        let min_children = if is_root { 2 } else { MIN_LEN + 1 };
+        let children = (count + 1).div_ceil(child_cap).max(min_children);
+        let per_child = (count - (children - 1)) / children;
+        let extra = (count - (children - 1)) % children;
+
+        for i in 0..children {
+            let child_count = per_child + usize::from(i < extra);
+            let child = self.build_subtree(iter, child_count, height - 1, false);
+            let node = unsafe { self.nodes.get_unchecked_mut_exclusive(idx.index()) };
+            node.children[i] = child;
+            if i + 1 < children {
+                let (k, v) = iter.next().expect("entry count mismatch");
+                node.keys[i].write(k);
+                node.vals[i].write(v);
+                node.len += 1;
+            }
+        }
+        unsafe { self.nodes.get_unchecked_mut_exclusive(idx.index()).size = count };
+        idx
+    }
+}
+
+/// Maximum number of entries in a subtree of the given height (leaves are height 0).
+fn max_subtree_len(height: u32) -> usize {
+    MAX_CHILDREN
+        .checked_pow(height + 1)
+        .map_or(usize::MAX, |n| n - 1)
 }
 
 impl<'brand, K, V> Default for BrandedBTreeMap<'brand, K, V> {
@@ -1313,4 +2095,205 @@ mod tests {
             assert_eq!(*map.get(&token, &0).unwrap(), 1);
         });
     }
+
+    #[test]
+    fn test_select_and_rank() {
+        GhostToken::new(|token| {
+            let mut map = BrandedBTreeMap::new();
+            // Scrambled insertion order exercises splits at every position.
+            for i in 0..500u32 {
+                let k = (i * 7919) % 500;
+                map.insert(k * 2, k);
+            }
+
+            for k in 0..500u32 {
+                assert_eq!(map.select(&token, k as usize), Some((&(k * 2), &k)));
+                assert_eq!(map.rank(&token, &(k * 2)), k as usize);
+                assert_eq!(map.rank(&token, &(k * 2 + 1)), k as usize + 1);
+            }
+            assert_eq!(map.select(&token, 500), None);
+
+            // Removals rebalance via rotations and merges; sizes must follow.
+            for k in (0..500u32).filter(|k| k % 3 == 0) {
+                assert_eq!(map.remove(&(k * 2)), Some(k));
+            }
+            let expected: Vec<u32> = (0..500).filter(|k| k % 3 != 0).collect();
+            for (i, &k) in expected.iter().enumerate() {
+                assert_eq!(map.select(&token, i).map(|(key, _)| *key), Some(k * 2));
+                assert_eq!(map.rank(&token, &(k * 2)), i);
+            }
+            assert_eq!(map.select(&token, expected.len()), None);
+        });
+    }
+
+    #[test]
+    fn test_append_and_split_off() {
+        GhostToken::new(|token| {
+            let mut evens = BrandedBTreeMap::new();
+            let mut odds = BrandedBTreeMap::new();
+            for i in 0..300 {
+                evens.insert(i * 2, i);
+                odds.insert(i * 2 + 1, i);
+            }
+            odds.insert(10, 999); // duplicate key: `other` wins
+
+            evens.append(&mut odds);
+            assert!(odds.is_empty());
+            assert_eq!(evens.len(), 600);
+            assert_eq!(evens.get(&token, &10), Some(&999));
+            assert!(evens.keys(&token).copied().eq(0..600));
+            assert_eq!(evens.select(&token, 321), Some((&321, &160)));
+
+            let upper = evens.split_off(&250);
+            assert_eq!(evens.len(), 250);
+            assert_eq!(upper.len(), 350);
+            assert!(evens.keys(&token).copied().eq(0..250));
+            assert!(upper.keys(&token).copied().eq(250..600));
+            assert_eq!(upper.rank(&token, &400), 150);
+
+            // Bulk-loaded trees must still support regular mutation.
+            for i in (0..250).step_by(2) {
+                assert!(evens.remove(&i).is_some());
+            }
+            evens.insert(1000, 0);
+            assert_eq!(evens.len(), 126);
+            assert_eq!(evens.select(&token, 125), Some((&1000, &0)));
+        });
+    }
+
+    #[test]
+    fn test_split_off_at_every_position_and_join_back() {
+        GhostToken::new(|token| {
+            // Even keys only, so odd split points fall between entries.
+            for at in 0usize..=600 {
+                let mut map = BrandedBTreeMap::new();
+                for i in 0..300 {
+                    map.insert(i * 2, i);
+                }
+                let mut upper = map.split_off(&at);
+                assert_eq!(map.validate_invariants(&token), Ok(()), "split at {at}");
+                assert_eq!(upper.validate_invariants(&token), Ok(()), "split at {at}");
+                assert!(map.keys(&token).copied().eq((0..at).filter(|k| k % 2 == 0)));
+                assert!(upper.keys(&token).copied().eq((at..600).filter(|k| k % 2 == 0)));
+
+                // Disjoint ranges take the node-level join.
+                if at % 2 == 0 {
+                    upper.append(&mut map);
+                    core::mem::swap(&mut map, &mut upper);
+                } else {
+                    map.append(&mut upper);
+                }
+                assert!(upper.is_empty());
+                assert_eq!(map.validate_invariants(&token), Ok(()), "join at {at}");
+                assert!(map.keys(&token).copied().eq((0..600).step_by(2)));
+                assert_eq!(map.rank(&token, &at), at.div_ceil(2));
+            }
+        });
+    }
+
+    #[test]
+    fn test_append_disjoint_ranges_of_different_heights() {
+        let sizes = [1, 2, 5, 11, 12, 40, 150, 700];
+        GhostToken::new(|token| {
+            for &a in &sizes {
+                for &b in &sizes {
+                    for lower_first in [true, false] {
+                        let mut lower = BrandedBTreeMap::new();
+                        let mut upper = BrandedBTreeMap::new();
+                        for i in 0..a {
+                            lower.insert(i, i);
+                        }
+                        for i in a..a + b {
+                            upper.insert(i, i);
+                        }
+                        let mut map = if lower_first {
+                            lower.append(&mut upper);
+                            lower
+                        } else {
+                            upper.append(&mut lower);
+                            upper
+                        };
+                        assert_eq!(map.validate_invariants(&token), Ok(()), "{a} + {b}");
+                        assert_eq!(map.len(), a + b);
+                        assert!(map.iter(&token).all(|(k, v)| k == v));
+                        assert!(map.keys(&token).copied().eq(0..a + b));
+                        assert_eq!(map.select(&token, a), Some((&a, &a)));
+
+                        // The joined tree keeps working as a regular map.
+                        for i in (0..a + b).step_by(3) {
+                            assert_eq!(map.remove(&i), Some(i));
+                        }
+                        map.insert(a + b, 0);
+                        assert_eq!(map.validate_invariants(&token), Ok(()), "{a} + {b}");
+                    }
+                }
+            }
+        });
+    }
+
+    #[test]
+    fn test_split_and_join_move_entries_without_dropping_them() {
+        use alloc_crate::rc::Rc;
+
+        let marker = Rc::new(());
+        GhostToken::new(|token| {
+            let mut map = BrandedBTreeMap::new();
+            for i in 0..500 {
+                map.insert(i, Rc::clone(&marker));
+            }
+            let mut upper = map.split_off(&137);
+            let mut top = upper.split_off(&400);
+            assert_eq!(Rc::strong_count(&marker), 501);
+            top.append(&mut map);
+            map.append(&mut upper);
+            assert_eq!((map.len(), top.len()), (263, 237));
+            assert_eq!(Rc::strong_count(&marker), 501);
+            map.append(&mut top);
+            assert_eq!(map.validate_invariants(&token), Ok(()));
+            assert_eq!(map.remove(&250).map(|rc| Rc::ptr_eq(&rc, &marker)), Some(true));
+            assert_eq!(Rc::strong_count(&marker), 500);
+        });
+        assert_eq!(Rc::strong_count(&marker), 1);
+    }
+
+    #[test]
+    fn test_validate_invariants() {
+        GhostToken::new(|token| {
+            let mut map = BrandedBTreeMap::new();
+            crate::debug_assert_valid!(map, &token);
+
+            // Pseudo-random insert/remove mix exercising splits, merges and rotations.
+            let mut x = 7u32;
+            for _ in 0..2000 {
+                x = x.wrapping_mul(1_103_515_245).wrapping_add(12_345);
+                let key = (x >> 16) % 500;
+                if x & 1 == 0 {
+                    map.insert(key, ());
+                } else {
+                    map.remove(&key);
+                }
+                assert_eq!(map.validate_invariants(&token), Ok(()));
+            }
+
+            let mut upper = map.split_off(&250);
+            crate::debug_assert_valid!(map, &token);
+            crate::debug_assert_valid!(upper, &token);
+            map.append(&mut upper);
+            crate::debug_assert_valid!(map, &token);
+        });
+    }
+
+    #[test]
+    fn test_into_vec_escapes_scope() {
+        let entries = GhostToken::new(|_token| {
+            let mut map = BrandedBTreeMap::new();
+            for i in (0..100u32).rev() {
+                map.insert(i, i.to_string());
+            }
+            map.into_vec()
+        });
+        assert_eq!(entries.len(), 100);
+        assert!(entries.windows(2).all(|w| w[0].0 < w[1].0));
+        assert_eq!(entries[42], (42, "42".to_string()));
+    }
 }
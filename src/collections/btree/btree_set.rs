@@ -4,7 +4,7 @@
 
 use super::btree_map::BrandedBTreeMap;
 use crate::token::traits::GhostBorrow;
-use std::borrow::Borrow;
+use core::borrow::Borrow;
 
 /// A B-Tree set.
 pub struct BrandedBTreeSet<'brand, T> {
@@ -60,6 +60,54 @@ where
         self.map.remove(value).is_some()
     }
 
+    /// Moves all values of `other` into `self`, leaving `other` empty.
+    pub fn append(&mut self, other: &mut Self) {
+        self.map.append(&mut other.map);
+    }
+
+    /// Splits the set at `value`, returning everything greater than or equal to it.
+    pub fn split_off<Q: ?Sized>(&mut self, value: &Q) -> Self
+    where
+        T: Borrow<Q>,
+        Q: Ord,
+    {
+        Self {
+            map: self.map.split_off(value),
+        }
+    }
+
+    /// Returns the `k`-th smallest value (0-based), or `None` if `k >= len()`.
+    pub fn select<'a, Token>(&'a self, token: &'a Token, k: usize) -> Option<&'a T>
+    where
+        Token: GhostBorrow<'brand>,
+    {
+        self.map.select(token, k).map(|(value, _)| value)
+    }
+
+    /// Returns the number of values strictly less than `value`.
+    pub fn rank<Q: ?Sized, Token>(&self, token: &Token, value: &Q) -> usize
+    where
+        T: Borrow<Q>,
+        Q: Ord,
+        Token: GhostBorrow<'brand>,
+    {
+        self.map.rank(token, value)
+    }
+
+    /// Checks the structural invariants of the underlying tree.
+    ///
+    /// See [`BrandedBTreeMap::validate_invariants`].
+    ///
+    /// # Errors
+    /// Returns a description of the first invariant found violated.
+    pub fn validate_invariants<Token>(&self, token: &Token) -> Result<(), &'static str>
+    where
+        T: Ord,
+        Token: GhostBorrow<'brand>,
+    {
+        self.map.validate_invariants(token)
+    }
+
     /// Returns an iterator over the values in the set.
     pub fn iter<'a, Token>(
         &'a self,
@@ -119,4 +167,23 @@ mod tests {
             assert_eq!(set.len(), 2);
         });
     }
+
+    #[test]
+    fn test_set_append_split_off() {
+        GhostToken::new(|token| {
+            let mut low: BrandedBTreeSet<i32> = BrandedBTreeSet::new();
+            let mut high = BrandedBTreeSet::new();
+            for i in 0..50 {
+                low.insert(i);
+                high.insert(i + 40);
+            }
+            low.append(&mut high);
+            assert!(high.is_empty());
+            assert!(low.iter(&token).copied().eq(0..90));
+
+            let tail = low.split_off(&75);
+            assert!(low.iter(&token).copied().eq(0..75));
+            assert!(tail.iter(&token).copied().eq(75..90));
+        });
+    }
 }
@@ -0,0 +1,684 @@
+//! `BrandedIntervalTree` — a map from possibly overlapping half-open intervals to
+//! token-gated values.
+//!
+//! Both this and [`BrandedIntervalMap`](crate::collections::other::BrandedIntervalMap)
+//! map intervals to values, but they keep different invariants and are not merged:
+//! - `BrandedIntervalMap` keeps its intervals disjoint, overwriting the overlapped parts
+//!   of older ones on insert. A point has at most one value, found by one binary search
+//!   over a sorted vector, but every insert is O(n).
+//! - `BrandedIntervalTree` keeps every inserted interval as it was given. A point may
+//!   lie in any number of them, so queries yield every match. Inserts and removals are
+//!   O(log n), and queries only descend into subtrees that can hold a match.
+//!
+//! The map cannot keep overlaps without changing what its lookups mean, so the two
+//! stay separate types rather than one structure with a mode.
+//!
+//! Intervals live in a B-tree whose nodes are stored in a `BrandedVec` arena, like
+//! [`BrandedBTreeMap`](super::BrandedBTreeMap). Entries sit in the leaves, ordered by
+//! `(start, end)`, and every node caches the largest `end` in its subtree. Queries use
+//! both orders to prune:
+//! - a subtree whose largest `end` is at or before the query start is skipped;
+//! - the walk stops at the first interval that starts after the query.
+//!
+//! Structural mutation (`insert`, `remove`) takes `&mut self`; reading values takes a
+//! shared token and mutating them an exclusive one.
+
+use crate::collections::BrandedCollection;
+use crate::collections::BrandedVec;
+use crate::token::traits::{GhostBorrow, GhostBorrowMut};
+use core::cmp::Ordering;
+use core::ops::Range;
+#[cfg(not(feature = "std"))]
+use alloc_crate::{vec, vec::Vec};
+
+const B: usize = 6;
+/// Maximum entries per leaf and children per internal node.
+const MAX_LEN: usize = 2 * B;
+/// Minimum entries/children of every node but the root.
+const MIN_LEN: usize = B - 1;
+const NONE: usize = usize::MAX;
+
+#[inline]
+fn cmp_interval<K: Ord>(a: &Range<K>, b: &Range<K>) -> Ordering {
+    a.start.cmp(&b.start).then_with(|| a.end.cmp(&b.end))
+}
+
+struct Node<K, V> {
+    /// Leaf payload, sorted by `(start, end)`. Empty in internal nodes.
+    entries: Vec<(Range<K>, V)>,
+    /// Child node indices. Empty in leaves.
+    children: Vec<usize>,
+    /// Smallest interval in each child's subtree, used for routing.
+    lows: Vec<Range<K>>,
+    /// Largest `end` in this subtree.
+    max_end: Option<K>,
+    is_leaf: bool,
+}
+
+impl<K, V> Node<K, V> {
+    fn new(is_leaf: bool) -> Self {
+        Self {
+            entries: Vec::new(),
+            children: Vec::new(),
+            lows: Vec::new(),
+            max_end: None,
+            is_leaf,
+        }
+    }
+
+    #[inline]
+    fn len(&self) -> usize {
+        if self.is_leaf {
+            self.entries.len()
+        } else {
+            self.children.len()
+        }
+    }
+}
+
+impl<K: Ord, V> Node<K, V> {
+    /// Index of the child whose subtree holds (or would hold) `range`.
+    #[inline]
+    fn route(&self, range: &Range<K>) -> usize {
+        self.lows
+            .partition_point(|low| cmp_interval(low, range) != Ordering::Greater)
+            .saturating_sub(1)
+    }
+}
+
+/// An interval tree mapping half-open intervals `start..end` to values, with stabbing
+/// and overlap queries.
+///
+/// Each distinct interval maps to one value; inserting the same interval again
+/// replaces it. Different intervals may overlap freely.
+pub struct BrandedIntervalTree<'brand, K, V> {
+    nodes: BrandedVec<'brand, Node<K, V>>,
+    free: Vec<usize>,
+    root: usize,
+    len: usize,
+}
+
+impl<'brand, K, V> BrandedIntervalTree<'brand, K, V> {
+    /// Creates an empty map.
+    pub fn new() -> Self {
+        Self {
+            nodes: BrandedVec::new(),
+            free: Vec::new(),
+            root: NONE,
+            len: 0,
+        }
+    }
+
+    /// Returns the number of intervals in the map.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if the map holds no intervals.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Removes every interval.
+    pub fn clear(&mut self) {
+        self.nodes.clear();
+        self.free.clear();
+        self.root = NONE;
+        self.len = 0;
+    }
+
+    fn alloc_node(&mut self, is_leaf: bool) -> usize {
+        match self.free.pop() {
+            Some(idx) => {
+                self.nodes.as_mut_slice_exclusive()[idx] = Node::new(is_leaf);
+                idx
+            }
+            None => {
+                self.nodes.push(Node::new(is_leaf));
+                self.nodes.len() - 1
+            }
+        }
+    }
+
+    fn free_node(&mut self, idx: usize) {
+        self.nodes.as_mut_slice_exclusive()[idx] = Node::new(true);
+        self.free.push(idx);
+    }
+}
+
+impl<'brand, K, V> BrandedIntervalTree<'brand, K, V>
+where
+    K: Ord,
+{
+    /// Finds the `(leaf, slot)` holding exactly `range`.
+    fn locate(&self, nodes: &[Node<K, V>], range: &Range<K>) -> Option<(usize, usize)> {
+        let mut idx = self.root;
+        while idx != NONE {
+            let node = &nodes[idx];
+            if node.is_leaf {
+                return node
+                    .entries
+                    .binary_search_by(|(r, _)| cmp_interval(r, range))
+                    .ok()
+                    .map(|slot| (idx, slot));
+            }
+            if cmp_interval(range, &node.lows[0]) == Ordering::Less {
+                return None;
+            }
+            idx = node.children[node.route(range)];
+        }
+        None
+    }
+
+    /// Returns the value stored for exactly `range`.
+    pub fn get<'a, Token>(&'a self, token: &'a Token, range: &Range<K>) -> Option<&'a V>
+    where
+        Token: GhostBorrow<'brand>,
+    {
+        let nodes = self.nodes.as_slice(token);
+        let (idx, slot) = self.locate(nodes, range)?;
+        Some(&nodes[idx].entries[slot].1)
+    }
+
+    /// Returns a mutable reference to the value stored for exactly `range`.
+    pub fn get_mut<'a, Token>(&'a self, token: &'a mut Token, range: &Range<K>) -> Option<&'a mut V>
+    where
+        Token: GhostBorrowMut<'brand>,
+    {
+        let (idx, slot) = self.locate(self.nodes.as_slice(token), range)?;
+        Some(&mut self.nodes.as_mut_slice(token)[idx].entries[slot].1)
+    }
+
+    fn query<'a, Token>(&'a self, token: &'a Token, query: Query<'a, K>) -> Intervals<'a, K, V>
+    where
+        Token: GhostBorrow<'brand>,
+    {
+        let nodes = self.nodes.as_slice(token);
+        let mut stack = Vec::new();
+        if self.root != NONE
+            && nodes[self.root]
+                .max_end
+                .as_ref()
+                .is_some_and(|end| !query.ends_before(end))
+        {
+            stack.push((self.root, 0));
+        }
+        Intervals {
+            nodes,
+            query,
+            stack,
+        }
+    }
+
+    /// Iterates over all intervals in `(start, end)` order.
+    pub fn iter<'a, Token>(&'a self, token: &'a Token) -> Intervals<'a, K, V>
+    where
+        Token: GhostBorrow<'brand>,
+    {
+        self.query(token, Query::All)
+    }
+
+    /// Iterates over the intervals containing `point`, in `(start, end)` order.
+    ///
+    /// **Time complexity**: \(O((k + 1) \log n)\) for `k` results.
+    pub fn stab<'a, Token>(&'a self, token: &'a Token, point: &'a K) -> Intervals<'a, K, V>
+    where
+        Token: GhostBorrow<'brand>,
+    {
+        self.query(token, Query::Stab(point))
+    }
+
+    /// Iterates over the intervals overlapping `range`, in `(start, end)` order.
+    ///
+    /// Intervals are half-open, so `0..5` and `5..10` do not overlap.
+    ///
+    /// **Time complexity**: \(O((k + 1) \log n)\) for `k` results.
+    pub fn overlapping<'a, Token>(
+        &'a self,
+        token: &'a Token,
+        range: &'a Range<K>,
+    ) -> Intervals<'a, K, V>
+    where
+        Token: GhostBorrow<'brand>,
+    {
+        self.query(token, Query::Overlap(range))
+    }
+}
+
+impl<'brand, K, V> BrandedIntervalTree<'brand, K, V>
+where
+    K: Ord + Clone,
+{
+    /// Recomputes the cached largest `end` of `idx` from its entries or children.
+    fn recompute_max_end(&mut self, idx: usize) {
+        let nodes = self.nodes.as_mut_slice_exclusive();
+        let node = &nodes[idx];
+        let max_end = if node.is_leaf {
+            node.entries.iter().map(|(r, _)| &r.end).max().cloned()
+        } else {
+            node.children
+                .iter()
+                .filter_map(|&c| nodes[c].max_end.as_ref())
+                .max()
+                .cloned()
+        };
+        nodes[idx].max_end = max_end;
+    }
+
+    fn first_low(&mut self, idx: usize) -> Range<K> {
+        let node = &self.nodes.as_mut_slice_exclusive()[idx];
+        if node.is_leaf {
+            node.entries[0].0.clone()
+        } else {
+            node.lows[0].clone()
+        }
+    }
+
+    /// Inserts `value` for `range`, returning the previous value for the same interval.
+    ///
+    /// # Panics
+    /// Panics if `range` is empty (`start >= end`).
+    pub fn insert(&mut self, range: Range<K>, value: V) -> Option<V> {
+        assert!(range.start < range.end, "interval must be non-empty");
+        if self.root == NONE {
+            self.root = self.alloc_node(true);
+        }
+
+        let (old, split) = self.insert_rec(self.root, range, value);
+        if let Some((low, right)) = split {
+            let left = self.root;
+            let left_low = self.first_low(left);
+            let root = self.alloc_node(false);
+            let node = &mut self.nodes.as_mut_slice_exclusive()[root];
+            node.children = vec![left, right];
+            node.lows = vec![left_low, low];
+            self.recompute_max_end(root);
+            self.root = root;
+        }
+        if old.is_none() {
+            self.len += 1;
+        }
+        old
+    }
+
+    /// Inserts into the subtree at `idx`, returning the replaced value and, if the node
+    /// overflowed, the new right sibling with its smallest interval.
+    fn insert_rec(
+        &mut self,
+        idx: usize,
+        range: Range<K>,
+        value: V,
+    ) -> (Option<V>, Option<(Range<K>, usize)>) {
+        let node = &mut self.nodes.as_mut_slice_exclusive()[idx];
+        // An existing interval already lies within `lows` and `max_end`, so widening
+        // them up front is correct whether or not this turns out to be a replacement.
+        if node.max_end.as_ref().is_none_or(|end| range.end > *end) {
+            node.max_end = Some(range.end.clone());
+        }
+
+        if node.is_leaf {
+            match node
+                .entries
+                .binary_search_by(|(r, _)| cmp_interval(r, &range))
+            {
+                Ok(slot) => {
+                    return (
+                        Some(core::mem::replace(&mut node.entries[slot].1, value)),
+                        None,
+                    )
+                }
+                Err(slot) => node.entries.insert(slot, (range, value)),
+            }
+        } else {
+            let pos = node.route(&range);
+            if cmp_interval(&range, &node.lows[pos]) == Ordering::Less {
+                node.lows[pos] = range.clone();
+            }
+            let child = node.children[pos];
+            let (old, split) = self.insert_rec(child, range, value);
+            if old.is_some() {
+                return (old, None);
+            }
+            if let Some((low, right)) = split {
+                let node = &mut self.nodes.as_mut_slice_exclusive()[idx];
+                node.children.insert(pos + 1, right);
+                node.lows.insert(pos + 1, low);
+            }
+        }
+
+        if self.nodes.as_mut_slice_exclusive()[idx].len() > MAX_LEN {
+            (None, Some(self.split(idx)))
+        } else {
+            (None, None)
+        }
+    }
+
+    /// Moves the upper half of `idx` into a new sibling, returning the sibling and its
+    /// smallest interval.
+    fn split(&mut self, idx: usize) -> (Range<K>, usize) {
+        let is_leaf = self.nodes.as_mut_slice_exclusive()[idx].is_leaf;
+        let right = self.alloc_node(is_leaf);
+        let nodes = self.nodes.as_mut_slice_exclusive();
+        let mid = nodes[idx].len() / 2;
+        let left = &mut nodes[idx];
+        let mut upper = Node::new(is_leaf);
+        if is_leaf {
+            upper.entries = left.entries.split_off(mid);
+        } else {
+            upper.children = left.children.split_off(mid);
+            upper.lows = left.lows.split_off(mid);
+        }
+        nodes[right] = upper;
+
+        self.recompute_max_end(idx);
+        self.recompute_max_end(right);
+        (self.first_low(right), right)
+    }
+
+    /// Removes exactly `range`, returning its value.
+    pub fn remove(&mut self, range: &Range<K>) -> Option<V> {
+        if self.root == NONE {
+            return None;
+        }
+        let value = self.remove_rec(self.root, range)?;
+        self.len -= 1;
+
+        let old_root = self.root;
+        let root = &self.nodes.as_mut_slice_exclusive()[old_root];
+        if root.len() == 0 {
+            self.root = NONE;
+        } else if !root.is_leaf && root.children.len() == 1 {
+            self.root = root.children[0];
+        }
+        if self.root != old_root {
+            self.free_node(old_root);
+        }
+        Some(value)
+    }
+
+    fn remove_rec(&mut self, idx: usize, range: &Range<K>) -> Option<V> {
+        let node = &mut self.nodes.as_mut_slice_exclusive()[idx];
+        let value = if node.is_leaf {
+            let slot = node
+                .entries
+                .binary_search_by(|(r, _)| cmp_interval(r, range))
+                .ok()?;
+            node.entries.remove(slot).1
+        } else {
+            if cmp_interval(range, &node.lows[0]) == Ordering::Less {
+                return None;
+            }
+            let pos = node.route(range);
+            let child = node.children[pos];
+            let value = self.remove_rec(child, range)?;
+
+            // Non-root nodes hold at least `MIN_LEN` items, so `child` is not empty.
+            let low = self.first_low(child);
+            self.nodes.as_mut_slice_exclusive()[idx].lows[pos] = low;
+            if self.nodes.as_mut_slice_exclusive()[child].len() < MIN_LEN {
+                self.rebalance(idx, pos);
+            }
+            value
+        };
+        self.recompute_max_end(idx);
+        Some(value)
+    }
+
+    /// Restores the minimum occupancy of child `pos` of `parent`, merging it with a
+    /// sibling or, if both together would overflow, splitting their items evenly.
+    fn rebalance(&mut self, parent: usize, pos: usize) {
+        let nodes = self.nodes.as_mut_slice_exclusive();
+        let siblings = &nodes[parent].children;
+        let (l, r) = if pos + 1 < siblings.len() {
+            (pos, pos + 1)
+        } else {
+            (pos - 1, pos)
+        };
+        let (left, right) = (siblings[l], siblings[r]);
+
+        let mut upper = core::mem::replace(&mut nodes[right], Node::new(true));
+        let lower = &mut nodes[left];
+        lower.entries.append(&mut upper.entries);
+        lower.children.append(&mut upper.children);
+        lower.lows.append(&mut upper.lows);
+
+        if lower.len() <= MAX_LEN {
+            let parent_node = &mut nodes[parent];
+            parent_node.children.remove(r);
+            parent_node.lows.remove(r);
+            self.free.push(right);
+            self.recompute_max_end(left);
+            return;
+        }
+
+        let mid = lower.len() / 2;
+        if lower.is_leaf {
+            upper.entries = lower.entries.split_off(mid);
+        } else {
+            upper.children = lower.children.split_off(mid);
+            upper.lows = lower.lows.split_off(mid);
+        }
+        nodes[right] = upper;
+        let low = self.first_low(right);
+        self.nodes.as_mut_slice_exclusive()[parent].lows[r] = low;
+        self.recompute_max_end(left);
+        self.recompute_max_end(right);
+    }
+}
+
+impl<'brand, K, V> Default for BrandedIntervalTree<'brand, K, V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<'brand, K, V> BrandedCollection<'brand> for BrandedIntervalTree<'brand, K, V> {
+    fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    fn len(&self) -> usize {
+        self.len
+    }
+}
+
+/// The predicate an [`Intervals`] walk filters by.
+enum Query<'a, K> {
+    All,
+    Stab(&'a K),
+    Overlap(&'a Range<K>),
+}
+
+impl<K: Ord> Query<'_, K> {
+    /// Whether every interval ending at or before `end` misses the query.
+    #[inline]
+    fn ends_before(&self, end: &K) -> bool {
+        match self {
+            Query::All => false,
+            Query::Stab(point) => end <= *point,
+            Query::Overlap(range) => *end <= range.start,
+        }
+    }
+
+    /// Whether every interval starting at or after `start` misses the query.
+    #[inline]
+    fn starts_after(&self, start: &K) -> bool {
+        match self {
+            Query::All => false,
+            Query::Stab(point) => start > *point,
+            Query::Overlap(range) => *start >= range.end,
+        }
+    }
+
+    #[inline]
+    fn matches(&self, interval: &Range<K>) -> bool {
+        !self.ends_before(&interval.end) && !self.starts_after(&interval.start)
+    }
+}
+
+/// Iterator over the intervals of a [`BrandedIntervalTree`] matching a query, in
+/// `(start, end)` order.
+pub struct Intervals<'a, K, V> {
+    nodes: &'a [Node<K, V>],
+    query: Query<'a, K>,
+    /// `(node, next slot)` frames of the in-order walk.
+    stack: Vec<(usize, usize)>,
+}
+
+impl<'a, K: Ord, V> Iterator for Intervals<'a, K, V> {
+    type Item = (&'a Range<K>, &'a V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let (idx, slot) = *self.stack.last()?;
+            let node = &self.nodes[idx];
+            if slot == node.len() {
+                self.stack.pop();
+                continue;
+            }
+            if let Some(top) = self.stack.last_mut() {
+                top.1 += 1;
+            }
+
+            let low = if node.is_leaf {
+                &node.entries[slot].0
+            } else {
+                &node.lows[slot]
+            };
+            if self.query.starts_after(&low.start) {
+                // Everything after this point starts even later.
+                self.stack.clear();
+                return None;
+            }
+
+            if node.is_leaf {
+                let (range, value) = &node.entries[slot];
+                if self.query.matches(range) {
+                    return Some((range, value));
+                }
+            } else {
+                let child = node.children[slot];
+                if self.nodes[child]
+                    .max_end
+                    .as_ref()
+                    .is_some_and(|end| !self.query.ends_before(end))
+                {
+                    self.stack.push((child, 0));
+                }
+            }
+        }
+    }
+}
+
+impl<K: Ord, V> core::iter::FusedIterator for Intervals<'_, K, V> {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::GhostToken;
+
+    fn stab<'brand>(
+        map: &BrandedIntervalTree<'brand, i32, &'static str>,
+        token: &GhostToken<'brand>,
+        point: i32,
+    ) -> Vec<&'static str> {
+        map.stab(token, &point).map(|(_, v)| *v).collect()
+    }
+
+    #[test]
+    fn interval_tree_stab_and_overlap() {
+        GhostToken::new(|mut token| {
+            let mut map = BrandedIntervalTree::new();
+            assert_eq!(map.insert(0..10, "a"), None);
+            map.insert(5..15, "b");
+            map.insert(20..30, "c");
+            map.insert(5..8, "d");
+            assert_eq!(map.insert(5..15, "B"), Some("b"));
+            assert_eq!(map.len(), 4);
+
+            assert_eq!(stab(&map, &token, 6), vec!["a", "d", "B"]);
+            assert_eq!(stab(&map, &token, 10), vec!["B"]);
+            assert!(stab(&map, &token, 15).is_empty());
+
+            let hits: Vec<_> = map
+                .overlapping(&token, &(14..21))
+                .map(|(r, _)| r.clone())
+                .collect();
+            assert_eq!(hits, vec![5..15, 20..30]);
+            assert_eq!(map.overlapping(&token, &(15..20)).count(), 0);
+
+            *map.get_mut(&mut token, &(20..30)).unwrap() = "C";
+            assert_eq!(map.get(&token, &(20..30)), Some(&"C"));
+            assert_eq!(map.remove(&(0..10)), Some("a"));
+            assert_eq!(map.remove(&(0..10)), None);
+            assert_eq!(stab(&map, &token, 6), vec!["d", "B"]);
+        });
+    }
+
+    #[test]
+    fn interval_tree_matches_brute_force() {
+        GhostToken::new(|token| {
+            let mut map = BrandedIntervalTree::new();
+            let mut model: Vec<(Range<u32>, u32)> = Vec::new();
+
+            let mut x = 17u32;
+            let mut next = || {
+                x = x.wrapping_mul(1_103_515_245).wrapping_add(12_345);
+                x >> 16
+            };
+            for step in 0..3000 {
+                let start = next() % 1000;
+                let range = start..start + 1 + next() % 60;
+                if next() % 3 == 0 && !model.is_empty() {
+                    let victim = model.remove(next() as usize % model.len());
+                    assert_eq!(map.remove(&victim.0), Some(victim.1));
+                } else {
+                    let old = model
+                        .iter()
+                        .position(|(r, _)| *r == range)
+                        .map(|i| model.remove(i).1);
+                    assert_eq!(map.insert(range.clone(), step), old);
+                    model.push((range, step));
+                }
+                assert_eq!(map.len(), model.len());
+
+                if step % 50 == 0 {
+                    model.sort_by(|a, b| cmp_interval(&a.0, &b.0));
+                    let all: Vec<_> = map.iter(&token).map(|(r, v)| (r.clone(), *v)).collect();
+                    assert_eq!(all, model);
+
+                    let p = next() % 1000;
+                    let stabbed: Vec<_> = map.stab(&token, &p).map(|(_, v)| *v).collect();
+                    let expected: Vec<_> = model
+                        .iter()
+                        .filter(|(r, _)| r.contains(&p))
+                        .map(|(_, v)| *v)
+                        .collect();
+                    assert_eq!(stabbed, expected);
+
+                    let q = p..p + next() % 100 + 1;
+                    let hits: Vec<_> = map.overlapping(&token, &q).map(|(_, v)| *v).collect();
+                    let expected: Vec<_> = model
+                        .iter()
+                        .filter(|(r, _)| r.start < q.end && q.start < r.end)
+                        .map(|(_, v)| *v)
+                        .collect();
+                    assert_eq!(hits, expected);
+                }
+            }
+
+            while let Some((range, value)) = model.pop() {
+                assert_eq!(map.remove(&range), Some(value));
+            }
+            assert!(map.is_empty());
+            assert_eq!(map.iter(&token).count(), 0);
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "non-empty")]
+    fn interval_tree_rejects_empty_intervals() {
+        let mut map: BrandedIntervalTree<'_, i32, ()> = BrandedIntervalTree::new();
+        map.insert(3..3, ());
+    }
+}
@@ -10,7 +10,9 @@ pub mod active_bplus_tree;
 pub mod bplus_tree;
 pub mod btree_map;
 pub mod btree_set;
+pub mod interval_tree;
 
 pub use active::{ActivateBTreeMap, ActivateBTreeSet, ActiveBTreeMap, ActiveBTreeSet};
 pub use btree_map::BrandedBTreeMap;
 pub use btree_set::BrandedBTreeSet;
+pub use interval_tree::BrandedIntervalTree;
@@ -6,7 +6,7 @@
 
 use super::{BrandedHashMap, BrandedHashSet};
 use crate::GhostToken;
-use std::hash::{BuildHasher, Hash};
+use core::hash::{BuildHasher, Hash};
 
 /// A wrapper around a mutable reference to a `BrandedHashMap` and a mutable reference to a `GhostToken`.
 pub struct ActiveHashMap<'a, 'brand, K, V, S> {
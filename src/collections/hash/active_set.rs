@@ -5,11 +5,11 @@
 
 use super::BrandedHashSet;
 use crate::GhostToken;
-use std::collections::hash_map::RandomState;
-use std::hash::{BuildHasher, Hash};
+use super::DefaultHashBuilder;
+use core::hash::{BuildHasher, Hash};
 
 /// A wrapper around a mutable reference to a `BrandedHashSet` and a mutable reference to a `GhostToken`.
-pub struct ActiveHashSet<'a, 'brand, K, S = RandomState> {
+pub struct ActiveHashSet<'a, 'brand, K, S = DefaultHashBuilder> {
     set: &'a mut BrandedHashSet<'brand, K, S>,
     token: &'a mut GhostToken<'brand>,
 }
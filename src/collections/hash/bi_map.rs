@@ -0,0 +1,319 @@
+//! `BrandedBiMap` — a bijective map between two key types.
+//!
+//! Every left value is paired with exactly one right value and vice versa, so the map
+//! can be queried in both directions in O(1). It is built from two synchronized
+//! [`BrandedHashMap`]s, one per direction, each storing a clone of the other side.
+//!
+//! Lookups take a token like any other branded map. There is no `get_mut`: changing a
+//! value in place would desynchronize the two halves, so pairs are replaced with
+//! [`insert_overwrite`](BrandedBiMap::insert_overwrite) instead.
+
+use super::{BrandedHashMap, DefaultHashBuilder};
+use crate::collections::BrandedCollection;
+use crate::token::traits::GhostBorrow;
+use core::borrow::Borrow;
+use core::hash::{BuildHasher, Hash};
+
+/// The pairs displaced by [`BrandedBiMap::insert_overwrite`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Overwritten<L, R> {
+    /// Neither value was present.
+    Neither,
+    /// The left value was paired with another right value; the old pair is returned.
+    Left(L, R),
+    /// The right value was paired with another left value; the old pair is returned.
+    Right(L, R),
+    /// The exact pair was already present.
+    Pair(L, R),
+    /// Both values were present in two different pairs, returned as
+    /// `(left's pair, right's pair)`.
+    Both((L, R), (L, R)),
+}
+
+/// A one-to-one map between left values `L` and right values `R`.
+pub struct BrandedBiMap<'brand, L, R, S = DefaultHashBuilder> {
+    left_to_right: BrandedHashMap<'brand, L, R, S>,
+    right_to_left: BrandedHashMap<'brand, R, L, S>,
+}
+
+impl<'brand, L, R> BrandedBiMap<'brand, L, R, DefaultHashBuilder>
+where
+    L: Eq + Hash + Clone,
+    R: Eq + Hash + Clone,
+{
+    /// Creates an empty map.
+    pub fn new() -> Self {
+        Self::with_hasher(DefaultHashBuilder::default())
+    }
+
+    /// Creates an empty map with room for `capacity` pairs.
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self::with_capacity_and_hasher(capacity, DefaultHashBuilder::default())
+    }
+}
+
+impl<'brand, L, R, S> BrandedBiMap<'brand, L, R, S>
+where
+    L: Eq + Hash + Clone,
+    R: Eq + Hash + Clone,
+    S: BuildHasher + Clone,
+{
+    /// Creates an empty map hashing both sides with `hash_builder`.
+    pub fn with_hasher(hash_builder: S) -> Self {
+        Self::with_capacity_and_hasher(0, hash_builder)
+    }
+
+    /// Creates an empty map with room for `capacity` pairs, hashing both sides with
+    /// `hash_builder`.
+    pub fn with_capacity_and_hasher(capacity: usize, hash_builder: S) -> Self {
+        Self {
+            left_to_right: BrandedHashMap::with_capacity_and_hasher(capacity, hash_builder.clone()),
+            right_to_left: BrandedHashMap::with_capacity_and_hasher(capacity, hash_builder),
+        }
+    }
+
+    /// Returns the number of pairs.
+    pub fn len(&self) -> usize {
+        self.left_to_right.len()
+    }
+
+    /// Returns `true` if the map holds no pairs.
+    pub fn is_empty(&self) -> bool {
+        self.left_to_right.is_empty()
+    }
+
+    /// Inserts the pair `(left, right)` if neither value is present yet.
+    ///
+    /// # Errors
+    /// Returns the pair unchanged if `left` or `right` is already paired, leaving the
+    /// map untouched.
+    pub fn insert(&mut self, left: L, right: R) -> Result<(), (L, R)> {
+        if self.left_to_right.contains_key(&left) || self.right_to_left.contains_key(&right) {
+            return Err((left, right));
+        }
+        self.left_to_right.insert(left.clone(), right.clone());
+        self.right_to_left.insert(right, left);
+        Ok(())
+    }
+
+    /// Inserts the pair `(left, right)`, first removing any pair containing `left` or
+    /// `right`, and reports what was displaced.
+    pub fn insert_overwrite(&mut self, left: L, right: R) -> Overwritten<L, R> {
+        let by_left = self.remove_by_left(&left);
+        let by_right = self.remove_by_right(&right);
+        let overwritten = match (by_left, by_right) {
+            (None, None) => Overwritten::Neither,
+            (Some((l, r)), None) if r == right => Overwritten::Pair(l, r),
+            (Some((l, r)), None) => Overwritten::Left(l, r),
+            (None, Some((l, r))) => Overwritten::Right(l, r),
+            (Some(by_left), Some(by_right)) => Overwritten::Both(by_left, by_right),
+        };
+        self.left_to_right.insert(left.clone(), right.clone());
+        self.right_to_left.insert(right, left);
+        overwritten
+    }
+
+    /// Returns the right value paired with `left`.
+    pub fn get_by_left<'a, Q, Token>(&'a self, token: &'a Token, left: &Q) -> Option<&'a R>
+    where
+        L: Borrow<Q>,
+        Q: ?Sized + Hash + Eq,
+        Token: GhostBorrow<'brand>,
+    {
+        self.left_to_right.get(token, left)
+    }
+
+    /// Returns the left value paired with `right`.
+    pub fn get_by_right<'a, Q, Token>(&'a self, token: &'a Token, right: &Q) -> Option<&'a L>
+    where
+        R: Borrow<Q>,
+        Q: ?Sized + Hash + Eq,
+        Token: GhostBorrow<'brand>,
+    {
+        self.right_to_left.get(token, right)
+    }
+
+    /// Returns `true` if `left` is paired with some right value.
+    pub fn contains_left<Q>(&self, left: &Q) -> bool
+    where
+        L: Borrow<Q>,
+        Q: ?Sized + Hash + Eq,
+    {
+        self.left_to_right.contains_key(left)
+    }
+
+    /// Returns `true` if `right` is paired with some left value.
+    pub fn contains_right<Q>(&self, right: &Q) -> bool
+    where
+        R: Borrow<Q>,
+        Q: ?Sized + Hash + Eq,
+    {
+        self.right_to_left.contains_key(right)
+    }
+
+    /// Removes the pair containing `left`, returning it.
+    ///
+    /// # Panics
+    /// Panics if the paired `right` value cannot be found by hashing, which happens
+    /// only when its `Hash` or `Eq` implementation is inconsistent.
+    pub fn remove_by_left<Q>(&mut self, left: &Q) -> Option<(L, R)>
+    where
+        L: Borrow<Q>,
+        Q: ?Sized + Hash + Eq,
+    {
+        let right = self.left_to_right.remove(left)?;
+        let left = self
+            .right_to_left
+            .remove(&right)
+            .expect("BrandedBiMap halves out of sync");
+        Some((left, right))
+    }
+
+    /// Removes the pair containing `right`, returning it.
+    ///
+    /// # Panics
+    /// Panics if the paired `left` value cannot be found by hashing, which happens
+    /// only when its `Hash` or `Eq` implementation is inconsistent.
+    pub fn remove_by_right<Q>(&mut self, right: &Q) -> Option<(L, R)>
+    where
+        R: Borrow<Q>,
+        Q: ?Sized + Hash + Eq,
+    {
+        let left = self.right_to_left.remove(right)?;
+        let right = self
+            .left_to_right
+            .remove(&left)
+            .expect("BrandedBiMap halves out of sync");
+        Some((left, right))
+    }
+
+    /// Removes every pair.
+    pub fn clear(&mut self) {
+        self.left_to_right.clear();
+        self.right_to_left.clear();
+    }
+
+    /// Iterates over the left values, in arbitrary order.
+    pub fn left_values(&self) -> impl Iterator<Item = &L> {
+        self.left_to_right.keys()
+    }
+
+    /// Iterates over the right values, in arbitrary order.
+    pub fn right_values(&self) -> impl Iterator<Item = &R> {
+        self.right_to_left.keys()
+    }
+
+    /// Iterates over every `(left, right)` pair, in arbitrary order.
+    pub fn iter<'a, Token>(
+        &'a self,
+        token: &'a Token,
+    ) -> impl Iterator<Item = (&'a L, &'a R)> + use<'a, 'brand, L, R, S, Token>
+    where
+        Token: GhostBorrow<'brand>,
+    {
+        self.left_to_right
+            .keys()
+            .zip(self.left_to_right.values(token))
+    }
+}
+
+impl<'brand, L, R, S> Default for BrandedBiMap<'brand, L, R, S>
+where
+    L: Eq + Hash + Clone,
+    R: Eq + Hash + Clone,
+    S: BuildHasher + Clone + Default,
+{
+    fn default() -> Self {
+        Self::with_hasher(S::default())
+    }
+}
+
+impl<'brand, L, R, S> BrandedCollection<'brand> for BrandedBiMap<'brand, L, R, S> {
+    fn is_empty(&self) -> bool {
+        self.left_to_right.is_empty()
+    }
+
+    fn len(&self) -> usize {
+        self.left_to_right.len()
+    }
+}
+
+impl<'brand, L, R, S> Extend<(L, R)> for BrandedBiMap<'brand, L, R, S>
+where
+    L: Eq + Hash + Clone,
+    R: Eq + Hash + Clone,
+    S: BuildHasher + Clone,
+{
+    /// Inserts every pair with [`insert_overwrite`](BrandedBiMap::insert_overwrite), so
+    /// later pairs win conflicts.
+    fn extend<I: IntoIterator<Item = (L, R)>>(&mut self, iter: I) {
+        for (left, right) in iter {
+            self.insert_overwrite(left, right);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::GhostToken;
+
+    #[test]
+    fn test_bimap_insert_and_lookup() {
+        GhostToken::new(|token| {
+            let mut map = BrandedBiMap::new();
+            assert!(map.is_empty());
+            assert_eq!(map.insert(1, "one".to_string()), Ok(()));
+            assert_eq!(map.insert(2, "two".to_string()), Ok(()));
+            assert_eq!(
+                map.insert(1, "uno".to_string()),
+                Err((1, "uno".to_string()))
+            );
+            assert_eq!(
+                map.insert(3, "two".to_string()),
+                Err((3, "two".to_string()))
+            );
+            assert_eq!(map.len(), 2);
+
+            assert_eq!(map.get_by_left(&token, &1).map(String::as_str), Some("one"));
+            assert_eq!(map.get_by_right(&token, "two"), Some(&2));
+            assert!(map.contains_left(&2));
+            assert!(!map.contains_right("three"));
+
+            let mut pairs: Vec<_> = map.iter(&token).map(|(l, r)| (*l, r.clone())).collect();
+            pairs.sort();
+            assert_eq!(pairs, [(1, "one".to_string()), (2, "two".to_string())]);
+
+            assert_eq!(map.remove_by_right("one"), Some((1, "one".to_string())));
+            assert_eq!(map.remove_by_left(&1), None);
+            assert!(!map.contains_right("one"));
+            map.clear();
+            assert!(map.is_empty());
+        });
+    }
+
+    #[test]
+    fn test_bimap_insert_overwrite() {
+        GhostToken::new(|token| {
+            let mut map = BrandedBiMap::new();
+            assert_eq!(map.insert_overwrite('a', 1), Overwritten::Neither);
+            assert_eq!(map.insert_overwrite('b', 2), Overwritten::Neither);
+            assert_eq!(map.insert_overwrite('a', 1), Overwritten::Pair('a', 1));
+            assert_eq!(map.insert_overwrite('a', 3), Overwritten::Left('a', 1));
+            assert_eq!(map.insert_overwrite('c', 3), Overwritten::Right('a', 3));
+            assert_eq!(
+                map.insert_overwrite('b', 3),
+                Overwritten::Both(('b', 2), ('c', 3))
+            );
+            assert_eq!(map.len(), 1);
+            assert_eq!(map.get_by_left(&token, &'b'), Some(&3));
+            assert_eq!(map.get_by_right(&token, &3), Some(&'b'));
+            assert!(!map.contains_left(&'a') && !map.contains_left(&'c'));
+            assert!(!map.contains_right(&1) && !map.contains_right(&2));
+
+            map.extend([('x', 10), ('y', 10)]);
+            assert_eq!(map.get_by_right(&token, &10), Some(&'y'));
+            assert!(!map.contains_left(&'x'));
+        });
+    }
+}
@@ -0,0 +1,724 @@
+//! `GhostConcurrentHashMap` — a concurrent hash map with lock-free reads.
+//!
+//! The table is an array of buckets, each a singly linked chain behind its own small
+//! lock. Lookups take no lock at all: they register in the map's reader epoch (one
+//! increment of a per-thread counter) and walk the chain, so they never wait for
+//! writers and scale with the number of readers. Writers lock only the bucket they
+//! change. This is the high-concurrency counterpart of [`ShardedBrandedHashMap`], whose
+//! readers and writers share a lock per shard.
+//!
+//! Memory that readers may still see (replaced values, removed entries, the old table
+//! after a resize) is retired and freed in batches once every reader that could hold
+//! it has left. Writers never wait for readers; reclamation is driven opportunistically
+//! by later writes, and whatever is left is freed when the map is dropped. Because the
+//! map frees its own garbage, keys and values may borrow data, branded state included.
+//!
+//! Since a replaced or removed value may still be read concurrently, `insert` and
+//! `remove` report whether the key was present instead of returning the old value.
+//!
+//! The table doubles when it grows past three quarters full. A resize locks every
+//! bucket of the old table in turn, so writers pause while it runs; readers do not.
+//!
+//! [`ShardedBrandedHashMap`]: super::ShardedBrandedHashMap
+
+use crate::concurrency::atomic::GhostShardedCounter;
+use crate::concurrency::sync::{GracePeriod, Reclaim, RetireList};
+use core::borrow::Borrow;
+use core::hash::{BuildHasher, Hash};
+use core::marker::PhantomData;
+use core::ptr;
+use core::sync::atomic::{AtomicBool, AtomicPtr, AtomicU8, Ordering};
+use std::collections::hash_map::RandomState;
+
+const MIN_BUCKETS: usize = 16;
+/// A chain this long on insert prompts a check whether the table should grow.
+const LONG_CHAIN: usize = 3;
+/// Spins before a thread waiting for a bucket lock or a resize starts yielding.
+const SPINS_BEFORE_YIELD: u32 = 64;
+
+const UNLOCKED: u8 = 0;
+const LOCKED: u8 = 1;
+/// The bucket's table was replaced by a resize; writers retry on the new table.
+const MOVED: u8 = 2;
+
+struct Entry<K, V> {
+    hash: u64,
+    key: K,
+    value: AtomicPtr<V>,
+}
+
+struct Link<K, V> {
+    entry: *mut Entry<K, V>,
+    next: AtomicPtr<Link<K, V>>,
+}
+
+struct Bucket<K, V> {
+    lock: AtomicU8,
+    head: AtomicPtr<Link<K, V>>,
+}
+
+struct Table<K, V> {
+    buckets: Box<[Bucket<K, V>]>,
+}
+
+/// An allocation unlinked from the map that readers may still be looking at.
+enum Retired<K, V> {
+    Value(*mut V),
+    /// An entry together with its current value.
+    Entry(*mut Entry<K, V>),
+    Link(*mut Link<K, V>),
+    Table(*mut Table<K, V>),
+}
+
+/// A hash map for many threads, with lock-free lookups and per-bucket write locks.
+///
+/// The closure passed to [`compute_if_present`](Self::compute_if_present) runs with a
+/// bucket locked and must not call back into the map.
+pub struct GhostConcurrentHashMap<'brand, K, V, S = RandomState> {
+    table: AtomicPtr<Table<K, V>>,
+    len: GhostShardedCounter<'brand>,
+    grace: GracePeriod,
+    retired: RetireList<Retired<K, V>>,
+    resizing: AtomicBool,
+    hash_builder: S,
+    _marker: PhantomData<(K, V)>,
+}
+
+// SAFETY: keys and values are shared between threads (`Sync`) and dropped by whichever
+// thread retires or reclaims them (`Send`); the hasher is only used through `&S`.
+unsafe impl<'brand, K: Send + Sync, V: Send + Sync, S: Send> Send
+    for GhostConcurrentHashMap<'brand, K, V, S>
+{
+}
+unsafe impl<'brand, K: Send + Sync, V: Send + Sync, S: Sync> Sync
+    for GhostConcurrentHashMap<'brand, K, V, S>
+{
+}
+
+impl<'brand, K, V> GhostConcurrentHashMap<'brand, K, V, RandomState>
+where
+    K: Eq + Hash,
+{
+    /// Creates an empty map.
+    pub fn new() -> Self {
+        Self::with_capacity_and_hasher(0, RandomState::new())
+    }
+
+    /// Creates an empty map with room for `capacity` entries before it resizes.
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self::with_capacity_and_hasher(capacity, RandomState::new())
+    }
+}
+
+impl<'brand, K, V> Default for GhostConcurrentHashMap<'brand, K, V, RandomState>
+where
+    K: Eq + Hash,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<'brand, K, V, S> GhostConcurrentHashMap<'brand, K, V, S>
+where
+    K: Eq + Hash,
+    S: BuildHasher,
+{
+    /// Creates an empty map hashing keys with `hash_builder`.
+    pub fn with_hasher(hash_builder: S) -> Self {
+        Self::with_capacity_and_hasher(0, hash_builder)
+    }
+
+    /// Creates an empty map with room for `capacity` entries, hashing keys with
+    /// `hash_builder`.
+    pub fn with_capacity_and_hasher(capacity: usize, hash_builder: S) -> Self {
+        let buckets = (capacity.saturating_mul(4) / 3)
+            .max(MIN_BUCKETS)
+            .next_power_of_two();
+        Self {
+            table: AtomicPtr::new(Table::alloc(buckets)),
+            len: GhostShardedCounter::new(),
+            grace: GracePeriod::new(),
+            retired: RetireList::new(),
+            resizing: AtomicBool::new(false),
+            hash_builder,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Returns the number of entries.
+    ///
+    /// The count is striped across threads and summed on demand, so it may be stale
+    /// while writers are active.
+    pub fn len(&self) -> usize {
+        // Decrements wrap, so a sum racing with writers can come out slightly negative.
+        usize::try_from(self.len.sum().cast_signed()).unwrap_or(0)
+    }
+
+    /// Returns `true` if the map holds no entries.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the number of buckets in the current table.
+    pub fn bucket_count(&self) -> usize {
+        let _section = self.grace.read();
+        self.current_table().buckets.len()
+    }
+
+    /// Calls `f` on the value for `key`, without taking any lock.
+    pub fn with<Q: ?Sized + Hash + Eq, R>(&self, key: &Q, f: impl FnOnce(&V) -> R) -> Option<R>
+    where
+        K: Borrow<Q>,
+    {
+        let hash = self.hash_builder.hash_one(key);
+        let _section = self.grace.read();
+        let bucket = self.current_table().bucket(hash);
+        let entry = Self::find(bucket, hash, key)?;
+        // SAFETY: the read section keeps the value alive even if it is replaced.
+        Some(f(unsafe { &*entry.value.load(Ordering::Acquire) }))
+    }
+
+    /// Returns a clone of the value for `key`.
+    pub fn get<Q: ?Sized + Hash + Eq>(&self, key: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        V: Clone,
+    {
+        self.with(key, V::clone)
+    }
+
+    /// Returns `true` if the map contains `key`.
+    pub fn contains_key<Q: ?Sized + Hash + Eq>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+    {
+        self.with(key, |_| ()).is_some()
+    }
+
+    /// Inserts a key-value pair, returning `true` if `key` was not present.
+    ///
+    /// If it was, the value is replaced and the key kept, as in `HashMap::insert`; the
+    /// old value is dropped once no reader can see it.
+    pub fn insert(&self, key: K, value: V) -> bool {
+        let hash = self.hash_builder.hash_one(&key);
+        // Stays a `Box` until published, so an unwind out of `K::eq` drops it.
+        let value = Box::new(value);
+        let section = self.grace.read();
+        let (table, bucket) = self.lock_bucket(hash);
+        // Unlocks on unwind out of `K::eq`.
+        let locked = BucketGuard(bucket);
+        let mut chain = 0;
+        let mut link = bucket.head.load(Ordering::Acquire);
+        // SAFETY: the bucket lock keeps the chain from changing and the read section
+        // keeps the table alive.
+        while let Some(l) = unsafe { link.as_ref() } {
+            let entry = unsafe { &*l.entry };
+            if entry.hash == hash && entry.key == key {
+                let old = entry.value.swap(Box::into_raw(value), Ordering::AcqRel);
+                drop(locked);
+                drop(section);
+                self.retire([Retired::Value(old)]);
+                return false;
+            }
+            chain += 1;
+            link = l.next.load(Ordering::Acquire);
+        }
+        let entry = Box::into_raw(Box::new(Entry {
+            hash,
+            key,
+            value: AtomicPtr::new(Box::into_raw(value)),
+        }));
+        let link = Box::into_raw(Box::new(Link {
+            entry,
+            next: AtomicPtr::new(bucket.head.load(Ordering::Relaxed)),
+        }));
+        bucket.head.store(link, Ordering::Release);
+        drop(locked);
+        self.len.increment();
+        if chain >= LONG_CHAIN && self.len() > table.buckets.len() / 4 * 3 {
+            self.grow(table);
+        }
+        drop(section);
+        self.retired.try_reclaim(&self.grace);
+        true
+    }
+
+    /// Removes `key`, returning `true` if it was present.
+    ///
+    /// The entry is dropped once no reader can see it.
+    pub fn remove<Q: ?Sized + Hash + Eq>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+    {
+        self.update_locked(key, |_| None)
+    }
+
+    /// Replaces the value for `key` with `f(&value)`, or removes the entry if `f`
+    /// returns `None`. Returns `true` if `key` was present, i.e. if `f` ran.
+    ///
+    /// `f` runs with the key's bucket locked, so updates of one key are atomic with
+    /// respect to each other; lookups keep seeing the old value until it returns.
+    pub fn compute_if_present<Q: ?Sized + Hash + Eq>(
+        &self,
+        key: &Q,
+        f: impl FnOnce(&V) -> Option<V>,
+    ) -> bool
+    where
+        K: Borrow<Q>,
+    {
+        self.update_locked(key, f)
+    }
+
+    fn update_locked<Q: ?Sized + Hash + Eq>(&self, key: &Q, f: impl FnOnce(&V) -> Option<V>) -> bool
+    where
+        K: Borrow<Q>,
+    {
+        let hash = self.hash_builder.hash_one(key);
+        let section = self.grace.read();
+        let (_, bucket) = self.lock_bucket(hash);
+        // Unlocks on unwind out of `f`.
+        let locked = BucketGuard(bucket);
+        let mut prev = &bucket.head;
+        let mut link = prev.load(Ordering::Acquire);
+        // SAFETY: as in `insert`.
+        while let Some(l) = unsafe { link.as_ref() } {
+            let entry = unsafe { &*l.entry };
+            if entry.hash == hash && entry.key.borrow() == key {
+                let current = unsafe { &*entry.value.load(Ordering::Acquire) };
+                let retired = match f(current) {
+                    Some(value) => {
+                        let new = Box::into_raw(Box::new(value));
+                        [
+                            Some(Retired::Value(entry.value.swap(new, Ordering::AcqRel))),
+                            None,
+                        ]
+                    }
+                    None => {
+                        prev.store(l.next.load(Ordering::Acquire), Ordering::Release);
+                        self.len.sub(1);
+                        [Some(Retired::Entry(l.entry)), Some(Retired::Link(link))]
+                    }
+                };
+                drop(locked);
+                drop(section);
+                self.retire(retired.into_iter().flatten());
+                return true;
+            }
+            prev = &l.next;
+            link = l.next.load(Ordering::Acquire);
+        }
+        false
+    }
+
+    /// Removes every entry.
+    ///
+    /// Every entry present when the call starts is removed. Buckets are emptied one at
+    /// a time, so entries inserted concurrently may survive.
+    pub fn clear(&self) {
+        // Hold off resizes, so that no entry moves to a table this walk does not see.
+        let mut spins = 0;
+        while self.resizing.swap(true, Ordering::Acquire) {
+            backoff(&mut spins);
+        }
+        let section = self.grace.read();
+        let mut retired = Vec::new();
+        for bucket in &*self.current_table().buckets {
+            // A bucket of the current table is never `MOVED`.
+            let locked = bucket.lock();
+            debug_assert!(locked);
+            let mut link = bucket.head.swap(ptr::null_mut(), Ordering::AcqRel);
+            bucket.unlock();
+            // SAFETY: the chain is unlinked but alive until retired and reclaimed.
+            while let Some(l) = unsafe { link.as_ref() } {
+                retired.push(Retired::Entry(l.entry));
+                retired.push(Retired::Link(link));
+                self.len.sub(1);
+                link = l.next.load(Ordering::Acquire);
+            }
+        }
+        self.resizing.store(false, Ordering::Release);
+        drop(section);
+        self.retire(retired);
+    }
+
+    /// Calls `f` on every entry, without taking any lock.
+    ///
+    /// Entries inserted or removed concurrently may or may not be visited.
+    pub fn for_each(&self, mut f: impl FnMut(&K, &V)) {
+        let _section = self.grace.read();
+        for bucket in &*self.current_table().buckets {
+            let mut link = bucket.head.load(Ordering::Acquire);
+            // SAFETY: the read section keeps everything reachable alive.
+            while let Some(l) = unsafe { link.as_ref() } {
+                let entry = unsafe { &*l.entry };
+                f(&entry.key, unsafe { &*entry.value.load(Ordering::Acquire) });
+                link = l.next.load(Ordering::Acquire);
+            }
+        }
+    }
+
+    /// Returns the current table. The caller must be in a read section.
+    fn current_table(&self) -> &Table<K, V> {
+        // SAFETY: tables are only freed after a grace period covering the caller.
+        unsafe { &*self.table.load(Ordering::Acquire) }
+    }
+
+    fn find<'a, Q: ?Sized + Eq>(
+        bucket: &'a Bucket<K, V>,
+        hash: u64,
+        key: &Q,
+    ) -> Option<&'a Entry<K, V>>
+    where
+        K: Borrow<Q>,
+    {
+        let mut link = bucket.head.load(Ordering::Acquire);
+        // SAFETY: the caller's read section keeps everything reachable alive.
+        while let Some(l) = unsafe { link.as_ref() } {
+            let entry = unsafe { &*l.entry };
+            if entry.hash == hash && entry.key.borrow() == key {
+                return Some(entry);
+            }
+            link = l.next.load(Ordering::Acquire);
+        }
+        None
+    }
+
+    /// Locks the bucket for `hash` in the current table. The caller must be in a read
+    /// section.
+    fn lock_bucket(&self, hash: u64) -> (&Table<K, V>, &Bucket<K, V>) {
+        loop {
+            let table = self.current_table();
+            let bucket = table.bucket(hash);
+            if bucket.lock() {
+                return (table, bucket);
+            }
+        }
+    }
+
+    /// Doubles the table if it is still `old`. The caller must be in a read section.
+    fn grow(&self, old: &Table<K, V>) {
+        if self.resizing.swap(true, Ordering::Acquire) {
+            return;
+        }
+        if ptr::eq(self.table.load(Ordering::Acquire), old) {
+            for bucket in &*old.buckets {
+                // A bucket of the current table is never `MOVED`.
+                let locked = bucket.lock();
+                debug_assert!(locked);
+            }
+            let new = Table::alloc(old.buckets.len() * 2);
+            // SAFETY: `new` is not shared yet.
+            let table = unsafe { &*new };
+            let mut retired = Vec::with_capacity(self.len() + 1);
+            for bucket in &*old.buckets {
+                let mut link = bucket.head.load(Ordering::Acquire);
+                // SAFETY: every bucket is locked, so the chains are stable.
+                while let Some(l) = unsafe { link.as_ref() } {
+                    let hash = unsafe { (*l.entry).hash };
+                    let target = table.bucket(hash);
+                    let copy = Box::into_raw(Box::new(Link {
+                        entry: l.entry,
+                        next: AtomicPtr::new(target.head.load(Ordering::Relaxed)),
+                    }));
+                    target.head.store(copy, Ordering::Relaxed);
+                    retired.push(Retired::Link(link));
+                    link = l.next.load(Ordering::Acquire);
+                }
+            }
+            self.table.store(new, Ordering::Release);
+            for bucket in &*old.buckets {
+                bucket.lock.store(MOVED, Ordering::Release);
+            }
+            retired.push(Retired::Table(ptr::from_ref(old).cast_mut()));
+            self.retire(retired);
+        }
+        self.resizing.store(false, Ordering::Release);
+    }
+
+    /// Queues unlinked allocations for reclamation.
+    fn retire(&self, items: impl IntoIterator<Item = Retired<K, V>>) {
+        self.retired.retire(&self.grace, items);
+    }
+}
+
+impl<K, V> Table<K, V> {
+    fn alloc(buckets: usize) -> *mut Self {
+        let buckets = (0..buckets)
+            .map(|_| Bucket {
+                lock: AtomicU8::new(UNLOCKED),
+                head: AtomicPtr::new(ptr::null_mut()),
+            })
+            .collect();
+        Box::into_raw(Box::new(Table { buckets }))
+    }
+
+    // Only the low bits of the hash pick the bucket, so truncating it is intended.
+    #[allow(clippy::cast_possible_truncation)]
+    #[inline]
+    fn bucket(&self, hash: u64) -> &Bucket<K, V> {
+        // The length is a power of two.
+        &self.buckets[hash as usize & (self.buckets.len() - 1)]
+    }
+}
+
+impl<K, V> Bucket<K, V> {
+    /// Locks the bucket, or returns `false` if its table has been replaced.
+    fn lock(&self) -> bool {
+        let mut spins = 0;
+        loop {
+            match self.lock.compare_exchange_weak(
+                UNLOCKED,
+                LOCKED,
+                Ordering::Acquire,
+                Ordering::Relaxed,
+            ) {
+                Ok(_) => return true,
+                Err(MOVED) => return false,
+                Err(_) => backoff(&mut spins),
+            }
+        }
+    }
+
+    fn unlock(&self) {
+        self.lock.store(UNLOCKED, Ordering::Release);
+    }
+}
+
+fn backoff(spins: &mut u32) {
+    if *spins < SPINS_BEFORE_YIELD {
+        *spins += 1;
+        core::hint::spin_loop();
+    } else {
+        std::thread::yield_now();
+    }
+}
+
+/// Unlocks a bucket when dropped.
+struct BucketGuard<'a, K, V>(&'a Bucket<K, V>);
+
+impl<K, V> Drop for BucketGuard<'_, K, V> {
+    fn drop(&mut self) {
+        self.0.unlock();
+    }
+}
+
+impl<K, V> Reclaim for Retired<K, V> {
+    unsafe fn reclaim(self) {
+        // SAFETY: every variant holds a pointer from `Box::into_raw` that is now
+        // exclusively ours.
+        unsafe {
+            match self {
+                Retired::Value(value) => drop(Box::from_raw(value)),
+                Retired::Entry(entry) => {
+                    let entry = Box::from_raw(entry);
+                    drop(Box::from_raw(entry.value.load(Ordering::Relaxed)));
+                }
+                Retired::Link(link) => drop(Box::from_raw(link)),
+                Retired::Table(table) => drop(Box::from_raw(table)),
+            }
+        }
+    }
+}
+
+impl<'brand, K, V, S> Drop for GhostConcurrentHashMap<'brand, K, V, S> {
+    fn drop(&mut self) {
+        // Retired allocations are freed when `retired` is dropped after this.
+        // SAFETY: `&mut self` rules out readers, so the current table is exclusively
+        // ours.
+        unsafe {
+            let table = Box::from_raw(*self.table.get_mut());
+            for bucket in &*table.buckets {
+                let mut link = bucket.head.load(Ordering::Relaxed);
+                while !link.is_null() {
+                    let next = (*link).next.load(Ordering::Relaxed);
+                    Retired::Entry((*link).entry).reclaim();
+                    Retired::Link(link).reclaim();
+                    link = next;
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::GhostToken;
+
+    #[test]
+    fn test_concurrent_map_basic() {
+        GhostToken::new(|_token| {
+            let map = GhostConcurrentHashMap::new();
+            assert!(map.is_empty());
+            assert!(map.insert("a".to_string(), 1));
+            assert!(!map.insert("a".to_string(), 2));
+            assert_eq!(map.get("a"), Some(2));
+            assert_eq!(map.with("a", |v| v * 10), Some(20));
+            assert!(map.compute_if_present("a", |v| Some(v + 1)));
+            assert_eq!(map.get("a"), Some(3));
+            assert!(!map.compute_if_present("b", |_| Some(0)));
+            assert!(map.insert("b".to_string(), 7));
+            assert!(map.compute_if_present("b", |_| None));
+            assert!(!map.contains_key("b"));
+            assert_eq!(map.len(), 1);
+
+            let buckets = map.bucket_count();
+            for i in 0..1000 {
+                map.insert(i.to_string(), i);
+            }
+            assert!(map.bucket_count() > buckets);
+            assert_eq!(map.len(), 1001);
+            assert!((0..1000).all(|i| map.get(&i.to_string()) == Some(i)));
+
+            let mut sum = 0;
+            map.for_each(|_, v| sum += v);
+            assert_eq!(sum, 3 + 999 * 1000 / 2);
+
+            assert!(map.remove("a"));
+            assert!(!map.remove("a"));
+            map.clear();
+            assert!(map.is_empty());
+            assert_eq!(map.get("1"), None);
+        });
+    }
+
+    #[test]
+    fn test_concurrent_map_readers_and_writers() {
+        use core::sync::atomic::AtomicUsize;
+
+        // Values borrow a local counter, which the map must not outlive.
+        struct Counted<'a>(usize, &'a AtomicUsize);
+        impl Drop for Counted<'_> {
+            fn drop(&mut self) {
+                self.1.fetch_add(1, Ordering::Relaxed);
+            }
+        }
+
+        let drops = AtomicUsize::new(0);
+        let created = AtomicUsize::new(0);
+        GhostToken::new(|_token| {
+            let map = GhostConcurrentHashMap::new();
+            let done = AtomicBool::new(false);
+            std::thread::scope(|s| {
+                for _ in 0..2 {
+                    s.spawn(|| {
+                        while !done.load(Ordering::Relaxed) {
+                            for k in 0..64usize {
+                                // A present value always matches its key.
+                                map.with(&k, |v: &Counted<'_>| assert_eq!(v.0 % 64, k));
+                            }
+                        }
+                    });
+                }
+                let writers: Vec<_> = (0..4usize)
+                    .map(|t| {
+                        let (map, drops, created) = (&map, &drops, &created);
+                        s.spawn(move || {
+                            for i in 0..2000 {
+                                let k = (t * 2000 + i) % 64;
+                                map.insert(k, Counted(k + 64 * i, drops));
+                                created.fetch_add(1, Ordering::Relaxed);
+                                if i % 3 == 0 {
+                                    map.remove(&k);
+                                }
+                                map.compute_if_present(&k, |v| {
+                                    created.fetch_add(1, Ordering::Relaxed);
+                                    Some(Counted(v.0 + 64, v.1))
+                                });
+                            }
+                        })
+                    })
+                    .collect();
+                for w in writers {
+                    w.join().unwrap();
+                }
+                done.store(true, Ordering::Relaxed);
+            });
+            assert!(map.len() <= 64);
+        });
+        // Every value created was dropped exactly once, by the time the map was.
+        assert_eq!(
+            drops.load(Ordering::Relaxed),
+            created.load(Ordering::Relaxed)
+        );
+    }
+
+    #[test]
+    fn test_concurrent_map_clear_during_resizes_removes_earlier_entries() {
+        GhostToken::new(|_token| {
+            let map = GhostConcurrentHashMap::new();
+            for round in 0..20usize {
+                let base = round * 10_000;
+                for k in base..base + 500 {
+                    map.insert(k, k);
+                }
+                std::thread::scope(|s| {
+                    // Keys above the earlier ones, inserted fast enough to grow the
+                    // table while `clear` walks it.
+                    s.spawn(|| {
+                        for k in base + 500..base + 5000 {
+                            map.insert(k, k);
+                        }
+                    });
+                    map.clear();
+                });
+                assert!((base..base + 500).all(|k| !map.contains_key(&k)));
+                map.clear();
+                assert!(map.is_empty());
+            }
+        });
+    }
+
+    #[test]
+    fn test_concurrent_map_insert_drops_the_value_when_eq_panics() {
+        use core::sync::atomic::AtomicUsize;
+        use std::panic::{catch_unwind, AssertUnwindSafe};
+
+        struct Key(u32);
+        impl Hash for Key {
+            fn hash<H: core::hash::Hasher>(&self, state: &mut H) {
+                self.0.hash(state);
+            }
+        }
+        impl PartialEq for Key {
+            fn eq(&self, other: &Self) -> bool {
+                assert!(self.0 != 1 && other.0 != 1, "comparing the cursed key");
+                self.0 == other.0
+            }
+        }
+        impl Eq for Key {}
+
+        struct Counted<'a>(&'a AtomicUsize);
+        impl Drop for Counted<'_> {
+            fn drop(&mut self) {
+                self.0.fetch_add(1, Ordering::Relaxed);
+            }
+        }
+
+        let drops = AtomicUsize::new(0);
+        // A single bucket, so the second insert compares against the first key.
+        let map = GhostConcurrentHashMap::with_hasher(
+            core::hash::BuildHasherDefault::<ConstantHasher>::default(),
+        );
+        map.insert(Key(0), Counted(&drops));
+        let result = catch_unwind(AssertUnwindSafe(|| map.insert(Key(1), Counted(&drops))));
+        assert!(result.is_err());
+        assert_eq!(drops.load(Ordering::Relaxed), 1);
+        // The bucket was unlocked on unwind.
+        assert!(map.insert(Key(2), Counted(&drops)));
+        drop(map);
+        assert_eq!(drops.load(Ordering::Relaxed), 3);
+    }
+
+    #[derive(Default)]
+    struct ConstantHasher;
+
+    impl core::hash::Hasher for ConstantHasher {
+        fn finish(&self) -> u64 {
+            0
+        }
+
+        fn write(&mut self, _: &[u8]) {}
+    }
+}
@@ -0,0 +1,186 @@
+//! `DeterministicState` — a seeded `BuildHasher` for reproducible iteration order.
+//!
+//! The branded hash collections iterate in slot order, which depends only on the hashes
+//! of the keys and on the sequence of operations performed. With the randomly seeded
+//! default hasher that order changes from run to run; with a `DeterministicState` of a
+//! given seed, the same inserts and removals always produce the same order.
+//!
+//! Like `FxHasher`, this is not a keyed hash: do not use it for keys an attacker can
+//! choose.
+
+use super::{BrandedHashMap, BrandedHashSet};
+use core::hash::{BuildHasher, Hash, Hasher};
+
+/// Seed used by [`DeterministicState::default`].
+const DEFAULT_SEED: u64 = 0x243f_6a88_85a3_08d3;
+/// Odd multiplier folding each input word into the state.
+const MULTIPLIER: u64 = 0x9e37_79b9_7f4a_7c15;
+
+/// A `BuildHasher` whose hashers start from a fixed seed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct DeterministicState {
+    seed: u64,
+}
+
+impl DeterministicState {
+    /// Creates a builder whose hashers start from `seed`.
+    pub const fn with_seed(seed: u64) -> Self {
+        Self { seed }
+    }
+
+    /// Returns the seed.
+    pub const fn seed(&self) -> u64 {
+        self.seed
+    }
+}
+
+impl Default for DeterministicState {
+    fn default() -> Self {
+        Self::with_seed(DEFAULT_SEED)
+    }
+}
+
+impl BuildHasher for DeterministicState {
+    type Hasher = DeterministicHasher;
+
+    #[inline]
+    fn build_hasher(&self) -> DeterministicHasher {
+        DeterministicHasher { state: self.seed }
+    }
+}
+
+/// The hasher built by [`DeterministicState`].
+///
+/// Words are folded in with a rotate, xor and multiply, and the result goes through
+/// the MurmurHash3 finalizer so both the high bits (used as the control byte) and the
+/// low bits (used as the slot index) depend on every input bit.
+#[derive(Debug, Clone)]
+pub struct DeterministicHasher {
+    state: u64,
+}
+
+impl DeterministicHasher {
+    #[inline]
+    fn add_to_hash(&mut self, word: u64) {
+        self.state = (self.state.rotate_left(5) ^ word).wrapping_mul(MULTIPLIER);
+    }
+}
+
+impl Hasher for DeterministicHasher {
+    #[inline]
+    fn write(&mut self, mut bytes: &[u8]) {
+        while let Some((chunk, rest)) = bytes.split_first_chunk::<8>() {
+            self.add_to_hash(u64::from_le_bytes(*chunk));
+            bytes = rest;
+        }
+        if !bytes.is_empty() {
+            let mut tail = [0u8; 8];
+            tail[..bytes.len()].copy_from_slice(bytes);
+            // Mix in the length so trailing zero bytes still change the hash.
+            self.add_to_hash(u64::from_le_bytes(tail) ^ ((bytes.len() as u64) << 59));
+        }
+    }
+
+    #[inline]
+    fn write_u8(&mut self, i: u8) {
+        self.add_to_hash(u64::from(i));
+    }
+
+    #[inline]
+    fn write_u16(&mut self, i: u16) {
+        self.add_to_hash(u64::from(i));
+    }
+
+    #[inline]
+    fn write_u32(&mut self, i: u32) {
+        self.add_to_hash(u64::from(i));
+    }
+
+    #[inline]
+    fn write_u64(&mut self, i: u64) {
+        self.add_to_hash(i);
+    }
+
+    #[inline]
+    fn write_usize(&mut self, i: usize) {
+        self.add_to_hash(i as u64);
+    }
+
+    #[inline]
+    fn finish(&self) -> u64 {
+        let mut h = self.state;
+        h ^= h >> 33;
+        h = h.wrapping_mul(0xff51_afd7_ed55_8ccd);
+        h ^= h >> 33;
+        h = h.wrapping_mul(0xc4ce_b9fe_1a85_ec53);
+        h ^ (h >> 33)
+    }
+}
+
+impl<'brand, K, V> BrandedHashMap<'brand, K, V, DeterministicState>
+where
+    K: Eq + Hash,
+{
+    /// Creates an empty map whose iteration order is reproducible for a given `seed`.
+    pub fn with_seed(seed: u64) -> Self {
+        Self::with_hasher(DeterministicState::with_seed(seed))
+    }
+}
+
+impl<'brand, K> BrandedHashSet<'brand, K, DeterministicState>
+where
+    K: Eq + Hash,
+{
+    /// Creates an empty set whose iteration order is reproducible for a given `seed`.
+    pub fn with_seed(seed: u64) -> Self {
+        Self::with_hasher(DeterministicState::with_seed(seed))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::GhostToken;
+
+    #[test]
+    fn deterministic_state_is_stable_and_seeded() {
+        let a = DeterministicState::with_seed(7);
+        assert_eq!(
+            a.hash_one("key"),
+            DeterministicState::with_seed(7).hash_one("key")
+        );
+        assert_ne!(
+            a.hash_one("key"),
+            DeterministicState::with_seed(8).hash_one("key")
+        );
+        assert_ne!(a.hash_one(&[1u8, 0][..]), a.hash_one(&[1u8][..]));
+        assert_eq!(DeterministicState::default().seed(), DEFAULT_SEED);
+    }
+
+    #[test]
+    fn deterministic_maps_iterate_identically() {
+        GhostToken::new(|token| {
+            let build = || {
+                let mut map = BrandedHashMap::with_seed(42);
+                for i in 0..500u32 {
+                    map.insert(i.to_string(), i);
+                }
+                for i in (0..500u32).step_by(3) {
+                    map.remove(&i.to_string());
+                }
+                map
+            };
+            let (a, b) = (build(), build());
+            assert!(a.keys().eq(b.keys()));
+            assert!(a.values(&token).eq(b.values(&token)));
+
+            let mut set = BrandedHashSet::with_seed(42);
+            let mut other = BrandedHashSet::with_seed(42);
+            for i in 0..100u64 {
+                set.insert(i);
+                other.insert(i);
+            }
+            assert!(set.iter().eq(other.iter()));
+        });
+    }
+}
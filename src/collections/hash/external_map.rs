@@ -7,8 +7,10 @@
 //! Lookups and Insertions require a `context` closure to resolve the `usize` index to the actual `&K`.
 
 use core::hash::{BuildHasher, Hash, Hasher};
-use std::collections::hash_map::RandomState;
-use std::borrow::Borrow;
+use super::DefaultHashBuilder;
+use core::borrow::Borrow;
+#[cfg(not(feature = "std"))]
+use alloc_crate::{boxed::Box, vec};
 
 // Control byte constants
 const EMPTY: u8 = 0xFF;
@@ -28,7 +30,7 @@ fn match_byte(x: u64, y: u8) -> u64 {
     has_zero_byte(x ^ pattern)
 }
 
-pub struct BrandedExternalHashMap<S = RandomState> {
+pub struct BrandedExternalHashMap<S = DefaultHashBuilder> {
     ctrl: Box<[u8]>,
     slots: Box<[usize]>, // Stores the external index
     items_count: usize,
@@ -36,13 +38,13 @@ pub struct BrandedExternalHashMap<S = RandomState> {
     hash_builder: S,
 }
 
-impl BrandedExternalHashMap<RandomState> {
+impl BrandedExternalHashMap<DefaultHashBuilder> {
     pub fn new() -> Self {
-        Self::with_capacity_and_hasher(0, RandomState::new())
+        Self::with_capacity_and_hasher(0, DefaultHashBuilder::default())
     }
 
     pub fn with_capacity(capacity: usize) -> Self {
-        Self::with_capacity_and_hasher(capacity, RandomState::new())
+        Self::with_capacity_and_hasher(capacity, DefaultHashBuilder::default())
     }
 }
 
@@ -115,7 +117,7 @@ where
         loop {
             let group_word = unsafe {
                 let ptr = self.ctrl.as_ptr().add(idx);
-                std::ptr::read_unaligned(ptr as *const u64)
+                core::ptr::read_unaligned(ptr.cast::<u64>())
             };
 
             let match_mask = match_byte(group_word, h2);
@@ -258,8 +260,8 @@ where
         R: Borrow<K>,
         Ctx: Fn(usize) -> Option<R>,
     {
-        let old_ctrl = std::mem::take(&mut self.ctrl);
-        let old_slots = std::mem::take(&mut self.slots);
+        let old_ctrl = core::mem::take(&mut self.ctrl);
+        let old_slots = core::mem::take(&mut self.slots);
         let old_cap = self.capacity;
 
         self.capacity = new_cap;
@@ -294,7 +296,7 @@ where
         loop {
             let group_word = unsafe {
                 let ptr = self.ctrl.as_ptr().add(idx);
-                std::ptr::read_unaligned(ptr as *const u64)
+                core::ptr::read_unaligned(ptr.cast::<u64>())
             };
 
             let empty_mask = match_byte(group_word, EMPTY);
@@ -0,0 +1,508 @@
+//! `BrandedHamtMap` — a persistent hash array mapped trie.
+//!
+//! Every version of the map is immutable: [`insert`](BrandedHamtMap::insert) and
+//! [`remove`](BrandedHamtMap::remove) return a new map and leave `self` untouched. Only
+//! the nodes on the path to the changed key are copied; everything else is shared
+//! between versions through [`BrandedRc`], so keeping old versions around for snapshots
+//! or rollback costs \(O(\log_{32} n)\) nodes per update.
+//!
+//! Keys are placed by their 64-bit hash, five bits per level. Branch nodes store only
+//! their present children, indexed by a 32-bit occupancy bitmap, and keys whose full
+//! hashes collide share one leaf.
+//!
+//! Because versions never change, lookups need no token: the brand only ties the
+//! shared nodes to the scope that created them.
+
+use super::DefaultHashBuilder;
+use crate::alloc::BrandedRc;
+#[cfg(not(feature = "std"))]
+use alloc_crate::{vec, vec::Vec};
+use core::borrow::Borrow;
+use core::hash::{BuildHasher, Hash};
+
+/// Hash bits consumed per level.
+const BITS: u32 = 5;
+const MASK: u64 = (1 << BITS) - 1;
+
+enum Node<'brand, K, V> {
+    /// Children in bitmap order; bit `i` of `bitmap` is set if slot `i` is present.
+    Branch {
+        bitmap: u32,
+        children: Vec<BrandedRc<'brand, Node<'brand, K, V>>>,
+    },
+    /// Every entry whose key hashes to `hash`.
+    Leaf { hash: u64, entries: Vec<(K, V)> },
+}
+
+/// Position of the child for `hash` at `shift`: its bitmap bit and its index among the
+/// present children.
+#[inline]
+fn slot(bitmap: u32, hash: u64, shift: u32) -> (u32, usize) {
+    let bit = 1u32 << ((hash >> shift) & MASK);
+    (bit, (bitmap & (bit - 1)).count_ones() as usize)
+}
+
+/// Builds the smallest subtree holding two leaves with different hashes.
+fn merge<'brand, K, V>(
+    a: BrandedRc<'brand, Node<'brand, K, V>>,
+    a_hash: u64,
+    b: BrandedRc<'brand, Node<'brand, K, V>>,
+    b_hash: u64,
+    shift: u32,
+) -> BrandedRc<'brand, Node<'brand, K, V>> {
+    let a_idx = (a_hash >> shift) & MASK;
+    let b_idx = (b_hash >> shift) & MASK;
+    let node = if a_idx == b_idx {
+        Node::Branch {
+            bitmap: 1 << a_idx,
+            children: vec![merge(a, a_hash, b, b_hash, shift + BITS)],
+        }
+    } else {
+        let children = if a_idx < b_idx {
+            vec![a, b]
+        } else {
+            vec![b, a]
+        };
+        Node::Branch {
+            bitmap: (1 << a_idx) | (1 << b_idx),
+            children,
+        }
+    };
+    BrandedRc::new(node)
+}
+
+/// Returns the node with `key` inserted and whether the key is new.
+fn insert_node<'brand, K, V>(
+    node: &BrandedRc<'brand, Node<'brand, K, V>>,
+    shift: u32,
+    hash: u64,
+    key: K,
+    value: V,
+) -> (BrandedRc<'brand, Node<'brand, K, V>>, bool)
+where
+    K: Eq + Clone,
+    V: Clone,
+{
+    match &**node {
+        Node::Leaf {
+            hash: leaf_hash,
+            entries,
+        } if *leaf_hash == hash => {
+            let mut entries = entries.clone();
+            let added = match entries.iter_mut().find(|(k, _)| *k == key) {
+                Some(entry) => {
+                    entry.1 = value;
+                    false
+                }
+                None => {
+                    entries.push((key, value));
+                    true
+                }
+            };
+            (BrandedRc::new(Node::Leaf { hash, entries }), added)
+        }
+        Node::Leaf {
+            hash: leaf_hash, ..
+        } => {
+            let leaf = BrandedRc::new(Node::Leaf {
+                hash,
+                entries: vec![(key, value)],
+            });
+            (merge(node.clone(), *leaf_hash, leaf, hash, shift), true)
+        }
+        Node::Branch { bitmap, children } => {
+            let (bit, pos) = slot(*bitmap, hash, shift);
+            let mut children = children.clone();
+            let added = if bitmap & bit == 0 {
+                let leaf = Node::Leaf {
+                    hash,
+                    entries: vec![(key, value)],
+                };
+                children.insert(pos, BrandedRc::new(leaf));
+                true
+            } else {
+                let (child, added) = insert_node(&children[pos], shift + BITS, hash, key, value);
+                children[pos] = child;
+                added
+            };
+            let node = Node::Branch {
+                bitmap: bitmap | bit,
+                children,
+            };
+            (BrandedRc::new(node), added)
+        }
+    }
+}
+
+/// Returns `None` if `key` is absent, otherwise the node without it (`None` once empty).
+#[allow(clippy::option_option)]
+fn remove_node<'brand, K, V, Q>(
+    node: &BrandedRc<'brand, Node<'brand, K, V>>,
+    shift: u32,
+    hash: u64,
+    key: &Q,
+) -> Option<Option<BrandedRc<'brand, Node<'brand, K, V>>>>
+where
+    K: Borrow<Q> + Clone,
+    V: Clone,
+    Q: ?Sized + Eq,
+{
+    match &**node {
+        Node::Leaf {
+            hash: leaf_hash,
+            entries,
+        } => {
+            if *leaf_hash != hash {
+                return None;
+            }
+            let pos = entries.iter().position(|(k, _)| k.borrow() == key)?;
+            if entries.len() == 1 {
+                return Some(None);
+            }
+            let mut entries = entries.clone();
+            entries.remove(pos);
+            Some(Some(BrandedRc::new(Node::Leaf { hash, entries })))
+        }
+        Node::Branch { bitmap, children } => {
+            let (bit, pos) = slot(*bitmap, hash, shift);
+            if bitmap & bit == 0 {
+                return None;
+            }
+            let child = remove_node(&children[pos], shift + BITS, hash, key)?;
+            let mut children = children.clone();
+            let bitmap = match child {
+                Some(child) => {
+                    children[pos] = child;
+                    *bitmap
+                }
+                None => {
+                    children.remove(pos);
+                    bitmap & !bit
+                }
+            };
+            // A lone leaf holds its full hash, so it can replace its parent.
+            match children.as_slice() {
+                [] => Some(None),
+                [only] if matches!(**only, Node::Leaf { .. }) => Some(Some(only.clone())),
+                _ => Some(Some(BrandedRc::new(Node::Branch { bitmap, children }))),
+            }
+        }
+    }
+}
+
+/// A persistent hash map whose versions share structure.
+pub struct BrandedHamtMap<'brand, K, V, S = DefaultHashBuilder> {
+    root: Option<BrandedRc<'brand, Node<'brand, K, V>>>,
+    len: usize,
+    hash_builder: S,
+}
+
+impl<'brand, K, V> BrandedHamtMap<'brand, K, V, DefaultHashBuilder> {
+    /// Creates an empty map.
+    pub fn new() -> Self {
+        Self::with_hasher(DefaultHashBuilder::default())
+    }
+}
+
+impl<'brand, K, V, S> BrandedHamtMap<'brand, K, V, S> {
+    /// Creates an empty map hashing keys with `hash_builder`.
+    pub fn with_hasher(hash_builder: S) -> Self {
+        Self {
+            root: None,
+            len: 0,
+            hash_builder,
+        }
+    }
+
+    /// Returns the number of entries.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if the map holds no entries.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns `true` if both maps are the same version, i.e. share their root node.
+    ///
+    /// Runs in O(1); `false` does not imply the contents differ.
+    pub fn ptr_eq(&self, other: &Self) -> bool {
+        match (&self.root, &other.root) {
+            (Some(a), Some(b)) => {
+                core::ptr::eq(core::ptr::from_ref(&**a), core::ptr::from_ref(&**b))
+            }
+            (None, None) => true,
+            _ => false,
+        }
+    }
+
+    /// Iterates over every entry, in hash order.
+    pub fn iter(&self) -> Iter<'_, 'brand, K, V> {
+        Iter {
+            stack: self.root.iter().map(|node| &**node).collect(),
+            entries: [].iter(),
+            remaining: self.len,
+        }
+    }
+}
+
+impl<'brand, K, V, S> BrandedHamtMap<'brand, K, V, S>
+where
+    K: Eq + Hash,
+    S: BuildHasher,
+{
+    /// Returns the value for `key`.
+    pub fn get<Q>(&self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq,
+    {
+        let hash = self.hash_builder.hash_one(key);
+        let mut node = self.root.as_deref()?;
+        let mut shift = 0;
+        loop {
+            match node {
+                Node::Leaf {
+                    hash: leaf_hash,
+                    entries,
+                } => {
+                    if *leaf_hash != hash {
+                        return None;
+                    }
+                    return entries
+                        .iter()
+                        .find(|(k, _)| k.borrow() == key)
+                        .map(|(_, v)| v);
+                }
+                Node::Branch { bitmap, children } => {
+                    let (bit, pos) = slot(*bitmap, hash, shift);
+                    if bitmap & bit == 0 {
+                        return None;
+                    }
+                    node = &children[pos];
+                    shift += BITS;
+                }
+            }
+        }
+    }
+
+    /// Returns `true` if the map contains `key`.
+    pub fn contains_key<Q>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq,
+    {
+        self.get(key).is_some()
+    }
+}
+
+impl<'brand, K, V, S> BrandedHamtMap<'brand, K, V, S>
+where
+    K: Eq + Hash + Clone,
+    V: Clone,
+    S: BuildHasher + Clone,
+{
+    /// Returns a new version with `key` mapped to `value`.
+    ///
+    /// **Time complexity**: \(O(\log_{32} n)\) node copies.
+    #[must_use]
+    pub fn insert(&self, key: K, value: V) -> Self {
+        let hash = self.hash_builder.hash_one(&key);
+        let (root, added) = match &self.root {
+            Some(root) => insert_node(root, 0, hash, key, value),
+            None => (
+                BrandedRc::new(Node::Leaf {
+                    hash,
+                    entries: vec![(key, value)],
+                }),
+                true,
+            ),
+        };
+        Self {
+            root: Some(root),
+            len: self.len + usize::from(added),
+            hash_builder: self.hash_builder.clone(),
+        }
+    }
+
+    /// Returns a new version without `key`; if `key` is absent this is a cheap copy of
+    /// `self` sharing its root.
+    #[must_use]
+    pub fn remove<Q>(&self, key: &Q) -> Self
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq,
+    {
+        let hash = self.hash_builder.hash_one(key);
+        match self
+            .root
+            .as_ref()
+            .and_then(|root| remove_node(root, 0, hash, key))
+        {
+            Some(root) => Self {
+                root,
+                len: self.len - 1,
+                hash_builder: self.hash_builder.clone(),
+            },
+            None => self.clone(),
+        }
+    }
+}
+
+impl<'brand, K, V, S: Clone> Clone for BrandedHamtMap<'brand, K, V, S> {
+    /// Copies the map in O(1); the copy shares every node.
+    fn clone(&self) -> Self {
+        Self {
+            root: self.root.clone(),
+            len: self.len,
+            hash_builder: self.hash_builder.clone(),
+        }
+    }
+}
+
+impl<'brand, K, V, S: Default> Default for BrandedHamtMap<'brand, K, V, S> {
+    fn default() -> Self {
+        Self::with_hasher(S::default())
+    }
+}
+
+impl<'brand, K, V, S> FromIterator<(K, V)> for BrandedHamtMap<'brand, K, V, S>
+where
+    K: Eq + Hash + Clone,
+    V: Clone,
+    S: BuildHasher + Clone + Default,
+{
+    fn from_iter<I: IntoIterator<Item = (K, V)>>(iter: I) -> Self {
+        iter.into_iter()
+            .fold(Self::default(), |map, (k, v)| map.insert(k, v))
+    }
+}
+
+impl<'brand, K, V, S> crate::collections::BrandedCollection<'brand>
+    for BrandedHamtMap<'brand, K, V, S>
+{
+    fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    fn len(&self) -> usize {
+        self.len
+    }
+}
+
+/// Iterator over the entries of a [`BrandedHamtMap`].
+pub struct Iter<'a, 'brand, K, V> {
+    stack: Vec<&'a Node<'brand, K, V>>,
+    entries: core::slice::Iter<'a, (K, V)>,
+    remaining: usize,
+}
+
+impl<'a, 'brand, K, V> Iterator for Iter<'a, 'brand, K, V> {
+    type Item = (&'a K, &'a V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some((k, v)) = self.entries.next() {
+                self.remaining -= 1;
+                return Some((k, v));
+            }
+            match self.stack.pop()? {
+                Node::Leaf { entries, .. } => self.entries = entries.iter(),
+                Node::Branch { children, .. } => {
+                    self.stack
+                        .extend(children.iter().rev().map(|child| &**child));
+                }
+            }
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl<K, V> ExactSizeIterator for Iter<'_, '_, K, V> {}
+
+impl<K, V> core::iter::FusedIterator for Iter<'_, '_, K, V> {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use core::hash::{BuildHasherDefault, Hasher};
+    use std::collections::HashMap;
+
+    /// Sends every key to the same hash to exercise collision leaves.
+    #[derive(Default)]
+    struct ConstantHasher;
+
+    impl Hasher for ConstantHasher {
+        fn finish(&self) -> u64 {
+            42
+        }
+        fn write(&mut self, _: &[u8]) {}
+    }
+
+    #[test]
+    fn test_hamt_versions_are_independent() {
+        let empty = BrandedHamtMap::new();
+        let v1 = empty.insert("a", 1).insert("b", 2);
+        let v2 = v1.insert("a", 10).insert("c", 3);
+        let v3 = v2.remove("b");
+
+        assert!(empty.is_empty());
+        assert_eq!((v1.get("a"), v1.get("c"), v1.len()), (Some(&1), None, 2));
+        assert_eq!(
+            (v2.get("a"), v2.get("b"), v2.len()),
+            (Some(&10), Some(&2), 3)
+        );
+        assert_eq!((v3.get("b"), v3.get("c"), v3.len()), (None, Some(&3), 2));
+
+        // Removing a missing key shares the root.
+        assert!(v3.remove("zzz").ptr_eq(&v3));
+        assert!(!v3.ptr_eq(&v2));
+    }
+
+    #[test]
+    fn test_hamt_matches_hash_map() {
+        let mut model = HashMap::new();
+        let mut map = BrandedHamtMap::new();
+        let mut snapshots = Vec::new();
+        for i in 0..2000u64 {
+            let key = i.wrapping_mul(0x9e37_79b9) % 700;
+            if i % 3 == 0 {
+                model.remove(&key);
+                map = map.remove(&key);
+            } else {
+                model.insert(key, i);
+                map = map.insert(key, i);
+            }
+            if i % 250 == 0 {
+                snapshots.push((map.clone(), model.clone()));
+            }
+        }
+        snapshots.push((map, model));
+        for (map, model) in snapshots {
+            assert_eq!(map.len(), model.len());
+            assert!(model.iter().all(|(k, v)| map.get(k) == Some(v)));
+            let mut entries: Vec<_> = map.iter().map(|(k, v)| (*k, *v)).collect();
+            entries.sort_unstable();
+            let mut expected: Vec<_> = model.into_iter().collect();
+            expected.sort_unstable();
+            assert_eq!(entries, expected);
+        }
+    }
+
+    #[test]
+    fn test_hamt_full_hash_collisions() {
+        let map: BrandedHamtMap<'_, u32, u32, BuildHasherDefault<ConstantHasher>> =
+            (0..10).map(|i| (i, i * i)).collect();
+        assert_eq!(map.len(), 10);
+        assert_eq!(map.get(&7), Some(&49));
+        let smaller = map.remove(&7).remove(&3);
+        assert_eq!(smaller.len(), 8);
+        assert_eq!(smaller.get(&7), None);
+        assert_eq!(map.get(&7), Some(&49));
+        assert_eq!(smaller.iter().count(), 8);
+    }
+}
@@ -14,19 +14,25 @@
 //! - **SWAR (SIMD Within A Register)**: Uses `u64` operations to check 8 slots in parallel.
 //! - **GhostToken Gating**: Values are wrapped in `GhostCell`, ensuring zero-cost safety.
 
+use crate::alloc::AllocError;
 use crate::GhostCell;
 use core::hash::{BuildHasher, Hash, Hasher};
 use core::mem::MaybeUninit;
-use std::alloc::{self, Layout};
+use alloc_crate::alloc::{self, Layout};
 use core::borrow::Borrow;
-use std::collections::hash_map::RandomState;
+use super::DefaultHashBuilder;
 // use std::marker::PhantomData;
-use std::ptr::NonNull;
+use core::ptr::NonNull;
+#[cfg(not(feature = "std"))]
+use alloc_crate::{boxed::Box, vec, vec::Vec};
 
 // Control byte constants
 const EMPTY: u8 = 0xFF;
 const DELETED: u8 = 0xFE;
 const GROUP_WIDTH: usize = 8;
+/// Default and largest allowed maximum load factor. Probing relies on every group
+/// eventually reaching an `EMPTY` byte, so the table may never fill completely.
+const MAX_LOAD_FACTOR: f32 = 0.875;
 
 /// Returns a mask where each byte is 0x80 if the corresponding byte in `x` is zero, else 0x00.
 #[inline(always)]
@@ -43,28 +49,80 @@ fn match_byte(x: u64, y: u8) -> u64 {
 
 /// Helper to allocate a slice of MaybeUninit without initializing it.
 fn alloc_slice<T>(len: usize) -> Box<[MaybeUninit<T>]> {
+    match try_alloc_slice(len) {
+        Ok(slice) => slice,
+        Err(AllocError) => alloc::handle_alloc_error(Layout::array::<MaybeUninit<T>>(len).unwrap()),
+    }
+}
+
+/// Fallible variant of [`alloc_slice`].
+fn try_alloc_slice<T>(len: usize) -> Result<Box<[MaybeUninit<T>]>, AllocError> {
     if len == 0 || core::mem::size_of::<T>() == 0 {
         // Handle ZSTs and empty allocations
         let ptr = NonNull::<MaybeUninit<T>>::dangling().as_ptr();
         unsafe {
-            let slice = std::slice::from_raw_parts_mut(ptr, len);
-            Box::from_raw(slice)
+            let slice = core::slice::from_raw_parts_mut(ptr, len);
+            Ok(Box::from_raw(slice))
         }
     } else {
-        let layout = Layout::array::<MaybeUninit<T>>(len).unwrap();
+        let layout = Layout::array::<MaybeUninit<T>>(len).map_err(|_| AllocError)?;
         unsafe {
             let ptr = alloc::alloc(layout) as *mut MaybeUninit<T>;
             if ptr.is_null() {
-                alloc::handle_alloc_error(layout);
+                return Err(AllocError);
             }
-            let slice = std::slice::from_raw_parts_mut(ptr, len);
-            Box::from_raw(slice)
+            let slice = core::slice::from_raw_parts_mut(ptr, len);
+            Ok(Box::from_raw(slice))
         }
     }
 }
 
+/// Allocates `len` control bytes set to `EMPTY`.
+fn try_alloc_ctrl(len: usize) -> Result<Box<[u8]>, AllocError> {
+    let mut ctrl = Vec::new();
+    ctrl.try_reserve_exact(len).map_err(|_| AllocError)?;
+    ctrl.resize(len, EMPTY);
+    Ok(ctrl.into_boxed_slice())
+}
+
+/// Number of used slots a table of `capacity` slots may hold at `load_factor`.
+// The product is non-negative and at most `capacity`, so the cast back only drops
+// the fraction; losing low bits of a huge `capacity` just shifts the limit slightly.
+#[allow(
+    clippy::cast_precision_loss,
+    clippy::cast_possible_truncation,
+    clippy::cast_sign_loss
+)]
+#[inline]
+fn max_items(capacity: usize, load_factor: f32) -> usize {
+    (capacity as f64 * f64::from(load_factor)) as usize
+}
+
+/// Smallest table size that holds `items` entries at `load_factor`, or `None` on
+/// overflow.
+// The estimate is non-negative and checked against `usize::MAX` before the cast, and
+// the doubling loop corrects any precision lost along the way.
+#[allow(
+    clippy::cast_precision_loss,
+    clippy::cast_possible_truncation,
+    clippy::cast_sign_loss
+)]
+fn buckets_for(items: usize, load_factor: f32) -> Option<usize> {
+    // No `ceil` without `std`; the loop below makes up for the truncation.
+    let estimate = items as f64 / f64::from(load_factor);
+    if estimate >= usize::MAX as f64 {
+        return None;
+    }
+    let mut capacity = (estimate as usize).checked_next_power_of_two()?.max(8);
+    // Correct for truncation and rounding in the float estimate.
+    while max_items(capacity, load_factor) < items {
+        capacity = capacity.checked_mul(2)?;
+    }
+    Some(capacity)
+}
+
 /// High-performance hash map with SwissTable-like layout.
-pub struct BrandedHashMap<'brand, K, V, S = RandomState> {
+pub struct BrandedHashMap<'brand, K, V, S = DefaultHashBuilder> {
     /// Control bytes: 0xFF=Empty, 0xFE=Deleted, 0..127=H2
     /// Size is capacity + GROUP_WIDTH for mirror bytes optimization.
     ctrl: Box<[u8]>,
@@ -80,22 +138,24 @@ pub struct BrandedHashMap<'brand, K, V, S = RandomState> {
     capacity: usize,
     /// Hash builder
     hash_builder: S,
+    /// Fraction of slots (live or tombstoned) that may be used before growing.
+    max_load_factor: f32,
 }
 
-impl<'brand, K, V> BrandedHashMap<'brand, K, V, RandomState>
+impl<'brand, K, V> BrandedHashMap<'brand, K, V, DefaultHashBuilder>
 where
     K: Eq + Hash,
 {
     /// Creates an empty map with default capacity.
     #[inline]
     pub fn new() -> Self {
-        Self::with_capacity_and_hasher(0, RandomState::new())
+        Self::with_capacity_and_hasher(0, DefaultHashBuilder::default())
     }
 
     /// Creates an empty map with at least the specified capacity.
     #[inline]
     pub fn with_capacity(capacity: usize) -> Self {
-        Self::with_capacity_and_hasher(capacity, RandomState::new())
+        Self::with_capacity_and_hasher(capacity, DefaultHashBuilder::default())
     }
 }
 
@@ -104,12 +164,18 @@ where
     K: Eq + Hash,
     S: BuildHasher,
 {
+    /// Creates an empty map which will use `hash_builder` to hash keys.
+    #[inline]
+    pub fn with_hasher(hash_builder: S) -> Self {
+        Self::with_capacity_and_hasher(0, hash_builder)
+    }
+
     /// Creates an empty map with capacity and hasher.
     pub fn with_capacity_and_hasher(capacity: usize, hash_builder: S) -> Self {
         let capacity = if capacity == 0 {
             0
         } else {
-            capacity.next_power_of_two().max(8)
+            buckets_for(capacity, MAX_LOAD_FACTOR).expect("capacity overflow")
         };
 
         if capacity == 0 {
@@ -121,6 +187,7 @@ where
                 len: 0,
                 capacity: 0,
                 hash_builder,
+                max_load_factor: MAX_LOAD_FACTOR,
             };
         }
 
@@ -139,9 +206,16 @@ where
             len: 0,
             capacity,
             hash_builder,
+            max_load_factor: MAX_LOAD_FACTOR,
         }
     }
 
+    /// Returns a reference to the map's `BuildHasher`.
+    #[inline]
+    pub fn hasher(&self) -> &S {
+        &self.hash_builder
+    }
+
     #[inline(always)]
     pub fn len(&self) -> usize {
         self.len
@@ -153,15 +227,62 @@ where
     }
 
     #[inline(always)]
+    /// Returns the number of slots in the table.
+    ///
+    /// The map grows once the slots in use (including tombstones left by removals)
+    /// reach [`max_load_factor`](Self::max_load_factor) of this.
     pub fn capacity(&self) -> usize {
         self.capacity
     }
 
+    /// Returns the fraction of slots that may be used before the table grows.
+    pub fn max_load_factor(&self) -> f32 {
+        self.max_load_factor
+    }
+
+    /// Sets the fraction of slots that may be used before the table grows, growing
+    /// immediately if the map is already over the new limit.
+    ///
+    /// Lower factors trade memory for shorter probe sequences.
+    ///
+    /// # Panics
+    /// Panics unless `0.0 < factor <= 0.875`.
+    pub fn set_max_load_factor(&mut self, factor: f32) {
+        assert!(
+            factor > 0.0 && factor <= MAX_LOAD_FACTOR,
+            "max load factor must be in (0, 0.875]"
+        );
+        self.max_load_factor = factor;
+        if self.capacity > 0 && self.items_count > self.max_items(self.capacity) {
+            let new_cap = buckets_for(self.len, factor).expect("capacity overflow");
+            self.grow(new_cap);
+        }
+    }
+
+    /// Number of used slots a table of `capacity` slots may hold.
     #[inline]
-    fn hash<Q: ?Sized + Hash>(&self, key: &Q) -> (usize, u8) {
+    fn max_items(&self, capacity: usize) -> usize {
+        max_items(capacity, self.max_load_factor)
+    }
+
+    /// Hashes `key` with the map's hasher.
+    ///
+    /// The result can be passed to the [`raw_entry_mut`](Self::raw_entry_mut) lookups
+    /// to avoid hashing the same key twice.
+    #[inline]
+    pub fn hash_key<Q: ?Sized + Hash>(&self, key: &Q) -> u64 {
         let mut hasher = self.hash_builder.build_hasher();
         key.hash(&mut hasher);
-        let hash = hasher.finish();
+        hasher.finish()
+    }
+
+    #[inline]
+    fn hash<Q: ?Sized + Hash>(&self, key: &Q) -> (usize, u8) {
+        self.split_hash(self.hash_key(key))
+    }
+
+    #[inline]
+    fn split_hash(&self, hash: u64) -> (usize, u8) {
         // Bottom bits for H1 (index) since capacity is power of 2
         let h1 = (hash as usize) & (self.capacity - 1);
         // Top 7 bits for H2 (tag) to ensure independence from H1
@@ -178,6 +299,17 @@ where
     where
         K: Borrow<Q>,
     {
+        self.find_slot_by(h1, h2, |k| k.borrow() == key)
+    }
+
+    /// Like `find_slot`, matching stored keys with `is_match`.
+    #[inline]
+    fn find_slot_by(
+        &self,
+        h1: usize,
+        h2: u8,
+        mut is_match: impl FnMut(&K) -> bool,
+    ) -> (usize, bool) {
         if self.capacity == 0 {
             return (0, false);
         }
@@ -204,7 +336,7 @@ where
 
             let group_word = unsafe {
                 let ptr = self.ctrl.as_ptr().add(idx);
-                std::ptr::read_unaligned(ptr as *const u64)
+                core::ptr::read_unaligned(ptr.cast::<u64>())
             };
 
             // Check for match
@@ -218,7 +350,7 @@ where
 
                     unsafe {
                         let k = self.keys.get_unchecked(slot_idx).assume_init_ref();
-                        if k.borrow() == key {
+                        if is_match(k) {
                             return (slot_idx, true);
                         }
                     }
@@ -338,12 +470,21 @@ where
         self.find_slot(key, h1, h2).1
     }
 
-    pub fn insert(&mut self, key: K, value: V) -> Option<V> {
-        if self.capacity == 0 || self.items_count >= self.capacity * 7 / 8 {
-            // Load factor 0.875
-            let new_cap = (self.capacity * 2).max(8);
-            self.grow(new_cap);
+    /// Inserts a key-value pair, reporting allocation failure instead of aborting.
+    ///
+    /// # Errors
+    /// Returns `AllocError` if the table needs to grow and the allocation fails; the
+    /// map is left unchanged and `key` and `value` are dropped.
+    pub fn try_insert(&mut self, key: K, value: V) -> Result<Option<V>, AllocError> {
+        if self.capacity == 0 || self.items_count >= self.max_items(self.capacity) {
+            let new_cap = self.capacity.checked_mul(2).ok_or(AllocError)?.max(8);
+            self.try_grow(new_cap)?;
         }
+        Ok(self.insert(key, value))
+    }
+
+    pub fn insert(&mut self, key: K, value: V) -> Option<V> {
+        self.reserve_one();
 
         let (h1, h2) = self.hash(&key);
         let (idx, found) = self.find_slot(&key, h1, h2);
@@ -355,30 +496,34 @@ where
         if found {
             unsafe {
                 let cell = self.values.get_unchecked_mut(idx).assume_init_mut();
-                let old_cell = std::mem::replace(cell, GhostCell::new(value));
+                let old_cell = core::mem::replace(cell, GhostCell::new(value));
                 Some(old_cell.into_inner())
             }
         } else {
-            // Insert
-            unsafe {
-                let ctrl_byte = self.ctrl.get_unchecked(idx);
-                let was_deleted = *ctrl_byte == DELETED;
-
-                self.keys.get_unchecked_mut(idx).write(key);
-                self.values
-                    .get_unchecked_mut(idx)
-                    .write(GhostCell::new(value));
-                self.ctrl[idx] = h2;
-                if idx < GROUP_WIDTH {
-                    self.ctrl[self.capacity + idx] = h2;
-                }
+            self.insert_at(idx, h2, key, value);
+            None
+        }
+    }
 
-                if !was_deleted {
-                    self.items_count += 1;
-                }
-                self.len += 1;
+    /// Writes a new entry into the free slot `idx` found by `find_slot`.
+    fn insert_at(&mut self, idx: usize, h2: u8, key: K, value: V) {
+        unsafe {
+            let ctrl_byte = self.ctrl.get_unchecked(idx);
+            let was_deleted = *ctrl_byte == DELETED;
+
+            self.keys.get_unchecked_mut(idx).write(key);
+            self.values
+                .get_unchecked_mut(idx)
+                .write(GhostCell::new(value));
+            self.ctrl[idx] = h2;
+            if idx < GROUP_WIDTH {
+                self.ctrl[self.capacity + idx] = h2;
             }
-            None
+
+            if !was_deleted {
+                self.items_count += 1;
+            }
+            self.len += 1;
         }
     }
 
@@ -393,49 +538,76 @@ where
         let (idx, found) = self.find_slot(key, h1, h2);
 
         if found {
-            unsafe {
-                // Mark as deleted
-                self.ctrl[idx] = DELETED;
-                if idx < GROUP_WIDTH {
-                    self.ctrl[self.capacity + idx] = DELETED;
-                }
+            Some(self.remove_at(idx).1)
+        } else {
+            None
+        }
+    }
 
-                self.len -= 1;
+    /// Removes the occupied slot `idx`, leaving a tombstone.
+    fn remove_at(&mut self, idx: usize) -> (K, V) {
+        // Mark as deleted
+        self.ctrl[idx] = DELETED;
+        if idx < GROUP_WIDTH {
+            self.ctrl[self.capacity + idx] = DELETED;
+        }
 
-                let key_ptr = self.keys.get_unchecked_mut(idx).as_mut_ptr();
-                let val_ptr = self.values.get_unchecked_mut(idx).as_mut_ptr();
+        self.len -= 1;
 
-                std::ptr::drop_in_place(key_ptr);
-                let val = std::ptr::read(val_ptr);
+        unsafe {
+            let key = self.keys.get_unchecked(idx).assume_init_read();
+            let val = self.values.get_unchecked(idx).assume_init_read();
+            (key, val.into_inner())
+        }
+    }
 
-                Some(val.into_inner())
-            }
-        } else {
-            None
+    /// Grows the table if one more insertion would exceed the load factor.
+    fn reserve_one(&mut self) {
+        if self.capacity == 0 || self.items_count >= self.max_items(self.capacity) {
+            let new_cap = (self.capacity * 2).max(8);
+            self.grow(new_cap);
         }
     }
 
     fn grow(&mut self, new_cap: usize) {
-        let old_ctrl = std::mem::take(&mut self.ctrl);
-        let old_keys = std::mem::take(&mut self.keys);
-        let old_values = std::mem::take(&mut self.values);
-        let old_cap = self.capacity;
-
-        // Allocate new arrays
-        self.capacity = new_cap;
-        if new_cap > 0 {
-            let ctrl_len = new_cap + GROUP_WIDTH;
-            self.ctrl = vec![EMPTY; ctrl_len].into_boxed_slice();
-            self.keys = alloc_slice(new_cap);
-            self.values = alloc_slice(new_cap);
-        } else {
-            self.items_count = 0;
-            self.len = 0;
+        if new_cap == 0 {
+            self.rehash_into(0, Box::new([]), Box::new([]), Box::new([]));
             return;
         }
+        let ctrl = vec![EMPTY; new_cap + GROUP_WIDTH].into_boxed_slice();
+        self.rehash_into(new_cap, ctrl, alloc_slice(new_cap), alloc_slice(new_cap));
+    }
 
+    /// Fallible variant of `grow`: every new array is allocated before the old table
+    /// is touched, so a failure leaves the map unchanged.
+    fn try_grow(&mut self, new_cap: usize) -> Result<(), AllocError> {
+        let ctrl_len = new_cap.checked_add(GROUP_WIDTH).ok_or(AllocError)?;
+        let ctrl = try_alloc_ctrl(ctrl_len)?;
+        let keys = try_alloc_slice(new_cap)?;
+        let values = try_alloc_slice(new_cap)?;
+        self.rehash_into(new_cap, ctrl, keys, values);
+        Ok(())
+    }
+
+    /// Moves every entry into freshly allocated arrays of capacity `new_cap`.
+    fn rehash_into(
+        &mut self,
+        new_cap: usize,
+        ctrl: Box<[u8]>,
+        keys: Box<[MaybeUninit<K>]>,
+        values: Box<[MaybeUninit<GhostCell<'brand, V>>]>,
+    ) {
+        let old_ctrl = core::mem::replace(&mut self.ctrl, ctrl);
+        let old_keys = core::mem::replace(&mut self.keys, keys);
+        let old_values = core::mem::replace(&mut self.values, values);
+        let old_cap = self.capacity;
+
+        self.capacity = new_cap;
         self.len = 0;
         self.items_count = 0;
+        if new_cap == 0 {
+            return;
+        }
 
         // Rehash
         for i in 0..old_cap {
@@ -461,7 +633,7 @@ where
         loop {
             let group_word = unsafe {
                 let ptr = self.ctrl.as_ptr().add(idx);
-                std::ptr::read_unaligned(ptr as *const u64)
+                core::ptr::read_unaligned(ptr.cast::<u64>())
             };
 
             let empty_mask = match_byte(group_word, EMPTY);
@@ -511,16 +683,64 @@ where
         self.items_count = 0;
     }
 
+    /// Reserves capacity for at least `additional` more entries.
+    ///
+    /// # Panics
+    /// Panics if the new capacity overflows `usize`.
     pub fn reserve(&mut self, additional: usize) {
-        let needed = self.len + additional;
-        if needed > self.capacity * 7 / 8 {
-            let new_cap = (needed * 8 / 7).next_power_of_two().max(8);
+        let needed = self.len.checked_add(additional).expect("capacity overflow");
+        if needed > self.max_items(self.capacity) {
+            let new_cap = buckets_for(needed, self.max_load_factor).expect("capacity overflow");
             if new_cap > self.capacity {
                 self.grow(new_cap);
             }
         }
     }
 
+    /// Tries to reserve capacity for at least `additional` more entries.
+    ///
+    /// # Errors
+    /// Returns `AllocError` if the capacity overflows or the allocation fails; the map
+    /// is left unchanged.
+    pub fn try_reserve(&mut self, additional: usize) -> Result<(), AllocError> {
+        let needed = self.len.checked_add(additional).ok_or(AllocError)?;
+        if needed > self.max_items(self.capacity) {
+            let new_cap = buckets_for(needed, self.max_load_factor).ok_or(AllocError)?;
+            if new_cap > self.capacity {
+                self.try_grow(new_cap)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Shrinks the table as much as possible while keeping every entry.
+    ///
+    /// This also clears the tombstones left by removals. An empty map releases its
+    /// allocation entirely.
+    pub fn shrink_to_fit(&mut self) {
+        self.shrink_to(0);
+    }
+
+    /// Shrinks the table while keeping room for at least `min_capacity` entries.
+    ///
+    /// Does nothing if the table is already no larger than needed and free of
+    /// tombstones.
+    ///
+    /// # Panics
+    /// Panics if the table size needed for `min_capacity` entries overflows `usize`.
+    pub fn shrink_to(&mut self, min_capacity: usize) {
+        let needed = self.len.max(min_capacity);
+        let new_cap = if needed == 0 {
+            0
+        } else {
+            buckets_for(needed, self.max_load_factor).expect("capacity overflow")
+        };
+        if new_cap < self.capacity || (new_cap == self.capacity && self.items_count > self.len)
+        {
+            self.grow(new_cap);
+        }
+    }
+
     // --- Iterators ---
 
     pub fn keys(&self) -> impl Iterator<Item = &K> {
@@ -556,6 +776,32 @@ where
             })
     }
 
+    /// Consumes the map, returning its entries in iteration order as a `Vec` that no
+    /// longer carries the brand and can leave the token scope.
+    pub fn into_vec(self) -> Vec<(K, V)> {
+        let mut entries = Vec::with_capacity(self.len);
+        entries.extend(self);
+        entries
+    }
+
+    /// Iterates over the entries in ascending key order.
+    ///
+    /// Only references to the entries are gathered and sorted; nothing is cloned. For a
+    /// stable order without sorting, build the map with a
+    /// [`DeterministicState`](super::DeterministicState) instead.
+    pub fn iter_sorted<'a, Token>(
+        &'a self,
+        token: &'a Token,
+    ) -> impl Iterator<Item = (&'a K, &'a V)> + use<'a, 'brand, K, V, S, Token>
+    where
+        K: Ord,
+        Token: crate::token::traits::GhostBorrow<'brand>,
+    {
+        let mut entries: Vec<(&K, &V)> = self.keys().zip(self.values(token)).collect();
+        entries.sort_unstable_by(|a, b| a.0.cmp(b.0));
+        entries.into_iter()
+    }
+
     /// Applies `f` to all entries in the map, allowing mutation of values.
     pub fn for_each_mut<F, Token>(&self, token: &mut Token, mut f: F)
     where
@@ -591,6 +837,288 @@ where
             items_left: self.len,
         }
     }
+
+    /// Returns a builder for looking up an entry by a precomputed hash.
+    ///
+    /// Unlike `get`/`insert`, a raw lookup takes the hash from the caller (see
+    /// [`hash_key`](Self::hash_key)) and compares stored keys through a borrowed form
+    /// or a predicate, so a vacant entry can be filled without cloning or re-hashing
+    /// the key. Because the map is borrowed mutably, entry values are reachable
+    /// without a token.
+    pub fn raw_entry_mut(&mut self) -> RawEntryBuilderMut<'_, 'brand, K, V, S> {
+        RawEntryBuilderMut { map: self }
+    }
+
+    /// Removes and yields every entry for which `pred` returns `true`.
+    ///
+    /// `pred` may also modify the values of the entries it keeps. Entries not yet
+    /// visited when the iterator is dropped are left in the map.
+    pub fn extract_if<F>(&mut self, pred: F) -> ExtractIf<'_, 'brand, K, V, S, F>
+    where
+        F: FnMut(&K, &mut V) -> bool,
+    {
+        ExtractIf {
+            map: self,
+            pred,
+            index: 0,
+        }
+    }
+}
+
+/// Builder returned by [`BrandedHashMap::raw_entry_mut`].
+pub struct RawEntryBuilderMut<'a, 'brand, K, V, S> {
+    map: &'a mut BrandedHashMap<'brand, K, V, S>,
+}
+
+impl<'a, 'brand, K, V, S> RawEntryBuilderMut<'a, 'brand, K, V, S>
+where
+    K: Eq + Hash,
+    S: BuildHasher,
+{
+    /// Looks up `key`, hashing it with the map's hasher.
+    pub fn from_key<Q: ?Sized + Hash + Eq>(self, key: &Q) -> RawEntryMut<'a, 'brand, K, V, S>
+    where
+        K: Borrow<Q>,
+    {
+        let hash = self.map.hash_key(key);
+        self.from_key_hashed_nocheck(hash, key)
+    }
+
+    /// Looks up `key` under a precomputed `hash`.
+    ///
+    /// `hash` must be the map's hash of `key`; otherwise the lookup may miss.
+    pub fn from_key_hashed_nocheck<Q: ?Sized + Eq>(
+        self,
+        hash: u64,
+        key: &Q,
+    ) -> RawEntryMut<'a, 'brand, K, V, S>
+    where
+        K: Borrow<Q>,
+    {
+        self.from_hash(hash, |k| k.borrow() == key)
+    }
+
+    /// Looks up the entry with `hash` whose key satisfies `is_match`.
+    pub fn from_hash<F>(self, hash: u64, is_match: F) -> RawEntryMut<'a, 'brand, K, V, S>
+    where
+        F: FnMut(&K) -> bool,
+    {
+        if self.map.capacity > 0 {
+            let (h1, h2) = self.map.split_hash(hash);
+            if let (idx, true) = self.map.find_slot_by(h1, h2, is_match) {
+                return RawEntryMut::Occupied(RawOccupiedEntryMut { map: self.map, idx });
+            }
+        }
+        RawEntryMut::Vacant(RawVacantEntryMut { map: self.map })
+    }
+}
+
+/// A view into a single entry found by a raw lookup.
+pub enum RawEntryMut<'a, 'brand, K, V, S> {
+    /// The entry exists.
+    Occupied(RawOccupiedEntryMut<'a, 'brand, K, V, S>),
+    /// No entry matched.
+    Vacant(RawVacantEntryMut<'a, 'brand, K, V, S>),
+}
+
+impl<'a, 'brand, K, V, S> RawEntryMut<'a, 'brand, K, V, S>
+where
+    K: Eq + Hash,
+    S: BuildHasher,
+{
+    /// Returns the entry, inserting `key` and `value` under `hash` if vacant.
+    pub fn or_insert(self, hash: u64, key: K, value: V) -> (&'a mut K, &'a mut V) {
+        match self {
+            RawEntryMut::Occupied(entry) => entry.into_key_value(),
+            RawEntryMut::Vacant(entry) => entry.insert_hashed_nocheck(hash, key, value),
+        }
+    }
+
+    /// Returns the entry, inserting the pair produced by `default` under `hash` if
+    /// vacant.
+    pub fn or_insert_with<F>(self, hash: u64, default: F) -> (&'a mut K, &'a mut V)
+    where
+        F: FnOnce() -> (K, V),
+    {
+        match self {
+            RawEntryMut::Occupied(entry) => entry.into_key_value(),
+            RawEntryMut::Vacant(entry) => {
+                let (key, value) = default();
+                entry.insert_hashed_nocheck(hash, key, value)
+            }
+        }
+    }
+
+    /// Applies `f` to the value if the entry is occupied.
+    pub fn and_modify<F>(mut self, f: F) -> Self
+    where
+        F: FnOnce(&K, &mut V),
+    {
+        if let RawEntryMut::Occupied(entry) = &mut self {
+            let (key, value) = entry.pair_mut();
+            f(key, value);
+        }
+        self
+    }
+}
+
+/// An occupied entry found by a raw lookup.
+pub struct RawOccupiedEntryMut<'a, 'brand, K, V, S> {
+    map: &'a mut BrandedHashMap<'brand, K, V, S>,
+    idx: usize,
+}
+
+impl<'a, 'brand, K, V, S> RawOccupiedEntryMut<'a, 'brand, K, V, S>
+where
+    K: Eq + Hash,
+    S: BuildHasher,
+{
+    fn pair_mut(&mut self) -> (&K, &mut V) {
+        unsafe {
+            (
+                self.map.keys.get_unchecked(self.idx).assume_init_ref(),
+                self.map
+                    .values
+                    .get_unchecked_mut(self.idx)
+                    .assume_init_mut()
+                    .get_mut(),
+            )
+        }
+    }
+
+    /// Returns the entry's key.
+    pub fn key(&self) -> &K {
+        unsafe { self.map.keys.get_unchecked(self.idx).assume_init_ref() }
+    }
+
+    /// Returns the entry's value.
+    pub fn get<'t, Token>(&'t self, token: &'t Token) -> &'t V
+    where
+        Token: crate::token::traits::GhostBorrow<'brand>,
+    {
+        unsafe {
+            self.map
+                .values
+                .get_unchecked(self.idx)
+                .assume_init_ref()
+                .borrow(token)
+        }
+    }
+
+    /// Returns the entry's value mutably.
+    pub fn get_mut(&mut self) -> &mut V {
+        self.pair_mut().1
+    }
+
+    /// Converts the entry into a mutable reference to its value.
+    pub fn into_mut(self) -> &'a mut V {
+        self.into_key_value().1
+    }
+
+    /// Converts the entry into references to its key and value.
+    ///
+    /// The key must not be modified in a way that changes its hash or equality.
+    pub fn into_key_value(self) -> (&'a mut K, &'a mut V) {
+        unsafe {
+            let key = self.map.keys.as_mut_ptr().add(self.idx);
+            let value = self.map.values.as_mut_ptr().add(self.idx);
+            (
+                (*key).assume_init_mut(),
+                (*value).assume_init_mut().get_mut(),
+            )
+        }
+    }
+
+    /// Replaces the value, returning the old one.
+    pub fn insert(&mut self, value: V) -> V {
+        core::mem::replace(self.get_mut(), value)
+    }
+
+    /// Removes the entry, returning its value.
+    pub fn remove(self) -> V {
+        self.remove_entry().1
+    }
+
+    /// Removes the entry, returning its key and value.
+    pub fn remove_entry(self) -> (K, V) {
+        self.map.remove_at(self.idx)
+    }
+}
+
+/// A vacant entry found by a raw lookup.
+pub struct RawVacantEntryMut<'a, 'brand, K, V, S> {
+    map: &'a mut BrandedHashMap<'brand, K, V, S>,
+}
+
+impl<'a, 'brand, K, V, S> RawVacantEntryMut<'a, 'brand, K, V, S>
+where
+    K: Eq + Hash,
+    S: BuildHasher,
+{
+    /// Inserts `key` and `value`, hashing `key` with the map's hasher.
+    pub fn insert(self, key: K, value: V) -> (&'a mut K, &'a mut V) {
+        let hash = self.map.hash_key(&key);
+        self.insert_hashed_nocheck(hash, key, value)
+    }
+
+    /// Inserts `key` and `value` under a precomputed `hash`.
+    ///
+    /// `hash` must be the map's hash of `key`; otherwise later lookups may miss it.
+    pub fn insert_hashed_nocheck(self, hash: u64, key: K, value: V) -> (&'a mut K, &'a mut V) {
+        self.map.reserve_one();
+        let (h1, h2) = self.map.split_hash(hash);
+        // The lookup that produced this entry found no match, so only a free slot
+        // can be returned.
+        let (idx, _) = self.map.find_slot_by(h1, h2, |_| false);
+        self.map.insert_at(idx, h2, key, value);
+        RawOccupiedEntryMut { map: self.map, idx }.into_key_value()
+    }
+}
+
+/// Iterator returned by [`BrandedHashMap::extract_if`].
+pub struct ExtractIf<'a, 'brand, K, V, S, F> {
+    map: &'a mut BrandedHashMap<'brand, K, V, S>,
+    pred: F,
+    index: usize,
+}
+
+impl<'a, 'brand, K, V, S, F> Iterator for ExtractIf<'a, 'brand, K, V, S, F>
+where
+    K: Eq + Hash,
+    S: BuildHasher,
+    F: FnMut(&K, &mut V) -> bool,
+{
+    type Item = (K, V);
+
+    fn next(&mut self) -> Option<(K, V)> {
+        while self.index < self.map.capacity {
+            let i = self.index;
+            self.index += 1;
+            if self.map.ctrl[i] & 0x80 == 0 {
+                let matched = unsafe {
+                    let key = self.map.keys.get_unchecked(i).assume_init_ref();
+                    let cell = self.map.values.get_unchecked_mut(i).assume_init_mut();
+                    (self.pred)(key, cell.get_mut())
+                };
+                if matched {
+                    return Some(self.map.remove_at(i));
+                }
+            }
+        }
+        None
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, Some(self.map.len))
+    }
+}
+
+impl<'a, 'brand, K, V, S, F> core::iter::FusedIterator for ExtractIf<'a, 'brand, K, V, S, F>
+where
+    K: Eq + Hash,
+    S: BuildHasher,
+    F: FnMut(&K, &mut V) -> bool,
+{
 }
 
 /// Mutable iterator over the map entries.
@@ -652,7 +1180,7 @@ impl<'a, 'brand, K, V, Token> ExactSizeIterator for IterMut<'a, 'brand, K, V, To
     Token: crate::token::traits::GhostBorrowMut<'brand>,
 {
 }
-impl<'a, 'brand, K, V, Token> std::iter::FusedIterator for IterMut<'a, 'brand, K, V, Token> where
+impl<'a, 'brand, K, V, Token> core::iter::FusedIterator for IterMut<'a, 'brand, K, V, Token> where
     Token: crate::token::traits::GhostBorrowMut<'brand>,
 {
 }
@@ -800,9 +1328,9 @@ impl<'brand, K, V, S> IntoIterator for BrandedHashMap<'brand, K, V, S> {
     type IntoIter = IntoIter<'brand, K, V>;
 
     fn into_iter(mut self) -> Self::IntoIter {
-        let ctrl = std::mem::take(&mut self.ctrl);
-        let keys = std::mem::take(&mut self.keys);
-        let values = std::mem::take(&mut self.values);
+        let ctrl = core::mem::take(&mut self.ctrl);
+        let keys = core::mem::take(&mut self.keys);
+        let values = core::mem::take(&mut self.values);
         let len = self.len;
         let capacity = self.capacity;
 
@@ -1031,4 +1559,179 @@ mod tests {
             assert!(map.is_empty());
         });
     }
+
+    #[test]
+    fn test_fallible_growth() {
+        GhostToken::new(|token| {
+            let mut map = BrandedHashMap::new();
+            map.try_reserve(20).unwrap();
+            let capacity = map.capacity();
+            assert!(capacity * 7 / 8 >= 20);
+            for i in 0..20 {
+                assert_eq!(map.try_insert(i, i * 10), Ok(None));
+            }
+            assert_eq!(map.capacity(), capacity);
+            assert_eq!(map.try_insert(3, 0), Ok(Some(30)));
+
+            // Growing past the reservation rehashes every entry.
+            for i in 20..100 {
+                map.try_insert(i, i * 10).unwrap();
+            }
+            assert!((4..100).all(|i| map.get(&token, &i) == Some(&(i * 10))));
+
+            assert_eq!(map.try_reserve(usize::MAX), Err(AllocError));
+            assert_eq!(map.len(), 100);
+        });
+    }
+
+    #[test]
+    fn test_raw_entry_mut_reuses_hash() {
+        GhostToken::new(|token| {
+            let mut map: BrandedHashMap<String, usize> = BrandedHashMap::new();
+            for word in "the cat saw the other cat near the door".split(' ') {
+                let hash = map.hash_key(word);
+                // The key is only allocated when a new word is seen.
+                let (_, count) = map
+                    .raw_entry_mut()
+                    .from_key_hashed_nocheck(hash, word)
+                    .or_insert_with(hash, || (word.to_string(), 0));
+                *count += 1;
+            }
+            assert_eq!(map.len(), 6);
+            assert_eq!(map.get(&token, "the"), Some(&3));
+            assert_eq!(map.get(&token, "cat"), Some(&2));
+
+            let hash = map.hash_key("door");
+            match map.raw_entry_mut().from_hash(hash, |k| k == "door") {
+                RawEntryMut::Occupied(mut entry) => {
+                    assert_eq!(entry.key(), "door");
+                    assert_eq!(entry.get(&token), &1);
+                    assert_eq!(entry.insert(10), 1);
+                    assert_eq!(entry.remove_entry(), ("door".to_string(), 10));
+                }
+                RawEntryMut::Vacant(_) => panic!("door should be present"),
+            }
+            assert!(!map.contains_key("door"));
+
+            match map.raw_entry_mut().from_key("dog") {
+                RawEntryMut::Occupied(_) => panic!("dog should be absent"),
+                RawEntryMut::Vacant(entry) => {
+                    entry.insert("dog".to_string(), 7);
+                }
+            }
+            map.raw_entry_mut()
+                .from_key("dog")
+                .and_modify(|_, v| *v *= 2);
+            assert_eq!(map.get(&token, "dog"), Some(&14));
+        });
+    }
+
+    #[test]
+    fn test_extract_if() {
+        GhostToken::new(|token| {
+            let mut map: BrandedHashMap<i32, i32> = (0..100).map(|i| (i, i)).collect();
+            let mut evens: Vec<(i32, i32)> = map
+                .extract_if(|k, v| {
+                    *v += 1;
+                    k % 2 == 0
+                })
+                .collect();
+            evens.sort();
+            assert_eq!(evens, (0..100).step_by(2).map(|i| (i, i + 1)).collect::<Vec<_>>());
+            assert_eq!(map.len(), 50);
+            assert!((1..100).step_by(2).all(|i| map.get(&token, &i) == Some(&(i + 1))));
+
+            // Stopping early leaves unvisited entries in place.
+            assert_eq!(map.extract_if(|_, _| true).take(10).count(), 10);
+            assert_eq!(map.len(), 40);
+            // Tombstones left behind are reused by later inserts.
+            for i in 0..100 {
+                map.insert(i, 0);
+            }
+            assert_eq!(map.len(), 100);
+        });
+    }
+
+    #[test]
+    fn test_capacity_management() {
+        GhostToken::new(|token| {
+            let mut map = BrandedHashMap::with_capacity(100);
+            let capacity = map.capacity();
+            for i in 0..100 {
+                map.insert(i, i);
+            }
+            // `with_capacity(n)` holds `n` entries without growing.
+            assert_eq!(map.capacity(), capacity);
+
+            for i in 10..100 {
+                map.remove(&i);
+            }
+            map.shrink_to_fit();
+            assert_eq!(map.capacity(), 16);
+            assert!((0..10).all(|i| map.get(&token, &i) == Some(&i)));
+
+            map.shrink_to(50);
+            assert_eq!(map.capacity(), 16);
+            map.reserve(50);
+            assert!(max_items(map.capacity(), map.max_load_factor()) >= 60);
+
+            map.clear();
+            map.shrink_to_fit();
+            assert_eq!(map.capacity(), 0);
+            map.insert(1, 1);
+            assert_eq!(map.get(&token, &1), Some(&1));
+        });
+    }
+
+    #[test]
+    fn test_max_load_factor() {
+        GhostToken::new(|token| {
+            let mut map = BrandedHashMap::new();
+            assert_eq!(map.max_load_factor(), 0.875);
+            for i in 0..14 {
+                map.insert(i, i);
+            }
+            assert_eq!(map.capacity(), 16);
+
+            // Lowering the factor grows the table right away.
+            map.set_max_load_factor(0.5);
+            assert_eq!(map.capacity(), 32);
+            for i in 14..100 {
+                map.insert(i, i);
+            }
+            assert!(map.len() <= map.capacity() / 2);
+            assert!((0..100).all(|i| map.get(&token, &i) == Some(&i)));
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "max load factor")]
+    fn test_max_load_factor_rejects_full_tables() {
+        BrandedHashMap::<u32, u32>::new().set_max_load_factor(1.0);
+    }
+
+    #[test]
+    fn test_iter_sorted() {
+        GhostToken::new(|token| {
+            let map: BrandedHashMap<'_, u32, char> =
+                [(3, 'c'), (1, 'a'), (4, 'd'), (2, 'b')].into_iter().collect();
+            let entries: Vec<_> = map.iter_sorted(&token).collect();
+            assert_eq!(entries, [(&1, &'a'), (&2, &'b'), (&3, &'c'), (&4, &'d')]);
+        });
+    }
+
+    #[test]
+    fn test_into_vec_escapes_scope() {
+        let mut entries = GhostToken::new(|mut token| {
+            let map: BrandedHashMap<'_, u32, String> =
+                (0..3).map(|i| (i, i.to_string())).collect();
+            map.get_mut(&mut token, &2).unwrap().push('!');
+            map.into_vec()
+        });
+        entries.sort();
+        assert_eq!(
+            entries,
+            [(0, "0".to_string()), (1, "1".to_string()), (2, "2!".to_string())]
+        );
+    }
 }
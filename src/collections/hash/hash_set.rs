@@ -6,17 +6,20 @@
 //! wrapper over `BrandedHashMap<K, ()>`.
 
 use super::hash_map::BrandedHashMap;
+use crate::alloc::AllocError;
 use crate::GhostToken;
-use std::collections::hash_map::RandomState;
-use std::hash::{BuildHasher, Hash};
+use super::DefaultHashBuilder;
+use core::hash::{BuildHasher, Hash};
+#[cfg(not(feature = "std"))]
+use alloc_crate::vec::Vec;
 
 /// A hash set with branded membership.
 #[repr(transparent)]
-pub struct BrandedHashSet<'brand, K, S = RandomState> {
+pub struct BrandedHashSet<'brand, K, S = DefaultHashBuilder> {
     inner: BrandedHashMap<'brand, K, (), S>,
 }
 
-impl<'brand, K> BrandedHashSet<'brand, K, RandomState>
+impl<'brand, K> BrandedHashSet<'brand, K, DefaultHashBuilder>
 where
     K: Eq + Hash,
 {
@@ -40,6 +43,25 @@ where
     K: Eq + Hash,
     S: BuildHasher,
 {
+    /// Creates an empty set which will use `hash_builder` to hash values.
+    pub fn with_hasher(hash_builder: S) -> Self {
+        Self {
+            inner: BrandedHashMap::with_hasher(hash_builder),
+        }
+    }
+
+    /// Creates an empty set with at least the specified capacity, using `hash_builder`.
+    pub fn with_capacity_and_hasher(capacity: usize, hash_builder: S) -> Self {
+        Self {
+            inner: BrandedHashMap::with_capacity_and_hasher(capacity, hash_builder),
+        }
+    }
+
+    /// Returns a reference to the set's `BuildHasher`.
+    pub fn hasher(&self) -> &S {
+        self.inner.hasher()
+    }
+
     /// Returns the number of elements.
     pub fn len(&self) -> usize {
         self.inner.len()
@@ -50,6 +72,48 @@ where
         self.inner.is_empty()
     }
 
+    /// Returns the number of slots in the table; see [`BrandedHashMap::capacity`].
+    pub fn capacity(&self) -> usize {
+        self.inner.capacity()
+    }
+
+    /// Reserves capacity for at least `additional` more values.
+    pub fn reserve(&mut self, additional: usize) {
+        self.inner.reserve(additional);
+    }
+
+    /// Tries to reserve capacity for at least `additional` more values.
+    ///
+    /// # Errors
+    /// Returns `AllocError` if the capacity overflows or the allocation fails; the set
+    /// is left unchanged.
+    pub fn try_reserve(&mut self, additional: usize) -> Result<(), AllocError> {
+        self.inner.try_reserve(additional)
+    }
+
+    /// Shrinks the table as much as possible while keeping every value.
+    pub fn shrink_to_fit(&mut self) {
+        self.inner.shrink_to_fit();
+    }
+
+    /// Shrinks the table while keeping room for at least `min_capacity` values.
+    pub fn shrink_to(&mut self, min_capacity: usize) {
+        self.inner.shrink_to(min_capacity);
+    }
+
+    /// Returns the fraction of slots that may be used before the table grows.
+    pub fn max_load_factor(&self) -> f32 {
+        self.inner.max_load_factor()
+    }
+
+    /// Sets the maximum load factor; see [`BrandedHashMap::set_max_load_factor`].
+    ///
+    /// # Panics
+    /// Panics unless `0.0 < factor <= 0.875`.
+    pub fn set_max_load_factor(&mut self, factor: f32) {
+        self.inner.set_max_load_factor(factor);
+    }
+
     /// Inserts a value. Returns `true` if it was not already present.
     pub fn insert(&mut self, value: K) -> bool {
         self.inner.insert(value, ()).is_none()
@@ -79,6 +143,16 @@ where
         self.inner.keys()
     }
 
+    /// Iterates over all values in ascending order, without cloning them.
+    pub fn iter_sorted(&self) -> impl Iterator<Item = &K>
+    where
+        K: Ord,
+    {
+        let mut values: Vec<&K> = self.inner.keys().collect();
+        values.sort_unstable();
+        values.into_iter()
+    }
+
     /// Bulk operation: applies `f` to all keys.
     ///
     /// Note: Keys are not token-gated since they are used for external access,
@@ -94,12 +168,13 @@ where
     }
 }
 
-impl<'brand, K> Default for BrandedHashSet<'brand, K, RandomState>
+impl<'brand, K, S> Default for BrandedHashSet<'brand, K, S>
 where
     K: Eq + Hash,
+    S: BuildHasher + Default,
 {
     fn default() -> Self {
-        Self::new()
+        Self::with_hasher(S::default())
     }
 }
 
@@ -122,4 +197,46 @@ mod tests {
             assert!(!set.contains(&"c"));
         });
     }
+
+    #[test]
+    fn branded_hash_set_with_hasher() {
+        use core::hash::BuildHasherDefault;
+        type Build = BuildHasherDefault<std::collections::hash_map::DefaultHasher>;
+
+        let mut set = BrandedHashSet::with_capacity_and_hasher(4, Build::default());
+        assert!(set.insert(1u32));
+        assert!(!set.insert(1u32));
+        assert!(set.contains(&1));
+        let _: &Build = set.hasher();
+
+        let empty: BrandedHashSet<'_, u32, Build> = BrandedHashSet::default();
+        assert!(empty.is_empty());
+    }
+
+    #[test]
+    fn branded_hash_set_capacity_management() {
+        let mut set = BrandedHashSet::new();
+        set.set_max_load_factor(0.5);
+        set.reserve(100);
+        assert!(set.capacity() >= 200);
+        for i in 0..100 {
+            set.insert(i);
+        }
+        for i in 4..100 {
+            set.remove(&i);
+        }
+        set.shrink_to_fit();
+        assert_eq!(set.capacity(), 8);
+        assert!((0..4).all(|i| set.contains(&i)));
+        assert_eq!(set.try_reserve(usize::MAX), Err(AllocError));
+    }
+
+    #[test]
+    fn branded_hash_set_iter_sorted() {
+        let mut set = BrandedHashSet::new();
+        for word in ["pear", "apple", "fig"] {
+            set.insert(word);
+        }
+        assert!(set.iter_sorted().eq(&["apple", "fig", "pear"]));
+    }
 }
@@ -17,7 +17,9 @@ use crate::collections::{BrandedCollection, BrandedVec, ZeroCopyMapOps};
 use crate::token::traits::{GhostBorrow, GhostBorrowMut};
 use crate::GhostCell;
 use core::hash::{BuildHasher, Hash, Hasher};
-use std::collections::hash_map::RandomState;
+use super::DefaultHashBuilder;
+#[cfg(not(feature = "std"))]
+use alloc_crate::{boxed::Box, vec, vec::Vec};
 // Control byte constants
 const EMPTY: u8 = 0xFF;
 const DELETED: u8 = 0xFE;
@@ -37,7 +39,7 @@ fn match_byte(x: u64, y: u8) -> u64 {
 }
 
 /// High-performance ordered hash map.
-pub struct BrandedIndexMap<'brand, K, V, S = RandomState> {
+pub struct BrandedIndexMap<'brand, K, V, S = DefaultHashBuilder> {
     /// Control bytes for the hash table part.
     ctrl: Box<[u8]>,
     /// Slots storing indices into the dense vectors.
@@ -59,21 +61,34 @@ pub struct BrandedIndexMap<'brand, K, V, S = RandomState> {
     hash_builder: S,
 }
 
-impl<'brand, K, V> BrandedIndexMap<'brand, K, V, RandomState> {
+impl<'brand, K, V> BrandedIndexMap<'brand, K, V, DefaultHashBuilder> {
     /// Creates an empty map with default capacity.
     #[inline]
     pub fn new() -> Self {
-        Self::with_capacity_and_hasher(0, RandomState::new())
+        Self::with_capacity_and_hasher(0, DefaultHashBuilder::default())
     }
 
     /// Creates an empty map with at least the specified capacity.
     #[inline]
     pub fn with_capacity(capacity: usize) -> Self {
-        Self::with_capacity_and_hasher(capacity, RandomState::new())
+        Self::with_capacity_and_hasher(capacity, DefaultHashBuilder::default())
     }
 }
 
 impl<'brand, K, V, S> BrandedIndexMap<'brand, K, V, S> {
+    /// Creates an empty map which will use `hash_builder` to hash keys.
+    #[inline]
+    pub fn with_hasher(hash_builder: S) -> Self {
+        Self::with_capacity_and_hasher(0, hash_builder)
+    }
+
+    /// Returns a reference to the map's `BuildHasher`.
+    #[inline]
+    pub fn hasher(&self) -> &S {
+        &self.hash_builder
+    }
+
+    /// Creates an empty map with capacity and hasher.
     pub fn with_capacity_and_hasher(capacity: usize, hash_builder: S) -> Self {
         let table_capacity = if capacity == 0 {
             0
@@ -241,7 +256,7 @@ where
         loop {
             let group_word = unsafe {
                 let ptr = self.ctrl.as_ptr().add(idx);
-                std::ptr::read_unaligned(ptr as *const u64)
+                core::ptr::read_unaligned(ptr.cast::<u64>())
             };
 
             let match_mask = match_byte(group_word, h2);
@@ -314,7 +329,7 @@ where
                 let dense_idx = *self.slots.get_unchecked(slot_idx);
                 // We need to swap the value in GhostCell
                 let cell = self.values.inner.get_unchecked_mut(dense_idx);
-                let old = std::mem::replace(cell, GhostCell::new(value));
+                let old = core::mem::replace(cell, GhostCell::new(value));
                 Some(old.into_inner())
             }
         } else {
@@ -344,64 +359,159 @@ where
     /// Removes a key from the map, returning the value.
     /// Performs a swap_remove on the dense vectors to perform in O(1).
     pub fn swap_remove(&mut self, key: &K) -> Option<V> {
+        let slot_idx = self.slot_of(key)?;
+        Some(self.swap_remove_slot(slot_idx).1)
+    }
+
+    /// Removes the entry at `index`, returning its key and value.
+    ///
+    /// The last entry takes its place, as in [`Vec::swap_remove`]. **Time complexity**: O(1).
+    ///
+    /// # Panics
+    /// Panics if the key at `index` can no longer be found by hashing, which happens
+    /// only when its `Hash` or `Eq` implementation is inconsistent.
+    pub fn swap_remove_index(&mut self, index: usize) -> Option<(K, V)> {
+        let key = self.keys.get(index)?;
+        let slot_idx = self
+            .slot_of(key)
+            .expect("BrandedIndexMap inconsistency during swap_remove_index");
+        Some(self.swap_remove_slot(slot_idx))
+    }
+
+    /// Returns the table slot holding `key`, if present.
+    #[inline]
+    fn slot_of(&self, key: &K) -> Option<usize> {
         if self.table_capacity == 0 {
             return None;
         }
-
         let (h1, h2) = self.hash(key);
         let (slot_idx, found) = self.find_slot(key, h1, h2);
+        found.then_some(slot_idx)
+    }
 
-        if found {
-            unsafe {
-                let dense_idx = *self.slots.get_unchecked(slot_idx);
+    /// Removes the entry referenced by the occupied table slot `slot_idx`.
+    fn swap_remove_slot(&mut self, slot_idx: usize) -> (K, V) {
+        unsafe {
+            let dense_idx = *self.slots.get_unchecked(slot_idx);
 
-                // Mark slot as deleted
-                self.ctrl[slot_idx] = DELETED;
-                if slot_idx < GROUP_WIDTH {
-                    self.ctrl[self.table_capacity + slot_idx] = DELETED;
-                }
-                // items_count stays same (DELETED is still "occupied" for probing)
-                // But if we want to be strict, items_count tracks load. DELETED adds to load.
-
-                // Remove from dense vectors
-                // swap_remove moves the last element to dense_idx.
-                // We need to update the hash table for the moved element.
-
-                let last_idx = self.keys.len() - 1;
-                if dense_idx == last_idx {
-                    // Simple case: removing the last element
-                    self.keys.pop();
-                    return self.values.pop().map(|c| c.into_inner());
-                }
+            // Mark slot as deleted
+            self.ctrl[slot_idx] = DELETED;
+            if slot_idx < GROUP_WIDTH {
+                self.ctrl[self.table_capacity + slot_idx] = DELETED;
+            }
+            // items_count stays same (DELETED is still "occupied" for probing)
+            // But if we want to be strict, items_count tracks load. DELETED adds to load.
+
+            // Remove from dense vectors
+            // swap_remove moves the last element to dense_idx.
+            // We need to update the hash table for the moved element.
+
+            let last_idx = self.keys.len() - 1;
+            if dense_idx == last_idx {
+                // Simple case: removing the last element
+                let key = self.keys.pop().unwrap();
+                let value = self.values.pop().unwrap().into_inner();
+                return (key, value);
+            }
 
-                // Find the slot for the last element (which will move)
-                // We must do this BEFORE swap_remove because find_slot relies on keys[idx] being valid.
-                let last_key = self.keys.get_unchecked(last_idx);
-                let (mh1, mh2) = self.hash(last_key);
-                let (moved_slot_idx, moved_found) = self.find_slot(last_key, mh1, mh2);
+            // Find the slot for the last element (which will move)
+            // We must do this BEFORE swap_remove because find_slot relies on keys[idx] being valid.
+            let moved_slot_idx = self
+                .slot_of(self.keys.get_unchecked(last_idx))
+                .expect("BrandedIndexMap inconsistency during swap_remove");
 
-                if !moved_found {
-                    panic!("BrandedIndexMap inconsistency during swap_remove");
-                }
+            // Update the slot to point to the new location (dense_idx)
+            // We can do this before swap_remove because we already have the index.
+            *self.slots.get_unchecked_mut(moved_slot_idx) = dense_idx;
 
-                // Update the slot to point to the new location (dense_idx)
-                // We can do this before swap_remove because we already have the index.
-                *self.slots.get_unchecked_mut(moved_slot_idx) = dense_idx;
+            // Now perform the swap
+            let key = self.keys.swap_remove(dense_idx);
+            let val_cell = self.values.swap_remove(dense_idx);
 
-                // Now perform the swap
-                self.keys.swap_remove(dense_idx);
-                let val_cell = self.values.swap_remove(dense_idx);
+            (key, val_cell.into_inner())
+        }
+    }
 
-                Some(val_cell.into_inner())
-            }
+    /// Returns the index of `key` in insertion order, if present.
+    pub fn get_index_of(&self, key: &K) -> Option<usize> {
+        self.slot_of(key).map(|slot_idx| self.slots[slot_idx])
+    }
+
+    /// Moves the entry at `from` to `to`, shifting the entries in between by one.
+    ///
+    /// **Time complexity**: O(|to - from|).
+    ///
+    /// # Panics
+    /// Panics if `from` or `to` is out of bounds.
+    pub fn move_index(&mut self, from: usize, to: usize) {
+        let len = self.keys.len();
+        assert!(from < len && to < len, "index out of bounds");
+        if from == to {
+            return;
+        }
+        let (lo, hi) = (from.min(to), from.max(to));
+
+        // Look up the affected slots while the table still matches the dense order.
+        let moved: Vec<usize> = self.keys[lo..=hi]
+            .iter()
+            .map(|key| {
+                self.slot_of(key)
+                    .expect("BrandedIndexMap inconsistency during move_index")
+            })
+            .collect();
+
+        let values = &mut self.values.as_mut_slice_exclusive()[lo..=hi];
+        if from < to {
+            self.keys[lo..=hi].rotate_left(1);
+            values.rotate_left(1);
         } else {
-            None
+            self.keys[lo..=hi].rotate_right(1);
+            values.rotate_right(1);
+        }
+
+        for (old, slot_idx) in (lo..=hi).zip(moved) {
+            self.slots[slot_idx] = if old == from {
+                to
+            } else if from < to {
+                old - 1
+            } else {
+                old + 1
+            };
         }
     }
 
+    /// Sorts the entries by key, keeping equal keys in their current order.
+    pub fn sort_keys(&mut self)
+    where
+        K: Ord,
+    {
+        self.sort_by(|k1, _, k2, _| k1.cmp(k2));
+    }
+
+    /// Sorts the entries with a comparator over keys and values.
+    ///
+    /// The sort is stable, and the hash table is rebuilt afterwards.
+    /// **Time complexity**: O(n log n).
+    pub fn sort_by<F>(&mut self, mut cmp: F)
+    where
+        F: FnMut(&K, &V, &K, &V) -> core::cmp::Ordering,
+    {
+        let values = core::mem::take(&mut self.values);
+        let mut entries: Vec<(K, V)> = core::mem::take(&mut self.keys)
+            .into_iter()
+            .zip(values.into_iter())
+            .collect();
+        entries.sort_by(|(k1, v1), (k2, v2)| cmp(k1, v1, k2, v2));
+        for (key, value) in entries {
+            self.keys.push(key);
+            self.values.push(value);
+        }
+        self.grow(self.table_capacity);
+    }
+
     fn grow(&mut self, new_cap: usize) {
-        let _old_ctrl = std::mem::take(&mut self.ctrl);
-        let _old_slots = std::mem::take(&mut self.slots);
+        let _old_ctrl = core::mem::take(&mut self.ctrl);
+        let _old_slots = core::mem::take(&mut self.slots);
         let _old_table_cap = self.table_capacity;
 
         self.table_capacity = new_cap;
@@ -433,7 +543,7 @@ where
             loop {
                 let group_word = unsafe {
                     let ptr = self.ctrl.as_ptr().add(idx);
-                    std::ptr::read_unaligned(ptr as *const u64)
+                    core::ptr::read_unaligned(ptr.cast::<u64>())
                 };
 
                 let empty_mask = match_byte(group_word, EMPTY);
@@ -615,4 +725,55 @@ mod tests {
             assert_eq!(map.get_index(&token, 1), Some((&"c", &3)));
         });
     }
+    #[test]
+    fn test_index_map_positional_operations() {
+        GhostToken::new(|token| {
+            let mut map = BrandedIndexMap::new();
+            for (i, key) in ["d", "a", "e", "b", "c"].into_iter().enumerate() {
+                map.insert(key, i);
+            }
+            assert_eq!(map.get_index_of(&"e"), Some(2));
+            assert_eq!(map.get_index_of(&"z"), None);
+
+            map.move_index(0, 3);
+            assert_eq!(
+                map.keys().copied().collect::<Vec<_>>(),
+                ["a", "e", "b", "d", "c"]
+            );
+            map.move_index(4, 1);
+            assert_eq!(
+                map.keys().copied().collect::<Vec<_>>(),
+                ["a", "c", "e", "b", "d"]
+            );
+            for (i, key) in map.keys().enumerate() {
+                assert_eq!(map.get_index_of(key), Some(i));
+            }
+            assert_eq!(map.get(&token, &"d"), Some(&0));
+
+            assert_eq!(map.swap_remove_index(1), Some(("c", 4)));
+            assert_eq!(map.swap_remove_index(9), None);
+            assert_eq!(map.get_index(&token, 1), Some((&"d", &0)));
+            assert_eq!(map.get_index_of(&"d"), Some(1));
+
+            map.sort_keys();
+            assert_eq!(
+                map.keys().copied().collect::<Vec<_>>(),
+                ["a", "b", "d", "e"]
+            );
+            map.sort_by(|_, v1, _, v2| v2.cmp(v1));
+            assert_eq!(
+                map.values(&token).copied().collect::<Vec<_>>(),
+                [3, 2, 1, 0]
+            );
+            for (i, key) in map.keys().enumerate() {
+                assert_eq!(map.get_index_of(key), Some(i));
+            }
+
+            // The rebuilt table keeps accepting inserts and removals.
+            map.insert("f", 5);
+            assert_eq!(map.swap_remove(&"b"), Some(3));
+            assert_eq!(map.get(&token, &"f"), Some(&5));
+            assert_eq!(map.len(), 4);
+        });
+    }
 }
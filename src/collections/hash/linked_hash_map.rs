@@ -14,9 +14,11 @@ use crate::token::traits::GhostBorrow;
 use crate::{GhostCell, GhostToken};
 use core::hash::{BuildHasher, Hash, Hasher};
 use core::mem::MaybeUninit;
-use std::alloc::{self, Layout};
-use std::collections::hash_map::RandomState;
-use std::ptr::NonNull;
+use alloc_crate::alloc::{self, Layout};
+use super::DefaultHashBuilder;
+use core::ptr::NonNull;
+#[cfg(not(feature = "std"))]
+use alloc_crate::{boxed::Box, vec};
 
 // Control byte constants
 const EMPTY: u8 = 0xFF;
@@ -42,7 +44,7 @@ fn alloc_slice<T>(len: usize) -> Box<[MaybeUninit<T>]> {
     if len == 0 || core::mem::size_of::<T>() == 0 {
         let ptr = NonNull::<MaybeUninit<T>>::dangling().as_ptr();
         unsafe {
-            let slice = std::slice::from_raw_parts_mut(ptr, len);
+            let slice = core::slice::from_raw_parts_mut(ptr, len);
             Box::from_raw(slice)
         }
     } else {
@@ -52,13 +54,13 @@ fn alloc_slice<T>(len: usize) -> Box<[MaybeUninit<T>]> {
             if ptr.is_null() {
                 alloc::handle_alloc_error(layout);
             }
-            let slice = std::slice::from_raw_parts_mut(ptr, len);
+            let slice = core::slice::from_raw_parts_mut(ptr, len);
             Box::from_raw(slice)
         }
     }
 }
 
-pub struct BrandedLinkedHashMap<'brand, K, V, S = RandomState> {
+pub struct BrandedLinkedHashMap<'brand, K, V, S = DefaultHashBuilder> {
     ctrl: Box<[u8]>,
     slots: Box<[usize]>, // Hash table: maps hash slot -> storage index
 
@@ -139,13 +141,13 @@ impl<'brand, K, V, S> BrandedLinkedHashMap<'brand, K, V, S> {
     }
 }
 
-impl<'brand, K, V> BrandedLinkedHashMap<'brand, K, V, RandomState> {
+impl<'brand, K, V> BrandedLinkedHashMap<'brand, K, V, DefaultHashBuilder> {
     pub fn new() -> Self {
-        Self::with_capacity_and_hasher(0, RandomState::new())
+        Self::with_capacity_and_hasher(0, DefaultHashBuilder::default())
     }
 
     pub fn with_capacity(capacity: usize) -> Self {
-        Self::with_capacity_and_hasher(capacity, RandomState::new())
+        Self::with_capacity_and_hasher(capacity, DefaultHashBuilder::default())
     }
 }
 
@@ -179,7 +181,7 @@ where
         loop {
             let group_word = unsafe {
                 let ptr = self.ctrl.as_ptr().add(idx);
-                std::ptr::read_unaligned(ptr as *const u64)
+                core::ptr::read_unaligned(ptr.cast::<u64>())
             };
 
             let match_mask = match_byte(group_word, h2);
@@ -247,7 +249,7 @@ where
             unsafe {
                 let storage_idx = *self.slots.get_unchecked(slot_idx);
                 let cell = self.values.get_unchecked_mut(storage_idx).assume_init_mut();
-                let old = std::mem::replace(cell, GhostCell::new(value));
+                let old = core::mem::replace(cell, GhostCell::new(value));
                 Some(old.into_inner())
             }
         } else {
@@ -490,10 +492,10 @@ where
     }
 
     fn grow(&mut self, new_cap: usize) {
-        let old_keys = std::mem::take(&mut self.keys);
-        let old_values = std::mem::take(&mut self.values);
-        let _old_prev = std::mem::take(&mut self.prev);
-        let old_next = std::mem::take(&mut self.next);
+        let old_keys = core::mem::take(&mut self.keys);
+        let old_values = core::mem::take(&mut self.values);
+        let _old_prev = core::mem::take(&mut self.prev);
+        let old_next = core::mem::take(&mut self.next);
         let old_head = self.head;
 
         // Re-initialize with new capacity
@@ -545,6 +547,88 @@ where
         // `Box<[MaybeUninit<T>]>` won't drop elements, only memory. Correct.
     }
 
+    /// Checks the structural invariants of the map, returning the first violation.
+    ///
+    /// Verifies that the order list is well linked (consistent `prev`/`next`, `head`
+    /// and `tail`) and holds exactly `len()` entries, that every listed entry is found
+    /// through the hash table at its own storage index, that the control bytes and their
+    /// mirrored group agree with the occupancy counters, and that the free list accounts
+    /// for every unused storage slot.
+    ///
+    /// **Time complexity**: \(O(capacity)\)
+    ///
+    /// # Errors
+    /// Returns a description of the first invariant found violated.
+    pub fn validate_invariants(&self) -> Result<(), &'static str> {
+        if self.capacity == 0 {
+            return if self.len == 0 && self.head == END_OF_LIST && self.tail == END_OF_LIST {
+                Ok(())
+            } else {
+                Err("unallocated map reports entries")
+            };
+        }
+
+        let mut listed = 0;
+        let mut prev = END_OF_LIST;
+        let mut curr = self.head;
+        while curr != END_OF_LIST {
+            if curr >= self.capacity || listed >= self.capacity {
+                return Err("order list is out of bounds or cyclic");
+            }
+            if self.prev[curr] != prev {
+                return Err("prev link does not match the order list");
+            }
+            // SAFETY: entries on the order list have initialized keys.
+            let key = unsafe { self.keys[curr].assume_init_ref() };
+            let (h1, h2) = self.hash(key);
+            let (slot, found) = self.find_slot(key, h1, h2);
+            if !found || self.slots[slot] != curr {
+                return Err("listed entry is not reachable through the hash table");
+            }
+            listed += 1;
+            prev = curr;
+            curr = self.next[curr];
+        }
+        if prev != self.tail {
+            return Err("tail is not the last entry of the order list");
+        }
+        if listed != self.len {
+            return Err("len does not match the order list");
+        }
+
+        let (mut full, mut deleted) = (0, 0);
+        for &c in &self.ctrl[..self.capacity] {
+            if c & 0x80 == 0 {
+                full += 1;
+            } else if c == DELETED {
+                deleted += 1;
+            }
+        }
+        if full != self.len {
+            return Err("number of full control bytes does not match len");
+        }
+        if full + deleted != self.items_count {
+            return Err("items_count does not match full and deleted control bytes");
+        }
+        if self.ctrl[..GROUP_WIDTH] != self.ctrl[self.capacity..] {
+            return Err("mirrored control bytes are out of sync");
+        }
+
+        let mut free = 0;
+        let mut curr = self.free_head;
+        while curr != END_OF_LIST {
+            if curr >= self.capacity || free >= self.capacity - self.len {
+                return Err("free list is out of bounds, cyclic or too long");
+            }
+            free += 1;
+            curr = self.next[curr];
+        }
+        if free != self.capacity - self.len {
+            return Err("free list does not cover every unused slot");
+        }
+        Ok(())
+    }
+
     // Iterators
 
     pub fn iter<'a, Token>(
@@ -726,4 +810,29 @@ mod tests {
             assert_eq!(map.get(&token, &"b"), Some(&2));
         });
     }
+
+    #[test]
+    fn test_linked_map_validate_invariants() {
+        let mut map = BrandedLinkedHashMap::new();
+        crate::debug_assert_valid!(map);
+
+        let mut x = 3u32;
+        for _ in 0..1000 {
+            x = x.wrapping_mul(1_103_515_245).wrapping_add(12_345);
+            let key = (x >> 16) % 64;
+            match x >> 30 {
+                0 | 1 => {
+                    map.insert(key, ());
+                }
+                2 => {
+                    map.remove(&key);
+                }
+                _ => {
+                    map.move_to_front(&key);
+                    map.pop_front();
+                }
+            }
+            assert_eq!(map.validate_invariants(), Ok(()));
+        }
+    }
 }
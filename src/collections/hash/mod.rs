@@ -5,15 +5,50 @@
 
 pub mod active;
 pub mod active_set;
+pub mod bi_map;
+#[cfg(all(feature = "std", target_has_atomic = "64"))]
+pub mod concurrent_map;
+pub mod deterministic;
+pub mod hamt_map;
 pub mod hash_map;
 pub mod external_map;
+#[cfg(any(feature = "fxhash", not(feature = "std")))]
+pub mod fx;
 pub mod hash_set;
 pub mod index_map;
 pub mod linked_hash_map;
+pub mod relational;
+#[cfg(feature = "std")]
+pub mod sharded_map;
 
 pub use active::{ActivateHashMap, ActiveHashMap};
 pub use active_set::{ActivateHashSet, ActiveHashSet};
+pub use bi_map::BrandedBiMap;
+#[cfg(all(feature = "std", target_has_atomic = "64"))]
+pub use concurrent_map::GhostConcurrentHashMap;
+pub use deterministic::{DeterministicHasher, DeterministicState};
+#[cfg(any(feature = "fxhash", not(feature = "std")))]
+pub use fx::{FxBrandedHashMap, FxBrandedHashSet, FxBrandedIndexMap, FxBuildHasher, FxHasher};
+pub use hamt_map::BrandedHamtMap;
 pub use hash_map::BrandedHashMap;
 pub use hash_set::BrandedHashSet;
 pub use index_map::BrandedIndexMap;
 pub use linked_hash_map::BrandedLinkedHashMap;
+pub use relational::{group_by_aggregate, hash_join};
+#[cfg(feature = "std")]
+pub use sharded_map::{ShardedBrandedHashMap, ShardedMapSnapshot};
+
+/// The hasher used by the branded hash collections when none is given.
+///
+/// With `std` this is the randomly seeded SipHash `RandomState`. Without it there is
+/// no source of randomness, so the deterministic `FxBuildHasher` is used instead;
+/// pass a keyed hasher explicitly if keys may be attacker-controlled.
+#[cfg(feature = "std")]
+pub type DefaultHashBuilder = std::collections::hash_map::RandomState;
+/// The hasher used by the branded hash collections when none is given.
+///
+/// With `std` this is the randomly seeded SipHash `RandomState`. Without it there is
+/// no source of randomness, so the deterministic `FxBuildHasher` is used instead;
+/// pass a keyed hasher explicitly if keys may be attacker-controlled.
+#[cfg(not(feature = "std"))]
+pub type DefaultHashBuilder = FxBuildHasher;
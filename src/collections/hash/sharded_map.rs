@@ -0,0 +1,582 @@
+//! `ShardedBrandedHashMap` — a concurrent hash map built from lock-guarded
+//! [`BrandedHashMap`] shards.
+//!
+//! Keys are partitioned across [`SHARD_COUNT`] shards by hash, and each shard sits
+//! behind its own reader-writer lock, so threads working on different shards never
+//! contend. The map takes ownership of the brand's `GhostToken`: nothing outside the
+//! map can then reach its `GhostCell`s, so a shard's read lock is enough to hand the
+//! token to lookups, and a shard's write lock gives `&mut` access to its values
+//! without a token at all. All methods therefore take `&self`, and the map can be
+//! shared across threads (e.g. with `std::thread::scope`).
+//!
+//! One hash is computed per operation; it selects the shard and is reused for the
+//! lookup inside it through [`BrandedHashMap::raw_entry_mut`].
+//!
+//! [`ShardedBrandedHashMap::snapshot`] captures the whole map as of one instant without
+//! stopping writers. Starting a snapshot opens a snapshot epoch; the snapshot then
+//! copies the shards one at a time, locking only the shard it is copying. A writer that
+//! reaches a shard the snapshot has not copied yet first saves the shard's entries for
+//! the snapshot, once per epoch, so the snapshot still sees the shard as it was when the
+//! epoch opened. Writers therefore never wait for a snapshot beyond the usual
+//! per-shard lock, and pay one copy only for shards they change during its epoch.
+
+use super::hash_map::RawEntryMut;
+use super::BrandedHashMap;
+use crate::concurrency::{CachePadded, SHARD_COUNT, SHARD_MASK};
+use crate::GhostToken;
+use core::borrow::Borrow;
+use core::hash::{BuildHasher, Hash};
+use core::sync::atomic::{AtomicU64, Ordering};
+use std::collections::hash_map::RandomState;
+use std::sync::{
+    Mutex, MutexGuard, OnceLock, PoisonError, RwLock, RwLockReadGuard, RwLockWriteGuard,
+};
+
+type Shard<'brand, K, V, S> = CachePadded<RwLock<BrandedHashMap<'brand, K, V, S>>>;
+
+/// Copies a shard's entries. Stored as a function pointer so that writers, which do not
+/// require `K: Clone` and `V: Clone`, can save shards for a snapshot that does.
+type CopyShard<'brand, K, V, S> =
+    fn(&BrandedHashMap<'brand, K, V, S>, &GhostToken<'brand>) -> Vec<(K, V)>;
+
+/// A shard's entries as of the start of snapshot epoch `epoch`, saved by the first
+/// writer to change the shard during that epoch.
+struct Saved<K, V> {
+    /// Epoch the shard was saved or copied for; `0` before any snapshot.
+    epoch: u64,
+    /// `None` once the snapshot has taken the entries, or if it copied the live shard.
+    entries: Option<Vec<(K, V)>>,
+}
+
+/// A hash map that can be read and written from many threads at once.
+pub struct ShardedBrandedHashMap<'brand, K, V, S = RandomState> {
+    token: GhostToken<'brand>,
+    shards: Box<[Shard<'brand, K, V, S>]>,
+    hash_builder: S,
+    /// Epoch of the running snapshot, or `0` if none is running.
+    snapshot_epoch: AtomicU64,
+    /// Serializes snapshots and holds the last epoch handed out.
+    snapshots: Mutex<u64>,
+    /// Per shard, the entries saved for the running snapshot.
+    saved: Box<[Mutex<Saved<K, V>>]>,
+    /// Set by the first snapshot, where `K: Clone` and `V: Clone` are known.
+    copy_shard: OnceLock<CopyShard<'brand, K, V, S>>,
+}
+
+impl<'brand, K, V> ShardedBrandedHashMap<'brand, K, V, RandomState>
+where
+    K: Eq + Hash,
+{
+    /// Creates an empty map owning `token`.
+    pub fn new(token: GhostToken<'brand>) -> Self {
+        Self::with_hasher(token, RandomState::new())
+    }
+}
+
+impl<'brand, K, V, S> ShardedBrandedHashMap<'brand, K, V, S>
+where
+    K: Eq + Hash,
+    S: BuildHasher + Clone,
+{
+    /// Creates an empty map owning `token` and hashing keys with `hash_builder`.
+    pub fn with_hasher(token: GhostToken<'brand>, hash_builder: S) -> Self {
+        let shards = (0..SHARD_COUNT)
+            .map(|_| {
+                CachePadded::new(RwLock::new(BrandedHashMap::with_hasher(
+                    hash_builder.clone(),
+                )))
+            })
+            .collect();
+        Self {
+            token,
+            shards,
+            hash_builder,
+            snapshot_epoch: AtomicU64::new(0),
+            snapshots: Mutex::new(0),
+            saved: (0..SHARD_COUNT)
+                .map(|_| {
+                    Mutex::new(Saved {
+                        epoch: 0,
+                        entries: None,
+                    })
+                })
+                .collect(),
+            copy_shard: OnceLock::new(),
+        }
+    }
+
+    /// Returns the number of shards.
+    pub fn shard_count(&self) -> usize {
+        self.shards.len()
+    }
+
+    #[inline]
+    fn hash<Q: ?Sized + Hash>(&self, key: &Q) -> (u64, usize) {
+        let hash = self.hash_builder.hash_one(key);
+        // The shard maps index with the low bits and tag with the top 7, so pick
+        // the shard from the middle.
+        (hash, (hash >> 32) as usize & SHARD_MASK)
+    }
+
+    fn read(&self, shard: usize) -> RwLockReadGuard<'_, BrandedHashMap<'brand, K, V, S>> {
+        self.shards[shard]
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Write-locks `shard`, first saving it for the running snapshot if there is one.
+    fn write(&self, shard: usize) -> RwLockWriteGuard<'_, BrandedHashMap<'brand, K, V, S>> {
+        let map = self.shards[shard]
+            .write()
+            .unwrap_or_else(PoisonError::into_inner);
+        if self.snapshot_epoch.load(Ordering::SeqCst) != 0 {
+            self.save_for_snapshot(shard, &map);
+        }
+        map
+    }
+
+    #[cold]
+    fn save_for_snapshot(&self, shard: usize, map: &BrandedHashMap<'brand, K, V, S>) {
+        let mut saved = lock(&self.saved[shard]);
+        // Read under the shard lock: a write that sees no epoch here happened before the
+        // snapshot started (or after it ended), and the snapshot, which copies the shard
+        // under the same lock, sees it.
+        let epoch = self.snapshot_epoch.load(Ordering::SeqCst);
+        // Otherwise, the shard is already saved, or already copied by the snapshot.
+        if epoch == 0 || saved.epoch == epoch {
+            return;
+        }
+        let copy = self.copy_shard.get().expect("set before a snapshot epoch opens");
+        *saved = Saved {
+            epoch,
+            entries: Some(copy(map, &self.token)),
+        };
+    }
+
+    /// Returns the number of entries.
+    ///
+    /// Shards are counted one at a time, so concurrent writers can make the result
+    /// stale by the time it is returned.
+    pub fn len(&self) -> usize {
+        (0..self.shards.len()).map(|i| self.read(i).len()).sum()
+    }
+
+    /// Returns `true` if no shard holds an entry.
+    pub fn is_empty(&self) -> bool {
+        (0..self.shards.len()).all(|i| self.read(i).is_empty())
+    }
+
+    /// Inserts a key-value pair, returning the previous value for `key`.
+    pub fn insert(&self, key: K, value: V) -> Option<V> {
+        let (hash, shard) = self.hash(&key);
+        let mut map = self.write(shard);
+        match map.raw_entry_mut().from_key_hashed_nocheck(hash, &key) {
+            RawEntryMut::Occupied(mut entry) => Some(entry.insert(value)),
+            RawEntryMut::Vacant(entry) => {
+                entry.insert_hashed_nocheck(hash, key, value);
+                None
+            }
+        }
+    }
+
+    /// Removes `key`, returning its value.
+    pub fn remove<Q: ?Sized + Hash + Eq>(&self, key: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+    {
+        let (hash, shard) = self.hash(key);
+        let mut map = self.write(shard);
+        match map.raw_entry_mut().from_key_hashed_nocheck(hash, key) {
+            RawEntryMut::Occupied(entry) => Some(entry.remove()),
+            RawEntryMut::Vacant(_) => None,
+        }
+    }
+
+    /// Returns `true` if the map contains `key`.
+    pub fn contains_key<Q: ?Sized + Hash + Eq>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+    {
+        let (_, shard) = self.hash(key);
+        self.read(shard).contains_key(key)
+    }
+
+    /// Returns a clone of the value for `key`.
+    pub fn get<Q: ?Sized + Hash + Eq>(&self, key: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        V: Clone,
+    {
+        self.with(key, V::clone)
+    }
+
+    /// Calls `f` on the value for `key` while its shard is read-locked.
+    pub fn with<Q: ?Sized + Hash + Eq, R>(&self, key: &Q, f: impl FnOnce(&V) -> R) -> Option<R>
+    where
+        K: Borrow<Q>,
+    {
+        let (_, shard) = self.hash(key);
+        self.read(shard).get(&self.token, key).map(f)
+    }
+
+    /// Calls `f` on the value for `key` while its shard is write-locked.
+    pub fn update<Q: ?Sized + Hash + Eq, R>(
+        &self,
+        key: &Q,
+        f: impl FnOnce(&mut V) -> R,
+    ) -> Option<R>
+    where
+        K: Borrow<Q>,
+    {
+        let (hash, shard) = self.hash(key);
+        let mut map = self.write(shard);
+        match map.raw_entry_mut().from_key_hashed_nocheck(hash, key) {
+            RawEntryMut::Occupied(mut entry) => Some(f(entry.get_mut())),
+            RawEntryMut::Vacant(_) => None,
+        }
+    }
+
+    /// Calls `f` on the value for `key`, first inserting `default()` if the key is
+    /// absent. The lookup, insertion and update happen under one lock acquisition.
+    pub fn upsert<R>(&self, key: K, default: impl FnOnce() -> V, f: impl FnOnce(&mut V) -> R) -> R {
+        let (hash, shard) = self.hash(&key);
+        let mut map = self.write(shard);
+        let (_, value) = map
+            .raw_entry_mut()
+            .from_key_hashed_nocheck(hash, &key)
+            .or_insert_with(hash, || (key, default()));
+        f(value)
+    }
+
+    /// Calls `f` with the raw entry for `key` and its hash, holding the write lock
+    /// of its shard.
+    ///
+    /// Every operation on keys of that shard waits until `f` returns, which lets
+    /// callers pair a map update with another side effect (such as a log append)
+    /// atomically per key. A vacant entry must only be filled with `key` under the
+    /// given hash.
+    pub fn raw_entry_with<Q: ?Sized + Hash + Eq, R>(
+        &self,
+        key: &Q,
+        f: impl FnOnce(RawEntryMut<'_, 'brand, K, V, S>, u64) -> R,
+    ) -> R
+    where
+        K: Borrow<Q>,
+    {
+        let (hash, shard) = self.hash(key);
+        let mut map = self.write(shard);
+        f(map.raw_entry_mut().from_key_hashed_nocheck(hash, key), hash)
+    }
+
+    /// Calls `f` on every entry, read-locking one shard at a time.
+    ///
+    /// Entries are visited shard by shard, so concurrent writers to shards not yet
+    /// visited can still change what is seen.
+    pub fn for_each(&self, mut f: impl FnMut(&K, &V)) {
+        for i in 0..self.shards.len() {
+            let map = self.read(i);
+            for key in map.keys() {
+                if let Some(value) = map.get(&self.token, key) {
+                    f(key, value);
+                }
+            }
+        }
+    }
+
+    /// Returns an iterator over every entry as of this call, for scrapes and backups
+    /// taken while writers keep running.
+    ///
+    /// Unlike [`for_each`](Self::for_each), which can observe writes to shards it has
+    /// not reached yet, the snapshot reflects a single instant: the start of its
+    /// snapshot epoch. It copies one shard at a time as it is iterated, holding only
+    /// that shard's read lock; writers to shards it has not reached save those shards
+    /// for it first (see the [module docs](self)). Snapshots of the same map run one at
+    /// a time, and the epoch ends when the snapshot is dropped.
+    pub fn snapshot(&self) -> ShardedMapSnapshot<'_, 'brand, K, V, S>
+    where
+        K: Clone,
+        V: Clone,
+    {
+        let copy = *self.copy_shard.get_or_init(|| copy_entries::<K, V, S>);
+        let mut last = lock(&self.snapshots);
+        *last += 1;
+        let epoch = *last;
+        self.snapshot_epoch.store(epoch, Ordering::SeqCst);
+        ShardedMapSnapshot {
+            map: self,
+            epoch,
+            copy,
+            next_shard: 0,
+            entries: Vec::new().into_iter(),
+            _serial: last,
+        }
+    }
+
+    /// Removes every entry, one shard at a time.
+    pub fn clear(&self) {
+        for i in 0..self.shards.len() {
+            self.write(i).clear();
+        }
+    }
+
+    /// Consumes the map, returning the token and every entry.
+    pub fn into_parts(self) -> (GhostToken<'brand>, Vec<(K, V)>) {
+        let entries = self
+            .shards
+            .into_vec()
+            .into_iter()
+            .flat_map(|shard| {
+                shard
+                    .into_inner()
+                    .into_inner()
+                    .unwrap_or_else(PoisonError::into_inner)
+            })
+            .collect();
+        (self.token, entries)
+    }
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+fn copy_entries<'brand, K: Clone + Eq + Hash, V: Clone, S: BuildHasher>(
+    map: &BrandedHashMap<'brand, K, V, S>,
+    token: &GhostToken<'brand>,
+) -> Vec<(K, V)> {
+    map.keys()
+        .zip(map.values(token))
+        .map(|(key, value)| (key.clone(), value.clone()))
+        .collect()
+}
+
+/// A point-in-time view of a [`ShardedBrandedHashMap`], iterated in shard order.
+///
+/// Returned by [`ShardedBrandedHashMap::snapshot`]; its snapshot epoch lasts until it is
+/// dropped.
+pub struct ShardedMapSnapshot<'a, 'brand, K, V, S = RandomState> {
+    map: &'a ShardedBrandedHashMap<'brand, K, V, S>,
+    epoch: u64,
+    copy: CopyShard<'brand, K, V, S>,
+    next_shard: usize,
+    /// Entries of the shard before `next_shard`, not yet yielded.
+    entries: std::vec::IntoIter<(K, V)>,
+    _serial: MutexGuard<'a, u64>,
+}
+
+impl<K, V, S> ShardedMapSnapshot<'_, '_, K, V, S> {
+    /// Returns the entries of the next shard as of the start of the epoch.
+    fn take_shard(&mut self) -> Vec<(K, V)> {
+        let shard = self.next_shard;
+        self.next_shard += 1;
+        let map = self.map.shards[shard]
+            .read()
+            .unwrap_or_else(PoisonError::into_inner);
+        let mut saved = lock(&self.map.saved[shard]);
+        if saved.epoch == self.epoch {
+            return saved.entries.take().unwrap_or_default();
+        }
+        // No writer has changed the shard in this epoch, and none can now save it.
+        saved.epoch = self.epoch;
+        (self.copy)(&map, &self.map.token)
+    }
+}
+
+impl<K, V, S> Iterator for ShardedMapSnapshot<'_, '_, K, V, S> {
+    type Item = (K, V);
+
+    fn next(&mut self) -> Option<(K, V)> {
+        loop {
+            if let Some(entry) = self.entries.next() {
+                return Some(entry);
+            }
+            if self.next_shard == self.map.shards.len() {
+                return None;
+            }
+            self.entries = self.take_shard().into_iter();
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let (low, _) = self.entries.size_hint();
+        if self.next_shard == self.map.shards.len() {
+            (low, Some(low))
+        } else {
+            (low, None)
+        }
+    }
+}
+
+impl<K, V, S> core::iter::FusedIterator for ShardedMapSnapshot<'_, '_, K, V, S> {}
+
+impl<K, V, S> Drop for ShardedMapSnapshot<'_, '_, K, V, S> {
+    fn drop(&mut self) {
+        // Close the epoch first, so that no writer saves a shard after it is released.
+        self.map.snapshot_epoch.store(0, Ordering::SeqCst);
+        for saved in &self.map.saved[self.next_shard..] {
+            let mut saved = lock(saved);
+            if saved.epoch == self.epoch {
+                saved.entries = None;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sharded_map_basic() {
+        GhostToken::new(|token| {
+            let map = ShardedBrandedHashMap::new(token);
+            assert!(map.is_empty());
+            assert_eq!(map.shard_count(), SHARD_COUNT);
+
+            assert_eq!(map.insert("a".to_string(), 1), None);
+            assert_eq!(map.insert("a".to_string(), 2), Some(1));
+            assert_eq!(map.get("a"), Some(2));
+            assert_eq!(map.update("a", |v| std::mem::replace(v, 5)), Some(2));
+            assert_eq!(map.with("a", |v| v * 10), Some(50));
+            assert_eq!(map.update("b", |v| *v += 1), None);
+            assert_eq!(
+                map.upsert(
+                    "b".to_string(),
+                    || 0,
+                    |v| {
+                        *v += 1;
+                        *v
+                    }
+                ),
+                1
+            );
+            assert_eq!(map.len(), 2);
+
+            map.raw_entry_with("c", |entry, hash| {
+                entry.or_insert(hash, "c".to_string(), 3);
+            });
+            let mut seen = Vec::new();
+            map.for_each(|k, v| seen.push((k.clone(), *v)));
+            seen.sort();
+            assert_eq!(
+                seen,
+                [
+                    ("a".to_string(), 5),
+                    ("b".to_string(), 1),
+                    ("c".to_string(), 3)
+                ]
+            );
+
+            assert_eq!(map.remove("a"), Some(5));
+            assert!(!map.contains_key("a"));
+            map.clear();
+            assert!(map.is_empty());
+        });
+    }
+
+    #[test]
+    fn test_sharded_map_concurrent_inserts() {
+        GhostToken::new(|token| {
+            let map = ShardedBrandedHashMap::new(token);
+            std::thread::scope(|s| {
+                for t in 0..8usize {
+                    let map = &map;
+                    s.spawn(move || {
+                        for i in 0..1000 {
+                            map.insert(t * 1000 + i, i);
+                            // Every thread also bumps a shared set of counters.
+                            map.upsert(usize::MAX - i % 10, || 0, |v| *v += 1);
+                        }
+                    });
+                }
+            });
+            assert_eq!(map.len(), 8 * 1000 + 10);
+            assert!((0..10).all(|i| map.get(&(usize::MAX - i)) == Some(800)));
+
+            let (_token, entries) = map.into_parts();
+            assert_eq!(entries.len(), 8010);
+        });
+    }
+
+    #[test]
+    fn test_sharded_map_snapshot_while_writers_run() {
+        use std::sync::atomic::{AtomicBool, Ordering};
+
+        // Each writer bumps its own keys round after round, always in the same order,
+        // so at any instant its counters read `r + 1, ..., r + 1, r, ..., r`. The keys
+        // spread over shards; a view mixing two instants breaks that shape.
+        const WRITERS: usize = 4;
+        const KEYS: usize = 16;
+        GhostToken::new(|token| {
+            let map = ShardedBrandedHashMap::new(token);
+            for key in 0..WRITERS * KEYS {
+                map.insert(key, 0u64);
+            }
+            let stop = AtomicBool::new(false);
+            std::thread::scope(|s| {
+                for t in 0..WRITERS {
+                    let (map, stop) = (&map, &stop);
+                    s.spawn(move || {
+                        while !stop.load(Ordering::Relaxed) {
+                            for key in t * KEYS..(t + 1) * KEYS {
+                                map.update(&key, |v| *v += 1);
+                            }
+                        }
+                    });
+                }
+                // Stops the writers even if an assertion fails, so the scope can join them.
+                struct StopOnDrop<'a>(&'a AtomicBool);
+                impl Drop for StopOnDrop<'_> {
+                    fn drop(&mut self) {
+                        self.0.store(true, Ordering::Relaxed);
+                    }
+                }
+                let _stop = StopOnDrop(&stop);
+
+                for _ in 0..200 {
+                    let mut values = vec![None; WRITERS * KEYS];
+                    for (key, value) in map.snapshot() {
+                        assert!(values[key].replace(value).is_none());
+                    }
+                    for counters in values.chunks(KEYS) {
+                        let first = counters[0].unwrap();
+                        for pair in counters.windows(2) {
+                            assert!(pair[0] >= pair[1], "torn snapshot: {counters:?}");
+                        }
+                        assert!(first - counters[KEYS - 1].unwrap() <= 1);
+                    }
+                }
+            });
+        });
+    }
+
+    #[test]
+    fn test_sharded_map_snapshot_ignores_writes_after_it_starts() {
+        GhostToken::new(|token| {
+            let map = ShardedBrandedHashMap::new(token);
+            for key in 0..200u32 {
+                map.insert(key, key * 10);
+            }
+            let mut snapshot = map.snapshot();
+            let first = snapshot.next();
+
+            // Writers are not blocked by the open snapshot, on any shard.
+            for key in 0..200 {
+                map.update(&key, |v| *v += 1);
+            }
+            map.insert(1000, 1);
+            assert_eq!(map.remove(&5), Some(51));
+            map.clear();
+            map.insert(7, 7);
+
+            let mut seen: Vec<(u32, u32)> = first.into_iter().chain(snapshot).collect();
+            seen.sort_unstable();
+            assert!(seen.iter().copied().eq((0..200).map(|key| (key, key * 10))));
+
+            // The next snapshot starts from the current contents.
+            assert_eq!(map.snapshot().collect::<Vec<_>>(), [(7, 7)]);
+            map.insert(8, 8);
+            assert_eq!(map.snapshot().count(), 2);
+        });
+    }
+}
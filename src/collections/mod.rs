@@ -8,35 +8,56 @@
 
 pub mod btree;
 pub mod hash;
+#[cfg(feature = "std")]
 pub mod other;
+#[cfg(feature = "std")]
 pub mod path;
+#[cfg(feature = "serde")]
+pub mod serde;
+#[cfg(feature = "std")]
 pub mod skip_list;
+#[cfg(feature = "std")]
 pub mod string;
 pub mod trie;
 pub mod vec;
 
 // Re-export commonly used types from submodules
-pub use btree::{BrandedBTreeMap, BrandedBTreeSet};
+pub use btree::{BrandedBTreeMap, BrandedBTreeSet, BrandedIntervalTree};
 pub use hash::{
-    ActivateHashMap, ActivateHashSet, ActiveHashMap, ActiveHashSet, BrandedHashMap, BrandedHashSet,
-    BrandedIndexMap,
+    ActivateHashMap, ActivateHashSet, ActiveHashMap, ActiveHashSet, BrandedBiMap, BrandedHamtMap,
+    BrandedHashMap, BrandedHashSet, BrandedIndexMap,
 };
+#[cfg(feature = "std")]
+pub use hash::{ShardedBrandedHashMap, ShardedMapSnapshot};
+#[cfg(feature = "std")]
 pub use other::{
-    ActiveDisjointSet, BrandedBinaryHeap, BrandedChain, BrandedCow, BrandedCowStrings,
-    BrandedDeque, BrandedDisjointSet, BrandedDoublyLinkedList, BrandedInterner, BrandedIntervalMap,
-    BrandedLruCache, BrandedSegmentTree, BrandedSegmentTreeViewMut, BrandedSlotMap, InternId,
-    SlotKey, TripodList,
+    ActiveDisjointSet, BrandedAliasTable, BrandedBinaryHeap, BrandedBloomFilter, BrandedChain,
+    BrandedCow, BrandedCowStrings, BrandedCuckooFilter, BrandedDeque, BrandedDisjointSet,
+    BrandedDoublyLinkedList, BrandedIndexedHeap, BrandedInterner, BrandedIntervalMap,
+    BrandedLazySegmentTree, BrandedLruCache, BrandedRingBuffer, BrandedSecondaryMap,
+    BrandedSegmentTree, BrandedSegmentTreeViewMut, BrandedSlotMap, BrandedTtlCache,
+    BrandedUnionFind, BrandedWeightedSampler, ConcurrentBrandedBloomFilter,
+    ConcurrentBrandedUnionFind, InternId, SlotKey, TripodList,
 };
+#[cfg(feature = "std")]
 pub use path::{BrandedOsString, BrandedPathBuf};
+#[cfg(feature = "std")]
 pub use skip_list::{ActivateSkipList, ActiveSkipList, BrandedSkipList};
-pub use trie::{BrandedRadixTrieMap, BrandedRadixTrieSet};
+pub use trie::{BrandedArtMap, BrandedRadixTrieMap, BrandedRadixTrieSet};
 pub use vec::{
-    ActivateVec, ActiveVec, BrandedArray, BrandedChunkedVec, BrandedMatrix, BrandedMatrixViewMut,
-    BrandedSlice, BrandedSliceMut, BrandedSmallVec, BrandedVec, BrandedVecDeque, ChunkedVec,
+    ActivateVec, ActiveVec, AppendVecSnapshot, BrandedArray, BrandedChunkedVec, BrandedFrontier,
+    BrandedMatrix, BrandedMatrixViewMut, BrandedPackedIntVec, BrandedSlice, BrandedSliceMut,
+    BrandedSmallVec, BrandedVec, BrandedVecDeque, BrandedVecMap, BrandedVecView, ChunkedVec,
+    ConcurrentBrandedAppendVec, StableHandle,
 };
 
+#[cfg(feature = "std")]
 pub use crate::alloc::BrandedArena;
-pub use string::{ActivateString, ActiveString, BrandedString};
+#[cfg(feature = "std")]
+pub use string::{
+    ActivateString, ActiveString, BrandedAhoCorasick, BrandedRope, BrandedString,
+    BrandedSuffixArray, RopeBuilder, RopeSlice,
+};
 
 // Re-export for trait definitions
 pub use crate::GhostToken;
@@ -92,3 +113,46 @@ pub trait ZeroCopyMapOps<'brand, K, V> {
         F: Fn(&K, &V) -> bool,
         Token: crate::token::traits::GhostBorrow<'brand>;
 }
+
+/// Panics if a collection's `validate_invariants` reports a violation.
+///
+/// Expands to nothing observable unless `debug_assertions` are enabled, so it can be
+/// sprinkled after suspicious mutations without costing anything in release builds.
+/// Pass the token as a second argument for collections whose check reads branded
+/// storage.
+///
+/// ```rust
+/// use halo::{debug_assert_valid, GhostToken};
+/// use halo::collections::BrandedBTreeMap;
+///
+/// GhostToken::new(|token| {
+///     let mut map = BrandedBTreeMap::new();
+///     map.insert(1, "one");
+///     debug_assert_valid!(map, &token);
+/// });
+/// ```
+#[macro_export]
+macro_rules! debug_assert_valid {
+    ($collection:expr $(,)?) => {
+        if cfg!(debug_assertions) {
+            if let Err(violation) = $collection.validate_invariants() {
+                panic!(
+                    "invariant violated in `{}`: {}",
+                    stringify!($collection),
+                    violation
+                );
+            }
+        }
+    };
+    ($collection:expr, $token:expr $(,)?) => {
+        if cfg!(debug_assertions) {
+            if let Err(violation) = $collection.validate_invariants($token) {
+                panic!(
+                    "invariant violated in `{}`: {}",
+                    stringify!($collection),
+                    violation
+                );
+            }
+        }
+    };
+}
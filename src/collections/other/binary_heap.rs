@@ -149,11 +149,40 @@ impl<'brand, T: Ord> BrandedBinaryHeap<'brand, T> {
         self.data.get(token, 0)
     }
 
+    /// Returns a guard granting mutable access to the greatest item.
+    ///
+    /// The heap is restored when the guard is dropped, so the item may be modified
+    /// freely (e.g. decreasing a priority).
+    pub fn peek_mut<'a, Token>(
+        &'a mut self,
+        token: &'a mut Token,
+    ) -> Option<PeekMut<'a, 'brand, T, Token>>
+    where
+        Token: GhostBorrowMut<'brand>,
+    {
+        if self.data.is_empty() {
+            None
+        } else {
+            Some(PeekMut { heap: self, token })
+        }
+    }
+
     /// Clears the binary heap.
     pub fn clear(&mut self) {
         self.data.clear();
     }
 
+    /// Consumes the heap and returns its elements in ascending order.
+    pub fn into_sorted_vec(mut self) -> Vec<T> {
+        let mut end = self.data.len();
+        while end > 1 {
+            end -= 1;
+            self.data.as_mut_slice_exclusive().swap(0, end);
+            self.sift_down_range(0, end);
+        }
+        self.data.into_iter().collect()
+    }
+
     fn sift_up(&mut self, node: usize) {
         let slice = self.data.as_mut_slice_exclusive();
         unsafe {
@@ -171,8 +200,13 @@ impl<'brand, T: Ord> BrandedBinaryHeap<'brand, T> {
     }
 
     fn sift_down(&mut self, node: usize) {
-        let slice = self.data.as_mut_slice_exclusive();
-        let len = slice.len();
+        let len = self.data.len();
+        self.sift_down_range(node, len);
+    }
+
+    /// Sifts `node` down within the first `len` elements.
+    fn sift_down_range(&mut self, node: usize, len: usize) {
+        let slice = &mut self.data.as_mut_slice_exclusive()[..len];
         unsafe {
             let mut hole = Hole::new(slice, node);
             let mut hole_pos = hole.pos();
@@ -200,6 +234,58 @@ impl<'brand, T: Ord> BrandedBinaryHeap<'brand, T> {
     }
 }
 
+/// Mutable access to the greatest item of a [`BrandedBinaryHeap`].
+///
+/// Created by [`BrandedBinaryHeap::peek_mut`]; restores the heap property on drop.
+pub struct PeekMut<'a, 'brand, T: Ord, Token: GhostBorrowMut<'brand>> {
+    heap: &'a mut BrandedBinaryHeap<'brand, T>,
+    token: &'a mut Token,
+}
+
+impl<'a, 'brand, T: Ord, Token: GhostBorrowMut<'brand>> PeekMut<'a, 'brand, T, Token> {
+    /// Removes the peeked item from the heap and returns it.
+    pub fn pop(this: Self) -> T {
+        let mut this = ManuallyDrop::new(this);
+        let heap = &mut *this.heap;
+        let last_idx = heap.data.len() - 1;
+        heap.data.as_mut_slice_exclusive().swap(0, last_idx);
+        let Some(item) = heap.data.pop() else {
+            unreachable!("PeekMut on empty heap");
+        };
+        let item = item.into_inner();
+        if !heap.data.is_empty() {
+            heap.sift_down(0);
+        }
+        item
+    }
+}
+
+impl<'a, 'brand, T: Ord, Token: GhostBorrowMut<'brand>> core::ops::Deref
+    for PeekMut<'a, 'brand, T, Token>
+{
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        // SAFETY: a `PeekMut` is only created for a non-empty heap.
+        unsafe { self.heap.data.get_unchecked(&*self.token, 0) }
+    }
+}
+
+impl<'a, 'brand, T: Ord, Token: GhostBorrowMut<'brand>> core::ops::DerefMut
+    for PeekMut<'a, 'brand, T, Token>
+{
+    fn deref_mut(&mut self) -> &mut T {
+        // SAFETY: a `PeekMut` is only created for a non-empty heap.
+        unsafe { self.heap.data.get_unchecked_mut_exclusive(0) }
+    }
+}
+
+impl<'a, 'brand, T: Ord, Token: GhostBorrowMut<'brand>> Drop for PeekMut<'a, 'brand, T, Token> {
+    fn drop(&mut self) {
+        self.heap.sift_down(0);
+    }
+}
+
 impl<'brand, T> BrandedBinaryHeap<'brand, T> {
     /// Iterates over all elements in the heap in arbitrary order.
     pub fn iter<'a, Token>(&'a self, token: &'a Token) -> impl Iterator<Item = &'a T> + use<'a, 'brand, T, Token>
@@ -314,4 +400,25 @@ mod tests {
             assert!(heap.all_ref(&token, |&x| x > 0));
         });
     }
+
+    #[test]
+    fn test_peek_mut_and_into_sorted_vec() {
+        GhostToken::new(|mut token| {
+            let mut heap = BrandedBinaryHeap::new();
+            for x in [4, 9, 1, 7, 3] {
+                heap.push(&mut token, x);
+            }
+
+            // Lowering the top re-sifts it when the guard drops.
+            *heap.peek_mut(&mut token).unwrap() = 2;
+            assert_eq!(heap.peek(&token), Some(&7));
+
+            let top = heap.peek_mut(&mut token).unwrap();
+            assert_eq!(PeekMut::pop(top), 7);
+            assert_eq!(heap.len(), 4);
+
+            assert_eq!(heap.into_sorted_vec(), vec![1, 2, 3, 4]);
+            assert!(BrandedBinaryHeap::<i32>::new().into_sorted_vec().is_empty());
+        });
+    }
 }
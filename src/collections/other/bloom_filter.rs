@@ -1,14 +1,68 @@
 //! `BrandedBloomFilter` — a probabilistic data structure with token-gated access.
 //!
-//! Uses `BrandedBitSet` to store bits. Supports `insert` and `contains`.
-//! Uses double hashing to simulate `k` hash functions.
+//! Uses `BrandedBitSet` to store bits. Supports `insert`, `contains` and merging
+//! filters with `union`. Uses double hashing to simulate `k` hash functions.
+//!
+//! [`ConcurrentBrandedBloomFilter`] is the same filter over a `GhostAtomicBitset`, so
+//! it can be filled and queried from many threads through `&self` without a token.
 
 use crate::collections::other::bit_set::BrandedBitSet;
+use crate::concurrency::atomic::GhostAtomicBitset;
 use crate::token::traits::{GhostBorrow, GhostBorrowMut};
-use core::hash::{BuildHasher, Hash, Hasher};
+use core::hash::{BuildHasher, Hash};
+use core::sync::atomic::Ordering;
 use std::collections::hash_map::RandomState;
 use std::marker::PhantomData;
 
+/// Returns the bit array size `m` and hash count `k` for `expected_items` at `fp_rate`.
+// Both results are at least 1 and float-to-int `as` saturates, so the casts can
+// only clamp an absurd size; `n` is an estimate, so losing its low bits is fine.
+#[allow(
+    clippy::cast_precision_loss,
+    clippy::cast_possible_truncation,
+    clippy::cast_sign_loss
+)]
+fn optimal_params(expected_items: usize, fp_rate: f64) -> (usize, u32) {
+    // m = - (n * ln p) / (ln 2)^2
+    let n = expected_items.max(1) as f64;
+    let ln2 = std::f64::consts::LN_2;
+    let m = (-(n * fp_rate.ln()) / (ln2 * ln2)).max(1.0);
+    let bit_size = m.ceil() as usize;
+
+    // k = (m / n) * ln 2
+    let k = (m / n) * ln2;
+    let num_hashes = k.ceil().max(1.0) as u32;
+    (bit_size, num_hashes)
+}
+
+/// Computes the two base hashes of `item`.
+fn hash_pair<T: Hash + ?Sized, S: BuildHasher>(hasher: &S, item: &T) -> (u64, u64) {
+    let h1 = hasher.hash_one(item);
+
+    // Use a mixing strategy to generate a second hash h2 from h1.
+    // This avoids traversing the item a second time.
+    // The mixing constants are from MurmurHash3's 64-bit finalizer.
+    let mut h2 = h1;
+    h2 = (h2 ^ (h2 >> 33)).wrapping_mul(0xff51_afd7_ed55_8ccd);
+    h2 = (h2 ^ (h2 >> 33)).wrapping_mul(0xc4ce_b9fe_1a85_ec53);
+    h2 = h2 ^ (h2 >> 33);
+
+    (h1, h2)
+}
+
+/// The `num_hashes` bit positions derived from `(h1, h2)` in a filter of `bit_size` bits.
+fn bit_indices(
+    (h1, h2): (u64, u64),
+    num_hashes: u32,
+    bit_size: usize,
+) -> impl Iterator<Item = usize> {
+    let m = bit_size as u64;
+    (0..num_hashes).map(move |i| {
+        let idx = h1.wrapping_add(u64::from(i).wrapping_mul(h2)) % m;
+        usize::try_from(idx).expect("a bit index is below `bit_size`")
+    })
+}
+
 /// A branded Bloom filter.
 pub struct BrandedBloomFilter<'brand, T, S = RandomState> {
     bits: BrandedBitSet<'brand>,
@@ -39,16 +93,7 @@ impl<'brand, T, S> BrandedBloomFilter<'brand, T, S> {
         fp_rate: f64,
         hasher: S,
     ) -> Self {
-        // m = - (n * ln p) / (ln 2)^2
-        let n = expected_items as f64;
-        let ln2 = std::f64::consts::LN_2;
-        let m = -1.0 * (n * fp_rate.ln()) / (ln2 * ln2);
-        let bit_size = m.ceil() as usize;
-
-        // k = (m / n) * ln 2
-        let k = (m / n) * ln2;
-        let num_hashes = k.ceil() as u32;
-
+        let (bit_size, num_hashes) = optimal_params(expected_items, fp_rate);
         Self {
             bits: BrandedBitSet::with_capacity(bit_size),
             num_hashes,
@@ -67,6 +112,53 @@ impl<'brand, T, S> BrandedBloomFilter<'brand, T, S> {
     pub fn set_bits_count(&self) -> usize {
         self.bits.len()
     }
+
+    /// Returns the size of the bit array.
+    pub fn bit_size(&self) -> usize {
+        self.bit_size
+    }
+
+    /// Returns the number of hash functions.
+    pub fn num_hashes(&self) -> u32 {
+        self.num_hashes
+    }
+
+    /// Creates an empty filter with the same size, hash count and hasher as `self`,
+    /// so that the two can later be merged with [`union`](Self::union).
+    #[must_use]
+    pub fn empty_like(&self) -> Self
+    where
+        S: Clone,
+    {
+        Self {
+            bits: BrandedBitSet::with_capacity(self.bit_size),
+            num_hashes: self.num_hashes,
+            bit_size: self.bit_size,
+            hasher: self.hasher.clone(),
+            _marker: PhantomData,
+        }
+    }
+
+    /// Merges `other` into `self`, so that `self` then reports every item inserted
+    /// into either filter.
+    ///
+    /// Both filters must hash identically: create one with
+    /// [`empty_like`](Self::empty_like) from the other, or give both a clone of the
+    /// same hasher. Filters using separately created `RandomState`s cannot be merged
+    /// meaningfully.
+    ///
+    /// # Panics
+    /// Panics if the filters differ in bit size or hash count.
+    pub fn union<Token>(&mut self, token: &mut Token, other: &Self)
+    where
+        Token: GhostBorrowMut<'brand>,
+    {
+        assert!(
+            self.bit_size == other.bit_size && self.num_hashes == other.num_hashes,
+            "BrandedBloomFilter::union requires filters with the same parameters"
+        );
+        self.bits.union_with(token, &other.bits);
+    }
 }
 
 impl<'brand, T, S> BrandedBloomFilter<'brand, T, S>
@@ -74,34 +166,14 @@ where
     T: Hash,
     S: BuildHasher,
 {
-    /// Helper to compute two hashes.
-    fn get_hashes(&self, item: &T) -> (u64, u64) {
-        let mut hasher = self.hasher.build_hasher();
-        item.hash(&mut hasher);
-        let h1 = hasher.finish();
-
-        // Use a mixing strategy to generate a second hash h2 from h1.
-        // This avoids traversing the item a second time.
-        // The mixing constants are from MurmurHash3's 64-bit finalizer.
-        let mut h2 = h1;
-        h2 = (h2 ^ (h2 >> 33)).wrapping_mul(0xff51_afd7_ed55_8ccd);
-        h2 = (h2 ^ (h2 >> 33)).wrapping_mul(0xc4ce_b9fe_1a85_ec53);
-        h2 = h2 ^ (h2 >> 33);
-
-        (h1, h2)
-    }
-
     /// Adds an item to the Bloom filter.
     pub fn insert<Token>(&mut self, token: &mut Token, item: &T)
     where
         Token: GhostBorrowMut<'brand>,
     {
-        let (h1, h2) = self.get_hashes(item);
-        let m = self.bit_size as u64;
-
-        for i in 0..self.num_hashes {
-            let idx = (h1.wrapping_add((i as u64).wrapping_mul(h2))) % m;
-            self.bits.insert(token, idx as usize);
+        let hashes = hash_pair(&self.hasher, item);
+        for idx in bit_indices(hashes, self.num_hashes, self.bit_size) {
+            self.bits.insert(token, idx);
         }
     }
 
@@ -110,18 +182,10 @@ where
     where
         Token: GhostBorrow<'brand>,
     {
-        let (h1, h2) = self.get_hashes(item);
-        let m = self.bit_size as u64;
-
-        for i in 0..self.num_hashes {
-            let idx = (h1.wrapping_add((i as u64).wrapping_mul(h2))) % m;
-            if !self.bits.contains(token, idx as usize) {
-                return false;
-            }
-        }
-        true
+        let hashes = hash_pair(&self.hasher, item);
+        bit_indices(hashes, self.num_hashes, self.bit_size)
+            .all(|idx| self.bits.contains(token, idx))
     }
-
 }
 
 impl<'brand, T> Default for BrandedBloomFilter<'brand, T> {
@@ -130,6 +194,80 @@ impl<'brand, T> Default for BrandedBloomFilter<'brand, T> {
     }
 }
 
+/// A Bloom filter that many threads can fill and query at once.
+///
+/// Bits live in a [`GhostAtomicBitset`], so `insert` and `contains` take `&self` and
+/// need no token; concurrent inserts never lose each other's bits.
+pub struct ConcurrentBrandedBloomFilter<'brand, T, S = RandomState> {
+    bits: GhostAtomicBitset<'brand>,
+    num_hashes: u32,
+    hasher: S,
+    _marker: PhantomData<fn(&T)>,
+}
+
+impl<T> ConcurrentBrandedBloomFilter<'_, T> {
+    /// Creates a new filter optimized for `expected_items` and `fp_rate`.
+    pub fn with_capacity_and_fp_rate(expected_items: usize, fp_rate: f64) -> Self {
+        Self::with_capacity_fp_rate_and_hasher(expected_items, fp_rate, RandomState::new())
+    }
+}
+
+impl<T, S> ConcurrentBrandedBloomFilter<'_, T, S> {
+    /// Creates a new filter with a custom hasher.
+    pub fn with_capacity_fp_rate_and_hasher(
+        expected_items: usize,
+        fp_rate: f64,
+        hasher: S,
+    ) -> Self {
+        let (bit_size, num_hashes) = optimal_params(expected_items, fp_rate);
+        Self {
+            bits: GhostAtomicBitset::new(bit_size),
+            num_hashes,
+            hasher,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Clears the filter.
+    ///
+    /// Inserts racing with the clear may or may not survive it.
+    pub fn clear(&self) {
+        self.bits.clear_all();
+    }
+
+    /// Returns the size of the bit array.
+    pub fn bit_size(&self) -> usize {
+        self.bits.len_bits()
+    }
+
+    /// Returns the number of hash functions.
+    pub fn num_hashes(&self) -> u32 {
+        self.num_hashes
+    }
+}
+
+impl<T, S> ConcurrentBrandedBloomFilter<'_, T, S>
+where
+    T: Hash,
+    S: BuildHasher,
+{
+    /// Adds an item, returning `true` if it was definitely not present before.
+    pub fn insert(&self, item: &T) -> bool {
+        let hashes = hash_pair(&self.hasher, item);
+        let mut newly_set = false;
+        for idx in bit_indices(hashes, self.num_hashes, self.bits.len_bits()) {
+            newly_set |= self.bits.test_and_set(idx, Ordering::Relaxed);
+        }
+        newly_set
+    }
+
+    /// Checks if an item is possibly in the filter.
+    pub fn contains(&self, item: &T) -> bool {
+        let hashes = hash_pair(&self.hasher, item);
+        bit_indices(hashes, self.num_hashes, self.bits.len_bits()).all(|idx| self.bits.is_set(idx))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -179,4 +317,57 @@ mod tests {
             assert!(measured_rate < 0.05, "FP rate too high: {}", measured_rate);
         });
     }
+
+    #[test]
+    fn test_bloom_union() {
+        GhostToken::new(|mut token| {
+            let mut evens = BrandedBloomFilter::with_capacity_and_fp_rate(200, 0.01);
+            let mut odds = evens.empty_like();
+            assert_eq!(odds.bit_size(), evens.bit_size());
+            for i in 0..100 {
+                evens.insert(&mut token, &(2 * i));
+                odds.insert(&mut token, &(2 * i + 1));
+            }
+            assert!(!evens.contains(&token, &1) || !evens.contains(&token, &3));
+
+            evens.union(&mut token, &odds);
+            assert!((0..200).all(|i| evens.contains(&token, &i)));
+        });
+    }
+
+    #[test]
+    fn test_concurrent_bloom_threads() {
+        let bloom = ConcurrentBrandedBloomFilter::with_capacity_and_fp_rate(4000, 0.01);
+        assert!(bloom.insert(&usize::MAX));
+        assert!(!bloom.insert(&usize::MAX));
+        std::thread::scope(|s| {
+            for t in 0..4usize {
+                let bloom = &bloom;
+                s.spawn(move || {
+                    for i in 0..1000 {
+                        bloom.insert(&(t * 1000 + i));
+                    }
+                });
+            }
+        });
+        assert!((0..4000).all(|i| bloom.contains(&i)));
+        let false_positives = (4000..14000).filter(|i| bloom.contains(i)).count();
+        assert!(
+            false_positives < 500,
+            "FP count too high: {false_positives}"
+        );
+
+        bloom.clear();
+        assert!(!bloom.contains(&0));
+    }
+
+    #[test]
+    fn test_bloom_zero_capacity() {
+        GhostToken::new(|mut token| {
+            let mut bloom = BrandedBloomFilter::with_capacity_and_fp_rate(0, 0.01);
+            assert!(bloom.bit_size() >= 1 && bloom.num_hashes() >= 1);
+            bloom.insert(&mut token, &7);
+            assert!(bloom.contains(&token, &7));
+        });
+    }
 }
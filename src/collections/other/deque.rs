@@ -62,7 +62,7 @@ where
     }
 }
 
-impl<'a, 'brand, T, const CAPACITY: usize, Token> std::iter::FusedIterator
+impl<'a, 'brand, T, const CAPACITY: usize, Token> core::iter::FusedIterator
     for BrandedDequeIter<'a, 'brand, T, CAPACITY, Token>
 where
     Token: GhostBorrow<'brand>,
@@ -290,12 +290,15 @@ impl<'brand, T, const CAPACITY: usize> BrandedDeque<'brand, T, CAPACITY> {
         }
     }
 
-    /// Iterates over the elements.
+    /// Iterates over the elements from front to back; call `.rev()` for back to front.
     #[inline]
     pub fn iter<'a, Token>(
         &'a self,
         token: &'a Token,
-    ) -> impl Iterator<Item = &'a T> + ExactSizeIterator + 'a + use<'a, 'brand, T, CAPACITY, Token>
+    ) -> impl DoubleEndedIterator<Item = &'a T>
+           + ExactSizeIterator
+           + 'a
+           + use<'a, 'brand, T, CAPACITY, Token>
     where
         Token: GhostBorrow<'brand>,
     {
@@ -306,6 +309,91 @@ impl<'brand, T, const CAPACITY: usize> BrandedDeque<'brand, T, CAPACITY> {
         }
     }
 
+    /// Swaps the elements at logical indices `i` and `j`.
+    ///
+    /// # Panics
+    /// Panics if either index is out of bounds.
+    #[inline]
+    pub fn swap(&mut self, i: usize, j: usize) {
+        assert!(
+            i < self.len && j < self.len,
+            "swap indices ({i}, {j}) out of bounds for length {}",
+            self.len
+        );
+        self.buffer
+            .swap((self.head + i) % CAPACITY, (self.head + j) % CAPACITY);
+    }
+
+    /// Rotates the deque `n` places to the left, so the element at index `n` becomes
+    /// the front.
+    ///
+    /// A full deque rotates in O(1) by moving its ends; otherwise `min(n, len - n)`
+    /// elements are moved.
+    ///
+    /// # Panics
+    /// Panics if `n > len`.
+    pub fn rotate_left(&mut self, n: usize) {
+        assert!(n <= self.len, "rotate_left by {n} exceeds length {}", self.len);
+        if n <= self.len - n {
+            self.rotate_front_to_back(n);
+        } else {
+            self.rotate_back_to_front(self.len - n);
+        }
+    }
+
+    /// Rotates the deque `n` places to the right, so the element at index
+    /// `len - n` becomes the front.
+    ///
+    /// # Panics
+    /// Panics if `n > len`.
+    pub fn rotate_right(&mut self, n: usize) {
+        assert!(n <= self.len, "rotate_right by {n} exceeds length {}", self.len);
+        if n <= self.len - n {
+            self.rotate_back_to_front(n);
+        } else {
+            self.rotate_front_to_back(self.len - n);
+        }
+    }
+
+    /// Moves `n` elements from the front to the back, preserving their order.
+    fn rotate_front_to_back(&mut self, n: usize) {
+        if self.is_full() {
+            // Head and tail coincide, so advancing both rotates in place.
+            self.head = (self.head + n) % CAPACITY;
+            self.tail = self.head;
+            return;
+        }
+        for _ in 0..n {
+            // SAFETY: `head` holds an element and `tail` is free because the deque is
+            // not full; the element is moved, not duplicated, as `head` advances.
+            unsafe {
+                let cell = self.buffer.get_unchecked(self.head).as_ptr().read();
+                self.buffer.get_unchecked_mut(self.tail).write(cell);
+            }
+            self.head = (self.head + 1) % CAPACITY;
+            self.tail = (self.tail + 1) % CAPACITY;
+        }
+    }
+
+    /// Moves `n` elements from the back to the front, preserving their order.
+    fn rotate_back_to_front(&mut self, n: usize) {
+        if self.is_full() {
+            self.head = (self.head + CAPACITY - n % CAPACITY) % CAPACITY;
+            self.tail = self.head;
+            return;
+        }
+        for _ in 0..n {
+            self.tail = (self.tail + CAPACITY - 1) % CAPACITY;
+            self.head = (self.head + CAPACITY - 1) % CAPACITY;
+            // SAFETY: the slot before `tail` holds the back element and the slot before
+            // `head` is free because the deque is not full.
+            unsafe {
+                let cell = self.buffer.get_unchecked(self.tail).as_ptr().read();
+                self.buffer.get_unchecked_mut(self.head).write(cell);
+            }
+        }
+    }
+
     /// Applies a function to all elements in the deque.
     ///
     /// This provides maximum efficiency for bulk operations by avoiding
@@ -511,4 +599,47 @@ mod tests {
             assert!(deque.all_ref(&token, |&x| x > 0));
         });
     }
+
+    fn contents<'brand>(
+        deque: &BrandedDeque<'brand, u32, 8>,
+        token: &GhostToken<'brand>,
+    ) -> Vec<u32> {
+        deque.iter(token).copied().collect()
+    }
+
+    #[test]
+    fn test_swap_rotate_and_reverse_iter() {
+        GhostToken::new(|mut token| {
+            let mut deque: BrandedDeque<'_, u32, 8> = BrandedDeque::new();
+            // Start mid-buffer so rotations cross the wrap-around point.
+            for i in 0..4 {
+                deque.push_back(i).unwrap();
+            }
+            for i in 0..4 {
+                deque.pop_front();
+                deque.push_back(i + 4).unwrap();
+            }
+            deque.push_front(3).unwrap();
+            assert_eq!(contents(&deque, &token), [3, 4, 5, 6, 7]);
+
+            deque.rotate_left(2);
+            assert_eq!(contents(&deque, &token), [5, 6, 7, 3, 4]);
+            deque.rotate_right(4);
+            assert_eq!(contents(&deque, &token), [6, 7, 3, 4, 5]);
+            deque.swap(0, 4);
+            *deque.get_mut(&mut token, 1).unwrap() += 10;
+            let reversed: Vec<u32> = deque.iter(&token).rev().copied().collect();
+            assert_eq!(reversed, [6, 4, 3, 17, 5]);
+
+            for i in 0..3 {
+                deque.push_back(i).unwrap();
+            }
+            assert!(deque.is_full());
+            deque.rotate_right(3);
+            assert_eq!(contents(&deque, &token), [0, 1, 2, 5, 17, 3, 4, 6]);
+            deque.rotate_left(8);
+            assert_eq!(*deque.front(&token).unwrap(), 0);
+            assert_eq!(*deque.back(&token).unwrap(), 6);
+        });
+    }
 }
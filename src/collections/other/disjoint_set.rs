@@ -39,6 +39,14 @@ impl<'brand> BrandedDisjointSet<'brand> {
         }
     }
 
+    /// Creates a disjoint set of `n` singleton sets, with IDs `0..n`.
+    pub fn with_singletons(n: usize) -> Self {
+        Self {
+            parent: (0..n).map(Cell::new).collect(),
+            rank: core::iter::repeat_n(0, n).collect(),
+        }
+    }
+
     /// Creates a new set containing a single element.
     /// Returns the representative ID of the new set.
     pub fn make_set<Token>(&mut self, _token: &mut Token) -> usize
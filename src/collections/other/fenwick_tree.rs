@@ -169,6 +169,33 @@ where
         }
     }
 
+    /// Returns the element at `index`.
+    ///
+    /// # Panics
+    /// Panics if `index` is out of bounds.
+    pub fn get<Token>(&self, token: &Token, index: usize) -> T
+    where
+        Token: GhostBorrow<'brand>,
+    {
+        assert!(index < self.len(), "Index out of bounds");
+        self.range_sum(token, index, index + 1)
+    }
+
+    /// Overwrites the element at `index` with `value`, returning the previous value.
+    ///
+    /// # Panics
+    /// Panics if `index` is out of bounds.
+    pub fn set<Token>(&mut self, token: &mut Token, index: usize, value: T) -> T
+    where
+        Token: GhostBorrowMut<'brand>,
+    {
+        let old = self.get(token, index);
+        let mut delta = value;
+        delta -= old;
+        self.add(token, index, delta);
+        old
+    }
+
     /// Pushes a new value to the end of the tree.
     pub fn push<Token>(&mut self, token: &mut Token, val: T)
     where
@@ -205,6 +232,38 @@ where
     }
 }
 
+impl<'brand, T> BrandedFenwickTree<'brand, T>
+where
+    T: Default + Copy + AddAssign + SubAssign + PartialOrd,
+{
+    /// Returns the smallest index whose prefix sum exceeds `target`, or `len()` if none does.
+    ///
+    /// Requires all elements to be non-negative, so that prefix sums are monotone.
+    ///
+    /// **Time complexity**: \(O(\log n)\)
+    pub fn upper_bound<Token>(&self, token: &Token, mut target: T) -> usize
+    where
+        Token: GhostBorrow<'brand>,
+    {
+        let n = self.len();
+        // `pos` counts the elements whose sum has been folded out of `target`.
+        let mut pos = 0;
+        let mut step = if n == 0 { 0 } else { 1 << n.ilog2() };
+        while step > 0 {
+            let next = pos + step;
+            if next <= n {
+                let node = *self.tree.borrow(token, next - 1);
+                if node <= target {
+                    target -= node;
+                    pos = next;
+                }
+            }
+            step >>= 1;
+        }
+        pos
+    }
+}
+
 impl<'brand, T> BrandedCollection<'brand> for BrandedFenwickTree<'brand, T> {
     fn is_empty(&self) -> bool {
         self.tree.is_empty()
@@ -312,6 +371,33 @@ mod tests {
         });
     }
 
+    #[test]
+    fn test_fenwick_tree_get_set() {
+        GhostToken::new(|mut token| {
+            let mut ft: BrandedFenwickTree<i64> = vec![5, -2, 7, 0].into_iter().collect();
+            assert_eq!(ft.get(&token, 1), -2);
+            assert_eq!(ft.set(&mut token, 1, 10), -2);
+            assert_eq!(ft.set(&mut token, 3, 4), 0);
+            assert_eq!(ft.get(&token, 1), 10);
+            assert_eq!(ft.prefix_sum(&token, 3), 26);
+        });
+    }
+
+    #[test]
+    fn test_fenwick_tree_upper_bound() {
+        GhostToken::new(|token| {
+            let ft: BrandedFenwickTree<u32> = vec![2, 0, 3, 1, 4, 0, 5].into_iter().collect();
+            // Prefix sums: 2, 2, 5, 6, 10, 10, 15.
+            assert_eq!(ft.upper_bound(&token, 0), 0);
+            assert_eq!(ft.upper_bound(&token, 1), 0);
+            assert_eq!(ft.upper_bound(&token, 2), 2);
+            assert_eq!(ft.upper_bound(&token, 9), 4);
+            assert_eq!(ft.upper_bound(&token, 10), 6);
+            assert_eq!(ft.upper_bound(&token, 15), 7);
+            assert_eq!(BrandedFenwickTree::<u32>::new().upper_bound(&token, 0), 0);
+        });
+    }
+
     #[test]
     #[should_panic(expected = "Index out of bounds")]
     fn test_fenwick_tree_oob_add() {
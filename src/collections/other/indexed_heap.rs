@@ -0,0 +1,301 @@
+//! `BrandedIndexedHeap` — a min-priority queue keyed by dense node ids.
+//!
+//! Alongside the binary heap of `(id, priority)` pairs, a position table maps every
+//! queued id to its slot in the heap. That makes
+//! [`decrease_key`](BrandedIndexedHeap::decrease_key) and
+//! [`remove`](BrandedIndexedHeap::remove) \(O(\log n)\) instead of requiring lazy
+//! deletion, which is what Dijkstra and Prim over the ghost graphs need: node indices
+//! are already dense `usize`s.
+//!
+//! Access to priorities is controlled via `GhostToken`, like
+//! [`BrandedBinaryHeap`](super::BrandedBinaryHeap).
+
+use crate::collections::vec::BrandedVec;
+use crate::token::traits::{GhostBorrow, GhostBorrowMut};
+use core::fmt;
+
+const ABSENT: usize = usize::MAX;
+
+/// A min-heap of node ids ordered by priority, with \(O(\log n)\) `decrease_key`.
+pub struct BrandedIndexedHeap<'brand, P> {
+    heap: BrandedVec<'brand, (usize, P)>,
+    /// `positions[id]` is the heap slot of `id`, or `ABSENT`.
+    positions: Vec<usize>,
+}
+
+impl<'brand, P: Ord> BrandedIndexedHeap<'brand, P> {
+    /// Creates an empty heap.
+    pub fn new() -> Self {
+        Self {
+            heap: BrandedVec::new(),
+            positions: Vec::new(),
+        }
+    }
+
+    /// Creates an empty heap with room for ids `0..capacity`.
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            heap: BrandedVec::with_capacity(capacity),
+            positions: vec![ABSENT; capacity],
+        }
+    }
+
+    /// Returns the number of queued ids.
+    pub fn len(&self) -> usize {
+        self.heap.len()
+    }
+
+    /// Returns `true` if no id is queued.
+    pub fn is_empty(&self) -> bool {
+        self.heap.is_empty()
+    }
+
+    /// Returns `true` if `id` is queued.
+    pub fn contains(&self, id: usize) -> bool {
+        self.positions.get(id).is_some_and(|&pos| pos != ABSENT)
+    }
+
+    /// Queues `id` with `priority`.
+    ///
+    /// Returns `false` and leaves the heap unchanged if `id` is already queued.
+    pub fn push<Token>(&mut self, _token: &mut Token, id: usize, priority: P) -> bool
+    where
+        Token: GhostBorrowMut<'brand>,
+    {
+        if self.contains(id) {
+            return false;
+        }
+        if id >= self.positions.len() {
+            self.positions.resize(id + 1, ABSENT);
+        }
+        let pos = self.heap.len();
+        self.heap.push((id, priority));
+        self.positions[id] = pos;
+        self.sift_up(pos);
+        true
+    }
+
+    /// Removes and returns the id with the smallest priority.
+    pub fn pop_min<Token>(&mut self, _token: &mut Token) -> Option<(usize, P)>
+    where
+        Token: GhostBorrowMut<'brand>,
+    {
+        self.remove_at(0)
+    }
+
+    /// Returns the id with the smallest priority without removing it.
+    pub fn peek_min<'a, Token>(&'a self, token: &'a Token) -> Option<(usize, &'a P)>
+    where
+        Token: GhostBorrow<'brand>,
+    {
+        self.heap.get(token, 0).map(|(id, p)| (*id, p))
+    }
+
+    /// Returns the priority of `id`, if queued.
+    pub fn priority<'a, Token>(&'a self, token: &'a Token, id: usize) -> Option<&'a P>
+    where
+        Token: GhostBorrow<'brand>,
+    {
+        let pos = *self.positions.get(id)?;
+        self.heap.get(token, pos).map(|(_, p)| p)
+    }
+
+    /// Lowers the priority of `id` to `priority`.
+    ///
+    /// Returns `false` and leaves the heap unchanged if `id` is not queued or
+    /// `priority` is not smaller than its current priority.
+    ///
+    /// **Time complexity**: \(O(\log n)\)
+    pub fn decrease_key<Token>(&mut self, _token: &mut Token, id: usize, priority: P) -> bool
+    where
+        Token: GhostBorrowMut<'brand>,
+    {
+        if !self.contains(id) {
+            return false;
+        }
+        let pos = self.positions[id];
+        let slot = &mut self.heap.as_mut_slice_exclusive()[pos].1;
+        if priority >= *slot {
+            return false;
+        }
+        *slot = priority;
+        self.sift_up(pos);
+        true
+    }
+
+    /// Queues `id` with `priority`, or lowers its priority if it is already queued
+    /// with a larger one. Returns `true` if the heap changed.
+    ///
+    /// This is the relaxation step of Dijkstra and Prim.
+    pub fn push_or_decrease<Token>(&mut self, token: &mut Token, id: usize, priority: P) -> bool
+    where
+        Token: GhostBorrowMut<'brand>,
+    {
+        if self.contains(id) {
+            self.decrease_key(token, id, priority)
+        } else {
+            self.push(token, id, priority)
+        }
+    }
+
+    /// Removes `id` from the heap, returning its priority.
+    pub fn remove<Token>(&mut self, _token: &mut Token, id: usize) -> Option<P>
+    where
+        Token: GhostBorrowMut<'brand>,
+    {
+        let pos = *self.positions.get(id)?;
+        if pos == ABSENT {
+            return None;
+        }
+        self.remove_at(pos).map(|(_, p)| p)
+    }
+
+    /// Removes all ids.
+    pub fn clear(&mut self) {
+        for &(id, _) in self.heap.as_mut_slice_exclusive().iter() {
+            self.positions[id] = ABSENT;
+        }
+        self.heap.clear();
+    }
+
+    fn remove_at(&mut self, pos: usize) -> Option<(usize, P)> {
+        let last = self.heap.len().checked_sub(1)?;
+        if pos > last {
+            return None;
+        }
+        self.swap(pos, last);
+        let (id, priority) = self.heap.pop()?.into_inner();
+        self.positions[id] = ABSENT;
+        if pos < last {
+            // The moved element may belong either above or below `pos`.
+            let pos = self.sift_up(pos);
+            self.sift_down(pos);
+        }
+        Some((id, priority))
+    }
+
+    #[inline]
+    fn swap(&mut self, a: usize, b: usize) {
+        let data = self.heap.as_mut_slice_exclusive();
+        data.swap(a, b);
+        self.positions[data[a].0] = a;
+        self.positions[data[b].0] = b;
+    }
+
+    /// Moves the element at `pos` up to its place; returns its final slot.
+    fn sift_up(&mut self, mut pos: usize) -> usize {
+        while pos > 0 {
+            let parent = (pos - 1) / 2;
+            let data = self.heap.as_mut_slice_exclusive();
+            if data[pos].1 >= data[parent].1 {
+                break;
+            }
+            self.swap(pos, parent);
+            pos = parent;
+        }
+        pos
+    }
+
+    fn sift_down(&mut self, mut pos: usize) {
+        let len = self.heap.len();
+        loop {
+            let data = self.heap.as_mut_slice_exclusive();
+            let left = 2 * pos + 1;
+            if left >= len {
+                break;
+            }
+            let right = left + 1;
+            let child = if right < len && data[right].1 < data[left].1 {
+                right
+            } else {
+                left
+            };
+            if data[pos].1 <= data[child].1 {
+                break;
+            }
+            self.swap(pos, child);
+            pos = child;
+        }
+    }
+}
+
+impl<'brand, P: Ord> Default for BrandedIndexedHeap<'brand, P> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<'brand, P> fmt::Debug for BrandedIndexedHeap<'brand, P> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("BrandedIndexedHeap")
+            .field("len", &self.heap.len())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::GhostAdjacencyGraph;
+    use crate::GhostToken;
+
+    #[test]
+    fn test_indexed_heap_decrease_key_and_remove() {
+        GhostToken::new(|mut token| {
+            let mut heap = BrandedIndexedHeap::new();
+            for (id, p) in [(3, 30), (0, 10), (7, 70), (5, 50), (1, 40)] {
+                assert!(heap.push(&mut token, id, p));
+            }
+            assert!(!heap.push(&mut token, 3, 1));
+            assert_eq!(heap.peek_min(&token), Some((0, &10)));
+
+            assert!(heap.decrease_key(&mut token, 7, 5));
+            assert!(!heap.decrease_key(&mut token, 7, 6));
+            assert!(!heap.decrease_key(&mut token, 2, 1));
+            assert_eq!(heap.priority(&token, 7), Some(&5));
+            assert_eq!(heap.pop_min(&mut token), Some((7, 5)));
+            assert!(!heap.contains(7));
+
+            assert_eq!(heap.remove(&mut token, 3), Some(30));
+            assert_eq!(heap.remove(&mut token, 3), None);
+            assert!(heap.push_or_decrease(&mut token, 5, 20));
+            assert!(heap.push_or_decrease(&mut token, 9, 15));
+
+            let mut order = Vec::new();
+            while let Some((id, _)) = heap.pop_min(&mut token) {
+                order.push(id);
+            }
+            assert_eq!(order, vec![0, 9, 5, 1]);
+            assert!(heap.is_empty());
+        });
+    }
+
+    #[test]
+    fn test_indexed_heap_dijkstra() {
+        GhostToken::new(|mut token| {
+            let graph = GhostAdjacencyGraph::from_adjacency(vec![
+                vec![1, 2],
+                vec![3],
+                vec![1, 3],
+                vec![4],
+                vec![],
+            ]);
+            // Weight of `u -> v` is `u + v + 1`.
+            let mut dist = [u64::MAX; 5];
+            let mut heap = BrandedIndexedHeap::with_capacity(5);
+            dist[0] = 0;
+            heap.push(&mut token, 0, 0u64);
+            while let Some((u, d)) = heap.pop_min(&mut token) {
+                let nbrs: Vec<usize> = graph.out_neighbors(&token, u).collect();
+                for v in nbrs {
+                    let nd = d + (u + v + 1) as u64;
+                    if nd < dist[v] {
+                        dist[v] = nd;
+                        heap.push_or_decrease(&mut token, v, nd);
+                    }
+                }
+            }
+            assert_eq!(dist, [0, 2, 3, 7, 15]);
+        });
+    }
+}
@@ -7,6 +7,14 @@
 //! - Uses `BrandedVec` for storage, keeping intervals sorted by start coordinate.
 //! - Uses `GhostToken` to ensure safe access to the underlying storage.
 //! - Zero-copy iteration over intervals.
+//!
+//! Because the intervals never overlap, a point maps to at most one value and a
+//! lookup is a single binary search over one contiguous slice. Inserting splits the
+//! intervals it overwrites and shifts the tail of the vector, so it costs O(n). Use it
+//! for segmentations of a line, such as address-space or ownership maps, that are
+//! read far more often than written. When intervals must keep overlapping, or many
+//! are inserted and removed, use
+//! [`BrandedIntervalTree`](crate::collections::btree::BrandedIntervalTree) instead.
 
 use crate::collections::{BrandedCollection, BrandedVec};
 use crate::token::traits::{GhostBorrow, GhostBorrowMut};
@@ -0,0 +1,366 @@
+//! `BrandedLazySegmentTree` — a segment tree with lazy propagation for range updates.
+//!
+//! Complements [`BrandedSegmentTree`](super::BrandedSegmentTree), which only supports
+//! point updates. Every node stores its aggregate (with its own pending update already
+//! applied) and an optional pending update for its children. Range updates stop at
+//! fully covered nodes; the pending update is pushed down the next time a mutation
+//! descends through that node.
+//!
+//! Queries take a shared token: instead of pushing updates down, they re-apply the
+//! pending updates of partially covered ancestors to the partial results on the way up.
+//!
+//! The three closures must satisfy the usual lazy-propagation laws:
+//! - `combine` is associative with `default_value` as identity;
+//! - `apply(combine(a, b), u, la + lb) == combine(apply(a, u, la), apply(b, u, lb))`;
+//! - `apply(apply(x, u1, l), u2, l) == apply(x, compose(u1, u2), l)`.
+
+use crate::collections::{BrandedCollection, BrandedVec};
+use crate::token::traits::{GhostBorrow, GhostBorrowMut};
+
+/// A branded segment tree supporting range updates and range queries.
+///
+/// - `combine(a, b)` merges two adjacent aggregates;
+/// - `apply(x, u, len)` applies update `u` to an aggregate `x` over `len` elements;
+/// - `compose(older, newer)` merges two pending updates into one.
+pub struct BrandedLazySegmentTree<'brand, T, U, F, A, C> {
+    tree: BrandedVec<'brand, T>,
+    lazy: BrandedVec<'brand, Option<U>>,
+    n: usize,
+    combine: F,
+    apply: A,
+    compose: C,
+    default_value: T,
+}
+
+impl<'brand, T, U, F, A, C> BrandedLazySegmentTree<'brand, T, U, F, A, C>
+where
+    T: Clone,
+    U: Clone,
+    F: Fn(&T, &T) -> T,
+    A: Fn(&T, &U, usize) -> T,
+    C: Fn(&U, &U) -> U,
+{
+    /// Creates a tree over `n` elements, all set to `default_value`.
+    ///
+    /// `default_value` must be the identity of `combine`.
+    pub fn new(n: usize, combine: F, apply: A, compose: C, default_value: T) -> Self {
+        let size = 4 * n;
+        let mut tree = BrandedVec::with_capacity(size);
+        let mut lazy = BrandedVec::with_capacity(size);
+        for _ in 0..size {
+            tree.push(default_value.clone());
+            lazy.push(None);
+        }
+        Self {
+            tree,
+            lazy,
+            n,
+            combine,
+            apply,
+            compose,
+            default_value,
+        }
+    }
+
+    /// Rebuilds the tree from `data`, discarding all pending updates.
+    ///
+    /// Positions past `data.len()` are reset to `default_value`.
+    ///
+    /// # Panics
+    /// Panics if `data` is longer than the tree.
+    pub fn build<Token>(&mut self, token: &mut Token, data: &[T])
+    where
+        Token: GhostBorrowMut<'brand>,
+    {
+        assert!(data.len() <= self.n);
+        for i in 0..self.lazy.len() {
+            *self.lazy.borrow_mut(token, i) = None;
+        }
+        self.build_recursive(token, data, 0, 0, self.n);
+    }
+
+    fn build_recursive<Token>(
+        &mut self,
+        token: &mut Token,
+        data: &[T],
+        node: usize,
+        start: usize,
+        end: usize,
+    ) where
+        Token: GhostBorrowMut<'brand>,
+    {
+        if start >= end {
+            return;
+        }
+        if start == end - 1 {
+            *self.tree.borrow_mut(token, node) = data
+                .get(start)
+                .cloned()
+                .unwrap_or_else(|| self.default_value.clone());
+            return;
+        }
+        let mid = start + (end - start) / 2;
+        self.build_recursive(token, data, 2 * node + 1, start, mid);
+        self.build_recursive(token, data, 2 * node + 2, mid, end);
+        self.pull(token, node);
+    }
+
+    /// Recomputes `node` from its children.
+    fn pull<Token>(&self, token: &mut Token, node: usize)
+    where
+        Token: GhostBorrowMut<'brand>,
+    {
+        let value = (self.combine)(
+            self.tree.borrow(token, 2 * node + 1),
+            self.tree.borrow(token, 2 * node + 2),
+        );
+        *self.tree.borrow_mut(token, node) = value;
+    }
+
+    /// Applies `update` to the whole subtree rooted at `node`, covering `len` elements.
+    fn apply_to<Token>(&self, token: &mut Token, node: usize, update: &U, len: usize)
+    where
+        Token: GhostBorrowMut<'brand>,
+    {
+        let value = (self.apply)(self.tree.borrow(token, node), update, len);
+        *self.tree.borrow_mut(token, node) = value;
+        // Leaves have no children to defer to.
+        if len > 1 {
+            let pending = self.lazy.borrow_mut(token, node);
+            *pending = Some(match pending.take() {
+                Some(older) => (self.compose)(&older, update),
+                None => update.clone(),
+            });
+        }
+    }
+
+    /// Moves the pending update of `node` onto its children.
+    fn push_down<Token>(&self, token: &mut Token, node: usize, start: usize, end: usize)
+    where
+        Token: GhostBorrowMut<'brand>,
+    {
+        if let Some(update) = self.lazy.borrow_mut(token, node).take() {
+            let mid = start + (end - start) / 2;
+            self.apply_to(token, 2 * node + 1, &update, mid - start);
+            self.apply_to(token, 2 * node + 2, &update, end - mid);
+        }
+    }
+
+    /// Sets the element at `index` to `value`.
+    ///
+    /// # Panics
+    /// Panics if `index >= len()`.
+    pub fn update<Token>(&mut self, token: &mut Token, index: usize, value: T)
+    where
+        Token: GhostBorrowMut<'brand>,
+    {
+        assert!(index < self.n, "index out of bounds");
+        self.update_recursive(token, 0, 0, self.n, index, value);
+    }
+
+    fn update_recursive<Token>(
+        &mut self,
+        token: &mut Token,
+        node: usize,
+        start: usize,
+        end: usize,
+        idx: usize,
+        val: T,
+    ) where
+        Token: GhostBorrowMut<'brand>,
+    {
+        if start == end - 1 {
+            *self.tree.borrow_mut(token, node) = val;
+            return;
+        }
+        self.push_down(token, node, start, end);
+        let mid = start + (end - start) / 2;
+        if idx < mid {
+            self.update_recursive(token, 2 * node + 1, start, mid, idx, val);
+        } else {
+            self.update_recursive(token, 2 * node + 2, mid, end, idx, val);
+        }
+        self.pull(token, node);
+    }
+
+    /// Applies `update` to every element in `[q_start, q_end)`.
+    ///
+    /// The range is clamped to the tree; an empty range is a no-op.
+    pub fn range_update<Token>(
+        &mut self,
+        token: &mut Token,
+        q_start: usize,
+        q_end: usize,
+        update: U,
+    ) where
+        Token: GhostBorrowMut<'brand>,
+    {
+        let q_end = q_end.min(self.n);
+        if q_start >= q_end {
+            return;
+        }
+        self.range_update_recursive(token, 0, 0, self.n, q_start, q_end, &update);
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn range_update_recursive<Token>(
+        &mut self,
+        token: &mut Token,
+        node: usize,
+        start: usize,
+        end: usize,
+        q_start: usize,
+        q_end: usize,
+        update: &U,
+    ) where
+        Token: GhostBorrowMut<'brand>,
+    {
+        if end <= q_start || start >= q_end {
+            return;
+        }
+        if q_start <= start && end <= q_end {
+            self.apply_to(token, node, update, end - start);
+            return;
+        }
+        self.push_down(token, node, start, end);
+        let mid = start + (end - start) / 2;
+        self.range_update_recursive(token, 2 * node + 1, start, mid, q_start, q_end, update);
+        self.range_update_recursive(token, 2 * node + 2, mid, end, q_start, q_end, update);
+        self.pull(token, node);
+    }
+
+    /// Queries the range `[q_start, q_end)`.
+    ///
+    /// Returns `default_value` for an empty range.
+    pub fn query<Token>(&self, token: &Token, q_start: usize, q_end: usize) -> T
+    where
+        Token: GhostBorrow<'brand>,
+    {
+        let q_end = q_end.min(self.n);
+        if q_start >= q_end {
+            return self.default_value.clone();
+        }
+        self.query_recursive(token, 0, 0, self.n, q_start, q_end)
+    }
+
+    fn query_recursive<Token>(
+        &self,
+        token: &Token,
+        node: usize,
+        start: usize,
+        end: usize,
+        q_start: usize,
+        q_end: usize,
+    ) -> T
+    where
+        Token: GhostBorrow<'brand>,
+    {
+        if q_start <= start && end <= q_end {
+            return self.tree.borrow(token, node).clone();
+        }
+        let mid = start + (end - start) / 2;
+        let result = if q_end <= mid {
+            self.query_recursive(token, 2 * node + 1, start, mid, q_start, q_end)
+        } else if q_start >= mid {
+            self.query_recursive(token, 2 * node + 2, mid, end, q_start, q_end)
+        } else {
+            let left = self.query_recursive(token, 2 * node + 1, start, mid, q_start, q_end);
+            let right = self.query_recursive(token, 2 * node + 2, mid, end, q_start, q_end);
+            (self.combine)(&left, &right)
+        };
+        // Children have not seen this node's pending update yet.
+        match self.lazy.borrow(token, node) {
+            Some(update) => {
+                let covered = q_end.min(end) - q_start.max(start);
+                (self.apply)(&result, update, covered)
+            }
+            None => result,
+        }
+    }
+
+    /// Returns the element at `index`.
+    ///
+    /// # Panics
+    /// Panics if `index >= len()`.
+    pub fn get<Token>(&self, token: &Token, index: usize) -> T
+    where
+        Token: GhostBorrow<'brand>,
+    {
+        assert!(index < self.n, "index out of bounds");
+        self.query(token, index, index + 1)
+    }
+}
+
+impl<'brand, T, U, F, A, C> BrandedCollection<'brand>
+    for BrandedLazySegmentTree<'brand, T, U, F, A, C>
+{
+    fn is_empty(&self) -> bool {
+        self.n == 0
+    }
+
+    fn len(&self) -> usize {
+        self.n
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::GhostToken;
+
+    #[test]
+    fn test_lazy_segment_tree_range_add_sum() {
+        GhostToken::new(|mut token| {
+            let mut st = BrandedLazySegmentTree::new(
+                10,
+                |a: &i64, b: &i64| a + b,
+                |x: &i64, add: &i64, len| x + add * i64::try_from(len).unwrap(),
+                |a: &i64, b: &i64| a + b,
+                0,
+            );
+            let mut naive: Vec<i64> = (1..=10).collect();
+            st.build(&mut token, &naive);
+
+            let ops = [(0, 10, 3), (2, 5, -1), (4, 9, 7), (7, 8, 2), (0, 3, 5)];
+            for &(l, r, add) in &ops {
+                st.range_update(&mut token, l, r, add);
+                naive[l..r].iter_mut().for_each(|x| *x += add);
+                for l in 0..=10 {
+                    for r in l..=10 {
+                        assert_eq!(st.query(&token, l, r), naive[l..r].iter().sum::<i64>());
+                    }
+                }
+            }
+
+            st.update(&mut token, 6, 100);
+            naive[6] = 100;
+            assert_eq!(st.query(&token, 0, 10), naive.iter().sum::<i64>());
+            assert_eq!(st.get(&token, 6), 100);
+            assert_eq!(st.get(&token, 5), naive[5]);
+        });
+    }
+
+    #[test]
+    fn test_lazy_segment_tree_range_assign_min() {
+        GhostToken::new(|mut token| {
+            // Range assignment with range minimum: later assignments win.
+            let mut st = BrandedLazySegmentTree::new(
+                6,
+                |a: &i32, b: &i32| *a.min(b),
+                |_: &i32, v: &i32, _| *v,
+                |_: &i32, newer: &i32| *newer,
+                i32::MAX,
+            );
+            st.build(&mut token, &[5, 3, 8, 6, 2, 7]);
+            st.range_update(&mut token, 1, 5, 4);
+            assert_eq!(st.query(&token, 0, 6), 4);
+            st.range_update(&mut token, 3, 4, 1);
+            assert_eq!(st.query(&token, 0, 3), 4);
+            assert_eq!(st.query(&token, 2, 6), 1);
+            assert_eq!(st.get(&token, 0), 5);
+            assert_eq!(st.get(&token, 5), 7);
+            assert_eq!(st.query(&token, 4, 4), i32::MAX);
+            assert_eq!(st.len(), 6);
+        });
+    }
+}
@@ -10,32 +10,48 @@ pub mod bloom_filter;
 pub mod chain;
 pub mod cow;
 pub mod cow_strings;
+pub mod cuckoo_filter;
 pub mod deque;
 pub mod disjoint_set;
 pub mod doubly_linked_list;
 pub mod fenwick_tree;
+pub mod indexed_heap;
 pub mod interner;
 pub mod interval_map;
+pub mod lazy_segment_tree;
 pub mod lru_cache;
+pub mod ring_buffer;
+pub mod secondary_map;
 pub mod segment_tree;
 pub mod slot_map;
 pub mod tripod_list;
 pub mod trusted_index;
+pub mod ttl_cache;
+pub mod union_find;
+pub mod weighted_sampler;
 
 pub use binary_heap::BrandedBinaryHeap;
 pub use bit_set::BrandedBitSet;
-pub use bloom_filter::BrandedBloomFilter;
+pub use bloom_filter::{BrandedBloomFilter, ConcurrentBrandedBloomFilter};
 pub use chain::BrandedChain;
 pub use cow::BrandedCow;
 pub use cow_strings::BrandedCowStrings;
+pub use cuckoo_filter::BrandedCuckooFilter;
 pub use deque::BrandedDeque;
 pub use disjoint_set::BrandedDisjointSet;
 pub use active::ActiveDisjointSet;
 pub use doubly_linked_list::BrandedDoublyLinkedList;
 pub use fenwick_tree::BrandedFenwickTree;
+pub use indexed_heap::BrandedIndexedHeap;
 pub use interner::{BrandedInterner, InternId};
 pub use interval_map::BrandedIntervalMap;
+pub use lazy_segment_tree::BrandedLazySegmentTree;
 pub use lru_cache::BrandedLruCache;
+pub use ring_buffer::BrandedRingBuffer;
+pub use secondary_map::BrandedSecondaryMap;
 pub use segment_tree::{BrandedSegmentTree, BrandedSegmentTreeViewMut};
 pub use slot_map::{BrandedSlotMap, SlotKey};
 pub use tripod_list::TripodList;
+pub use ttl_cache::BrandedTtlCache;
+pub use union_find::{BrandedUnionFind, ConcurrentBrandedUnionFind};
+pub use weighted_sampler::{BrandedAliasTable, BrandedWeightedSampler};
@@ -0,0 +1,218 @@
+//! `BrandedRingBuffer` — a fixed-capacity window that overwrites its oldest element.
+//!
+//! This is the single-threaded counterpart of the concurrent `GhostRingBuffer`: no
+//! atomics, just a [`BrandedDeque`] that evicts from the front when a push would
+//! overflow it. It suits windowed statistics (moving averages, recent-event logs)
+//! kept inside a token scope, where the window is read with a shared token and
+//! updated in place with a mutable one.
+
+use super::BrandedDeque;
+use crate::token::traits::{GhostBorrow, GhostBorrowMut};
+use crate::GhostCell;
+
+/// A ring buffer of at most `N` elements, iterated from oldest to newest.
+pub struct BrandedRingBuffer<'brand, T, const N: usize> {
+    deque: BrandedDeque<'brand, T, N>,
+}
+
+impl<'brand, T, const N: usize> BrandedRingBuffer<'brand, T, N> {
+    /// Creates an empty ring buffer.
+    pub const fn new() -> Self {
+        Self {
+            deque: BrandedDeque::new(),
+        }
+    }
+
+    /// Returns the capacity `N`.
+    #[inline]
+    pub const fn capacity(&self) -> usize {
+        N
+    }
+
+    /// Returns the number of elements.
+    #[inline]
+    pub const fn len(&self) -> usize {
+        self.deque.len()
+    }
+
+    /// Returns `true` if the buffer holds no elements.
+    #[inline]
+    pub const fn is_empty(&self) -> bool {
+        self.deque.is_empty()
+    }
+
+    /// Returns `true` if the next push will evict the oldest element.
+    #[inline]
+    pub const fn is_full(&self) -> bool {
+        self.deque.is_full()
+    }
+
+    /// Appends `value` as the newest element, evicting and returning the oldest one if
+    /// the buffer is full.
+    ///
+    /// With `N == 0` nothing can be stored and `value` is returned immediately.
+    pub fn push_overwrite(&mut self, value: T) -> Option<T> {
+        if N == 0 {
+            return Some(value);
+        }
+        let evicted = if self.is_full() {
+            self.pop_oldest()
+        } else {
+            None
+        };
+        let pushed = self.deque.push_back(value);
+        debug_assert!(pushed.is_some(), "a slot was freed above");
+        evicted
+    }
+
+    /// Appends `value` as the newest element if there is room.
+    ///
+    /// # Errors
+    /// Returns `value` back if the buffer is full.
+    pub fn try_push(&mut self, value: T) -> Result<(), T> {
+        if self.is_full() {
+            return Err(value);
+        }
+        self.deque.push_back(value);
+        Ok(())
+    }
+
+    /// Removes and returns the oldest element.
+    pub fn pop_oldest(&mut self) -> Option<T> {
+        self.deque.pop_front().map(GhostCell::into_inner)
+    }
+
+    /// Removes and returns the newest element.
+    pub fn pop_newest(&mut self) -> Option<T> {
+        self.deque.pop_back().map(GhostCell::into_inner)
+    }
+
+    /// Returns the oldest element.
+    pub fn oldest<'a, Token>(&'a self, token: &'a Token) -> Option<&'a T>
+    where
+        Token: GhostBorrow<'brand>,
+    {
+        self.deque.front(token)
+    }
+
+    /// Returns the newest element.
+    pub fn newest<'a, Token>(&'a self, token: &'a Token) -> Option<&'a T>
+    where
+        Token: GhostBorrow<'brand>,
+    {
+        self.deque.back(token)
+    }
+
+    /// Returns the element `index` places after the oldest.
+    pub fn get<'a, Token>(&'a self, token: &'a Token, index: usize) -> Option<&'a T>
+    where
+        Token: GhostBorrow<'brand>,
+    {
+        self.deque.get(token, index)
+    }
+
+    /// Returns a mutable reference to the element `index` places after the oldest.
+    pub fn get_mut<'a, Token>(&'a self, token: &'a mut Token, index: usize) -> Option<&'a mut T>
+    where
+        Token: GhostBorrowMut<'brand>,
+    {
+        self.deque.get_mut(token, index)
+    }
+
+    /// Iterates from the oldest element to the newest; call `.rev()` for the reverse.
+    pub fn iter<'a, Token>(
+        &'a self,
+        token: &'a Token,
+    ) -> impl DoubleEndedIterator<Item = &'a T> + ExactSizeIterator + use<'a, 'brand, T, N, Token>
+    where
+        Token: GhostBorrow<'brand>,
+    {
+        self.deque.iter(token)
+    }
+
+    /// Applies `f` to every element, oldest first.
+    pub fn for_each_mut<F, Token>(&self, token: &mut Token, f: F)
+    where
+        F: FnMut(&mut T),
+        Token: GhostBorrowMut<'brand>,
+    {
+        self.deque.for_each_mut(token, f);
+    }
+
+    /// Removes every element.
+    pub fn clear(&mut self) {
+        self.deque.clear();
+    }
+}
+
+impl<T, const N: usize> Default for BrandedRingBuffer<'_, T, N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T, const N: usize> Extend<T> for BrandedRingBuffer<'_, T, N> {
+    /// Pushes every item with [`push_overwrite`](BrandedRingBuffer::push_overwrite), so
+    /// only the last `N` are kept.
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for value in iter {
+            self.push_overwrite(value);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::GhostToken;
+
+    #[test]
+    fn test_ring_buffer_overwrites_oldest() {
+        GhostToken::new(|mut token| {
+            let mut ring: BrandedRingBuffer<'_, u32, 3> = BrandedRingBuffer::new();
+            assert_eq!(ring.push_overwrite(1), None);
+            assert_eq!(ring.try_push(2), Ok(()));
+            assert_eq!(ring.push_overwrite(3), None);
+            assert!(ring.is_full());
+            assert_eq!(ring.try_push(9), Err(9));
+            assert_eq!(ring.push_overwrite(4), Some(1));
+            ring.extend([5, 6]);
+
+            assert_eq!(ring.iter(&token).copied().collect::<Vec<_>>(), [4, 5, 6]);
+            assert_eq!(
+                ring.iter(&token).rev().copied().collect::<Vec<_>>(),
+                [6, 5, 4]
+            );
+            assert_eq!(ring.oldest(&token), Some(&4));
+            assert_eq!(ring.newest(&token), Some(&6));
+
+            *ring.get_mut(&mut token, 1).unwrap() = 50;
+            ring.for_each_mut(&mut token, |x| *x += 1);
+            assert_eq!(ring.get(&token, 1), Some(&51));
+            assert_eq!(ring.pop_newest(), Some(7));
+            assert_eq!(ring.pop_oldest(), Some(5));
+            assert_eq!(ring.len(), 1);
+            ring.clear();
+            assert!(ring.is_empty());
+        });
+    }
+
+    #[test]
+    fn test_ring_buffer_moving_average() {
+        GhostToken::new(|token| {
+            let mut window: BrandedRingBuffer<'_, f64, 4> = BrandedRingBuffer::new();
+            let averages: Vec<f64> = (1..=6)
+                .map(|x| {
+                    window.push_overwrite(f64::from(x));
+                    let len = u32::try_from(window.len()).unwrap();
+                    window.iter(&token).sum::<f64>() / f64::from(len)
+                })
+                .collect();
+            assert_eq!(averages, [1.0, 1.5, 2.0, 2.5, 3.5, 4.5]);
+
+            let mut empty: BrandedRingBuffer<'_, u8, 0> = BrandedRingBuffer::new();
+            assert_eq!(empty.push_overwrite(1), Some(1));
+            assert!(empty.is_empty());
+        });
+    }
+}
@@ -0,0 +1,205 @@
+//! `BrandedSecondaryMap` — extra data attached to the keys of a [`BrandedSlotMap`].
+//!
+//! A secondary map stores values indexed by [`SlotKey`]s issued by a primary
+//! [`BrandedSlotMap`], so components can live in separate maps (an entity system, or
+//! per-node annotations of a graph whose nodes sit in a slot map) without widening
+//! the primary value type.
+//!
+//! Each slot remembers the generation of the key it was written with. A lookup only
+//! succeeds for that exact key, so values attached to a removed entity are never
+//! returned for a later entity reusing its slot, and a stale key cannot overwrite a
+//! newer entry.
+//!
+//! [`BrandedSlotMap`]: super::BrandedSlotMap
+
+use super::slot_map::SlotKey;
+use crate::collections::BrandedCollection;
+use crate::token::traits::{GhostBorrow, GhostBorrowMut};
+use crate::BrandedVec;
+
+/// A map from the keys of a [`BrandedSlotMap`](super::BrandedSlotMap) to values.
+pub struct BrandedSecondaryMap<'brand, V> {
+    slots: BrandedVec<'brand, Option<V>>,
+    /// Generation of the newest key written to each slot.
+    generations: Vec<u32>,
+    len: usize,
+}
+
+impl<'brand, V> BrandedSecondaryMap<'brand, V> {
+    /// Creates an empty secondary map.
+    pub fn new() -> Self {
+        Self {
+            slots: BrandedVec::new(),
+            generations: Vec::new(),
+            len: 0,
+        }
+    }
+
+    /// Inserts a value for `key`, returning the value previously stored for it.
+    ///
+    /// A value stored under an older key for the same slot is dropped and replaced.
+    /// If the slot was already written with a *newer* key, `key` is stale: nothing is
+    /// inserted and `None` is returned.
+    pub fn insert(&mut self, key: SlotKey<'brand>, value: V) -> Option<V> {
+        let idx = key.index();
+        if idx >= self.slots.len() {
+            self.slots.resize_with(idx + 1, || None);
+            self.generations.resize(idx + 1, 0);
+        }
+        let Some(slot) = self.slots.get_mut_exclusive(idx) else {
+            unreachable!("slot was just allocated");
+        };
+        let generation = &mut self.generations[idx];
+        if *generation > key.generation() {
+            return None;
+        }
+
+        let previous = slot.replace(value);
+        let current = core::mem::replace(generation, key.generation()) == key.generation();
+        match previous {
+            Some(old) if current => Some(old),
+            Some(_) => None,
+            None => {
+                self.len += 1;
+                None
+            }
+        }
+    }
+
+    /// Removes the value for `key`, returning it.
+    pub fn remove(&mut self, key: SlotKey<'brand>) -> Option<V> {
+        let idx = key.index();
+        if self.generations.get(idx) != Some(&key.generation()) {
+            return None;
+        }
+        let value = self.slots.get_mut_exclusive(idx)?.take();
+        if value.is_some() {
+            self.len -= 1;
+        }
+        value
+    }
+
+    /// Returns a shared reference to the value for `key`.
+    pub fn get<'a, Token>(&'a self, token: &'a Token, key: SlotKey<'brand>) -> Option<&'a V>
+    where
+        Token: GhostBorrow<'brand>,
+    {
+        let idx = key.index();
+        if self.generations.get(idx) != Some(&key.generation()) {
+            return None;
+        }
+        self.slots.get(token, idx)?.as_ref()
+    }
+
+    /// Returns a mutable reference to the value for `key`.
+    pub fn get_mut<'a, Token>(
+        &'a self,
+        token: &'a mut Token,
+        key: SlotKey<'brand>,
+    ) -> Option<&'a mut V>
+    where
+        Token: GhostBorrowMut<'brand>,
+    {
+        let idx = key.index();
+        if self.generations.get(idx) != Some(&key.generation()) {
+            return None;
+        }
+        self.slots.get_mut(token, idx)?.as_mut()
+    }
+
+    /// Returns `true` if the map holds a value for `key`.
+    pub fn contains_key<Token>(&self, token: &Token, key: SlotKey<'brand>) -> bool
+    where
+        Token: GhostBorrow<'brand>,
+    {
+        self.get(token, key).is_some()
+    }
+
+    /// Removes every value.
+    pub fn clear(&mut self) {
+        self.slots.clear();
+        self.generations.clear();
+        self.len = 0;
+    }
+
+    /// Iterates over the stored keys and values in slot order.
+    pub fn iter<'a, Token>(
+        &'a self,
+        token: &'a Token,
+    ) -> impl Iterator<Item = (SlotKey<'brand>, &'a V)> + use<'a, 'brand, V, Token>
+    where
+        Token: GhostBorrow<'brand>,
+    {
+        self.slots
+            .iter(token)
+            .zip(&self.generations)
+            .enumerate()
+            .filter_map(|(idx, (slot, &generation))| {
+                let value = slot.as_ref()?;
+                Some((SlotKey::new(u32::try_from(idx).ok()?, generation), value))
+            })
+    }
+}
+
+impl<'brand, V> BrandedCollection<'brand> for BrandedSecondaryMap<'brand, V> {
+    fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    fn len(&self) -> usize {
+        self.len
+    }
+}
+
+impl<'brand, V> Default for BrandedSecondaryMap<'brand, V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::collections::BrandedSlotMap;
+    use crate::GhostToken;
+
+    #[test]
+    fn test_secondary_map_follows_primary_keys() {
+        GhostToken::new(|mut token| {
+            let mut entities = BrandedSlotMap::new();
+            let mut names = BrandedSecondaryMap::new();
+            let a = entities.insert(&mut token, ());
+            let b = entities.insert(&mut token, ());
+
+            assert_eq!(names.insert(b, "bob"), None);
+            assert_eq!(names.insert(a, "alice"), None);
+            assert_eq!(names.insert(a, "ann"), Some("alice"));
+            assert_eq!(names.len(), 2);
+            *names.get_mut(&mut token, b).unwrap() = "bo";
+
+            let all: Vec<_> = names.iter(&token).collect();
+            assert_eq!(all, [(a, &"ann"), (b, &"bo")]);
+
+            // The slot of `a` is reused with a new generation.
+            entities.remove(&mut token, a);
+            let c = entities.insert(&mut token, ());
+            assert_eq!(c.index(), a.index());
+            assert!(!names.contains_key(&token, c));
+            assert_eq!(names.get(&token, a), Some(&"ann"));
+
+            // The new key replaces the stale entry, after which the old key is inert.
+            assert_eq!(names.insert(c, "carol"), None);
+            assert_eq!(names.len(), 2);
+            assert_eq!(names.get(&token, a), None);
+            assert_eq!(names.insert(a, "stale"), None);
+            assert_eq!(names.remove(a), None);
+            assert_eq!(names.get(&token, c), Some(&"carol"));
+
+            assert_eq!(names.remove(c), Some("carol"));
+            assert_eq!(names.len(), 1);
+            names.clear();
+            assert!(names.is_empty());
+            assert_eq!(names.get(&token, b), None);
+        });
+    }
+}
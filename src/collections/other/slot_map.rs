@@ -7,7 +7,11 @@
 //! - Uses `BrandedVec` for storage.
 //! - Uses a `union` to overlap occupied and free states, minimizing memory usage.
 //! - Keys are branded to prevent misuse across different maps.
-//! - Generation counters prevent ABA problems when slots are reused.
+//! - Generation counters prevent ABA problems when slots are reused. A slot whose
+//!   generation counter is exhausted is retired rather than wrapped around, so a stale
+//!   key can never match a later value.
+//! - [`BrandedSecondaryMap`](super::BrandedSecondaryMap) attaches extra data to the
+//!   same keys.
 
 use crate::collections::BrandedCollection;
 use crate::token::traits::{GhostBorrow, GhostBorrowMut};
@@ -27,15 +31,30 @@ pub struct SlotKey<'brand> {
 }
 
 impl<'brand> SlotKey<'brand> {
-    fn new(index: u32, generation: u32) -> Self {
+    pub(super) fn new(index: u32, generation: u32) -> Self {
         Self {
             index,
             generation,
             _marker: PhantomData,
         }
     }
+
+    /// Returns the slot index of the key.
+    #[inline]
+    pub fn index(self) -> usize {
+        self.index as usize
+    }
+
+    /// Returns the generation of the slot when the key was issued.
+    #[inline]
+    pub fn generation(self) -> u32 {
+        self.generation
+    }
 }
 
+/// Generation of a slot that can never be reused.
+const RETIRED: u32 = u32::MAX;
+
 /// Internal entry in the slot map.
 union SlotData<T> {
     /// If occupied, contains the value.
@@ -173,12 +192,16 @@ impl<'brand, T> BrandedSlotMap<'brand, T> {
                 unsafe {
                     let value = ManuallyDrop::take(&mut entry.data.value);
 
-                    entry.data.next_free = self.free_head;
-                    self.free_head = idx as u32;
-
                     // Increment to Odd (Free)
                     entry.generation = entry.generation.wrapping_add(1);
 
+                    // Reusing a slot with an exhausted counter would wrap it back to a
+                    // generation some old key may still hold, so retire it instead.
+                    if entry.generation != RETIRED {
+                        entry.data.next_free = self.free_head;
+                        self.free_head = key.index;
+                    }
+
                     return Some(value);
                 }
             }
@@ -217,23 +240,19 @@ impl<'brand, T> BrandedSlotMap<'brand, T> {
             }
         }
 
-        // Rebuild free list
-        self.free_head = 0;
+        // Rebuild the free list in index order, leaving retired slots out.
+        self.free_head = u32::MAX;
         self.len = 0;
 
-        let cap = self.slots.len();
-        if cap > 0 {
-            for idx in 0..cap {
-                let entry = unsafe { self.slots.get_unchecked_mut(token, idx) };
-                // Set next free
-                if idx < cap - 1 {
-                    entry.data.next_free = (idx + 1) as u32;
-                } else {
-                    entry.data.next_free = u32::MAX;
-                }
+        for idx in (0..self.slots.len()).rev() {
+            let entry = unsafe { self.slots.get_unchecked_mut(token, idx) };
+            if entry.generation != RETIRED {
+                let Ok(index) = u32::try_from(idx) else {
+                    unreachable!("slot indices come from `u32` keys");
+                };
+                entry.data.next_free = self.free_head;
+                self.free_head = index;
             }
-        } else {
-            self.free_head = u32::MAX;
         }
     }
 }
@@ -308,6 +327,20 @@ impl<'brand, T> Default for BrandedSlotMap<'brand, T> {
     }
 }
 
+impl<'brand, T> Drop for BrandedSlotMap<'brand, T> {
+    fn drop(&mut self) {
+        if self.len == 0 {
+            return;
+        }
+        for entry in self.slots.as_mut_slice_exclusive() {
+            if entry.generation % 2 == 0 {
+                // SAFETY: even generations mark occupied slots.
+                unsafe { ManuallyDrop::drop(&mut entry.data.value) };
+            }
+        }
+    }
+}
+
 // SAFETY: BrandedSlotMap is Send/Sync if T is.
 unsafe impl<'brand, T: Send> Send for BrandedSlotMap<'brand, T> {}
 unsafe impl<'brand, T: Sync> Sync for BrandedSlotMap<'brand, T> {}
@@ -363,7 +396,7 @@ mod tests {
             let mut count = 0;
             for (k, v) in map.iter(&token) {
                 assert!(keys.contains(&k));
-                assert_eq!(k.index as i32 * 10, *v as i32);
+                assert_eq!(k.index() * 10, usize::try_from(*v).unwrap());
                 count += 1;
             }
             assert_eq!(count, 10);
@@ -389,4 +422,43 @@ mod tests {
             assert_eq!(map.len(), 5);
         });
     }
+    #[test]
+    fn test_slot_map_retires_exhausted_slots() {
+        GhostToken::new(|mut token| {
+            let mut map = BrandedSlotMap::new();
+            let k = map.insert(&mut token, 'a');
+            map.remove(&mut token, k);
+            // Fast-forward the free slot to its last usable generation.
+            map.slots.get_mut_exclusive(0).unwrap().generation = RETIRED - 2;
+
+            let last = map.insert(&mut token, 'b');
+            assert_eq!((last.index(), last.generation()), (0, RETIRED - 1));
+            assert_eq!(map.remove(&mut token, last), Some('b'));
+
+            // The slot is not handed out again, even after clearing.
+            let fresh = map.insert(&mut token, 'c');
+            assert_eq!(fresh.index(), 1);
+            map.clear(&mut token);
+            let k0 = map.insert(&mut token, 'd');
+            let k1 = map.insert(&mut token, 'e');
+            assert_eq!((k0.index(), k1.index()), (1, 2));
+            assert!(map.get(&token, last).is_none());
+        });
+    }
+
+    #[test]
+    fn test_slot_map_drops_values() {
+        use std::rc::Rc;
+
+        let value = Rc::new(());
+        GhostToken::new(|mut token| {
+            let mut map = BrandedSlotMap::new();
+            let k = map.insert(&mut token, Rc::clone(&value));
+            map.insert(&mut token, Rc::clone(&value));
+            map.insert(&mut token, Rc::clone(&value));
+            drop(map.remove(&mut token, k));
+            assert_eq!(Rc::strong_count(&value), 3);
+        });
+        assert_eq!(Rc::strong_count(&value), 1);
+    }
 }
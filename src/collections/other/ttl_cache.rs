@@ -0,0 +1,409 @@
+//! `BrandedTtlCache` — a size-bounded cache with per-entry time-to-live.
+//!
+//! Built from the same pieces as [`BrandedLruCache`](super::BrandedLruCache):
+//! - a `BrandedExternalHashMap` maps keys to indices into the recency list;
+//! - a `BrandedDoublyLinkedList` keeps entries in LRU order (front = most recent);
+//! - a hashed timer wheel of `WHEEL_SLOTS` buckets schedules expirations.
+//!
+//! Time is a logical `u64` tick supplied by the caller. Nothing expires on its own:
+//! [`advance_to`](BrandedTtlCache::advance_to) moves the clock forward and drains
+//! every wheel bucket it passes over. Between calls, every live entry has a deadline
+//! strictly after [`now`](BrandedTtlCache::now), so lookups never see stale values.
+//!
+//! When the cache is full, inserting a new key evicts the least recently used entry,
+//! regardless of its remaining TTL.
+//!
+//! Every entry has exactly one wheel record and remembers where it is. Resetting a TTL
+//! moves the record to its new bucket and removing the entry removes it, both by
+//! swap-removal, so the wheel never holds more records than the cache holds entries.
+
+use crate::collections::hash::external_map::BrandedExternalHashMap;
+use crate::collections::other::BrandedDoublyLinkedList;
+use crate::token::traits::{GhostBorrow, GhostBorrowMut};
+use core::fmt;
+use core::hash::Hash;
+
+const WHEEL_SLOTS: usize = 256;
+const WHEEL_MASK: u64 = WHEEL_SLOTS as u64 - 1;
+
+struct Entry<K, V> {
+    key: K,
+    value: V,
+    expires_at: u64,
+    /// Position of this entry's record in its wheel bucket.
+    wheel_pos: usize,
+}
+
+/// A bounded LRU cache whose entries expire after a per-entry time-to-live.
+pub struct BrandedTtlCache<'brand, K, V> {
+    map: BrandedExternalHashMap,
+    list: BrandedDoublyLinkedList<'brand, Entry<K, V>>,
+    /// List indices of the entries, bucketed by `expires_at & WHEEL_MASK`.
+    wheel: Box<[Vec<usize>]>,
+    capacity: usize,
+    default_ttl: u64,
+    now: u64,
+}
+
+impl<'brand, K, V> BrandedTtlCache<'brand, K, V> {
+    /// Returns the number of live entries.
+    pub fn len(&self) -> usize {
+        self.list.len()
+    }
+
+    /// Returns `true` if the cache is empty.
+    pub fn is_empty(&self) -> bool {
+        self.list.is_empty()
+    }
+
+    /// Returns the maximum number of entries.
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Returns the TTL used by [`insert`](Self::insert).
+    pub fn default_ttl(&self) -> u64 {
+        self.default_ttl
+    }
+
+    /// Returns the current logical time.
+    pub fn now(&self) -> u64 {
+        self.now
+    }
+}
+
+impl<'brand, K, V> BrandedTtlCache<'brand, K, V>
+where
+    K: Hash + Eq,
+{
+    /// Creates a cache holding at most `capacity` entries, each living `default_ttl`
+    /// ticks unless inserted with [`insert_with_ttl`](Self::insert_with_ttl).
+    ///
+    /// # Panics
+    /// Panics if `capacity` or `default_ttl` is zero.
+    pub fn new(capacity: usize, default_ttl: u64) -> Self {
+        assert!(capacity > 0, "capacity must be non-zero");
+        assert!(default_ttl > 0, "ttl must be non-zero");
+        Self {
+            map: BrandedExternalHashMap::with_capacity(capacity),
+            list: BrandedDoublyLinkedList::new(),
+            wheel: (0..WHEEL_SLOTS).map(|_| Vec::new()).collect(),
+            capacity,
+            default_ttl,
+            now: 0,
+        }
+    }
+
+    fn index_of<Token>(&self, token: &Token, key: &K) -> Option<usize>
+    where
+        Token: GhostBorrow<'brand>,
+    {
+        self.map
+            .get(key, |idx| self.list.get(token, idx).map(|e| &e.key))
+    }
+
+    /// Adds the wheel record of the entry at `index`, in the bucket of its deadline.
+    fn schedule<Token>(&mut self, token: &mut Token, index: usize)
+    where
+        Token: GhostBorrowMut<'brand>,
+    {
+        let entry = self.list.get_mut(token, index).expect("Corrupted cache");
+        let bucket = &mut self.wheel[slot_of(entry.expires_at)];
+        entry.wheel_pos = bucket.len();
+        bucket.push(index);
+    }
+
+    /// Removes the wheel record of the entry at `index`.
+    fn unschedule<Token>(&mut self, token: &mut Token, index: usize)
+    where
+        Token: GhostBorrowMut<'brand>,
+    {
+        let entry = self.list.get(token, index).expect("Corrupted cache");
+        let (slot, pos) = (slot_of(entry.expires_at), entry.wheel_pos);
+        let bucket = &mut self.wheel[slot];
+        debug_assert_eq!(bucket[pos], index, "wheel record out of place");
+        bucket.swap_remove(pos);
+        if let Some(&moved) = bucket.get(pos) {
+            self.list
+                .get_mut(token, moved)
+                .expect("Corrupted cache")
+                .wheel_pos = pos;
+        }
+    }
+
+    /// Unlinks the entry at `index` from the map, the list and the wheel.
+    fn remove_at<Token>(&mut self, token: &mut Token, index: usize) -> (K, V)
+    where
+        Token: GhostBorrowMut<'brand>,
+    {
+        self.unschedule(token, index);
+        let list = &self.list;
+        if let Some(entry) = list.get(token, index) {
+            self.map
+                .remove(&entry.key, |idx| list.get(token, idx).map(|e| &e.key));
+        }
+        self.list.move_to_back(token, index);
+        let entry = self.list.pop_back(token).expect("Corrupted cache");
+        (entry.key, entry.value)
+    }
+
+    /// Returns a reference to the value for `key`, marking it most recently used.
+    pub fn get<'a, Token>(&'a mut self, token: &'a mut Token, key: &K) -> Option<&'a V>
+    where
+        Token: GhostBorrowMut<'brand>,
+    {
+        let index = self.index_of(token, key)?;
+        self.list.move_to_front(token, index);
+        self.list.get(token, index).map(|e| &e.value)
+    }
+
+    /// Returns a mutable reference to the value for `key`, marking it most recently used.
+    pub fn get_mut<'a, Token>(&'a mut self, token: &'a mut Token, key: &K) -> Option<&'a mut V>
+    where
+        Token: GhostBorrowMut<'brand>,
+    {
+        let index = self.index_of(token, key)?;
+        self.list.move_to_front(token, index);
+        self.list.get_mut(token, index).map(|e| &mut e.value)
+    }
+
+    /// Returns a reference to the value without updating the LRU order.
+    pub fn peek<'a, Token>(&'a self, token: &'a Token, key: &K) -> Option<&'a V>
+    where
+        Token: GhostBorrow<'brand>,
+    {
+        let index = self.index_of(token, key)?;
+        self.list.get(token, index).map(|e| &e.value)
+    }
+
+    /// Returns the number of ticks until `key` expires.
+    pub fn ttl_remaining<Token>(&self, token: &Token, key: &K) -> Option<u64>
+    where
+        Token: GhostBorrow<'brand>,
+    {
+        let index = self.index_of(token, key)?;
+        self.list.get(token, index).map(|e| e.expires_at - self.now)
+    }
+
+    /// Inserts `key` with the default TTL; see [`insert_with_ttl`](Self::insert_with_ttl).
+    pub fn insert<Token>(&mut self, token: &mut Token, key: K, value: V) -> Option<V>
+    where
+        Token: GhostBorrowMut<'brand>,
+    {
+        self.insert_with_ttl(token, key, value, self.default_ttl)
+    }
+
+    /// Inserts `key`, expiring `ttl` ticks from now, and marks it most recently used.
+    ///
+    /// An existing entry has its value replaced and its TTL reset; the old value is
+    /// returned. Otherwise, if the cache is full, the least recently used entry is
+    /// evicted first.
+    ///
+    /// # Panics
+    /// Panics if `ttl` is zero.
+    pub fn insert_with_ttl<Token>(
+        &mut self,
+        token: &mut Token,
+        key: K,
+        value: V,
+        ttl: u64,
+    ) -> Option<V>
+    where
+        Token: GhostBorrowMut<'brand>,
+    {
+        assert!(ttl > 0, "ttl must be non-zero");
+        let expires_at = self.now.saturating_add(ttl);
+
+        if let Some(index) = self.index_of(token, &key) {
+            self.list.move_to_front(token, index);
+            self.unschedule(token, index);
+            let entry = self.list.get_mut(token, index).expect("Corrupted cache");
+            entry.expires_at = expires_at;
+            let old = std::mem::replace(&mut entry.value, value);
+            self.schedule(token, index);
+            return Some(old);
+        }
+
+        if self.len() == self.capacity {
+            if let Some(lru) = self.list_back_index(token) {
+                self.remove_at(token, lru);
+            }
+        }
+
+        let index = self.list.push_front(
+            token,
+            Entry {
+                key,
+                value,
+                expires_at,
+                wheel_pos: 0,
+            },
+        );
+        let list = &self.list;
+        let key = &list.get(token, index).expect("Corrupted cache").key;
+        self.map
+            .insert(key, index, |idx| list.get(token, idx).map(|e| &e.key));
+        self.schedule(token, index);
+        None
+    }
+
+    fn list_back_index<Token>(&self, token: &Token) -> Option<usize>
+    where
+        Token: GhostBorrow<'brand>,
+    {
+        let key = &self.list.back(token)?.key;
+        self.index_of(token, key)
+    }
+
+    /// Removes `key`, returning its value.
+    pub fn remove<Token>(&mut self, token: &mut Token, key: &K) -> Option<V>
+    where
+        Token: GhostBorrowMut<'brand>,
+    {
+        let index = self.index_of(token, key)?;
+        Some(self.remove_at(token, index).1)
+    }
+
+    /// Advances the clock to `now` and removes every entry whose deadline has passed,
+    /// returning them in wheel order.
+    ///
+    /// Times at or before the current [`now`](Self::now) are ignored.
+    ///
+    /// **Time complexity**: \(O(\min(\Delta, W) + r)\) for a jump of `Δ` ticks, wheel
+    /// size `W` and `r` wheel records visited.
+    pub fn advance_to<Token>(&mut self, token: &mut Token, now: u64) -> Vec<(K, V)>
+    where
+        Token: GhostBorrowMut<'brand>,
+    {
+        let mut expired = Vec::new();
+        if now <= self.now {
+            return expired;
+        }
+
+        let ticks = (now - self.now).min(WHEEL_SLOTS as u64);
+        for tick in 1..=ticks {
+            let slot = slot_of(self.now.wrapping_add(tick));
+            let mut pos = 0;
+            while let Some(&index) = self.wheel[slot].get(pos) {
+                let due = self
+                    .list
+                    .get(token, index)
+                    .is_some_and(|e| e.expires_at <= now);
+                if due {
+                    // Swap-removes this record, moving the bucket's last one to `pos`.
+                    expired.push(self.remove_at(token, index));
+                } else {
+                    // Due on a later revolution of the wheel.
+                    pos += 1;
+                }
+            }
+        }
+        self.now = now;
+        expired
+    }
+
+    /// Removes all entries. The clock is left unchanged.
+    pub fn clear<Token>(&mut self, token: &mut Token)
+    where
+        Token: GhostBorrowMut<'brand>,
+    {
+        self.list.clear(token);
+        self.map = BrandedExternalHashMap::with_capacity(self.capacity);
+        self.wheel.iter_mut().for_each(Vec::clear);
+    }
+}
+
+/// The wheel bucket of a deadline.
+#[inline]
+fn slot_of(deadline: u64) -> usize {
+    // Masked to `WHEEL_SLOTS - 1`, so the cast is lossless.
+    (deadline & WHEEL_MASK) as usize
+}
+
+impl<'brand, K, V> fmt::Debug for BrandedTtlCache<'brand, K, V> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("BrandedTtlCache")
+            .field("len", &self.len())
+            .field("capacity", &self.capacity())
+            .field("now", &self.now())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::GhostToken;
+
+    #[test]
+    fn test_ttl_cache_expiration() {
+        GhostToken::new(|mut token| {
+            let mut cache = BrandedTtlCache::new(8, 10);
+            cache.insert(&mut token, "a", 1);
+            cache.insert_with_ttl(&mut token, "b", 2, 3);
+            cache.insert_with_ttl(&mut token, "c", 3, 1000);
+            assert_eq!(cache.ttl_remaining(&token, &"b"), Some(3));
+
+            assert!(cache.advance_to(&mut token, 2).is_empty());
+            assert_eq!(cache.advance_to(&mut token, 3), vec![("b", 2)]);
+            assert_eq!(cache.peek(&token, &"b"), None);
+
+            // Re-inserting resets the TTL, moving the record from t=10 to t=13.
+            assert_eq!(cache.insert(&mut token, "a", 11), Some(1));
+            assert!(cache.advance_to(&mut token, 12).is_empty());
+            assert_eq!(cache.advance_to(&mut token, 13), vec![("a", 11)]);
+
+            // A jump longer than a wheel revolution still finds every due entry.
+            assert_eq!(cache.ttl_remaining(&token, &"c"), Some(987));
+            assert_eq!(cache.advance_to(&mut token, 5000), vec![("c", 3)]);
+            assert!(cache.is_empty());
+        });
+    }
+
+    #[test]
+    fn test_ttl_cache_lru_fallback() {
+        GhostToken::new(|mut token| {
+            let mut cache = BrandedTtlCache::new(2, 100);
+            cache.insert(&mut token, "a", 1);
+            cache.insert(&mut token, "b", 2);
+            assert_eq!(cache.get(&mut token, &"a"), Some(&1)); // "b" is now LRU
+
+            cache.insert(&mut token, "c", 3);
+            assert_eq!(cache.peek(&token, &"b"), None);
+            assert_eq!(cache.len(), 2);
+
+            *cache.get_mut(&mut token, &"c").unwrap() += 10;
+            assert_eq!(cache.remove(&mut token, &"c"), Some(13));
+            assert_eq!(cache.remove(&mut token, &"c"), None);
+
+            // Evicted and removed entries never fire from the wheel.
+            assert_eq!(cache.advance_to(&mut token, 100), vec![("a", 1)]);
+        });
+    }
+
+    #[test]
+    fn test_ttl_cache_wheel_holds_one_record_per_entry() {
+        GhostToken::new(|mut token| {
+            let mut cache = BrandedTtlCache::new(4, 10);
+            let records = |cache: &BrandedTtlCache<'_, u32, u32>| {
+                cache.wheel.iter().map(Vec::len).sum::<usize>()
+            };
+            for round in 0..1000 {
+                // Live keys re-inserted with TTLs landing in many buckets.
+                cache.insert_with_ttl(&mut token, round % 3, round, 1 + u64::from(round % 300));
+                assert_eq!(records(&cache), cache.len());
+            }
+            // Evictions and removals take their records along.
+            for k in 3..10 {
+                cache.insert(&mut token, k, k);
+            }
+            assert_eq!(cache.len(), 4);
+            cache.remove(&mut token, &9);
+            assert_eq!(records(&cache), 3);
+
+            let expired = cache.advance_to(&mut token, 1000);
+            assert_eq!(expired.len(), 3);
+            assert_eq!(records(&cache), 0);
+        });
+    }
+}
@@ -0,0 +1,224 @@
+//! Union-find for connectivity: sequential and lock-free.
+//!
+//! - [`BrandedUnionFind`] is [`BrandedDisjointSet`]: path compression and union by
+//!   rank, token-gated, for single-threaded use such as Kruskal's algorithm.
+//! - [`ConcurrentBrandedUnionFind`] takes `&self` for every operation, so many threads
+//!   can union and query at once, e.g. to compute connected components over edges
+//!   split between workers. It follows Jayanti and Tarjan's concurrent disjoint sets:
+//!   roots are linked by CAS in a fixed random order of the elements, and finds halve
+//!   their paths with CAS.
+
+use core::marker::PhantomData;
+use core::sync::atomic::{AtomicUsize, Ordering};
+
+use super::disjoint_set::BrandedDisjointSet;
+
+/// Union-find with path compression and union by rank; see [`BrandedDisjointSet`].
+pub type BrandedUnionFind<'brand> = BrandedDisjointSet<'brand>;
+
+/// A lock-free union-find over the elements `0..len`.
+///
+/// Union by rank would need the rank and the parent pointer updated together, so
+/// roots are instead linked by a fixed pseudo-random priority of the element ids,
+/// which keeps trees shallow in expectation and gives every link one direction,
+/// ruling out cycles between racing unions.
+pub struct ConcurrentBrandedUnionFind<'brand> {
+    parent: Box<[AtomicUsize]>,
+    sets: AtomicUsize,
+    _brand: PhantomData<fn(&'brand ()) -> &'brand ()>,
+}
+
+/// A bijection on `u64`, used as the linking priority of an element.
+#[inline]
+fn priority(x: usize) -> u64 {
+    let mut z = (x as u64).wrapping_add(0x9e37_79b9_7f4a_7c15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+}
+
+impl ConcurrentBrandedUnionFind<'_> {
+    /// Creates `len` singleton sets, with ids `0..len`.
+    pub fn new(len: usize) -> Self {
+        Self {
+            parent: (0..len).map(AtomicUsize::new).collect(),
+            sets: AtomicUsize::new(len),
+            _brand: PhantomData,
+        }
+    }
+
+    /// Returns the number of elements.
+    pub fn len(&self) -> usize {
+        self.parent.len()
+    }
+
+    /// Returns `true` if there are no elements.
+    pub fn is_empty(&self) -> bool {
+        self.parent.is_empty()
+    }
+
+    /// Returns the number of disjoint sets.
+    pub fn set_count(&self) -> usize {
+        self.sets.load(Ordering::Acquire)
+    }
+
+    /// Finds the representative of the set containing `x`, halving the path to it.
+    ///
+    /// Under concurrent unions the result may stop being the representative right
+    /// away; [`same_set`](Self::same_set) accounts for that.
+    ///
+    /// # Panics
+    /// Panics if `x` is out of bounds.
+    pub fn find(&self, mut x: usize) -> usize {
+        loop {
+            let p = self.parent[x].load(Ordering::Acquire);
+            if p == x {
+                return x;
+            }
+            let gp = self.parent[p].load(Ordering::Acquire);
+            if gp != p {
+                // Losing this race only means another thread shortened the path.
+                let _ = self.parent[x].compare_exchange_weak(
+                    p,
+                    gp,
+                    Ordering::Release,
+                    Ordering::Relaxed,
+                );
+            }
+            x = gp;
+        }
+    }
+
+    /// Unites the sets containing `a` and `b`.
+    /// Returns `true` if they were in different sets, `false` otherwise.
+    ///
+    /// # Panics
+    /// Panics if `a` or `b` is out of bounds.
+    pub fn union(&self, mut a: usize, mut b: usize) -> bool {
+        loop {
+            a = self.find(a);
+            b = self.find(b);
+            if a == b {
+                return false;
+            }
+            let (child, root) = if priority(a) < priority(b) {
+                (a, b)
+            } else {
+                (b, a)
+            };
+            // Fails only if `child` stopped being a root meanwhile; retry from the
+            // new roots.
+            if self.parent[child]
+                .compare_exchange(child, root, Ordering::AcqRel, Ordering::Acquire)
+                .is_ok()
+            {
+                self.sets.fetch_sub(1, Ordering::AcqRel);
+                return true;
+            }
+        }
+    }
+
+    /// Returns `true` if `a` and `b` are in the same set.
+    ///
+    /// # Panics
+    /// Panics if `a` or `b` is out of bounds.
+    pub fn same_set(&self, mut a: usize, mut b: usize) -> bool {
+        loop {
+            a = self.find(a);
+            b = self.find(b);
+            if a == b {
+                return true;
+            }
+            // Different roots mean different sets only if `a` was still a root after
+            // `b` was found.
+            if self.parent[a].load(Ordering::Acquire) == a {
+                return false;
+            }
+        }
+    }
+
+    /// Returns the representative of every element, indexed by element.
+    ///
+    /// Takes `&mut self` so that no union can run meanwhile, which makes the result a
+    /// consistent snapshot.
+    pub fn representatives(&mut self) -> Vec<usize> {
+        (0..self.len()).map(|x| self.find(x)).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::GhostToken;
+    use proptest::prelude::*;
+
+    #[test]
+    fn test_union_find_sequential_alias() {
+        GhostToken::new(|mut token| {
+            let mut uf = BrandedUnionFind::with_singletons(4);
+            assert!(uf.union(&mut token, 0, 3));
+            assert!(!uf.union(&mut token, 3, 0));
+            assert_eq!(uf.find(&token, 3), uf.find(&token, 0));
+            assert_ne!(uf.find(&token, 1), uf.find(&token, 0));
+            assert_eq!(uf.len(), 4);
+        });
+    }
+
+    proptest! {
+        #![proptest_config(ProptestConfig::with_cases(64))]
+
+        #[test]
+        fn test_concurrent_union_find_matches_naive_labels(
+            (n, edges) in (1..200usize).prop_flat_map(|n| {
+                (Just(n), proptest::collection::vec((0..n, 0..n), 0..300))
+            }),
+        ) {
+            let mut uf = ConcurrentBrandedUnionFind::new(n);
+            let merged = AtomicUsize::new(0);
+            std::thread::scope(|s| {
+                for chunk in edges.chunks(edges.len().div_ceil(4).max(1)) {
+                    let (uf, merged) = (&uf, &merged);
+                    s.spawn(move || {
+                        for &(a, b) in chunk {
+                            if uf.union(a, b) {
+                                merged.fetch_add(1, Ordering::Relaxed);
+                            }
+                            assert!(uf.same_set(a, b));
+                        }
+                    });
+                }
+            });
+
+            // Every node carries its set's label; a union relabels one whole set.
+            let mut label: Vec<usize> = (0..n).collect();
+            let mut expected_merges = 0;
+            for &(a, b) in &edges {
+                let (from, to) = (label[b], label[a]);
+                if from != to {
+                    label.iter_mut().filter(|l| **l == from).for_each(|l| *l = to);
+                    expected_merges += 1;
+                }
+            }
+            prop_assert_eq!(merged.load(Ordering::Relaxed), expected_merges);
+            prop_assert_eq!(uf.set_count(), n - expected_merges);
+
+            GhostToken::new(|mut token| {
+                let mut sequential = BrandedUnionFind::with_singletons(n);
+                for &(a, b) in &edges {
+                    sequential.union(&mut token, a, b);
+                }
+                let reps = uf.representatives();
+                for a in 0..n {
+                    for b in 0..n {
+                        let same = label[a] == label[b];
+                        prop_assert_eq!(reps[a] == reps[b], same);
+                        prop_assert_eq!(uf.same_set(a, b), same);
+                        let found = (sequential.find(&token, a), sequential.find(&token, b));
+                        prop_assert_eq!(found.0 == found.1, same);
+                    }
+                }
+                Ok(())
+            })?;
+        }
+    }
+}
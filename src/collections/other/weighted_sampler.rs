@@ -0,0 +1,295 @@
+//! Weighted random sampling over indices `0..n`.
+//!
+//! - [`BrandedAliasTable`] — Vose's alias method. \(O(n)\) to build, \(O(1)\) per
+//!   sample, but the weights are fixed once built. Suited to static transition
+//!   tables such as the per-node neighbor distributions of a biased random walk.
+//! - [`BrandedWeightedSampler`] — weights kept in a
+//!   [`BrandedFenwickTree`](super::BrandedFenwickTree), so single weights can change
+//!   in \(O(\log n)\) and sampling is an \(O(\log n)\) prefix-sum search. Suited to
+//!   simulations whose rates change as they run.
+//!
+//! Both draw from any [`rand::Rng`] supplied by the caller, so runs can be seeded.
+
+use crate::collections::other::BrandedFenwickTree;
+use crate::collections::{BrandedCollection, BrandedVec};
+use crate::token::traits::{GhostBorrow, GhostBorrowMut};
+use rand::Rng;
+
+fn check_weight(weight: f64) {
+    assert!(
+        weight.is_finite() && weight >= 0.0,
+        "weights must be finite and non-negative"
+    );
+}
+
+/// A fixed discrete distribution sampled in \(O(1)\) with Vose's alias method.
+pub struct BrandedAliasTable<'brand> {
+    /// Probability of keeping column `i` rather than taking its alias.
+    prob: BrandedVec<'brand, f64>,
+    alias: BrandedVec<'brand, usize>,
+}
+
+impl<'brand> BrandedAliasTable<'brand> {
+    /// Builds a table where index `i` is drawn with probability `weights[i] / sum`.
+    ///
+    /// # Panics
+    /// Panics if `weights` is empty, contains a negative or non-finite weight, or
+    /// sums to zero.
+    pub fn new(weights: &[f64]) -> Self {
+        assert!(!weights.is_empty(), "weights must not be empty");
+        weights.iter().copied().for_each(check_weight);
+        let total: f64 = weights.iter().sum();
+        assert!(total > 0.0, "weights must not all be zero");
+
+        let n = weights.len();
+        // Exact: a slice of `f64`s cannot get anywhere near 2^53 elements.
+        #[allow(clippy::cast_precision_loss)]
+        let len = n as f64;
+        let mut scaled: Vec<f64> = weights.iter().map(|w| w * len / total).collect();
+        let mut prob = vec![1.0; n];
+        let mut alias: Vec<usize> = (0..n).collect();
+        let (mut small, mut large): (Vec<usize>, Vec<usize>) =
+            (0..n).partition(|&i| scaled[i] < 1.0);
+
+        while let (Some(&s), Some(&l)) = (small.last(), large.last()) {
+            small.pop();
+            prob[s] = scaled[s];
+            alias[s] = l;
+            scaled[l] -= 1.0 - scaled[s];
+            if scaled[l] < 1.0 {
+                large.pop();
+                small.push(l);
+            }
+        }
+        // Whatever is left is 1 up to rounding error.
+
+        Self {
+            prob: prob.into_iter().collect(),
+            alias: alias.into_iter().collect(),
+        }
+    }
+
+    /// Returns the number of outcomes.
+    pub fn len(&self) -> usize {
+        self.prob.len()
+    }
+
+    /// Returns `true` if the table has no outcomes. Never true for a built table.
+    pub fn is_empty(&self) -> bool {
+        self.prob.is_empty()
+    }
+
+    /// Draws an index.
+    ///
+    /// **Time complexity**: \(O(1)\)
+    pub fn sample<Token, R>(&self, token: &Token, rng: &mut R) -> usize
+    where
+        Token: GhostBorrow<'brand>,
+        R: Rng + ?Sized,
+    {
+        let i = rng.gen_range(0..self.len());
+        if rng.gen::<f64>() < *self.prob.borrow(token, i) {
+            i
+        } else {
+            *self.alias.borrow(token, i)
+        }
+    }
+}
+
+impl<'brand> BrandedCollection<'brand> for BrandedAliasTable<'brand> {
+    fn is_empty(&self) -> bool {
+        self.prob.is_empty()
+    }
+
+    fn len(&self) -> usize {
+        self.prob.len()
+    }
+}
+
+/// A discrete distribution whose weights can be updated between samples.
+pub struct BrandedWeightedSampler<'brand> {
+    tree: BrandedFenwickTree<'brand, f64>,
+    weights: Vec<f64>,
+}
+
+impl<'brand> BrandedWeightedSampler<'brand> {
+    /// Creates a sampler with no outcomes.
+    pub fn new() -> Self {
+        Self {
+            tree: BrandedFenwickTree::new(),
+            weights: Vec::new(),
+        }
+    }
+
+    /// Creates a sampler over `weights`, in \(O(n)\).
+    ///
+    /// # Panics
+    /// Panics if a weight is negative or non-finite.
+    pub fn from_weights(weights: &[f64]) -> Self {
+        weights.iter().copied().for_each(check_weight);
+        Self {
+            tree: weights.iter().copied().collect(),
+            weights: weights.to_vec(),
+        }
+    }
+
+    /// Returns the number of outcomes.
+    pub fn len(&self) -> usize {
+        self.weights.len()
+    }
+
+    /// Returns `true` if there are no outcomes.
+    pub fn is_empty(&self) -> bool {
+        self.weights.is_empty()
+    }
+
+    /// Returns the weight of `index`.
+    ///
+    /// # Panics
+    /// Panics if `index` is out of bounds.
+    pub fn weight(&self, index: usize) -> f64 {
+        self.weights[index]
+    }
+
+    /// Returns the sum of all weights.
+    pub fn total_weight<Token>(&self, token: &Token) -> f64
+    where
+        Token: GhostBorrow<'brand>,
+    {
+        if self.is_empty() {
+            0.0
+        } else {
+            self.tree.prefix_sum(token, self.len() - 1)
+        }
+    }
+
+    /// Appends an outcome with `weight`, returning its index.
+    ///
+    /// # Panics
+    /// Panics if `weight` is negative or non-finite.
+    pub fn push<Token>(&mut self, token: &mut Token, weight: f64) -> usize
+    where
+        Token: GhostBorrowMut<'brand>,
+    {
+        check_weight(weight);
+        self.tree.push(token, weight);
+        self.weights.push(weight);
+        self.weights.len() - 1
+    }
+
+    /// Sets the weight of `index`, returning the previous weight.
+    ///
+    /// **Time complexity**: \(O(\log n)\)
+    ///
+    /// # Panics
+    /// Panics if `index` is out of bounds or `weight` is negative or non-finite.
+    pub fn set_weight<Token>(&mut self, token: &mut Token, index: usize, weight: f64) -> f64
+    where
+        Token: GhostBorrowMut<'brand>,
+    {
+        check_weight(weight);
+        let old = core::mem::replace(&mut self.weights[index], weight);
+        self.tree.add(token, index, weight - old);
+        old
+    }
+
+    /// Draws an index with probability proportional to its weight.
+    ///
+    /// Returns `None` if the total weight is zero.
+    ///
+    /// **Time complexity**: \(O(\log n)\)
+    pub fn sample<Token, R>(&self, token: &Token, rng: &mut R) -> Option<usize>
+    where
+        Token: GhostBorrow<'brand>,
+        R: Rng + ?Sized,
+    {
+        let total = self.total_weight(token);
+        if total <= 0.0 {
+            return None;
+        }
+        let index = self.tree.upper_bound(token, rng.gen::<f64>() * total);
+        if index < self.len() {
+            return Some(index);
+        }
+        // Rounding pushed the target past the last prefix sum.
+        self.weights.iter().rposition(|&w| w > 0.0)
+    }
+}
+
+impl<'brand> Default for BrandedWeightedSampler<'brand> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<'brand> BrandedCollection<'brand> for BrandedWeightedSampler<'brand> {
+    fn is_empty(&self) -> bool {
+        self.weights.is_empty()
+    }
+
+    fn len(&self) -> usize {
+        self.weights.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::GhostToken;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    fn frequencies(n: usize, draws: usize, mut draw: impl FnMut() -> usize) -> Vec<f64> {
+        let mut counts = vec![0usize; n];
+        for _ in 0..draws {
+            counts[draw()] += 1;
+        }
+        let to_f64 = |c: usize| f64::from(u32::try_from(c).unwrap());
+        counts.iter().map(|&c| to_f64(c) / to_f64(draws)).collect()
+    }
+
+    #[test]
+    fn test_alias_table_matches_weights() {
+        GhostToken::new(|token| {
+            let weights = [1.0, 0.0, 3.0, 6.0];
+            let table = BrandedAliasTable::new(&weights);
+            let mut rng = StdRng::seed_from_u64(7);
+            let freq = frequencies(4, 100_000, || table.sample(&token, &mut rng));
+            assert_eq!(freq[1], 0.0);
+            for (f, w) in freq.iter().zip(weights) {
+                assert!((f - w / 10.0).abs() < 0.01, "{freq:?}");
+            }
+        });
+    }
+
+    #[test]
+    fn test_weighted_sampler_tracks_updates() {
+        GhostToken::new(|mut token| {
+            let mut sampler = BrandedWeightedSampler::from_weights(&[2.0, 2.0]);
+            assert_eq!(sampler.push(&mut token, 4.0), 2);
+            assert_eq!(sampler.total_weight(&token), 8.0);
+
+            let mut rng = StdRng::seed_from_u64(11);
+            let freq = frequencies(3, 50_000, || sampler.sample(&token, &mut rng).unwrap());
+            for (f, expected) in freq.iter().zip([0.25, 0.25, 0.5]) {
+                assert!((f - expected).abs() < 0.01, "{freq:?}");
+            }
+
+            assert_eq!(sampler.set_weight(&mut token, 2, 0.0), 4.0);
+            assert_eq!(sampler.set_weight(&mut token, 0, 0.0), 2.0);
+            for _ in 0..1000 {
+                assert_eq!(sampler.sample(&token, &mut rng), Some(1));
+            }
+
+            sampler.set_weight(&mut token, 1, 0.0);
+            assert_eq!(sampler.sample(&token, &mut rng), None);
+            assert_eq!(BrandedWeightedSampler::new().sample(&token, &mut rng), None);
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "non-negative")]
+    fn test_alias_table_rejects_negative_weights() {
+        BrandedAliasTable::new(&[1.0, -1.0]);
+    }
+}
@@ -1,3 +1,4 @@
+use crate::alloc::AllocError;
 use crate::GhostCell;
 use crate::token::traits::GhostBorrow;
 use std::path::{Path, PathBuf};
@@ -59,6 +60,17 @@ impl<'brand> BrandedPathBuf<'brand> {
         self.inner.get_mut().reserve(additional);
     }
 
+    /// Tries to reserve capacity for at least `additional` more bytes.
+    ///
+    /// # Errors
+    /// Returns `AllocError` if the capacity overflows or the allocator fails.
+    pub fn try_reserve(&mut self, additional: usize) -> Result<(), AllocError> {
+        self.inner
+            .get_mut()
+            .try_reserve(additional)
+            .map_err(|_| AllocError)
+    }
+
     /// Reserves the minimum capacity for at least `additional` more bytes.
     pub fn reserve_exact(&mut self, additional: usize) {
         self.inner.get_mut().reserve_exact(additional);
@@ -0,0 +1,442 @@
+//! Serde support for branded collections (`serde` feature).
+//!
+//! Branded values can only be read with a token, so the collections do not implement
+//! `Serialize` themselves. [`SerializeWithToken`] serializes a collection given a token,
+//! and [`SerializeWithToken::with_token`] pairs the two into a [`WithToken`] adapter
+//! that any serde serializer accepts.
+//!
+//! The tries need a token to insert, so deserialization goes through unbranded
+//! builders instead: [`SeqBuilder`] and [`MapBuilder`] hold the decoded elements and
+//! turn into whichever collection is wanted once a brand is available.
+//!
+//! Sequences serialize as sequences and maps as maps, in iteration order. Trie keys
+//! are stored as raw bytes, which many formats cannot use as map keys, so
+//! `BrandedRadixTrieMap` and `BrandedArtMap` serialize as a sequence of
+//! `(bytes, value)` pairs; [`MapBuilder`] accepts both shapes.
+//!
+//! ```
+//! use halo::collections::serde::{MapBuilder, SerializeWithToken};
+//! use halo::collections::BrandedIndexMap;
+//! use halo::GhostToken;
+//!
+//! GhostToken::new(|token| {
+//!     let mut map = BrandedIndexMap::new();
+//!     map.insert("b".to_string(), 2);
+//!     map.insert("a".to_string(), 1);
+//!     let json = serde_json::to_string(&map.with_token(&token)).unwrap();
+//!     assert_eq!(json, r#"{"b":2,"a":1}"#);
+//!
+//!     let builder: MapBuilder<String, i32> = serde_json::from_str(&json).unwrap();
+//!     let restored: BrandedIndexMap<'_, String, i32> = builder.into_index_map();
+//!     assert_eq!(restored.get_index(&token, 0), Some((&"b".to_string(), &2)));
+//! });
+//! ```
+
+use crate::alloc::BrandedRc;
+use crate::collections::{
+    BrandedArtMap, BrandedBTreeMap, BrandedHashMap, BrandedIndexMap, BrandedRadixTrieMap,
+    BrandedRadixTrieSet, BrandedVec,
+};
+use crate::token::traits::{GhostBorrow, GhostBorrowMut};
+use core::fmt;
+use core::hash::{BuildHasher, Hash};
+use core::marker::PhantomData;
+use serde::de::{Deserialize, Deserializer, MapAccess, SeqAccess, Visitor};
+use serde::ser::{Serialize, Serializer};
+
+/// A collection whose contents can be serialized given a token for its brand.
+pub trait SerializeWithToken<'brand> {
+    /// Serializes the collection, reading its values through `token`.
+    fn serialize_with<S, Token>(&self, token: &Token, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+        Token: GhostBorrow<'brand>;
+
+    /// Pairs the collection with `token` into a value implementing `Serialize`.
+    fn with_token<'a, Token>(&'a self, token: &'a Token) -> WithToken<'a, 'brand, Self, Token>
+    where
+        Self: Sized,
+        Token: GhostBorrow<'brand>,
+    {
+        WithToken {
+            collection: self,
+            token,
+            _brand: PhantomData,
+        }
+    }
+}
+
+/// A collection borrowed together with a token, serializable with serde.
+pub struct WithToken<'a, 'brand, C, Token> {
+    collection: &'a C,
+    token: &'a Token,
+    _brand: PhantomData<fn(&'brand ()) -> &'brand ()>,
+}
+
+impl<'a, 'brand, C, Token> Serialize for WithToken<'a, 'brand, C, Token>
+where
+    C: SerializeWithToken<'brand>,
+    Token: GhostBorrow<'brand>,
+{
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.collection.serialize_with(self.token, serializer)
+    }
+}
+
+/// Serializes a byte slice with `serialize_bytes`.
+struct Bytes<'a>(&'a [u8]);
+
+impl Serialize for Bytes<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_bytes(self.0)
+    }
+}
+
+/// A trie key, which the trie hands out as a shared branded byte vector.
+struct TrieKey<'a, 'brand, Token> {
+    key: BrandedRc<'brand, BrandedVec<'brand, u8>>,
+    token: &'a Token,
+}
+
+impl<'brand, Token> Serialize for TrieKey<'_, 'brand, Token>
+where
+    Token: GhostBorrow<'brand>,
+{
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_bytes(self.key.as_slice(self.token))
+    }
+}
+
+impl<'brand, T> SerializeWithToken<'brand> for BrandedVec<'brand, T>
+where
+    T: Serialize,
+{
+    fn serialize_with<S, Token>(&self, token: &Token, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+        Token: GhostBorrow<'brand>,
+    {
+        serializer.collect_seq(self.iter(token))
+    }
+}
+
+impl<'brand, K, V, H> SerializeWithToken<'brand> for BrandedHashMap<'brand, K, V, H>
+where
+    K: Eq + Hash + Serialize,
+    V: Serialize,
+    H: BuildHasher,
+{
+    fn serialize_with<S, Token>(&self, token: &Token, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+        Token: GhostBorrow<'brand>,
+    {
+        serializer.collect_map(self.keys().zip(self.values(token)))
+    }
+}
+
+impl<'brand, K, V> SerializeWithToken<'brand> for BrandedBTreeMap<'brand, K, V>
+where
+    K: Ord + Serialize,
+    V: Serialize,
+{
+    fn serialize_with<S, Token>(&self, token: &Token, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+        Token: GhostBorrow<'brand>,
+    {
+        serializer.collect_map(self.iter(token))
+    }
+}
+
+impl<'brand, K, V, H> SerializeWithToken<'brand> for BrandedIndexMap<'brand, K, V, H>
+where
+    K: Serialize,
+    V: Serialize,
+{
+    fn serialize_with<S, Token>(&self, token: &Token, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+        Token: GhostBorrow<'brand>,
+    {
+        serializer.collect_map(self.iter(token))
+    }
+}
+
+impl<'brand, K, V> SerializeWithToken<'brand> for BrandedRadixTrieMap<'brand, K, V>
+where
+    V: Serialize,
+{
+    fn serialize_with<S, Token>(&self, token: &Token, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+        Token: GhostBorrow<'brand>,
+    {
+        serializer.collect_seq(
+            self.iter(token)
+                .map(|(key, value)| (TrieKey { key, token }, value)),
+        )
+    }
+}
+
+impl<'brand, T> SerializeWithToken<'brand> for BrandedRadixTrieSet<'brand, T>
+where
+    T: AsRef<[u8]>,
+{
+    fn serialize_with<S, Token>(&self, token: &Token, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+        Token: GhostBorrow<'brand>,
+    {
+        serializer.collect_seq(self.iter(token).map(|key| TrieKey { key, token }))
+    }
+}
+
+impl<'brand, K, V> SerializeWithToken<'brand> for BrandedArtMap<'brand, K, V>
+where
+    V: Serialize,
+{
+    fn serialize_with<S, Token>(&self, token: &Token, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+        Token: GhostBorrow<'brand>,
+    {
+        serializer.collect_seq(self.iter(token).map(|(key, value)| (Bytes(key), value)))
+    }
+}
+
+/// Deserialized elements of a sequence, ready to become a branded collection.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SeqBuilder<T> {
+    items: Vec<T>,
+}
+
+impl<T> SeqBuilder<T> {
+    /// Returns the elements in serialized order.
+    pub fn into_inner(self) -> Vec<T> {
+        self.items
+    }
+
+    /// Builds a `BrandedVec` of the elements.
+    pub fn into_vec<'brand>(self) -> BrandedVec<'brand, T> {
+        self.items.into_iter().collect()
+    }
+
+    /// Builds a `BrandedRadixTrieSet` of the elements.
+    pub fn into_trie_set<'brand, Token>(self, token: &mut Token) -> BrandedRadixTrieSet<'brand, T>
+    where
+        T: AsRef<[u8]>,
+        Token: GhostBorrowMut<'brand>,
+    {
+        let mut set = BrandedRadixTrieSet::new();
+        for item in self.items {
+            set.insert(token, item);
+        }
+        set
+    }
+}
+
+impl<'de, T> Deserialize<'de> for SeqBuilder<T>
+where
+    T: Deserialize<'de>,
+{
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Vec::deserialize(deserializer).map(|items| Self { items })
+    }
+}
+
+/// Deserialized entries of a map, ready to become a branded collection.
+///
+/// Entries keep their serialized order. Both maps and sequences of `(key, value)`
+/// pairs are accepted.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MapBuilder<K, V> {
+    entries: Vec<(K, V)>,
+}
+
+impl<K, V> MapBuilder<K, V> {
+    /// Returns the entries in serialized order.
+    pub fn into_inner(self) -> Vec<(K, V)> {
+        self.entries
+    }
+
+    /// Builds a `BrandedHashMap`; later entries win over earlier ones with equal keys.
+    pub fn into_hash_map<'brand, H>(self) -> BrandedHashMap<'brand, K, V, H>
+    where
+        K: Eq + Hash,
+        H: BuildHasher + Default,
+    {
+        let mut map = BrandedHashMap::with_hasher(H::default());
+        for (key, value) in self.entries {
+            map.insert(key, value);
+        }
+        map
+    }
+
+    /// Builds a `BrandedBTreeMap`; later entries win over earlier ones with equal keys.
+    pub fn into_btree_map<'brand>(self) -> BrandedBTreeMap<'brand, K, V>
+    where
+        K: Ord,
+    {
+        let mut map = BrandedBTreeMap::new();
+        for (key, value) in self.entries {
+            map.insert(key, value);
+        }
+        map
+    }
+
+    /// Builds a `BrandedIndexMap` in serialized order.
+    pub fn into_index_map<'brand, H>(self) -> BrandedIndexMap<'brand, K, V, H>
+    where
+        K: Eq + Hash,
+        H: BuildHasher + Default,
+    {
+        let mut map = BrandedIndexMap::with_hasher(H::default());
+        for (key, value) in self.entries {
+            map.insert(key, value);
+        }
+        map
+    }
+
+    /// Builds a `BrandedRadixTrieMap`.
+    pub fn into_trie_map<'brand, Token>(
+        self,
+        token: &mut Token,
+    ) -> BrandedRadixTrieMap<'brand, K, V>
+    where
+        K: AsRef<[u8]>,
+        Token: GhostBorrowMut<'brand>,
+    {
+        let mut map = BrandedRadixTrieMap::new();
+        for (key, value) in self.entries {
+            map.insert(token, key, value);
+        }
+        map
+    }
+
+    /// Builds a `BrandedArtMap`.
+    pub fn into_art_map<'brand, Token>(self, token: &mut Token) -> BrandedArtMap<'brand, K, V>
+    where
+        K: AsRef<[u8]>,
+        Token: GhostBorrowMut<'brand>,
+    {
+        let mut map = BrandedArtMap::new();
+        for (key, value) in self.entries {
+            map.insert(token, key, value);
+        }
+        map
+    }
+}
+
+struct MapBuilderVisitor<K, V>(PhantomData<fn() -> (K, V)>);
+
+impl<'de, K, V> Visitor<'de> for MapBuilderVisitor<K, V>
+where
+    K: Deserialize<'de>,
+    V: Deserialize<'de>,
+{
+    type Value = MapBuilder<K, V>;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("a map or a sequence of key-value pairs")
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut access: A) -> Result<Self::Value, A::Error> {
+        let mut entries = Vec::with_capacity(access.size_hint().unwrap_or(0).min(4096));
+        while let Some(entry) = access.next_entry()? {
+            entries.push(entry);
+        }
+        Ok(MapBuilder { entries })
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut access: A) -> Result<Self::Value, A::Error> {
+        let mut entries = Vec::with_capacity(access.size_hint().unwrap_or(0).min(4096));
+        while let Some(entry) = access.next_element()? {
+            entries.push(entry);
+        }
+        Ok(MapBuilder { entries })
+    }
+}
+
+impl<'de, K, V> Deserialize<'de> for MapBuilder<K, V>
+where
+    K: Deserialize<'de>,
+    V: Deserialize<'de>,
+{
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_any(MapBuilderVisitor(PhantomData))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::GhostToken;
+
+    #[test]
+    fn test_serde_round_trips() {
+        GhostToken::new(|token| {
+            let vec: BrandedVec<'_, u32> = [3, 1, 2].into_iter().collect();
+            let json = serde_json::to_string(&vec.with_token(&token)).unwrap();
+            assert_eq!(json, "[3,1,2]");
+            let restored = serde_json::from_str::<SeqBuilder<u32>>(&json)
+                .unwrap()
+                .into_vec();
+            assert_eq!(restored.as_slice(&token), [3, 1, 2]);
+
+            let mut hash = BrandedHashMap::new();
+            hash.insert("x".to_string(), 1);
+            hash.insert("y".to_string(), 2);
+            let json = serde_json::to_string(&hash.with_token(&token)).unwrap();
+            let restored: BrandedHashMap<'_, String, i32> =
+                serde_json::from_str::<MapBuilder<_, _>>(&json)
+                    .unwrap()
+                    .into_hash_map();
+            assert_eq!(restored.len(), 2);
+            assert_eq!(restored.get(&token, "y"), Some(&2));
+
+            let mut btree = BrandedBTreeMap::new();
+            for (k, v) in [(5, 'e'), (1, 'a'), (3, 'c')] {
+                btree.insert(k, v);
+            }
+            let json = serde_json::to_string(&btree.with_token(&token)).unwrap();
+            assert_eq!(json, r#"{"1":"a","3":"c","5":"e"}"#);
+            let restored = serde_json::from_str::<MapBuilder<i32, char>>(&json)
+                .unwrap()
+                .into_btree_map();
+            assert_eq!(restored.get(&token, &3), Some(&'c'));
+        });
+    }
+
+    #[test]
+    fn test_serde_tries_use_byte_keys() {
+        GhostToken::new(|mut token| {
+            let mut trie = BrandedRadixTrieMap::new();
+            trie.insert(&mut token, b"ab".to_vec(), 1);
+            trie.insert(&mut token, b"a".to_vec(), 2);
+            let json = serde_json::to_string(&trie.with_token(&token)).unwrap();
+            let builder: MapBuilder<Vec<u8>, i32> = serde_json::from_str(&json).unwrap();
+            let mut entries = builder.clone().into_inner();
+            entries.sort();
+            assert_eq!(entries, [(b"a".to_vec(), 2), (b"ab".to_vec(), 1)]);
+
+            // The same entries feed an ART map, which serializes in key order.
+            let art = builder.into_art_map(&mut token);
+            let json = serde_json::to_string(&art.with_token(&token)).unwrap();
+            assert_eq!(json, "[[[97],2],[[97,98],1]]");
+            let trie = serde_json::from_str::<MapBuilder<Vec<u8>, i32>>(&json)
+                .unwrap()
+                .into_trie_map(&mut token);
+            assert_eq!(trie.get(&token, b"ab".to_vec()), Some(&1));
+
+            let mut set = BrandedRadixTrieSet::new();
+            set.insert(&mut token, b"key".to_vec());
+            let json = serde_json::to_string(&set.with_token(&token)).unwrap();
+            assert_eq!(json, "[[107,101,121]]");
+            let set = serde_json::from_str::<SeqBuilder<Vec<u8>>>(&json)
+                .unwrap()
+                .into_trie_set(&mut token);
+            assert!(set.contains(&token, b"key".to_vec()));
+        });
+    }
+}
@@ -794,6 +794,105 @@ impl<'brand, K, V> BrandedSkipList<'brand, K, V> {
     }
 }
 
+impl<'brand, K, V> BrandedSkipList<'brand, K, V>
+where
+    K: Ord,
+{
+    /// Checks the structural invariants of the skip list, returning the first violation.
+    ///
+    /// Verifies that the `next_chunk` chain visits every chunk once in ascending key
+    /// order, that chunk lengths and levels are in range, that `len()` matches the
+    /// entry count, and that each index level links exactly the chunks tall enough for
+    /// it, in chain order.
+    ///
+    /// **Time complexity**: \(O(n)\)
+    ///
+    /// # Errors
+    /// Returns a description of the first invariant found violated.
+    pub fn validate_invariants<Token>(&self, token: &Token) -> Result<(), &'static str>
+    where
+        Token: GhostBorrow<'brand>,
+    {
+        let n = self.nodes.len();
+        // Position of each chunk along the `next_chunk` chain.
+        let mut pos = vec![usize::MAX; n];
+        let mut per_level = [0usize; MAX_LEVEL];
+        let mut chunks = 0;
+        let mut entries = 0;
+        let mut prev_last: Option<&K> = None;
+
+        let mut curr = self.head_links[0];
+        while curr.is_some() {
+            let i = curr.index();
+            if i >= n || pos[i] != usize::MAX {
+                return Err("chunk chain is out of bounds or cyclic");
+            }
+            pos[i] = chunks;
+            chunks += 1;
+
+            let node = self.nodes.get(token, i).ok_or("chunk index out of bounds")?;
+            let len = node.len as usize;
+            let level = node.level as usize;
+            if len == 0 || len > CHUNK_SIZE {
+                return Err("chunk length out of range");
+            }
+            if level == 0 || level > self.max_level {
+                return Err("chunk level out of range");
+            }
+            if node.link_offset as usize + level > self.links.len() {
+                return Err("chunk links out of bounds");
+            }
+            // SAFETY: the first `len` keys of a chunk are initialized.
+            let keys = unsafe { std::slice::from_raw_parts(node.keys.as_ptr().cast::<K>(), len) };
+            if keys.windows(2).any(|w| w[0] >= w[1])
+                || prev_last.is_some_and(|last| *last >= keys[0])
+            {
+                return Err("keys are not in strictly ascending order");
+            }
+            prev_last = Some(&keys[len - 1]);
+            entries += len;
+            per_level[..level].iter_mut().for_each(|c| *c += 1);
+            curr = node.next_chunk;
+        }
+        if chunks != n {
+            return Err("chunk is unreachable from the head");
+        }
+        if entries != self.len {
+            return Err("len does not match the number of entries");
+        }
+
+        for (level, &expected) in per_level.iter().enumerate() {
+            let mut linked = 0;
+            let mut last_pos = None;
+            let mut curr = self.head_links[level];
+            while curr.is_some() {
+                let i = curr.index();
+                if i >= n || pos[i] == usize::MAX {
+                    return Err("index link points outside the chunk chain");
+                }
+                if last_pos.is_some_and(|p| p >= pos[i]) {
+                    return Err("index level is not in chunk order");
+                }
+                last_pos = Some(pos[i]);
+                linked += 1;
+
+                let node = self.nodes.get(token, i).ok_or("chunk index out of bounds")?;
+                if node.level as usize <= level {
+                    return Err("chunk is linked above its level");
+                }
+                curr = *self
+                    .links
+                    .get(token, node.link_offset as usize + level)
+                    .ok_or("chunk links out of bounds")?;
+            }
+            if linked != expected {
+                return Err("index level does not link every chunk of that height");
+            }
+        }
+        Ok(())
+    }
+}
+
 impl<'brand, K, V> Default for BrandedSkipList<'brand, K, V> {
     fn default() -> Self {
         Self::new()
@@ -882,4 +981,19 @@ mod tests {
             }
         });
     }
+
+    #[test]
+    fn test_skip_list_validate_invariants() {
+        GhostToken::new(|mut token| {
+            let mut list = BrandedSkipList::with_seed(42);
+            crate::debug_assert_valid!(list, &token);
+
+            let mut x = 11u32;
+            for _ in 0..500 {
+                x = x.wrapping_mul(1_103_515_245).wrapping_add(12_345);
+                list.insert(&mut token, (x >> 16) % 1000, ());
+                assert_eq!(list.validate_invariants(&token), Ok(()));
+            }
+        });
+    }
 }
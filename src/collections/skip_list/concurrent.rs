@@ -0,0 +1,768 @@
+//! `GhostSkipListMap` — a lock-free concurrent ordered map.
+//!
+//! A classic lock-free skip list (Harris-style marked links, as in Herlihy & Shavit's
+//! `LockFreeSkipList`): a node is removed by first taking its value, then marking its
+//! forward links top-down, after which any traversal that meets it unlinks it. Inserts
+//! link a node at the bottom level with one CAS and then build its tower. No operation
+//! takes a lock, so the map suits ordered indexes shared across a thread pool.
+//!
+//! Lookups and scans run inside a read section of the map's grace period; nodes and
+//! values unlinked by writers are retired and freed in batches once no reader can hold
+//! them, so a [`GhostSkipListReadGuard`] can hand out plain `&K`/`&V` references that
+//! stay valid while it lives. Keys and values may borrow data, as nothing outlives the
+//! map.
+//!
+//! Removed and replaced values may still be read concurrently, so `insert` and `remove`
+//! report whether the key was present instead of returning the old value.
+
+use crate::concurrency::atomic::GhostShardedCounter;
+use crate::concurrency::sync::{GracePeriod, ReadSection, Reclaim, RetireList};
+use core::borrow::Borrow;
+use core::cell::Cell;
+use core::cmp::Ordering as CmpOrdering;
+use core::hash::BuildHasher;
+use core::marker::PhantomData;
+use core::ops::{Bound, RangeBounds};
+use core::ptr;
+use core::sync::atomic::{AtomicPtr, AtomicU8, Ordering};
+
+/// Tower heights are capped here; with a branching factor of two this keeps searches
+/// logarithmic up to ~16M entries.
+const MAX_HEIGHT: usize = 24;
+/// Low bit of a forward link: the node owning the link is removed at that level.
+const MARK: usize = 1;
+
+struct Node<K, V> {
+    key: K,
+    /// Null once the entry has been removed (or before it is published).
+    value: AtomicPtr<V>,
+    /// The inserter and the remover each hold one; the last to finish with the node's
+    /// links retires it.
+    refs: AtomicU8,
+    next: Box<[AtomicPtr<Node<K, V>>]>,
+}
+
+enum Retired<K, V> {
+    Value(*mut V),
+    Node(*mut Node<K, V>),
+}
+
+type Links<K, V> = [AtomicPtr<Node<K, V>>];
+
+/// A lock-free ordered map for many threads.
+pub struct GhostSkipListMap<'brand, K, V> {
+    head: [AtomicPtr<Node<K, V>>; MAX_HEIGHT],
+    len: GhostShardedCounter<'brand>,
+    grace: GracePeriod,
+    retired: RetireList<Retired<K, V>>,
+    _marker: PhantomData<(K, V)>,
+}
+
+// SAFETY: keys and values are shared between threads (`Sync`) and dropped by whichever
+// thread retires or reclaims them (`Send`).
+unsafe impl<'brand, K: Send + Sync, V: Send + Sync> Send for GhostSkipListMap<'brand, K, V> {}
+unsafe impl<'brand, K: Send + Sync, V: Send + Sync> Sync for GhostSkipListMap<'brand, K, V> {}
+
+/// A read section over a [`GhostSkipListMap`].
+///
+/// References handed out by the guard stay valid until it is dropped, even if the
+/// entries are removed meanwhile. Holding a guard for long delays the reclamation of
+/// everything removed in the meantime.
+pub struct GhostSkipListReadGuard<'a, 'brand, K, V> {
+    map: &'a GhostSkipListMap<'brand, K, V>,
+    _section: ReadSection<'a>,
+}
+
+#[inline]
+fn is_marked<T>(p: *mut T) -> bool {
+    p.addr() & MARK != 0
+}
+
+#[inline]
+fn unmarked<T>(p: *mut T) -> *mut T {
+    p.map_addr(|a| a & !MARK)
+}
+
+/// Marks `link`, returning `false` if it already was.
+fn mark(link: &AtomicPtr<impl Sized>) -> bool {
+    let mut cur = link.load(Ordering::Acquire);
+    while !is_marked(cur) {
+        match link.compare_exchange_weak(
+            cur,
+            cur.map_addr(|a| a | MARK),
+            Ordering::AcqRel,
+            Ordering::Acquire,
+        ) {
+            Ok(_) => return true,
+            Err(actual) => cur = actual,
+        }
+    }
+    false
+}
+
+/// Draws a tower height with `P(h) = 2^-h`.
+fn random_height() -> usize {
+    std::thread_local! {
+        static STATE: Cell<u64> = Cell::new(
+            std::collections::hash_map::RandomState::new().hash_one(std::thread::current().id())
+                | 1,
+        );
+    }
+    STATE.with(|state| {
+        // xorshift64
+        let mut x = state.get();
+        x ^= x << 13;
+        x ^= x >> 7;
+        x ^= x << 17;
+        state.set(x);
+        (x.trailing_ones() as usize + 1).min(MAX_HEIGHT)
+    })
+}
+
+impl<'brand, K: Ord, V> GhostSkipListMap<'brand, K, V> {
+    /// Creates an empty map.
+    pub fn new() -> Self {
+        Self {
+            head: core::array::from_fn(|_| AtomicPtr::new(ptr::null_mut())),
+            len: GhostShardedCounter::new(),
+            grace: GracePeriod::new(),
+            retired: RetireList::new(),
+            _marker: PhantomData,
+        }
+    }
+
+    /// Returns the number of entries.
+    ///
+    /// The count is striped across threads and summed on demand, so it may be stale
+    /// while writers are active.
+    pub fn len(&self) -> usize {
+        // Decrements wrap, so a sum racing with writers can come out slightly negative.
+        usize::try_from(self.len.sum().cast_signed()).unwrap_or(0)
+    }
+
+    /// Returns `true` if the map holds no entries.
+    pub fn is_empty(&self) -> bool {
+        self.read().iter().next().is_none()
+    }
+
+    /// Enters a read section, for lookups and scans that return references.
+    pub fn read(&self) -> GhostSkipListReadGuard<'_, 'brand, K, V> {
+        GhostSkipListReadGuard {
+            map: self,
+            _section: self.grace.read(),
+        }
+    }
+
+    /// Calls `f` on the value for `key`.
+    pub fn with<Q: ?Sized + Ord, R>(&self, key: &Q, f: impl FnOnce(&V) -> R) -> Option<R>
+    where
+        K: Borrow<Q>,
+    {
+        self.read().get(key).map(f)
+    }
+
+    /// Returns a clone of the value for `key`.
+    pub fn get<Q: ?Sized + Ord>(&self, key: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        V: Clone,
+    {
+        self.with(key, V::clone)
+    }
+
+    /// Returns `true` if the map contains `key`.
+    pub fn contains_key<Q: ?Sized + Ord>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+    {
+        self.with(key, |_| ()).is_some()
+    }
+
+    /// Inserts a key-value pair, returning `true` if `key` was not present.
+    ///
+    /// If it was, the value is replaced and the key kept; the old value is dropped
+    /// once no reader can see it.
+    pub fn insert(&self, key: K, value: V) -> bool {
+        let value = Box::into_raw(Box::new(value));
+        let height = random_height();
+        let node = Box::into_raw(Box::new(Node {
+            key,
+            value: AtomicPtr::new(ptr::null_mut()),
+            refs: AtomicU8::new(2),
+            next: (0..height)
+                .map(|_| AtomicPtr::new(ptr::null_mut()))
+                .collect(),
+        }));
+        // SAFETY: `node` is ours until linked, and reachable nodes stay alive while the
+        // read section lasts.
+        let n = unsafe { &*node };
+        let section = self.grace.read();
+        let (mut preds, mut succs) = loop {
+            let (preds, succs) = self.find(&n.key);
+            // Only the first node with a key can be live; the ones behind it are older
+            // and removed.
+            if let Some(s) = unsafe { succs[0].as_ref() } {
+                if s.key == n.key {
+                    if let Some(old) = replace_value(s, value) {
+                        drop(section);
+                        // SAFETY: `node` was never published.
+                        drop(unsafe { Box::from_raw(node) });
+                        self.retire([Retired::Value(old)]);
+                        return false;
+                    }
+                }
+            }
+            n.value.store(value, Ordering::Relaxed);
+            n.next[0].store(succs[0], Ordering::Relaxed);
+            if preds[0]
+                .compare_exchange(succs[0], node, Ordering::AcqRel, Ordering::Acquire)
+                .is_ok()
+            {
+                break (preds, succs);
+            }
+            n.value.store(ptr::null_mut(), Ordering::Relaxed);
+        };
+        self.len.increment();
+
+        // Build the tower. A remover marks links top-down, so a marked link means the
+        // node is already being removed and building stops.
+        'levels: for level in 1..height {
+            loop {
+                let cur = n.next[level].load(Ordering::Acquire);
+                if is_marked(cur)
+                    || n.next[level]
+                        .compare_exchange(cur, succs[level], Ordering::AcqRel, Ordering::Acquire)
+                        .is_err()
+                {
+                    break 'levels;
+                }
+                if preds[level]
+                    .compare_exchange(succs[level], node, Ordering::AcqRel, Ordering::Acquire)
+                    .is_ok()
+                {
+                    continue 'levels;
+                }
+                (preds, succs) = self.find(&n.key);
+            }
+        }
+        // A removal that raced with building may have missed links made after its
+        // unlinking pass; unlink again now that building is over.
+        if is_marked(n.next[0].load(Ordering::Acquire)) {
+            self.unlink(n);
+        }
+        drop(section);
+        self.release(node);
+        self.retired.try_reclaim(&self.grace);
+        true
+    }
+
+    /// Removes `key`, returning `true` if it was present.
+    ///
+    /// The entry is dropped once no reader can see it.
+    pub fn remove<Q: ?Sized + Ord>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+    {
+        let section = self.grace.read();
+        let (_, succs) = self.find(key);
+        // SAFETY: reachable nodes stay alive while the read section lasts.
+        let Some(n) = (unsafe { succs[0].as_ref() }) else {
+            return false;
+        };
+        if n.key.borrow() != key {
+            return false;
+        }
+        // Taking the value is what removes the entry; only one remover succeeds.
+        let mut value = n.value.load(Ordering::Acquire);
+        loop {
+            if value.is_null() {
+                return false;
+            }
+            match n.value.compare_exchange_weak(
+                value,
+                ptr::null_mut(),
+                Ordering::AcqRel,
+                Ordering::Acquire,
+            ) {
+                Ok(_) => break,
+                Err(actual) => value = actual,
+            }
+        }
+        self.len.sub(1);
+        for link in n.next.iter().rev() {
+            mark(link);
+        }
+        self.unlink(n);
+        drop(section);
+        self.retire([Retired::Value(value)]);
+        self.release(succs[0]);
+        true
+    }
+
+    /// Drops one of the node's two references, retiring it with the last.
+    fn release(&self, node: *mut Node<K, V>) {
+        // SAFETY: the caller holds a reference, which keeps the node alive.
+        if unsafe { &*node }.refs.fetch_sub(1, Ordering::AcqRel) == 1 {
+            self.retire([Retired::Node(node)]);
+        }
+    }
+
+    fn retire(&self, items: impl IntoIterator<Item = Retired<K, V>>) {
+        self.retired.retire(&self.grace, items);
+    }
+
+    /// Finds, at every level, the last link before `key` and the first node at or after
+    /// it, unlinking removed nodes on the way. The caller must be in a read section.
+    #[allow(clippy::type_complexity)]
+    fn find<Q: ?Sized + Ord>(
+        &self,
+        key: &Q,
+    ) -> (
+        [&AtomicPtr<Node<K, V>>; MAX_HEIGHT],
+        [*mut Node<K, V>; MAX_HEIGHT],
+    )
+    where
+        K: Borrow<Q>,
+    {
+        'retry: loop {
+            let mut preds = [&self.head[0]; MAX_HEIGHT];
+            let mut succs = [ptr::null_mut(); MAX_HEIGHT];
+            let mut pred: &Links<K, V> = &self.head;
+            for level in (0..MAX_HEIGHT).rev() {
+                let mut curr = pred[level].load(Ordering::Acquire);
+                if is_marked(curr) {
+                    continue 'retry;
+                }
+                // SAFETY: reachable nodes stay alive while the read section lasts.
+                while let Some(c) = unsafe { curr.as_ref() } {
+                    let succ = c.next[level].load(Ordering::Acquire);
+                    if is_marked(succ) {
+                        if pred[level]
+                            .compare_exchange(
+                                curr,
+                                unmarked(succ),
+                                Ordering::AcqRel,
+                                Ordering::Acquire,
+                            )
+                            .is_err()
+                        {
+                            continue 'retry;
+                        }
+                        curr = unmarked(succ);
+                        continue;
+                    }
+                    if c.key.borrow() < key {
+                        pred = &c.next;
+                        curr = succ;
+                    } else {
+                        break;
+                    }
+                }
+                preds[level] = &pred[level];
+                succs[level] = curr;
+            }
+            return (preds, succs);
+        }
+    }
+
+    /// Unlinks the marked node `target` from every level. The caller must be in a read
+    /// section.
+    fn unlink(&self, target: &Node<K, V>) {
+        'retry: loop {
+            let mut pred: &Links<K, V> = &self.head;
+            for level in (0..MAX_HEIGHT).rev() {
+                let mut link = &pred[level];
+                let mut curr = link.load(Ordering::Acquire);
+                if is_marked(curr) {
+                    continue 'retry;
+                }
+                // `pred` stays before every node with the target's key, so the next level
+                // down starts before them too; `link` walks on through those nodes,
+                // whose order may differ between levels.
+                // SAFETY: reachable nodes stay alive while the read section lasts.
+                while let Some(c) = unsafe { curr.as_ref() } {
+                    let succ = c.next[level].load(Ordering::Acquire);
+                    if is_marked(succ) {
+                        if link
+                            .compare_exchange(
+                                curr,
+                                unmarked(succ),
+                                Ordering::AcqRel,
+                                Ordering::Acquire,
+                            )
+                            .is_err()
+                        {
+                            continue 'retry;
+                        }
+                        curr = unmarked(succ);
+                        continue;
+                    }
+                    match c.key.cmp(&target.key) {
+                        CmpOrdering::Less => pred = &c.next,
+                        CmpOrdering::Equal => {}
+                        CmpOrdering::Greater => break,
+                    }
+                    link = &c.next[level];
+                    curr = succ;
+                }
+            }
+            return;
+        }
+    }
+}
+
+/// Replaces the value of a live node, returning the old one, or `None` if the node has
+/// been removed.
+fn replace_value<K, V>(node: &Node<K, V>, value: *mut V) -> Option<*mut V> {
+    let mut cur = node.value.load(Ordering::Acquire);
+    while !cur.is_null() {
+        match node
+            .value
+            .compare_exchange_weak(cur, value, Ordering::AcqRel, Ordering::Acquire)
+        {
+            Ok(old) => return Some(old),
+            Err(actual) => cur = actual,
+        }
+    }
+    None
+}
+
+impl<'brand, K: Ord, V> Default for GhostSkipListMap<'brand, K, V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<'a, 'brand, K: Ord, V> GhostSkipListReadGuard<'a, 'brand, K, V> {
+    /// Returns the value for `key`.
+    pub fn get<Q: ?Sized + Ord>(&self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+    {
+        let node = self.seek(Bound::Included(key))?;
+        if node.key.borrow() != key {
+            return None;
+        }
+        // SAFETY: the read section keeps the value alive even if it is replaced.
+        unsafe { node.value.load(Ordering::Acquire).as_ref() }
+    }
+
+    /// Returns the first entry.
+    pub fn first(&self) -> Option<(&K, &V)> {
+        self.iter().next()
+    }
+
+    /// Iterates over all entries in key order.
+    pub fn iter(&self) -> GhostSkipListRange<'_, K, V, K, core::ops::RangeFull> {
+        self.range(..)
+    }
+
+    /// Iterates over the entries with keys in `range`, in key order.
+    ///
+    /// Entries inserted or removed concurrently may or may not be seen, but every
+    /// entry present throughout the scan is.
+    pub fn range<Q, R>(&self, range: R) -> GhostSkipListRange<'_, K, V, Q, R>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Ord,
+        R: RangeBounds<Q>,
+    {
+        GhostSkipListRange {
+            next: self.seek(range.start_bound()),
+            range,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Returns the first node not before `bound`, skipping removed nodes.
+    fn seek<Q: ?Sized + Ord>(&self, bound: Bound<&Q>) -> Option<&Node<K, V>>
+    where
+        K: Borrow<Q>,
+    {
+        let before = |node: &Node<K, V>| match bound {
+            Bound::Included(key) => node.key.borrow() < key,
+            Bound::Excluded(key) => node.key.borrow() <= key,
+            Bound::Unbounded => false,
+        };
+        let mut pred: &Links<K, V> = &self.map.head;
+        let mut found = None;
+        for level in (0..MAX_HEIGHT).rev() {
+            let mut curr = unmarked(pred[level].load(Ordering::Acquire));
+            found = None;
+            // SAFETY: reachable nodes stay alive while the read section lasts.
+            while let Some(c) = unsafe { curr.as_ref() } {
+                let succ = c.next[level].load(Ordering::Acquire);
+                if is_marked(succ) {
+                    curr = unmarked(succ);
+                } else if before(c) {
+                    pred = &c.next;
+                    curr = succ;
+                } else {
+                    found = Some(c);
+                    break;
+                }
+            }
+        }
+        found
+    }
+}
+
+/// An iterator over a key range of a [`GhostSkipListMap`], from
+/// [`GhostSkipListReadGuard::range`].
+pub struct GhostSkipListRange<'g, K, V, Q: ?Sized, R> {
+    next: Option<&'g Node<K, V>>,
+    range: R,
+    _marker: PhantomData<fn(&Q)>,
+}
+
+impl<'g, K, V, Q, R> Iterator for GhostSkipListRange<'g, K, V, Q, R>
+where
+    K: Borrow<Q>,
+    Q: ?Sized + Ord,
+    R: RangeBounds<Q>,
+{
+    type Item = (&'g K, &'g V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while let Some(node) = self.next {
+            let past_end = match self.range.end_bound() {
+                Bound::Included(end) => node.key.borrow() > end,
+                Bound::Excluded(end) => node.key.borrow() >= end,
+                Bound::Unbounded => false,
+            };
+            if past_end {
+                self.next = None;
+                return None;
+            }
+            // SAFETY: the guard's read section, which `'g` borrows, keeps reachable nodes
+            // and values alive.
+            unsafe {
+                self.next = unmarked(node.next[0].load(Ordering::Acquire)).as_ref();
+            }
+            if let Some(value) = unsafe { node.value.load(Ordering::Acquire).as_ref() } {
+                return Some((&node.key, value));
+            }
+        }
+        None
+    }
+}
+
+impl<K, V> Reclaim for Retired<K, V> {
+    unsafe fn reclaim(self) {
+        // SAFETY: the pointers come from `Box::into_raw` and are now exclusively ours.
+        unsafe {
+            match self {
+                Retired::Value(value) => drop(Box::from_raw(value)),
+                Retired::Node(node) => {
+                    let node = Box::from_raw(node);
+                    let value = node.value.load(Ordering::Relaxed);
+                    if !value.is_null() {
+                        drop(Box::from_raw(value));
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl<'brand, K, V> Drop for GhostSkipListMap<'brand, K, V> {
+    fn drop(&mut self) {
+        // Removed nodes are retired, and freed when `retired` is dropped after this;
+        // every node still on the bottom level is live.
+        let mut node = unmarked(*self.head[0].get_mut());
+        while !node.is_null() {
+            // SAFETY: `&mut self` rules out readers and writers, so the node is ours.
+            unsafe {
+                let next = unmarked((*node).next[0].load(Ordering::Relaxed));
+                Retired::Node(node).reclaim();
+                node = next;
+            }
+        }
+    }
+}
+
+/// A lock-free ordered set: a [`GhostSkipListMap`] without values.
+pub struct GhostSkipListSet<'brand, K> {
+    map: GhostSkipListMap<'brand, K, ()>,
+}
+
+impl<'brand, K: Ord> GhostSkipListSet<'brand, K> {
+    /// Creates an empty set.
+    pub fn new() -> Self {
+        Self {
+            map: GhostSkipListMap::new(),
+        }
+    }
+
+    /// Returns the number of keys; see [`GhostSkipListMap::len`].
+    pub fn len(&self) -> usize {
+        self.map.len()
+    }
+
+    /// Returns `true` if the set holds no keys.
+    pub fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+
+    /// Adds `key`, returning `true` if it was not present.
+    pub fn insert(&self, key: K) -> bool {
+        self.map.insert(key, ())
+    }
+
+    /// Removes `key`, returning `true` if it was present.
+    pub fn remove<Q: ?Sized + Ord>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+    {
+        self.map.remove(key)
+    }
+
+    /// Returns `true` if the set contains `key`.
+    pub fn contains<Q: ?Sized + Ord>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+    {
+        self.map.contains_key(key)
+    }
+
+    /// Enters a read section; scan keys with `guard.iter()` or `guard.range(..)`.
+    pub fn read(&self) -> GhostSkipListReadGuard<'_, 'brand, K, ()> {
+        self.map.read()
+    }
+}
+
+impl<'brand, K: Ord> Default for GhostSkipListSet<'brand, K> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::GhostToken;
+    use core::sync::atomic::{AtomicBool, AtomicUsize};
+
+    #[test]
+    fn test_skip_list_map_basic() {
+        GhostToken::new(|_token| {
+            let map = GhostSkipListMap::new();
+            assert!(map.is_empty());
+            for i in (0..500).rev() {
+                assert!(map.insert(i * 2, i));
+            }
+            assert!(!map.insert(10, 50));
+            assert_eq!(map.get(&10), Some(50));
+            assert_eq!(map.get(&11), None);
+            assert_eq!(map.with(&4, |v| v + 1), Some(3));
+            assert_eq!(map.len(), 500);
+
+            {
+                let guard = map.read();
+                let keys: Vec<_> = guard.range(100..=110).map(|(k, _)| *k).collect();
+                assert_eq!(keys, [100, 102, 104, 106, 108, 110]);
+                let keys: Vec<_> = guard
+                    .range((Bound::Excluded(101), Bound::Excluded(106)))
+                    .map(|(k, _)| *k)
+                    .collect();
+                assert_eq!(keys, [102, 104]);
+                assert_eq!(guard.first(), Some((&0, &0)));
+                assert_eq!(guard.iter().count(), 500);
+                assert!(guard.iter().map(|(k, _)| k).is_sorted());
+            }
+
+            assert!(map.remove(&100));
+            assert!(!map.remove(&100));
+            assert!(!map.contains_key(&100));
+            assert!(map.insert(100, 7));
+            assert_eq!(map.get(&100), Some(7));
+            for i in 0..500 {
+                map.remove(&(i * 2));
+            }
+            assert!(map.is_empty());
+            assert_eq!(map.len(), 0);
+
+            let set = GhostSkipListSet::new();
+            assert!(set.insert("b"));
+            assert!(set.insert("a"));
+            assert!(!set.insert("a"));
+            assert!(set.contains("a"));
+            assert!(set.remove("b"));
+            assert_eq!(
+                set.read().iter().map(|(k, _)| *k).collect::<Vec<_>>(),
+                ["a"]
+            );
+        });
+    }
+
+    #[test]
+    fn test_skip_list_map_concurrent_range_scans() {
+        // Values borrow a local counter, which the map must not outlive.
+        struct Counted<'a>(usize, &'a AtomicUsize);
+        impl Drop for Counted<'_> {
+            fn drop(&mut self) {
+                self.1.fetch_add(1, Ordering::Relaxed);
+            }
+        }
+
+        let drops = AtomicUsize::new(0);
+        let created = AtomicUsize::new(0);
+        GhostToken::new(|_token| {
+            let map = GhostSkipListMap::new();
+            // Even keys are never removed, so every scan must see all of them in range.
+            for k in (0..256usize).step_by(2) {
+                map.insert(k, Counted(k, &drops));
+                created.fetch_add(1, Ordering::Relaxed);
+            }
+            let done = AtomicBool::new(false);
+            std::thread::scope(|s| {
+                for _ in 0..2 {
+                    s.spawn(|| {
+                        while !done.load(Ordering::Relaxed) {
+                            let guard = map.read();
+                            let mut last = None;
+                            let mut evens = 0;
+                            for (k, v) in guard.range(32..224) {
+                                assert!(last < Some(*k));
+                                assert_eq!(v.0 % 256, *k);
+                                evens += usize::from(k % 2 == 0);
+                                last = Some(*k);
+                            }
+                            assert_eq!(evens, 96);
+                        }
+                    });
+                }
+                let writers: Vec<_> = (0..4usize)
+                    .map(|t| {
+                        let (map, drops, created) = (&map, &drops, &created);
+                        s.spawn(move || {
+                            for i in 0..3000 {
+                                let k = ((t * 3000 + i) * 7 % 128) * 2 + 1;
+                                map.insert(k, Counted(k + 256 * i, drops));
+                                created.fetch_add(1, Ordering::Relaxed);
+                                if i % 2 == 0 {
+                                    map.remove(&k);
+                                }
+                                if i % 5 == 0 {
+                                    let even = k - 1;
+                                    map.insert(even, Counted(even + 256 * i, drops));
+                                    created.fetch_add(1, Ordering::Relaxed);
+                                }
+                            }
+                        })
+                    })
+                    .collect();
+                for w in writers {
+                    w.join().unwrap();
+                }
+                done.store(true, Ordering::Relaxed);
+            });
+            assert_eq!(map.len(), map.read().iter().count());
+        });
+        // Every value created was dropped exactly once, by the time the map was.
+        assert_eq!(
+            drops.load(Ordering::Relaxed),
+            created.load(Ordering::Relaxed)
+        );
+    }
+}
@@ -1,5 +1,11 @@
 pub mod active;
 pub mod branded;
+#[cfg(all(feature = "std", target_has_atomic = "64"))]
+pub mod concurrent;
 
 pub use active::{ActivateSkipList, ActiveSkipList};
 pub use branded::BrandedSkipList;
+#[cfg(all(feature = "std", target_has_atomic = "64"))]
+pub use concurrent::{
+    GhostSkipListMap, GhostSkipListRange, GhostSkipListReadGuard, GhostSkipListSet,
+};
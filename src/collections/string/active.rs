@@ -66,6 +66,36 @@ impl<'a, 'brand> ActiveString<'a, 'brand> {
         self.string.push(ch);
     }
 
+    /// Inserts a string slice at byte offset `idx`.
+    ///
+    /// # Panics
+    /// Panics if `idx` is out of bounds or does not lie on a `char` boundary.
+    #[inline]
+    pub fn insert_str(&mut self, idx: usize, string: &str) {
+        self.string.insert_str(idx, string);
+    }
+
+    /// Inserts a character at byte offset `idx`.
+    ///
+    /// # Panics
+    /// Panics if `idx` is out of bounds or does not lie on a `char` boundary.
+    #[inline]
+    pub fn insert(&mut self, idx: usize, ch: char) {
+        self.string.insert(idx, ch);
+    }
+
+    /// Replaces the bytes in `range` with `replace_with`.
+    ///
+    /// # Panics
+    /// Panics if the range is out of bounds or does not lie on `char` boundaries.
+    #[inline]
+    pub fn replace_range<R>(&mut self, range: R, replace_with: &str)
+    where
+        R: core::ops::RangeBounds<usize>,
+    {
+        self.string.replace_range(range, replace_with);
+    }
+
     /// Truncates the string to `new_len`.
     #[inline]
     pub fn truncate(&mut self, new_len: usize) {
@@ -99,6 +129,20 @@ impl<'a, 'brand> ActiveString<'a, 'brand> {
     }
 }
 
+impl<'a, 'brand> fmt::Write for ActiveString<'a, 'brand> {
+    #[inline]
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.push_str(s);
+        Ok(())
+    }
+
+    #[inline]
+    fn write_char(&mut self, c: char) -> fmt::Result {
+        self.push(c);
+        Ok(())
+    }
+}
+
 impl<'a, 'brand> fmt::Display for ActiveString<'a, 'brand> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         fmt::Display::fmt(self.as_str(), f)
@@ -167,4 +211,22 @@ mod tests {
             assert_eq!(bytes, vec![65, 66, 67]);
         });
     }
+
+    #[test]
+    fn test_active_string_formatting_and_edits() {
+        use std::fmt::Write;
+
+        GhostToken::new(|mut token| {
+            let mut s = BrandedString::new();
+            let mut active = s.activate(&mut token);
+            for i in 0..3 {
+                write!(active, "{i},").unwrap();
+            }
+            active.write_char('x').unwrap();
+            active.replace_range(1..2, ";");
+            active.insert_str(0, "[");
+            active.insert(active.len(), ']');
+            assert_eq!(active.as_str(), "[0;1,2,x]");
+        });
+    }
 }
@@ -0,0 +1,354 @@
+//! `BrandedAhoCorasick` — a multi-pattern matcher over byte streams.
+//!
+//! The automaton is a trie of the patterns stored in a `BrandedVec` node arena, with
+//! failure links (longest proper suffix that is also a trie path) and dictionary links
+//! (nearest suffix state that ends a pattern). Scanning a haystack of length `n` costs
+//! \(O(n + z)\) for `z` reported matches, independently of the number of patterns.
+//!
+//! Matching is streaming: [`find_iter_chunks`](BrandedAhoCorasick::find_iter_chunks)
+//! consumes any iterator of byte chunks and reports matches that straddle chunk
+//! boundaries, with offsets relative to the start of the stream. Every occurrence is
+//! reported, including overlapping ones, in order of their end offset.
+
+use crate::collections::BrandedVec;
+use crate::token::traits::GhostBorrow;
+use core::iter::FusedIterator;
+
+const ROOT: usize = 0;
+const NONE: usize = usize::MAX;
+
+struct Node {
+    /// Outgoing trie edges, sorted by byte.
+    edges: Vec<(u8, usize)>,
+    fail: usize,
+    /// Nearest state on the failure chain (excluding this one) that ends a pattern.
+    dict: usize,
+    /// Pattern ending at this state.
+    pattern: Option<usize>,
+    depth: usize,
+}
+
+impl Node {
+    fn new(depth: usize) -> Self {
+        Self {
+            edges: Vec::new(),
+            fail: ROOT,
+            dict: NONE,
+            pattern: None,
+            depth,
+        }
+    }
+
+    #[inline]
+    fn edge(&self, byte: u8) -> Option<usize> {
+        self.edges
+            .binary_search_by_key(&byte, |&(b, _)| b)
+            .ok()
+            .map(|i| self.edges[i].1)
+    }
+}
+
+/// A reported occurrence of a pattern.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Match {
+    /// Index of the pattern, in the order given to [`BrandedAhoCorasick::new`].
+    pub pattern: usize,
+    /// Offset of the first byte of the occurrence.
+    pub start: usize,
+    /// Offset one past the last byte of the occurrence.
+    pub end: usize,
+}
+
+/// An Aho–Corasick automaton over a fixed set of byte patterns.
+pub struct BrandedAhoCorasick<'brand> {
+    nodes: BrandedVec<'brand, Node>,
+    pattern_count: usize,
+}
+
+impl<'brand> BrandedAhoCorasick<'brand> {
+    /// Builds an automaton for `patterns`.
+    ///
+    /// Duplicate patterns are reported under the index of their first occurrence.
+    ///
+    /// **Time complexity**: \(O(L \log \sigma)\) for total pattern length `L`.
+    ///
+    /// # Panics
+    /// Panics if any pattern is empty.
+    pub fn new<I, P>(patterns: I) -> Self
+    where
+        I: IntoIterator<Item = P>,
+        P: AsRef<[u8]>,
+    {
+        let mut nodes = vec![Node::new(0)];
+        let mut pattern_count = 0;
+        for pattern in patterns {
+            let pattern = pattern.as_ref();
+            assert!(!pattern.is_empty(), "patterns must be non-empty");
+            let mut state = ROOT;
+            for &byte in pattern {
+                state = match nodes[state].edge(byte) {
+                    Some(next) => next,
+                    None => {
+                        let next = nodes.len();
+                        nodes.push(Node::new(nodes[state].depth + 1));
+                        let edges = &mut nodes[state].edges;
+                        let pos = edges.partition_point(|&(b, _)| b < byte);
+                        edges.insert(pos, (byte, next));
+                        next
+                    }
+                };
+            }
+            nodes[state].pattern.get_or_insert(pattern_count);
+            pattern_count += 1;
+        }
+
+        // Breadth-first, so every failure target is finished before it is used.
+        let mut queue = std::collections::VecDeque::new();
+        queue.extend(nodes[ROOT].edges.iter().map(|&(_, child)| child));
+        while let Some(state) = queue.pop_front() {
+            for i in 0..nodes[state].edges.len() {
+                let (byte, child) = nodes[state].edges[i];
+                let mut f = nodes[state].fail;
+                let fail = loop {
+                    if let Some(next) = nodes[f].edge(byte) {
+                        break next;
+                    }
+                    if f == ROOT {
+                        break ROOT;
+                    }
+                    f = nodes[f].fail;
+                };
+                nodes[child].fail = fail;
+                nodes[child].dict = if nodes[fail].pattern.is_some() {
+                    fail
+                } else {
+                    nodes[fail].dict
+                };
+                queue.push_back(child);
+            }
+        }
+
+        Self {
+            nodes: nodes.into_iter().collect(),
+            pattern_count,
+        }
+    }
+
+    /// Returns the number of patterns.
+    pub fn pattern_count(&self) -> usize {
+        self.pattern_count
+    }
+
+    /// Returns the number of automaton states.
+    pub fn state_count(&self) -> usize {
+        self.nodes.len()
+    }
+
+    /// Returns `true` if any pattern occurs in `haystack`.
+    pub fn is_match<Token>(&self, token: &Token, haystack: &[u8]) -> bool
+    where
+        Token: GhostBorrow<'brand>,
+    {
+        self.find_iter(token, haystack).next().is_some()
+    }
+
+    /// Iterates over every occurrence of every pattern in `haystack`.
+    pub fn find_iter<'a, Token>(
+        &'a self,
+        token: &'a Token,
+        haystack: &'a [u8],
+    ) -> FindIter<'a, core::iter::Once<&'a [u8]>>
+    where
+        Token: GhostBorrow<'brand>,
+    {
+        self.find_iter_chunks(token, core::iter::once(haystack))
+    }
+
+    /// Iterates over every occurrence of every pattern in the concatenation of
+    /// `chunks`, pulling chunks lazily.
+    ///
+    /// Offsets are relative to the start of the first chunk, and occurrences spanning
+    /// several chunks are found.
+    pub fn find_iter_chunks<'a, Token, I>(
+        &'a self,
+        token: &'a Token,
+        chunks: I,
+    ) -> FindIter<'a, I::IntoIter>
+    where
+        Token: GhostBorrow<'brand>,
+        I: IntoIterator,
+        I::Item: AsRef<[u8]>,
+    {
+        FindIter {
+            nodes: self.nodes.as_slice(token),
+            chunks: chunks.into_iter(),
+            chunk: None,
+            pos: 0,
+            offset: 0,
+            state: ROOT,
+            pending: NONE,
+        }
+    }
+}
+
+/// Iterator over the matches of a [`BrandedAhoCorasick`] in a chunked stream.
+pub struct FindIter<'a, I>
+where
+    I: Iterator,
+{
+    nodes: &'a [Node],
+    chunks: I,
+    chunk: Option<I::Item>,
+    /// Position within `chunk` of the next byte to consume.
+    pos: usize,
+    /// Stream offset of the start of `chunk`.
+    offset: usize,
+    state: usize,
+    /// Next state on the output chain of `state` to report, or `NONE`.
+    pending: usize,
+}
+
+impl<'a, I> FindIter<'a, I>
+where
+    I: Iterator,
+    I::Item: AsRef<[u8]>,
+{
+    #[inline]
+    fn step(&self, mut state: usize, byte: u8) -> usize {
+        loop {
+            if let Some(next) = self.nodes[state].edge(byte) {
+                return next;
+            }
+            if state == ROOT {
+                return ROOT;
+            }
+            state = self.nodes[state].fail;
+        }
+    }
+
+    /// Stream offset one past the last consumed byte.
+    #[inline]
+    fn end(&self) -> usize {
+        self.offset + self.pos
+    }
+}
+
+impl<'a, I> Iterator for FindIter<'a, I>
+where
+    I: Iterator,
+    I::Item: AsRef<[u8]>,
+{
+    type Item = Match;
+
+    fn next(&mut self) -> Option<Match> {
+        loop {
+            while self.pending != NONE {
+                let node = &self.nodes[self.pending];
+                self.pending = node.dict;
+                if let Some(pattern) = node.pattern {
+                    let end = self.end();
+                    return Some(Match {
+                        pattern,
+                        start: end - node.depth,
+                        end,
+                    });
+                }
+            }
+
+            let byte = match &self.chunk {
+                Some(chunk) if self.pos < chunk.as_ref().len() => chunk.as_ref()[self.pos],
+                _ => {
+                    if let Some(chunk) = &self.chunk {
+                        self.offset += chunk.as_ref().len();
+                    }
+                    self.chunk = Some(self.chunks.next()?);
+                    self.pos = 0;
+                    continue;
+                }
+            };
+            self.pos += 1;
+            self.state = self.step(self.state, byte);
+            self.pending = self.state;
+        }
+    }
+}
+
+impl<'a, I> FusedIterator for FindIter<'a, I>
+where
+    I: FusedIterator,
+    I::Item: AsRef<[u8]>,
+{
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::collections::BrandedString;
+    use crate::GhostToken;
+
+    fn naive(patterns: &[&[u8]], haystack: &[u8]) -> Vec<Match> {
+        let mut out = Vec::new();
+        for end in 1..=haystack.len() {
+            for (i, p) in patterns.iter().enumerate() {
+                if end >= p.len() && &haystack[end - p.len()..end] == *p {
+                    // Duplicates report the first index.
+                    let pattern = patterns.iter().position(|q| q == p).unwrap();
+                    if pattern == i {
+                        out.push(Match {
+                            pattern,
+                            start: end - p.len(),
+                            end,
+                        });
+                    }
+                }
+            }
+        }
+        out
+    }
+
+    fn sorted(mut matches: Vec<Match>) -> Vec<Match> {
+        matches.sort_by_key(|m| (m.end, m.pattern));
+        matches
+    }
+
+    #[test]
+    fn test_aho_corasick_overlapping_matches() {
+        GhostToken::new(|token| {
+            let patterns: [&[u8]; 5] = [b"he", b"she", b"his", b"hers", b"e"];
+            let ac = BrandedAhoCorasick::new(patterns);
+            assert_eq!(ac.pattern_count(), 5);
+
+            let text = BrandedString::from("ushers and his shed");
+            let haystack = text.as_bytes(&token);
+            let found: Vec<Match> = ac.find_iter(&token, haystack).collect();
+            assert_eq!(sorted(found.clone()), sorted(naive(&patterns, haystack)));
+            assert_eq!(
+                found[..4].iter().map(|m| m.pattern).collect::<Vec<_>>(),
+                vec![1, 0, 4, 3]
+            );
+            assert!(ac.is_match(&token, b"xxhisxx"));
+            assert!(!ac.is_match(&token, b"abcd"));
+        });
+    }
+
+    #[test]
+    fn test_aho_corasick_streams_across_chunks() {
+        GhostToken::new(|token| {
+            let patterns: [&[u8]; 4] = [b"ERROR", b"WARN", b"RROR", b"WARN"];
+            let ac = BrandedAhoCorasick::new(patterns);
+            let log = b"ok ERROR: disk; WARN: fan; ERROR!".to_vec();
+            let expected = naive(&patterns, &log);
+
+            for size in [1, 2, 3, 7, 64] {
+                let chunks = log.chunks(size).map(<[u8]>::to_vec);
+                let found: Vec<Match> = ac.find_iter_chunks(&token, chunks).collect();
+                assert_eq!(sorted(found), sorted(expected.clone()), "chunk size {size}");
+            }
+            // Empty chunks are skipped transparently.
+            let chunks: [&[u8]; 4] = [b"", b"ER", b"", b"ROR"];
+            let found: Vec<Match> = ac.find_iter_chunks(&token, chunks).collect();
+            assert_eq!(found.len(), 2);
+            assert_eq!((found[0].start, found[0].end), (0, 5));
+        });
+    }
+}
@@ -20,8 +20,11 @@
 //! to perform bulk operations without per-byte token overhead, while trusting `BrandedVec`'s
 //! branding guarantees.
 
+use crate::alloc::AllocError;
 use crate::collections::BrandedVec;
 use crate::{GhostCell, GhostToken};
+use core::ops::{Bound, RangeBounds};
+use std::fmt;
 use std::mem;
 
 /// A branded string compatible with GhostCell.
@@ -84,23 +87,24 @@ impl<'brand> BrandedString<'brand> {
         self.vec.as_slice(token)
     }
 
+    /// Returns the underlying bytes for structural edits.
+    ///
+    /// Callers must leave the bytes valid UTF-8.
+    #[inline]
+    fn bytes_mut(&mut self) -> &mut Vec<u8> {
+        // SAFETY: `Vec<GhostCell<u8>>` has the same layout as `Vec<u8>`, and `&mut self`
+        // rules out any outstanding token-gated borrow of the bytes.
+        unsafe { &mut *core::ptr::addr_of_mut!(self.vec.inner).cast::<Vec<u8>>() }
+    }
+
     /// Appends a string slice.
     ///
     /// Does NOT require a token because we are owners of the structure and
     /// we are appending new, valid values.
     #[inline]
     pub fn push_str(&mut self, string: &str) {
-        // SAFETY:
-        // 1. `Vec<GhostCell<u8>>` layout == `Vec<u8>`.
-        // 2. Appending valid UTF-8 bytes to a valid UTF-8 string maintains validity.
-        unsafe {
-            // Access the inner vector directly for performance
-            // Cast &mut Vec<GhostCell<u8>> to &mut Vec<u8>
-            let vec_ptr = &mut self.vec.inner as *mut Vec<GhostCell<'brand, u8>>;
-            let vec_u8_ptr = vec_ptr as *mut Vec<u8>;
-            let vec_u8 = &mut *vec_u8_ptr;
-            vec_u8.extend_from_slice(string.as_bytes());
-        }
+        // Appending valid UTF-8 bytes to a valid UTF-8 string maintains validity.
+        self.bytes_mut().extend_from_slice(string.as_bytes());
     }
 
     /// Appends a character.
@@ -112,6 +116,82 @@ impl<'brand> BrandedString<'brand> {
         self.push_str(s);
     }
 
+    /// Appends a string slice, reporting allocation failure instead of aborting.
+    ///
+    /// # Errors
+    /// Returns `AllocError` if growing the buffer fails; the string is left unchanged.
+    #[inline]
+    pub fn try_push_str(&mut self, string: &str) -> Result<(), AllocError> {
+        self.try_reserve(string.len())?;
+        self.push_str(string);
+        Ok(())
+    }
+
+    /// Appends a character, reporting allocation failure instead of aborting.
+    ///
+    /// # Errors
+    /// Returns `AllocError` if growing the buffer fails; the string is left unchanged.
+    #[inline]
+    pub fn try_push(&mut self, ch: char) -> Result<(), AllocError> {
+        let mut buf = [0; 4];
+        self.try_push_str(ch.encode_utf8(&mut buf))
+    }
+
+    /// Inserts a string slice at byte offset `idx`.
+    ///
+    /// Does NOT require a token.
+    ///
+    /// # Panics
+    /// Panics if `idx` is out of bounds or does not lie on a `char` boundary.
+    #[inline]
+    pub fn insert_str(&mut self, idx: usize, string: &str) {
+        self.replace_range(idx..idx, string);
+    }
+
+    /// Inserts a character at byte offset `idx`.
+    ///
+    /// # Panics
+    /// Panics if `idx` is out of bounds or does not lie on a `char` boundary.
+    #[inline]
+    pub fn insert(&mut self, idx: usize, ch: char) {
+        self.insert_str(idx, ch.encode_utf8(&mut [0; 4]));
+    }
+
+    /// Replaces the bytes in `range` with `replace_with`.
+    ///
+    /// Does NOT require a token.
+    ///
+    /// # Panics
+    /// Panics if the range is out of bounds or does not lie on `char` boundaries.
+    pub fn replace_range<R>(&mut self, range: R, replace_with: &str)
+    where
+        R: RangeBounds<usize>,
+    {
+        let start = match range.start_bound() {
+            Bound::Included(&n) => n,
+            Bound::Excluded(&n) => n + 1,
+            Bound::Unbounded => 0,
+        };
+        let end = match range.end_bound() {
+            Bound::Included(&n) => n + 1,
+            Bound::Excluded(&n) => n,
+            Bound::Unbounded => self.len(),
+        };
+        assert!(
+            start <= end && end <= self.len(),
+            "range {start}..{end} out of bounds for string of length {}",
+            self.len()
+        );
+        assert!(
+            self.is_char_boundary_internal(start) && self.is_char_boundary_internal(end),
+            "range {start}..{end} does not lie on char boundaries"
+        );
+        // Swapping whole chars for valid UTF-8 keeps the bytes valid.
+        self.bytes_mut()
+            .splice(start..end, replace_with.bytes())
+            .for_each(drop);
+    }
+
     /// Returns the length of the string.
     ///
     /// Does NOT require a token.
@@ -144,6 +224,15 @@ impl<'brand> BrandedString<'brand> {
         self.vec.reserve(additional);
     }
 
+    /// Tries to reserve capacity for at least `additional` more bytes.
+    ///
+    /// # Errors
+    /// Returns `AllocError` if the capacity overflows or the allocator fails.
+    #[inline]
+    pub fn try_reserve(&mut self, additional: usize) -> Result<(), AllocError> {
+        self.vec.try_reserve(additional)
+    }
+
     /// Clears the string.
     ///
     /// Does NOT require a token.
@@ -202,6 +291,22 @@ impl<'brand> Default for BrandedString<'brand> {
     }
 }
 
+/// Appending needs no token, so a `BrandedString` can be written to directly with
+/// `write!` inside or outside a token scope.
+impl<'brand> fmt::Write for BrandedString<'brand> {
+    #[inline]
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.push_str(s);
+        Ok(())
+    }
+
+    #[inline]
+    fn write_char(&mut self, c: char) -> fmt::Result {
+        self.push(c);
+        Ok(())
+    }
+}
+
 impl<'brand> From<String> for BrandedString<'brand> {
     fn from(s: String) -> Self {
         Self::from_string(s)
@@ -283,4 +388,38 @@ mod tests {
             assert_eq!(s.as_bytes(&token), b"abc");
         });
     }
+
+    #[test]
+    fn test_branded_string_fallible_growth() {
+        let mut s = BrandedString::new();
+        s.try_reserve(8).unwrap();
+        s.try_push_str("ab").unwrap();
+        s.try_push('é').unwrap();
+        assert_eq!(s.try_reserve(usize::MAX), Err(AllocError));
+        GhostToken::new(|token| {
+            assert_eq!(s.as_str(&token), "abé");
+        });
+    }
+
+    #[test]
+    fn test_branded_string_edits_and_write() {
+        use std::fmt::Write;
+
+        let mut s = BrandedString::from("héllo");
+        s.insert_str(0, ">> ");
+        s.insert(s.len(), '!');
+        s.replace_range(3..9, "wörld");
+        write!(s, " {}+{}={}", 1, 2, 1 + 2).unwrap();
+        s.replace_range(..=2, "");
+        GhostToken::new(|token| {
+            assert_eq!(s.as_str(&token), "wörld! 1+2=3");
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "char boundaries")]
+    fn test_branded_string_replace_range_off_boundary() {
+        let mut s = BrandedString::from("héllo");
+        s.replace_range(2..3, "e");
+    }
 }
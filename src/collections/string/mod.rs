@@ -1,5 +1,11 @@
 pub mod active;
+pub mod aho_corasick;
 pub mod branded;
+pub mod rope;
+pub mod suffix_array;
 
 pub use active::{ActivateString, ActiveString};
+pub use aho_corasick::BrandedAhoCorasick;
 pub use branded::BrandedString;
+pub use rope::{BrandedRope, RopeBuilder, RopeSlice};
+pub use suffix_array::BrandedSuffixArray;
@@ -0,0 +1,748 @@
+//! `BrandedRope` — a text buffer of bounded-size chunks for cheap edits in the middle.
+//!
+//! The text is split into UTF-8 chunks of at most [`MAX_CHUNK`] bytes, stored in a
+//! `BrandedVec`, with a table of chunk start offsets. Edits rewrite only the chunks
+//! they touch (splitting a chunk that overflows and merging ones that become small),
+//! so an insertion or removal moves \(O(\text{chunk})\) bytes instead of the whole
+//! text. Locating an offset is a binary search over the start table.
+//!
+//! As with [`BrandedString`](super::BrandedString), structural edits need only
+//! `&mut self`, while reading the text needs a token. Readers never materialize a
+//! `String`: [`chunks`](BrandedRope::chunks) yields the `&str` segments in place, and
+//! [`slice`](BrandedRope::slice) returns a [`RopeSlice`] view over a byte range.
+//!
+//! Bulk edits go through [`splice`](BrandedRope::splice), which replaces a whole range
+//! in one local rewrite, and large texts are assembled with [`RopeBuilder`].
+
+use crate::collections::BrandedVec;
+use crate::GhostBorrow;
+use core::iter::FusedIterator;
+use core::ops::{Bound, Range, RangeBounds};
+
+/// Maximum size of a chunk in bytes.
+pub const MAX_CHUNK: usize = 1024;
+/// Chunks smaller than this are merged with a neighbour after a removal when the
+/// result fits in a chunk.
+const MIN_CHUNK: usize = MAX_CHUNK / 4;
+
+/// Splits `s` into the fewest chunks of at most [`MAX_CHUNK`] bytes, of roughly
+/// equal size and cut at char boundaries.
+fn split_chunks(s: &str) -> impl Iterator<Item = String> + '_ {
+    let mut rest = s;
+    core::iter::from_fn(move || {
+        if rest.is_empty() {
+            return None;
+        }
+        let pieces = rest.len().div_ceil(MAX_CHUNK);
+        let mut at = rest.len().div_ceil(pieces);
+        while !rest.is_char_boundary(at) {
+            at -= 1;
+        }
+        let (head, tail) = rest.split_at(at);
+        rest = tail;
+        Some(head.to_string())
+    })
+}
+
+/// Resolves `range` against a sequence of `len` bytes.
+fn resolve(range: impl RangeBounds<usize>, len: usize) -> Range<usize> {
+    let start = match range.start_bound() {
+        Bound::Included(&s) => s,
+        Bound::Excluded(&s) => s + 1,
+        Bound::Unbounded => 0,
+    };
+    let end = match range.end_bound() {
+        Bound::Included(&e) => e + 1,
+        Bound::Excluded(&e) => e,
+        Bound::Unbounded => len,
+    };
+    assert!(
+        start <= end && end <= len,
+        "range {start}..{end} out of bounds for rope of length {len}"
+    );
+    start..end
+}
+
+/// A chunked UTF-8 text buffer with token-gated reads.
+pub struct BrandedRope<'brand> {
+    chunks: BrandedVec<'brand, String>,
+    /// Byte offset at which each chunk starts.
+    starts: Vec<usize>,
+    len: usize,
+}
+
+impl<'brand> BrandedRope<'brand> {
+    /// Creates an empty rope.
+    pub fn new() -> Self {
+        Self {
+            chunks: BrandedVec::new(),
+            starts: Vec::new(),
+            len: 0,
+        }
+    }
+
+    /// Returns the length in bytes.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if the rope holds no text.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns the number of chunks the text is stored in.
+    #[inline]
+    pub fn chunk_count(&self) -> usize {
+        self.starts.len()
+    }
+
+    /// Recomputes the start offsets of chunks `from..`.
+    fn reindex(&mut self, from: usize) {
+        let chunks = self.chunks.as_mut_slice_exclusive();
+        let mut offset = match from.checked_sub(1) {
+            Some(prev) => self.starts[prev] + chunks[prev].len(),
+            None => 0,
+        };
+        self.starts.truncate(from);
+        for chunk in &chunks[from..] {
+            self.starts.push(offset);
+            offset += chunk.len();
+        }
+        self.len = offset;
+    }
+
+    /// Returns the chunk holding byte `idx` and the offset within it. An offset on a
+    /// chunk boundary resolves to the start of the later chunk, and `len` to the end
+    /// of the last chunk.
+    #[inline]
+    fn locate(&self, idx: usize) -> (usize, usize) {
+        let chunk = self.starts.partition_point(|&s| s <= idx).saturating_sub(1);
+        (chunk, idx - self.starts[chunk])
+    }
+
+    /// Appends `s` to the end of the rope.
+    pub fn push_str(&mut self, s: &str) {
+        let from = self.chunk_count().saturating_sub(1);
+        match self.chunks.as_mut_slice_exclusive().last_mut() {
+            Some(last) if last.len() + s.len() <= MAX_CHUNK => last.push_str(s),
+            _ => {
+                for chunk in split_chunks(s) {
+                    self.chunks.push(chunk);
+                }
+            }
+        }
+        self.reindex(from);
+    }
+
+    /// Inserts `s` at byte offset `idx`.
+    ///
+    /// # Panics
+    /// Panics if `idx` is greater than the length or not on a char boundary.
+    pub fn insert(&mut self, idx: usize, s: &str) {
+        self.splice(idx..idx, s);
+    }
+
+    /// Removes the bytes in `range`.
+    ///
+    /// # Panics
+    /// Panics if the range is out of bounds or does not lie on char boundaries.
+    pub fn remove(&mut self, range: impl RangeBounds<usize>) {
+        self.splice(range, "");
+    }
+
+    /// Replaces the bytes in `range` with `replacement`.
+    ///
+    /// Only the chunks holding the ends of the range are rewritten: the chunks in
+    /// between are dropped whole, the text around the edit is re-split into balanced
+    /// chunks, and undersized chunks at its edges are merged with their neighbours.
+    /// The cost is \(O(\text{chunk} + m + c)\) for a replacement of `m` bytes in a rope
+    /// of `c` chunks, however large the range.
+    ///
+    /// # Panics
+    /// Panics if the range is out of bounds or does not lie on char boundaries.
+    pub fn splice(&mut self, range: impl RangeBounds<usize>, replacement: &str) {
+        let Range { start, end } = resolve(range, self.len);
+        if self.starts.is_empty() {
+            return self.push_str(replacement);
+        }
+        let (i, a) = self.locate(start);
+        let (j, b) = self.locate(end);
+        let chunks = self.chunks.as_mut_slice_exclusive();
+        assert!(
+            chunks[i].is_char_boundary(a) && chunks[j].is_char_boundary(b),
+            "range {start}..{end} does not lie on char boundaries"
+        );
+
+        let new_len = a + replacement.len() + (chunks[j].len() - b);
+        let last = if i == j && new_len <= MAX_CHUNK {
+            chunks[i].replace_range(a..b, replacement);
+            i
+        } else {
+            let mut text = String::with_capacity(new_len);
+            text.push_str(&chunks[i][..a]);
+            text.push_str(replacement);
+            text.push_str(&chunks[j][b..]);
+            let pieces: Vec<String> = split_chunks(&text).collect();
+            let count = pieces.len();
+            self.chunks.splice(i..=j, pieces).for_each(drop);
+            (i + count).saturating_sub(1)
+        };
+        self.merge_around(last);
+        self.merge_around(i);
+        self.reindex(i.saturating_sub(1).min(self.chunks.len()));
+    }
+
+    /// Drops empty chunks at `i` and `i + 1`, then merges chunk `i` with a neighbour
+    /// while either is small and the two fit in one chunk, so that no two small chunks
+    /// are left side by side.
+    fn merge_around(&mut self, mut i: usize) {
+        for k in [i + 1, i] {
+            if self
+                .chunks
+                .get_mut_exclusive(k)
+                .is_some_and(|c| c.is_empty())
+            {
+                self.chunks.remove(k);
+            }
+        }
+        'merged: loop {
+            for k in [i, i.saturating_sub(1)] {
+                let chunks = self.chunks.as_mut_slice_exclusive();
+                if k + 1 >= chunks.len() {
+                    continue;
+                }
+                let (left, right) = (chunks[k].len(), chunks[k + 1].len());
+                if (left < MIN_CHUNK || right < MIN_CHUNK) && left + right <= MAX_CHUNK {
+                    let next = self.chunks.remove(k + 1).into_inner();
+                    self.chunks.as_mut_slice_exclusive()[k].push_str(&next);
+                    i = k;
+                    continue 'merged;
+                }
+            }
+            return;
+        }
+    }
+
+    /// Checks the structural invariants of the rope, returning the first violation.
+    ///
+    /// Verifies that the start table has one entry per chunk and that each entry is
+    /// the sum of the chunk lengths before it, that the cached length is the total,
+    /// and that every chunk holds between 1 and [`MAX_CHUNK`] bytes with no two
+    /// chunks under the merge threshold side by side.
+    ///
+    /// **Time complexity**: \(O(c)\) for `c` chunks
+    ///
+    /// # Errors
+    /// Returns a description of the first invariant found violated.
+    pub fn validate_invariants<Token>(&self, token: &Token) -> Result<(), &'static str>
+    where
+        Token: GhostBorrow<'brand>,
+    {
+        let chunks = self.chunks.as_slice(token);
+        if chunks.len() != self.starts.len() {
+            return Err("start table length differs from the chunk count");
+        }
+        let mut offset = 0;
+        for (chunk, &start) in chunks.iter().zip(&self.starts) {
+            if start != offset {
+                return Err("chunk start offset is stale");
+            }
+            if chunk.is_empty() || chunk.len() > MAX_CHUNK {
+                return Err("chunk length out of range");
+            }
+            offset += chunk.len();
+        }
+        if offset != self.len {
+            return Err("cached length differs from the total chunk length");
+        }
+        if chunks
+            .windows(2)
+            .any(|pair| pair[0].len() < MIN_CHUNK && pair[1].len() < MIN_CHUNK)
+        {
+            return Err("two undersized chunks are left unmerged");
+        }
+        Ok(())
+    }
+
+    /// Returns a view of the bytes in `range`.
+    ///
+    /// # Panics
+    /// Panics if the range is out of bounds. A range that does not lie on char
+    /// boundaries panics when the view is read.
+    pub fn slice(&self, range: impl RangeBounds<usize>) -> RopeSlice<'_, 'brand> {
+        let Range { start, end } = resolve(range, self.len);
+        RopeSlice {
+            rope: self,
+            start,
+            end,
+        }
+    }
+
+    /// Iterates over the text as `&str` chunks, in order.
+    pub fn chunks<'a, Token>(&'a self, token: &'a Token) -> Chunks<'a>
+    where
+        Token: GhostBorrow<'brand>,
+    {
+        self.slice(..).chunks(token)
+    }
+
+    /// Iterates over the bytes of the text.
+    pub fn bytes<'a, Token>(&'a self, token: &'a Token) -> impl Iterator<Item = u8> + 'a
+    where
+        Token: GhostBorrow<'brand>,
+    {
+        self.chunks(token).flat_map(str::bytes)
+    }
+
+    /// Iterates over the chars of the text.
+    pub fn chars<'a, Token>(&'a self, token: &'a Token) -> impl Iterator<Item = char> + 'a
+    where
+        Token: GhostBorrow<'brand>,
+    {
+        self.chunks(token).flat_map(str::chars)
+    }
+
+    /// Iterates over the chars of the text and their byte offsets.
+    pub fn char_indices<'a, Token>(
+        &'a self,
+        token: &'a Token,
+    ) -> impl Iterator<Item = (usize, char)> + 'a
+    where
+        Token: GhostBorrow<'brand>,
+    {
+        self.slice(..).char_indices(token)
+    }
+
+    /// Copies the text into a `String`.
+    pub fn to_string<Token>(&self, token: &Token) -> String
+    where
+        Token: GhostBorrow<'brand>,
+    {
+        self.chunks(token).collect()
+    }
+}
+
+impl<'brand> Default for BrandedRope<'brand> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<'brand> From<&str> for BrandedRope<'brand> {
+    fn from(s: &str) -> Self {
+        let mut rope = Self::new();
+        rope.push_str(s);
+        rope
+    }
+}
+
+impl<'a, 'brand> FromIterator<&'a str> for BrandedRope<'brand> {
+    fn from_iter<I: IntoIterator<Item = &'a str>>(iter: I) -> Self {
+        let mut builder = RopeBuilder::new();
+        for s in iter {
+            builder.push_str(s);
+        }
+        builder.finish()
+    }
+}
+
+/// Builds a [`BrandedRope`] from many fragments in time linear in the total length.
+///
+/// Fragments are packed into full chunks as they arrive and the start table is built
+/// once at the end, so no chunk is ever rewritten or re-split.
+#[derive(Default)]
+pub struct RopeBuilder {
+    chunks: Vec<String>,
+    current: String,
+    len: usize,
+}
+
+impl RopeBuilder {
+    /// Creates an empty builder.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Appends `s`.
+    pub fn push_str(&mut self, mut s: &str) -> &mut Self {
+        self.len += s.len();
+        while !s.is_empty() {
+            let room = MAX_CHUNK - self.current.len();
+            if s.len() <= room {
+                self.current.push_str(s);
+                break;
+            }
+            let mut at = room;
+            while !s.is_char_boundary(at) {
+                at -= 1;
+            }
+            self.current.push_str(&s[..at]);
+            s = &s[at..];
+            let full = core::mem::replace(&mut self.current, String::with_capacity(MAX_CHUNK));
+            self.chunks.push(full);
+        }
+        self
+    }
+
+    /// Appends `ch`.
+    pub fn push(&mut self, ch: char) -> &mut Self {
+        self.push_str(ch.encode_utf8(&mut [0; 4]))
+    }
+
+    /// Returns the number of bytes appended so far.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if nothing has been appended.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Finishes the rope.
+    pub fn finish<'brand>(mut self) -> BrandedRope<'brand> {
+        if !self.current.is_empty() {
+            self.chunks.push(self.current);
+        }
+        let mut offset = 0;
+        let starts = self
+            .chunks
+            .iter()
+            .map(|chunk| {
+                offset += chunk.len();
+                offset - chunk.len()
+            })
+            .collect();
+        BrandedRope {
+            chunks: self.chunks.into_iter().collect(),
+            starts,
+            len: self.len,
+        }
+    }
+}
+
+/// A borrowed view of a byte range of a [`BrandedRope`].
+#[derive(Clone, Copy)]
+pub struct RopeSlice<'a, 'brand> {
+    rope: &'a BrandedRope<'brand>,
+    start: usize,
+    end: usize,
+}
+
+impl<'a, 'brand> RopeSlice<'a, 'brand> {
+    /// Returns the length of the view in bytes.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.end - self.start
+    }
+
+    /// Returns `true` if the view is empty.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.start == self.end
+    }
+
+    /// Returns a view of `range`, relative to the start of this view.
+    ///
+    /// # Panics
+    /// Panics if the range is out of bounds.
+    pub fn slice(&self, range: impl RangeBounds<usize>) -> RopeSlice<'a, 'brand> {
+        let Range { start, end } = resolve(range, self.len());
+        RopeSlice {
+            rope: self.rope,
+            start: self.start + start,
+            end: self.start + end,
+        }
+    }
+
+    /// Iterates over the text of the view as `&str` chunks, in order.
+    ///
+    /// # Panics
+    /// Panics if the view does not start and end on char boundaries.
+    pub fn chunks<Token>(&self, token: &'a Token) -> Chunks<'a>
+    where
+        Token: GhostBorrow<'brand>,
+    {
+        let chunks = self.rope.chunks.as_slice(token);
+        if self.is_empty() {
+            return Chunks {
+                chunks: &[],
+                offset: 0,
+                remaining: 0,
+            };
+        }
+        let (first, offset) = self.rope.locate(self.start);
+        Chunks {
+            chunks: &chunks[first..],
+            offset,
+            remaining: self.len(),
+        }
+    }
+
+    /// Iterates over the bytes of the view.
+    pub fn bytes<Token>(&self, token: &'a Token) -> impl Iterator<Item = u8> + 'a
+    where
+        Token: GhostBorrow<'brand>,
+    {
+        self.chunks(token).flat_map(str::bytes)
+    }
+
+    /// Iterates over the chars of the view.
+    pub fn chars<Token>(&self, token: &'a Token) -> impl Iterator<Item = char> + 'a
+    where
+        Token: GhostBorrow<'brand>,
+    {
+        self.chunks(token).flat_map(str::chars)
+    }
+
+    /// Iterates over the chars of the view and their byte offsets from its start.
+    pub fn char_indices<Token>(&self, token: &'a Token) -> impl Iterator<Item = (usize, char)> + 'a
+    where
+        Token: GhostBorrow<'brand>,
+    {
+        self.chunks(token)
+            .scan(0, |base, chunk| {
+                let offset = *base;
+                *base += chunk.len();
+                Some(chunk.char_indices().map(move |(i, c)| (offset + i, c)))
+            })
+            .flatten()
+    }
+
+    /// Copies the text of the view into a `String`.
+    pub fn to_string<Token>(&self, token: &'a Token) -> String
+    where
+        Token: GhostBorrow<'brand>,
+    {
+        self.chunks(token).collect()
+    }
+}
+
+/// Iterator over the `&str` chunks of a [`BrandedRope`] or [`RopeSlice`].
+pub struct Chunks<'a> {
+    chunks: &'a [String],
+    /// Offset into the first remaining chunk.
+    offset: usize,
+    /// Bytes left to yield.
+    remaining: usize,
+}
+
+impl<'a> Iterator for Chunks<'a> {
+    type Item = &'a str;
+
+    fn next(&mut self) -> Option<&'a str> {
+        if self.remaining == 0 {
+            return None;
+        }
+        let (first, rest) = self.chunks.split_first()?;
+        let take = (first.len() - self.offset).min(self.remaining);
+        let chunk = &first[self.offset..self.offset + take];
+        self.chunks = rest;
+        self.offset = 0;
+        self.remaining -= take;
+        Some(chunk)
+    }
+}
+
+impl FusedIterator for Chunks<'_> {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::GhostToken;
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
+
+    fn text(n: usize) -> String {
+        (0..n).map(|i| ["a", "é", "水", "🦀"][i % 4]).collect()
+    }
+
+    #[test]
+    fn test_rope_iterators_match_string() {
+        GhostToken::new(|token| {
+            let s = text(3000);
+            let rope = BrandedRope::from(s.as_str());
+            assert_eq!(rope.len(), s.len());
+            assert!(rope.chunk_count() > 1);
+            assert!(rope.chunks(&token).all(|c| c.len() <= MAX_CHUNK));
+            assert_eq!(rope.to_string(&token), s);
+            assert!(rope.bytes(&token).eq(s.bytes()));
+            assert!(rope.chars(&token).eq(s.chars()));
+            assert!(rope.char_indices(&token).eq(s.char_indices()));
+
+            // Views across chunk boundaries, nested views, and empty views.
+            let (a, b) = (
+                s.char_indices().nth(250).unwrap().0,
+                s.char_indices().nth(2500).unwrap().0,
+            );
+            let view = rope.slice(a..b);
+            assert_eq!(view.len(), b - a);
+            assert_eq!(view.to_string(&token), &s[a..b]);
+            assert!(view.char_indices(&token).eq(s[a..b].char_indices()));
+            // The view starts on '水' (3 bytes) and ends after '🦀' (4 bytes).
+            let inner = view.slice(3..view.len() - 4);
+            assert_eq!(inner.to_string(&token), &s[a + 3..b - 4]);
+            assert_eq!(rope.slice(a..a).chunks(&token).count(), 0);
+            assert_eq!(rope.slice(..).to_string(&token), s);
+        });
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_rope_slice_off_char_boundary_panics_on_read() {
+        GhostToken::new(|token| {
+            let rope = BrandedRope::from("é");
+            let view = rope.slice(1..);
+            view.chunks(&token).for_each(drop);
+        });
+    }
+
+    #[test]
+    fn test_rope_edits_match_string() {
+        GhostToken::new(|token| {
+            let mut rng = StdRng::seed_from_u64(11);
+            let mut rope = BrandedRope::new();
+            let mut model = String::new();
+            let boundary = |s: &str, rng: &mut StdRng| {
+                let mut i = rng.gen_range(0..=s.len());
+                while !s.is_char_boundary(i) {
+                    i -= 1;
+                }
+                i
+            };
+            for step in 0..2000 {
+                if rng.gen_bool(0.6) || model.is_empty() {
+                    let piece = text(rng.gen_range(0..if step % 50 == 0 { 1500 } else { 40 }));
+                    let at = boundary(&model, &mut rng);
+                    rope.insert(at, &piece);
+                    model.insert_str(at, &piece);
+                } else {
+                    let a = boundary(&model, &mut rng);
+                    let b = a + boundary(&model[a..], &mut rng);
+                    rope.remove(a..b);
+                    model.replace_range(a..b, "");
+                }
+                assert_eq!(rope.len(), model.len());
+                crate::debug_assert_valid!(rope, &token);
+            }
+            assert_eq!(rope.to_string(&token), model);
+            assert!(rope
+                .chunks(&token)
+                .all(|c| !c.is_empty() && c.len() <= MAX_CHUNK));
+
+            rope.push_str("tail");
+            model.push_str("tail");
+            rope.remove(..);
+            assert!(rope.is_empty());
+            assert_eq!(rope.chunk_count(), 0);
+        });
+    }
+
+    #[test]
+    fn test_rope_builder_packs_fragments() {
+        GhostToken::new(|token| {
+            let fragments: Vec<String> = (0..5000).map(|i| text(i % 7)).collect();
+            let expected: String = fragments.concat();
+
+            let mut builder = RopeBuilder::new();
+            for f in &fragments {
+                builder.push_str(f);
+            }
+            builder.push('!');
+            assert_eq!(builder.len(), expected.len() + 1);
+            let rope = builder.finish();
+            assert_eq!(rope.len(), expected.len() + 1);
+            assert_eq!(rope.to_string(&token), expected.clone() + "!");
+            // Every chunk but the last is filled to within one char of capacity.
+            let sizes: Vec<usize> = rope.chunks(&token).map(str::len).collect();
+            assert!(sizes[..sizes.len() - 1]
+                .iter()
+                .all(|&n| n > MAX_CHUNK - 4 && n <= MAX_CHUNK));
+
+            let collected: BrandedRope = fragments.iter().map(String::as_str).collect();
+            assert_eq!(collected.to_string(&token), expected);
+            assert!(RopeBuilder::new().finish().is_empty());
+        });
+    }
+
+    #[test]
+    fn test_rope_splice() {
+        GhostToken::new(|token| {
+            let s = text(20_000);
+            let mut rope = BrandedRope::from(s.as_str());
+            let mut model = s.clone();
+            let chunks_before = rope.chunk_count();
+
+            // Replacing most of the text rewrites only the two edge chunks.
+            let (a, b) = (
+                model.char_indices().nth(3).unwrap().0,
+                model.char_indices().nth(19_990).unwrap().0,
+            );
+            rope.splice(a..b, "middle");
+            model.replace_range(a..b, "middle");
+            assert_eq!(rope.to_string(&token), model);
+            assert!(rope.chunk_count() < chunks_before);
+            assert!(rope.chunk_count() <= 2);
+
+            let big = text(5000);
+            rope.splice(a..a, &big);
+            model.replace_range(a..a, &big);
+            assert_eq!(rope.to_string(&token), model);
+            rope.splice(.., "");
+            model.clear();
+            assert!(rope.is_empty());
+            rope.splice(.., "fresh");
+            assert_eq!(rope.to_string(&token), "fresh");
+
+            let mut rng = StdRng::seed_from_u64(5);
+            model = "fresh".to_string();
+            for _ in 0..1000 {
+                let mut a = rng.gen_range(0..=model.len());
+                while !model.is_char_boundary(a) {
+                    a -= 1;
+                }
+                let mut b = a + rng.gen_range(0..=(model.len() - a).min(3000));
+                while !model.is_char_boundary(b) {
+                    b -= 1;
+                }
+                let piece = text(rng.gen_range(0..2500));
+                rope.splice(a..b, &piece);
+                model.replace_range(a..b, &piece);
+                assert_eq!(rope.len(), model.len());
+                crate::debug_assert_valid!(rope, &token);
+            }
+            assert_eq!(rope.to_string(&token), model);
+            assert!(rope
+                .chunks(&token)
+                .all(|c| !c.is_empty() && c.len() <= MAX_CHUNK));
+        });
+    }
+
+    #[test]
+    fn test_rope_validate_invariants() {
+        GhostToken::new(|token| {
+            let mut rope = BrandedRope::from("x".repeat(8000).as_str());
+            assert_eq!(rope.validate_invariants(&token), Ok(()));
+            // Removals across chunk boundaries leave small remnants that must merge.
+            for k in 1..40 {
+                let a = k * 997 % rope.len();
+                rope.remove(a..(a + 150).min(rope.len()));
+                rope.insert(a / 2, "yy");
+                assert_eq!(rope.validate_invariants(&token), Ok(()));
+            }
+
+            rope.len += 1;
+            assert!(rope.validate_invariants(&token).is_err());
+            rope.len -= 1;
+            rope.starts[0] += 1;
+            assert!(rope.validate_invariants(&token).is_err());
+            rope.starts[0] -= 1;
+            rope.chunks.push(String::new());
+            rope.starts.push(rope.len);
+            assert_eq!(rope.validate_invariants(&token), Err("chunk length out of range"));
+        });
+    }
+}
@@ -0,0 +1,356 @@
+//! `BrandedSuffixArray` — a suffix array with LCP array over a byte text.
+//!
+//! Construction uses SA-IS (induced sorting), which is linear in the text length; the
+//! LCP array is computed with Kasai's algorithm, also in linear time. The text, suffix
+//! array and LCP array are stored in `BrandedVec`s, so reads are token-gated like the
+//! [`BrandedString`] the index is usually built from.
+//!
+//! Substring search binary-searches the suffix array: all occurrences of a pattern of
+//! length `m` are found in \(O(m \log n)\) and returned as a contiguous run of the
+//! suffix array, without allocating.
+
+use crate::collections::{BrandedCollection, BrandedString, BrandedVec};
+use crate::token::traits::GhostBorrow;
+use crate::GhostToken;
+use core::ops::Range;
+
+const NONE: usize = usize::MAX;
+
+/// A suffix array, LCP array and copy of the text they index.
+pub struct BrandedSuffixArray<'brand> {
+    text: BrandedVec<'brand, u8>,
+    sa: BrandedVec<'brand, usize>,
+    lcp: BrandedVec<'brand, usize>,
+}
+
+impl<'brand> BrandedSuffixArray<'brand> {
+    /// Indexes a copy of `text`.
+    ///
+    /// **Time complexity**: \(O(n)\)
+    pub fn from_bytes(text: &[u8]) -> Self {
+        let symbols: Vec<usize> = text.iter().map(|&b| usize::from(b)).collect();
+        let sa = sa_is(&symbols, 255);
+        let lcp = kasai(text, &sa);
+        Self {
+            text: text.iter().copied().collect(),
+            sa: sa.into_iter().collect(),
+            lcp: lcp.into_iter().collect(),
+        }
+    }
+
+    /// Indexes the current contents of `string`.
+    pub fn from_branded_string(string: &BrandedString<'brand>, token: &GhostToken<'brand>) -> Self {
+        Self::from_bytes(string.as_bytes(token))
+    }
+
+    /// Returns the length of the indexed text.
+    pub fn len(&self) -> usize {
+        self.text.len()
+    }
+
+    /// Returns `true` if the indexed text is empty.
+    pub fn is_empty(&self) -> bool {
+        self.text.is_empty()
+    }
+
+    /// Returns the indexed text.
+    pub fn text<'a, Token>(&'a self, token: &'a Token) -> &'a [u8]
+    where
+        Token: GhostBorrow<'brand>,
+    {
+        self.text.as_slice(token)
+    }
+
+    /// Returns the start offsets of all suffixes, in lexicographic order.
+    pub fn suffix_array<'a, Token>(&'a self, token: &'a Token) -> &'a [usize]
+    where
+        Token: GhostBorrow<'brand>,
+    {
+        self.sa.as_slice(token)
+    }
+
+    /// Returns the LCP array: entry `i` is the length of the longest common prefix of
+    /// the suffixes at ranks `i - 1` and `i`, and entry `0` is `0`.
+    pub fn lcp<'a, Token>(&'a self, token: &'a Token) -> &'a [usize]
+    where
+        Token: GhostBorrow<'brand>,
+    {
+        self.lcp.as_slice(token)
+    }
+
+    /// Returns the range of suffix-array ranks whose suffixes start with `pattern`.
+    fn rank_range<Token>(&self, token: &Token, pattern: &[u8]) -> Range<usize>
+    where
+        Token: GhostBorrow<'brand>,
+    {
+        let text = self.text.as_slice(token);
+        let sa = self.sa.as_slice(token);
+        let prefix = |p: usize| &text[p..(p + pattern.len()).min(text.len())];
+        let start = sa.partition_point(|&p| prefix(p) < pattern);
+        let end = start + sa[start..].partition_point(|&p| prefix(p) == pattern);
+        start..end
+    }
+
+    /// Returns the start offsets of every occurrence of `pattern`, in suffix order
+    /// (not text order).
+    ///
+    /// An empty pattern matches at every offset.
+    ///
+    /// **Time complexity**: \(O(m \log n)\)
+    pub fn find_all<'a, Token>(&'a self, token: &'a Token, pattern: &[u8]) -> &'a [usize]
+    where
+        Token: GhostBorrow<'brand>,
+    {
+        let range = self.rank_range(token, pattern);
+        &self.sa.as_slice(token)[range]
+    }
+
+    /// Returns the number of occurrences of `pattern`.
+    pub fn count<Token>(&self, token: &Token, pattern: &[u8]) -> usize
+    where
+        Token: GhostBorrow<'brand>,
+    {
+        self.rank_range(token, pattern).len()
+    }
+
+    /// Returns `true` if `pattern` occurs in the text.
+    pub fn contains<Token>(&self, token: &Token, pattern: &[u8]) -> bool
+    where
+        Token: GhostBorrow<'brand>,
+    {
+        !self.rank_range(token, pattern).is_empty()
+    }
+
+    /// Returns the position of the longest substring occurring at least twice, or `None`
+    /// if no byte repeats.
+    ///
+    /// **Time complexity**: \(O(n)\)
+    pub fn longest_repeated_substring<Token>(&self, token: &Token) -> Option<Range<usize>>
+    where
+        Token: GhostBorrow<'brand>,
+    {
+        let lcp = self.lcp.as_slice(token);
+        let (rank, &len) = lcp.iter().enumerate().max_by_key(|&(_, &l)| l)?;
+        if len == 0 {
+            return None;
+        }
+        let start = self.sa.as_slice(token)[rank];
+        Some(start..start + len)
+    }
+}
+
+impl<'brand> BrandedCollection<'brand> for BrandedSuffixArray<'brand> {
+    fn is_empty(&self) -> bool {
+        self.text.is_empty()
+    }
+
+    fn len(&self) -> usize {
+        self.text.len()
+    }
+}
+
+/// Suffix array of `s` over the alphabet `0..=upper`, by induced sorting.
+fn sa_is(s: &[usize], upper: usize) -> Vec<usize> {
+    let n = s.len();
+    match n {
+        0 => return Vec::new(),
+        1 => return vec![0],
+        2 => return if s[0] < s[1] { vec![0, 1] } else { vec![1, 0] },
+        _ => {}
+    }
+
+    // `ls[i]`: suffix `i` is S-type (smaller than suffix `i + 1`).
+    let mut ls = vec![false; n];
+    for i in (0..n - 1).rev() {
+        ls[i] = if s[i] == s[i + 1] {
+            ls[i + 1]
+        } else {
+            s[i] < s[i + 1]
+        };
+    }
+
+    // Bucket starts: `sum_l[c]` for L-type suffixes, `sum_s[c]` for S-type ones.
+    let mut sum_l = vec![0; upper + 1];
+    let mut sum_s = vec![0; upper + 1];
+    for i in 0..n {
+        if ls[i] {
+            sum_l[s[i] + 1] += 1;
+        } else {
+            sum_s[s[i]] += 1;
+        }
+    }
+    for c in 0..=upper {
+        sum_s[c] += sum_l[c];
+        if c < upper {
+            sum_l[c + 1] += sum_s[c];
+        }
+    }
+
+    let is_lms = |i: usize| i > 0 && !ls[i - 1] && ls[i];
+    let lms: Vec<usize> = (1..n).filter(|&i| is_lms(i)).collect();
+    let mut lms_index = vec![NONE; n];
+    for (k, &i) in lms.iter().enumerate() {
+        lms_index[i] = k;
+    }
+
+    let mut sa = vec![NONE; n];
+    induce(s, &ls, &sum_l, &sum_s, &lms, &mut sa);
+
+    if !lms.is_empty() {
+        // Name LMS substrings in sorted order, then sort them recursively.
+        let sorted_lms: Vec<usize> = sa
+            .iter()
+            .copied()
+            .filter(|&v| v != NONE && is_lms(v))
+            .collect();
+        let m = lms.len();
+        let mut rec_s = vec![0; m];
+        let mut rec_upper = 0;
+        for w in sorted_lms.windows(2) {
+            let (mut l, mut r) = (w[0], w[1]);
+            let end_l = lms.get(lms_index[l] + 1).copied().unwrap_or(n);
+            let end_r = lms.get(lms_index[r] + 1).copied().unwrap_or(n);
+            let mut same = end_l - l == end_r - r;
+            if same {
+                while l < end_l && s[l] == s[r] {
+                    l += 1;
+                    r += 1;
+                }
+                same = l < n && r < n && s[l] == s[r];
+            }
+            if !same {
+                rec_upper += 1;
+            }
+            rec_s[lms_index[w[1]]] = rec_upper;
+        }
+
+        let rec_sa = sa_is(&rec_s, rec_upper);
+        let sorted_lms: Vec<usize> = rec_sa.iter().map(|&k| lms[k]).collect();
+        induce(s, &ls, &sum_l, &sum_s, &sorted_lms, &mut sa);
+    }
+    sa
+}
+
+/// Places the LMS suffixes `lms` (in order) into their buckets, then induces the
+/// L-type suffixes left to right and the S-type suffixes right to left.
+fn induce(
+    s: &[usize],
+    ls: &[bool],
+    sum_l: &[usize],
+    sum_s: &[usize],
+    lms: &[usize],
+    sa: &mut [usize],
+) {
+    let n = s.len();
+    sa.fill(NONE);
+    let mut buf = sum_s.to_vec();
+    for &d in lms {
+        sa[buf[s[d]]] = d;
+        buf[s[d]] += 1;
+    }
+
+    buf.copy_from_slice(sum_l);
+    sa[buf[s[n - 1]]] = n - 1;
+    buf[s[n - 1]] += 1;
+    for i in 0..n {
+        let v = sa[i];
+        if v != NONE && v >= 1 && !ls[v - 1] {
+            sa[buf[s[v - 1]]] = v - 1;
+            buf[s[v - 1]] += 1;
+        }
+    }
+
+    buf.copy_from_slice(sum_l);
+    for i in (0..n).rev() {
+        let v = sa[i];
+        if v != NONE && v >= 1 && ls[v - 1] {
+            buf[s[v - 1] + 1] -= 1;
+            sa[buf[s[v - 1] + 1]] = v - 1;
+        }
+    }
+}
+
+/// LCP array by Kasai's algorithm; `lcp[0] = 0`.
+fn kasai(text: &[u8], sa: &[usize]) -> Vec<usize> {
+    let n = text.len();
+    let mut rank = vec![0; n];
+    for (r, &p) in sa.iter().enumerate() {
+        rank[p] = r;
+    }
+    let mut lcp = vec![0; n];
+    let mut h = 0usize;
+    for i in 0..n {
+        if rank[i] == 0 {
+            h = 0;
+            continue;
+        }
+        let j = sa[rank[i] - 1];
+        while i + h < n && j + h < n && text[i + h] == text[j + h] {
+            h += 1;
+        }
+        lcp[rank[i]] = h;
+        h = h.saturating_sub(1);
+    }
+    lcp
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
+
+    fn naive_sa(text: &[u8]) -> Vec<usize> {
+        let mut sa: Vec<usize> = (0..text.len()).collect();
+        sa.sort_by_key(|&i| &text[i..]);
+        sa
+    }
+
+    #[test]
+    fn test_suffix_array_banana() {
+        GhostToken::new(|token| {
+            let s = BrandedString::from("banana");
+            let index = BrandedSuffixArray::from_branded_string(&s, &token);
+            assert_eq!(index.suffix_array(&token), &[5, 3, 1, 0, 4, 2]);
+            assert_eq!(index.lcp(&token), &[0, 1, 3, 0, 0, 2]);
+
+            let mut hits = index.find_all(&token, b"ana").to_vec();
+            hits.sort_unstable();
+            assert_eq!(hits, vec![1, 3]);
+            assert_eq!(index.count(&token, b"a"), 3);
+            assert_eq!(index.count(&token, b""), 6);
+            assert!(index.contains(&token, b"nan"));
+            assert!(!index.contains(&token, b"nab"));
+            assert!(!index.contains(&token, b"bananas"));
+            assert_eq!(index.longest_repeated_substring(&token), Some(1..4));
+
+            let empty = BrandedSuffixArray::from_bytes(b"");
+            assert!(empty.is_empty());
+            assert_eq!(empty.count(&token, b"a"), 0);
+            assert_eq!(empty.longest_repeated_substring(&token), None);
+        });
+    }
+
+    #[test]
+    fn test_suffix_array_matches_naive() {
+        GhostToken::new(|token| {
+            let mut rng = StdRng::seed_from_u64(42);
+            for len in [3, 10, 64, 300] {
+                for alphabet in [1u8, 2, 4, 255] {
+                    let text: Vec<u8> = (0..len).map(|_| rng.gen_range(0..alphabet)).collect();
+                    let index = BrandedSuffixArray::from_bytes(&text);
+                    let sa = naive_sa(&text);
+                    assert_eq!(index.suffix_array(&token), sa.as_slice());
+                    for r in 1..len {
+                        let (a, b) = (&text[sa[r - 1]..], &text[sa[r]..]);
+                        let lcp = a.iter().zip(b).take_while(|(x, y)| x == y).count();
+                        assert_eq!(index.lcp(&token)[r], lcp);
+                    }
+                    let pattern = &text[len / 3..len / 3 + 2];
+                    let expected = text.windows(2).filter(|w| *w == pattern).count();
+                    assert_eq!(index.count(&token, pattern), expected);
+                }
+            }
+        });
+    }
+}
@@ -72,6 +72,11 @@ where
     pub fn iter(&self) -> impl Iterator<Item = (crate::alloc::BrandedRc<'brand, crate::collections::vec::BrandedVec<'brand, u8>>, &V)> + use<'_, 'brand, K, V, Token> {
         self.map.iter(self.token)
     }
+
+    /// Iterates over the entries whose keys start with `prefix`.
+    pub fn iter_prefix<P: AsRef<[u8]>>(&self, prefix: P) -> impl Iterator<Item = (crate::alloc::BrandedRc<'brand, crate::collections::vec::BrandedVec<'brand, u8>>, &V)> + use<'_, 'brand, K, V, P, Token> {
+        self.map.iter_prefix(self.token, prefix)
+    }
 }
 
 /// Extension trait to easily create ActiveRadixTrieMap from BrandedRadixTrieMap.
@@ -0,0 +1,914 @@
+//! `BrandedArtMap` — an Adaptive Radix Tree (ART) over byte-string keys.
+//!
+//! Like [`BrandedRadixTrieMap`](super::BrandedRadixTrieMap) the tree compresses
+//! single-child paths into node prefixes and keeps its nodes in a `BrandedVec` arena,
+//! but inner nodes choose their child layout from the number of children:
+//!
+//! | Node    | Children | Lookup                              |
+//! |---------|----------|-------------------------------------|
+//! | Node4   | 1–4      | linear scan of sorted key bytes     |
+//! | Node16  | 5–16     | SIMD compare (`simd` feature) or scan |
+//! | Node48  | 17–48    | 256-entry byte index into 48 slots  |
+//! | Node256 | 49–256   | direct indexing                     |
+//!
+//! Sparse nodes stay small and dense nodes need no search at all, so lookups on dense
+//! key sets touch far less memory than a sorted child list. Nodes grow and shrink
+//! between layouts as keys are inserted and removed.
+//!
+//! Leaves store their full key, so iteration hands out `&[u8]` keys without rebuilding
+//! them, and keys may be prefixes of one another: a key ending at an inner node is
+//! kept in that node's terminal leaf.
+
+use core::marker::PhantomData;
+use alloc_crate::boxed::Box;
+use alloc_crate::vec::Vec;
+
+use super::map::common_prefix_len;
+use super::node::NodePrefix;
+use crate::collections::{BrandedCollection, BrandedVec};
+use crate::{GhostBorrow, GhostBorrowMut};
+
+const CORRUPTED: &str = "corrupted ART arena";
+const NONE: usize = usize::MAX;
+
+/// Largest child count at which a node shrinks to the next smaller layout. Kept below
+/// the smaller layout's capacity so alternating inserts and removes do not thrash.
+const SHRINK_16: usize = 3;
+const SHRINK_48: usize = 12;
+const SHRINK_256: usize = 37;
+
+enum Slot<V> {
+    Free(usize),
+    Leaf(Leaf<V>),
+    Inner(Inner),
+}
+
+struct Leaf<V> {
+    key: Box<[u8]>,
+    value: V,
+}
+
+struct Inner {
+    prefix: NodePrefix,
+    /// Leaf whose key ends exactly after `prefix`.
+    terminal: Option<usize>,
+    children: Children,
+}
+
+/// Children of a Node4 or Node16, sorted by key byte.
+struct Sorted<const N: usize> {
+    len: u8,
+    keys: [u8; N],
+    children: [usize; N],
+}
+
+struct Indexed48 {
+    len: u8,
+    /// `index[byte]` is one past the slot in `children`, or 0 if absent.
+    index: [u8; 256],
+    children: [usize; 48],
+}
+
+struct Direct256 {
+    len: u16,
+    /// `NONE` marks an absent child.
+    children: [usize; 256],
+}
+
+enum Children {
+    Node4(Sorted<4>),
+    Node16(Box<Sorted<16>>),
+    Node48(Box<Indexed48>),
+    Node256(Box<Direct256>),
+}
+
+impl<const N: usize> Sorted<N> {
+    fn new() -> Self {
+        Self {
+            len: 0,
+            keys: [0; N],
+            children: [NONE; N],
+        }
+    }
+
+    #[inline]
+    fn position(&self, byte: u8) -> Option<usize> {
+        self.keys[..self.len as usize]
+            .iter()
+            .position(|&k| k == byte)
+    }
+
+    fn insert(&mut self, byte: u8, child: usize) {
+        let len = self.len as usize;
+        let pos = self.keys[..len].partition_point(|&k| k < byte);
+        self.keys.copy_within(pos..len, pos + 1);
+        self.children.copy_within(pos..len, pos + 1);
+        self.keys[pos] = byte;
+        self.children[pos] = child;
+        self.len += 1;
+    }
+
+    fn remove(&mut self, byte: u8) {
+        if let Some(pos) = self.position(byte) {
+            let len = self.len as usize;
+            self.keys.copy_within(pos + 1..len, pos);
+            self.children.copy_within(pos + 1..len, pos);
+            self.len -= 1;
+        }
+    }
+
+    fn next_from(&self, from: usize) -> Option<(u8, usize)> {
+        let len = self.len as usize;
+        let pos = self.keys[..len].partition_point(|&k| usize::from(k) < from);
+        (pos < len).then(|| (self.keys[pos], self.children[pos]))
+    }
+}
+
+impl Sorted<16> {
+    /// Finds `byte` with one 16-lane compare.
+    #[cfg(all(feature = "simd", target_arch = "x86_64"))]
+    #[inline]
+    fn find(&self, byte: u8) -> Option<usize> {
+        use core::arch::x86_64::{
+            _mm_cmpeq_epi8, _mm_loadu_si128, _mm_movemask_epi8, _mm_set1_epi8,
+        };
+        // SAFETY: SSE2 is part of the x86_64 baseline, and the load reads exactly the
+        // 16 bytes of `keys`.
+        let hits = unsafe {
+            let keys = _mm_loadu_si128(self.keys.as_ptr().cast());
+            _mm_movemask_epi8(_mm_cmpeq_epi8(keys, _mm_set1_epi8(byte as i8))) as u32
+        };
+        let hits = hits & ((1u32 << self.len) - 1);
+        (hits != 0).then(|| self.children[hits.trailing_zeros() as usize])
+    }
+
+    #[cfg(not(all(feature = "simd", target_arch = "x86_64")))]
+    #[inline]
+    fn find(&self, byte: u8) -> Option<usize> {
+        self.position(byte).map(|pos| self.children[pos])
+    }
+}
+
+impl Children {
+    fn len(&self) -> usize {
+        match self {
+            Children::Node4(n) => n.len as usize,
+            Children::Node16(n) => n.len as usize,
+            Children::Node48(n) => n.len as usize,
+            Children::Node256(n) => n.len as usize,
+        }
+    }
+
+    #[inline]
+    fn get(&self, byte: u8) -> Option<usize> {
+        match self {
+            Children::Node4(n) => n.position(byte).map(|pos| n.children[pos]),
+            Children::Node16(n) => n.find(byte),
+            Children::Node48(n) => match n.index[byte as usize] {
+                0 => None,
+                slot => Some(n.children[slot as usize - 1]),
+            },
+            Children::Node256(n) => Some(n.children[byte as usize]).filter(|&c| c != NONE),
+        }
+    }
+
+    fn get_mut(&mut self, byte: u8) -> Option<&mut usize> {
+        match self {
+            Children::Node4(n) => n.position(byte).map(|pos| &mut n.children[pos]),
+            Children::Node16(n) => n.position(byte).map(|pos| &mut n.children[pos]),
+            Children::Node48(n) => match n.index[byte as usize] {
+                0 => None,
+                slot => Some(&mut n.children[slot as usize - 1]),
+            },
+            Children::Node256(n) => Some(&mut n.children[byte as usize]).filter(|c| **c != NONE),
+        }
+    }
+
+    /// First child whose key byte is at least `from` (which may be 256).
+    fn next_from(&self, from: usize) -> Option<(u8, usize)> {
+        let start = u8::try_from(from).ok();
+        match self {
+            Children::Node4(n) => n.next_from(from),
+            Children::Node16(n) => n.next_from(from),
+            Children::Node48(n) => (start?..=u8::MAX)
+                .find(|&b| n.index[usize::from(b)] != 0)
+                .map(|b| (b, n.children[usize::from(n.index[usize::from(b)]) - 1])),
+            Children::Node256(n) => (start?..=u8::MAX)
+                .find(|&b| n.children[usize::from(b)] != NONE)
+                .map(|b| (b, n.children[usize::from(b)])),
+        }
+    }
+
+    fn entries(&self) -> Vec<(u8, usize)> {
+        let mut entries = Vec::with_capacity(self.len());
+        let mut from = 0;
+        while let Some((byte, child)) = self.next_from(from) {
+            entries.push((byte, child));
+            from = usize::from(byte) + 1;
+        }
+        entries
+    }
+
+    /// Builds the smallest layout of at least `capacity` holding the sorted `entries`.
+    fn with_entries(capacity: usize, entries: &[(u8, usize)]) -> Self {
+        match capacity {
+            0..=4 => {
+                let mut n = Sorted::new();
+                for &(b, c) in entries {
+                    n.insert(b, c);
+                }
+                Children::Node4(n)
+            }
+            5..=16 => {
+                let mut n = Box::new(Sorted::new());
+                for &(b, c) in entries {
+                    n.insert(b, c);
+                }
+                Children::Node16(n)
+            }
+            17..=48 => {
+                let mut n = Box::new(Indexed48 {
+                    len: 0,
+                    index: [0; 256],
+                    children: [NONE; 48],
+                });
+                for &(b, c) in entries {
+                    n.children[usize::from(n.len)] = c;
+                    n.len += 1;
+                    n.index[usize::from(b)] = n.len;
+                }
+                Children::Node48(n)
+            }
+            _ => {
+                let mut n = Box::new(Direct256 {
+                    len: 0,
+                    children: [NONE; 256],
+                });
+                for &(b, c) in entries {
+                    n.children[usize::from(b)] = c;
+                    n.len += 1;
+                }
+                Children::Node256(n)
+            }
+        }
+    }
+
+    /// Adds a child for a byte that has none, growing the layout if it is full.
+    fn insert(&mut self, byte: u8, child: usize) {
+        let full = match self {
+            Children::Node4(n) => n.len == 4,
+            Children::Node16(n) => n.len == 16,
+            Children::Node48(n) => n.len == 48,
+            Children::Node256(_) => false,
+        };
+        if full {
+            *self = Children::with_entries(self.len() + 1, &self.entries());
+        }
+        match self {
+            Children::Node4(n) => n.insert(byte, child),
+            Children::Node16(n) => n.insert(byte, child),
+            Children::Node48(n) => {
+                n.children[usize::from(n.len)] = child;
+                n.len += 1;
+                n.index[usize::from(byte)] = n.len;
+            }
+            Children::Node256(n) => {
+                n.children[byte as usize] = child;
+                n.len += 1;
+            }
+        }
+    }
+
+    /// Removes the child for `byte`, shrinking the layout once it is sparse enough.
+    fn remove(&mut self, byte: u8) {
+        let shrink = match self {
+            Children::Node4(n) => {
+                n.remove(byte);
+                false
+            }
+            Children::Node16(n) => {
+                n.remove(byte);
+                n.len as usize <= SHRINK_16
+            }
+            Children::Node48(n) => {
+                let slot = n.index[byte as usize];
+                if slot != 0 {
+                    // Keep slots dense by moving the last one into the hole.
+                    let hole = slot as usize - 1;
+                    let last = n.len as usize - 1;
+                    if hole != last {
+                        let moved = n.index.iter().position(|&s| s as usize == last + 1);
+                        n.children[hole] = n.children[last];
+                        n.index[moved.expect(CORRUPTED)] = slot;
+                    }
+                    n.children[last] = NONE;
+                    n.index[byte as usize] = 0;
+                    n.len -= 1;
+                }
+                n.len as usize <= SHRINK_48
+            }
+            Children::Node256(n) => {
+                if n.children[byte as usize] != NONE {
+                    n.children[byte as usize] = NONE;
+                    n.len -= 1;
+                }
+                n.len as usize <= SHRINK_256
+            }
+        };
+        if shrink {
+            *self = Children::with_entries(self.len(), &self.entries());
+        }
+    }
+}
+
+/// Where the parent stores the index of a node.
+#[derive(Clone, Copy)]
+enum Link {
+    Root,
+    Child(usize, u8),
+}
+
+/// An ordered map from byte-string keys to values, stored as an Adaptive Radix Tree.
+///
+/// Keys must implement `AsRef<[u8]>`; entries iterate in lexicographic byte order.
+pub struct BrandedArtMap<'brand, K, V> {
+    nodes: BrandedVec<'brand, Slot<V>>,
+    root: Option<usize>,
+    free_head: Option<usize>,
+    len: usize,
+    _marker: PhantomData<K>,
+}
+
+impl<'brand, K, V> BrandedArtMap<'brand, K, V> {
+    /// Creates a new empty map.
+    pub fn new() -> Self {
+        Self::with_capacity(0)
+    }
+
+    /// Creates a new empty map with room for `capacity` arena nodes.
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            nodes: BrandedVec::with_capacity(capacity),
+            root: None,
+            free_head: None,
+            len: 0,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Returns the number of entries.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if the map is empty.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Removes every entry.
+    pub fn clear(&mut self) {
+        self.nodes.clear();
+        self.root = None;
+        self.free_head = None;
+        self.len = 0;
+    }
+
+    fn alloc(&mut self, slot: Slot<V>) -> usize {
+        if let Some(idx) = self.free_head {
+            let free = self.nodes.get_mut_exclusive(idx).expect(CORRUPTED);
+            let Slot::Free(next) = core::mem::replace(free, slot) else {
+                panic!("{CORRUPTED}");
+            };
+            self.free_head = (next != NONE).then_some(next);
+            idx
+        } else {
+            self.nodes.push(slot);
+            self.nodes.len() - 1
+        }
+    }
+
+    fn alloc_leaf(&mut self, key: &[u8], value: V) -> usize {
+        self.alloc(Slot::Leaf(Leaf {
+            key: key.into(),
+            value,
+        }))
+    }
+
+    fn free(&mut self, idx: usize) -> Slot<V> {
+        let next = self.free_head.replace(idx).unwrap_or(NONE);
+        let slot = self.nodes.get_mut_exclusive(idx).expect(CORRUPTED);
+        core::mem::replace(slot, Slot::Free(next))
+    }
+
+    fn inner_mut(&mut self, idx: usize) -> &mut Inner {
+        match self.nodes.get_mut_exclusive(idx) {
+            Some(Slot::Inner(node)) => node,
+            _ => panic!("{CORRUPTED}"),
+        }
+    }
+
+    fn leaf_value_mut(&mut self, idx: usize) -> &mut V {
+        match self.nodes.get_mut_exclusive(idx) {
+            Some(Slot::Leaf(leaf)) => &mut leaf.value,
+            _ => panic!("{CORRUPTED}"),
+        }
+    }
+
+    fn set_link(&mut self, link: Link, idx: usize) {
+        match link {
+            Link::Root => self.root = Some(idx),
+            Link::Child(parent, byte) => {
+                *self
+                    .inner_mut(parent)
+                    .children
+                    .get_mut(byte)
+                    .expect(CORRUPTED) = idx;
+            }
+        }
+    }
+
+    /// Restores the invariant that an inner node holds at least two entries
+    /// (children plus terminal) after one was removed, splicing the node out if not.
+    fn compact(&mut self, idx: usize, link: Link) {
+        let node = self.inner_mut(idx);
+        let replacement = match (node.children.len(), node.terminal) {
+            (0, Some(terminal)) => terminal,
+            (1, None) => {
+                let (byte, child) = node.children.next_from(0).expect(CORRUPTED);
+                let mut merged = node.prefix.to_vec();
+                merged.push(byte);
+                if let Some(Slot::Inner(child)) = self.nodes.get_mut_exclusive(child) {
+                    merged.extend_from_slice(&child.prefix);
+                    child.prefix = NodePrefix::new(&merged);
+                }
+                child
+            }
+            _ => return,
+        };
+        self.set_link(link, replacement);
+        self.free(idx);
+    }
+
+    fn find_leaf<Token>(&self, token: &Token, key: &[u8]) -> Option<usize>
+    where
+        Token: GhostBorrow<'brand>,
+    {
+        let mut idx = self.root?;
+        let mut depth = 0;
+        loop {
+            match self.nodes.get(token, idx).expect(CORRUPTED) {
+                Slot::Leaf(leaf) => return (*leaf.key == *key).then_some(idx),
+                Slot::Inner(node) => {
+                    if !key[depth..].starts_with(&node.prefix) {
+                        return None;
+                    }
+                    depth += node.prefix.len();
+                    match key.get(depth) {
+                        None => return node.terminal,
+                        Some(&byte) => idx = node.children.get(byte)?,
+                    }
+                    depth += 1;
+                }
+                Slot::Free(_) => panic!("{CORRUPTED}"),
+            }
+        }
+    }
+
+    /// Returns an iterator over the entries in lexicographic key order.
+    pub fn iter<'a, Token>(&'a self, token: &'a Token) -> Iter<'a, 'brand, V, Token>
+    where
+        Token: GhostBorrow<'brand>,
+    {
+        Iter {
+            nodes: &self.nodes,
+            token,
+            stack: self.root.map(|root| (root, 0, false)).into_iter().collect(),
+        }
+    }
+}
+
+impl<'brand, K, V> BrandedArtMap<'brand, K, V>
+where
+    K: AsRef<[u8]>,
+{
+    /// Inserts a key-value pair, returning the previous value for the key.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the node arena is corrupted, which indicates a bug in the map.
+    pub fn insert<Token>(&mut self, token: &mut Token, key: K, value: V) -> Option<V>
+    where
+        Token: GhostBorrowMut<'brand>,
+    {
+        enum Step {
+            Replace(usize),
+            SplitLeaf {
+                common: usize,
+                leaf_byte: Option<u8>,
+            },
+            SplitPrefix {
+                common: usize,
+                node_byte: u8,
+            },
+            AddTerminal,
+            Descend(u8, usize),
+            AddChild(u8),
+        }
+
+        let key = key.as_ref();
+        let Some(mut idx) = self.root else {
+            self.root = Some(self.alloc_leaf(key, value));
+            self.len += 1;
+            return None;
+        };
+        let mut link = Link::Root;
+        let mut depth = 0;
+        loop {
+            let step = match self.nodes.get(token, idx).expect(CORRUPTED) {
+                Slot::Leaf(leaf) if *leaf.key == *key => Step::Replace(idx),
+                Slot::Leaf(leaf) => {
+                    let common = common_prefix_len(&leaf.key[depth..], &key[depth..]);
+                    Step::SplitLeaf {
+                        common,
+                        leaf_byte: leaf.key.get(depth + common).copied(),
+                    }
+                }
+                Slot::Inner(node) => {
+                    let common = common_prefix_len(&node.prefix, &key[depth..]);
+                    if common < node.prefix.len() {
+                        Step::SplitPrefix {
+                            common,
+                            node_byte: node.prefix[common],
+                        }
+                    } else {
+                        depth += common;
+                        match key.get(depth) {
+                            None => match node.terminal {
+                                Some(terminal) => Step::Replace(terminal),
+                                None => Step::AddTerminal,
+                            },
+                            Some(&byte) => match node.children.get(byte) {
+                                Some(child) => Step::Descend(byte, child),
+                                None => Step::AddChild(byte),
+                            },
+                        }
+                    }
+                }
+                Slot::Free(_) => panic!("{CORRUPTED}"),
+            };
+
+            match step {
+                Step::Replace(leaf) => {
+                    return Some(core::mem::replace(self.leaf_value_mut(leaf), value));
+                }
+                Step::Descend(byte, child) => {
+                    link = Link::Child(idx, byte);
+                    idx = child;
+                    depth += 1;
+                    continue;
+                }
+                Step::AddTerminal => {
+                    let leaf = self.alloc_leaf(key, value);
+                    self.inner_mut(idx).terminal = Some(leaf);
+                }
+                Step::AddChild(byte) => {
+                    let leaf = self.alloc_leaf(key, value);
+                    self.inner_mut(idx).children.insert(byte, leaf);
+                }
+                Step::SplitLeaf { common, leaf_byte } => {
+                    // Both keys share `key[depth..depth + common]`, then diverge or end.
+                    let mut node = Inner {
+                        prefix: NodePrefix::new(&key[depth..depth + common]),
+                        terminal: None,
+                        children: Children::Node4(Sorted::new()),
+                    };
+                    match leaf_byte {
+                        Some(byte) => node.children.insert(byte, idx),
+                        None => node.terminal = Some(idx),
+                    }
+                    self.hang_split(link, node, key, depth + common, value);
+                }
+                Step::SplitPrefix { common, node_byte } => {
+                    // The key leaves the compressed path partway: hang the old node
+                    // below a new one holding the shared part.
+                    let old = self.inner_mut(idx);
+                    old.prefix = NodePrefix::new(&old.prefix[common + 1..]);
+                    let mut node = Inner {
+                        prefix: NodePrefix::new(&key[depth..depth + common]),
+                        terminal: None,
+                        children: Children::Node4(Sorted::new()),
+                    };
+                    node.children.insert(node_byte, idx);
+                    self.hang_split(link, node, key, depth + common, value);
+                }
+            }
+            self.len += 1;
+            return None;
+        }
+    }
+
+    /// Stores a new leaf for `key` in the split node `node`, below the byte at
+    /// `at` (or as its terminal when the key ends there), and links the node
+    /// in place of the one it split.
+    fn hang_split(&mut self, link: Link, mut node: Inner, key: &[u8], at: usize, value: V) {
+        let leaf = self.alloc_leaf(key, value);
+        match key.get(at) {
+            Some(&byte) => node.children.insert(byte, leaf),
+            None => node.terminal = Some(leaf),
+        }
+        let node = self.alloc(Slot::Inner(node));
+        self.set_link(link, node);
+    }
+
+    /// Returns a reference to the value for `key`.
+    pub fn get<'a, Token>(&'a self, token: &'a Token, key: K) -> Option<&'a V>
+    where
+        Token: GhostBorrow<'brand>,
+    {
+        let leaf = self.find_leaf(token, key.as_ref())?;
+        match self.nodes.get(token, leaf) {
+            Some(Slot::Leaf(leaf)) => Some(&leaf.value),
+            _ => None,
+        }
+    }
+
+    /// Returns a mutable reference to the value for `key`.
+    pub fn get_mut<'a, Token>(&'a self, token: &'a mut Token, key: K) -> Option<&'a mut V>
+    where
+        Token: GhostBorrowMut<'brand>,
+    {
+        let leaf = self.find_leaf(token, key.as_ref())?;
+        match self.nodes.get_mut(token, leaf) {
+            Some(Slot::Leaf(leaf)) => Some(&mut leaf.value),
+            _ => None,
+        }
+    }
+
+    /// Returns `true` if the map contains `key`.
+    pub fn contains_key<Token>(&self, token: &Token, key: K) -> bool
+    where
+        Token: GhostBorrow<'brand>,
+    {
+        self.find_leaf(token, key.as_ref()).is_some()
+    }
+
+    /// Removes `key`, returning its value.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the node arena is corrupted, which indicates a bug in the map.
+    pub fn remove<Token>(&mut self, token: &mut Token, key: K) -> Option<V>
+    where
+        Token: GhostBorrowMut<'brand>,
+    {
+        enum Found {
+            Leaf,
+            Terminal(usize),
+            Child(u8, usize),
+        }
+
+        let key = key.as_ref();
+        let mut idx = self.root?;
+        let mut link = Link::Root;
+        let mut parent = None;
+        let mut depth = 0;
+        loop {
+            let found = match self.nodes.get(token, idx).expect(CORRUPTED) {
+                Slot::Leaf(leaf) if *leaf.key == *key => Found::Leaf,
+                Slot::Leaf(_) => return None,
+                Slot::Inner(node) => {
+                    if !key[depth..].starts_with(&node.prefix) {
+                        return None;
+                    }
+                    depth += node.prefix.len();
+                    match key.get(depth) {
+                        None => Found::Terminal(node.terminal?),
+                        Some(&byte) => Found::Child(byte, node.children.get(byte)?),
+                    }
+                }
+                Slot::Free(_) => panic!("{CORRUPTED}"),
+            };
+
+            let removed = match found {
+                Found::Child(byte, child) => {
+                    parent = Some((idx, link));
+                    link = Link::Child(idx, byte);
+                    idx = child;
+                    depth += 1;
+                    continue;
+                }
+                Found::Leaf => {
+                    let removed = self.free(idx);
+                    match (parent, link) {
+                        (Some((parent, parent_link)), Link::Child(_, byte)) => {
+                            self.inner_mut(parent).children.remove(byte);
+                            self.compact(parent, parent_link);
+                        }
+                        _ => self.root = None,
+                    }
+                    removed
+                }
+                Found::Terminal(terminal) => {
+                    self.inner_mut(idx).terminal = None;
+                    let removed = self.free(terminal);
+                    self.compact(idx, link);
+                    removed
+                }
+            };
+            self.len -= 1;
+            let Slot::Leaf(leaf) = removed else {
+                panic!("{CORRUPTED}");
+            };
+            return Some(leaf.value);
+        }
+    }
+}
+
+impl<K, V> Default for BrandedArtMap<'_, K, V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<'brand, K, V> BrandedCollection<'brand> for BrandedArtMap<'brand, K, V> {
+    fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    fn len(&self) -> usize {
+        self.len
+    }
+}
+
+/// Iterator over the entries of a [`BrandedArtMap`] in key order.
+pub struct Iter<'a, 'brand, V, Token> {
+    nodes: &'a BrandedVec<'brand, Slot<V>>,
+    token: &'a Token,
+    /// Nodes being visited: (index, next child byte, terminal visited).
+    stack: Vec<(usize, usize, bool)>,
+}
+
+impl<'a, 'brand, V, Token> Iterator for Iter<'a, 'brand, V, Token>
+where
+    Token: GhostBorrow<'brand>,
+{
+    type Item = (&'a [u8], &'a V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let top = self.stack.last_mut()?;
+            match self.nodes.get(self.token, top.0).expect(CORRUPTED) {
+                Slot::Leaf(leaf) => {
+                    self.stack.pop();
+                    return Some((&leaf.key, &leaf.value));
+                }
+                Slot::Inner(node) => {
+                    // A terminal key is a prefix of every key below, so it comes first.
+                    if !top.2 {
+                        top.2 = true;
+                        if let Some(terminal) = node.terminal {
+                            self.stack.push((terminal, 0, false));
+                        }
+                        continue;
+                    }
+                    match node.children.next_from(top.1) {
+                        Some((byte, child)) => {
+                            top.1 = usize::from(byte) + 1;
+                            self.stack.push((child, 0, false));
+                        }
+                        None => {
+                            self.stack.pop();
+                        }
+                    }
+                }
+                Slot::Free(_) => panic!("{CORRUPTED}"),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::GhostToken;
+    use alloc_crate::collections::BTreeMap;
+
+    fn layouts<'brand, K, V>(
+        map: &BrandedArtMap<'brand, K, V>,
+        token: &GhostToken<'brand>,
+    ) -> [usize; 4] {
+        let mut counts = [0; 4];
+        for slot in map.nodes.as_slice(token) {
+            if let Slot::Inner(node) = slot {
+                counts[match node.children {
+                    Children::Node4(_) => 0,
+                    Children::Node16(_) => 1,
+                    Children::Node48(_) => 2,
+                    Children::Node256(_) => 3,
+                }] += 1;
+            }
+        }
+        counts
+    }
+
+    #[test]
+    fn test_art_prefix_keys_and_splits() {
+        GhostToken::new(|mut token| {
+            let mut map = BrandedArtMap::new();
+            for (i, w) in [
+                "romane", "romanus", "romulus", "rubens", "ruber", "rubicon", "r", "rom", "",
+            ]
+            .iter()
+            .enumerate()
+            {
+                assert_eq!(map.insert(&mut token, *w, i), None);
+            }
+            assert_eq!(map.len(), 9);
+            assert_eq!(map.get(&token, "rom"), Some(&7));
+            assert_eq!(map.get(&token, ""), Some(&8));
+            assert_eq!(map.get(&token, "ro"), None);
+            assert_eq!(map.get(&token, "romanusx"), None);
+            assert_eq!(map.insert(&mut token, "ruber", 40), Some(4));
+            *map.get_mut(&mut token, "rubens").unwrap() += 30;
+
+            let keys: Vec<&[u8]> = map.iter(&token).map(|(k, _)| k).collect();
+            let mut sorted = keys.clone();
+            sorted.sort();
+            assert_eq!(keys, sorted);
+            assert_eq!(keys[..3], [&b""[..], b"r", b"rom"]);
+
+            for w in ["r", "rom", "romulus", "", "missing", "rub"] {
+                map.remove(&mut token, w);
+            }
+            let rest: Vec<(&[u8], &usize)> = map.iter(&token).collect();
+            assert_eq!(
+                rest,
+                [
+                    (&b"romane"[..], &0),
+                    (b"romanus", &1),
+                    (b"rubens", &33),
+                    (b"ruber", &40),
+                    (b"rubicon", &5)
+                ]
+            );
+        });
+    }
+
+    #[test]
+    fn test_art_nodes_grow_and_shrink() {
+        GhostToken::new(|mut token| {
+            let mut map = BrandedArtMap::new();
+            let key = |b: u8| [b'k', b];
+            for b in 0..=255u8 {
+                map.insert(&mut token, key(b), b);
+                let expected = match b {
+                    0 => [0, 0, 0, 0],
+                    1..=3 => [1, 0, 0, 0],
+                    4..=15 => [0, 1, 0, 0],
+                    16..=47 => [0, 0, 1, 0],
+                    _ => [0, 0, 0, 1],
+                };
+                assert_eq!(layouts(&map, &token), expected, "after inserting {b}");
+            }
+            assert!((0..=255u8).all(|b| map.get(&token, key(b)) == Some(&b)));
+
+            for b in (1..=255u8).rev() {
+                assert_eq!(map.remove(&mut token, key(b)), Some(b));
+                assert_eq!(map.get(&token, key(b - 1)), Some(&(b - 1)));
+            }
+            assert_eq!(layouts(&map, &token), [0, 0, 0, 0]);
+            assert_eq!(map.iter(&token).collect::<Vec<_>>(), [(&b"k\0"[..], &0)]);
+            assert_eq!(map.remove(&mut token, key(0)), Some(0));
+            assert!(map.is_empty());
+            assert_eq!(map.iter(&token).count(), 0);
+        });
+    }
+
+    #[test]
+    fn test_art_matches_btreemap() {
+        use rand::rngs::StdRng;
+        use rand::{Rng, SeedableRng};
+
+        GhostToken::new(|mut token| {
+            let mut rng = StdRng::seed_from_u64(7);
+            let mut map = BrandedArtMap::new();
+            let mut model = BTreeMap::new();
+            for i in 0..20_000u32 {
+                // Short keys over a small alphabet make prefixes and dense nodes common.
+                let len = rng.gen_range(0..5);
+                let key: Vec<u8> = (0..len).map(|_| rng.gen_range(0..80u8)).collect();
+                if rng.gen_bool(0.6) {
+                    assert_eq!(map.insert(&mut token, key.clone(), i), model.insert(key, i));
+                } else {
+                    assert_eq!(map.remove(&mut token, key.clone()), model.remove(&key));
+                }
+            }
+            assert_eq!(map.len(), model.len());
+            assert!(map
+                .iter(&token)
+                .map(|(k, v)| (k.to_vec(), *v))
+                .eq(model.into_iter()));
+        });
+    }
+}
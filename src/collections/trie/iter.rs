@@ -4,7 +4,7 @@ use crate::alloc::BrandedRc;
 use crate::collections::BrandedVec;
 use crate::token::traits::GhostBorrow;
 use crate::GhostToken;
-use std::vec::Vec;
+use alloc_crate::vec::Vec;
 
 /// Iterator over key-value pairs of `BrandedRadixTrieMap`.
 /// Yields `(BrandedRc<BrandedVec<u8>>, &V)`.
@@ -26,13 +26,25 @@ where
 {
     /// Creates a new iterator over the map.
     pub fn new(map: &'a BrandedRadixTrieMap<'brand, K, V>, token: &'a Token) -> Self {
+        Self::from_node(map, token, map.root, &[])
+    }
+
+    /// Creates an iterator over the subtree rooted at `node_idx`, whose parent is
+    /// reached by the bytes `path`.
+    pub(crate) fn from_node(
+        map: &'a BrandedRadixTrieMap<'brand, K, V>,
+        token: &'a Token,
+        node_idx: Option<usize>,
+        path: &[u8],
+    ) -> Self {
         let mut stack = Vec::new();
         let mut key_buf = BrandedVec::new();
 
-        if let Some(root_idx) = map.root {
-            if let Some(NodeSlot::Occupied(node)) = map.nodes.get(token, root_idx) {
+        if let Some(idx) = node_idx {
+            if let Some(NodeSlot::Occupied(node)) = map.nodes.get(token, idx) {
+                key_buf.extend(path.iter().copied());
                 key_buf.extend(node.prefix.iter().copied());
-                stack.push((root_idx, 0));
+                stack.push((idx, 0));
             }
         }
 
@@ -2,12 +2,14 @@
 use core::marker::PhantomData;
 // use std::boxed::Box;
 // use std::ptr::NonNull;
-use std::vec::Vec;
+use alloc_crate::vec::Vec;
 
 use super::node::{Node, NodePrefix, NodeSlot};
 use crate::collections::{BrandedCollection, BrandedVec, ZeroCopyMapOps};
 use crate::GhostBorrow;
 use crate::GhostBorrowMut;
+#[cfg(not(feature = "std"))]
+use alloc_crate::vec;
 
 /// A high-performance Radix Trie Map (Prefix Tree) optimized for branded usage.
 ///
@@ -163,6 +165,68 @@ impl<'brand, K, V> BrandedRadixTrieMap<'brand, K, V> {
         super::iter::Iter::new(self, token)
     }
 
+    /// Finds the topmost node whose key starts with `prefix`, returning it together
+    /// with the bytes leading to its parent.
+    fn find_prefix_node<Token>(&self, token: &Token, prefix: &[u8]) -> Option<(usize, Vec<u8>)>
+    where
+        Token: GhostBorrow<'brand>,
+    {
+        let mut curr_idx = self.root?;
+        let mut offset = 0;
+        loop {
+            let NodeSlot::Occupied(node) = self.nodes.get(token, curr_idx).expect("Corrupted Trie")
+            else {
+                return None;
+            };
+            let rest = &prefix[offset..];
+            let label = node.prefix.as_slice();
+            if rest.len() <= label.len() {
+                // The prefix ends inside (or at the end of) this edge.
+                return label
+                    .starts_with(rest)
+                    .then(|| (curr_idx, prefix[..offset].to_vec()));
+            }
+            if !rest.starts_with(label) {
+                return None;
+            }
+            offset += label.len();
+            curr_idx = node.get_child(prefix[offset])?;
+        }
+    }
+
+    /// Returns an iterator over the entries whose keys start with `prefix`, in
+    /// lexicographic order.
+    ///
+    /// Only the subtree under `prefix` is visited.
+    pub fn iter_prefix<'a, P, Token>(
+        &'a self,
+        token: &'a Token,
+        prefix: P,
+    ) -> impl Iterator<Item = (crate::alloc::BrandedRc<'brand, BrandedVec<'brand, u8>>, &'a V)> + use<'a, 'brand, K, V, P, Token>
+    where
+        P: AsRef<[u8]>,
+        Token: GhostBorrow<'brand>,
+    {
+        match self.find_prefix_node(token, prefix.as_ref()) {
+            Some((node_idx, path)) => super::iter::Iter::from_node(self, token, Some(node_idx), &path),
+            None => super::iter::Iter::from_node(self, token, None, &[]),
+        }
+    }
+
+    /// Returns an iterator over the keys that start with `prefix`, in lexicographic
+    /// order.
+    pub fn keys_with_prefix<'a, P, Token>(
+        &'a self,
+        token: &'a Token,
+        prefix: P,
+    ) -> impl Iterator<Item = crate::alloc::BrandedRc<'brand, BrandedVec<'brand, u8>>> + use<'a, 'brand, K, V, P, Token>
+    where
+        P: AsRef<[u8]>,
+        Token: GhostBorrow<'brand>,
+    {
+        self.iter_prefix(token, prefix).map(|(k, _)| k)
+    }
+
     /// Iterates over all elements, passing the key (as slice) and value to the closure.
     /// This avoids allocating a new Vec for each key.
     pub fn for_each<F, Token>(&self, token: &Token, mut f: F)
@@ -532,10 +596,78 @@ where
 
         old_val
     }
+
+    /// Checks the structural invariants of the trie, returning the first violation.
+    ///
+    /// Verifies that every node is reached exactly once from the root, that child edges
+    /// are sorted and labelled with the first byte of the child's prefix, that only the
+    /// root may be an empty leaf, that the value count matches `len()`, and that every
+    /// arena slot is either reachable or on the free list.
+    ///
+    /// **Time complexity**: \(O(n)\) in the number of arena slots
+    ///
+    /// # Errors
+    /// Returns a description of the first invariant found violated.
+    pub fn validate_invariants<Token>(&self, token: &Token) -> Result<(), &'static str>
+    where
+        Token: GhostBorrow<'brand>,
+    {
+        let slots = self.nodes.len();
+        let mut seen = vec![false; slots];
+        let mut reachable = 0;
+        let mut values = 0;
+        let mut stack: Vec<usize> = self.root.into_iter().collect();
+        while let Some(idx) = stack.pop() {
+            if idx >= slots || core::mem::replace(&mut seen[idx], true) {
+                return Err("node is out of bounds or reachable twice");
+            }
+            reachable += 1;
+            let Some(NodeSlot::Occupied(node)) = self.nodes.get(token, idx) else {
+                return Err("reachable slot is on the free list");
+            };
+            if node.value.is_some() {
+                values += 1;
+            } else if node.children.is_empty() && Some(idx) != self.root {
+                return Err("non-root node has neither a value nor children");
+            }
+            if node.children.windows(2).any(|w| w[0].0 >= w[1].0) {
+                return Err("child edges are not strictly sorted");
+            }
+            for &(byte, child) in &node.children {
+                if let Some(NodeSlot::Occupied(c)) = self.nodes.get(token, child) {
+                    if c.prefix.first() != Some(&byte) {
+                        return Err("child edge byte does not match the child's prefix");
+                    }
+                }
+                stack.push(child);
+            }
+        }
+        if values != self.len {
+            return Err("len does not match the number of stored values");
+        }
+
+        let mut free = 0;
+        let mut next = self.free_head;
+        while let Some(idx) = next {
+            if idx >= slots || core::mem::replace(&mut seen[idx], true) {
+                return Err("free list is cyclic or overlaps reachable nodes");
+            }
+            free += 1;
+            next = match self.nodes.get(token, idx) {
+                Some(NodeSlot::Free(n)) if *n == usize::MAX => None,
+                Some(NodeSlot::Free(n)) => Some(*n),
+                _ => return Err("free list links to an occupied slot"),
+            };
+        }
+        if reachable + free != slots {
+            return Err("arena slots are leaked (neither reachable nor free)");
+        }
+        Ok(())
+    }
 }
 
 // Helper function
-fn common_prefix_len(a: &[u8], b: &[u8]) -> usize {
+pub(super) fn common_prefix_len(a: &[u8], b: &[u8]) -> usize {
     a.iter().zip(b.iter()).take_while(|(x, y)| x == y).count()
 }
 
@@ -631,6 +763,28 @@ mod tests {
 
             map.insert(&mut token, "abd", 4);
             assert_eq!(map.get(&token, "abd"), Some(&4));
+            crate::debug_assert_valid!(map, &token);
+        });
+    }
+
+    #[test]
+    fn test_branded_trie_validate_invariants() {
+        GhostToken::new(|mut token| {
+            let mut map = BrandedRadixTrieMap::new();
+            let words = ["romane", "romanus", "romulus", "rubens", "ruber", "rubicon", "r"];
+            for (i, w) in words.iter().enumerate() {
+                map.insert(&mut token, *w, i);
+                assert_eq!(map.validate_invariants(&token), Ok(()));
+            }
+            for w in ["romulus", "r", "ruber", "missing"] {
+                map.remove(&mut token, w);
+                assert_eq!(map.validate_invariants(&token), Ok(()));
+            }
+            assert_eq!(map.len(), 4);
+
+            // Freed slots are recycled without leaking.
+            map.insert(&mut token, "rubicundus", 9);
+            crate::debug_assert_valid!(map, &token);
         });
     }
 
@@ -662,4 +816,39 @@ mod tests {
             assert_eq!(*items[2].1, 3);
         });
     }
+
+    #[test]
+    fn test_branded_trie_iter_prefix() {
+        GhostToken::new(|mut token| {
+            let mut map = BrandedRadixTrieMap::new();
+            let words = ["romane", "romanus", "romulus", "rubens", "ruber", "rubicon", "r", "x"];
+            for (i, w) in words.iter().enumerate() {
+                map.insert(&mut token, *w, i);
+            }
+
+            let keys = |prefix: &str| -> Vec<String> {
+                map.keys_with_prefix(&token, prefix)
+                    .map(|k| String::from_utf8(k.as_slice(&token).to_vec()).unwrap())
+                    .collect()
+            };
+            // Prefixes ending mid-edge, on a node, and on a stored key.
+            assert_eq!(keys("rom"), ["romane", "romanus", "romulus"]);
+            assert_eq!(keys("roma"), ["romane", "romanus"]);
+            assert_eq!(keys("rube"), ["rubens", "ruber"]);
+            assert_eq!(keys("rubicon"), ["rubicon"]);
+            assert_eq!(keys("r").len(), 7);
+            assert_eq!(keys("r")[0], "r");
+            assert_eq!(keys(""), {
+                let mut all: Vec<String> = words.iter().map(|w| w.to_string()).collect();
+                all.sort();
+                all
+            });
+            assert!(keys("rubicons").is_empty());
+            assert!(keys("ra").is_empty());
+            assert!(keys("y").is_empty());
+
+            let values: Vec<usize> = map.iter_prefix(&token, b"rub").map(|(_, v)| *v).collect();
+            assert_eq!(values, [3, 4, 5]);
+        });
+    }
 }
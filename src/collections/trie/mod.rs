@@ -5,11 +5,13 @@
 //! supports safe interior mutability via `GhostToken`.
 
 pub mod active;
+pub mod art;
 pub mod iter;
 pub mod map;
 pub mod node;
 pub mod set;
 
 pub use active::{ActiveRadixTrieMap, ActiveRadixTrieSet};
+pub use art::BrandedArtMap;
 pub use map::BrandedRadixTrieMap;
 pub use set::BrandedRadixTrieSet;
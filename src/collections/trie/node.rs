@@ -1,5 +1,5 @@
-use std::boxed::Box;
-use std::vec::Vec;
+use alloc_crate::boxed::Box;
+use alloc_crate::vec::Vec;
 use core::ops::Deref;
 
 /// Max size for inline prefix. 15 bytes leaves 1 byte for length in a 16-byte alignment.
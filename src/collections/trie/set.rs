@@ -90,6 +90,19 @@ where
     {
         self.map.iter(token).map(|(k, _)| k)
     }
+
+    /// Checks the structural invariants of the underlying trie.
+    ///
+    /// See [`BrandedRadixTrieMap::validate_invariants`].
+    ///
+    /// # Errors
+    /// Returns a description of the first invariant found violated.
+    pub fn validate_invariants<Token>(&self, token: &Token) -> Result<(), &'static str>
+    where
+        Token: GhostBorrow<'brand>,
+    {
+        self.map.validate_invariants(token)
+    }
 }
 
 impl<'brand, T> BrandedCollection<'brand> for BrandedRadixTrieSet<'brand, T> {
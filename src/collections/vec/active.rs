@@ -96,12 +96,12 @@ where
     }
 
     /// Iterates over elements by shared reference.
-    pub fn iter(&self) -> impl Iterator<Item = &T> + ExactSizeIterator + DoubleEndedIterator + std::iter::FusedIterator + use<'_, 'brand, T, Token> {
+    pub fn iter(&self) -> impl Iterator<Item = &T> + ExactSizeIterator + DoubleEndedIterator + core::iter::FusedIterator + use<'_, 'brand, T, Token> {
         self.as_slice().into_slice().iter()
     }
 
     /// Iterates over elements by mutable reference.
-    pub fn iter_mut(&mut self) -> impl Iterator<Item = &mut T> + ExactSizeIterator + DoubleEndedIterator + std::iter::FusedIterator + use<'_, 'brand, T, Token> {
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = &mut T> + ExactSizeIterator + DoubleEndedIterator + core::iter::FusedIterator + use<'_, 'brand, T, Token> {
         self.as_mut_slice().into_mut_slice().iter_mut()
     }
 
@@ -218,12 +218,12 @@ where
     }
 
     /// Iterates over elements.
-    pub fn iter(&self) -> impl Iterator<Item = &T> + DoubleEndedIterator + std::iter::FusedIterator + use<'_, 'brand, T, Token> {
+    pub fn iter(&self) -> impl Iterator<Item = &T> + DoubleEndedIterator + core::iter::FusedIterator + use<'_, 'brand, T, Token> {
         self.deque.iter(self.token)
     }
 
     /// Iterates over elements (mutable).
-    pub fn iter_mut(&mut self) -> impl Iterator<Item = &mut T> + DoubleEndedIterator + std::iter::FusedIterator + use<'_, 'brand, T, Token> {
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = &mut T> + DoubleEndedIterator + core::iter::FusedIterator + use<'_, 'brand, T, Token> {
         self.deque.iter_mut(self.token)
     }
 
@@ -234,6 +234,31 @@ where
     {
         self.deque.for_each_mut(self.token, f)
     }
+
+    /// Returns the contents as two slices, front first.
+    pub fn as_slices(&self) -> (&[T], &[T]) {
+        self.deque.as_slices(self.token)
+    }
+
+    /// Returns the contents as two mutable slices, front first.
+    pub fn as_mut_slices(&mut self) -> (&mut [T], &mut [T]) {
+        self.deque.as_mut_slices(self.token)
+    }
+
+    /// Makes the contents contiguous and returns them as one slice.
+    pub fn make_contiguous(&mut self) -> &mut [T] {
+        self.deque.make_contiguous(self.token)
+    }
+
+    /// Rotates the deque `k` steps to the left.
+    pub fn rotate_left(&mut self, k: usize) {
+        self.deque.rotate_left(k);
+    }
+
+    /// Rotates the deque `k` steps to the right.
+    pub fn rotate_right(&mut self, k: usize) {
+        self.deque.rotate_right(k);
+    }
 }
 
 /// Extension trait to easily create ActiveVecDeque from BrandedVecDeque.
@@ -0,0 +1,375 @@
+//! `ConcurrentBrandedAppendVec` — an append-only vector that many threads push to at
+//! once, with snapshot iteration.
+//!
+//! Elements live in buckets of doubling size that are allocated once and never move,
+//! so a shared reference to an element stays valid for as long as the vector is
+//! borrowed. A push claims an index, writes the element, and then publishes it by
+//! advancing the committed length over every finished slot, in index order.
+//!
+//! The committed length is the vector's epoch: a
+//! [`snapshot`](ConcurrentBrandedAppendVec::snapshot) records it and iterates exactly
+//! the elements below it. Later pushes only ever add elements past that prefix, so the
+//! snapshot stays consistent while writers keep appending, without stopping them.
+//!
+//! Pushes, lookups and snapshots take a shared token of the vector's brand, like the
+//! other concurrent branded worklists, so the vector can only be used inside the
+//! brand's scope.
+
+use core::cell::UnsafeCell;
+use core::iter::FusedIterator;
+use core::marker::PhantomData;
+use core::mem::MaybeUninit;
+use core::ptr;
+use core::sync::atomic::{AtomicBool, AtomicPtr, AtomicUsize, Ordering};
+
+use crate::token::traits::GhostBorrow;
+#[cfg(not(feature = "std"))]
+use alloc_crate::boxed::Box;
+
+/// Bucket `b` holds `FIRST_BUCKET << b` elements.
+const FIRST_BUCKET_BITS: u32 = 5;
+const FIRST_BUCKET: usize = 1 << FIRST_BUCKET_BITS;
+const BUCKETS: usize = (usize::BITS - FIRST_BUCKET_BITS) as usize;
+
+struct Slot<T> {
+    /// Set once `value` is written.
+    ready: AtomicBool,
+    value: UnsafeCell<MaybeUninit<T>>,
+}
+
+/// An append-only vector that takes `&self` for pushes, so it can be shared across
+/// threads, and hands out consistent snapshots while pushes continue.
+///
+/// Elements cannot be removed or mutated through `&self`; that is what makes a
+/// snapshot a fixed prefix.
+pub struct ConcurrentBrandedAppendVec<'brand, T> {
+    buckets: [AtomicPtr<Slot<T>>; BUCKETS],
+    /// Indices handed out to pushes so far.
+    reserved: AtomicUsize,
+    /// Every index below this one holds a published element.
+    committed: AtomicUsize,
+    _brand: PhantomData<fn(&'brand ()) -> &'brand ()>,
+    _owns: PhantomData<T>,
+}
+
+// SAFETY: elements are only moved in by `push` and dropped by `Drop`; shared access
+// hands out `&T` only.
+unsafe impl<T: Send> Send for ConcurrentBrandedAppendVec<'_, T> {}
+// SAFETY: pushes from several threads move `T`s in (`Send`); readers share `&T` (`Sync`).
+unsafe impl<T: Send + Sync> Sync for ConcurrentBrandedAppendVec<'_, T> {}
+
+/// Returns the bucket of index `i` and its offset within the bucket.
+#[inline]
+fn location(i: usize) -> (usize, usize) {
+    assert!(i <= usize::MAX - FIRST_BUCKET, "capacity overflow");
+    let j = i + FIRST_BUCKET;
+    let high = usize::BITS - 1 - j.leading_zeros();
+    ((high - FIRST_BUCKET_BITS) as usize, j - (1 << high))
+}
+
+impl<'brand, T> ConcurrentBrandedAppendVec<'brand, T> {
+    /// Creates an empty vector. No memory is allocated until the first push.
+    pub fn new() -> Self {
+        Self {
+            buckets: core::array::from_fn(|_| AtomicPtr::new(ptr::null_mut())),
+            reserved: AtomicUsize::new(0),
+            committed: AtomicUsize::new(0),
+            _brand: PhantomData,
+            _owns: PhantomData,
+        }
+    }
+
+    /// Returns the number of published elements.
+    ///
+    /// Pushes still in progress are not counted.
+    pub fn len(&self) -> usize {
+        self.committed.load(Ordering::Acquire)
+    }
+
+    /// Returns `true` if no element has been published.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Appends `value` and returns its index.
+    ///
+    /// The element becomes visible to [`get`](Self::get) and to new snapshots once
+    /// every element before it is published as well.
+    pub fn push<Token: GhostBorrow<'brand>>(&self, _token: &Token, value: T) -> usize {
+        let i = self.reserved.fetch_add(1, Ordering::Relaxed);
+        let (bucket, offset) = location(i);
+        // SAFETY: `offset` is within the bucket, and index `i` belongs to this call
+        // alone, so nobody else touches the slot's value until it is marked ready.
+        unsafe {
+            let slot = &*self.bucket(bucket).add(offset);
+            (*slot.value.get()).write(value);
+            // SeqCst pairs with the loads in `advance`: either this thread sees the
+            // committed length reach `i`, or the thread that moved it there sees the
+            // slot ready.
+            slot.ready.store(true, Ordering::SeqCst);
+        }
+        self.advance();
+        i
+    }
+
+    /// Returns the element at `idx` if it is published.
+    pub fn get<'a, Token: GhostBorrow<'brand>>(
+        &'a self,
+        _token: &'a Token,
+        idx: usize,
+    ) -> Option<&'a T> {
+        if idx < self.len() {
+            // SAFETY: indices below the committed length hold published elements.
+            Some(unsafe { self.get_published(idx) })
+        } else {
+            None
+        }
+    }
+
+    /// Returns a snapshot of the elements published so far.
+    ///
+    /// The snapshot iterates the first [`len`](Self::len) elements as of this call,
+    /// in index order, and sees none of the elements pushed afterwards.
+    pub fn snapshot<'a, Token: GhostBorrow<'brand>>(
+        &'a self,
+        _token: &'a Token,
+    ) -> AppendVecSnapshot<'a, 'brand, T> {
+        AppendVecSnapshot {
+            vec: self,
+            front: 0,
+            back: self.len(),
+        }
+    }
+
+    /// Returns the first slot of `bucket`, allocating the bucket if needed.
+    fn bucket(&self, bucket: usize) -> *mut Slot<T> {
+        let current = self.buckets[bucket].load(Ordering::Acquire);
+        if !current.is_null() {
+            return current;
+        }
+        let slots: Box<[Slot<T>]> = (0..FIRST_BUCKET << bucket)
+            .map(|_| Slot {
+                ready: AtomicBool::new(false),
+                value: UnsafeCell::new(MaybeUninit::uninit()),
+            })
+            .collect();
+        let fresh = Box::into_raw(slots).cast::<Slot<T>>();
+        match self.buckets[bucket].compare_exchange(
+            ptr::null_mut(),
+            fresh,
+            Ordering::AcqRel,
+            Ordering::Acquire,
+        ) {
+            Ok(_) => fresh,
+            Err(installed) => {
+                // Another push allocated the bucket first.
+                // SAFETY: `fresh` came from `Box::into_raw` above and was never shared.
+                drop(unsafe {
+                    Box::from_raw(ptr::slice_from_raw_parts_mut(fresh, FIRST_BUCKET << bucket))
+                });
+                installed
+            }
+        }
+    }
+
+    /// Returns the slot at `idx`, or `None` if its bucket is not allocated.
+    fn slot(&self, idx: usize) -> Option<&Slot<T>> {
+        let (bucket, offset) = location(idx);
+        let first = self.buckets[bucket].load(Ordering::Acquire);
+        // SAFETY: `offset` is within the bucket, which lives as long as `self`.
+        (!first.is_null()).then(|| unsafe { &*first.add(offset) })
+    }
+
+    /// Moves the committed length past every ready slot.
+    fn advance(&self) {
+        let mut committed = self.committed.load(Ordering::SeqCst);
+        while let Some(slot) = self.slot(committed) {
+            if !slot.ready.load(Ordering::SeqCst) {
+                // Its push will advance further once it is done.
+                break;
+            }
+            match self.committed.compare_exchange(
+                committed,
+                committed + 1,
+                Ordering::SeqCst,
+                Ordering::SeqCst,
+            ) {
+                Ok(_) => committed += 1,
+                Err(actual) => committed = actual,
+            }
+        }
+    }
+
+    /// # Safety
+    /// `idx` must be below the committed length.
+    unsafe fn get_published(&self, idx: usize) -> &T {
+        let (bucket, offset) = location(idx);
+        // SAFETY: a published element's bucket is allocated and its value written, and
+        // the acquire load of the committed length made the write visible.
+        unsafe {
+            let first = self.buckets[bucket].load(Ordering::Acquire);
+            (*(*first.add(offset)).value.get()).assume_init_ref()
+        }
+    }
+}
+
+impl<T> Default for ConcurrentBrandedAppendVec<'_, T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Drop for ConcurrentBrandedAppendVec<'_, T> {
+    fn drop(&mut self) {
+        for (bucket, first) in self.buckets.iter_mut().enumerate() {
+            let first = *first.get_mut();
+            if first.is_null() {
+                continue;
+            }
+            // SAFETY: the bucket came from `Box::into_raw` in `bucket`, with this length.
+            let slots = unsafe {
+                Box::from_raw(ptr::slice_from_raw_parts_mut(first, FIRST_BUCKET << bucket))
+            };
+            for slot in &*slots {
+                if slot.ready.load(Ordering::Relaxed) {
+                    // SAFETY: a ready slot holds an initialized value, dropped once here.
+                    unsafe { (*slot.value.get()).assume_init_drop() };
+                }
+            }
+        }
+    }
+}
+
+/// A consistent view of a [`ConcurrentBrandedAppendVec`], iterating the elements that
+/// were published when it was taken.
+pub struct AppendVecSnapshot<'a, 'brand, T> {
+    vec: &'a ConcurrentBrandedAppendVec<'brand, T>,
+    front: usize,
+    back: usize,
+}
+
+impl<T> Clone for AppendVecSnapshot<'_, '_, T> {
+    fn clone(&self) -> Self {
+        Self {
+            vec: self.vec,
+            front: self.front,
+            back: self.back,
+        }
+    }
+}
+
+impl<'a, T> Iterator for AppendVecSnapshot<'a, '_, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<&'a T> {
+        if self.front == self.back {
+            return None;
+        }
+        // SAFETY: `back` never exceeds the committed length seen by `snapshot`.
+        let item = unsafe { self.vec.get_published(self.front) };
+        self.front += 1;
+        Some(item)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.back - self.front;
+        (len, Some(len))
+    }
+}
+
+impl<'a, T> DoubleEndedIterator for AppendVecSnapshot<'a, '_, T> {
+    fn next_back(&mut self) -> Option<&'a T> {
+        if self.front == self.back {
+            return None;
+        }
+        self.back -= 1;
+        // SAFETY: as in `next`.
+        Some(unsafe { self.vec.get_published(self.back) })
+    }
+}
+
+impl<T> ExactSizeIterator for AppendVecSnapshot<'_, '_, T> {}
+impl<T> FusedIterator for AppendVecSnapshot<'_, '_, T> {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::GhostToken;
+
+    #[test]
+    fn test_append_vec_push_get_snapshot() {
+        GhostToken::new(|token| {
+            let vec = ConcurrentBrandedAppendVec::new();
+            assert!(vec.is_empty());
+            for i in 0..100 {
+                assert_eq!(vec.push(&token, i.to_string()), i);
+            }
+            assert_eq!(vec.len(), 100);
+            assert_eq!(vec.get(&token, 40).map(String::as_str), Some("40"));
+            assert_eq!(vec.get(&token, 100), None);
+
+            let snapshot = vec.snapshot(&token);
+            vec.push(&token, "late".to_string());
+            assert_eq!(snapshot.len(), 100);
+            assert!(snapshot
+                .clone()
+                .eq((0..100).map(|i| i.to_string()).collect::<Vec<_>>().iter()));
+            assert_eq!(snapshot.rev().next().map(String::as_str), Some("99"));
+        });
+    }
+
+    #[test]
+    fn test_append_vec_snapshot_while_writers_push() {
+        const WRITERS: usize = 4;
+        const PER_WRITER: usize = 20_000;
+        GhostToken::new(|token| {
+            let vec = ConcurrentBrandedAppendVec::new();
+            let done = AtomicUsize::new(0);
+            std::thread::scope(|s| {
+                for t in 0..WRITERS {
+                    let (vec, done, token) = (&vec, &done, &token);
+                    s.spawn(move || {
+                        for seq in 0..PER_WRITER {
+                            vec.push(token, (t, seq));
+                        }
+                        done.fetch_add(1, Ordering::Release);
+                    });
+                }
+
+                let mut previous: Vec<(usize, usize)> = Vec::new();
+                while done.load(Ordering::Acquire) < WRITERS {
+                    let snapshot = vec.snapshot(&token);
+                    let len = snapshot.len();
+                    let items: Vec<(usize, usize)> = snapshot.copied().collect();
+                    assert_eq!(items.len(), len);
+                    // An earlier snapshot is a prefix of a later one.
+                    assert!(items.starts_with(&previous));
+                    // Each writer claims increasing indices, so its published elements
+                    // form a gapless prefix of its sequence.
+                    let mut next_seq = [0; WRITERS];
+                    for &(t, seq) in &items {
+                        assert_eq!(seq, next_seq[t]);
+                        next_seq[t] += 1;
+                    }
+                    previous = items;
+                }
+            });
+            assert_eq!(vec.len(), WRITERS * PER_WRITER);
+            assert_eq!(vec.snapshot(&token).count(), WRITERS * PER_WRITER);
+        });
+    }
+
+    #[test]
+    fn test_append_vec_drops_elements() {
+        let marker = std::sync::Arc::new(());
+        GhostToken::new(|token| {
+            let vec = ConcurrentBrandedAppendVec::new();
+            for _ in 0..1000 {
+                vec.push(&token, marker.clone());
+            }
+            assert_eq!(std::sync::Arc::strong_count(&marker), 1001);
+            drop(vec);
+        });
+        assert_eq!(std::sync::Arc::strong_count(&marker), 1);
+    }
+}
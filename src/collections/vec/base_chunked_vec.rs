@@ -54,8 +54,13 @@
 //! chunked_vec.for_each_mut(|x| *x *= 2);
 //! ```
 
+use crate::alloc::AllocError;
 use core::iter::FusedIterator;
+use core::ptr::NonNull;
 use core::{mem::MaybeUninit, ptr};
+use alloc_crate::alloc::Layout;
+#[cfg(not(feature = "std"))]
+use alloc_crate::{boxed::Box, vec::Vec};
 
 /// A vector backed by fixed-size chunks of `MaybeUninit<T>`.
 ///
@@ -64,9 +69,22 @@ use core::{mem::MaybeUninit, ptr};
 /// - **Contiguous Chunks**: Each chunk is an owned array on the heap, ensuring good cache locality for elements in the same chunk.
 /// - **Zero-cost Branding**: Works seamlessly with GhostToken-gated elements.
 /// - **Minimal Overhead**: Does not store capacity per element; only uses one `Vec` of `Box` pointers.
+///
+/// ### Address Stability
+/// An element never moves between the `push` that stores it and its removal. Growth
+/// allocates new chunks and only moves the `Box` pointers to them, and moving the
+/// `ChunkedVec` itself leaves the chunks where they are. Only `pop`, `swap_remove` and
+/// `truncate` end an element's life early, and `swap_remove` moves the last element
+/// into the freed slot. [`StableHandle`] makes this part of the API: it records an
+/// element's address once, for intrusive structures that link elements to one another.
+/// Each removal bumps its slot's generation, so a handle to a removed element is
+/// rejected even after a later push reuses the slot.
 pub struct ChunkedVec<T, const CHUNK: usize> {
     chunks: Vec<Box<[MaybeUninit<T>; CHUNK]>>,
     len: usize,
+    /// Generation of each slot, bumped whenever the slot's element is removed or
+    /// replaced. Only grown when a slot is vacated; missing entries are generation 0.
+    generations: Vec<u32>,
 }
 
 impl<T, const CHUNK: usize> ChunkedVec<T, CHUNK> {
@@ -76,6 +94,7 @@ impl<T, const CHUNK: usize> ChunkedVec<T, CHUNK> {
         Self {
             chunks: Vec::new(),
             len: 0,
+            generations: Vec::new(),
         }
     }
 
@@ -129,6 +148,36 @@ impl<T, const CHUNK: usize> ChunkedVec<T, CHUNK> {
         }
     }
 
+    /// Tries to reserve enough space for at least `additional` more elements.
+    ///
+    /// # Errors
+    /// Returns `AllocError` if the capacity overflows or a chunk allocation fails.
+    /// Chunks allocated before the failure are kept.
+    pub fn try_reserve(&mut self, additional: usize) -> Result<(), AllocError> {
+        let needed = self.len.checked_add(additional).ok_or(AllocError)?;
+        if needed <= self.capacity() {
+            return Ok(());
+        }
+        let needed_chunks = needed.div_ceil(CHUNK);
+        self.chunks
+            .try_reserve(needed_chunks - self.chunks.len())
+            .map_err(|_| AllocError)?;
+        while self.chunks.len() < needed_chunks {
+            self.chunks.push(try_new_uninit_chunk::<T, CHUNK>()?);
+        }
+        Ok(())
+    }
+
+    /// Pushes an element and returns its index, reporting allocation failure instead
+    /// of aborting.
+    ///
+    /// # Errors
+    /// Returns `AllocError` if a new chunk cannot be allocated; `value` is dropped.
+    pub fn try_push(&mut self, value: T) -> Result<usize, AllocError> {
+        self.try_reserve(1)?;
+        Ok(self.push(value))
+    }
+
     /// Pushes an element and returns its index.
     pub fn push(&mut self, value: T) -> usize {
         assert!(CHUNK != 0, "ChunkedVec CHUNK must be > 0");
@@ -151,6 +200,39 @@ impl<T, const CHUNK: usize> ChunkedVec<T, CHUNK> {
         idx
     }
 
+    /// Pushes an element and returns a [`StableHandle`] to it.
+    pub fn push_get_handle(&mut self, value: T) -> StableHandle<T, CHUNK> {
+        let index = self.push(value);
+        let Some(handle) = self.handle(index) else {
+            unreachable!("element was just pushed");
+        };
+        handle
+    }
+
+    /// Returns a [`StableHandle`] to element `idx` if in-bounds.
+    pub fn handle(&self, idx: usize) -> Option<StableHandle<T, CHUNK>> {
+        self.get(idx).map(|element| StableHandle {
+            index: idx,
+            generation: self.generation(idx),
+            ptr: NonNull::from(element),
+        })
+    }
+
+    #[inline]
+    fn generation(&self, idx: usize) -> u32 {
+        self.generations.get(idx).copied().unwrap_or(0)
+    }
+
+    /// Invalidates every handle to slots `start..end`.
+    fn bump_generations(&mut self, start: usize, end: usize) {
+        if self.generations.len() < end {
+            self.generations.resize(end, 0);
+        }
+        for generation in &mut self.generations[start..end] {
+            *generation = generation.wrapping_add(1);
+        }
+    }
+
     /// Returns a shared reference to element `idx` if in-bounds.
     pub fn get(&self, idx: usize) -> Option<&T> {
         if idx >= self.len {
@@ -197,6 +279,75 @@ impl<T, const CHUNK: usize> ChunkedVec<T, CHUNK> {
         }
     }
 
+    /// Removes the last element and returns it, or `None` if the vector is empty.
+    ///
+    /// The chunk is kept allocated for later pushes.
+    pub fn pop(&mut self) -> Option<T> {
+        if self.len == 0 {
+            return None;
+        }
+        self.len -= 1;
+        self.bump_generations(self.len, self.len + 1);
+        // SAFETY: the slot at the old last index is initialized, and lowering `len`
+        // first hands ownership of it to us.
+        Some(unsafe { self.slot_ptr(self.len).read() })
+    }
+
+    /// Removes the element at `idx` and returns it, moving the last element into its
+    /// place. O(1), but does not preserve order.
+    ///
+    /// The moved element changes address, so handles to it and to the removed element
+    /// both go stale.
+    ///
+    /// # Panics
+    /// Panics if `idx >= len`.
+    pub fn swap_remove(&mut self, idx: usize) -> T {
+        let len = self.len;
+        assert!(idx < len, "swap_remove index {idx} out of bounds for length {len}");
+        let last = self.pop().expect("vector is non-empty");
+        if idx == len - 1 {
+            return last;
+        }
+        self.bump_generations(idx, idx + 1);
+        // SAFETY: `idx < self.len` after the pop, so the slot is initialized.
+        unsafe { ptr::replace(self.slot_ptr(idx), last) }
+    }
+
+    /// Shortens the vector to `len` elements, dropping the rest. Has no effect if `len`
+    /// is not less than the current length.
+    ///
+    /// Chunks stay allocated, so the capacity is unchanged.
+    pub fn truncate(&mut self, len: usize) {
+        if self.len > len {
+            self.bump_generations(len, self.len);
+        }
+        while self.len > len {
+            let (c, o) = index_split::<CHUNK>(self.len - 1);
+            let keep = len.max(c * CHUNK);
+            let tail_len = self.len - keep;
+            // Lower `len` before dropping so a panicking destructor cannot cause a
+            // double drop.
+            self.len = keep;
+            // SAFETY: slots `keep..=old last` lie in chunk `c` and are initialized.
+            unsafe {
+                let base: *mut T = self.chunks.get_unchecked_mut(c).as_mut_ptr().cast();
+                let first = o + 1 - tail_len;
+                ptr::drop_in_place(ptr::slice_from_raw_parts_mut(base.add(first), tail_len));
+            }
+        }
+    }
+
+    /// Returns a pointer to the slot at `idx`.
+    ///
+    /// # Safety
+    /// `idx` must be below the capacity.
+    #[inline]
+    unsafe fn slot_ptr(&mut self, idx: usize) -> *mut T {
+        let (c, o) = index_split::<CHUNK>(idx);
+        let base: *mut T = self.chunks.get_unchecked_mut(c).as_mut_ptr().cast();
+        base.add(o)
+    }
+
     /// Returns an iterator over `&T`.
     #[inline]
     pub fn iter(&self) -> ChunkedIter<'_, T, CHUNK> {
@@ -216,15 +367,10 @@ impl<T, const CHUNK: usize> ChunkedVec<T, CHUNK> {
             return;
         }
 
-        for (chunk_idx, chunk) in self.chunks.iter().enumerate() {
-            let initialized_count = if chunk_idx == self.chunks.len() - 1 {
-                // Last chunk: only count initialized elements
-                let elements_in_chunk = self.len - (chunk_idx * CHUNK);
-                elements_in_chunk
-            } else {
-                // Full chunk
-                CHUNK
-            };
+        let used = self.len.div_ceil(CHUNK);
+        for (chunk_idx, chunk) in self.chunks[..used].iter().enumerate() {
+            // Chunks past `len` may be allocated by `reserve` or left by `truncate`.
+            let initialized_count = (self.len - chunk_idx * CHUNK).min(CHUNK);
 
             for i in 0..initialized_count {
                 // SAFETY: We only access initialized elements within bounds
@@ -249,17 +395,10 @@ impl<T, const CHUNK: usize> ChunkedVec<T, CHUNK> {
             return;
         }
 
-        let num_chunks = self.chunks.len();
-        for chunk_idx in 0..num_chunks {
+        let used = self.len.div_ceil(CHUNK);
+        for chunk_idx in 0..used {
             let chunk = &mut self.chunks[chunk_idx];
-            let initialized_count = if chunk_idx == num_chunks - 1 {
-                // Last chunk: only count initialized elements
-                let elements_in_chunk = self.len - (chunk_idx * CHUNK);
-                elements_in_chunk
-            } else {
-                // Full chunk
-                CHUNK
-            };
+            let initialized_count = (self.len - chunk_idx * CHUNK).min(CHUNK);
 
             for i in 0..initialized_count {
                 // SAFETY: We only access initialized elements within bounds
@@ -289,7 +428,6 @@ impl<T, const CHUNK: usize> ChunkedVec<T, CHUNK> {
             return;
         }
 
-        let num_chunks = self.chunks.len();
         let mut current_idx = start;
         while current_idx < end {
             let (chunk_idx, elem_idx) = index_split::<CHUNK>(current_idx);
@@ -297,13 +435,7 @@ impl<T, const CHUNK: usize> ChunkedVec<T, CHUNK> {
 
             // Calculate how many elements we can process in this chunk
             let chunk_start = current_idx;
-            let chunk_end = if chunk_idx == num_chunks - 1 {
-                // Last chunk: only up to initialized elements
-                len
-            } else {
-                // Full chunk
-                (chunk_idx + 1) * CHUNK
-            };
+            let chunk_end = ((chunk_idx + 1) * CHUNK).min(len);
             let process_end = chunk_end.min(end);
 
             for i in elem_idx..(elem_idx + (process_end - chunk_start)) {
@@ -318,6 +450,58 @@ impl<T, const CHUNK: usize> ChunkedVec<T, CHUNK> {
             current_idx = process_end;
         }
     }
+
+    /// Iterates over all elements by shared reference, in parallel.
+    ///
+    /// Work is split along chunk boundaries, so each rayon task reads whole chunks.
+    #[cfg(feature = "rayon")]
+    pub fn par_iter(&self) -> impl rayon::iter::ParallelIterator<Item = &T> + '_
+    where
+        T: Sync,
+    {
+        use rayon::prelude::*;
+        let len = self.len;
+        self.chunks
+            .par_iter()
+            .enumerate()
+            .flat_map_iter(move |(chunk_idx, chunk)| {
+                // Chunks past `len` may be allocated by `reserve`; they yield nothing.
+                let initialized_count = len.saturating_sub(chunk_idx * CHUNK).min(CHUNK);
+                // SAFETY: the first `initialized_count` slots of this chunk are initialized.
+                unsafe {
+                    let base: *const T = chunk.as_ptr().cast();
+                    core::slice::from_raw_parts(base, initialized_count).iter()
+                }
+            })
+    }
+
+    /// Yields the initialized part of each chunk as a disjoint `&mut [T]` region, in
+    /// parallel.
+    ///
+    /// Every region except the last holds exactly `CHUNK` elements.
+    #[cfg(feature = "rayon")]
+    pub fn par_chunks_mut(
+        &mut self,
+    ) -> impl rayon::iter::IndexedParallelIterator<Item = &mut [T]> + '_
+    where
+        T: Send,
+    {
+        use rayon::prelude::*;
+        let len = self.len;
+        let used = len.div_ceil(CHUNK);
+        self.chunks[..used]
+            .par_iter_mut()
+            .enumerate()
+            .map(move |(chunk_idx, chunk)| {
+                let initialized_count = (len - chunk_idx * CHUNK).min(CHUNK);
+                // SAFETY: `chunk_idx < used`, so the first `initialized_count` slots are
+                // initialized, and each chunk is borrowed by exactly one task.
+                unsafe {
+                    let base: *mut T = chunk.as_mut_ptr().cast();
+                    core::slice::from_raw_parts_mut(base, initialized_count)
+                }
+            })
+    }
 }
 
 impl<T, const CHUNK: usize> Default for ChunkedVec<T, CHUNK> {
@@ -363,6 +547,122 @@ impl<T, const CHUNK: usize> Drop for ChunkedVec<T, CHUNK> {
     }
 }
 
+/// The index, slot generation and fixed address of an element of a [`ChunkedVec`].
+///
+/// Handles are `Copy` and hold no borrow, so elements can store handles to other
+/// elements of the same vector. Access goes through the vector, which checks that the
+/// handle was taken from it and that its element has not been removed since;
+/// [`as_ptr`](Self::as_ptr) exposes the address itself, which stays valid until the
+/// element is removed or moved, or the vector is dropped (see the address-stability
+/// guarantee on [`ChunkedVec`]).
+pub struct StableHandle<T, const CHUNK: usize> {
+    index: usize,
+    generation: u32,
+    ptr: NonNull<T>,
+}
+
+impl<T, const CHUNK: usize> StableHandle<T, CHUNK> {
+    /// Returns the index of the element.
+    #[inline]
+    pub fn index(&self) -> usize {
+        self.index
+    }
+
+    /// Returns the address of the element.
+    ///
+    /// Dereferencing it is sound while the element is still in the vector at this
+    /// address and the vector is not otherwise borrowed in a conflicting way.
+    #[inline]
+    pub fn as_ptr(&self) -> NonNull<T> {
+        self.ptr
+    }
+
+    /// Returns `true` if the handle was taken from `vec` and its element is still
+    /// there: `false` once the element has been removed, even if a later push has
+    /// reused its slot.
+    #[inline]
+    pub fn is_live(&self, vec: &ChunkedVec<T, CHUNK>) -> bool {
+        self.validate(vec).is_ok()
+    }
+
+    fn validate(&self, vec: &ChunkedVec<T, CHUNK>) -> Result<(), &'static str> {
+        // Chunks are never freed while the vector lives, so a matching slot address at
+        // this index proves the handle came from `vec`.
+        let (c, o) = index_split::<CHUNK>(self.index);
+        let slot = vec
+            .chunks
+            .get(c)
+            .map(|chunk| chunk.as_ptr().cast::<T>().wrapping_add(o));
+        if slot != Some(self.ptr.as_ptr().cast_const()) {
+            return Err("StableHandle used with a different ChunkedVec");
+        }
+        if self.index >= vec.len || vec.generation(self.index) != self.generation {
+            return Err("stale StableHandle: its element was removed");
+        }
+        Ok(())
+    }
+
+    #[inline]
+    fn check(&self, vec: &ChunkedVec<T, CHUNK>) {
+        if let Err(msg) = self.validate(vec) {
+            panic!("{msg}");
+        }
+    }
+
+    /// Returns a reference to the element.
+    ///
+    /// # Panics
+    /// Panics if the handle was not taken from `vec`, or its element was removed.
+    #[inline]
+    pub fn get<'a>(&self, vec: &'a ChunkedVec<T, CHUNK>) -> &'a T {
+        self.check(vec);
+        // SAFETY: the element belongs to `vec`, which is borrowed for `'a`.
+        unsafe { self.ptr.as_ref() }
+    }
+
+    /// Returns a mutable reference to the element.
+    ///
+    /// # Panics
+    /// Panics if the handle was not taken from `vec`, or its element was removed.
+    #[inline]
+    pub fn get_mut<'a>(&self, vec: &'a mut ChunkedVec<T, CHUNK>) -> &'a mut T {
+        self.check(vec);
+        // SAFETY: the element belongs to `vec`, which is exclusively borrowed for `'a`.
+        unsafe { &mut *self.ptr.as_ptr() }
+    }
+}
+
+impl<T, const CHUNK: usize> Clone for StableHandle<T, CHUNK> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T, const CHUNK: usize> Copy for StableHandle<T, CHUNK> {}
+
+impl<T, const CHUNK: usize> PartialEq for StableHandle<T, CHUNK> {
+    fn eq(&self, other: &Self) -> bool {
+        self.ptr == other.ptr && self.generation == other.generation
+    }
+}
+
+impl<T, const CHUNK: usize> Eq for StableHandle<T, CHUNK> {}
+
+impl<T, const CHUNK: usize> core::fmt::Debug for StableHandle<T, CHUNK> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("StableHandle")
+            .field("index", &self.index)
+            .field("generation", &self.generation)
+            .field("ptr", &self.ptr)
+            .finish()
+    }
+}
+
+// SAFETY: a handle is an index, a generation and an address; the element can only be reached through
+// a borrow of its vector (or unsafe code), so sharing the handle itself is harmless.
+unsafe impl<T, const CHUNK: usize> Send for StableHandle<T, CHUNK> {}
+unsafe impl<T, const CHUNK: usize> Sync for StableHandle<T, CHUNK> {}
+
 /// Iterator over `&T` for a `ChunkedVec`.
 pub struct ChunkedIter<'a, T, const CHUNK: usize> {
     vec: &'a ChunkedVec<T, CHUNK>,
@@ -417,6 +717,122 @@ mod tests {
         assert_eq!(sum, (0..v.len()).sum::<usize>());
     }
 
+    #[test]
+    fn chunked_vec_stable_handles_survive_growth_and_moves() {
+        const CHUNK: usize = 4;
+        let mut v: ChunkedVec<String, CHUNK> = ChunkedVec::new();
+        let handles: Vec<StableHandle<String, CHUNK>> =
+            (0..10).map(|i| v.push_get_handle(i.to_string())).collect();
+        let addresses: Vec<*const String> = handles
+            .iter()
+            .map(|h| h.as_ptr().as_ptr().cast_const())
+            .collect();
+
+        // Grow well past the chunk table's capacity, then move the vector.
+        for i in 10..1000 {
+            v.push(i.to_string());
+        }
+        let mut moved = v;
+        for (i, h) in handles.iter().enumerate() {
+            assert_eq!(h.index(), i);
+            assert_eq!(h.get(&moved), &i.to_string());
+            assert!(ptr::eq(h.get(&moved), addresses[i]));
+        }
+        handles[3].get_mut(&mut moved).push('!');
+        assert_eq!(moved.get(3).map(String::as_str), Some("3!"));
+        assert_eq!(moved.handle(3), Some(handles[3]));
+        assert_eq!(moved.handle(1000), None);
+    }
+
+    #[test]
+    fn chunked_vec_stable_handles_link_elements() {
+        struct Node {
+            value: u32,
+            next: Option<StableHandle<Node, 2>>,
+        }
+
+        let mut nodes: ChunkedVec<Node, 2> = ChunkedVec::new();
+        let mut head = None;
+        for value in 0..5 {
+            head = Some(nodes.push_get_handle(Node { value, next: head }));
+        }
+        let mut values = Vec::new();
+        let mut cursor = head;
+        while let Some(h) = cursor {
+            let node = h.get(&nodes);
+            values.push(node.value);
+            cursor = node.next;
+        }
+        assert_eq!(values, [4, 3, 2, 1, 0]);
+    }
+
+    #[test]
+    #[should_panic(expected = "different ChunkedVec")]
+    fn chunked_vec_stable_handle_rejects_other_vec() {
+        let mut a: ChunkedVec<u8, 4> = ChunkedVec::new();
+        let mut b: ChunkedVec<u8, 4> = ChunkedVec::new();
+        let h = a.push_get_handle(1);
+        b.push(1);
+        h.get(&b);
+    }
+
+    #[test]
+    fn chunked_vec_stable_handles_go_stale_on_swap_remove() {
+        let mut v: ChunkedVec<u32, 4> = ChunkedVec::new();
+        let handles: Vec<_> = (0..6).map(|i| v.push_get_handle(i)).collect();
+        assert_eq!(v.swap_remove(1), 1);
+
+        // The removed element's slot now holds the moved one, at its old address, and
+        // the moved element's old slot is empty: both handles are stale.
+        assert_eq!(v.get(1), Some(&5));
+        assert!(!handles[1].is_live(&v));
+        assert!(!handles[5].is_live(&v));
+        assert!(handles.iter().enumerate().all(|(i, h)| h.is_live(&v) == !matches!(i, 1 | 5)));
+        let fresh = v.handle(1).unwrap();
+        assert_ne!(fresh, handles[1]);
+        assert_eq!(*fresh.get(&v), 5);
+
+        // Removing the last element in place still invalidates its handle.
+        assert_eq!(v.swap_remove(4), 4);
+        assert!(!handles[4].is_live(&v));
+        v.truncate(1);
+        assert!(handles[0].is_live(&v));
+        assert!(!handles[2].is_live(&v) && !fresh.is_live(&v));
+    }
+
+    #[test]
+    #[should_panic(expected = "stale StableHandle")]
+    fn chunked_vec_stable_handle_rejects_reused_slot_after_pop_and_push() {
+        let mut v: ChunkedVec<u32, 4> = ChunkedVec::new();
+        v.push(0);
+        let h = v.push_get_handle(1);
+        assert_eq!(v.pop(), Some(1));
+        v.push(2);
+        // Same index, same address, different element.
+        assert_eq!(v.handle(1).unwrap().as_ptr(), h.as_ptr());
+        assert!(!h.is_live(&v));
+        let _ = h.get(&v);
+    }
+
+    #[cfg(feature = "rayon")]
+    #[test]
+    fn chunked_vec_par_iter_and_par_chunks_mut() {
+        use rayon::prelude::*;
+
+        let mut v: ChunkedVec<usize, 8> = ChunkedVec::new();
+        v.reserve(64);
+        for i in 0..21 {
+            v.push(i);
+        }
+        assert_eq!(v.par_iter().count(), 21);
+        assert_eq!(v.par_iter().sum::<usize>(), (0..21).sum::<usize>());
+
+        let lens: Vec<usize> = v.par_chunks_mut().map(|c| c.len()).collect();
+        assert_eq!(lens, vec![8, 8, 5]);
+        v.par_chunks_mut().for_each(|c| c.iter_mut().for_each(|x| *x *= 2));
+        assert!(v.iter().copied().eq((0..21).map(|i| i * 2)));
+    }
+
     #[test]
     fn chunked_vec_push_get_across_chunks_non_power_of_two_chunk() {
         const CHUNK: usize = 6;
@@ -548,6 +964,50 @@ mod tests {
         assert_eq!(*v.get(6).unwrap(), 12); // 6 * 2
         assert_eq!(*v.get(7).unwrap(), 7); // unchanged
     }
+
+    #[test]
+    fn chunked_vec_fallible_growth() {
+        let mut v: ChunkedVec<u32, 4> = ChunkedVec::new();
+        v.try_reserve(5).unwrap();
+        assert_eq!(v.chunk_count(), 2);
+        for i in 0..6 {
+            assert_eq!(v.try_push(i), Ok(i as usize));
+        }
+        assert_eq!(v.chunk_count(), 2);
+        assert_eq!(v.try_reserve(usize::MAX), Err(AllocError));
+        assert_eq!(v.len(), 6);
+    }
+
+    #[test]
+    fn chunked_vec_pop_swap_remove_truncate() {
+        use alloc_crate::rc::Rc;
+
+        let marker = Rc::new(());
+        let mut v: ChunkedVec<(u32, Rc<()>), 4> = ChunkedVec::new();
+        for i in 0..11 {
+            v.push((i, marker.clone()));
+        }
+        assert_eq!(v.swap_remove(1).0, 1);
+        assert_eq!(v.get(1).unwrap().0, 10);
+        assert_eq!(v.swap_remove(9).0, 9);
+        assert_eq!(v.pop().unwrap().0, 8);
+        assert_eq!(v.len(), 8);
+
+        v.truncate(3);
+        assert_eq!(Rc::strong_count(&marker), 4);
+        assert_eq!(v.chunk_count(), 3);
+        let mut seen = Vec::new();
+        v.for_each(|(i, _)| seen.push(*i));
+        assert_eq!(seen, [0, 10, 2]);
+
+        v.push((20, marker.clone()));
+        v.push((21, marker.clone()));
+        v.for_each_mut(|(i, _)| *i += 1);
+        assert_eq!(v.iter().map(|(i, _)| *i).collect::<Vec<_>>(), [1, 11, 3, 21, 22]);
+        v.truncate(0);
+        assert!(v.pop().is_none());
+        assert_eq!(Rc::strong_count(&marker), 1);
+    }
 }
 
 #[inline(always)]
@@ -571,3 +1031,19 @@ fn new_uninit_chunk<T, const CHUNK: usize>() -> Box<[MaybeUninit<T>; CHUNK]> {
     // write elements individually and only drop the initialized prefix.
     unsafe { Box::<[MaybeUninit<T>; CHUNK]>::new_uninit().assume_init() }
 }
+
+#[inline]
+fn try_new_uninit_chunk<T, const CHUNK: usize>() -> Result<Box<[MaybeUninit<T>; CHUNK]>, AllocError> {
+    let layout = Layout::new::<[MaybeUninit<T>; CHUNK]>();
+    if layout.size() == 0 {
+        return Ok(new_uninit_chunk::<T, CHUNK>());
+    }
+    // SAFETY: `layout` has a non-zero size.
+    let ptr = unsafe { alloc_crate::alloc::alloc(layout) }.cast::<[MaybeUninit<T>; CHUNK]>();
+    if ptr.is_null() {
+        return Err(AllocError);
+    }
+    // SAFETY: `ptr` comes from the global allocator with the array's layout, and an
+    // uninitialized `[MaybeUninit<T>; CHUNK]` is valid.
+    Ok(unsafe { Box::from_raw(ptr) })
+}
@@ -24,7 +24,10 @@ use crate::collections::ZeroCopyOps;
 use crate::token::traits::{GhostBorrow, GhostBorrowMut};
 use crate::GhostCell;
 use core::mem::MaybeUninit;
-use std::slice;
+use core::ptr;
+use core::slice;
+#[cfg(not(feature = "std"))]
+use alloc_crate::boxed::Box;
 
 /// Zero-cost iterator for BrandedChunkedVec.
 pub struct BrandedChunkedVecIter<'a, 'brand, T, const CHUNK: usize, Token> {
@@ -50,7 +53,9 @@ where
                 // But for now, direct access via borrow is good.
                 // SAFETY: We checked bounds (chunk_index < len)
                 let item = unsafe {
-                    node.chunk.data.get_unchecked(self.chunk_index).borrow(self.token)
+                    node.chunk
+                        .get_unchecked(self.chunk_index)
+                        .borrow(self.token)
                 };
                 self.chunk_index += 1;
                 return Some(item);
@@ -90,7 +95,7 @@ pub struct ChunkMutIter<'a, 'brand, T, const CHUNK: usize, Token> {
     current: Option<&'a ChunkNode<'brand, T, CHUNK>>,
     // We rely on the token conceptually, but don't need it at runtime
     // because we use unsafe pointer casting based on the token's authority.
-    _marker: std::marker::PhantomData<&'a mut Token>,
+    _marker: core::marker::PhantomData<&'a mut Token>,
 }
 
 impl<'a, 'brand, T, const CHUNK: usize, Token> Iterator for ChunkMutIter<'a, 'brand, T, CHUNK, Token>
@@ -127,8 +132,9 @@ where
 #[repr(C, align(64))] // Cache line alignment for optimal performance
 struct BrandedChunk<'brand, T, const CHUNK: usize> {
     /// The branded data - entire chunk is token-gated as one unit
-    /// Stored first for optimal cache line utilization
-    data: [GhostCell<'brand, T>; CHUNK],
+    /// Stored first for optimal cache line utilization; only the first
+    /// `initialized` slots hold values.
+    data: [MaybeUninit<GhostCell<'brand, T>>; CHUNK],
     /// Number of initialized elements in this chunk
     /// Separated to avoid cache line pollution during bulk access
     initialized: usize,
@@ -137,12 +143,9 @@ struct BrandedChunk<'brand, T, const CHUNK: usize> {
 impl<'brand, T, const CHUNK: usize> BrandedChunk<'brand, T, CHUNK> {
     /// Creates a new empty chunk.
     const fn new() -> Self {
-        // SAFETY: GhostCell<T> can be zero-initialized
-        unsafe {
-            Self {
-                data: MaybeUninit::uninit().assume_init(),
-                initialized: 0,
-            }
+        Self {
+            data: [const { MaybeUninit::uninit() }; CHUNK],
+            initialized: 0,
         }
     }
 
@@ -165,8 +168,9 @@ impl<'brand, T, const CHUNK: usize> BrandedChunk<'brand, T, CHUNK> {
     #[inline(always)]
     unsafe fn push_unchecked(&mut self, value: T) {
         debug_assert!(self.has_space());
-        let slot = self.data.get_unchecked_mut(self.initialized);
-        *slot = GhostCell::new(value);
+        self.data
+            .get_unchecked_mut(self.initialized)
+            .write(GhostCell::new(value));
         self.initialized += 1;
     }
 
@@ -177,7 +181,17 @@ impl<'brand, T, const CHUNK: usize> BrandedChunk<'brand, T, CHUNK> {
     #[inline(always)]
     unsafe fn get_unchecked(&self, index: usize) -> &GhostCell<'brand, T> {
         debug_assert!(index < self.initialized);
-        self.data.get_unchecked(index)
+        self.data.get_unchecked(index).assume_init_ref()
+    }
+
+    /// Gets an exclusive reference to an element in the chunk.
+    ///
+    /// # Safety
+    /// `index` must be < `len()`.
+    #[inline]
+    unsafe fn get_unchecked_mut(&mut self, index: usize) -> &mut GhostCell<'brand, T> {
+        debug_assert!(index < self.initialized);
+        self.data.get_unchecked_mut(index).assume_init_mut()
     }
 
     /// Returns the chunk as a shared slice.
@@ -221,6 +235,19 @@ impl<'brand, T, const CHUNK: usize> BrandedChunk<'brand, T, CHUNK> {
     }
 }
 
+impl<'brand, T, const CHUNK: usize> Drop for BrandedChunk<'brand, T, CHUNK> {
+    fn drop(&mut self) {
+        // SAFETY: the first `initialized` slots hold values, and `MaybeUninit` and
+        // `GhostCell` are layout-transparent over `T`.
+        unsafe {
+            ptr::drop_in_place(ptr::slice_from_raw_parts_mut(
+                self.data.as_mut_ptr().cast::<T>(),
+                self.initialized,
+            ));
+        }
+    }
+}
+
 /// A linked list node for chunks.
 struct ChunkNode<'brand, T, const CHUNK: usize> {
     chunk: BrandedChunk<'brand, T, CHUNK>,
@@ -381,7 +408,7 @@ impl<'brand, T, const CHUNK: usize> BrandedChunkedVec<'brand, T, CHUNK> {
 
         unsafe {
             // Accessing inner data directly:
-            let cell_ref = current.chunk.data.get_unchecked_mut(elem_idx);
+            let cell_ref = current.chunk.get_unchecked_mut(elem_idx);
             Some(cell_ref.get_mut())
         }
     }
@@ -402,6 +429,131 @@ impl<'brand, T, const CHUNK: usize> BrandedChunkedVec<'brand, T, CHUNK> {
         }
     }
 
+    /// Iterates over the elements by exclusive reference.
+    #[inline]
+    pub fn iter_mut<'a, Token>(
+        &'a self,
+        token: &'a mut Token,
+    ) -> impl Iterator<Item = &'a mut T> + use<'a, 'brand, T, CHUNK, Token>
+    where
+        Token: GhostBorrowMut<'brand>,
+    {
+        self.chunks_mut(token).flatten()
+    }
+
+    /// Iterates over the elements by exclusive reference, without a token.
+    ///
+    /// This requires exclusive access to the vector (`&mut self`).
+    pub fn iter_mut_exclusive(
+        &mut self,
+    ) -> impl Iterator<Item = &mut T> + use<'_, 'brand, T, CHUNK> {
+        let mut current = self.head.as_deref_mut();
+        core::iter::from_fn(move || {
+            let ChunkNode { chunk, next } = current.take()?;
+            current = next.as_deref_mut();
+            Some(chunk.as_mut_slice_exclusive())
+        })
+        .flatten()
+    }
+
+    /// Removes and drops every element, releasing all chunks.
+    pub fn clear(&mut self) {
+        // Unlink chunks one at a time so long chains are not dropped recursively.
+        let mut next = self.head.take();
+        while let Some(mut node) = next {
+            next = node.next.take();
+        }
+        self.len = 0;
+    }
+
+    /// Removes the last element and returns it, or `None` if the vector is empty.
+    ///
+    /// A chunk left empty is released.
+    pub fn pop(&mut self) -> Option<T> {
+        if self.len == 0 {
+            return None;
+        }
+        let (chunk_idx, elem_idx) = Self::index_to_chunk(self.len - 1);
+        let node = self.node_mut(chunk_idx);
+        node.chunk.initialized -= 1;
+        // SAFETY: the slot was the chunk's last initialized one, and lowering
+        // `initialized` hands ownership of it to us.
+        let value = unsafe { node.chunk.data.get_unchecked(elem_idx).assume_init_read() };
+        self.len -= 1;
+        if elem_idx == 0 {
+            self.unlink_from(chunk_idx);
+        }
+        Some(value.into_inner())
+    }
+
+    /// Removes the element at `index` and returns it, moving the last element into its
+    /// place. Does not preserve order.
+    ///
+    /// # Panics
+    /// Panics if `index >= len`.
+    pub fn swap_remove(&mut self, index: usize) -> T {
+        let len = self.len;
+        assert!(
+            index < len,
+            "swap_remove index {index} out of bounds for length {len}"
+        );
+        let last = self.pop().expect("vector is non-empty");
+        if index == len - 1 {
+            return last;
+        }
+        let (chunk_idx, elem_idx) = Self::index_to_chunk(index);
+        // SAFETY: `index < self.len` after the pop, so the slot is initialized.
+        let cell = unsafe { self.node_mut(chunk_idx).chunk.get_unchecked_mut(elem_idx) };
+        core::mem::replace(cell.get_mut(), last)
+    }
+
+    /// Shortens the vector to `len` elements, dropping the rest and releasing chunks
+    /// left empty. Has no effect if `len` is not less than the current length.
+    pub fn truncate(&mut self, len: usize) {
+        if len >= self.len {
+            return;
+        }
+        let (chunk_idx, elem_idx) = Self::index_to_chunk(len);
+        // Keep the chunk holding index `len` only if some of its elements survive.
+        let kept_chunks = chunk_idx + usize::from(elem_idx > 0);
+        self.unlink_from(kept_chunks);
+        self.len = len;
+        if elem_idx > 0 {
+            let chunk = &mut self.node_mut(chunk_idx).chunk;
+            let old = chunk.initialized;
+            chunk.initialized = elem_idx;
+            // SAFETY: slots `elem_idx..old` were initialized and are no longer counted.
+            unsafe {
+                ptr::drop_in_place(ptr::slice_from_raw_parts_mut(
+                    chunk.data.as_mut_ptr().add(elem_idx).cast::<T>(),
+                    old - elem_idx,
+                ));
+            }
+        }
+    }
+
+    /// Returns the node of chunk `chunk_idx`, which must exist.
+    fn node_mut(&mut self, chunk_idx: usize) -> &mut ChunkNode<'brand, T, CHUNK> {
+        let mut current = self.head.as_deref_mut().expect("chunk exists");
+        for _ in 0..chunk_idx {
+            current = current.next.as_deref_mut().expect("chunk exists");
+        }
+        current
+    }
+
+    /// Releases chunk `chunk_idx` and every chunk after it.
+    fn unlink_from(&mut self, chunk_idx: usize) {
+        let mut next = if chunk_idx == 0 {
+            self.head.take()
+        } else {
+            self.node_mut(chunk_idx - 1).next.take()
+        };
+        // Unlink one at a time so long chains are not dropped recursively.
+        while let Some(mut node) = next {
+            next = node.next.take();
+        }
+    }
+
     /// Returns an iterator over chunks as slices.
     pub fn chunks<'a, Token>(
         &'a self,
@@ -426,7 +578,7 @@ impl<'brand, T, const CHUNK: usize> BrandedChunkedVec<'brand, T, CHUNK> {
     {
         ChunkMutIter {
             current: self.head.as_deref(),
-            _marker: std::marker::PhantomData::<&mut Token>,
+            _marker: core::marker::PhantomData::<&mut Token>,
         }
     }
 
@@ -570,9 +722,9 @@ impl<'brand, T, const CHUNK: usize> Default for BrandedChunkedVec<'brand, T, CHU
 
 impl<'brand, T, const CHUNK: usize> Drop for BrandedChunkedVec<'brand, T, CHUNK> {
     fn drop(&mut self) {
-        // The chunks will be dropped automatically, but we need to ensure
-        // GhostCells are properly cleaned up. Since GhostCell implements Drop,
-        // this should happen naturally.
+        // Each chunk drops its own initialized elements; this only avoids recursing
+        // through the chunk list.
+        self.clear();
     }
 }
 
@@ -723,4 +875,74 @@ mod tests {
             assert_eq!(*vec.get(&token, 3).unwrap(), 40);
         });
     }
+    #[test]
+    fn branded_chunked_vec_iter_mut_and_clear() {
+        GhostToken::new(|mut token| {
+            let mut vec = BrandedChunkedVec::<_, 2>::new();
+            for i in 0..5 {
+                vec.push(i.to_string());
+            }
+            vec.iter_mut(&mut token).for_each(|s| s.push('!'));
+            let all: Vec<&str> = vec.iter(&token).map(String::as_str).collect();
+            assert_eq!(all, ["0!", "1!", "2!", "3!", "4!"]);
+
+            for s in vec.iter_mut_exclusive() {
+                s.pop();
+            }
+            assert_eq!(vec.get(&token, 4).map(String::as_str), Some("4"));
+
+            vec.clear();
+            assert!(vec.is_empty());
+            assert_eq!(vec.chunk_count(), 0);
+            assert_eq!(vec.push("again".to_string()), 0);
+            assert_eq!(vec.get(&token, 0).map(String::as_str), Some("again"));
+        });
+    }
+
+    #[test]
+    fn branded_chunked_vec_drops_only_initialized_elements() {
+        use alloc_crate::rc::Rc;
+
+        let counter = Rc::new(());
+        {
+            let mut vec = BrandedChunkedVec::<_, 4>::new();
+            for _ in 0..5 {
+                vec.push(Rc::clone(&counter));
+            }
+            assert_eq!(Rc::strong_count(&counter), 6);
+        }
+        assert_eq!(Rc::strong_count(&counter), 1);
+    }
+
+    #[test]
+    fn branded_chunked_vec_swap_remove_and_truncate() {
+        use alloc_crate::rc::Rc;
+
+        let counter = Rc::new(());
+        GhostToken::new(|token| {
+            let mut vec = BrandedChunkedVec::<_, 3>::new();
+            for i in 0..10 {
+                vec.push((i, Rc::clone(&counter)));
+            }
+            assert_eq!(vec.swap_remove(0).0, 0);
+            assert_eq!(vec.swap_remove(8).0, 8);
+            assert_eq!(vec.pop().unwrap().0, 7);
+            assert_eq!(vec.chunk_count(), 3);
+            let ids: Vec<u32> = vec.iter(&token).map(|(i, _)| *i).collect();
+            assert_eq!(ids, [9, 1, 2, 3, 4, 5, 6]);
+
+            vec.truncate(4);
+            assert_eq!(vec.chunk_count(), 2);
+            assert_eq!(Rc::strong_count(&counter), 5);
+            vec.truncate(3);
+            assert_eq!(vec.chunk_count(), 1);
+            assert_eq!(vec.push((30, Rc::clone(&counter))), 3);
+            assert_eq!(vec.get(&token, 3).map(|(i, _)| *i), Some(30));
+
+            vec.truncate(0);
+            assert!(vec.is_empty() && vec.pop().is_none());
+            assert_eq!(vec.chunk_count(), 0);
+        });
+        assert_eq!(Rc::strong_count(&counter), 1);
+    }
 }
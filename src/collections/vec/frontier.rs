@@ -0,0 +1,224 @@
+//! `BrandedFrontier` — a double-buffered work container for level-synchronous algorithms.
+//!
+//! Level-synchronous algorithms (BFS layering, Hopcroft–Karp phases, k-core peeling,
+//! Bellman–Ford style relaxation rounds) repeatedly consume a *current* batch of work
+//! while producing the *next* batch. The naive pattern allocates a fresh `Vec` for
+//! every level; `BrandedFrontier` instead keeps two [`BrandedVecDeque`] buffers and
+//! swaps them in `O(1)` at each level boundary, so their allocations are reused for
+//! the entire run.
+
+use crate::collections::vec::vec_deque::{self, BrandedVecDeque};
+use crate::token::traits::{GhostBorrow, GhostBorrowMut};
+use crate::GhostCell;
+use core::mem;
+
+/// A double-buffered (`current` / `next`) frontier of token-gated elements.
+pub struct BrandedFrontier<'brand, T> {
+    current: BrandedVecDeque<'brand, T>,
+    next: BrandedVecDeque<'brand, T>,
+    level: usize,
+}
+
+impl<'brand, T> BrandedFrontier<'brand, T> {
+    /// Creates an empty frontier at level 0.
+    pub fn new() -> Self {
+        Self {
+            current: BrandedVecDeque::new(),
+            next: BrandedVecDeque::new(),
+            level: 0,
+        }
+    }
+
+    /// Creates an empty frontier where each buffer can hold `capacity` elements
+    /// without reallocating.
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            current: BrandedVecDeque::with_capacity(capacity),
+            next: BrandedVecDeque::with_capacity(capacity),
+            level: 0,
+        }
+    }
+
+    /// Returns the number of completed level swaps.
+    #[inline]
+    pub fn level(&self) -> usize {
+        self.level
+    }
+
+    /// Number of elements remaining in the current level.
+    #[inline]
+    pub fn current_len(&self) -> usize {
+        self.current.len()
+    }
+
+    /// Number of elements queued for the next level.
+    #[inline]
+    pub fn next_len(&self) -> usize {
+        self.next.len()
+    }
+
+    /// Returns `true` if both the current and next levels are empty.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.current.is_empty() && self.next.is_empty()
+    }
+
+    /// Pushes an element into the current level.
+    ///
+    /// Typically used to seed the frontier before the first level is processed.
+    #[inline]
+    pub fn push_current(&mut self, value: T) {
+        self.current.push_back(value);
+    }
+
+    /// Pushes an element into the next level.
+    #[inline]
+    pub fn push_next(&mut self, value: T) {
+        self.next.push_back(value);
+    }
+
+    /// Pops the next element of the current level, in FIFO order.
+    #[inline]
+    pub fn pop_current(&mut self) -> Option<T> {
+        self.current.pop_front().map(GhostCell::into_inner)
+    }
+
+    /// Advances to the next level.
+    ///
+    /// Any elements left in the current level are dropped; the `next` buffer becomes
+    /// the `current` buffer and the old `current` allocation is reused for the new
+    /// `next` level. Returns `true` if the new current level is non-empty.
+    pub fn advance(&mut self) -> bool {
+        self.current.clear();
+        mem::swap(&mut self.current, &mut self.next);
+        self.level += 1;
+        !self.current.is_empty()
+    }
+
+    /// Drains the current level, yielding its elements in FIFO order.
+    ///
+    /// To push into the next level while consuming, use
+    /// [`process_level`](Self::process_level) instead.
+    pub fn drain_current(&mut self) -> vec_deque::Drain<'_, 'brand, T> {
+        self.current.drain(..)
+    }
+
+    /// Processes every element of the current level, allowing `f` to push work into
+    /// the next level, and then advances.
+    ///
+    /// Returns `true` if the new current level is non-empty.
+    pub fn process_level(&mut self, mut f: impl FnMut(T, &mut BrandedVecDeque<'brand, T>)) -> bool {
+        while let Some(cell) = self.current.pop_front() {
+            f(cell.into_inner(), &mut self.next);
+        }
+        self.advance()
+    }
+
+    /// Returns a shared reference to the element at `idx` in the current level.
+    #[inline]
+    pub fn get_current<'a, Token>(&'a self, token: &'a Token, idx: usize) -> Option<&'a T>
+    where
+        Token: GhostBorrow<'brand>,
+    {
+        self.current.get(token, idx)
+    }
+
+    /// Applies `f` to every element of the current level.
+    #[inline]
+    pub fn for_each_current<Token>(&self, token: &Token, f: impl FnMut(&T))
+    where
+        Token: GhostBorrow<'brand>,
+    {
+        self.current.for_each(token, f);
+    }
+
+    /// Applies `f` to every element of the current level, mutably.
+    #[inline]
+    pub fn for_each_current_mut<Token>(&self, token: &mut Token, f: impl FnMut(&mut T))
+    where
+        Token: GhostBorrowMut<'brand>,
+    {
+        self.current.for_each_mut(token, f);
+    }
+
+    /// Read-only view of the current level buffer.
+    #[inline]
+    pub fn current(&self) -> &BrandedVecDeque<'brand, T> {
+        &self.current
+    }
+
+    /// Read-only view of the next level buffer.
+    #[inline]
+    pub fn next(&self) -> &BrandedVecDeque<'brand, T> {
+        &self.next
+    }
+
+    /// Clears both buffers and resets the level counter, keeping allocations.
+    pub fn clear(&mut self) {
+        self.current.clear();
+        self.next.clear();
+        self.level = 0;
+    }
+}
+
+impl<'brand, T> Default for BrandedFrontier<'brand, T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::GhostToken;
+
+    #[test]
+    fn frontier_swaps_levels() {
+        GhostToken::new(|token| {
+            let mut f = BrandedFrontier::with_capacity(4);
+            f.push_current(1u32);
+            f.push_next(2);
+            f.push_next(3);
+            assert_eq!(f.current_len(), 1);
+            assert_eq!(f.next_len(), 2);
+
+            assert_eq!(f.pop_current(), Some(1));
+            assert!(f.advance());
+            assert_eq!(f.level(), 1);
+            assert_eq!(f.get_current(&token, 0), Some(&2));
+
+            let mut seen = Vec::new();
+            f.for_each_current(&token, |x| seen.push(*x));
+            assert_eq!(seen, vec![2, 3]);
+
+            assert_eq!(f.drain_current().collect::<Vec<_>>(), vec![2, 3]);
+            assert!(!f.advance());
+            assert!(f.is_empty());
+        });
+    }
+
+    #[test]
+    fn frontier_process_level_layers() {
+        // Binary-tree layering: node n has children 2n+1, 2n+2 while < 7.
+        let mut f = BrandedFrontier::new();
+        f.push_current(0usize);
+        let mut layers = Vec::new();
+        loop {
+            let mut layer = Vec::new();
+            let more = f.process_level(|n, next| {
+                layer.push(n);
+                for c in [2 * n + 1, 2 * n + 2] {
+                    if c < 7 {
+                        next.push_back(c);
+                    }
+                }
+            });
+            layers.push(layer);
+            if !more {
+                break;
+            }
+        }
+        assert_eq!(layers, vec![vec![0], vec![1, 2], vec![3, 4, 5, 6]]);
+        assert_eq!(f.level(), 3);
+    }
+}
@@ -13,8 +13,8 @@
 
 use crate::collections::vec::{slice::BrandedSlice, slice::BrandedSliceMut, BrandedVec};
 use crate::{GhostCell, GhostToken};
-use std::marker::PhantomData;
-use std::slice;
+use core::marker::PhantomData;
+use core::slice;
 
 /// A branded 2D matrix.
 pub struct BrandedMatrix<'brand, T> {
@@ -4,19 +4,27 @@
 //! branded for safe concurrent access patterns.
 
 pub mod active;
+pub mod append_vec;
 pub mod base_chunked_vec;
 pub mod chunked_vec;
+pub mod frontier;
 pub mod matrix;
+pub mod packed_int_vec;
 pub mod slice;
 pub mod small_vec;
 pub mod vec;
 pub mod vec_deque;
+pub mod vec_map;
 
 pub use active::{ActivateVec, ActiveVec};
-pub use base_chunked_vec::ChunkedVec;
+pub use append_vec::{AppendVecSnapshot, ConcurrentBrandedAppendVec};
+pub use base_chunked_vec::{ChunkedVec, StableHandle};
 pub use chunked_vec::BrandedChunkedVec;
+pub use frontier::BrandedFrontier;
 pub use matrix::{BrandedMatrix, BrandedMatrixViewMut};
-pub use slice::{BrandedSlice, BrandedSliceMut};
+pub use packed_int_vec::BrandedPackedIntVec;
+pub use slice::{BrandedSlice, BrandedSliceMut, BrandedVecView};
 pub use small_vec::BrandedSmallVec;
 pub use vec::{BrandedArray, BrandedVec};
 pub use vec_deque::BrandedVecDeque;
+pub use vec_map::BrandedVecMap;
@@ -0,0 +1,345 @@
+//! `BrandedPackedIntVec` — a vector of fixed-width small integers, bit-packed.
+//!
+//! Every element occupies exactly `width` bits (1..=64, chosen per vector) of a
+//! contiguous `BrandedVec<u64>` word array; elements may straddle a word boundary.
+//! Storing per-node labels from a tiny domain (colors, levels, small counters) this
+//! way takes `width / 64` of the memory of a `Vec<usize>`.
+//!
+//! Access is controlled via `GhostToken`, like the other word-backed structures
+//! (see [`BrandedBitSet`](crate::collections::other::bit_set::BrandedBitSet)).
+
+use crate::collections::vec::BrandedVec;
+use crate::token::traits::{GhostBorrow, GhostBorrowMut};
+
+const WORD_BITS: usize = 64;
+
+/// A branded vector of `width`-bit unsigned integers.
+pub struct BrandedPackedIntVec<'brand> {
+    words: BrandedVec<'brand, u64>,
+    width: u32,
+    len: usize,
+}
+
+#[inline]
+fn low_mask(width: u32) -> u64 {
+    if width == 64 {
+        u64::MAX
+    } else {
+        (1u64 << width) - 1
+    }
+}
+
+#[inline]
+fn words_for(len: usize, width: u32) -> usize {
+    (len * width as usize).div_ceil(WORD_BITS)
+}
+
+/// Reads the `width`-bit field starting at bit `pos`.
+#[inline]
+fn read_field(words: &[u64], pos: usize, width: u32) -> u64 {
+    let word = pos / WORD_BITS;
+    let offset = pos % WORD_BITS;
+    let mut value = words[word] >> offset;
+    if offset + width as usize > WORD_BITS {
+        value |= words[word + 1] << (WORD_BITS - offset);
+    }
+    value & low_mask(width)
+}
+
+/// Overwrites the `width`-bit field starting at bit `pos` with `value`.
+#[inline]
+fn write_field(words: &mut [u64], pos: usize, width: u32, value: u64) {
+    let mask = low_mask(width);
+    let word = pos / WORD_BITS;
+    let offset = pos % WORD_BITS;
+    words[word] = (words[word] & !(mask << offset)) | (value << offset);
+    if offset + width as usize > WORD_BITS {
+        let shift = WORD_BITS - offset;
+        words[word + 1] = (words[word + 1] & !(mask >> shift)) | (value >> shift);
+    }
+}
+
+impl<'brand> BrandedPackedIntVec<'brand> {
+    /// Creates an empty vector of `width`-bit integers.
+    ///
+    /// # Panics
+    /// Panics if `width` is not in `1..=64`.
+    pub fn new(width: u32) -> Self {
+        Self::with_capacity(width, 0)
+    }
+
+    /// Creates an empty vector with room for `capacity` elements.
+    ///
+    /// # Panics
+    /// Panics if `width` is not in `1..=64`.
+    pub fn with_capacity(width: u32, capacity: usize) -> Self {
+        assert!((1..=64).contains(&width), "width must be in 1..=64");
+        Self {
+            words: BrandedVec::with_capacity(words_for(capacity, width)),
+            width,
+            len: 0,
+        }
+    }
+
+    /// Creates a vector of `len` copies of `value`.
+    ///
+    /// # Panics
+    /// Panics if `width` is not in `1..=64` or `value` does not fit in `width` bits.
+    pub fn from_elem(width: u32, value: u64, len: usize) -> Self {
+        let mut v = Self::with_capacity(width, len);
+        v.resize(len, value);
+        v
+    }
+
+    /// Returns the number of bits per element.
+    #[inline]
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    /// Returns the largest value an element can hold.
+    #[inline]
+    pub fn max_value(&self) -> u64 {
+        low_mask(self.width)
+    }
+
+    /// Returns the number of elements.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if the vector has no elements.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns the number of heap bytes used by the packed words.
+    #[inline]
+    pub fn size_in_bytes(&self) -> usize {
+        self.words.len() * core::mem::size_of::<u64>()
+    }
+
+    #[inline]
+    fn check_value(&self, value: u64) {
+        assert!(
+            value <= self.max_value(),
+            "value {value} does not fit in {} bits",
+            self.width
+        );
+    }
+
+    /// Appends `value`.
+    ///
+    /// # Panics
+    /// Panics if `value` does not fit in `width` bits.
+    pub fn push(&mut self, value: u64) {
+        self.check_value(value);
+        let needed = words_for(self.len + 1, self.width);
+        if self.words.len() < needed {
+            self.words.push(0);
+        }
+        let pos = self.len * self.width as usize;
+        write_field(self.words.as_mut_slice_exclusive(), pos, self.width, value);
+        self.len += 1;
+    }
+
+    /// Removes and returns the last element.
+    pub fn pop(&mut self) -> Option<u64> {
+        if self.len == 0 {
+            return None;
+        }
+        let pos = (self.len - 1) * self.width as usize;
+        let value = read_field(self.words.as_mut_slice_exclusive(), pos, self.width);
+        self.truncate(self.len - 1);
+        Some(value)
+    }
+
+    /// Resizes to `new_len` elements, filling new slots with `value`.
+    ///
+    /// # Panics
+    /// Panics if `value` does not fit in `width` bits.
+    pub fn resize(&mut self, new_len: usize, value: u64) {
+        self.check_value(value);
+        if new_len <= self.len {
+            self.truncate(new_len);
+            return;
+        }
+        self.words
+            .reserve(words_for(new_len, self.width).saturating_sub(self.words.len()));
+        for _ in self.len..new_len {
+            self.push(value);
+        }
+    }
+
+    /// Shortens the vector to `len` elements; no-op if already shorter.
+    ///
+    /// Stale bits left in the last word are harmless: writes always clear a field
+    /// before setting it.
+    pub fn truncate(&mut self, len: usize) {
+        if len < self.len {
+            self.len = len;
+            self.words.truncate(words_for(len, self.width));
+        }
+    }
+
+    /// Removes all elements.
+    pub fn clear(&mut self) {
+        self.words.clear();
+        self.len = 0;
+    }
+
+    /// Returns the element at `idx`, or `None` if out of bounds.
+    #[inline]
+    pub fn get<Token>(&self, token: &Token, idx: usize) -> Option<u64>
+    where
+        Token: GhostBorrow<'brand>,
+    {
+        if idx >= self.len {
+            return None;
+        }
+        Some(read_field(
+            self.words.as_slice(token),
+            idx * self.width as usize,
+            self.width,
+        ))
+    }
+
+    /// Overwrites the element at `idx`, returning the previous value.
+    ///
+    /// # Panics
+    /// Panics if `idx >= len()` or `value` does not fit in `width` bits.
+    #[inline]
+    pub fn set<Token>(&self, token: &mut Token, idx: usize, value: u64) -> u64
+    where
+        Token: GhostBorrowMut<'brand>,
+    {
+        assert!(idx < self.len, "index out of bounds");
+        self.check_value(value);
+        let pos = idx * self.width as usize;
+        let words = self.words.as_mut_slice(token);
+        let old = read_field(words, pos, self.width);
+        write_field(words, pos, self.width, value);
+        old
+    }
+
+    /// Iterates over all elements in order.
+    pub fn iter<'a, Token>(&'a self, token: &'a Token) -> Iter<'a>
+    where
+        Token: GhostBorrow<'brand>,
+    {
+        Iter {
+            words: self.words.as_slice(token),
+            width: self.width,
+            pos: 0,
+            end: self.len * self.width as usize,
+        }
+    }
+
+    /// Applies `f` to every element, storing the returned value.
+    ///
+    /// # Panics
+    /// Panics if `f` returns a value that does not fit in `width` bits.
+    pub fn for_each_mut<Token>(&self, token: &mut Token, mut f: impl FnMut(u64) -> u64)
+    where
+        Token: GhostBorrowMut<'brand>,
+    {
+        let width = self.width;
+        let max = self.max_value();
+        let words = self.words.as_mut_slice(token);
+        for pos in (0..self.len).map(|i| i * width as usize) {
+            let value = f(read_field(words, pos, width));
+            assert!(value <= max, "value {value} does not fit in {width} bits");
+            write_field(words, pos, width, value);
+        }
+    }
+}
+
+/// Iterator over the elements of a [`BrandedPackedIntVec`].
+pub struct Iter<'a> {
+    words: &'a [u64],
+    width: u32,
+    pos: usize,
+    end: usize,
+}
+
+impl Iterator for Iter<'_> {
+    type Item = u64;
+
+    #[inline]
+    fn next(&mut self) -> Option<u64> {
+        if self.pos >= self.end {
+            return None;
+        }
+        let value = read_field(self.words, self.pos, self.width);
+        self.pos += self.width as usize;
+        Some(value)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let n = (self.end - self.pos) / self.width as usize;
+        (n, Some(n))
+    }
+}
+
+impl ExactSizeIterator for Iter<'_> {}
+impl core::iter::FusedIterator for Iter<'_> {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::GhostToken;
+
+    #[test]
+    fn packed_int_vec_roundtrip_across_word_boundaries() {
+        GhostToken::new(|mut token| {
+            // Width 5 does not divide 64, so elements straddle words.
+            let mut v = BrandedPackedIntVec::new(5);
+            for i in 0..100u64 {
+                v.push(i % 32);
+            }
+            assert_eq!(v.len(), 100);
+            assert_eq!(v.size_in_bytes(), 500usize.div_ceil(64) * 8);
+            assert!(v.iter(&token).eq((0..100u64).map(|i| i % 32)));
+
+            assert_eq!(v.set(&mut token, 12, 31), 12);
+            assert_eq!(v.get(&token, 12), Some(31));
+            assert_eq!(v.get(&token, 11), Some(11));
+            assert_eq!(v.get(&token, 13), Some(13));
+            assert_eq!(v.get(&token, 100), None);
+
+            v.for_each_mut(&mut token, |x| x / 2);
+            assert_eq!(v.get(&token, 12), Some(15));
+
+            assert_eq!(v.pop(), Some(99 % 32 / 2));
+            v.truncate(10);
+            assert_eq!(v.iter(&token).len(), 10);
+        });
+    }
+
+    #[test]
+    fn packed_int_vec_full_width_and_bounds() {
+        GhostToken::new(|mut token| {
+            let v = BrandedPackedIntVec::from_elem(64, u64::MAX, 3);
+            assert!(v.iter(&token).all(|x| x == u64::MAX));
+            v.set(&mut token, 1, 7);
+            assert_eq!(
+                v.iter(&token).collect::<Vec<_>>(),
+                vec![u64::MAX, 7, u64::MAX]
+            );
+
+            let mut bits = BrandedPackedIntVec::new(1);
+            bits.resize(65, 1);
+            assert_eq!(bits.iter(&token).sum::<u64>(), 65);
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "does not fit")]
+    fn packed_int_vec_rejects_wide_values() {
+        let mut v = BrandedPackedIntVec::new(3);
+        v.push(8);
+    }
+}
@@ -8,10 +8,13 @@
 //!   capability itself because holding `&mut GhostCell` allows exclusive access to the
 //!   inner value *without* needing the original `GhostToken`. This allows splitting
 //!   mutable access to a vector into disjoint regions that can be mutated in parallel.
+//! - `BrandedVecView<'a, T>` is a region handed out by `BrandedVec::split_views`. It is
+//!   detached from the vector's brand and can be re-branded with a fresh region token,
+//!   so each worker gets its own token-gated sub-collection.
 
 use crate::{GhostCell, GhostToken};
 use crate::token::traits::GhostBorrow;
-use std::slice;
+use core::slice;
 
 /// A slice of token-gated elements, bundled with the token required to read them.
 pub struct BrandedSlice<'a, 'brand, T, Token = GhostToken<'brand>>
@@ -105,8 +108,8 @@ where
     /// Returns a sub-slice.
     pub fn sub_slice<R>(&self, range: R) -> Self
     where
-        R: std::ops::RangeBounds<usize>
-            + std::slice::SliceIndex<[GhostCell<'brand, T>], Output = [GhostCell<'brand, T>]>,
+        R: core::ops::RangeBounds<usize>
+            + core::slice::SliceIndex<[GhostCell<'brand, T>], Output = [GhostCell<'brand, T>]>,
     {
         Self {
             slice: &self.slice[range],
@@ -236,8 +239,8 @@ impl<'a, 'brand, T> BrandedSliceMut<'a, 'brand, T> {
     /// Returns a mutable sub-slice.
     pub fn sub_slice_mut<R>(self, range: R) -> Self
     where
-        R: std::ops::RangeBounds<usize>
-            + std::slice::SliceIndex<[GhostCell<'brand, T>], Output = [GhostCell<'brand, T>]>,
+        R: core::ops::RangeBounds<usize>
+            + core::slice::SliceIndex<[GhostCell<'brand, T>], Output = [GhostCell<'brand, T>]>,
     {
         Self {
             slice: &mut self.slice[range],
@@ -255,7 +258,7 @@ impl<'a, 'brand, T> BrandedSliceMut<'a, 'brand, T> {
     /// Sorts the slice with a comparator function.
     pub fn sort_by<F>(&mut self, compare: F)
     where
-        F: FnMut(&T, &T) -> std::cmp::Ordering,
+        F: FnMut(&T, &T) -> core::cmp::Ordering,
     {
         self.as_mut_slice().sort_by(compare);
     }
@@ -284,6 +287,73 @@ impl<'a, 'brand, T> IntoIterator for BrandedSliceMut<'a, 'brand, T> {
     }
 }
 
+/// A disjoint region of a `BrandedVec`, produced by
+/// [`BrandedVec::split_views`](crate::collections::BrandedVec::split_views).
+///
+/// The view owns exclusive access to its elements for `'a` and carries no brand of its
+/// own. Call [`with_region`](Self::with_region) to open it under a fresh region brand:
+/// the closure receives the region's cells together with a `GhostToken` for that brand,
+/// so code written against branded cells works unchanged on the region. Views are
+/// `Send` when `T` is, so disjoint regions can be processed on different threads.
+pub struct BrandedVecView<'a, T> {
+    offset: usize,
+    slice: &'a mut [T],
+}
+
+impl<'a, T> BrandedVecView<'a, T> {
+    pub(crate) fn new(offset: usize, slice: &'a mut [T]) -> Self {
+        Self { offset, slice }
+    }
+
+    /// Returns the index in the parent vector of the view's first element.
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+
+    /// Returns the number of elements in the view.
+    pub fn len(&self) -> usize {
+        self.slice.len()
+    }
+
+    /// Returns `true` if the view is empty.
+    pub fn is_empty(&self) -> bool {
+        self.slice.is_empty()
+    }
+
+    /// Returns the view's elements as a mutable slice.
+    pub fn as_mut_slice(&mut self) -> &mut [T] {
+        self.slice
+    }
+
+    /// Consumes the view, returning its elements as a mutable slice.
+    pub fn into_mut_slice(self) -> &'a mut [T] {
+        self.slice
+    }
+
+    /// Opens the view under a fresh region brand.
+    ///
+    /// `f` receives the view's cells, branded `'region`, and the only token of that
+    /// brand. Neither can escape the closure, so the region stays isolated from every
+    /// other view and from the parent vector.
+    pub fn with_region<R>(
+        self,
+        f: impl for<'r, 'region> FnOnce(&'r [GhostCell<'region, T>], GhostToken<'region>) -> R,
+    ) -> R {
+        GhostToken::new(|token| {
+            // SAFETY: `GhostCell<T>` is `repr(transparent)` over `T`, and the view's
+            // exclusive borrow is consumed here, so the cells are only reachable through
+            // the fresh `token`.
+            let cells = unsafe {
+                slice::from_raw_parts(
+                    self.slice.as_mut_ptr() as *const GhostCell<'_, T>,
+                    self.slice.len(),
+                )
+            };
+            f(cells, token)
+        })
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -325,4 +395,60 @@ mod tests {
             assert_eq!(*vec.get(&token, 0).unwrap(), 1);
         });
     }
+
+    #[test]
+    fn test_split_views_regions() {
+        GhostToken::new(|mut token| {
+            let vec: BrandedVec<'_, u64> = (0..100).collect();
+            {
+                let views = vec.split_views(&mut token, [50..100, 0..10, 10..50]);
+                assert_eq!(
+                    views
+                        .iter()
+                        .map(|v| (v.offset(), v.len()))
+                        .collect::<Vec<_>>(),
+                    [(50, 50), (0, 10), (10, 40)]
+                );
+                std::thread::scope(|s| {
+                    for view in views {
+                        s.spawn(move || {
+                            let offset = view.offset() as u64;
+                            view.with_region(|cells, mut region| {
+                                for (i, cell) in cells.iter().enumerate() {
+                                    *cell.borrow_mut(&mut region) += offset * 1000 + i as u64;
+                                }
+                            });
+                        });
+                    }
+                });
+            }
+            assert_eq!(*vec.get(&token, 0).unwrap(), 0);
+            assert_eq!(*vec.get(&token, 12).unwrap(), 12 + 10_000 + 2);
+            assert_eq!(*vec.get(&token, 99).unwrap(), 99 + 50_000 + 49);
+        });
+    }
+
+    #[test]
+    fn test_split_views_empty_range_sharing_a_start() {
+        GhostToken::new(|mut token| {
+            let vec: BrandedVec<'_, u8> = (0..10).collect();
+            for ranges in [[3..5, 3..3, 5..5], [3..3, 3..5, 5..5], [5..5, 3..5, 3..3]] {
+                let views = vec.split_views(&mut token, ranges.clone());
+                let spans: Vec<_> = views
+                    .iter()
+                    .map(|v| v.offset()..v.offset() + v.len())
+                    .collect();
+                assert_eq!(spans, ranges);
+            }
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "overlap")]
+    fn test_split_views_rejects_overlap() {
+        GhostToken::new(|mut token| {
+            let vec: BrandedVec<'_, u8> = (0..10).collect();
+            let _ = vec.split_views(&mut token, [0..5, 4..6]);
+        });
+    }
 }
@@ -165,7 +165,7 @@ impl<'brand, T, const N: usize> BrandedSmallVec<'brand, T, N> {
     pub fn iter<'a, Token>(
         &'a self,
         token: &'a Token,
-    ) -> impl Iterator<Item = &'a T> + ExactSizeIterator + DoubleEndedIterator + std::iter::FusedIterator + use<'a, 'brand, T, N, Token>
+    ) -> impl Iterator<Item = &'a T> + ExactSizeIterator + DoubleEndedIterator + core::iter::FusedIterator + use<'a, 'brand, T, N, Token>
     where
         Token: GhostBorrow<'brand>,
     {
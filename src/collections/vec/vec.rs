@@ -14,9 +14,16 @@
 //!
 //! This is exactly the separation of *permissions* (token) from *data* (cells).
 
+use crate::alloc::AllocError;
+#[cfg(feature = "rayon")]
+use crate::collections::vec::BrandedSliceMut;
+use crate::collections::vec::BrandedVecView;
 use crate::GhostCell;
 use crate::token::traits::{GhostBorrow, GhostBorrowMut};
-use std::mem::MaybeUninit;
+use core::mem::MaybeUninit;
+use core::ptr;
+#[cfg(not(feature = "std"))]
+use alloc_crate::vec::Vec;
 
 /// Compile-time assertion types for const generics bounds checking
 pub struct Assert<const COND: bool>;
@@ -82,11 +89,52 @@ impl<'brand, T> BrandedVec<'brand, T> {
         self.inner.reserve(additional);
     }
 
+    /// Tries to reserve capacity for at least `additional` more elements.
+    ///
+    /// # Errors
+    /// Returns `AllocError` if the capacity overflows or the allocator fails; the
+    /// vector is left unchanged.
+    pub fn try_reserve(&mut self, additional: usize) -> Result<(), AllocError> {
+        self.inner.try_reserve(additional).map_err(|_| AllocError)
+    }
+
     /// Pushes a new element.
     pub fn push(&mut self, value: T) {
         self.inner.push(GhostCell::new(value));
     }
 
+    /// Pushes a new element, reporting allocation failure instead of aborting.
+    ///
+    /// # Errors
+    /// Returns `AllocError` if growing the buffer fails; `value` is dropped.
+    pub fn try_push(&mut self, value: T) -> Result<(), AllocError> {
+        self.try_reserve(1)?;
+        self.push(value);
+        Ok(())
+    }
+
+    /// Consumes the vector, returning its elements as a plain `Vec` that no longer
+    /// carries the brand and can leave the token scope. No element is moved or copied.
+    pub fn into_vec(self) -> Vec<T> {
+        let mut inner = core::mem::ManuallyDrop::new(self.inner);
+        // SAFETY: `GhostCell<T>` is `repr(transparent)` over `T`, so the buffer has the
+        // same layout and the same allocation parameters as a `Vec<T>`.
+        unsafe {
+            Vec::from_raw_parts(
+                inner.as_mut_ptr().cast::<T>(),
+                inner.len(),
+                inner.capacity(),
+            )
+        }
+    }
+
+    /// Moves every element of `other` onto the end of `self`, leaving `other` empty.
+    ///
+    /// The cells are moved with one bulk copy; both vectors must share the brand.
+    pub fn append(&mut self, other: &mut Self) {
+        self.inner.append(&mut other.inner);
+    }
+
     /// Pops the last element.
     pub fn pop(&mut self) -> Option<GhostCell<'brand, T>> {
         self.inner.pop()
@@ -161,6 +209,43 @@ impl<'brand, T> BrandedVec<'brand, T> {
         self.inner.retain(|c| f(c.borrow_mut(token)));
     }
 
+    /// Sorts the vector with a comparator, stably.
+    ///
+    /// Elements are resolved through the shared `token`, so the comparator can also use
+    /// it to follow handles into other branded storage (e.g. sort node indices by a key
+    /// stored in an arena) without copying keys out first.
+    pub fn sort_by<F, Token>(&mut self, token: &Token, mut compare: F)
+    where
+        F: FnMut(&T, &T) -> core::cmp::Ordering,
+        Token: GhostBorrow<'brand>,
+    {
+        self.inner.sort_by(|a, b| compare(a.borrow(token), b.borrow(token)));
+    }
+
+    /// Sorts the vector by a key extraction function, without preserving the order of
+    /// equal elements.
+    pub fn sort_unstable_by_key<K, F, Token>(&mut self, token: &Token, mut f: F)
+    where
+        F: FnMut(&T) -> K,
+        K: Ord,
+        Token: GhostBorrow<'brand>,
+    {
+        self.inner.sort_unstable_by_key(|c| f(c.borrow(token)));
+    }
+
+    /// Sorts the vector by a key extraction function, calling `f` once per element.
+    ///
+    /// Prefer this over [`sort_unstable_by_key`](Self::sort_unstable_by_key) when the key
+    /// is expensive to compute (e.g. it walks several branded structures).
+    pub fn sort_by_cached_key<K, F, Token>(&mut self, token: &Token, mut f: F)
+    where
+        F: FnMut(&T) -> K,
+        K: Ord,
+        Token: GhostBorrow<'brand>,
+    {
+        self.inner.sort_by_cached_key(|c| f(c.borrow(token)));
+    }
+
     /// Returns a token-gated shared reference to element `idx`, if in bounds.
     #[inline(always)]
     pub fn get<'a, Token>(&'a self, token: &'a Token, idx: usize) -> Option<&'a T>
@@ -191,6 +276,27 @@ impl<'brand, T> BrandedVec<'brand, T> {
         }
     }
 
+    /// Returns token-gated exclusive references to several distinct elements at once.
+    ///
+    /// Mirrors [`slice::get_disjoint_mut`]: fails if any index is out of bounds or if
+    /// two indices are equal. Useful for updating both endpoints of an edge.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`GetDisjointMutError`](core::slice::GetDisjointMutError) if an index is
+    /// out of bounds or repeated.
+    #[inline]
+    pub fn get_disjoint_mut<'a, Token, const N: usize>(
+        &'a self,
+        token: &'a mut Token,
+        indices: [usize; N],
+    ) -> Result<[&'a mut T; N], core::slice::GetDisjointMutError>
+    where
+        Token: GhostBorrowMut<'brand>,
+    {
+        self.as_mut_slice(token).get_disjoint_mut(indices)
+    }
+
     /// Returns a token-gated shared reference to element `idx` without bounds checking.
     ///
     /// # Safety
@@ -281,7 +387,7 @@ impl<'brand, T> BrandedVec<'brand, T> {
         // We obtain a pointer to elements and create a slice.
         unsafe {
             let ptr = self.inner.as_ptr() as *const T;
-            std::slice::from_raw_parts(ptr, self.inner.len())
+            core::slice::from_raw_parts(ptr, self.inner.len())
         }
     }
 
@@ -303,8 +409,56 @@ impl<'brand, T> BrandedVec<'brand, T> {
     {
         unsafe {
             let ptr = self.inner.as_ptr() as *mut T;
-            std::slice::from_raw_parts_mut(ptr, self.inner.len())
+            core::slice::from_raw_parts_mut(ptr, self.inner.len())
+        }
+    }
+
+    /// Splits the vector into disjoint views over `ranges`, returned in the same order.
+    ///
+    /// The mutable token is held for as long as any view is alive, so the views are the
+    /// only way to reach the elements meanwhile. Each view can be moved to its own
+    /// thread and opened under a fresh region brand with
+    /// [`BrandedVecView::with_region`]; nothing is copied.
+    ///
+    /// # Panics
+    /// Panics if a range is out of bounds, decreasing, or overlaps another range.
+    pub fn split_views<'a, Token, I>(
+        &'a self,
+        token: &'a mut Token,
+        ranges: I,
+    ) -> Vec<BrandedVecView<'a, T>>
+    where
+        Token: GhostBorrowMut<'brand>,
+        I: IntoIterator<Item = core::ops::Range<usize>>,
+    {
+        let mut order: Vec<(usize, core::ops::Range<usize>)> =
+            ranges.into_iter().enumerate().collect();
+        // An empty range sorts before a range starting at the same index, so it never
+        // looks like an overlap.
+        order.sort_unstable_by_key(|(_, range)| (range.start, range.end));
+
+        let mut views: Vec<Option<BrandedVecView<'a, T>>> =
+            core::iter::repeat_with(|| None).take(order.len()).collect();
+        let mut rest = self.as_mut_slice(token);
+        let mut consumed = 0;
+        for (slot, range) in order {
+            assert!(
+                range.start <= range.end && range.end <= self.len(),
+                "split_views range {range:?} out of bounds for length {}",
+                self.len()
+            );
+            assert!(
+                range.start >= consumed,
+                "split_views ranges overlap at index {}",
+                range.start
+            );
+            let (_, tail) = core::mem::take(&mut rest).split_at_mut(range.start - consumed);
+            let (view, tail) = tail.split_at_mut(range.len());
+            views[slot] = Some(BrandedVecView::new(range.start, view));
+            rest = tail;
+            consumed = range.end;
         }
+        views.into_iter().map(Option::unwrap).collect()
     }
 
     /// Returns a mutable slice of the underlying elements without a token.
@@ -321,7 +475,7 @@ impl<'brand, T> BrandedVec<'brand, T> {
     pub fn as_mut_slice_exclusive(&mut self) -> &mut [T] {
         unsafe {
             let ptr = self.inner.as_mut_ptr() as *mut T;
-            std::slice::from_raw_parts_mut(ptr, self.inner.len())
+            core::slice::from_raw_parts_mut(ptr, self.inner.len())
         }
     }
 
@@ -337,7 +491,7 @@ impl<'brand, T> BrandedVec<'brand, T> {
     ) -> impl Iterator<Item = &'a T>
            + ExactSizeIterator
            + DoubleEndedIterator
-           + std::iter::FusedIterator
+           + core::iter::FusedIterator
            + use<'a, 'brand, T, Token>
     where
         Token: GhostBorrow<'brand>,
@@ -345,6 +499,41 @@ impl<'brand, T> BrandedVec<'brand, T> {
         self.as_slice(token).iter()
     }
 
+    /// Iterates over all elements by shared reference, in parallel.
+    ///
+    /// The shared token proves no `&mut T` exists for the lifetime of the iterator,
+    /// so handing out `&T` to rayon's worker threads only requires `T: Sync`.
+    #[cfg(feature = "rayon")]
+    pub fn par_iter<'a, Token>(&'a self, token: &'a Token) -> rayon::slice::Iter<'a, T>
+    where
+        T: Sync,
+        Token: GhostBorrow<'brand>,
+    {
+        use rayon::prelude::*;
+        self.as_slice(token).par_iter()
+    }
+
+    /// Splits the vector into disjoint regions of `chunk_size` elements (the last may
+    /// be shorter) and yields them as a parallel iterator.
+    ///
+    /// Each region is a [`BrandedSliceMut`], which carries exclusive access to its own
+    /// cells without the `GhostToken`, so workers can mutate their regions concurrently.
+    ///
+    /// # Panics
+    /// Panics if `chunk_size` is 0.
+    #[cfg(feature = "rayon")]
+    pub fn par_chunks_mut<'a>(
+        &'a mut self,
+        chunk_size: usize,
+    ) -> impl rayon::iter::IndexedParallelIterator<Item = BrandedSliceMut<'a, 'brand, T>>
+           + use<'a, 'brand, T>
+    where
+        T: Send,
+    {
+        use rayon::prelude::*;
+        self.inner.par_chunks_mut(chunk_size).map(BrandedSliceMut::new)
+    }
+
     /// Applies `f` to each element by exclusive reference.
     ///
     /// This is the canonical safe pattern for *sequential* exclusive iteration:
@@ -443,7 +632,7 @@ impl<'brand, T> BrandedVec<'brand, T> {
     /// Zero-cost min_by operation with custom comparator.
     pub fn min_by_ref<'a, F, Token>(&'a self, token: &'a Token, f: F) -> Option<&'a T>
     where
-        F: Fn(&T, &T) -> std::cmp::Ordering,
+        F: Fn(&T, &T) -> core::cmp::Ordering,
         Token: GhostBorrow<'brand>,
     {
         self.iter(token).min_by(|a, b| f(a, b))
@@ -452,7 +641,7 @@ impl<'brand, T> BrandedVec<'brand, T> {
     /// Zero-cost max_by operation with custom comparator.
     pub fn max_by_ref<'a, F, Token>(&'a self, token: &'a Token, f: F) -> Option<&'a T>
     where
-        F: Fn(&T, &T) -> std::cmp::Ordering,
+        F: Fn(&T, &T) -> core::cmp::Ordering,
         Token: GhostBorrow<'brand>,
     {
         self.iter(token).max_by(|a, b| f(a, b))
@@ -460,13 +649,38 @@ impl<'brand, T> BrandedVec<'brand, T> {
 
     /// Creates a draining iterator that removes the specified range in the vector
     /// and yields the removed items.
+    ///
+    /// Like [`pop`](Self::pop), [`remove`](Self::remove) and [`splice`](Self::splice),
+    /// this takes no token: the items are moved out of their cells, and `&mut self`
+    /// already rules out any other borrow of them. A token is needed only to reach
+    /// items that stay behind in the vector's cells, as in [`retain`](Self::retain).
     pub fn drain<R>(&mut self, range: R) -> impl Iterator<Item = T> + '_
     where
-        R: std::ops::RangeBounds<usize>,
+        R: core::ops::RangeBounds<usize>,
     {
         self.inner.drain(range).map(GhostCell::into_inner)
     }
 
+    /// Replaces the specified range with the items of `replace_with`, yielding the
+    /// removed items.
+    ///
+    /// As with [`Vec::splice`], the replacement happens when the returned iterator is
+    /// dropped, and the removed range is dropped even if the iterator is not consumed.
+    pub fn splice<'a, R, I>(
+        &'a mut self,
+        range: R,
+        replace_with: I,
+    ) -> impl Iterator<Item = T> + use<'a, 'brand, T, R, I>
+    where
+        R: core::ops::RangeBounds<usize>,
+        I: IntoIterator<Item = T>,
+        I::IntoIter: 'a,
+    {
+        self.inner
+            .splice(range, replace_with.into_iter().map(GhostCell::new))
+            .map(GhostCell::into_inner)
+    }
+
     /// Clones the branded vector using the token to access elements.
     ///
     /// This enables deep copying of the vector's contents when T is Clone.
@@ -486,6 +700,37 @@ impl<'brand, T> BrandedVec<'brand, T> {
     }
 }
 
+impl<'brand, T: Copy> BrandedVec<'brand, T> {
+    /// Appends every element of `other` with a single bulk copy.
+    pub fn extend_from_slice(&mut self, other: &[T]) {
+        let len = self.inner.len();
+        self.inner.reserve(other.len());
+        // SAFETY: `GhostCell<T>` is `repr(transparent)` over `T`, the destination has
+        // room for `other.len()` more elements after the reserve, and `T: Copy` means the
+        // copied values need no drop bookkeeping. `other` cannot alias our spare
+        // capacity, which is not reachable from outside.
+        unsafe {
+            ptr::copy_nonoverlapping(
+                other.as_ptr(),
+                self.inner.as_mut_ptr().add(len).cast::<T>(),
+                other.len(),
+            );
+            self.inner.set_len(len + other.len());
+        }
+    }
+
+    /// Overwrites every element with the contents of `src`, in one bulk copy.
+    ///
+    /// # Panics
+    /// Panics if `src` and the vector have different lengths.
+    pub fn copy_from_slice<Token>(&self, token: &mut Token, src: &[T])
+    where
+        Token: GhostBorrowMut<'brand>,
+    {
+        self.as_mut_slice(token).copy_from_slice(src);
+    }
+}
+
 impl<'brand, T> crate::collections::BrandedCollection<'brand> for BrandedVec<'brand, T> {
     #[inline(always)]
     fn is_empty(&self) -> bool {
@@ -544,7 +789,7 @@ impl<'brand, T> FromIterator<T> for BrandedVec<'brand, T> {
 impl<'brand, T> IntoIterator for BrandedVec<'brand, T> {
     type Item = T;
     type IntoIter =
-        std::iter::Map<std::vec::IntoIter<GhostCell<'brand, T>>, fn(GhostCell<'brand, T>) -> T>;
+        core::iter::Map<alloc_crate::vec::IntoIter<GhostCell<'brand, T>>, fn(GhostCell<'brand, T>) -> T>;
 
     fn into_iter(self) -> Self::IntoIter {
         self.inner.into_iter().map(GhostCell::into_inner)
@@ -745,7 +990,7 @@ impl<'brand, T, const CAPACITY: usize> BrandedArray<'brand, T, CAPACITY> {
         unsafe {
             // Cast *const MaybeUninit<GhostCell<T>> to *const T is valid because layouts match
             let ptr = self.inner.as_ptr() as *const T;
-            std::slice::from_raw_parts(ptr, self.len)
+            core::slice::from_raw_parts(ptr, self.len)
         }
     }
 
@@ -759,7 +1004,7 @@ impl<'brand, T, const CAPACITY: usize> BrandedArray<'brand, T, CAPACITY> {
             // Cast *const MaybeUninit<GhostCell<T>> to *mut T is valid because layouts match
             // We have exclusive access to the token, which grants exclusive access to the cells
             let ptr = self.inner.as_ptr() as *mut T;
-            std::slice::from_raw_parts_mut(ptr, self.len)
+            core::slice::from_raw_parts_mut(ptr, self.len)
         }
     }
 
@@ -939,6 +1184,27 @@ mod tests {
         });
     }
 
+    #[test]
+    fn branded_vec_get_disjoint_mut() {
+        GhostToken::new(|mut token| {
+            let v: BrandedVec<'_, i32> = (0..5).collect();
+
+            let [a, b] = v.get_disjoint_mut(&mut token, [4, 1]).unwrap();
+            core::mem::swap(a, b);
+            *a += 10;
+            assert_eq!(v.as_slice(&token), &[0, 4, 2, 3, 11]);
+
+            assert_eq!(
+                v.get_disjoint_mut(&mut token, [2, 2]).err(),
+                Some(core::slice::GetDisjointMutError::OverlappingIndices)
+            );
+            assert_eq!(
+                v.get_disjoint_mut(&mut token, [0, 5]).err(),
+                Some(core::slice::GetDisjointMutError::IndexOutOfBounds)
+            );
+        });
+    }
+
     #[test]
     fn branded_vec_iter_and_iter_mut() {
         GhostToken::new(|mut token| {
@@ -1110,4 +1376,91 @@ mod tests {
             assert_eq!(*v2.borrow(&token, 0), 1);
         });
     }
+
+    #[test]
+    fn branded_vec_sort_through_token() {
+        GhostToken::new(|token| {
+            let keys: BrandedVec<'_, &str> = ["d", "b", "a", "c", "b"].into_iter().collect();
+            let mut order: BrandedVec<'_, usize> = (0..5).collect();
+
+            // Stable: the two "b" rows keep their relative order.
+            order.sort_by(&token, |&a, &b| keys.borrow(&token, a).cmp(keys.borrow(&token, b)));
+            assert_eq!(order.as_slice(&token), &[2, 1, 4, 3, 0]);
+
+            order.sort_unstable_by_key(&token, |&i| i);
+            assert_eq!(order.as_slice(&token), &[0, 1, 2, 3, 4]);
+
+            let mut calls = 0;
+            order.sort_by_cached_key(&token, |&i| {
+                calls += 1;
+                core::cmp::Reverse(*keys.borrow(&token, i))
+            });
+            assert_eq!(calls, 5);
+            assert_eq!(order.as_slice(&token), &[0, 3, 1, 4, 2]);
+        });
+    }
+
+    #[cfg(feature = "rayon")]
+    #[test]
+    fn branded_vec_par_iter_and_par_chunks_mut() {
+        use rayon::prelude::*;
+
+        GhostToken::new(|token| {
+            let mut v: BrandedVec<'_, u64> = (0..1000).collect();
+            assert_eq!(v.par_iter(&token).sum::<u64>(), 999 * 1000 / 2);
+
+            v.par_chunks_mut(64).enumerate().for_each(|(i, mut region)| {
+                for x in region.iter_mut() {
+                    *x += i as u64 * 1000;
+                }
+            });
+            assert_eq!(*v.borrow(&token, 63), 63);
+            assert_eq!(*v.borrow(&token, 64), 1064);
+            assert_eq!(*v.borrow(&token, 999), 15 * 1000 + 999);
+        });
+    }
+
+    #[test]
+    fn branded_vec_fallible_growth() {
+        GhostToken::new(|token| {
+            let mut v = BrandedVec::new();
+            v.try_reserve(10).unwrap();
+            assert!(v.capacity() >= 10);
+            v.try_push(1).unwrap();
+            v.try_push(2).unwrap();
+            assert_eq!(v.as_slice(&token), &[1, 2]);
+            assert_eq!(v.try_reserve(usize::MAX), Err(AllocError));
+            assert_eq!(v.len(), 2);
+        });
+    }
+
+    #[test]
+    fn branded_vec_bulk_copies() {
+        GhostToken::new(|mut token| {
+            let mut v: BrandedVec<'_, u32> = BrandedVec::new();
+            v.extend_from_slice(&[]);
+            v.extend_from_slice(&[1, 2, 3]);
+            v.extend_from_slice(&[4, 5]);
+            assert_eq!(v.as_slice(&token), &[1, 2, 3, 4, 5]);
+
+            let mut other: BrandedVec<'_, u32> = (6..9).collect();
+            v.append(&mut other);
+            assert!(other.is_empty());
+            assert_eq!(v.len(), 8);
+            assert_eq!(*v.borrow(&token, 7), 8);
+
+            v.copy_from_slice(&mut token, &[0; 8]);
+            assert!(v.as_slice(&token).iter().all(|&x| x == 0));
+        });
+    }
+
+    #[test]
+    fn branded_vec_into_vec_escapes_scope() {
+        let words: Vec<String> = GhostToken::new(|mut token| {
+            let v: BrandedVec<'_, String> = ["a", "b"].iter().map(|s| s.to_string()).collect();
+            v.get_mut(&mut token, 1).unwrap().push('!');
+            v.into_vec()
+        });
+        assert_eq!(words, ["a", "b!"]);
+    }
 }
@@ -9,7 +9,7 @@ use crate::token::traits::{GhostBorrow, GhostBorrowMut};
 use crate::GhostCell;
 use core::mem;
 use core::ptr::{self, NonNull};
-use std::alloc::{alloc, dealloc, handle_alloc_error, Layout};
+use alloc_crate::alloc::{alloc, dealloc, handle_alloc_error, Layout};
 
 /// A double-ended queue of token-gated elements.
 pub struct BrandedVecDeque<'brand, T> {
@@ -323,16 +323,16 @@ impl<'brand, T> BrandedVecDeque<'brand, T> {
         let cap = self.cap;
 
         unsafe {
-            if head + self.len <= cap {
+            if self.len <= cap - head {
                 // Contiguous
-                let s1 = std::slice::from_raw_parts(ptr.add(head) as *const T, self.len);
+                let s1 = core::slice::from_raw_parts(ptr.add(head) as *const T, self.len);
                 (s1, &[])
             } else {
                 // Wrapped
                 let len1 = cap - head;
                 let len2 = self.len - len1;
-                let s1 = std::slice::from_raw_parts(ptr.add(head) as *const T, len1);
-                let s2 = std::slice::from_raw_parts(ptr as *const T, len2);
+                let s1 = core::slice::from_raw_parts(ptr.add(head) as *const T, len1);
+                let s2 = core::slice::from_raw_parts(ptr as *const T, len2);
                 (s1, s2)
             }
         }
@@ -354,14 +354,14 @@ impl<'brand, T> BrandedVecDeque<'brand, T> {
         let cap = self.cap;
 
         unsafe {
-            if head + self.len <= cap {
-                let s1 = std::slice::from_raw_parts_mut(ptr.add(head) as *mut T, self.len);
+            if self.len <= cap - head {
+                let s1 = core::slice::from_raw_parts_mut(ptr.add(head).cast::<T>(), self.len);
                 (s1, &mut [])
             } else {
                 let len1 = cap - head;
                 let len2 = self.len - len1;
-                let s1 = std::slice::from_raw_parts_mut(ptr.add(head) as *mut T, len1);
-                let s2 = std::slice::from_raw_parts_mut(ptr as *mut T, len2);
+                let s1 = core::slice::from_raw_parts_mut(ptr.add(head).cast::<T>(), len1);
+                let s2 = core::slice::from_raw_parts_mut(ptr.cast::<T>(), len2);
                 (s1, s2)
             }
         }
@@ -373,7 +373,7 @@ impl<'brand, T> BrandedVecDeque<'brand, T> {
         token: &'a Token,
     ) -> impl Iterator<Item = &'a T>
            + DoubleEndedIterator
-           + std::iter::FusedIterator
+           + core::iter::FusedIterator
            + use<'a, 'brand, T, Token>
     where
         Token: GhostBorrow<'brand>,
@@ -388,7 +388,7 @@ impl<'brand, T> BrandedVecDeque<'brand, T> {
         token: &'a mut Token,
     ) -> impl Iterator<Item = &'a mut T>
            + DoubleEndedIterator
-           + std::iter::FusedIterator
+           + core::iter::FusedIterator
            + use<'a, 'brand, T, Token>
     where
         Token: GhostBorrowMut<'brand>,
@@ -433,9 +433,9 @@ impl<'brand, T> BrandedVecDeque<'brand, T> {
                 let mut d_idx = old_tail;
 
                 while remaining > 0 {
-                    let s_contig = std::cmp::min(remaining, cap - s_idx);
-                    let d_contig = std::cmp::min(remaining, cap - d_idx);
-                    let chunk_len = std::cmp::min(s_contig, d_contig);
+                    let s_contig = core::cmp::min(remaining, cap - s_idx);
+                    let d_contig = core::cmp::min(remaining, cap - d_idx);
+                    let chunk_len = core::cmp::min(s_contig, d_contig);
 
                     ptr::copy(ptr.add(s_idx), ptr.add(d_idx), chunk_len);
 
@@ -489,7 +489,7 @@ impl<'brand, T> BrandedVecDeque<'brand, T> {
                     let s_back = if s_end == 0 { cap } else { s_end };
                     let d_back = if d_end == 0 { cap } else { d_end };
 
-                    let chunk_len = std::cmp::min(remaining, std::cmp::min(s_back, d_back));
+                    let chunk_len = core::cmp::min(remaining, core::cmp::min(s_back, d_back));
 
                     // Calculate start indices for the chunk
                     let s_start = (s_end + cap - chunk_len) % cap;
@@ -507,56 +507,37 @@ impl<'brand, T> BrandedVecDeque<'brand, T> {
         }
     }
 
-    /// Rearranges the internal storage so that the deque contents are contiguous.
+    /// Rearranges the internal storage so that the deque contents are contiguous,
+    /// and returns them as one slice.
     ///
-    /// Returns a mutable slice to the contiguous contents.
-    /// This may require allocating a new buffer if the deque is wrapped.
-    pub fn make_contiguous(&mut self) -> &mut [GhostCell<'brand, T>] {
-        if self.len == 0 {
-            return &mut [];
-        }
-
-        let ptr = self.ptr.as_ptr();
-        let head = self.head;
-        let tail = self.tail();
-        let cap = self.cap;
-
-        unsafe {
-            if tail <= head {
-                // Wrapped case.
-                // The logical contents are [head..cap] followed by [0..tail].
-                // We want to make them contiguous.
-                // The safest way is to allocate a new buffer and copy the parts in order.
-
-                let head_len = cap - head;
-                let tail_len = tail;
-
-                // Allocate new buffer
-                let new_cap = self.cap;
-                let new_layout = Layout::array::<GhostCell<'brand, T>>(new_cap).unwrap();
-                let new_ptr = alloc(new_layout) as *mut GhostCell<'brand, T>;
-                if new_ptr.is_null() { handle_alloc_error(new_layout); }
-
-                // Copy head part to start of new buffer
-                ptr::copy_nonoverlapping(ptr.add(head), new_ptr, head_len);
-
-                // Copy tail part to follow head part
-                if tail_len > 0 {
-                    ptr::copy_nonoverlapping(ptr, new_ptr.add(head_len), tail_len);
-                }
-
-                // Deallocate old buffer
-                let old_layout = Layout::array::<GhostCell<'brand, T>>(self.cap).unwrap();
-                dealloc(ptr as *mut u8, old_layout);
-
-                // Update pointers
-                self.ptr = NonNull::new_unchecked(new_ptr);
+    /// After this call, [`as_slices`](Self::as_slices) returns everything in its first
+    /// slice until the deque wraps again. The elements are rotated within the existing
+    /// buffer, so nothing is allocated.
+    ///
+    /// **Time complexity**: \(O(\text{capacity})\) if the contents wrap, \(O(1)\) otherwise.
+    pub fn make_contiguous<'a, Token>(&'a mut self, token: &'a mut Token) -> &'a mut [T]
+    where
+        Token: GhostBorrowMut<'brand>,
+    {
+        if self.len > self.cap - self.head {
+            if mem::size_of::<T>() == 0 {
+                self.head = 0;
+            } else {
+                // SAFETY: the buffer holds `cap` slots, some initialized; rotating them
+                // as `MaybeUninit` moves bytes without reading or dropping any value.
+                let slots = unsafe {
+                    core::slice::from_raw_parts_mut(
+                        self.ptr.as_ptr().cast::<mem::MaybeUninit<GhostCell<'brand, T>>>(),
+                        self.cap,
+                    )
+                };
+                slots.rotate_left(self.head);
                 self.head = 0;
             }
-
-            // Now contiguous.
-            std::slice::from_raw_parts_mut(self.ptr.as_ptr().add(self.head), self.len)
         }
+        let (front, back) = self.as_mut_slices(token);
+        debug_assert!(back.is_empty());
+        front
     }
 }
 
@@ -575,23 +556,23 @@ impl<'brand, T> BrandedVecDeque<'brand, T> {
         replace_with: I,
     ) -> Splice<'_, 'brand, T, I::IntoIter>
     where
-        R: std::ops::RangeBounds<usize>,
+        R: core::ops::RangeBounds<usize>,
         I: IntoIterator<Item = T>,
     {
         let len = self.len();
         let start = match range.start_bound() {
-            std::ops::Bound::Included(&n) => n,
-            std::ops::Bound::Excluded(&n) => n + 1,
-            std::ops::Bound::Unbounded => 0,
+            core::ops::Bound::Included(&n) => n,
+            core::ops::Bound::Excluded(&n) => n + 1,
+            core::ops::Bound::Unbounded => 0,
         };
         let end = match range.end_bound() {
-            std::ops::Bound::Included(&n) => n + 1,
-            std::ops::Bound::Excluded(&n) => n,
-            std::ops::Bound::Unbounded => len,
+            core::ops::Bound::Included(&n) => n + 1,
+            core::ops::Bound::Excluded(&n) => n,
+            core::ops::Bound::Unbounded => len,
         };
 
-        let start = std::cmp::min(start, len);
-        let end = std::cmp::min(end, len);
+        let start = core::cmp::min(start, len);
+        let end = core::cmp::min(end, len);
 
         let count = end.saturating_sub(start);
         let suffix_len = len - end;
@@ -763,22 +744,22 @@ impl<'brand, T> BrandedVecDeque<'brand, T> {
     /// and yields the removed items.
     pub fn drain<R>(&mut self, range: R) -> Drain<'_, 'brand, T>
     where
-        R: std::ops::RangeBounds<usize>,
+        R: core::ops::RangeBounds<usize>,
     {
         let len = self.len();
         let start = match range.start_bound() {
-            std::ops::Bound::Included(&n) => n,
-            std::ops::Bound::Excluded(&n) => n + 1,
-            std::ops::Bound::Unbounded => 0,
+            core::ops::Bound::Included(&n) => n,
+            core::ops::Bound::Excluded(&n) => n + 1,
+            core::ops::Bound::Unbounded => 0,
         };
         let end = match range.end_bound() {
-            std::ops::Bound::Included(&n) => n + 1,
-            std::ops::Bound::Excluded(&n) => n,
-            std::ops::Bound::Unbounded => len,
+            core::ops::Bound::Included(&n) => n + 1,
+            core::ops::Bound::Excluded(&n) => n,
+            core::ops::Bound::Unbounded => len,
         };
 
-        let start = std::cmp::min(start, len);
-        let end = std::cmp::min(end, len);
+        let start = core::cmp::min(start, len);
+        let end = core::cmp::min(end, len);
 
         if start >= end {
             return Drain {
@@ -867,7 +848,7 @@ mod tests {
     use super::*;
     use crate::GhostToken;
     use std::cell::RefCell;
-    use std::rc::Rc;
+    use alloc_crate::rc::Rc;
 
     #[test]
     fn branded_vec_deque_basic() {
@@ -966,4 +947,56 @@ mod tests {
             assert_eq!(s2, &[5]);
         });
     }
+    #[test]
+    fn branded_vec_deque_make_contiguous_and_rotate() {
+        GhostToken::new(|mut token| {
+            let mut dq = BrandedVecDeque::with_capacity(8);
+            let mut model = alloc_crate::collections::VecDeque::new();
+            for i in 0..6 {
+                dq.push_back(i);
+                model.push_back(i);
+            }
+            for i in 6..9 {
+                dq.pop_front();
+                model.pop_front();
+                dq.push_back(i);
+                model.push_back(i);
+            }
+            // head=3, len=6, cap=8: the contents wrap.
+            assert!(!dq.as_slices(&token).1.is_empty());
+
+            dq.rotate_left(2);
+            model.rotate_left(2);
+            dq.rotate_right(5);
+            model.rotate_right(5);
+            let (a, b) = dq.as_slices(&token);
+            assert_eq!([a, b].concat(), model.iter().copied().collect::<Vec<_>>());
+
+            let capacity = dq.capacity();
+            let slice = dq.make_contiguous(&mut token);
+            assert_eq!(slice, model.make_contiguous());
+            slice.reverse();
+            model.make_contiguous().reverse();
+            assert_eq!(dq.capacity(), capacity);
+            assert_eq!(dq.as_slices(&token), model.as_slices());
+
+            // Pushing at both ends keeps working after the rearrangement.
+            dq.push_front(100);
+            dq.push_back(200);
+            model.push_front(100);
+            model.push_back(200);
+            let items: Vec<_> = dq.iter(&token).copied().collect();
+            assert_eq!(items, model.iter().copied().collect::<Vec<_>>());
+        });
+    }
+
+    #[test]
+    fn branded_vec_deque_make_contiguous_zst() {
+        GhostToken::new(|mut token| {
+            let mut dq = BrandedVecDeque::new();
+            dq.push_back(());
+            dq.push_front(());
+            assert_eq!(dq.make_contiguous(&mut token).len(), 2);
+        });
+    }
 }
@@ -0,0 +1,397 @@
+//! `BrandedVecMap` — an ordered map stored as a sorted vector of pairs.
+//!
+//! Entries live contiguously in a [`BrandedVec`] sorted by key, so lookups are a binary
+//! search and iteration is a plain slice walk with no pointer chasing. Insertions and
+//! removals shift the tail of the vector, which makes this the right choice for small
+//! maps and for maps that are built once (see
+//! [`from_sorted_vec`](BrandedVecMap::from_sorted_vec)) and then mostly read; large,
+//! write-heavy maps are better served by
+//! [`BrandedBTreeMap`](crate::collections::BrandedBTreeMap).
+//!
+//! As with `BrandedVec`, structural changes go through `&mut self` while reading and
+//! updating entries goes through the token.
+
+use super::BrandedVec;
+use crate::collections::BrandedCollection;
+use crate::token::traits::{GhostBorrow, GhostBorrowMut};
+#[cfg(not(feature = "std"))]
+use alloc_crate::vec::Vec;
+use core::borrow::Borrow;
+use core::cmp::Ordering;
+use core::ops::{Bound, RangeBounds};
+
+/// An ordered map over a sorted, token-gated vector of `(K, V)` pairs.
+pub struct BrandedVecMap<'brand, K, V> {
+    entries: BrandedVec<'brand, (K, V)>,
+}
+
+impl<'brand, K, V> BrandedVecMap<'brand, K, V> {
+    /// Creates an empty map.
+    pub fn new() -> Self {
+        Self {
+            entries: BrandedVec::new(),
+        }
+    }
+
+    /// Creates an empty map with room for `capacity` entries.
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            entries: BrandedVec::with_capacity(capacity),
+        }
+    }
+
+    /// Returns the number of entries.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns `true` if the map holds no entries.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Removes every entry.
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+
+    /// Returns the entries as a slice sorted by key.
+    pub fn as_slice<'a, Token>(&'a self, token: &'a Token) -> &'a [(K, V)]
+    where
+        Token: GhostBorrow<'brand>,
+    {
+        self.entries.as_slice(token)
+    }
+
+    /// Iterates over the entries in key order.
+    pub fn iter<'a, Token>(
+        &'a self,
+        token: &'a Token,
+    ) -> impl DoubleEndedIterator<Item = (&'a K, &'a V)> + ExactSizeIterator + use<'a, 'brand, K, V, Token>
+    where
+        Token: GhostBorrow<'brand>,
+    {
+        self.as_slice(token).iter().map(|(k, v)| (k, v))
+    }
+
+    /// Iterates over the entries in key order, with mutable values.
+    pub fn iter_mut<'a, Token>(
+        &'a self,
+        token: &'a mut Token,
+    ) -> impl DoubleEndedIterator<Item = (&'a K, &'a mut V)>
+           + ExactSizeIterator
+           + use<'a, 'brand, K, V, Token>
+    where
+        Token: GhostBorrowMut<'brand>,
+    {
+        self.entries
+            .as_mut_slice(token)
+            .iter_mut()
+            .map(|(k, v)| (&*k, v))
+    }
+
+    /// Iterates over the keys in order.
+    pub fn keys<'a, Token>(
+        &'a self,
+        token: &'a Token,
+    ) -> impl DoubleEndedIterator<Item = &'a K> + ExactSizeIterator + use<'a, 'brand, K, V, Token>
+    where
+        Token: GhostBorrow<'brand>,
+    {
+        self.as_slice(token).iter().map(|(k, _)| k)
+    }
+
+    /// Iterates over the values in key order.
+    pub fn values<'a, Token>(
+        &'a self,
+        token: &'a Token,
+    ) -> impl DoubleEndedIterator<Item = &'a V> + ExactSizeIterator + use<'a, 'brand, K, V, Token>
+    where
+        Token: GhostBorrow<'brand>,
+    {
+        self.as_slice(token).iter().map(|(_, v)| v)
+    }
+
+    /// Returns the entry with the smallest key.
+    pub fn first<'a, Token>(&'a self, token: &'a Token) -> Option<(&'a K, &'a V)>
+    where
+        Token: GhostBorrow<'brand>,
+    {
+        self.as_slice(token).first().map(|(k, v)| (k, v))
+    }
+
+    /// Returns the entry with the largest key.
+    pub fn last<'a, Token>(&'a self, token: &'a Token) -> Option<(&'a K, &'a V)>
+    where
+        Token: GhostBorrow<'brand>,
+    {
+        self.as_slice(token).last().map(|(k, v)| (k, v))
+    }
+}
+
+impl<'brand, K: Ord, V> BrandedVecMap<'brand, K, V> {
+    /// Builds a map from entries already sorted by strictly increasing key.
+    ///
+    /// **Time complexity**: \(O(n)\), with no sorting or copying.
+    ///
+    /// # Panics
+    /// Panics if the keys are not strictly increasing.
+    pub fn from_sorted_vec(entries: Vec<(K, V)>) -> Self {
+        assert!(
+            entries.windows(2).all(|w| w[0].0 < w[1].0),
+            "from_sorted_vec requires strictly increasing keys"
+        );
+        Self {
+            entries: entries.into_iter().collect(),
+        }
+    }
+
+    /// Binary-searches `key` among the entries held in `slice`.
+    #[inline]
+    fn search<Q>(slice: &[(K, V)], key: &Q) -> Result<usize, usize>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Ord,
+    {
+        slice.binary_search_by(|(k, _)| k.borrow().cmp(key))
+    }
+
+    /// Inserts `value` under `key`, returning the previous value.
+    ///
+    /// **Time complexity**: \(O(\log n)\) to find the slot, plus \(O(n)\) to shift the
+    /// tail when the key is new.
+    pub fn insert(&mut self, key: K, value: V) -> Option<V> {
+        let slice = self.entries.as_mut_slice_exclusive();
+        match Self::search(slice, &key) {
+            Ok(i) => Some(core::mem::replace(&mut slice[i].1, value)),
+            Err(i) => {
+                self.entries.insert(i, (key, value));
+                None
+            }
+        }
+    }
+
+    /// Removes `key`, returning its value.
+    pub fn remove<Q>(&mut self, key: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Ord,
+    {
+        self.remove_entry(key).map(|(_, v)| v)
+    }
+
+    /// Removes `key`, returning the stored key and its value.
+    pub fn remove_entry<Q>(&mut self, key: &Q) -> Option<(K, V)>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Ord,
+    {
+        let i = Self::search(self.entries.as_mut_slice_exclusive(), key).ok()?;
+        Some(self.entries.remove(i).into_inner())
+    }
+
+    /// Keeps only the entries for which `f` returns `true`, preserving order.
+    pub fn retain<F, Token>(&mut self, token: &mut Token, mut f: F)
+    where
+        F: FnMut(&K, &mut V) -> bool,
+        Token: GhostBorrowMut<'brand>,
+    {
+        self.entries.retain(token, |(k, v)| f(k, v));
+    }
+
+    /// Returns the value for `key`.
+    pub fn get<'a, Q, Token>(&'a self, token: &'a Token, key: &Q) -> Option<&'a V>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Ord,
+        Token: GhostBorrow<'brand>,
+    {
+        self.get_key_value(token, key).map(|(_, v)| v)
+    }
+
+    /// Returns the stored key and the value for `key`.
+    pub fn get_key_value<'a, Q, Token>(
+        &'a self,
+        token: &'a Token,
+        key: &Q,
+    ) -> Option<(&'a K, &'a V)>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Ord,
+        Token: GhostBorrow<'brand>,
+    {
+        let slice = self.as_slice(token);
+        let (k, v) = &slice[Self::search(slice, key).ok()?];
+        Some((k, v))
+    }
+
+    /// Returns a mutable reference to the value for `key`.
+    pub fn get_mut<'a, Q, Token>(&'a self, token: &'a mut Token, key: &Q) -> Option<&'a mut V>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Ord,
+        Token: GhostBorrowMut<'brand>,
+    {
+        let slice = self.entries.as_mut_slice(token);
+        let i = Self::search(slice, key).ok()?;
+        Some(&mut slice[i].1)
+    }
+
+    /// Returns `true` if the map contains `key`.
+    pub fn contains_key<Q, Token>(&self, token: &Token, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Ord,
+        Token: GhostBorrow<'brand>,
+    {
+        Self::search(self.as_slice(token), key).is_ok()
+    }
+
+    /// Returns the entries whose keys fall in `range`, in key order.
+    pub fn range<'a, Q, R, Token>(&'a self, token: &'a Token, range: R) -> &'a [(K, V)]
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Ord,
+        R: RangeBounds<Q>,
+        Token: GhostBorrow<'brand>,
+    {
+        let slice = self.as_slice(token);
+        let start = match range.start_bound() {
+            Bound::Included(q) => slice.partition_point(|(k, _)| k.borrow() < q),
+            Bound::Excluded(q) => slice.partition_point(|(k, _)| k.borrow() <= q),
+            Bound::Unbounded => 0,
+        };
+        let end = match range.end_bound() {
+            Bound::Included(q) => slice.partition_point(|(k, _)| k.borrow() <= q),
+            Bound::Excluded(q) => slice.partition_point(|(k, _)| k.borrow() < q),
+            Bound::Unbounded => slice.len(),
+        };
+        &slice[start..end.max(start)]
+    }
+}
+
+impl<'brand, K, V> Default for BrandedVecMap<'brand, K, V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<'brand, K, V> BrandedCollection<'brand> for BrandedVecMap<'brand, K, V> {
+    fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    fn len(&self) -> usize {
+        self.entries.len()
+    }
+}
+
+impl<'brand, K: Ord, V> FromIterator<(K, V)> for BrandedVecMap<'brand, K, V> {
+    /// Collects and sorts the entries; for duplicate keys the last value wins.
+    fn from_iter<I: IntoIterator<Item = (K, V)>>(iter: I) -> Self {
+        let mut entries: Vec<(K, V)> = iter.into_iter().collect();
+        // Stable, so equal keys stay in input order and the last one can be kept.
+        entries.sort_by(|a, b| a.0.cmp(&b.0));
+        let mut deduped: Vec<(K, V)> = Vec::with_capacity(entries.len());
+        for entry in entries {
+            match deduped.last_mut() {
+                Some(last) if last.0.cmp(&entry.0) == Ordering::Equal => *last = entry,
+                _ => deduped.push(entry),
+            }
+        }
+        Self {
+            entries: deduped.into_iter().collect(),
+        }
+    }
+}
+
+impl<'brand, K, V> IntoIterator for BrandedVecMap<'brand, K, V> {
+    type Item = (K, V);
+    type IntoIter = <BrandedVec<'brand, (K, V)> as IntoIterator>::IntoIter;
+
+    /// Consumes the map, yielding its entries in key order.
+    fn into_iter(self) -> Self::IntoIter {
+        self.entries.into_iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::GhostToken;
+    use std::collections::BTreeMap;
+
+    #[test]
+    fn test_vec_map_basic() {
+        GhostToken::new(|mut token| {
+            let mut map = BrandedVecMap::new();
+            assert_eq!(map.insert(3, "c"), None);
+            assert_eq!(map.insert(1, "a"), None);
+            assert_eq!(map.insert(2, "b"), None);
+            assert_eq!(map.insert(2, "B"), Some("b"));
+            assert_eq!(map.len(), 3);
+
+            assert_eq!(map.get(&token, &2), Some(&"B"));
+            assert!(!map.contains_key(&token, &4));
+            assert_eq!(map.keys(&token).copied().collect::<Vec<_>>(), [1, 2, 3]);
+            assert_eq!(map.first(&token), Some((&1, &"a")));
+            assert_eq!(map.last(&token), Some((&3, &"c")));
+
+            *map.get_mut(&mut token, &1).unwrap() = "A";
+            for (_, v) in map.iter_mut(&mut token) {
+                *v = if *v == "c" { "C" } else { v };
+            }
+            assert_eq!(
+                map.values(&token).copied().collect::<Vec<_>>(),
+                ["A", "B", "C"]
+            );
+
+            assert_eq!(map.remove(&2), Some("B"));
+            assert_eq!(map.remove(&2), None);
+            map.retain(&mut token, |k, _| *k > 1);
+            assert_eq!(map.into_iter().collect::<Vec<_>>(), [(3, "C")]);
+        });
+    }
+
+    #[test]
+    fn test_vec_map_construction_and_range() {
+        GhostToken::new(|token| {
+            let map = BrandedVecMap::from_sorted_vec((0..10).map(|i| (i * 10, i)).collect());
+            assert_eq!(map.range(&token, 20..=40).len(), 3);
+            assert_eq!(map.range(&token, 25..40), [(30, 3)]);
+            assert_eq!(map.range(&token, ..).len(), 10);
+            assert!(map.range(&token, 95..).is_empty());
+
+            let collected: BrandedVecMap<'_, _, _> = [(2, 'x'), (1, 'a'), (2, 'b'), (0, 'z')]
+                .into_iter()
+                .collect();
+            assert_eq!(
+                collected.iter(&token).collect::<Vec<_>>(),
+                [(&0, &'z'), (&1, &'a'), (&2, &'b')]
+            );
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "strictly increasing")]
+    fn test_vec_map_from_sorted_vec_rejects_unsorted() {
+        let _ = BrandedVecMap::from_sorted_vec(vec![(2, ()), (1, ())]);
+    }
+
+    #[test]
+    fn test_vec_map_matches_btree_map() {
+        GhostToken::new(|token| {
+            let mut model = BTreeMap::new();
+            let mut map = BrandedVecMap::new();
+            for i in 0..500u32 {
+                let key = i.wrapping_mul(2_654_435_761) % 97;
+                if i % 4 == 0 {
+                    assert_eq!(map.remove(&key), model.remove(&key));
+                } else {
+                    assert_eq!(map.insert(key, i), model.insert(key, i));
+                }
+            }
+            assert!(map.iter(&token).eq(model.iter()));
+        });
+    }
+}
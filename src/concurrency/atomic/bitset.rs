@@ -1,9 +1,18 @@
 //! Branded atomic bitsets.
 //!
 //! This is a dense alternative to `Vec<AtomicBool>` for visited sets / flags.
+//!
+//! Bulk operations (`set_range`, `union_with`, `count_ones`, ...) work a word at a time:
+//! one RMW or store per `usize` instead of one per bit. The words are atomics, so they
+//! are not read with vector loads; the per-word popcounts and masks are already cheap
+//! next to the atomic accesses themselves.
 
+use core::ops::Range;
 use core::sync::atomic::Ordering;
 
+#[cfg(not(feature = "std"))]
+use alloc_crate::vec::Vec;
+
 use super::GhostAtomicUsize;
 
 /// A branded, word-packed atomic bitset.
@@ -53,6 +62,184 @@ impl<'brand> GhostAtomicBitset<'brand> {
         unsafe { self.test_and_set_unchecked(bit, order) }
     }
 
+    /// Returns the number of set bits.
+    pub fn count_ones(&self) -> usize {
+        (0..self.words.len())
+            .map(|i| self.word(i).count_ones() as usize)
+            .sum()
+    }
+
+    /// Sets every bit in `range`.
+    ///
+    /// Words fully inside the range are stored, the partial ones at either end are
+    /// updated with `fetch_or`. All accesses are `Relaxed`.
+    ///
+    /// # Panics
+    /// Panics if `range` is decreasing or extends past `len_bits()`.
+    pub fn set_range(&self, range: Range<usize>) {
+        self.for_range_words(range, |word, mask| {
+            if mask == usize::MAX {
+                word.store(usize::MAX, Ordering::Relaxed);
+            } else {
+                word.fetch_or(mask, Ordering::Relaxed);
+            }
+        });
+    }
+
+    /// Clears every bit in `range`.
+    ///
+    /// The counterpart of [`set_range`](Self::set_range), with `fetch_and` at the ends.
+    ///
+    /// # Panics
+    /// Panics if `range` is decreasing or extends past `len_bits()`.
+    pub fn clear_range(&self, range: Range<usize>) {
+        self.for_range_words(range, |word, mask| {
+            if mask == usize::MAX {
+                word.store(0, Ordering::Relaxed);
+            } else {
+                word.fetch_and(!mask, Ordering::Relaxed);
+            }
+        });
+    }
+
+    /// Sets every bit that is set in `other`, one `fetch_or` per word, and returns how
+    /// many bits this call changed from cleared to set.
+    ///
+    /// Words of `other` that are zero are skipped, so merging a sparse frontier touches
+    /// few words of `self`.
+    ///
+    /// # Panics
+    /// Panics if the bitsets have different lengths.
+    pub fn union_with(&self, other: &GhostAtomicBitset<'_>, order: Ordering) -> usize {
+        self.assert_same_len(other);
+        let mut added = 0;
+        for i in 0..self.words.len() {
+            let bits = other.word(i);
+            if bits != 0 {
+                let prev = self.words[i].fetch_or(bits, order);
+                added += (bits & !prev).count_ones() as usize;
+            }
+        }
+        added
+    }
+
+    /// Clears every bit that is cleared in `other`, one `fetch_and` per word.
+    ///
+    /// # Panics
+    /// Panics if the bitsets have different lengths.
+    pub fn intersect_with(&self, other: &GhostAtomicBitset<'_>, order: Ordering) {
+        self.assert_same_len(other);
+        for i in 0..self.words.len() {
+            let bits = other.word(i);
+            if bits != usize::MAX {
+                self.words[i].fetch_and(bits, order);
+            }
+        }
+    }
+
+    fn assert_same_len(&self, other: &GhostAtomicBitset<'_>) {
+        assert_eq!(
+            self.bits, other.bits,
+            "bitsets of {} and {} bits cannot be combined",
+            self.bits, other.bits
+        );
+    }
+
+    /// Calls `f` with each word overlapping `range` and the mask of its bits in range.
+    fn for_range_words(
+        &self,
+        range: Range<usize>,
+        mut f: impl FnMut(&GhostAtomicUsize<'brand>, usize),
+    ) {
+        assert!(
+            range.start <= range.end && range.end <= self.bits,
+            "bit range {range:?} out of bounds for length {}",
+            self.bits
+        );
+        if range.is_empty() {
+            return;
+        }
+        let word_bits = usize::BITS as usize;
+        let first = range.start / word_bits;
+        let last = (range.end - 1) / word_bits;
+        for i in first..=last {
+            let lo = if i == first { range.start % word_bits } else { 0 };
+            let hi = if i == last {
+                (range.end - 1) % word_bits + 1
+            } else {
+                word_bits
+            };
+            f(&self.words[i], (usize::MAX >> (word_bits - (hi - lo))) << lo);
+        }
+    }
+
+    /// Returns the first set bit at or after `idx`, or `None` if there is none.
+    ///
+    /// Scans whole words with a trailing-zero count rather than probing each bit.
+    pub fn first_set_from(&self, idx: usize) -> Option<usize> {
+        if idx >= self.bits {
+            return None;
+        }
+        let word_bits = usize::BITS as usize;
+        let (start, mask) = bit_word_mask(idx);
+        // Keep `idx`'s bit and everything above it in the first word.
+        let mut word = self.word(start) & !(mask - 1);
+        for i in start..self.words.len() {
+            if i > start {
+                word = self.word(i);
+            }
+            if word != 0 {
+                let bit = i * word_bits + word.trailing_zeros() as usize;
+                return (bit < self.bits).then_some(bit);
+            }
+        }
+        None
+    }
+
+    /// Iterates over the indices of set bits in increasing order.
+    ///
+    /// Each word is loaded once when the iterator reaches it, so bits changed
+    /// concurrently may or may not be reported.
+    pub fn iter_ones(&self) -> impl Iterator<Item = usize> + use<'_, 'brand> {
+        self.iter_bits(false)
+    }
+
+    /// Iterates over the indices of cleared bits in increasing order.
+    ///
+    /// Like [`iter_ones`](Self::iter_ones), this reads each word once.
+    pub fn iter_zeros(&self) -> impl Iterator<Item = usize> + use<'_, 'brand> {
+        self.iter_bits(true)
+    }
+
+    fn iter_bits(&self, invert: bool) -> impl Iterator<Item = usize> + use<'_, 'brand> {
+        let word_bits = usize::BITS as usize;
+        let bits = self.bits;
+        (0..self.words.len()).flat_map(move |i| {
+            let base = i * word_bits;
+            let mut word = self.word(i);
+            if invert {
+                word = !word;
+            }
+            // The last word may extend past `len_bits()`.
+            if bits - base < word_bits {
+                word &= (1usize << (bits - base)) - 1;
+            }
+            core::iter::from_fn(move || {
+                if word == 0 {
+                    return None;
+                }
+                let bit = word.trailing_zeros() as usize;
+                word &= word - 1;
+                Some(base + bit)
+            })
+        })
+    }
+
+    #[inline]
+    fn word(&self, i: usize) -> usize {
+        self.words[i].load(Ordering::Relaxed)
+    }
+
     /// # Safety
     /// Caller must ensure `bit < len_bits()`.
     #[inline(always)]
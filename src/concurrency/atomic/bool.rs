@@ -1,7 +1,6 @@
-use core::{
-    marker::PhantomData,
-    sync::atomic::{AtomicBool, Ordering},
-};
+use core::{marker::PhantomData, sync::atomic::Ordering};
+
+use super::primitive::AtomicBool;
 
 /// A branded `AtomicBool`.
 #[repr(transparent)]
@@ -12,6 +11,7 @@ pub struct GhostAtomicBool<'brand> {
 
 impl<'brand> GhostAtomicBool<'brand> {
     /// Creates a new branded atomic bool.
+    #[cfg(not(loom))]
     #[inline(always)]
     pub const fn new(value: bool) -> Self {
         Self {
@@ -20,6 +20,15 @@ impl<'brand> GhostAtomicBool<'brand> {
         }
     }
 
+    /// Creates a new branded atomic bool.
+    #[cfg(loom)]
+    pub fn new(value: bool) -> Self {
+        Self {
+            inner: AtomicBool::new(value),
+            _brand: PhantomData,
+        }
+    }
+
     /// Loads the current value.
     #[inline(always)]
     pub fn load(&self, order: Ordering) -> bool {
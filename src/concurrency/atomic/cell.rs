@@ -0,0 +1,242 @@
+//! `GhostAtomicCell` — a branded atomic cell for small `Copy` values.
+//!
+//! The value is stored in place and every access goes through the atomic integer of
+//! its size, so one generic type covers flags, small enums and id newtypes that would
+//! otherwise each need a hand-written atomic wrapper.
+//!
+//! A value type must implement [`NoUninit`], bytemuck's promise that it has no
+//! padding bytes. Primitives already do: integers, floats, `bool`, `char` and
+//! `Option<NonZero*>` need nothing extra. Fieldless `#[repr(uN)]` enums and
+//! `#[repr(transparent)]` newtypes derive it; the derive checks the layout, and the
+//! `bytemuck(crate)` attribute points it at this module so callers need no direct
+//! bytemuck dependency:
+//!
+//! ```
+//! use core::sync::atomic::Ordering;
+//! use halo::concurrency::atomic::cell::NoUninit;
+//! use halo::concurrency::atomic::GhostAtomicCell;
+//!
+//! #[derive(Clone, Copy, PartialEq, Eq, Debug, NoUninit)]
+//! #[bytemuck(crate = "halo::concurrency::atomic::cell")]
+//! #[repr(u8)]
+//! enum Phase {
+//!     Idle,
+//!     Running,
+//! }
+//!
+//! let phase = GhostAtomicCell::new(Phase::Idle);
+//! phase.store(Phase::Running, Ordering::Release);
+//! assert_eq!(phase.load(Ordering::Acquire), Phase::Running);
+//! ```
+
+use core::{
+    cell::UnsafeCell,
+    marker::PhantomData,
+    mem::{align_of, size_of, transmute_copy},
+    sync::atomic::{AtomicU16, AtomicU32, AtomicU8, Ordering},
+};
+
+#[cfg(target_has_atomic = "64")]
+use core::sync::atomic::AtomicU64;
+
+pub use bytemuck::NoUninit;
+
+/// A branded atomic cell for any `Copy` value of 1, 2, 4 or 8 bytes.
+///
+/// The value is stored in place and accessed through the atomic integer of its size,
+/// so small enums and newtypes get lock-free `load`/`store`/`compare_exchange` without
+/// a per-width wrapper. `T` must implement [`NoUninit`] (no padding bytes; see the
+/// [module docs](self) for deriving it) and be as aligned as that atomic integer,
+/// which holds for primitives, fieldless `#[repr(uN)]` enums and
+/// `#[repr(transparent)]` newtypes over them; other types fail to compile.
+///
+/// Comparisons are bitwise: `compare_exchange` on floats treats `0.0` and `-0.0` as
+/// different and a NaN as equal to itself.
+///
+/// The brand is a compile-time marker used to tie an atomic to a Ghost “domain”.
+/// It does **not** affect the atomic’s concurrency behavior.
+#[repr(transparent)]
+pub struct GhostAtomicCell<'brand, T: NoUninit> {
+    value: UnsafeCell<T>,
+    _brand: PhantomData<&'brand mut ()>,
+}
+
+/// Runs `$body` with `$atomic` bound to the cell viewed as the atomic integer of
+/// `T`'s size, and `$int` naming that integer type.
+macro_rules! with_atomic {
+    ($cell:expr, $int:ident, $atomic:ident => $body:expr) => {{
+        let ptr = $cell.value.get();
+        match size_of::<T>() {
+            1 => {
+                type $int = u8;
+                // SAFETY: `LAYOUT_OK` checked size and alignment for `AtomicU8`.
+                let $atomic = unsafe { &*ptr.cast::<AtomicU8>() };
+                $body
+            }
+            2 => {
+                type $int = u16;
+                // SAFETY: as above, for `AtomicU16`.
+                let $atomic = unsafe { &*ptr.cast::<AtomicU16>() };
+                $body
+            }
+            4 => {
+                type $int = u32;
+                // SAFETY: as above, for `AtomicU32`.
+                let $atomic = unsafe { &*ptr.cast::<AtomicU32>() };
+                $body
+            }
+            #[cfg(target_has_atomic = "64")]
+            8 => {
+                type $int = u64;
+                // SAFETY: as above, for `AtomicU64`.
+                let $atomic = unsafe { &*ptr.cast::<AtomicU64>() };
+                $body
+            }
+            _ => unreachable!(),
+        }
+    }};
+}
+
+impl<'brand, T: NoUninit> GhostAtomicCell<'brand, T> {
+    const LAYOUT_OK: () = assert!(
+        match size_of::<T>() {
+            1 => align_of::<T>() >= align_of::<AtomicU8>(),
+            2 => align_of::<T>() >= align_of::<AtomicU16>(),
+            4 => align_of::<T>() >= align_of::<AtomicU32>(),
+            #[cfg(target_has_atomic = "64")]
+            8 => align_of::<T>() >= align_of::<AtomicU64>(),
+            _ => false,
+        },
+        "GhostAtomicCell<T> needs a T of 1, 2, 4 or 8 bytes, aligned like that atomic"
+    );
+
+    /// Creates a new atomic cell.
+    #[inline]
+    pub const fn new(value: T) -> Self {
+        let () = Self::LAYOUT_OK;
+        Self {
+            value: UnsafeCell::new(value),
+            _brand: PhantomData,
+        }
+    }
+
+    /// Consumes the cell, returning the value.
+    #[inline]
+    pub fn into_inner(self) -> T {
+        self.value.into_inner()
+    }
+
+    /// Returns a mutable reference to the value; `&mut self` rules out concurrent access.
+    #[inline]
+    pub fn get_mut(&mut self) -> &mut T {
+        self.value.get_mut()
+    }
+
+    /// Loads the current value.
+    #[inline]
+    pub fn load(&self, order: Ordering) -> T {
+        with_atomic!(self, Int, atomic => unsafe { from_bits::<T, Int>(atomic.load(order)) })
+    }
+
+    /// Stores a new value.
+    #[inline]
+    pub fn store(&self, value: T, order: Ordering) {
+        with_atomic!(self, Int, atomic => atomic.store(to_bits::<T, Int>(value), order));
+    }
+
+    /// Swaps the current value, returning the previous value.
+    #[inline]
+    pub fn swap(&self, value: T, order: Ordering) -> T {
+        with_atomic!(self, Int, atomic => unsafe {
+            from_bits::<T, Int>(atomic.swap(to_bits::<T, Int>(value), order))
+        })
+    }
+
+    /// Stores `new` if the current value is bitwise equal to `current`, returning the
+    /// previous value.
+    ///
+    /// # Errors
+    /// Returns the current value if it did not match `current`.
+    #[inline]
+    pub fn compare_exchange(
+        &self,
+        current: T,
+        new: T,
+        success: Ordering,
+        failure: Ordering,
+    ) -> Result<T, T> {
+        with_atomic!(self, Int, atomic => {
+            let result = atomic.compare_exchange(
+                to_bits::<T, Int>(current),
+                to_bits::<T, Int>(new),
+                success,
+                failure,
+            );
+            // SAFETY: both arms hold bits of a `T` stored in this cell.
+            unsafe {
+                result
+                    .map(|bits| from_bits::<T, Int>(bits))
+                    .map_err(|bits| from_bits::<T, Int>(bits))
+            }
+        })
+    }
+
+    /// Applies `f` until it returns `None` or its result is stored without
+    /// interference, like `AtomicU32::fetch_update`.
+    ///
+    /// Returns the previous value once a value from `f` is stored.
+    ///
+    /// # Errors
+    /// Returns the current value if `f` returned `None`.
+    pub fn fetch_update<F>(
+        &self,
+        set_order: Ordering,
+        fetch_order: Ordering,
+        mut f: F,
+    ) -> Result<T, T>
+    where
+        F: FnMut(T) -> Option<T>,
+    {
+        with_atomic!(self, Int, atomic => {
+            // SAFETY: every value the atomic hands out is the bits of a stored `T`.
+            let result = atomic.fetch_update(set_order, fetch_order, |bits| {
+                f(unsafe { from_bits::<T, Int>(bits) }).map(to_bits::<T, Int>)
+            });
+            unsafe {
+                result
+                    .map(|bits| from_bits::<T, Int>(bits))
+                    .map_err(|bits| from_bits::<T, Int>(bits))
+            }
+        })
+    }
+}
+
+impl<'brand, T: NoUninit + Default> Default for GhostAtomicCell<'brand, T> {
+    fn default() -> Self {
+        Self::new(T::default())
+    }
+}
+
+/// Reinterprets `value` as the integer of the same size.
+#[inline]
+fn to_bits<T: NoUninit, Int>(value: T) -> Int {
+    debug_assert_eq!(size_of::<T>(), size_of::<Int>());
+    // SAFETY: the sizes match, and `NoUninit` means every byte of `value` is initialized.
+    unsafe { transmute_copy(&value) }
+}
+
+/// Reinterprets `bits` as a `T`.
+///
+/// # Safety
+/// `bits` must have been produced by `to_bits` from a valid `T`.
+#[inline]
+unsafe fn from_bits<T: NoUninit, Int>(bits: Int) -> T {
+    debug_assert_eq!(size_of::<T>(), size_of::<Int>());
+    // SAFETY: guaranteed by the caller.
+    unsafe { transmute_copy(&bits) }
+}
+
+// SAFETY: the value is only accessed atomically (or through `&mut self`), so sharing
+// the cell moves `T` values between threads, which `T: Send` permits.
+unsafe impl<'brand, T: NoUninit + Send> Send for GhostAtomicCell<'brand, T> {}
+unsafe impl<'brand, T: NoUninit + Send> Sync for GhostAtomicCell<'brand, T> {}
@@ -9,15 +9,28 @@
 //! - The goal is that the *wrapper* overhead is optimized away.
 
 /// Branded atomic bitsets.
+#[cfg(feature = "alloc")]
 pub mod bitset;
 /// Branded `AtomicBool`.
 pub mod bool;
+/// Branded atomic cell for word-sized `Copy` values.
+pub mod cell;
+/// Branded counter striped over per-thread shards.
+#[cfg(all(feature = "std", target_has_atomic = "64"))]
+pub mod sharded_counter;
 /// Branded `AtomicU64`.
+#[cfg(target_has_atomic = "64")]
 pub mod u64;
 /// Branded `AtomicUsize`.
 pub mod usize;
+pub(crate) mod primitive;
 
+#[cfg(feature = "alloc")]
 pub use bitset::GhostAtomicBitset;
 pub use bool::GhostAtomicBool;
+pub use cell::GhostAtomicCell;
+#[cfg(all(feature = "std", target_has_atomic = "64"))]
+pub use sharded_counter::GhostShardedCounter;
+#[cfg(target_has_atomic = "64")]
 pub use u64::GhostAtomicU64;
 pub use usize::GhostAtomicUsize;
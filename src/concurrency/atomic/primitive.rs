@@ -0,0 +1,33 @@
+//! The atomics the lock-free structures are built on.
+//!
+//! Normally these are `core`'s. Built with `--cfg loom` they are [loom]'s instead, so
+//! the structures can be model-checked: loom runs a test under every interleaving of
+//! its threads, and every outcome of the weak memory model it simulates.
+//!
+//! ```text
+//! RUSTFLAGS="--cfg loom" cargo test --release --test loom
+//! ```
+//!
+//! Loom atomics cannot be created in `const` context, so the branded atomics lose
+//! their `const fn new` under loom. Two users stay on `core`'s atomics:
+//! [`GhostAtomicCell`](super::GhostAtomicCell), which reinterprets its storage as an
+//! atomic integer, and [`GhostShardedCounter`](super::GhostShardedCounter), which
+//! only collects statistics and must remain constructible in statics.
+//!
+//! [loom]: https://docs.rs/loom
+
+#[cfg(not(loom))]
+pub(crate) use core::sync::atomic::{AtomicBool, AtomicUsize};
+#[cfg(all(not(loom), target_has_atomic = "64"))]
+pub(crate) use core::sync::atomic::AtomicU64;
+#[cfg(all(not(loom), feature = "std"))]
+pub(crate) use core::sync::atomic::{fence, AtomicPtr};
+
+#[cfg(loom)]
+pub(crate) use loom::sync::atomic::{fence, AtomicBool, AtomicPtr, AtomicU64, AtomicUsize};
+/// The lock guarding the cold paths of lock-free structures; loom's under loom, whose
+/// threads must never block on a lock it cannot see.
+#[cfg(all(feature = "std", not(loom)))]
+pub(crate) use std::sync::Mutex;
+#[cfg(loom)]
+pub(crate) use loom::sync::Mutex;
@@ -0,0 +1,78 @@
+use core::marker::PhantomData;
+// Core's atomics even under loom: see `primitive`.
+use core::sync::atomic::{AtomicU64, Ordering};
+
+use crate::concurrency::{current_shard_index, CachePadded, SHARD_COUNT};
+
+/// A branded counter striped over [`SHARD_COUNT`] cache-padded slots.
+///
+/// `add` touches only the calling thread's slot, so threads counting concurrently do
+/// not contend on one cache line; `sum` folds every slot. The price is size (one cache
+/// line per shard) and a `sum` that is not a snapshot: additions racing with it may or
+/// may not be included. That suits statistics and progress counters, not values that
+/// drive synchronization.
+///
+/// Slots wrap on overflow and `sub` may take a slot "below zero"; the wrapping `sum`
+/// still yields the net total.
+pub struct GhostShardedCounter<'brand> {
+    shards: [CachePadded<AtomicU64>; SHARD_COUNT],
+    _brand: PhantomData<&'brand mut ()>,
+}
+
+impl<'brand> GhostShardedCounter<'brand> {
+    /// Creates a counter at zero.
+    pub const fn new() -> Self {
+        Self {
+            shards: [const { CachePadded::new(AtomicU64::new(0)) }; SHARD_COUNT],
+            _brand: PhantomData,
+        }
+    }
+
+    /// Adds `n` to the calling thread's slot.
+    #[inline]
+    pub fn add(&self, n: u64) {
+        self.shards[current_shard_index()].fetch_add(n, Ordering::Relaxed);
+    }
+
+    /// Subtracts `n` through the calling thread's slot.
+    #[inline]
+    pub fn sub(&self, n: u64) {
+        self.shards[current_shard_index()].fetch_sub(n, Ordering::Relaxed);
+    }
+
+    /// Adds one.
+    #[inline]
+    pub fn increment(&self) {
+        self.add(1);
+    }
+
+    /// Returns the total over all slots.
+    pub fn sum(&self) -> u64 {
+        self.shards
+            .iter()
+            .fold(0, |acc, s| acc.wrapping_add(s.load(Ordering::Relaxed)))
+    }
+
+    /// Resets every slot to zero.
+    ///
+    /// Additions racing with the reset may survive it.
+    pub fn reset(&self) {
+        for s in &self.shards {
+            s.store(0, Ordering::Relaxed);
+        }
+    }
+}
+
+impl<'brand> Default for GhostShardedCounter<'brand> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<'brand> core::fmt::Debug for GhostShardedCounter<'brand> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_tuple("GhostShardedCounter")
+            .field(&self.sum())
+            .finish()
+    }
+}
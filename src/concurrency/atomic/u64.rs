@@ -1,7 +1,6 @@
-use core::{
-    marker::PhantomData,
-    sync::atomic::{AtomicU64, Ordering},
-};
+use core::{marker::PhantomData, sync::atomic::Ordering};
+
+use super::primitive::AtomicU64;
 
 /// A branded `AtomicU64`.
 ///
@@ -15,6 +14,7 @@ pub struct GhostAtomicU64<'brand> {
 
 impl<'brand> GhostAtomicU64<'brand> {
     /// Creates a new atomic value.
+    #[cfg(not(loom))]
     #[inline(always)]
     pub const fn new(value: u64) -> Self {
         Self {
@@ -23,6 +23,15 @@ impl<'brand> GhostAtomicU64<'brand> {
         }
     }
 
+    /// Creates a new atomic value.
+    #[cfg(loom)]
+    pub fn new(value: u64) -> Self {
+        Self {
+            inner: AtomicU64::new(value),
+            _brand: PhantomData,
+        }
+    }
+
     /// Loads the current value.
     #[inline(always)]
     pub fn load(&self, order: Ordering) -> u64 {
@@ -1,7 +1,6 @@
-use core::{
-    marker::PhantomData,
-    sync::atomic::{AtomicUsize, Ordering},
-};
+use core::{marker::PhantomData, sync::atomic::Ordering};
+
+use super::primitive::AtomicUsize;
 
 /// A branded `AtomicUsize`.
 #[repr(transparent)]
@@ -12,6 +11,7 @@ pub struct GhostAtomicUsize<'brand> {
 
 impl<'brand> GhostAtomicUsize<'brand> {
     /// Creates a new branded atomic usize.
+    #[cfg(not(loom))]
     #[inline(always)]
     pub const fn new(value: usize) -> Self {
         Self {
@@ -20,6 +20,15 @@ impl<'brand> GhostAtomicUsize<'brand> {
         }
     }
 
+    /// Creates a new branded atomic usize.
+    #[cfg(loom)]
+    pub fn new(value: usize) -> Self {
+        Self {
+            inner: AtomicUsize::new(value),
+            _brand: PhantomData,
+        }
+    }
+
     /// Loads the current value.
     #[inline(always)]
     pub fn load(&self, order: Ordering) -> usize {
@@ -1,19 +1,73 @@
 //! Cache-padded wrapper to prevent false sharing.
+//!
+//! The padding is chosen per target at compile time ([`CACHE_LINE_SIZE`]);
+//! [`cache_line_size`] reports what the running machine actually uses, for code that
+//! lays out memory at run time.
 
-use std::ops::{Deref, DerefMut};
+use core::ops::{Deref, DerefMut};
 
 /// Helper struct for cache line padding to avoid false sharing.
-/// We use 128 bytes to be safe for most architectures (x86 is 64, Apple Silicon can be 128).
-#[repr(align(128))]
+///
+/// Aligned to [`CACHE_LINE_SIZE`]: 128 bytes on x86-64 and AArch64 (Intel's
+/// adjacent-line prefetcher pulls lines in pairs, and Apple Silicon uses 128-byte
+/// lines), 256 on s390x, 32 on 32-bit ARM, MIPS and SPARC, and 64 elsewhere.
+#[cfg_attr(
+    any(
+        target_arch = "x86_64",
+        target_arch = "aarch64",
+        target_arch = "arm64ec",
+        target_arch = "powerpc64",
+    ),
+    repr(align(128))
+)]
+#[cfg_attr(target_arch = "s390x", repr(align(256)))]
+#[cfg_attr(
+    any(
+        target_arch = "arm",
+        target_arch = "mips",
+        target_arch = "mips32r6",
+        target_arch = "mips64",
+        target_arch = "mips64r6",
+        target_arch = "sparc",
+        target_arch = "hexagon",
+    ),
+    repr(align(32))
+)]
+#[cfg_attr(
+    not(any(
+        target_arch = "x86_64",
+        target_arch = "aarch64",
+        target_arch = "arm64ec",
+        target_arch = "powerpc64",
+        target_arch = "s390x",
+        target_arch = "arm",
+        target_arch = "mips",
+        target_arch = "mips32r6",
+        target_arch = "mips64",
+        target_arch = "mips64r6",
+        target_arch = "sparc",
+        target_arch = "hexagon",
+    )),
+    repr(align(64))
+)]
 pub struct CachePadded<T> {
     value: T,
 }
 
+/// The alignment of [`CachePadded`]: the span that two independently written values
+/// must be apart on the compilation target.
+pub const CACHE_LINE_SIZE: usize = core::mem::align_of::<CachePadded<u8>>();
+
 impl<T> CachePadded<T> {
     /// Creates a new cache-padded value.
     pub const fn new(value: T) -> Self {
         Self { value }
     }
+
+    /// Returns the inner value.
+    pub fn into_inner(self) -> T {
+        self.value
+    }
 }
 
 impl<T> Deref for CachePadded<T> {
@@ -28,3 +82,101 @@ impl<T> DerefMut for CachePadded<T> {
         &mut self.value
     }
 }
+
+/// Returns the coherency line size of the running CPU.
+///
+/// Read from the OS once (sysfs on Linux, `hw.cachelinesize` on Apple platforms) and
+/// cached; falls back to [`CACHE_LINE_SIZE`] where it is unavailable or implausible,
+/// and always without `std`. The value may be smaller than `CACHE_LINE_SIZE`, which
+/// also covers prefetching of neighbouring lines; round allocations up to the larger
+/// of the two to keep them apart.
+pub fn cache_line_size() -> usize {
+    #[cfg(feature = "std")]
+    {
+        use core::sync::atomic::{AtomicUsize, Ordering};
+
+        static DETECTED: AtomicUsize = AtomicUsize::new(0);
+        let cached = DETECTED.load(Ordering::Relaxed);
+        if cached != 0 {
+            return cached;
+        }
+        let size = detect_line_size()
+            .filter(|size| size.is_power_of_two() && (16..=1024).contains(size))
+            .unwrap_or(CACHE_LINE_SIZE);
+        DETECTED.store(size, Ordering::Relaxed);
+        size
+    }
+    #[cfg(not(feature = "std"))]
+    {
+        CACHE_LINE_SIZE
+    }
+}
+
+/// Returns the distance, in `T`s, between slots of a run-time array of `T` that must
+/// not share a cache line: the larger of [`cache_line_size`] and [`CACHE_LINE_SIZE`],
+/// rounded up to whole `T`s.
+#[cfg(feature = "std")]
+pub(crate) fn padded_stride<T>() -> usize {
+    cache_line_size()
+        .max(CACHE_LINE_SIZE)
+        .div_ceil(core::mem::size_of::<T>().max(1))
+}
+
+#[cfg(all(feature = "std", any(target_os = "linux", target_os = "android")))]
+fn detect_line_size() -> Option<usize> {
+    std::fs::read_to_string("/sys/devices/system/cpu/cpu0/cache/index0/coherency_line_size")
+        .ok()?
+        .trim()
+        .parse()
+        .ok()
+}
+
+#[cfg(all(feature = "std", target_vendor = "apple"))]
+fn detect_line_size() -> Option<usize> {
+    let mut size: u64 = 0;
+    let mut len = core::mem::size_of::<u64>();
+    // SAFETY: `size` and `len` describe a valid, writable buffer for the value.
+    let rc = unsafe {
+        libc::sysctlbyname(
+            c"hw.cachelinesize".as_ptr(),
+            core::ptr::addr_of_mut!(size).cast(),
+            &mut len,
+            core::ptr::null_mut(),
+            0,
+        )
+    };
+    (rc == 0).then(|| usize::try_from(size).ok()).flatten()
+}
+
+#[cfg(all(
+    feature = "std",
+    not(any(target_os = "linux", target_os = "android", target_vendor = "apple"))
+))]
+fn detect_line_size() -> Option<usize> {
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cache_padded_alignment() {
+        assert!(CACHE_LINE_SIZE.is_power_of_two());
+        assert_eq!(core::mem::size_of::<CachePadded<u8>>(), CACHE_LINE_SIZE);
+        let pair = [CachePadded::new(0u64), CachePadded::new(1u64)];
+        let gap = core::ptr::from_ref(&*pair[1]).addr() - core::ptr::from_ref(&*pair[0]).addr();
+        assert!(gap >= CACHE_LINE_SIZE);
+
+        let detected = cache_line_size();
+        assert!(detected.is_power_of_two());
+        assert_eq!(cache_line_size(), detected);
+
+        #[cfg(feature = "std")]
+        {
+            let stride = padded_stride::<u64>() * core::mem::size_of::<u64>();
+            assert!(stride >= detected && stride >= CACHE_LINE_SIZE);
+            assert_eq!(padded_stride::<[u8; 1024]>(), 1);
+        }
+    }
+}
@@ -3,19 +3,37 @@
 //! Important: Ghost types enforce aliasing discipline, not synchronization.
 //! This module provides *scoped* patterns for sending/sharing the token across
 //! threads with minimal overhead and without locking the data itself.
+//!
+//! Without the `std` feature only the thread-agnostic layer (`atomic`,
+//! `cache_padded`) is available.
 
 pub mod atomic;
 pub mod cache_padded;
+#[cfg(feature = "rayon")]
+pub mod par;
+#[cfg(feature = "std")]
+pub mod pool;
+#[cfg(feature = "std")]
 pub mod scoped;
 /// Synchronization primitives.
+#[cfg(feature = "std")]
 pub mod sync;
+#[cfg(feature = "std")]
+pub mod token_registry;
+#[cfg(feature = "std")]
 pub mod worklist;
 
-pub use cache_padded::CachePadded;
+pub use cache_padded::{cache_line_size, CachePadded, CACHE_LINE_SIZE};
+#[cfg(feature = "std")]
+pub use token_registry::TokenRegistry;
 
+#[cfg(feature = "std")]
 use std::cell::Cell;
+#[cfg(feature = "std")]
 use std::collections::hash_map::DefaultHasher;
+#[cfg(feature = "std")]
 use std::hash::{Hash, Hasher};
+#[cfg(feature = "std")]
 use std::thread;
 
 /// The number of shards used for sharded concurrency patterns.
@@ -24,11 +42,54 @@ pub const SHARD_COUNT: usize = 32;
 /// Bitmask for fast shard index calculation.
 pub const SHARD_MASK: usize = SHARD_COUNT - 1;
 
+#[cfg(feature = "std")]
+static NEXT_SHARD_INDEX: core::sync::atomic::AtomicUsize = core::sync::atomic::AtomicUsize::new(0);
+
+#[cfg(feature = "std")]
 thread_local! {
     static THREAD_SHARD_INDEX: Cell<Option<usize>> = const { Cell::new(None) };
+    static THREAD_SLOT: ThreadSlot = const { ThreadSlot(Cell::new(None)) };
+}
+
+/// Thread slots not held by a live thread, handed out lowest first.
+#[cfg(feature = "std")]
+static FREE_THREAD_SLOTS: std::sync::Mutex<FreeThreadSlots> =
+    std::sync::Mutex::new(FreeThreadSlots {
+        next: 0,
+        free: std::collections::BinaryHeap::new(),
+    });
+
+#[cfg(feature = "std")]
+struct FreeThreadSlots {
+    /// Slots at and above `next` have never been handed out.
+    next: usize,
+    free: std::collections::BinaryHeap<core::cmp::Reverse<usize>>,
+}
+
+/// The calling thread's slot, returned to the free list when the thread exits.
+#[cfg(feature = "std")]
+struct ThreadSlot(Cell<Option<usize>>);
+
+#[cfg(feature = "std")]
+impl Drop for ThreadSlot {
+    fn drop(&mut self) {
+        if let Some(slot) = self.0.get() {
+            lock_free_thread_slots()
+                .free
+                .push(core::cmp::Reverse(slot));
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+fn lock_free_thread_slots() -> std::sync::MutexGuard<'static, FreeThreadSlots> {
+    FREE_THREAD_SLOTS
+        .lock()
+        .unwrap_or_else(std::sync::PoisonError::into_inner)
 }
 
 /// Generates a hash for the current thread.
+#[cfg(feature = "std")]
 pub fn current_thread_hash() -> usize {
     let mut hasher = DefaultHasher::new();
     thread::current().id().hash(&mut hasher);
@@ -37,15 +98,53 @@ pub fn current_thread_hash() -> usize {
 
 /// Returns the shard index for the current thread.
 ///
-/// This value is cached thread-locally to avoid recomputing the hash.
+/// Threads are assigned shards round-robin on first use and the index is cached
+/// thread-locally. Unlike hashing the thread id, this spreads up to `SHARD_COUNT`
+/// threads over distinct shards and never allocates, so it is safe to call from inside
+/// a global allocator.
+#[cfg(feature = "std")]
 pub fn current_shard_index() -> usize {
     THREAD_SHARD_INDEX.with(|idx| {
         if let Some(i) = idx.get() {
             i
         } else {
-            let i = current_thread_hash() & SHARD_MASK;
+            let i = NEXT_SHARD_INDEX.fetch_add(1, core::sync::atomic::Ordering::Relaxed)
+                & SHARD_MASK;
             idx.set(Some(i));
             i
         }
     })
 }
+
+/// Returns a slot index that no other live thread holds.
+///
+/// Unlike [`current_shard_index`], slots are unique: a thread takes the lowest free
+/// slot on first use and keeps it until it exits, when the slot is recycled. Slots
+/// thus stay below the largest number of threads ever alive at once, and can index
+/// dense per-thread tables such as [`TokenRegistry`].
+///
+/// Takes a lock (and may allocate) on a thread's first call, so unlike the shard
+/// index it must not be used from inside a global allocator.
+#[cfg(feature = "std")]
+pub fn current_thread_slot() -> usize {
+    let take = || {
+        let mut slots = lock_free_thread_slots();
+        match slots.free.pop() {
+            Some(core::cmp::Reverse(slot)) => slot,
+            None => {
+                slots.next += 1;
+                slots.next - 1
+            }
+        }
+    };
+    THREAD_SLOT
+        .try_with(|slot| {
+            slot.0.get().unwrap_or_else(|| {
+                let s = take();
+                slot.0.set(Some(s));
+                s
+            })
+        })
+        // During thread teardown the slot may be gone; take one for good.
+        .unwrap_or_else(|_| take())
+}
@@ -0,0 +1,113 @@
+//! Bridges between branded tokens and rayon's thread pool.
+//!
+//! [`with_token_par`] runs a closure on a rayon worker with a read token for the
+//! caller's brand. The token is `Copy` and `Send`, so the closure can hand it to
+//! `rayon::join`, `par_iter` and friends, and existing rayon code can read branded
+//! cells without restructuring. To feed halo worklists into rayon pipelines, see
+//! [`GhostTreiberStack::par_drain`] and [`GhostChaseLevDeque::par_steal`].
+//!
+//! ```
+//! use halo::concurrency::par::with_token_par;
+//! use halo::{GhostCell, GhostToken};
+//! use rayon::prelude::*;
+//!
+//! GhostToken::new(|token| {
+//!     let cells: Vec<GhostCell<'_, u64>> = (1..=100).map(GhostCell::new).collect();
+//!     let sum: u64 = with_token_par(&token, |read| {
+//!         cells.par_iter().map(|c| *c.borrow(&read)).sum()
+//!     });
+//!     assert_eq!(sum, 5050);
+//! });
+//! ```
+//!
+//! [`GhostTreiberStack::par_drain`]: crate::concurrency::worklist::GhostTreiberStack::par_drain
+//! [`GhostChaseLevDeque::par_steal`]: crate::concurrency::worklist::GhostChaseLevDeque::par_steal
+
+use crate::token::ImmutableChild;
+use crate::GhostToken;
+
+/// Runs `f` on rayon's global pool with a read token for `token`'s brand.
+///
+/// Called from a rayon worker, `f` runs in place; otherwise the calling thread blocks
+/// until a worker has run it.
+pub fn with_token_par<'t, 'brand, F, R>(token: &'t GhostToken<'brand>, f: F) -> R
+where
+    F: FnOnce(ImmutableChild<'t, 'brand>) -> R + Send,
+    R: Send,
+{
+    let (read, _) = token.split_immutable();
+    rayon::scope(move |_| f(read))
+}
+
+/// Like [`with_token_par`], but runs `f` on `pool` instead of the global pool.
+pub fn with_token_par_in<'t, 'brand, F, R>(
+    pool: &rayon::ThreadPool,
+    token: &'t GhostToken<'brand>,
+    f: F,
+) -> R
+where
+    F: FnOnce(ImmutableChild<'t, 'brand>) -> R + Send,
+    R: Send,
+{
+    let (read, _) = token.split_immutable();
+    pool.install(move || f(read))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::concurrency::worklist::{GhostChaseLevDeque, GhostTreiberStack};
+    use crate::GhostCell;
+    use rayon::prelude::*;
+
+    #[test]
+    fn test_with_token_par_reads_cells_on_the_pool() {
+        GhostToken::new(|token| {
+            let cells: Vec<GhostCell<'_, usize>> = (0..1000).map(GhostCell::new).collect();
+            let pool = rayon::ThreadPoolBuilder::new()
+                .num_threads(3)
+                .build()
+                .unwrap();
+
+            let (max, on_pool) = with_token_par_in(&pool, &token, |read| {
+                let max = cells.par_iter().map(|c| *c.borrow(&read)).max();
+                (max, rayon::current_thread_index().is_some())
+            });
+            assert_eq!(max, Some(999));
+            assert!(on_pool);
+
+            let evens = with_token_par(&token, |read| {
+                let (a, b) = cells.split_at(500);
+                let (x, y) = rayon::join(
+                    || a.iter().filter(|c| *c.borrow(&read) % 2 == 0).count(),
+                    || b.iter().filter(|c| *c.borrow(&read) % 2 == 0).count(),
+                );
+                x + y
+            });
+            assert_eq!(evens, 500);
+        });
+    }
+
+    #[test]
+    fn test_worklists_drain_into_parallel_iterators() {
+        GhostToken::new(|token| {
+            let stack = GhostTreiberStack::new(1000);
+            for i in 0..1000 {
+                stack.push(&token, i);
+            }
+            let mut drained: Vec<usize> = stack.par_drain(&token).collect();
+            drained.sort_unstable();
+            assert_eq!(drained, (0..1000).collect::<Vec<_>>());
+            assert_eq!(stack.pop(&token), None);
+
+            let deque = GhostChaseLevDeque::new(16);
+            for i in 0..1000 {
+                deque.push_bottom(&token, i);
+            }
+            let (read, _) = token.split_immutable();
+            let sum: usize = deque.par_steal(&read).sum();
+            assert_eq!(sum, 999 * 1000 / 2);
+            assert_eq!(deque.pop_bottom(&token), None);
+        });
+    }
+}
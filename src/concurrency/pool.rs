@@ -0,0 +1,342 @@
+//! A scoped work-stealing thread pool for branded data.
+//!
+//! [`GhostThreadPool::scope`] starts one scoped worker per thread, each owning a
+//! [`GhostChaseLevDeque`] of pending tasks, and returns once every task spawned in the
+//! scope has finished. Tasks receive a [`GhostTaskContext`] carrying a read token for
+//! the caller's brand, so they can read branded cells directly, and can spawn follow-up
+//! tasks onto their worker's deque. Idle workers steal batches from the others.
+//!
+//! Mutation goes through disjoint regions: split a `BrandedVec` of another brand with
+//! [`BrandedVec::split_views`](crate::collections::BrandedVec::split_views) and hand
+//! the views to [`GhostPoolScope::spawn_regions`], one task per region.
+//!
+//! ```
+//! use halo::concurrency::pool::GhostThreadPool;
+//! use halo::{GhostCell, GhostToken};
+//! use std::sync::atomic::{AtomicUsize, Ordering};
+//!
+//! GhostToken::new(|token| {
+//!     let cells: Vec<GhostCell<'_, usize>> = (0..100).map(GhostCell::new).collect();
+//!     let sum = AtomicUsize::new(0);
+//!
+//!     GhostThreadPool::new(4).scope(&token, |s| {
+//!         for chunk in cells.chunks(10) {
+//!             let sum = &sum;
+//!             s.spawn(move |ctx| {
+//!                 let part: usize = chunk.iter().map(|c| *c.borrow(&ctx.token())).sum();
+//!                 sum.fetch_add(part, Ordering::Relaxed);
+//!             });
+//!         }
+//!     });
+//!     assert_eq!(sum.into_inner(), 4950);
+//! });
+//! ```
+
+use core::cell::Cell;
+use core::marker::PhantomData;
+use core::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::any::Any;
+use std::collections::VecDeque;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+use crate::collections::vec::slice::BrandedVecView;
+use crate::concurrency::sync::Signal;
+use crate::concurrency::worklist::GhostChaseLevDeque;
+use crate::concurrency::CachePadded;
+use crate::token::hierarchy::{FullAccess, HierarchicalGhostToken};
+use crate::token::ImmutableChild;
+use crate::GhostToken;
+
+/// Initial capacity of each worker's deque; deques grow on demand.
+const DEQUE_CAPACITY: usize = 64;
+
+type Task<'env, 'brand> = Box<dyn FnOnce(&GhostTaskContext<'_, 'env, 'brand>) + Send + 'env>;
+
+/// A pool of scoped worker threads with per-worker work-stealing deques.
+///
+/// The pool itself only records the thread count; workers live for the duration of
+/// one [`scope`](Self::scope) call, like `std::thread::scope`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GhostThreadPool {
+    threads: usize,
+}
+
+impl GhostThreadPool {
+    /// Creates a pool running `threads` workers per scope.
+    ///
+    /// # Panics
+    /// Panics if `threads` is zero.
+    pub fn new(threads: usize) -> Self {
+        assert!(threads > 0, "a thread pool needs at least one thread");
+        Self { threads }
+    }
+
+    /// Returns the number of workers started per scope.
+    #[inline]
+    pub fn threads(&self) -> usize {
+        self.threads
+    }
+
+    /// Runs `f` with a scope for spawning tasks, and returns once every task spawned in
+    /// it (directly or by other tasks) has finished.
+    ///
+    /// Tasks may borrow anything that outlives this call, and read `token`'s brand
+    /// through [`GhostTaskContext::token`].
+    ///
+    /// # Panics
+    /// If `f` or any task panics, the remaining tasks still run to completion and the
+    /// first panic is then resumed on the calling thread.
+    pub fn scope<'env, 'brand, F, R>(&self, token: &'env GhostToken<'brand>, f: F) -> R
+    where
+        F: FnOnce(&GhostPoolScope<'_, 'env, 'brand>) -> R,
+    {
+        let (read, _) = token.split_immutable();
+        GhostToken::new(|queues| {
+            let (steal, _) = queues.split_immutable();
+            let shared = Shared::new(self.threads);
+            let result = thread::scope(|s| {
+                for worker in 0..self.threads {
+                    let shared = &shared;
+                    s.spawn(move || shared.work(worker, steal, read));
+                }
+                let _finish = Finish(&shared);
+                f(&GhostPoolScope {
+                    spawner: &shared,
+                    read,
+                    _env: PhantomData,
+                })
+            });
+            if let Some(payload) = shared.panic.lock().unwrap().take() {
+                panic::resume_unwind(payload);
+            }
+            result
+        })
+    }
+}
+
+impl Default for GhostThreadPool {
+    /// Creates a pool with one worker per available CPU.
+    fn default() -> Self {
+        Self::new(thread::available_parallelism().map_or(1, core::num::NonZeroUsize::get))
+    }
+}
+
+/// Handle for spawning tasks into a running [`GhostThreadPool::scope`].
+pub struct GhostPoolScope<'s, 'env, 'brand> {
+    spawner: &'s dyn Spawner<'env, 'brand>,
+    read: ImmutableChild<'env, 'brand>,
+    // Invariant in `'env`, so tasks cannot borrow locals of the scope closure.
+    _env: PhantomData<&'env mut &'env ()>,
+}
+
+impl<'s, 'env, 'brand> GhostPoolScope<'s, 'env, 'brand> {
+    /// Queues `task` to run on some worker.
+    pub fn spawn<F>(&self, task: F)
+    where
+        F: FnOnce(&GhostTaskContext<'_, 'env, 'brand>) + Send + 'env,
+    {
+        self.spawner.inject(Box::new(task));
+    }
+
+    /// Spawns one task per view, calling `f` with the task context and the view.
+    ///
+    /// The views must come from a vector whose token is not the one shared with the
+    /// scope, e.g. an output vector split with `BrandedVec::split_views`.
+    pub fn spawn_regions<T, I, F>(&self, views: I, f: F)
+    where
+        T: Send + 'env,
+        I: IntoIterator<Item = BrandedVecView<'env, T>>,
+        F: Fn(&GhostTaskContext<'_, 'env, 'brand>, BrandedVecView<'env, T>) + Send + Sync + 'env,
+    {
+        let f = Arc::new(f);
+        for view in views {
+            let f = Arc::clone(&f);
+            self.spawn(move |ctx| f(ctx, view));
+        }
+    }
+
+    /// Returns a read token for the scope's brand.
+    #[inline]
+    pub fn token(&self) -> ImmutableChild<'env, 'brand> {
+        self.read
+    }
+}
+
+/// What a running task knows about its worker.
+///
+/// The context is tied to the worker thread running the task and is not `Sync`.
+pub struct GhostTaskContext<'a, 'env, 'brand> {
+    spawner: &'a dyn Spawner<'env, 'brand>,
+    worker: usize,
+    threads: usize,
+    read: ImmutableChild<'env, 'brand>,
+    // `spawn` pushes onto this worker's deque, which only its owner may do.
+    _not_sync: PhantomData<Cell<()>>,
+}
+
+impl<'a, 'env, 'brand> GhostTaskContext<'a, 'env, 'brand> {
+    /// Returns a read token for the scope's brand.
+    #[inline]
+    pub fn token(&self) -> ImmutableChild<'env, 'brand> {
+        self.read
+    }
+
+    /// Returns the index of the worker running this task, in `0..threads()`.
+    #[inline]
+    pub fn worker_index(&self) -> usize {
+        self.worker
+    }
+
+    /// Returns the number of workers in the scope.
+    #[inline]
+    pub fn threads(&self) -> usize {
+        self.threads
+    }
+
+    /// Queues `task` on this worker's deque, where it runs next unless stolen.
+    pub fn spawn<F>(&self, task: F)
+    where
+        F: FnOnce(&GhostTaskContext<'_, 'env, 'brand>) + Send + 'env,
+    {
+        self.spawner.push_local(self.worker, Box::new(task));
+    }
+}
+
+/// Erases the queue brand of `Shared` from the public handles.
+trait Spawner<'env, 'brand>: Sync {
+    fn inject(&self, task: Task<'env, 'brand>);
+    fn push_local(&self, worker: usize, task: Task<'env, 'brand>);
+}
+
+struct Shared<'q, 'env, 'brand> {
+    deques: Box<[CachePadded<GhostChaseLevDeque<'q>>]>,
+    /// Tasks spawned from outside the workers.
+    injector: Mutex<VecDeque<Task<'env, 'brand>>>,
+    /// Tasks spawned but not yet finished.
+    pending: AtomicUsize,
+    shutdown: AtomicBool,
+    signal: Signal,
+    panic: Mutex<Option<Box<dyn Any + Send>>>,
+}
+
+impl<'q, 'env, 'brand> Shared<'q, 'env, 'brand> {
+    fn new(threads: usize) -> Self {
+        Self {
+            deques: (0..threads)
+                .map(|_| CachePadded::new(GhostChaseLevDeque::new(DEQUE_CAPACITY)))
+                .collect(),
+            injector: Mutex::new(VecDeque::new()),
+            pending: AtomicUsize::new(0),
+            shutdown: AtomicBool::new(false),
+            signal: Signal::new(),
+            panic: Mutex::new(None),
+        }
+    }
+
+    fn work(
+        &self,
+        worker: usize,
+        steal: ImmutableChild<'_, 'q>,
+        read: ImmutableChild<'env, 'brand>,
+    ) {
+        let ctx = GhostTaskContext {
+            spawner: self,
+            worker,
+            threads: self.deques.len(),
+            read,
+            _not_sync: PhantomData,
+        };
+        loop {
+            let mut found = self.find_task(worker, steal);
+            if found.is_none() {
+                self.signal.wait_while(|| {
+                    found = self.find_task(worker, steal);
+                    found.is_none() && !self.shutdown.load(Ordering::Acquire)
+                });
+            }
+            match found {
+                Some(task) => self.run(task, &ctx),
+                None if self.shutdown.load(Ordering::Acquire) => return,
+                None => {}
+            }
+        }
+    }
+
+    fn find_task(
+        &self,
+        worker: usize,
+        steal: ImmutableChild<'_, 'q>,
+    ) -> Option<Task<'env, 'brand>> {
+        // SAFETY: called from `work`, on `worker`'s thread, which owns the deque.
+        let owner: HierarchicalGhostToken<'_, 'q, FullAccess> =
+            unsafe { HierarchicalGhostToken::new() };
+        let own = &self.deques[worker];
+        let raw = own.pop_bottom(&owner).or_else(|| {
+            let injected = self.injector.lock().unwrap().pop_front();
+            if let Some(task) = injected {
+                return Some(Self::into_raw(task));
+            }
+            let n = self.deques.len();
+            (1..n)
+                .map(|k| &self.deques[(worker + k) % n])
+                .find_map(|victim| victim.steal_batch_and_pop(&steal, own, &owner))
+        })?;
+        // SAFETY: every index in a deque came from `into_raw` and is popped once.
+        Some(unsafe { Self::from_raw(raw) })
+    }
+
+    fn run(&self, task: Task<'env, 'brand>, ctx: &GhostTaskContext<'_, 'env, 'brand>) {
+        if let Err(payload) = panic::catch_unwind(AssertUnwindSafe(|| task(ctx))) {
+            self.panic.lock().unwrap().get_or_insert(payload);
+        }
+        if self.pending.fetch_sub(1, Ordering::AcqRel) == 1 {
+            self.signal.notify();
+        }
+    }
+
+    fn into_raw(task: Task<'env, 'brand>) -> usize {
+        Box::into_raw(Box::new(task)) as usize
+    }
+
+    /// # Safety
+    /// `raw` must come from `into_raw` and not have been converted back yet.
+    unsafe fn from_raw(raw: usize) -> Task<'env, 'brand> {
+        *unsafe { Box::from_raw(raw as *mut Task<'env, 'brand>) }
+    }
+}
+
+impl<'q, 'env, 'brand> Spawner<'env, 'brand> for Shared<'q, 'env, 'brand> {
+    fn inject(&self, task: Task<'env, 'brand>) {
+        self.pending.fetch_add(1, Ordering::AcqRel);
+        self.injector.lock().unwrap().push_back(task);
+        self.signal.notify();
+    }
+
+    fn push_local(&self, worker: usize, task: Task<'env, 'brand>) {
+        self.pending.fetch_add(1, Ordering::AcqRel);
+        // SAFETY: `GhostTaskContext` is not `Sync`, so this runs on `worker`'s thread,
+        // which owns the deque.
+        let owner: HierarchicalGhostToken<'_, 'q, FullAccess> =
+            unsafe { HierarchicalGhostToken::new() };
+        self.deques[worker].push_bottom(&owner, Self::into_raw(task));
+        self.signal.notify();
+    }
+}
+
+/// Waits for outstanding tasks and stops the workers, even if the scope closure panics.
+struct Finish<'a, 'q, 'env, 'brand>(&'a Shared<'q, 'env, 'brand>);
+
+impl Drop for Finish<'_, '_, '_, '_> {
+    fn drop(&mut self) {
+        let shared = self.0;
+        while shared.pending.load(Ordering::Acquire) != 0 {
+            shared
+                .signal
+                .wait_while(|| shared.pending.load(Ordering::Acquire) != 0);
+        }
+        shared.shutdown.store(true, Ordering::Release);
+        shared.signal.notify();
+    }
+}
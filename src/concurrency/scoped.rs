@@ -4,12 +4,23 @@
 //! - **Read-scope**: share `&GhostToken<'brand>` across threads for read-only access.
 //! - **Write-scope**: move `GhostToken<'brand>` by value into a thread and return it
 //!   ("baton passing") for exclusive mutation without locking.
+//!
+//! [`broadcast`] covers the most common read-scope shape: the same closure on every
+//! worker, results collected per worker. [`GhostWaitGroup`] tracks subtasks spawned
+//! dynamically inside a scope.
 // People's expectation from GhostCell (per RustBelt paper) is "no runtime borrow state";
 // these helpers keep that property while still respecting Rust's thread/lifetime rules.
 
+use crate::concurrency::sync::{wait_on_u32, wake_all_u32};
+use crate::token::traits::GhostBorrow;
 use crate::GhostToken;
+use core::sync::atomic::{AtomicU32, Ordering};
+use std::sync::Arc;
 
 /// A scoped environment that can spawn tasks using a shared `&GhostToken<'brand>`.
+///
+/// The handle is `Copy`, so spawned tasks can take it along and spawn subtasks.
+#[derive(Clone, Copy)]
 pub struct GhostReadScope<'scope, 'env, 'brand> {
     scope: &'scope std::thread::Scope<'scope, 'env>,
     token: &'env GhostToken<'brand>,
@@ -94,6 +105,37 @@ where
     })
 }
 
+/// Runs `f` once on each of `workers` workers sharing `&GhostToken<'brand>`, and returns
+/// the results indexed by worker.
+///
+/// Worker `0` is the calling thread and the others are scoped threads, so `f` may
+/// borrow from the caller. `f` receives the token and the worker index; combine the
+/// per-worker results (sums, partial vectors, ...) from the returned `Vec`.
+///
+/// # Panics
+/// Panics if `workers` is zero. If `f` panics on any worker, the other workers still
+/// run to completion and the panic is then resumed on the calling thread.
+pub fn broadcast<'brand, R, F>(token: &GhostToken<'brand>, workers: usize, f: F) -> Vec<R>
+where
+    F: Fn(&GhostToken<'brand>, usize) -> R + Sync,
+    R: Send,
+{
+    assert!(workers != 0, "workers must be > 0");
+
+    let f = &f;
+    with_read_scope(token, |scope| {
+        let handles: Vec<_> = (1..workers)
+            .map(|worker| scope.spawn(move |t| f(t, worker)))
+            .collect();
+        let first = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| f(token, 0)));
+        let rest: Vec<_> = handles.into_iter().map(|h| h.join()).collect();
+        core::iter::once(first)
+            .chain(rest)
+            .map(|r| r.unwrap_or_else(|payload| std::panic::resume_unwind(payload)))
+            .collect()
+    })
+}
+
 /// Runs a **lock-free** two-phase parallel pattern:
 ///
 /// 1. A parallel **compute phase** where all threads share `&GhostToken<'brand>` (read-only).
@@ -114,14 +156,79 @@ where
 {
     assert!(threads != 0, "threads must be > 0");
 
-    let work: Vec<W> = with_read_scope(&*token, |scope| {
-        let compute = &compute;
-        let mut hs = Vec::with_capacity(threads);
-        for tid in 0..threads {
-            hs.push(scope.spawn(move |t| (compute)(t, tid)));
+    let work = broadcast(token, threads, compute);
+    commit(token, work)
+}
+
+/// Tracks completion of a dynamic set of tasks.
+///
+/// Every clone registers one more task and dropping a handle completes it, so a task
+/// holds its handle for as long as it runs and may clone it for subtasks it spawns.
+/// [`wait`](Self::wait) gives up the caller's own handle and parks until every other
+/// handle has been dropped. Waiting requires a token of the brand.
+pub struct GhostWaitGroup<'brand> {
+    pending: Arc<AtomicU32>,
+    _brand: core::marker::PhantomData<fn(&'brand ()) -> &'brand ()>,
+}
+
+impl<'brand> GhostWaitGroup<'brand> {
+    /// Creates a wait group with one registered task: the returned handle.
+    pub fn new() -> Self {
+        Self {
+            pending: Arc::new(AtomicU32::new(1)),
+            _brand: core::marker::PhantomData,
         }
-        hs.into_iter().map(|h| h.join().unwrap()).collect()
-    });
+    }
 
-    commit(token, work)
+    /// Returns the number of live handles, including this one.
+    #[inline]
+    pub fn pending(&self) -> u32 {
+        self.pending.load(Ordering::Acquire)
+    }
+
+    /// Drops this handle and blocks until all other handles are dropped.
+    pub fn wait(self, _token: &impl GhostBorrow<'brand>) {
+        let pending = Arc::clone(&self.pending);
+        drop(self);
+        loop {
+            let n = pending.load(Ordering::Acquire);
+            if n == 0 {
+                return;
+            }
+            wait_on_u32(&pending, n);
+        }
+    }
+}
+
+impl<'brand> Default for GhostWaitGroup<'brand> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<'brand> Clone for GhostWaitGroup<'brand> {
+    fn clone(&self) -> Self {
+        let prev = self.pending.fetch_add(1, Ordering::Relaxed);
+        assert!(prev != u32::MAX, "too many GhostWaitGroup handles");
+        Self {
+            pending: Arc::clone(&self.pending),
+            _brand: core::marker::PhantomData,
+        }
+    }
+}
+
+impl<'brand> Drop for GhostWaitGroup<'brand> {
+    fn drop(&mut self) {
+        if self.pending.fetch_sub(1, Ordering::AcqRel) == 1 {
+            wake_all_u32(&self.pending);
+        }
+    }
+}
+
+impl<'brand> core::fmt::Debug for GhostWaitGroup<'brand> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("GhostWaitGroup")
+            .field("pending", &self.pending())
+            .finish()
+    }
 }
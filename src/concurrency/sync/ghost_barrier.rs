@@ -1,24 +1,68 @@
 //! `GhostBarrier` — a token-gated barrier.
 
-use std::sync::{Barrier, BarrierWaitResult};
-use std::marker::PhantomData;
+use super::{wait_on_u32, wake_all_u32};
 use crate::token::traits::GhostBorrow;
+use core::marker::PhantomData;
+use core::sync::atomic::{AtomicU32, AtomicUsize, Ordering};
 
-/// A barrier that requires a `GhostToken` to participate.
+/// Bits of `state` that count arrivals; the bits above hold the generation.
+const COUNT_BITS: u32 = usize::BITS / 2;
+const COUNT_MASK: usize = (1 << COUNT_BITS) - 1;
+
+/// The generation held in the upper bits of a `state` value.
+#[inline]
+fn generation_of(state: usize) -> u32 {
+    let Ok(generation) = u32::try_from(state >> COUNT_BITS) else {
+        unreachable!("half of a usize fits in 32 bits");
+    };
+    generation
+}
+
+/// A reusable barrier that requires a `GhostToken` to participate.
 ///
 /// This ensures that only threads with access to a specific brand can synchronize
 /// using this barrier. This is useful for scoped concurrency where threads
-/// operate on shared branded data.
+/// operate on shared branded data, e.g. swapping BFS frontiers between phases.
+///
+/// Waiting threads sleep on the crate's futex layer. Every completed rendezvous
+/// starts a new generation, so the barrier can be reused right away.
 pub struct GhostBarrier<'brand> {
-    inner: Barrier,
+    /// Generation in the high bits and arrivals in the low bits, updated together so
+    /// that an arrival always knows which generation it belongs to.
+    state: AtomicUsize,
+    /// The generation from `state`, published once it is complete; waiters sleep on it.
+    generation: AtomicU32,
+    n: usize,
     _phantom: PhantomData<fn(&'brand ()) -> &'brand ()>,
 }
 
+/// Returned by [`GhostBarrier::wait`]; tells one thread per rendezvous it was the leader.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BarrierWaitResult {
+    leader: bool,
+}
+
+impl BarrierWaitResult {
+    /// Returns `true` for exactly one thread of each rendezvous: the last to arrive.
+    #[inline]
+    pub fn is_leader(&self) -> bool {
+        self.leader
+    }
+}
+
 impl<'brand> GhostBarrier<'brand> {
     /// Creates a new barrier that can block a given number of threads.
+    ///
+    /// A barrier for zero threads behaves like one for a single thread.
+    ///
+    /// # Panics
+    /// Panics if `n` does not fit the arrival counter (`2^16 - 1` on 32-bit targets).
     pub fn new(n: usize) -> Self {
+        assert!(n <= COUNT_MASK, "GhostBarrier supports at most {COUNT_MASK} threads");
         Self {
-            inner: Barrier::new(n),
+            state: AtomicUsize::new(0),
+            generation: AtomicU32::new(0),
+            n: n.max(1),
             _phantom: PhantomData,
         }
     }
@@ -28,6 +72,21 @@ impl<'brand> GhostBarrier<'brand> {
     /// The `_token` argument proves that the thread possesses the necessary
     /// capability (branded token) to participate in this synchronization scope.
     pub fn wait(&self, _token: &impl GhostBorrow<'brand>) -> BarrierWaitResult {
-        self.inner.wait()
+        let prev = self.state.fetch_add(1, Ordering::AcqRel);
+        let generation = generation_of(prev);
+        if (prev & COUNT_MASK) + 1 == self.n {
+            // Everyone of this generation has arrived and no one of the next can arrive
+            // before being released, so the reset cannot lose an arrival.
+            let next = (prev >> COUNT_BITS).wrapping_add(1) << COUNT_BITS;
+            self.state.store(next, Ordering::Relaxed);
+            self.generation
+                .store(generation_of(next), Ordering::Release);
+            wake_all_u32(&self.generation);
+            return BarrierWaitResult { leader: true };
+        }
+        while self.generation.load(Ordering::Acquire) == generation {
+            wait_on_u32(&self.generation, generation);
+        }
+        BarrierWaitResult { leader: false }
     }
 }
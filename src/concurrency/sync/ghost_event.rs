@@ -0,0 +1,106 @@
+//! `GhostEvent` — a token-gated manual-reset event.
+
+use super::{wait_on_u32, wait_on_u32_timeout, wake_all_u32};
+use crate::token::traits::GhostBorrow;
+use core::marker::PhantomData;
+use core::sync::atomic::{AtomicU32, Ordering};
+use std::time::{Duration, Instant};
+
+const SET: u32 = 1;
+/// Set while threads are parked waiting for the event.
+const PARKED: u32 = 2;
+
+/// An event flag: threads wait until it is set, and it stays set until reset.
+///
+/// Setting it once and never resetting it makes a one-shot signal ("the shared state
+/// is ready"); resetting it re-arms it for the next round. Unlike
+/// [`GhostLatch`](super::GhostLatch), the flag is not counted: setting it several
+/// times is the same as setting it once. Waiting requires a token of the brand;
+/// setting and resetting do not.
+pub struct GhostEvent<'brand> {
+    state: AtomicU32,
+    _phantom: PhantomData<fn(&'brand ()) -> &'brand ()>,
+}
+
+impl<'brand> GhostEvent<'brand> {
+    /// Creates an event that is not set.
+    pub const fn new() -> Self {
+        Self {
+            state: AtomicU32::new(0),
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Sets the event, releasing every waiting thread.
+    ///
+    /// Writes made before `set` are visible to the threads that see the event set.
+    pub fn set(&self) {
+        if self.state.swap(SET, Ordering::Release) & PARKED != 0 {
+            wake_all_u32(&self.state);
+        }
+    }
+
+    /// Clears the event, so that later waits block until the next [`set`](Self::set).
+    pub fn reset(&self) {
+        // Keep `PARKED`: threads still asleep must be woken by the next `set`.
+        self.state.fetch_and(!SET, Ordering::Relaxed);
+    }
+
+    /// Returns `true` if the event is set, without blocking.
+    #[inline]
+    pub fn is_set(&self) -> bool {
+        self.state.load(Ordering::Acquire) & SET != 0
+    }
+
+    /// Blocks until the event is set.
+    ///
+    /// The `_token` argument proves that the thread possesses the necessary
+    /// capability (branded token) to participate in this synchronization scope.
+    pub fn wait(&self, _token: &impl GhostBorrow<'brand>) {
+        while let Some(parked) = self.prepare_park() {
+            wait_on_u32(&self.state, parked);
+        }
+    }
+
+    /// Like [`wait`](Self::wait), but gives up after `timeout`; returns whether the
+    /// event was set.
+    pub fn wait_timeout(&self, _token: &impl GhostBorrow<'brand>, timeout: Duration) -> bool {
+        let deadline = Instant::now() + timeout;
+        while let Some(parked) = self.prepare_park() {
+            let Some(left) = deadline.checked_duration_since(Instant::now()) else {
+                return false;
+            };
+            wait_on_u32_timeout(&self.state, parked, left);
+        }
+        true
+    }
+
+    /// Returns `None` if the event is set, or else the state value to sleep on, with
+    /// `PARKED` recorded in it.
+    fn prepare_park(&self) -> Option<u32> {
+        let mut state = self.state.load(Ordering::Acquire);
+        loop {
+            if state & SET != 0 {
+                return None;
+            }
+            if state & PARKED != 0 {
+                return Some(state);
+            }
+            match self.state.compare_exchange_weak(
+                state,
+                state | PARKED,
+                Ordering::Relaxed,
+                Ordering::Acquire,
+            ) {
+                Ok(_) => return Some(state | PARKED),
+                Err(actual) => state = actual,
+            }
+        }
+    }
+}
+
+impl Default for GhostEvent<'_> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
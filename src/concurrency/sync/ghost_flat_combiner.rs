@@ -0,0 +1,237 @@
+//! `GhostFlatCombiner` — flat combining over a token-gated structure.
+//!
+//! The combiner owns a `GhostToken` and the structure it gates. A thread that wants to
+//! run an operation publishes it in a slot and then either waits for the result or, if
+//! nobody holds the combiner lock, takes it and runs every published operation in one
+//! pass. The structure is touched by one thread at a time with a hot cache, and the
+//! lock changes hands once per batch instead of once per operation, so structures that
+//! need `&mut GhostToken` keep up under moderate contention without being redesigned.
+//!
+//! Slots are picked by [`current_shard_index`]; when every slot is taken the thread
+//! falls back to taking the lock itself.
+
+use core::cell::UnsafeCell;
+use core::hint;
+use core::sync::atomic::{AtomicBool, AtomicU8, Ordering};
+use std::any::Any;
+use std::panic::{self, AssertUnwindSafe};
+
+use crate::concurrency::{current_shard_index, CachePadded, SHARD_COUNT};
+use crate::token::GhostToken;
+
+/// Spins before a waiting thread starts yielding its time slice.
+const SPINS_BEFORE_YIELD: u32 = 64;
+/// Passes over the slots a combiner makes while it keeps finding work.
+const MAX_COMBINE_PASSES: usize = 3;
+
+const EMPTY: u8 = 0;
+/// Claimed by a thread that is still writing its request.
+const CLAIMED: u8 = 1;
+const PENDING: u8 = 2;
+const DONE: u8 = 3;
+
+/// Runs the request behind the pointer against the structure.
+type Call<'brand, T> = unsafe fn(*mut (), &mut T, &mut GhostToken<'brand>);
+type Panic = Box<dyn Any + Send>;
+
+/// A structure and its token, operated on through flat combining.
+///
+/// Operations are closures taking `&mut T` and `&mut GhostToken<'brand>`, so any
+/// branded structure (or plain value) can sit behind the combiner unchanged. They may
+/// run on another thread than the caller, hence the `Send` bounds.
+///
+/// A panicking operation is reported to the thread that submitted it; the structure is
+/// left as the operation left it, as with a poisoned mutex that is used anyway.
+pub struct GhostFlatCombiner<'brand, T> {
+    locked: AtomicBool,
+    slots: [CachePadded<Slot<'brand, T>>; SHARD_COUNT],
+    state: UnsafeCell<(T, GhostToken<'brand>)>,
+}
+
+struct Slot<'brand, T> {
+    state: AtomicU8,
+    call: UnsafeCell<Option<(Call<'brand, T>, *mut ())>>,
+    panic: UnsafeCell<Option<Panic>>,
+}
+
+/// A request on the submitting thread's stack.
+struct Request<F, R> {
+    op: Option<F>,
+    result: Option<R>,
+}
+
+// SAFETY: the structure and token are only reached by the thread holding the combiner
+// lock, and slots hand requests (`F: Send`) and results (`R: Send`) between threads
+// with acquire/release on the slot state.
+unsafe impl<'brand, T: Send> Send for GhostFlatCombiner<'brand, T> {}
+unsafe impl<'brand, T: Send> Sync for GhostFlatCombiner<'brand, T> {}
+
+impl<'brand, T> GhostFlatCombiner<'brand, T> {
+    /// Creates a combiner owning `value` and the token that gates it.
+    pub fn new(value: T, token: GhostToken<'brand>) -> Self {
+        Self {
+            locked: AtomicBool::new(false),
+            slots: core::array::from_fn(|_| {
+                CachePadded::new(Slot {
+                    state: AtomicU8::new(EMPTY),
+                    call: UnsafeCell::new(None),
+                    panic: UnsafeCell::new(None),
+                })
+            }),
+            state: UnsafeCell::new((value, token)),
+        }
+    }
+
+    /// Runs `op` on the structure, possibly on another thread, and returns its result.
+    ///
+    /// # Panics
+    /// Resumes the panic if `op` panicked.
+    pub fn apply<F, R>(&self, op: F) -> R
+    where
+        F: FnOnce(&mut T, &mut GhostToken<'brand>) -> R + Send,
+        R: Send,
+    {
+        let mut request = Request {
+            op: Some(op),
+            result: None,
+        };
+        let env = core::ptr::addr_of_mut!(request).cast::<()>();
+        match self.claim_slot() {
+            Some(slot) => {
+                // SAFETY: the slot is `CLAIMED` by this thread, so no one else reads it.
+                unsafe { *slot.call.get() = Some((call::<T, F, R>, env)) };
+                slot.state.store(PENDING, Ordering::Release);
+                self.wait(slot);
+                // SAFETY: `DONE` hands the slot back to this thread.
+                let panic = unsafe { (*slot.panic.get()).take() };
+                slot.state.store(EMPTY, Ordering::Release);
+                if let Some(payload) = panic {
+                    panic::resume_unwind(payload);
+                }
+            }
+            None => {
+                self.lock();
+                let _unlock = Unlock(&self.locked);
+                // SAFETY: the lock gives this thread the structure; `env` points at a
+                // live `Request<F, R>`.
+                unsafe {
+                    let (value, token) = &mut *self.state.get();
+                    call::<T, F, R>(env, value, token);
+                }
+                self.combine();
+            }
+        }
+        request
+            .result
+            .expect("combined operation produced no result")
+    }
+
+    /// Returns a mutable reference to the structure; `&mut self` rules out operations.
+    pub fn get_mut(&mut self) -> &mut T {
+        &mut self.state.get_mut().0
+    }
+
+    /// Consumes the combiner, returning the structure and its token.
+    pub fn into_inner(self) -> (T, GhostToken<'brand>) {
+        self.state.into_inner()
+    }
+
+    /// Claims a free slot, starting at the caller's shard.
+    fn claim_slot(&self) -> Option<&Slot<'brand, T>> {
+        let start = current_shard_index();
+        (0..SHARD_COUNT)
+            .map(|i| &*self.slots[(start + i) % SHARD_COUNT])
+            .find(|slot| {
+                slot.state
+                    .compare_exchange(EMPTY, CLAIMED, Ordering::Acquire, Ordering::Relaxed)
+                    .is_ok()
+            })
+    }
+
+    /// Waits for `slot` to be done, combining whenever the lock is free.
+    fn wait(&self, slot: &Slot<'brand, T>) {
+        let mut spins = 0;
+        while slot.state.load(Ordering::Acquire) != DONE {
+            if self.try_lock() {
+                let _unlock = Unlock(&self.locked);
+                self.combine();
+                continue;
+            }
+            if spins < SPINS_BEFORE_YIELD {
+                spins += 1;
+                hint::spin_loop();
+            } else {
+                std::thread::yield_now();
+            }
+        }
+    }
+
+    /// Runs published requests until a pass finds none. The caller holds the lock.
+    fn combine(&self) {
+        // SAFETY: the caller holds the lock, so this thread owns the structure.
+        let (value, token) = unsafe { &mut *self.state.get() };
+        for _ in 0..MAX_COMBINE_PASSES {
+            let mut found = false;
+            for slot in &self.slots {
+                if slot.state.load(Ordering::Acquire) != PENDING {
+                    continue;
+                }
+                found = true;
+                // SAFETY: `PENDING` hands the slot to the lock holder until `DONE`; the
+                // request it points at lives until its owner sees `DONE`.
+                unsafe {
+                    let (run, env) = (*slot.call.get()).take().expect("pending slot");
+                    let outcome = panic::catch_unwind(AssertUnwindSafe(|| run(env, value, token)));
+                    *slot.panic.get() = outcome.err();
+                }
+                slot.state.store(DONE, Ordering::Release);
+            }
+            if !found {
+                break;
+            }
+        }
+    }
+
+    fn try_lock(&self) -> bool {
+        !self.locked.load(Ordering::Relaxed)
+            && self
+                .locked
+                .compare_exchange(false, true, Ordering::Acquire, Ordering::Relaxed)
+                .is_ok()
+    }
+
+    fn lock(&self) {
+        let mut spins = 0;
+        while !self.try_lock() {
+            if spins < SPINS_BEFORE_YIELD {
+                spins += 1;
+                hint::spin_loop();
+            } else {
+                std::thread::yield_now();
+            }
+        }
+    }
+}
+
+/// Runs the `Request<F, R>` behind `env` and stores its result.
+///
+/// # Safety
+/// `env` must point to a live `Request<F, R>` that no other thread accesses meanwhile.
+unsafe fn call<'brand, T, F, R>(env: *mut (), value: &mut T, token: &mut GhostToken<'brand>)
+where
+    F: FnOnce(&mut T, &mut GhostToken<'brand>) -> R,
+{
+    // SAFETY: guaranteed by the caller.
+    let request = unsafe { &mut *env.cast::<Request<F, R>>() };
+    let op = request.op.take().expect("request run twice");
+    request.result = Some(op(value, token));
+}
+
+/// Releases the combiner lock, also on unwind.
+struct Unlock<'a>(&'a AtomicBool);
+
+impl Drop for Unlock<'_> {
+    fn drop(&mut self) {
+        self.0.store(false, Ordering::Release);
+    }
+}
@@ -0,0 +1,92 @@
+//! `GhostLatch` — a token-gated count-down latch.
+
+use super::{wait_on_u32, wait_on_u32_timeout, wake_all_u32};
+use crate::token::traits::GhostBorrow;
+use core::marker::PhantomData;
+use core::sync::atomic::{AtomicU32, Ordering};
+use std::time::{Duration, Instant};
+
+/// A single-use latch: threads wait until the count has been brought down to zero.
+///
+/// Unlike [`GhostBarrier`](super::GhostBarrier), the threads counting down do not wait
+/// themselves, so a coordinator can wait for `n` workers to finish a phase while the
+/// workers move on. Waiting requires a token of the brand, like the barrier; counting
+/// down does not.
+pub struct GhostLatch<'brand> {
+    count: AtomicU32,
+    _phantom: PhantomData<fn(&'brand ()) -> &'brand ()>,
+}
+
+impl<'brand> GhostLatch<'brand> {
+    /// Creates a latch that opens after `count` calls to [`count_down`](Self::count_down).
+    pub const fn new(count: u32) -> Self {
+        Self {
+            count: AtomicU32::new(count),
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Decrements the count, releasing the waiters when it reaches zero.
+    ///
+    /// # Panics
+    /// Panics if the latch is already open.
+    pub fn count_down(&self) {
+        self.count_down_by(1);
+    }
+
+    /// Decrements the count by `n`, releasing the waiters when it reaches zero.
+    ///
+    /// # Panics
+    /// Panics if `n` exceeds the remaining count.
+    pub fn count_down_by(&self, n: u32) {
+        let prev = self
+            .count
+            .fetch_update(Ordering::Release, Ordering::Relaxed, |c| c.checked_sub(n))
+            .unwrap_or_else(|c| panic!("GhostLatch counted down by {n} with {c} remaining"));
+        if prev == n && n != 0 {
+            wake_all_u32(&self.count);
+        }
+    }
+
+    /// Returns the remaining count; zero once the latch is open.
+    #[inline]
+    pub fn count(&self) -> u32 {
+        self.count.load(Ordering::Acquire)
+    }
+
+    /// Returns `true` if the latch is open, without blocking.
+    #[inline]
+    pub fn try_wait(&self) -> bool {
+        self.count() == 0
+    }
+
+    /// Blocks until the latch is open.
+    ///
+    /// The `_token` argument proves that the thread possesses the necessary
+    /// capability (branded token) to participate in this synchronization scope.
+    pub fn wait(&self, _token: &impl GhostBorrow<'brand>) {
+        loop {
+            let count = self.count();
+            if count == 0 {
+                return;
+            }
+            wait_on_u32(&self.count, count);
+        }
+    }
+
+    /// Like [`wait`](Self::wait), but gives up after `timeout`; returns whether the
+    /// latch is open.
+    pub fn wait_timeout(&self, _token: &impl GhostBorrow<'brand>, timeout: Duration) -> bool {
+        let deadline = Instant::now() + timeout;
+        loop {
+            let count = self.count();
+            if count == 0 {
+                return true;
+            }
+            let Some(left) = deadline.checked_duration_since(Instant::now()) else {
+                return false;
+            };
+            wait_on_u32_timeout(&self.count, count, left);
+        }
+    }
+}
@@ -1,15 +1,55 @@
 //! `GhostOnceLock` — a thread-safe, token-branded once-lock.
+//!
+//! Built on the futex layer rather than `std::sync::OnceLock`, so that initialization
+//! may fail without poisoning or losing the error, and so that threads can park until
+//! another thread has initialized the value.
 
-use std::sync::OnceLock;
+use super::{wait_on_u32, wake_all_u32};
 use crate::token::traits::{GhostBorrow, GhostBorrowMut};
-use crate::cell::raw::GhostUnsafeCell;
+use core::cell::UnsafeCell;
+use core::convert::Infallible;
+use core::marker::PhantomData;
+use core::mem::MaybeUninit;
+use core::sync::atomic::{AtomicU32, Ordering};
+
+const INCOMPLETE: u32 = 0;
+const RUNNING: u32 = 1;
+const COMPLETE: u32 = 2;
+const STATE_MASK: u32 = 3;
+/// Set while threads are parked waiting for the state to change.
+const PARKED: u32 = 4;
 
 /// A thread-safe initialization primitive that requires a ghost token for access.
 ///
 /// `GhostOnceLock` mirrors `std::sync::OnceLock` but ensures that the value
 /// can only be accessed by threads possessing the correct `GhostToken` (or a compatible guard).
+///
+/// At most one initializer runs at a time; other threads calling an initializing method
+/// park until it finishes. If it fails or panics, the lock stays uninitialized and the
+/// next caller runs its own initializer.
 pub struct GhostOnceLock<'brand, T> {
-    inner: GhostUnsafeCell<'brand, OnceLock<T>>,
+    state: AtomicU32,
+    value: UnsafeCell<MaybeUninit<T>>,
+    _brand: PhantomData<fn(&'brand ()) -> &'brand ()>,
+}
+
+// SAFETY: same bounds as `std::sync::OnceLock`: the value is shared by reference across
+// threads (`Sync`) and may be written by a thread other than the one dropping it (`Send`).
+unsafe impl<'brand, T: Send + Sync> Sync for GhostOnceLock<'brand, T> {}
+unsafe impl<'brand, T: Send> Send for GhostOnceLock<'brand, T> {}
+
+/// Publishes the outcome of an initializer, also when it unwinds.
+struct Finish<'a> {
+    state: &'a AtomicU32,
+    to: u32,
+}
+
+impl Drop for Finish<'_> {
+    fn drop(&mut self) {
+        if self.state.swap(self.to, Ordering::AcqRel) & PARKED != 0 {
+            wake_all_u32(self.state);
+        }
+    }
 }
 
 impl<'brand, T> GhostOnceLock<'brand, T> {
@@ -17,26 +57,49 @@ impl<'brand, T> GhostOnceLock<'brand, T> {
     #[inline]
     pub const fn new() -> Self {
         Self {
-            inner: GhostUnsafeCell::new(OnceLock::new()),
+            state: AtomicU32::new(INCOMPLETE),
+            value: UnsafeCell::new(MaybeUninit::uninit()),
+            _brand: PhantomData,
         }
     }
 
+    #[inline]
+    fn is_complete(&self) -> bool {
+        self.state.load(Ordering::Acquire) & STATE_MASK == COMPLETE
+    }
+
+    /// # Safety
+    /// The lock must be initialized.
+    #[inline]
+    unsafe fn get_unchecked(&self) -> &T {
+        // SAFETY: the value was written before `COMPLETE` was published, and is only
+        // mutated again through `&mut self` or a mutable token.
+        unsafe { (*self.value.get()).assume_init_ref() }
+    }
+
     /// Returns `true` if the lock has been initialized.
     #[inline]
-    pub fn is_initialized(&self, token: &impl GhostBorrow<'brand>) -> bool {
-        self.inner.get(token).get().is_some()
+    pub fn is_initialized(&self, _token: &impl GhostBorrow<'brand>) -> bool {
+        self.is_complete()
     }
 
     /// Gets a reference to the value if initialized, requiring a token.
     #[inline]
-    pub fn get<'a>(&'a self, token: &'a impl GhostBorrow<'brand>) -> Option<&'a T> {
-        self.inner.get(token).get()
+    pub fn get<'a>(&'a self, _token: &'a impl GhostBorrow<'brand>) -> Option<&'a T> {
+        // SAFETY: checked just before.
+        self.is_complete().then(|| unsafe { self.get_unchecked() })
     }
 
     /// Gets a mutable reference to the value if initialized, requiring a mutable token.
     #[inline]
-    pub fn get_mut_branded<'a>(&'a self, token: &'a mut impl GhostBorrowMut<'brand>) -> Option<&'a mut T> {
-        self.inner.get_mut(token).get_mut()
+    pub fn get_mut_branded<'a>(
+        &'a self,
+        _token: &'a mut impl GhostBorrowMut<'brand>,
+    ) -> Option<&'a mut T> {
+        // SAFETY: the mutable token excludes every other borrow of the brand, so no
+        // reference to the value or initializer can be live.
+        self.is_complete()
+            .then(|| unsafe { (*self.value.get()).assume_init_mut() })
     }
 
     /// Gets a mutable reference to the value if initialized, without requiring a token.
@@ -44,15 +107,20 @@ impl<'brand, T> GhostOnceLock<'brand, T> {
     /// This is safe because `&mut self` guarantees exclusive access.
     #[inline]
     pub fn get_mut(&mut self) -> Option<&mut T> {
-        self.inner.get_mut_exclusive().get_mut()
+        // SAFETY: checked just before.
+        (*self.state.get_mut() & STATE_MASK == COMPLETE)
+            .then(|| unsafe { self.value.get_mut().assume_init_mut() })
     }
 
     /// Sets the value if uninitialized, requiring a token.
     ///
     /// Returns `Ok(())` if the value was set, or `Err(value)` if it was already set.
+    /// Waits if another thread is initializing the lock.
     #[inline]
     pub fn set(&self, token: &impl GhostBorrow<'brand>, value: T) -> Result<(), T> {
-        self.inner.get(token).set(value)
+        let mut value = Some(value);
+        self.get_or_init(token, || value.take().expect("initializer runs once"));
+        value.map_or(Ok(()), Err)
     }
 
     /// Gets the value, initializing it with `f` if needed, requiring a token.
@@ -61,13 +129,101 @@ impl<'brand, T> GhostOnceLock<'brand, T> {
     where
         F: FnOnce() -> T,
     {
-        self.inner.get(token).get_or_init(f)
+        let Ok(value) = self.get_or_try_init(token, || Ok::<T, Infallible>(f()));
+        value
+    }
+
+    /// Gets the value, initializing it with `f` if needed, requiring a token.
+    ///
+    /// Calling an initializing method of the same lock from `f` deadlocks.
+    ///
+    /// # Errors
+    /// If `f` fails, its error is returned and the lock stays uninitialized; threads
+    /// parked on the lock meanwhile wake up and retry with their own initializers.
+    #[inline]
+    pub fn get_or_try_init<'a, F, E>(
+        &'a self,
+        _token: &'a impl GhostBorrow<'brand>,
+        f: F,
+    ) -> Result<&'a T, E>
+    where
+        F: FnOnce() -> Result<T, E>,
+    {
+        if self.is_complete() {
+            // SAFETY: checked just before.
+            return Ok(unsafe { self.get_unchecked() });
+        }
+        self.initialize(f)
+    }
+
+    #[cold]
+    fn initialize<E>(&self, f: impl FnOnce() -> Result<T, E>) -> Result<&T, E> {
+        let mut state = self.state.load(Ordering::Acquire);
+        loop {
+            match state & STATE_MASK {
+                // SAFETY: the state says so.
+                COMPLETE => return Ok(unsafe { self.get_unchecked() }),
+                INCOMPLETE => {
+                    if let Err(actual) = self.state.compare_exchange_weak(
+                        state,
+                        state | RUNNING,
+                        Ordering::Acquire,
+                        Ordering::Acquire,
+                    ) {
+                        state = actual;
+                        continue;
+                    }
+                    let mut finish = Finish {
+                        state: &self.state,
+                        to: INCOMPLETE,
+                    };
+                    let value = f()?;
+                    // SAFETY: `RUNNING` gives this thread exclusive access to the slot.
+                    unsafe { (*self.value.get()).write(value) };
+                    finish.to = COMPLETE;
+                    drop(finish);
+                    // SAFETY: just initialized.
+                    return Ok(unsafe { self.get_unchecked() });
+                }
+                _ => state = self.park(state),
+            }
+        }
+    }
+
+    /// Parks until the state changes from `state`, returning the new one.
+    fn park(&self, state: u32) -> u32 {
+        if state & PARKED == 0 {
+            if let Err(actual) = self.state.compare_exchange_weak(
+                state,
+                state | PARKED,
+                Ordering::Relaxed,
+                Ordering::Acquire,
+            ) {
+                return actual;
+            }
+        }
+        wait_on_u32(&self.state, state | PARKED);
+        self.state.load(Ordering::Acquire)
+    }
+
+    /// Blocks until the lock is initialized, by another thread if need be, and returns
+    /// the value.
+    ///
+    /// Failed initializers do not wake the caller for good; it keeps waiting for one
+    /// that succeeds.
+    pub fn wait<'a>(&'a self, _token: &'a impl GhostBorrow<'brand>) -> &'a T {
+        let mut state = self.state.load(Ordering::Acquire);
+        while state & STATE_MASK != COMPLETE {
+            state = self.park(state);
+        }
+        // SAFETY: the state says so.
+        unsafe { self.get_unchecked() }
     }
 
     /// Consumes the lock, returning the initialized value if it exists.
     #[inline]
-    pub fn into_inner(self) -> Option<T> {
-        self.inner.into_inner().into_inner()
+    pub fn into_inner(mut self) -> Option<T> {
+        self.take()
     }
 
     /// Takes the value out of the lock, leaving it uninitialized, without requiring a token.
@@ -75,7 +231,13 @@ impl<'brand, T> GhostOnceLock<'brand, T> {
     /// This is safe because `&mut self` guarantees exclusive access.
     #[inline]
     pub fn take(&mut self) -> Option<T> {
-        self.inner.get_mut_exclusive().take()
+        let state = self.state.get_mut();
+        if *state & STATE_MASK != COMPLETE {
+            return None;
+        }
+        *state = INCOMPLETE;
+        // SAFETY: it was initialized, and is no longer marked as such.
+        Some(unsafe { self.value.get_mut().assume_init_read() })
     }
 }
 
@@ -85,6 +247,8 @@ impl<'brand, T> Default for GhostOnceLock<'brand, T> {
     }
 }
 
-// SAFETY: GhostOnceLock is Sync/Send if OnceLock<T> is Sync/Send.
-// This is automatically handled by the compiler as GhostUnsafeCell is Sync/Send
-// with appropriate bounds.
+impl<'brand, T> Drop for GhostOnceLock<'brand, T> {
+    fn drop(&mut self) {
+        drop(self.take());
+    }
+}
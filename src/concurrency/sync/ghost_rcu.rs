@@ -0,0 +1,157 @@
+//! `GhostRcuCell` — a read-mostly cell with grace-period reclamation.
+//!
+//! Readers register in one of two reader epochs and get a [`GhostRcuReadGuard`] that
+//! dereferences to the value. Registration is one increment of a per-thread, cache-padded
+//! counter, so readers on different threads do not contend. Writers install a new value
+//! with a pointer swap, flip the epoch so new readers register on the other side, and
+//! wait for the readers of the old epoch to leave (the grace period) before dropping
+//! the old value.
+//!
+//! Reclamation happens on the writer's thread before `store` returns, so `T` may
+//! borrow data (including branded state) and nothing outlives the cell. The cost is
+//! that writers block while old readers hold their guards; a thread must not write to
+//! a cell while it holds one of that cell's read guards.
+//!
+//! This suits routing tables, configuration and similar state that many threads read
+//! on the hot path and that changes rarely, as a whole.
+
+use core::marker::PhantomData;
+use core::ops::Deref;
+use core::ptr;
+use core::sync::atomic::{AtomicPtr, Ordering};
+use std::sync::{Mutex, MutexGuard, PoisonError};
+
+use super::grace::{GracePeriod, ReadSection};
+
+/// A cell whose value is replaced wholesale and read without locks.
+///
+/// The reader counters take two cache lines per shard, so the cell is a few KiB; it is
+/// meant for a handful of hot shared values, not for every element of a collection.
+pub struct GhostRcuCell<'brand, T> {
+    current: AtomicPtr<T>,
+    grace: GracePeriod,
+    /// Serializes writers, so grace periods do not overlap.
+    writer: Mutex<()>,
+    _marker: PhantomData<(Box<T>, &'brand mut ())>,
+}
+
+/// A snapshot of a [`GhostRcuCell`]'s value.
+///
+/// The value stays alive while the guard exists, even if a writer replaces it; that
+/// writer waits until the guard is dropped.
+pub struct GhostRcuReadGuard<'a, T> {
+    value: &'a T,
+    _section: ReadSection<'a>,
+}
+
+impl<'brand, T> GhostRcuCell<'brand, T> {
+    /// Creates a cell holding `value`.
+    pub fn new(value: T) -> Self {
+        Self {
+            current: AtomicPtr::new(Box::into_raw(Box::new(value))),
+            grace: GracePeriod::new(),
+            writer: Mutex::new(()),
+            _marker: PhantomData,
+        }
+    }
+
+    /// Returns a snapshot of the current value.
+    pub fn load(&self) -> GhostRcuReadGuard<'_, T> {
+        let section = self.grace.read();
+        // SAFETY: the pointer is always a live `Box`, and the read section keeps any
+        // writer that replaces it from dropping it before the guard is gone.
+        let value = unsafe { &*self.current.load(Ordering::SeqCst) };
+        GhostRcuReadGuard {
+            value,
+            _section: section,
+        }
+    }
+
+    /// Returns a clone of the current value.
+    pub fn load_cloned(&self) -> T
+    where
+        T: Clone,
+    {
+        self.load().clone()
+    }
+
+    /// Installs `value` and drops the previous one once no reader can see it.
+    ///
+    /// Blocks until readers holding snapshots of the previous value release them.
+    pub fn store(&self, value: T) {
+        let _writer = self.lock_writer();
+        self.replace_locked(value);
+    }
+
+    /// Replaces the value with `f(&current)` and returns `f`'s second output.
+    ///
+    /// Writers are serialized, so `f` sees the latest value and runs exactly once;
+    /// readers keep seeing the old value until the new one is installed.
+    pub fn update<R>(&self, f: impl FnOnce(&T) -> (T, R)) -> R {
+        let _writer = self.lock_writer();
+        // SAFETY: only writers replace the pointer, and this one holds the writer lock.
+        let current = unsafe { &*self.current.load(Ordering::Acquire) };
+        let (next, result) = f(current);
+        self.replace_locked(next);
+        result
+    }
+
+    /// Writers hold no invariant across a panic, so a poisoned lock is still usable.
+    fn lock_writer(&self) -> MutexGuard<'_, ()> {
+        self.writer.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Swaps in `value`, waits out the grace period and drops the old value.
+    fn replace_locked(&self, value: T) {
+        let new = Box::into_raw(Box::new(value));
+        let old = self.current.swap(new, Ordering::SeqCst);
+        // Readers registered before this may hold `old`; later ones only see `new`.
+        self.grace.synchronize();
+        // SAFETY: `old` came from `Box::into_raw`, is unreachable from the cell, and no
+        // reader that could see it is left.
+        drop(unsafe { Box::from_raw(old) });
+    }
+
+    /// Returns a mutable reference to the value; `&mut self` rules out readers.
+    pub fn get_mut(&mut self) -> &mut T {
+        // SAFETY: the pointer is a live `Box` and `&mut self` makes access exclusive.
+        unsafe { &mut *self.current.load(Ordering::Relaxed) }
+    }
+
+    /// Consumes the cell, returning the value.
+    pub fn into_inner(self) -> T {
+        let ptr = self.current.swap(ptr::null_mut(), Ordering::Relaxed);
+        // SAFETY: the pointer was a live `Box`; `Drop` skips the null left behind.
+        *unsafe { Box::from_raw(ptr) }
+    }
+}
+
+impl<'brand, T: Default> Default for GhostRcuCell<'brand, T> {
+    fn default() -> Self {
+        Self::new(T::default())
+    }
+}
+
+impl<'brand, T> Drop for GhostRcuCell<'brand, T> {
+    fn drop(&mut self) {
+        let ptr = *self.current.get_mut();
+        if !ptr.is_null() {
+            // SAFETY: `&mut self` means no read guard is alive, and old values were
+            // already dropped by their writers.
+            drop(unsafe { Box::from_raw(ptr) });
+        }
+    }
+}
+
+// SAFETY: readers share `&T` across threads (`T: Sync`), and the value is dropped by
+// whichever thread replaces it (`T: Send`).
+unsafe impl<'brand, T: Send + Sync> Send for GhostRcuCell<'brand, T> {}
+unsafe impl<'brand, T: Send + Sync> Sync for GhostRcuCell<'brand, T> {}
+
+impl<T> Deref for GhostRcuReadGuard<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        self.value
+    }
+}
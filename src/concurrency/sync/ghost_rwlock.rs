@@ -0,0 +1,356 @@
+//! `GhostRwLock` — a reader-writer lock that guards a `GhostToken`.
+//!
+//! Readers share `&GhostToken`, so any number of threads can read the branded cells at
+//! once; a writer gets `&mut GhostToken` to mutate or rebuild them. An upgradable read
+//! coexists with plain readers but excludes writers and other upgradable reads, so it
+//! can later be turned into a write lock without letting another writer in between.
+//!
+//! The whole lock is one `AtomicU32` and blocks with the futex-style helpers of this
+//! module.
+
+use super::{wait_on_u32, wake_all_u32};
+use crate::token::traits::{GhostBorrow, GhostBorrowMut};
+use crate::token::GhostToken;
+use core::cell::UnsafeCell;
+use core::mem;
+use core::ops::{Deref, DerefMut};
+use core::sync::atomic::{AtomicU32, Ordering};
+
+/// A writer holds the lock.
+const WRITER: u32 = 1 << 31;
+/// An upgradable reader holds the lock.
+const UPGRADABLE: u32 = 1 << 30;
+/// A writer or upgrading reader is parked.
+const WRITERS_PARKED: u32 = 1 << 29;
+/// A reader or would-be upgradable reader is parked.
+const READERS_PARKED: u32 = 1 << 28;
+/// One reader.
+const READER: u32 = 1;
+/// Mask of the reader count.
+const READERS_MASK: u32 = READERS_PARKED - 1;
+
+/// Which side a `GhostRwLock` favours when readers and writers contend.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum RwLockPolicy {
+    /// New readers wait while a writer is waiting, so writers cannot starve. The default.
+    #[default]
+    WriterPreferred,
+    /// New readers enter whenever no writer holds the lock. Maximizes read throughput,
+    /// but a steady stream of readers can starve writers.
+    ReaderPreferred,
+}
+
+/// A reader-writer lock that protects a `GhostToken`.
+///
+/// The read-mostly counterpart of [`GhostMutex`](super::GhostMutex): share a branded
+/// structure between threads, read it concurrently through [`read`](Self::read), and
+/// take [`write`](Self::write) on the occasions it must be rebuilt.
+pub struct GhostRwLock<'brand> {
+    token: UnsafeCell<GhostToken<'brand>>,
+    /// Reader count and flag bits; see the constants above.
+    state: AtomicU32,
+    policy: RwLockPolicy,
+}
+
+unsafe impl<'brand> Sync for GhostRwLock<'brand> {}
+unsafe impl<'brand> Send for GhostRwLock<'brand> {}
+
+impl<'brand> GhostRwLock<'brand> {
+    /// Creates a writer-preferring lock wrapping the given token.
+    pub const fn new(token: GhostToken<'brand>) -> Self {
+        Self::with_policy(token, RwLockPolicy::WriterPreferred)
+    }
+
+    /// Creates a lock wrapping the given token with the given fairness policy.
+    pub const fn with_policy(token: GhostToken<'brand>, policy: RwLockPolicy) -> Self {
+        Self {
+            token: UnsafeCell::new(token),
+            state: AtomicU32::new(0),
+            policy,
+        }
+    }
+
+    /// Returns the fairness policy.
+    pub const fn policy(&self) -> RwLockPolicy {
+        self.policy
+    }
+
+    /// Acquires shared read access, blocking while a writer holds the lock (or, under
+    /// [`RwLockPolicy::WriterPreferred`], while one is waiting).
+    pub fn read(&self) -> GhostRwLockReadGuard<'_, 'brand> {
+        loop {
+            if let Some(guard) = self.try_read() {
+                return guard;
+            }
+            self.park_reader(|s| self.read_blocked(s));
+        }
+    }
+
+    /// Attempts to acquire shared read access without blocking.
+    ///
+    /// # Panics
+    /// Panics if the reader count would overflow (2^28 - 1 concurrent readers).
+    pub fn try_read(&self) -> Option<GhostRwLockReadGuard<'_, 'brand>> {
+        let mut state = self.state.load(Ordering::Relaxed);
+        while !self.read_blocked(state) {
+            assert!(state & READERS_MASK != READERS_MASK, "too many GhostRwLock readers");
+            match self.state.compare_exchange_weak(
+                state,
+                state + READER,
+                Ordering::Acquire,
+                Ordering::Relaxed,
+            ) {
+                Ok(_) => return Some(GhostRwLockReadGuard { lock: self }),
+                Err(s) => state = s,
+            }
+        }
+        None
+    }
+
+    /// Acquires an upgradable read, blocking while a writer or another upgradable
+    /// reader holds the lock.
+    pub fn upgradable_read(&self) -> GhostRwLockUpgradableGuard<'_, 'brand> {
+        loop {
+            if let Some(guard) = self.try_upgradable_read() {
+                return guard;
+            }
+            self.park_reader(|s| self.upgradable_blocked(s));
+        }
+    }
+
+    /// Attempts to acquire an upgradable read without blocking.
+    pub fn try_upgradable_read(&self) -> Option<GhostRwLockUpgradableGuard<'_, 'brand>> {
+        let mut state = self.state.load(Ordering::Relaxed);
+        while !self.upgradable_blocked(state) {
+            match self.state.compare_exchange_weak(
+                state,
+                state | UPGRADABLE,
+                Ordering::Acquire,
+                Ordering::Relaxed,
+            ) {
+                Ok(_) => return Some(GhostRwLockUpgradableGuard { lock: self }),
+                Err(s) => state = s,
+            }
+        }
+        None
+    }
+
+    /// Acquires exclusive write access, blocking until every other guard is dropped.
+    pub fn write(&self) -> GhostRwLockWriteGuard<'_, 'brand> {
+        loop {
+            if let Some(guard) = self.try_write() {
+                return guard;
+            }
+            self.park_writer(|s| s & (WRITER | UPGRADABLE | READERS_MASK) != 0);
+        }
+    }
+
+    /// Attempts to acquire exclusive write access without blocking.
+    pub fn try_write(&self) -> Option<GhostRwLockWriteGuard<'_, 'brand>> {
+        let mut state = self.state.load(Ordering::Relaxed);
+        while state & (WRITER | UPGRADABLE | READERS_MASK) == 0 {
+            // Parked bits are kept so the unlock wakes their owners.
+            match self.state.compare_exchange_weak(
+                state,
+                state | WRITER,
+                Ordering::Acquire,
+                Ordering::Relaxed,
+            ) {
+                Ok(_) => return Some(GhostRwLockWriteGuard { lock: self }),
+                Err(s) => state = s,
+            }
+        }
+        None
+    }
+
+    #[inline]
+    fn read_blocked(&self, state: u32) -> bool {
+        state & WRITER != 0
+            || (self.policy == RwLockPolicy::WriterPreferred && state & WRITERS_PARKED != 0)
+    }
+
+    #[inline]
+    fn upgradable_blocked(&self, state: u32) -> bool {
+        state & UPGRADABLE != 0 || self.read_blocked(state)
+    }
+
+    /// Sets `READERS_PARKED` and sleeps while `blocked` holds.
+    #[cold]
+    fn park_reader(&self, blocked: impl Fn(u32) -> bool) {
+        self.park(READERS_PARKED, blocked);
+    }
+
+    /// Sets `WRITERS_PARKED` and sleeps while `blocked` holds.
+    #[cold]
+    fn park_writer(&self, blocked: impl Fn(u32) -> bool) {
+        self.park(WRITERS_PARKED, blocked);
+    }
+
+    #[inline]
+    fn park(&self, parked_bit: u32, blocked: impl Fn(u32) -> bool) {
+        let mut state = self.state.load(Ordering::Relaxed);
+        while blocked(state) {
+            if state & parked_bit == 0 {
+                match self.state.compare_exchange_weak(
+                    state,
+                    state | parked_bit,
+                    Ordering::Relaxed,
+                    Ordering::Relaxed,
+                ) {
+                    Ok(_) => state |= parked_bit,
+                    Err(s) => {
+                        state = s;
+                        continue;
+                    }
+                }
+            }
+            wait_on_u32(&self.state, state);
+            state = self.state.load(Ordering::Relaxed);
+        }
+    }
+
+    fn unlock_read(&self) {
+        let prev = self.state.fetch_sub(READER, Ordering::Release);
+        // The last reader out lets a parked writer or upgrader in.
+        if prev & READERS_MASK == READER && prev & WRITERS_PARKED != 0 {
+            wake_all_u32(&self.state);
+        }
+    }
+
+    fn unlock_upgradable(&self) {
+        let prev = self
+            .state
+            .fetch_and(!(UPGRADABLE | READERS_PARKED), Ordering::Release);
+        if prev & (READERS_PARKED | WRITERS_PARKED) != 0 {
+            wake_all_u32(&self.state);
+        }
+    }
+
+    fn unlock_write(&self) {
+        // Only parked bits can change while a writer holds the lock, and the woken
+        // threads set them again if they have to keep waiting.
+        if self.state.swap(0, Ordering::Release) & (READERS_PARKED | WRITERS_PARKED) != 0 {
+            wake_all_u32(&self.state);
+        }
+    }
+}
+
+/// A guard that provides shared access to the `GhostToken` protected by a `GhostRwLock`.
+pub struct GhostRwLockReadGuard<'a, 'brand> {
+    lock: &'a GhostRwLock<'brand>,
+}
+
+impl<'a, 'brand> Deref for GhostRwLockReadGuard<'a, 'brand> {
+    type Target = GhostToken<'brand>;
+
+    fn deref(&self) -> &Self::Target {
+        // SAFETY: no writer holds the lock while a read guard exists.
+        unsafe { &*self.lock.token.get() }
+    }
+}
+
+impl<'a, 'brand> Drop for GhostRwLockReadGuard<'a, 'brand> {
+    fn drop(&mut self) {
+        self.lock.unlock_read();
+    }
+}
+
+impl<'a, 'brand> GhostBorrow<'brand> for GhostRwLockReadGuard<'a, 'brand> {}
+
+/// A shared guard that can be upgraded to exclusive access without another writer
+/// getting in first.
+pub struct GhostRwLockUpgradableGuard<'a, 'brand> {
+    lock: &'a GhostRwLock<'brand>,
+}
+
+impl<'a, 'brand> GhostRwLockUpgradableGuard<'a, 'brand> {
+    /// Waits for the remaining readers to leave and converts to a write guard.
+    pub fn upgrade(self) -> GhostRwLockWriteGuard<'a, 'brand> {
+        let lock = self.lock;
+        mem::forget(self);
+        loop {
+            if Self::try_upgrade_raw(lock) {
+                return GhostRwLockWriteGuard { lock };
+            }
+            lock.park_writer(|s| s & READERS_MASK != 0);
+        }
+    }
+
+    /// Converts to a write guard if no plain readers remain.
+    ///
+    /// # Errors
+    /// Returns the guard unchanged if readers are still present.
+    pub fn try_upgrade(self) -> Result<GhostRwLockWriteGuard<'a, 'brand>, Self> {
+        if Self::try_upgrade_raw(self.lock) {
+            let lock = self.lock;
+            mem::forget(self);
+            Ok(GhostRwLockWriteGuard { lock })
+        } else {
+            Err(self)
+        }
+    }
+
+    fn try_upgrade_raw(lock: &GhostRwLock<'brand>) -> bool {
+        let mut state = lock.state.load(Ordering::Relaxed);
+        while state & READERS_MASK == 0 {
+            match lock.state.compare_exchange_weak(
+                state,
+                (state & !UPGRADABLE) | WRITER,
+                Ordering::Acquire,
+                Ordering::Relaxed,
+            ) {
+                Ok(_) => return true,
+                Err(s) => state = s,
+            }
+        }
+        false
+    }
+}
+
+impl<'a, 'brand> Deref for GhostRwLockUpgradableGuard<'a, 'brand> {
+    type Target = GhostToken<'brand>;
+
+    fn deref(&self) -> &Self::Target {
+        // SAFETY: no writer holds the lock while an upgradable guard exists.
+        unsafe { &*self.lock.token.get() }
+    }
+}
+
+impl<'a, 'brand> Drop for GhostRwLockUpgradableGuard<'a, 'brand> {
+    fn drop(&mut self) {
+        self.lock.unlock_upgradable();
+    }
+}
+
+impl<'a, 'brand> GhostBorrow<'brand> for GhostRwLockUpgradableGuard<'a, 'brand> {}
+
+/// A guard that provides exclusive access to the `GhostToken` protected by a
+/// `GhostRwLock`.
+pub struct GhostRwLockWriteGuard<'a, 'brand> {
+    lock: &'a GhostRwLock<'brand>,
+}
+
+impl<'a, 'brand> Deref for GhostRwLockWriteGuard<'a, 'brand> {
+    type Target = GhostToken<'brand>;
+
+    fn deref(&self) -> &Self::Target {
+        // SAFETY: we hold the lock exclusively.
+        unsafe { &*self.lock.token.get() }
+    }
+}
+
+impl<'a, 'brand> DerefMut for GhostRwLockWriteGuard<'a, 'brand> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        // SAFETY: we hold the lock exclusively.
+        unsafe { &mut *self.lock.token.get() }
+    }
+}
+
+impl<'a, 'brand> Drop for GhostRwLockWriteGuard<'a, 'brand> {
+    fn drop(&mut self) {
+        self.lock.unlock_write();
+    }
+}
+
+impl<'a, 'brand> GhostBorrow<'brand> for GhostRwLockWriteGuard<'a, 'brand> {}
+impl<'a, 'brand> GhostBorrowMut<'brand> for GhostRwLockWriteGuard<'a, 'brand> {}
@@ -0,0 +1,144 @@
+//! `GhostSemaphore` — a token-gated counting semaphore.
+
+use super::{wait_on_u32, wake_all_u32};
+use crate::token::traits::GhostBorrow;
+use core::marker::PhantomData;
+use core::sync::atomic::{AtomicU32, Ordering};
+
+/// A counting semaphore: acquiring takes permits from a pool, parking until enough
+/// are available, and releasing returns them.
+///
+/// Use it to bound how many threads work at once, e.g. on a branded resource that
+/// tolerates only a few concurrent users. Acquiring requires a token of the brand, like
+/// [`GhostLatch::wait`](super::GhostLatch::wait); releasing does not.
+pub struct GhostSemaphore<'brand> {
+    permits: AtomicU32,
+    /// Number of threads parked (or about to park) on `permits`.
+    waiters: AtomicU32,
+    _phantom: PhantomData<fn(&'brand ()) -> &'brand ()>,
+}
+
+/// Permits held from a [`GhostSemaphore`], returned to it on drop.
+#[must_use = "the permits are released as soon as the guard is dropped"]
+pub struct GhostSemaphorePermit<'a, 'brand> {
+    semaphore: &'a GhostSemaphore<'brand>,
+    count: u32,
+}
+
+impl<'brand> GhostSemaphore<'brand> {
+    /// Creates a semaphore holding `permits` permits.
+    pub const fn new(permits: u32) -> Self {
+        Self {
+            permits: AtomicU32::new(permits),
+            waiters: AtomicU32::new(0),
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Returns the number of permits currently available.
+    #[inline]
+    pub fn available_permits(&self) -> u32 {
+        self.permits.load(Ordering::Acquire)
+    }
+
+    /// Takes one permit, blocking until one is available.
+    pub fn acquire<'a>(
+        &'a self,
+        token: &impl GhostBorrow<'brand>,
+    ) -> GhostSemaphorePermit<'a, 'brand> {
+        self.acquire_many(token, 1)
+    }
+
+    /// Takes `n` permits at once, blocking until that many are available.
+    ///
+    /// The permits are taken together, never piecemeal, so two callers asking for
+    /// several permits cannot deadlock by each holding part of what they need. A
+    /// request for many permits may wait while smaller ones keep succeeding.
+    pub fn acquire_many<'a>(
+        &'a self,
+        _token: &impl GhostBorrow<'brand>,
+        n: u32,
+    ) -> GhostSemaphorePermit<'a, 'brand> {
+        loop {
+            if self.take(n).is_ok() {
+                return GhostSemaphorePermit {
+                    semaphore: self,
+                    count: n,
+                };
+            }
+            // Announce the wait before sleeping; `release` checks `waiters` after
+            // adding permits, so one of the two sides sees the other.
+            self.waiters.fetch_add(1, Ordering::SeqCst);
+            let available = self.permits.load(Ordering::SeqCst);
+            if available < n {
+                wait_on_u32(&self.permits, available);
+            }
+            self.waiters.fetch_sub(1, Ordering::Relaxed);
+        }
+    }
+
+    /// Takes one permit if one is available, without blocking.
+    pub fn try_acquire<'a>(
+        &'a self,
+        token: &impl GhostBorrow<'brand>,
+    ) -> Option<GhostSemaphorePermit<'a, 'brand>> {
+        self.try_acquire_many(token, 1)
+    }
+
+    /// Takes `n` permits if that many are available, without blocking.
+    pub fn try_acquire_many<'a>(
+        &'a self,
+        _token: &impl GhostBorrow<'brand>,
+        n: u32,
+    ) -> Option<GhostSemaphorePermit<'a, 'brand>> {
+        self.take(n).ok().map(|()| GhostSemaphorePermit {
+            semaphore: self,
+            count: n,
+        })
+    }
+
+    /// Takes `n` permits, or returns how many are available.
+    fn take(&self, n: u32) -> Result<(), u32> {
+        self.permits
+            .fetch_update(Ordering::Acquire, Ordering::Relaxed, |p| p.checked_sub(n))
+            .map(|_| ())
+    }
+
+    /// Adds `n` permits, waking threads waiting for them.
+    ///
+    /// This is how permits whose guard was [forgotten](GhostSemaphorePermit::forget)
+    /// come back, and how the pool grows beyond its initial size.
+    ///
+    /// # Panics
+    /// Panics if the number of permits would overflow a `u32`.
+    pub fn release(&self, n: u32) {
+        self.permits
+            .fetch_update(Ordering::SeqCst, Ordering::Relaxed, |p| p.checked_add(n))
+            .unwrap_or_else(|p| panic!("GhostSemaphore released {n} permits with {p} available"));
+        // Waiters may ask for different amounts, so wake them all to let each one
+        // check whether its request now fits.
+        if n != 0 && self.waiters.load(Ordering::SeqCst) != 0 {
+            wake_all_u32(&self.permits);
+        }
+    }
+}
+
+impl GhostSemaphorePermit<'_, '_> {
+    /// Returns the number of permits held.
+    #[inline]
+    pub fn count(&self) -> u32 {
+        self.count
+    }
+
+    /// Drops the guard without returning its permits, which leaves the semaphore with
+    /// fewer permits until they are added back with [`GhostSemaphore::release`].
+    pub fn forget(self) {
+        core::mem::forget(self);
+    }
+}
+
+impl Drop for GhostSemaphorePermit<'_, '_> {
+    fn drop(&mut self) {
+        self.semaphore.release(self.count);
+    }
+}
@@ -0,0 +1,165 @@
+//! `GhostSeqLock` — a sequence lock for small, frequently read `Copy` state.
+//!
+//! Writers make the sequence odd, update the value and make it even again. Readers
+//! never write shared memory: they copy the value between two loads of the sequence
+//! and retry if it was odd or changed. Reads therefore never block the writer and
+//! scale with the number of readers, at the cost of retries while a write is running.
+//!
+//! The copy may race with a write, so it is taken as `MaybeUninit<T>` with a volatile
+//! read and only turned into a `T` once the sequence check has confirmed it was not
+//! torn. This is the usual seqlock compromise (crossbeam's `AtomicCell` fallback does
+//! the same); the Rust memory model has no race-tolerant `memcpy` yet.
+
+use crate::cell::raw::GhostUnsafeCell;
+use core::hint;
+use core::mem::MaybeUninit;
+use core::ptr;
+use core::sync::atomic::{fence, AtomicU32, Ordering};
+
+/// Spins before a waiting writer starts yielding its time slice.
+const SPINS_BEFORE_YIELD: u32 = 64;
+
+/// A sequence lock holding a `T: Copy`.
+///
+/// Suits configuration snapshots and similar state that is read far more often than
+/// it is written. Writers exclude each other with a short spin; readers never block.
+pub struct GhostSeqLock<'brand, T: Copy> {
+    seq: AtomicU32,
+    value: GhostUnsafeCell<'brand, T>,
+}
+
+// SAFETY: the value is only written by the single writer holding the odd sequence,
+// and readers only keep copies that the sequence check proved consistent.
+unsafe impl<'brand, T: Copy + Send> Sync for GhostSeqLock<'brand, T> {}
+
+impl<'brand, T: Copy> GhostSeqLock<'brand, T> {
+    /// Creates a sequence lock holding `value`.
+    pub const fn new(value: T) -> Self {
+        Self {
+            seq: AtomicU32::new(0),
+            value: GhostUnsafeCell::new(value),
+        }
+    }
+
+    /// Returns a consistent copy of the value, retrying while writes interfere.
+    pub fn read(&self) -> T {
+        loop {
+            if let Some(value) = self.try_read() {
+                return value;
+            }
+            hint::spin_loop();
+        }
+    }
+
+    /// Makes one attempt to copy the value, returning `None` if a write was running
+    /// or completed during the copy.
+    pub fn try_read(&self) -> Option<T> {
+        let before = self.seq.load(Ordering::Acquire);
+        if before & 1 != 0 {
+            return None;
+        }
+        // SAFETY: the pointer is valid; the copy is only assumed initialized below, once
+        // the unchanged sequence shows no write overlapped it.
+        let copy = unsafe {
+            ptr::read_volatile(
+                self.value
+                    .as_mut_ptr_unchecked()
+                    .cast::<MaybeUninit<T>>()
+                    .cast_const(),
+            )
+        };
+        fence(Ordering::Acquire);
+        let after = self.seq.load(Ordering::Relaxed);
+        // SAFETY: an even sequence that did not move brackets a complete value.
+        (before == after).then(|| unsafe { copy.assume_init() })
+    }
+
+    /// Returns the sequence number, which grows by two with every completed write.
+    ///
+    /// Comparing it with an earlier value tells whether the state changed meanwhile.
+    #[inline]
+    pub fn version(&self) -> u32 {
+        self.seq.load(Ordering::Acquire) & !1
+    }
+
+    /// Replaces the value.
+    pub fn write(&self, value: T) {
+        let _write = self.lock();
+        // SAFETY: `lock` made this the only writer; readers tolerate the race.
+        unsafe { ptr::write_volatile(self.value.as_mut_ptr_unchecked(), value) };
+    }
+
+    /// Updates the value in place with `f`, as one write.
+    ///
+    /// `f` works on a copy, so readers never see a partial update, and if `f` panics
+    /// the value is left unchanged.
+    pub fn update<R>(&self, f: impl FnOnce(&mut T) -> R) -> R {
+        let _write = self.lock();
+        let ptr = self.value.as_mut_ptr_unchecked();
+        // SAFETY: `lock` made this the only writer, so the value is stable.
+        let mut value = unsafe { ptr::read(ptr) };
+        let result = f(&mut value);
+        // SAFETY: as in `write`.
+        unsafe { ptr::write_volatile(ptr, value) };
+        result
+    }
+
+    /// Returns a mutable reference to the value; `&mut self` rules out readers.
+    pub fn get_mut(&mut self) -> &mut T {
+        self.value.get_mut_exclusive()
+    }
+
+    /// Consumes the lock, returning the value.
+    pub fn into_inner(self) -> T {
+        self.value.into_inner()
+    }
+
+    fn lock(&self) -> WriteGuard<'_> {
+        let mut spins = 0;
+        loop {
+            let seq = self.seq.load(Ordering::Relaxed);
+            if seq & 1 == 0
+                && self
+                    .seq
+                    .compare_exchange_weak(
+                        seq,
+                        seq.wrapping_add(1),
+                        Ordering::Acquire,
+                        Ordering::Relaxed,
+                    )
+                    .is_ok()
+            {
+                // Keep the value writes after the odd sequence.
+                fence(Ordering::Release);
+                return WriteGuard {
+                    seq: &self.seq,
+                    odd: seq.wrapping_add(1),
+                };
+            }
+            if spins < SPINS_BEFORE_YIELD {
+                spins += 1;
+                hint::spin_loop();
+            } else {
+                std::thread::yield_now();
+            }
+        }
+    }
+}
+
+impl<'brand, T: Copy + Default> Default for GhostSeqLock<'brand, T> {
+    fn default() -> Self {
+        Self::new(T::default())
+    }
+}
+
+/// Ends a write by making the sequence even again, also on unwind.
+struct WriteGuard<'a> {
+    seq: &'a AtomicU32,
+    odd: u32,
+}
+
+impl Drop for WriteGuard<'_> {
+    fn drop(&mut self) {
+        self.seq.store(self.odd.wrapping_add(1), Ordering::Release);
+    }
+}
@@ -0,0 +1,257 @@
+//! Grace periods for structures with lock-free readers.
+//!
+//! Readers enter a [`ReadSection`] by incrementing a per-thread counter of the current
+//! reader epoch; the counters are spaced a cache line of the running machine apart. A
+//! writer that has unlinked memory starts a grace period by flipping the epoch, so that
+//! new readers register on the other side, and may free the memory once the readers of
+//! the old epoch have left. Memory is freed by its owner, not a global collector, so it
+//! never outlives the structure or the data it borrows.
+//!
+//! A grace period may only start once the previous one is complete; [`try_begin`]
+//! checks that, so starting one needs no extra locking.
+//!
+//! Structures whose writers must not wait for readers collect unlinked allocations in a
+//! [`RetireList`], which frees them in batches as grace periods complete.
+//!
+//! [`try_begin`]: GracePeriod::try_begin
+
+use core::sync::atomic::Ordering;
+use std::sync::{PoisonError, TryLockError};
+
+use crate::concurrency::atomic::primitive::{AtomicUsize, Mutex};
+use crate::concurrency::cache_padded::padded_stride;
+use crate::concurrency::{current_shard_index, SHARD_COUNT};
+
+/// Spins before a thread waiting out a grace period starts yielding.
+const SPINS_BEFORE_YIELD: u32 = 64;
+/// Retired allocations collected before a grace period is started for them.
+const RECLAIM_BATCH: usize = 64;
+
+/// Reader registration and epoch flipping for one structure.
+///
+/// The counters take two cache lines per shard, a few KiB in total.
+pub(crate) struct GracePeriod {
+    /// Low bit selects the reader epoch new readers register in.
+    epoch: AtomicUsize,
+    /// `SHARD_COUNT` reader counts per epoch, `stride` apart.
+    readers: Box<[AtomicUsize]>,
+    stride: usize,
+}
+
+/// A reader's registration; memory it can reach stays alive until it is dropped.
+pub(crate) struct ReadSection<'a> {
+    slot: &'a AtomicUsize,
+}
+
+/// A started grace period: the reader epoch whose readers have to leave.
+#[derive(Clone, Copy)]
+pub(crate) struct GraceEpoch(usize);
+
+impl GracePeriod {
+    pub(crate) fn new() -> Self {
+        // Sized when the structure is built, so the gap follows the running machine's
+        // line size rather than the compilation target's.
+        let stride = padded_stride::<AtomicUsize>();
+        Self {
+            epoch: AtomicUsize::new(0),
+            readers: (0..2 * SHARD_COUNT * stride)
+                .map(|_| AtomicUsize::new(0))
+                .collect(),
+            stride,
+        }
+    }
+
+    /// Registers the calling thread as a reader.
+    pub(crate) fn read(&self) -> ReadSection<'_> {
+        let shard = current_shard_index();
+        loop {
+            let epoch = self.epoch.load(Ordering::SeqCst) & 1;
+            let slot = self.slot(epoch, shard);
+            slot.fetch_add(1, Ordering::SeqCst);
+            // If the epoch flipped in between, a writer may already be checking the
+            // other side and would miss this registration; back out and retry.
+            if self.epoch.load(Ordering::SeqCst) & 1 == epoch {
+                return ReadSection { slot };
+            }
+            slot.fetch_sub(1, Ordering::Release);
+        }
+    }
+
+    /// Starts a grace period covering every reader registered so far.
+    ///
+    /// Returns `None` if the previous grace period is not complete yet. Memory unlinked
+    /// before a successful call may be freed once [`is_complete`](Self::is_complete)
+    /// returns `true` for the returned epoch.
+    pub(crate) fn try_begin(&self) -> Option<GraceEpoch> {
+        let epoch = self.epoch.load(Ordering::SeqCst);
+        let old = epoch & 1;
+        // Readers of the other side belong to the previous grace period; flipping back
+        // onto them before they leave would mix them with new readers.
+        if !self.is_quiescent(old ^ 1) {
+            return None;
+        }
+        self.epoch
+            .compare_exchange(
+                epoch,
+                epoch.wrapping_add(1),
+                Ordering::SeqCst,
+                Ordering::SeqCst,
+            )
+            .ok()
+            .map(|_| GraceEpoch(old))
+    }
+
+    /// Returns `true` once every reader covered by `epoch` has left.
+    pub(crate) fn is_complete(&self, epoch: GraceEpoch) -> bool {
+        self.is_quiescent(epoch.0)
+    }
+
+    /// Starts a grace period and waits for it to complete.
+    ///
+    /// Must not be called from inside a [`ReadSection`] of the same structure.
+    pub(crate) fn synchronize(&self) {
+        let mut spins = 0;
+        let epoch = loop {
+            if let Some(epoch) = self.try_begin() {
+                break epoch;
+            }
+            backoff(&mut spins);
+        };
+        while !self.is_complete(epoch) {
+            backoff(&mut spins);
+        }
+    }
+
+    fn is_quiescent(&self, epoch: usize) -> bool {
+        // A reader's count stays raised while it is registered, so scanning the slots
+        // one by one cannot miss it.
+        (0..SHARD_COUNT).all(|shard| self.slot(epoch, shard).load(Ordering::SeqCst) == 0)
+    }
+
+    fn slot(&self, epoch: usize, shard: usize) -> &AtomicUsize {
+        &self.readers[(epoch * SHARD_COUNT + shard) * self.stride]
+    }
+}
+
+impl Drop for ReadSection<'_> {
+    fn drop(&mut self) {
+        self.slot.fetch_sub(1, Ordering::Release);
+    }
+}
+
+fn backoff(spins: &mut u32) {
+    if *spins < SPINS_BEFORE_YIELD {
+        *spins += 1;
+        core::hint::spin_loop();
+    } else {
+        std::thread::yield_now();
+    }
+}
+
+/// An unlinked allocation that a [`RetireList`] frees once no reader can reach it.
+pub(crate) trait Reclaim {
+    /// Frees the allocation.
+    ///
+    /// # Safety
+    /// No thread may still reach the allocation.
+    unsafe fn reclaim(self);
+}
+
+/// Unlinked allocations waiting for grace periods, freed in batches.
+///
+/// Retiring never waits for readers: a batch is freed by whichever later call finds
+/// its grace period complete. Whatever is left is freed when the list is dropped,
+/// which the owning structure only allows once it has no readers.
+pub(crate) struct RetireList<R: Reclaim> {
+    batches: Mutex<Batches<R>>,
+    /// Allocations collected before a grace period is started for them.
+    batch: usize,
+}
+
+struct Batches<R> {
+    collecting: Vec<R>,
+    /// A batch waiting for its grace period to complete.
+    waiting: Option<(GraceEpoch, Vec<R>)>,
+}
+
+impl<R: Reclaim> RetireList<R> {
+    pub(crate) fn new() -> Self {
+        Self::with_batch(RECLAIM_BATCH)
+    }
+
+    /// A list that starts a grace period once `batch` allocations are collected.
+    ///
+    /// Structures that retire rarely but in large pieces pass a small `batch`, so an
+    /// allocation is not kept waiting for others that may never come.
+    pub(crate) fn with_batch(batch: usize) -> Self {
+        Self {
+            batches: Mutex::new(Batches {
+                collecting: Vec::new(),
+                waiting: None,
+            }),
+            batch,
+        }
+    }
+
+    /// Whether every retired allocation has been freed.
+    pub(crate) fn is_empty(&self) -> bool {
+        let batches = self.batches.lock().unwrap_or_else(PoisonError::into_inner);
+        batches.waiting.is_none() && batches.collecting.is_empty()
+    }
+
+    /// Queues allocations that are no longer reachable for new readers of `grace`.
+    pub(crate) fn retire(&self, grace: &GracePeriod, items: impl IntoIterator<Item = R>) {
+        self.batches
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .collecting
+            .extend(items);
+        self.try_reclaim(grace);
+    }
+
+    /// Frees a batch whose grace period is over and starts one for the next batch,
+    /// unless another thread is already doing so.
+    pub(crate) fn try_reclaim(&self, grace: &GracePeriod) {
+        let mut batches = match self.batches.try_lock() {
+            Ok(batches) => batches,
+            Err(TryLockError::Poisoned(e)) => e.into_inner(),
+            Err(TryLockError::WouldBlock) => return,
+        };
+        let mut done = None;
+        if let Some((epoch, _)) = batches.waiting {
+            if grace.is_complete(epoch) {
+                done = batches.waiting.take().map(|(_, batch)| batch);
+            }
+        }
+        if batches.waiting.is_none() && batches.collecting.len() >= self.batch {
+            if let Some(epoch) = grace.try_begin() {
+                let batch = core::mem::take(&mut batches.collecting);
+                batches.waiting = Some((epoch, batch));
+            }
+        }
+        drop(batches);
+        for item in done.into_iter().flatten() {
+            // SAFETY: the item was unlinked before its grace period started, and every
+            // reader that could reach it has left.
+            unsafe { item.reclaim() };
+        }
+    }
+}
+
+impl<R: Reclaim> Drop for RetireList<R> {
+    fn drop(&mut self) {
+        let batches = self
+            .batches
+            .get_mut()
+            .unwrap_or_else(PoisonError::into_inner);
+        let waiting = batches.waiting.take().map(|(_, batch)| batch);
+        for item in waiting
+            .into_iter()
+            .flatten()
+            .chain(batches.collecting.drain(..))
+        {
+            // SAFETY: the owner is being dropped, so it has no readers left.
+            unsafe { item.reclaim() };
+        }
+    }
+}
@@ -1,49 +1,302 @@
 pub mod ghost_barrier;
 pub mod ghost_channel;
 pub mod ghost_condvar;
+pub mod ghost_event;
+pub mod ghost_flat_combiner;
+pub mod ghost_latch;
 pub mod ghost_mutex;
 pub mod ghost_once_lock;
+pub mod ghost_rcu;
+pub mod ghost_rwlock;
+pub mod ghost_seqlock;
+pub mod ghost_semaphore;
 pub mod mpmc;
+pub mod mpmc_channel;
+mod grace;
+mod signal;
 
-pub use ghost_barrier::GhostBarrier;
+pub use ghost_barrier::{BarrierWaitResult, GhostBarrier};
 pub use ghost_channel::{
     ghost_channel, ghost_oneshot, GhostOneshotReceiver, GhostOneshotSender, GhostReceiver,
     GhostSender, OneshotRecvError, OneshotSendError, RecvError, SendError, TryRecvError,
 };
 pub use ghost_condvar::GhostCondvar;
+pub use ghost_event::GhostEvent;
+pub use ghost_flat_combiner::GhostFlatCombiner;
+pub use ghost_latch::GhostLatch;
 pub use ghost_mutex::{GhostMutex, GhostMutexGuard};
 pub use ghost_once_lock::GhostOnceLock;
+pub use ghost_rcu::{GhostRcuCell, GhostRcuReadGuard};
+pub use ghost_rwlock::{
+    GhostRwLock, GhostRwLockReadGuard, GhostRwLockUpgradableGuard, GhostRwLockWriteGuard,
+    RwLockPolicy,
+};
+pub use ghost_seqlock::GhostSeqLock;
+pub use ghost_semaphore::{GhostSemaphore, GhostSemaphorePermit};
 pub use mpmc::GhostRingBuffer;
+pub use mpmc_channel::{ghost_mpmc_channel, GhostMpmcReceiver, GhostMpmcSender, TrySendError};
+pub(crate) use grace::{GracePeriod, ReadSection, Reclaim, RetireList};
+pub(crate) use signal::Signal;
 
 use core::sync::atomic::{AtomicBool, AtomicU32, AtomicUsize, Ordering};
+use std::time::{Duration, Instant};
 
 #[cfg(windows)]
 use windows_sys::Win32::System::Threading::{
     WaitOnAddress, WakeByAddressAll, WakeByAddressSingle,
 };
 
-#[cfg(target_os = "linux")]
-use libc::{SYS_futex, FUTEX_PRIVATE_FLAG, FUTEX_WAIT, FUTEX_WAKE};
+#[cfg(any(
+    target_os = "linux",
+    target_vendor = "apple",
+    target_os = "freebsd",
+    target_os = "openbsd"
+))]
+use futex::{futex_wait, futex_wake_all, futex_wake_one, parked_wait, parked_wake};
 
-#[cfg(target_os = "linux")]
-#[inline]
-fn futex_wait(addr: *const u32, expected: u32) {
-    unsafe {
-        libc::syscall(
-            SYS_futex,
-            addr,
-            FUTEX_WAIT | FUTEX_PRIVATE_FLAG,
-            expected,
-            core::ptr::null::<libc::timespec>(),
-        );
+/// Native wait/wake on an aligned 32-bit word: the Linux and OpenBSD futex, `__ulock` on
+/// Apple platforms and `_umtx_op` on FreeBSD. Every backend compares the word with the
+/// expected value atomically with going to sleep, and may return spuriously.
+#[cfg(any(
+    target_os = "linux",
+    target_vendor = "apple",
+    target_os = "freebsd",
+    target_os = "openbsd"
+))]
+mod futex {
+    use core::sync::atomic::{AtomicU32, Ordering};
+    use std::time::Duration;
+
+    /// Converts a relative timeout, saturating at the largest representable one.
+    #[cfg(any(target_os = "linux", target_os = "freebsd", target_os = "openbsd"))]
+    #[inline]
+    fn timespec(timeout: Duration) -> libc::timespec {
+        libc::timespec {
+            tv_sec: libc::time_t::try_from(timeout.as_secs()).unwrap_or(libc::time_t::MAX),
+            // Below one billion, so it fits whatever integer type `tv_nsec` is; the
+            // conversion is only fallible where that type is 32 bits wide.
+            #[allow(clippy::unnecessary_fallible_conversions)]
+            tv_nsec: timeout.subsec_nanos().try_into().unwrap_or(999_999_999),
+        }
     }
-}
 
-#[cfg(target_os = "linux")]
-#[inline]
-fn futex_wake(addr: *const u32, count: i32) {
-    unsafe {
-        libc::syscall(SYS_futex, addr, FUTEX_WAKE | FUTEX_PRIVATE_FLAG, count);
+    #[cfg(target_os = "linux")]
+    #[inline]
+    pub(super) fn futex_wait(addr: *const u32, expected: u32, timeout: Option<Duration>) {
+        use libc::{SYS_futex, FUTEX_PRIVATE_FLAG, FUTEX_WAIT};
+        let ts = timeout.map(timespec);
+        unsafe {
+            libc::syscall(
+                SYS_futex,
+                addr,
+                FUTEX_WAIT | FUTEX_PRIVATE_FLAG,
+                expected,
+                ts.as_ref().map_or(core::ptr::null(), core::ptr::from_ref),
+            );
+        }
+    }
+
+    #[cfg(target_os = "linux")]
+    #[inline]
+    fn futex_wake(addr: *const u32, count: i32) {
+        use libc::{SYS_futex, FUTEX_PRIVATE_FLAG, FUTEX_WAKE};
+        unsafe {
+            libc::syscall(SYS_futex, addr, FUTEX_WAKE | FUTEX_PRIVATE_FLAG, count);
+        }
+    }
+
+    #[cfg(target_os = "openbsd")]
+    #[inline]
+    pub(super) fn futex_wait(addr: *const u32, expected: u32, timeout: Option<Duration>) {
+        use libc::{FUTEX_PRIVATE_FLAG, FUTEX_WAIT};
+        let ts = timeout.map(timespec);
+        unsafe {
+            libc::futex(
+                addr as *mut u32,
+                FUTEX_WAIT | FUTEX_PRIVATE_FLAG,
+                expected as i32,
+                ts.as_ref().map_or(core::ptr::null(), core::ptr::from_ref),
+                core::ptr::null_mut(),
+            );
+        }
+    }
+
+    #[cfg(target_os = "openbsd")]
+    #[inline]
+    fn futex_wake(addr: *const u32, count: i32) {
+        use libc::{FUTEX_PRIVATE_FLAG, FUTEX_WAKE};
+        unsafe {
+            libc::futex(
+                addr as *mut u32,
+                FUTEX_WAKE | FUTEX_PRIVATE_FLAG,
+                count,
+                core::ptr::null(),
+                core::ptr::null_mut(),
+            );
+        }
+    }
+
+    #[cfg(any(target_os = "linux", target_os = "openbsd"))]
+    #[inline]
+    pub(super) fn futex_wake_one(addr: *const u32) {
+        futex_wake(addr, 1);
+    }
+
+    #[cfg(any(target_os = "linux", target_os = "openbsd"))]
+    #[inline]
+    pub(super) fn futex_wake_all(addr: *const u32) {
+        futex_wake(addr, i32::MAX);
+    }
+
+    // `__ulock_wait`/`__ulock_wake` have been in libSystem since macOS 10.12 and iOS 10;
+    // the public `os_sync_wait_on_address` needs macOS 14.4, so the older entry points
+    // are used directly. They are not exported by the `libc` crate.
+    #[cfg(target_vendor = "apple")]
+    const UL_COMPARE_AND_WAIT: u32 = 1;
+    #[cfg(target_vendor = "apple")]
+    const ULF_WAKE_ALL: u32 = 0x0000_0100;
+    #[cfg(target_vendor = "apple")]
+    const ULF_NO_ERRNO: u32 = 0x0100_0000;
+
+    #[cfg(target_vendor = "apple")]
+    extern "C" {
+        fn __ulock_wait(
+            operation: u32,
+            addr: *mut libc::c_void,
+            value: u64,
+            timeout_us: u32,
+        ) -> libc::c_int;
+        fn __ulock_wake(operation: u32, addr: *mut libc::c_void, wake_value: u64) -> libc::c_int;
+    }
+
+    #[cfg(target_vendor = "apple")]
+    #[inline]
+    pub(super) fn futex_wait(addr: *const u32, expected: u32, timeout: Option<Duration>) {
+        // A timeout of zero waits indefinitely, so finite ones are at least 1µs. Longer
+        // than `u32::MAX` µs (about 71 minutes) wakes early, which callers treat as
+        // spurious.
+        let timeout_us = timeout.map_or(0, |t| t.as_micros().clamp(1, u32::MAX.into()) as u32);
+        unsafe {
+            __ulock_wait(
+                UL_COMPARE_AND_WAIT | ULF_NO_ERRNO,
+                addr as *mut libc::c_void,
+                u64::from(expected),
+                timeout_us,
+            );
+        }
+    }
+
+    #[cfg(target_vendor = "apple")]
+    #[inline]
+    pub(super) fn futex_wake_one(addr: *const u32) {
+        unsafe {
+            __ulock_wake(UL_COMPARE_AND_WAIT | ULF_NO_ERRNO, addr as *mut libc::c_void, 0);
+        }
+    }
+
+    #[cfg(target_vendor = "apple")]
+    #[inline]
+    pub(super) fn futex_wake_all(addr: *const u32) {
+        unsafe {
+            __ulock_wake(
+                UL_COMPARE_AND_WAIT | ULF_WAKE_ALL | ULF_NO_ERRNO,
+                addr as *mut libc::c_void,
+                0,
+            );
+        }
+    }
+
+    #[cfg(target_os = "freebsd")]
+    #[inline]
+    pub(super) fn futex_wait(addr: *const u32, expected: u32, timeout: Option<Duration>) {
+        // A timeout is passed as `uaddr2`, with its size smuggled through `uaddr`.
+        let mut ts = timeout.map(timespec);
+        let (size, ts_ptr) = match ts.as_mut() {
+            Some(ts) => (
+                core::mem::size_of::<libc::timespec>() as *mut libc::c_void,
+                ts as *mut libc::timespec as *mut libc::c_void,
+            ),
+            None => (core::ptr::null_mut(), core::ptr::null_mut()),
+        };
+        unsafe {
+            libc::_umtx_op(
+                addr as *mut libc::c_void,
+                libc::UMTX_OP_WAIT_UINT_PRIVATE,
+                libc::c_ulong::from(expected),
+                size,
+                ts_ptr,
+            );
+        }
+    }
+
+    #[cfg(target_os = "freebsd")]
+    #[inline]
+    fn umtx_wake(addr: *const u32, count: libc::c_int) {
+        unsafe {
+            libc::_umtx_op(
+                addr as *mut libc::c_void,
+                libc::UMTX_OP_WAKE_PRIVATE,
+                count as libc::c_ulong,
+                core::ptr::null_mut(),
+                core::ptr::null_mut(),
+            );
+        }
+    }
+
+    #[cfg(target_os = "freebsd")]
+    #[inline]
+    pub(super) fn futex_wake_one(addr: *const u32) {
+        umtx_wake(addr, 1);
+    }
+
+    #[cfg(target_os = "freebsd")]
+    #[inline]
+    pub(super) fn futex_wake_all(addr: *const u32) {
+        umtx_wake(addr, libc::c_int::MAX);
+    }
+
+    // The primitives above only operate on aligned 32-bit words, so waits on `bool` and
+    // `usize` sleep on a per-address epoch counter instead. Wakers bump the epoch after
+    // changing the value; a waiter reads the epoch *before* checking the value, so a wake
+    // between the check and the `futex_wait` changes the epoch and the wait returns at
+    // once. Addresses share buckets, so every wake is broadcast to the bucket and callers
+    // must re-check their value, as they already do for spurious wakeups.
+    const PARKING_BUCKETS: usize = 64;
+
+    static PARKING_EPOCHS: [crate::concurrency::CachePadded<AtomicU32>; PARKING_BUCKETS] =
+        [const { crate::concurrency::CachePadded::new(AtomicU32::new(0)) }; PARKING_BUCKETS];
+
+    /// 2^w divided by the golden ratio, for Fibonacci hashing on `w`-bit words.
+    #[cfg(target_pointer_width = "64")]
+    const FIBONACCI: usize = 0x9e37_79b9_7f4a_7c15;
+    #[cfg(not(target_pointer_width = "64"))]
+    const FIBONACCI: usize = 0x9e37_79b9;
+
+    #[inline]
+    fn parking_epoch<T>(addr: *const T) -> &'static AtomicU32 {
+        // Fibonacci hashing; the low bits of an address carry little entropy.
+        let hash = (addr.addr() >> 3).wrapping_mul(FIBONACCI);
+        &PARKING_EPOCHS[hash >> (usize::BITS - PARKING_BUCKETS.trailing_zeros())]
+    }
+
+    #[inline]
+    pub(super) fn parked_wait<T>(
+        addr: *const T,
+        still_expected: impl FnOnce() -> bool,
+        timeout: Option<Duration>,
+    ) {
+        let epoch = parking_epoch(addr);
+        let seen = epoch.load(Ordering::SeqCst);
+        if still_expected() {
+            futex_wait(epoch.as_ptr(), seen, timeout);
+        }
+    }
+
+    #[inline]
+    pub(super) fn parked_wake<T>(addr: *const T) {
+        let epoch = parking_epoch(addr);
+        epoch.fetch_add(1, Ordering::SeqCst);
+        futex_wake_all(epoch.as_ptr());
     }
 }
 
@@ -54,32 +307,44 @@ pub fn wake_all_bool(addr: &AtomicBool) {
     unsafe {
         WakeByAddressAll(addr as *const _ as *mut _);
     }
+    #[cfg(any(
+        target_os = "linux",
+        target_vendor = "apple",
+        target_os = "freebsd",
+        target_os = "openbsd"
+    ))]
+    parked_wake(addr);
 }
 
 #[inline]
 /// Wakes one thread waiting on the given boolean address.
+///
+/// Outside Windows this may wake more than one waiter.
 pub fn wake_one_bool(addr: &AtomicBool) {
     #[cfg(windows)]
     unsafe {
         WakeByAddressSingle(addr as *const _ as *mut _);
     }
+    #[cfg(any(
+        target_os = "linux",
+        target_vendor = "apple",
+        target_os = "freebsd",
+        target_os = "openbsd"
+    ))]
+    parked_wake(addr);
 }
 
 #[inline]
 /// Waits on the given boolean address until the value changes from `expected`.
 pub fn wait_on_bool(addr: &AtomicBool, expected: bool) {
-    #[cfg(windows)]
-    unsafe {
-        let expected_ptr = &expected as *const bool as *const _;
-        let addr_ptr = addr as *const _ as *mut _;
-        let size = core::mem::size_of::<bool>();
-        WaitOnAddress(addr_ptr, expected_ptr, size, u32::MAX);
-        return;
-    }
-    #[cfg(not(any(windows, target_os = "linux")))]
-    while addr.load(Ordering::SeqCst) == expected {
-        std::thread::yield_now();
-    }
+    wait_on_word(addr, expected, || addr.load(Ordering::SeqCst) == expected, None);
+}
+
+/// Like [`wait_on_bool`], but gives up once `timeout` has elapsed.
+pub fn wait_on_bool_timeout(addr: &AtomicBool, expected: bool, timeout: Duration) -> WaitResult {
+    timed_wait(timeout, |timeout| {
+        wait_on_word(addr, expected, || addr.load(Ordering::SeqCst) == expected, timeout);
+    })
 }
 
 /// Wakes all threads waiting on the given address.
@@ -89,31 +354,48 @@ pub fn wake_all_usize(addr: &AtomicUsize) {
     unsafe {
         WakeByAddressAll(addr as *const _ as *mut _);
     }
+    #[cfg(any(
+        target_os = "linux",
+        target_vendor = "apple",
+        target_os = "freebsd",
+        target_os = "openbsd"
+    ))]
+    parked_wake(addr);
 }
 
 /// Wakes one thread waiting on the given address.
+///
+/// Outside Windows this may wake more than one waiter.
 #[inline]
 pub fn wake_one_usize(addr: &AtomicUsize) {
     #[cfg(windows)]
     unsafe {
         WakeByAddressSingle(addr as *const _ as *mut _);
     }
+    #[cfg(any(
+        target_os = "linux",
+        target_vendor = "apple",
+        target_os = "freebsd",
+        target_os = "openbsd"
+    ))]
+    parked_wake(addr);
 }
 
 /// Waits on the given address until the value changes from `expected`.
 #[inline]
 pub fn wait_on_usize(addr: &AtomicUsize, expected: usize) {
-    #[cfg(windows)]
-    unsafe {
-        let expected_ptr = &expected as *const usize as *const _;
-        let addr_ptr = addr as *const _ as *mut _;
-        let size = core::mem::size_of::<usize>();
-        WaitOnAddress(addr_ptr, expected_ptr, size, u32::MAX);
-    }
-    #[cfg(not(any(windows, target_os = "linux")))]
-    while addr.load(Ordering::SeqCst) == expected {
-        std::thread::yield_now();
-    }
+    wait_on_word(addr, expected, || addr.load(Ordering::SeqCst) == expected, None);
+}
+
+/// Like [`wait_on_usize`], but gives up once `timeout` has elapsed.
+pub fn wait_on_usize_timeout(
+    addr: &AtomicUsize,
+    expected: usize,
+    timeout: Duration,
+) -> WaitResult {
+    timed_wait(timeout, |timeout| {
+        wait_on_word(addr, expected, || addr.load(Ordering::SeqCst) == expected, timeout);
+    })
 }
 
 /// Wakes all threads waiting on the given address.
@@ -123,10 +405,13 @@ pub fn wake_all_u32(addr: &AtomicU32) {
     unsafe {
         WakeByAddressAll(addr as *const _ as *mut _);
     }
-    #[cfg(target_os = "linux")]
-    {
-        futex_wake(addr as *const _ as *const u32, i32::MAX);
-    }
+    #[cfg(any(
+        target_os = "linux",
+        target_vendor = "apple",
+        target_os = "freebsd",
+        target_os = "openbsd"
+    ))]
+    futex_wake_all(addr.as_ptr());
 }
 
 /// Wakes one thread waiting on the given address.
@@ -136,30 +421,152 @@ pub fn wake_one_u32(addr: &AtomicU32) {
     unsafe {
         WakeByAddressSingle(addr as *const _ as *mut _);
     }
-    #[cfg(target_os = "linux")]
-    {
-        futex_wake(addr as *const _ as *const u32, 1);
-    }
+    #[cfg(any(
+        target_os = "linux",
+        target_vendor = "apple",
+        target_os = "freebsd",
+        target_os = "openbsd"
+    ))]
+    futex_wake_one(addr.as_ptr());
 }
 
 /// Waits on the given address until the value changes from `expected`.
 #[inline]
 pub fn wait_on_u32(addr: &AtomicU32, expected: u32) {
+    wait_on_u32_impl(addr, expected, None);
+}
+
+/// Like [`wait_on_u32`], but gives up once `timeout` has elapsed.
+pub fn wait_on_u32_timeout(addr: &AtomicU32, expected: u32, timeout: Duration) -> WaitResult {
+    timed_wait(timeout, |timeout| wait_on_u32_impl(addr, expected, timeout))
+}
+
+/// Outcome of a timed wait such as [`wait_on_u32_timeout`].
+///
+/// Like the untimed waits, a timed wait can return spuriously, so callers re-check the
+/// value whatever the result.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum WaitResult {
+    /// The wait returned before the timeout elapsed.
+    Woken,
+    /// The timeout elapsed.
+    TimedOut,
+}
+
+impl WaitResult {
+    /// Returns `true` for [`WaitResult::TimedOut`].
+    #[inline]
+    pub const fn timed_out(self) -> bool {
+        matches!(self, WaitResult::TimedOut)
+    }
+}
+
+/// Runs `wait` with `timeout` and reports whether it elapsed, judged by the clock rather
+/// than by each backend's error codes.
+#[inline]
+fn timed_wait(timeout: Duration, wait: impl FnOnce(Option<Duration>)) -> WaitResult {
+    let start = Instant::now();
+    wait(Some(timeout));
+    if start.elapsed() >= timeout {
+        WaitResult::TimedOut
+    } else {
+        WaitResult::Woken
+    }
+}
+
+/// Converts a timeout to `WaitOnAddress` milliseconds, rounding up and staying below
+/// `INFINITE`.
+#[cfg(windows)]
+#[inline]
+fn timeout_ms(timeout: Option<Duration>) -> u32 {
+    timeout.map_or(u32::MAX, |t| {
+        t.as_nanos().div_ceil(1_000_000).min(u128::from(u32::MAX - 1)) as u32
+    })
+}
+
+#[inline]
+fn wait_on_u32_impl(addr: &AtomicU32, expected: u32, timeout: Option<Duration>) {
     #[cfg(windows)]
     unsafe {
         let expected_ptr = &expected as *const u32 as *const _;
         let addr_ptr = addr as *const _ as *mut _;
         let size = core::mem::size_of::<u32>();
-        WaitOnAddress(addr_ptr, expected_ptr, size, u32::MAX);
+        WaitOnAddress(addr_ptr, expected_ptr, size, timeout_ms(timeout));
     }
-    #[cfg(target_os = "linux")]
+    #[cfg(any(
+        target_os = "linux",
+        target_vendor = "apple",
+        target_os = "freebsd",
+        target_os = "openbsd"
+    ))]
+    if addr.load(Ordering::SeqCst) == expected {
+        futex_wait(addr.as_ptr(), expected, timeout);
+    }
+    #[cfg(not(any(
+        windows,
+        target_os = "linux",
+        target_vendor = "apple",
+        target_os = "freebsd",
+        target_os = "openbsd"
+    )))]
+    spin_wait(|| addr.load(Ordering::SeqCst) == expected, timeout);
+}
+
+/// Waits on a `bool` or `usize` atomic. `expected` is the plain value compared by
+/// `WaitOnAddress`; the other backends poll `still_expected`.
+#[inline]
+fn wait_on_word<A, V>(
+    addr: &A,
+    expected: V,
+    still_expected: impl Fn() -> bool,
+    timeout: Option<Duration>,
+) {
+    debug_assert_eq!(core::mem::size_of::<A>(), core::mem::size_of::<V>());
+    #[cfg(windows)]
     unsafe {
-        if addr.load(Ordering::SeqCst) == expected {
-            futex_wait(addr as *const _ as *const u32, expected);
-        }
+        let _ = &still_expected;
+        let expected_ptr = &expected as *const V as *const _;
+        let addr_ptr = addr as *const A as *mut _;
+        let size = core::mem::size_of::<V>();
+        WaitOnAddress(addr_ptr, expected_ptr, size, timeout_ms(timeout));
     }
-    #[cfg(not(any(windows, target_os = "linux")))]
-    while addr.load(Ordering::SeqCst) == expected {
+    #[cfg(any(
+        target_os = "linux",
+        target_vendor = "apple",
+        target_os = "freebsd",
+        target_os = "openbsd"
+    ))]
+    {
+        let _ = expected;
+        parked_wait(core::ptr::from_ref(addr), still_expected, timeout);
+    }
+    #[cfg(not(any(
+        windows,
+        target_os = "linux",
+        target_vendor = "apple",
+        target_os = "freebsd",
+        target_os = "openbsd"
+    )))]
+    {
+        let _ = (addr, expected);
+        spin_wait(still_expected, timeout);
+    }
+}
+
+/// Fallback for platforms without an address-wait primitive.
+#[cfg(not(any(
+    windows,
+    target_os = "linux",
+    target_vendor = "apple",
+    target_os = "freebsd",
+    target_os = "openbsd"
+)))]
+fn spin_wait(still_expected: impl Fn() -> bool, timeout: Option<Duration>) {
+    let start = Instant::now();
+    while still_expected() {
+        if timeout.is_some_and(|t| start.elapsed() >= t) {
+            return;
+        }
         std::thread::yield_now();
     }
 }
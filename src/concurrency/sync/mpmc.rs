@@ -7,6 +7,7 @@
 //! the token for `try_push`/`try_pop` operations (making it fully concurrent).
 
 use crate::concurrency::atomic::GhostAtomicUsize;
+use crate::concurrency::CachePadded;
 use core::cell::UnsafeCell;
 use core::mem::MaybeUninit;
 use core::sync::atomic::Ordering;
@@ -22,17 +23,11 @@ struct Slot<'brand, T> {
 }
 
 /// A lock-free, bounded MPMC queue.
-#[repr(C)]
-#[repr(align(64))]
 pub struct GhostRingBuffer<'brand, T> {
-    /// The head index (enqueue position).
-    head: GhostAtomicUsize<'brand>,
-    /// Padding to prevent false sharing.
-    _pad1: [u8; 56],
-    /// The tail index (dequeue position).
-    tail: GhostAtomicUsize<'brand>,
-    /// Padding to prevent false sharing.
-    _pad2: [u8; 56],
+    /// The head index (enqueue position), on its own cache line.
+    head: CachePadded<GhostAtomicUsize<'brand>>,
+    /// The tail index (dequeue position), on its own cache line.
+    tail: CachePadded<GhostAtomicUsize<'brand>>,
     /// The buffer.
     buffer: Box<[Slot<'brand, T>]>,
     /// Capacity mask (capacity - 1).
@@ -63,10 +58,8 @@ impl<'brand, T> GhostRingBuffer<'brand, T> {
         }
 
         Self {
-            head: GhostAtomicUsize::new(0),
-            _pad1: [0; 56],
-            tail: GhostAtomicUsize::new(0),
-            _pad2: [0; 56],
+            head: CachePadded::new(GhostAtomicUsize::new(0)),
+            tail: CachePadded::new(GhostAtomicUsize::new(0)),
             buffer: buffer.into_boxed_slice(),
             mask,
         }
@@ -0,0 +1,327 @@
+//! Branded bounded MPMC channel built on [`GhostRingBuffer`], with [`ghost_select!`].
+//!
+//! `ghost_mpmc_channel(capacity)` returns cloneable sender and receiver handles over a
+//! shared lock-free ring. Dropping the last sender disconnects the receivers once the
+//! buffered items are drained; dropping the last receiver makes every send fail. Like
+//! the MPSC channel, operations take a token to prove they run inside the brand.
+//!
+//! Blocking uses epoch counters and the futex helpers of this module, so a thread
+//! waiting on a full or empty channel sleeps instead of spinning.
+//!
+//! [`ghost_select!`]: crate::ghost_select
+
+use super::ghost_channel::{RecvError, SendError, TryRecvError};
+use super::mpmc::GhostRingBuffer;
+use super::Signal;
+use crate::token::traits::GhostBorrow;
+use core::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+/// Error returned by `try_send`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TrySendError<T> {
+    /// The channel is full.
+    Full(T),
+    /// Every receiver has been dropped.
+    Disconnected(T),
+}
+
+impl<T> TrySendError<T> {
+    /// Returns the value that could not be sent.
+    pub fn into_inner(self) -> T {
+        match self {
+            TrySendError::Full(t) | TrySendError::Disconnected(t) => t,
+        }
+    }
+}
+
+/// Notified on every send and sender disconnect, for threads in `ghost_select!`.
+static SELECT_SIGNAL: Signal = Signal::new();
+
+/// Registration of a thread blocked in [`ghost_select!`](crate::ghost_select).
+///
+/// Implementation detail of the macro.
+#[doc(hidden)]
+pub struct SelectWaiter {
+    seen: u32,
+}
+
+impl SelectWaiter {
+    /// Registers before the arms are polled, so no send after the poll is missed.
+    #[inline]
+    pub fn register() -> Self {
+        Self {
+            seen: SELECT_SIGNAL.register(),
+        }
+    }
+
+    /// Sleeps until a send or disconnect on any channel since `register`.
+    #[inline]
+    pub fn wait(self) {
+        SELECT_SIGNAL.wait(self.seen);
+    }
+}
+
+impl Drop for SelectWaiter {
+    fn drop(&mut self) {
+        SELECT_SIGNAL.unregister();
+    }
+}
+
+struct Shared<'brand, T> {
+    ring: GhostRingBuffer<'brand, T>,
+    senders: AtomicUsize,
+    receivers: AtomicUsize,
+    /// Receivers waiting for an item or disconnection.
+    not_empty: Signal,
+    /// Senders waiting for room or disconnection.
+    not_full: Signal,
+}
+
+/// The sending half of a branded MPMC channel. Clone it for more producers.
+pub struct GhostMpmcSender<'brand, T> {
+    shared: Arc<Shared<'brand, T>>,
+}
+
+/// The receiving half of a branded MPMC channel. Clone it for more consumers; each item
+/// is received by exactly one of them.
+pub struct GhostMpmcReceiver<'brand, T> {
+    shared: Arc<Shared<'brand, T>>,
+}
+
+/// Creates a bounded MPMC channel holding at least `capacity` items, returning the
+/// sender/receiver halves.
+///
+/// The capacity is rounded up to a power of two, and to at least 2.
+pub fn ghost_mpmc_channel<'brand, T>(
+    capacity: usize,
+) -> (GhostMpmcSender<'brand, T>, GhostMpmcReceiver<'brand, T>) {
+    let shared = Arc::new(Shared {
+        ring: GhostRingBuffer::new(capacity),
+        senders: AtomicUsize::new(1),
+        receivers: AtomicUsize::new(1),
+        not_empty: Signal::new(),
+        not_full: Signal::new(),
+    });
+    (
+        GhostMpmcSender {
+            shared: shared.clone(),
+        },
+        GhostMpmcReceiver { shared },
+    )
+}
+
+impl<'brand, T> GhostMpmcSender<'brand, T> {
+    /// Sends a value, blocking while the channel is full.
+    ///
+    /// # Errors
+    /// Returns the value if every receiver has been dropped.
+    pub fn send(&self, mut t: T, token: &impl GhostBorrow<'brand>) -> Result<(), SendError<T>> {
+        let shared = &*self.shared;
+        loop {
+            match self.try_send(t, token) {
+                Ok(()) => return Ok(()),
+                Err(TrySendError::Disconnected(v)) => return Err(SendError(v)),
+                Err(TrySendError::Full(v)) => t = v,
+            }
+            shared.not_full.wait_while(|| {
+                shared.ring.is_full() && shared.receivers.load(Ordering::SeqCst) != 0
+            });
+        }
+    }
+
+    /// Sends a value if there is room, without blocking.
+    ///
+    /// # Errors
+    /// Returns the value in [`TrySendError::Full`] or [`TrySendError::Disconnected`].
+    pub fn try_send(&self, t: T, _token: &impl GhostBorrow<'brand>) -> Result<(), TrySendError<T>> {
+        let shared = &*self.shared;
+        if shared.receivers.load(Ordering::SeqCst) == 0 {
+            return Err(TrySendError::Disconnected(t));
+        }
+        shared.ring.try_push(t).map_err(TrySendError::Full)?;
+        shared.not_empty.notify();
+        SELECT_SIGNAL.notify();
+        Ok(())
+    }
+
+    /// Returns `true` once every receiver has been dropped.
+    pub fn is_disconnected(&self) -> bool {
+        self.shared.receivers.load(Ordering::SeqCst) == 0
+    }
+
+    /// Returns the capacity of the channel.
+    pub fn capacity(&self) -> usize {
+        self.shared.ring.capacity()
+    }
+}
+
+impl<'brand, T> GhostMpmcReceiver<'brand, T> {
+    /// Receives a value, blocking while the channel is empty.
+    ///
+    /// # Errors
+    /// Returns [`RecvError`] once the channel is empty and every sender has been dropped.
+    pub fn recv(&self, token: &impl GhostBorrow<'brand>) -> Result<T, RecvError> {
+        let shared = &*self.shared;
+        loop {
+            match self.try_recv(token) {
+                Ok(t) => return Ok(t),
+                Err(TryRecvError::Disconnected) => return Err(RecvError),
+                Err(TryRecvError::Empty) => {}
+            }
+            shared.not_empty.wait_while(|| {
+                shared.ring.is_empty() && shared.senders.load(Ordering::SeqCst) != 0
+            });
+        }
+    }
+
+    /// Receives a pending value without blocking.
+    ///
+    /// # Errors
+    /// Returns [`TryRecvError::Empty`] if nothing is buffered, or
+    /// [`TryRecvError::Disconnected`] if additionally every sender has been dropped.
+    pub fn try_recv(&self, _token: &impl GhostBorrow<'brand>) -> Result<T, TryRecvError> {
+        let shared = &*self.shared;
+        let popped = match shared.ring.try_pop() {
+            Some(t) => Some(t),
+            // A sender may push and then disconnect between the pop and the load.
+            None if shared.senders.load(Ordering::SeqCst) == 0 => shared.ring.try_pop(),
+            None => return Err(TryRecvError::Empty),
+        };
+        let t = popped.ok_or(TryRecvError::Disconnected)?;
+        shared.not_full.notify();
+        Ok(t)
+    }
+
+    /// Returns `true` once every sender has been dropped, even if items remain buffered.
+    pub fn is_disconnected(&self) -> bool {
+        self.shared.senders.load(Ordering::SeqCst) == 0
+    }
+
+    /// Returns `true` if no items are buffered.
+    pub fn is_empty(&self) -> bool {
+        self.shared.ring.is_empty()
+    }
+
+    /// Returns the capacity of the channel.
+    pub fn capacity(&self) -> usize {
+        self.shared.ring.capacity()
+    }
+}
+
+impl<'brand, T> Clone for GhostMpmcSender<'brand, T> {
+    fn clone(&self) -> Self {
+        self.shared.senders.fetch_add(1, Ordering::Relaxed);
+        Self {
+            shared: self.shared.clone(),
+        }
+    }
+}
+
+impl<'brand, T> Clone for GhostMpmcReceiver<'brand, T> {
+    fn clone(&self) -> Self {
+        self.shared.receivers.fetch_add(1, Ordering::Relaxed);
+        Self {
+            shared: self.shared.clone(),
+        }
+    }
+}
+
+impl<'brand, T> Drop for GhostMpmcSender<'brand, T> {
+    fn drop(&mut self) {
+        if self.shared.senders.fetch_sub(1, Ordering::SeqCst) == 1 {
+            self.shared.not_empty.notify();
+            SELECT_SIGNAL.notify();
+        }
+    }
+}
+
+impl<'brand, T> Drop for GhostMpmcReceiver<'brand, T> {
+    fn drop(&mut self) {
+        if self.shared.receivers.fetch_sub(1, Ordering::SeqCst) == 1 {
+            self.shared.not_full.notify();
+        }
+    }
+}
+
+/// Waits on several [`GhostMpmcReceiver`]s at once and runs the arm of the first one
+/// that is ready.
+///
+/// Each arm is `recv(receiver, token) -> result => body`, where `result` is bound to
+/// `Ok(value)` or, once that channel is empty and disconnected, `Err(RecvError)`. Arms
+/// are polled in order, so an earlier busy channel can starve a later one. A final
+/// `default => body` arm makes the select non-blocking. The whole `ghost_select!`
+/// evaluates to the chosen body. Avoid `break` and `continue` in bodies: the blocking
+/// form runs inside a loop of its own.
+///
+/// # Example
+///
+/// ```rust
+/// use halo::concurrency::sync::ghost_mpmc_channel;
+/// use halo::{ghost_select, GhostToken};
+///
+/// GhostToken::new(|token| {
+///     let (numbers_tx, numbers) = ghost_mpmc_channel::<u32>(4);
+///     let (words_tx, words) = ghost_mpmc_channel::<&str>(4);
+///     words_tx.send("hi", &token).unwrap();
+///     drop(numbers_tx);
+///
+///     let got = ghost_select! {
+///         recv(numbers, &token) -> n => format!("number {n:?}"),
+///         recv(words, &token) -> w => format!("word {}", w.unwrap()),
+///     };
+///     // The disconnected `numbers` channel is ready too, and comes first.
+///     assert_eq!(got, "number Err(RecvError)");
+///
+///     let empty = ghost_select! {
+///         recv(words, &token) -> w => w.is_ok(),
+///         default => false,
+///     };
+///     assert!(empty);
+/// });
+/// ```
+#[macro_export]
+macro_rules! ghost_select {
+    (
+        $(recv($rx:expr, $token:expr) -> $res:pat => $body:expr,)+
+        default => $default:expr $(,)?
+    ) => {
+        'ghost_select: {
+            $(
+                if let ::core::option::Option::Some(res) =
+                    $crate::concurrency::sync::GhostMpmcReceiver::poll_select(&$rx, $token)
+                {
+                    let $res = res;
+                    break 'ghost_select $body;
+                }
+            )+
+            $default
+        }
+    };
+    ($(recv($rx:expr, $token:expr) -> $res:pat => $body:expr),+ $(,)?) => {
+        loop {
+            let waiter = $crate::concurrency::sync::mpmc_channel::SelectWaiter::register();
+            $(
+                if let ::core::option::Option::Some(res) =
+                    $crate::concurrency::sync::GhostMpmcReceiver::poll_select(&$rx, $token)
+                {
+                    let $res = res;
+                    break $body;
+                }
+            )+
+            waiter.wait();
+        }
+    };
+}
+
+impl<'brand, T> GhostMpmcReceiver<'brand, T> {
+    /// One `ghost_select!` poll: `None` while the channel is empty but connected.
+    #[doc(hidden)]
+    pub fn poll_select(&self, token: &impl GhostBorrow<'brand>) -> Option<Result<T, RecvError>> {
+        match self.try_recv(token) {
+            Ok(t) => Some(Ok(t)),
+            Err(TryRecvError::Disconnected) => Some(Err(RecvError)),
+            Err(TryRecvError::Empty) => None,
+        }
+    }
+}
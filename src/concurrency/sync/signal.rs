@@ -0,0 +1,60 @@
+//! Epoch-based wake-up point shared by the blocking primitives of this module.
+
+use super::{wait_on_u32, wake_all_u32};
+use core::sync::atomic::{fence, AtomicU32, Ordering};
+
+/// A wake-up point for threads waiting on some condition.
+///
+/// Waiters register, read the epoch, re-check their condition and sleep on the epoch;
+/// notifiers bump it only when someone is registered. The `SeqCst` fences make sure
+/// either the notifier sees the registration or the waiter sees the new state.
+pub(crate) struct Signal {
+    epoch: AtomicU32,
+    waiters: AtomicU32,
+}
+
+impl Signal {
+    pub(crate) const fn new() -> Self {
+        Self {
+            epoch: AtomicU32::new(0),
+            waiters: AtomicU32::new(0),
+        }
+    }
+
+    /// Registers a waiter and returns the epoch to sleep on.
+    #[inline]
+    pub(crate) fn register(&self) -> u32 {
+        self.waiters.fetch_add(1, Ordering::Relaxed);
+        fence(Ordering::SeqCst);
+        self.epoch.load(Ordering::Acquire)
+    }
+
+    #[inline]
+    pub(crate) fn unregister(&self) {
+        self.waiters.fetch_sub(1, Ordering::Relaxed);
+    }
+
+    /// Sleeps until the epoch moves past `seen`, the value returned by `register`.
+    #[inline]
+    pub(crate) fn wait(&self, seen: u32) {
+        wait_on_u32(&self.epoch, seen);
+    }
+
+    /// Sleeps while `blocked` holds, returning at the next notification (or spuriously).
+    pub(crate) fn wait_while(&self, blocked: impl FnOnce() -> bool) {
+        let seen = self.register();
+        if blocked() {
+            self.wait(seen);
+        }
+        self.unregister();
+    }
+
+    #[inline]
+    pub(crate) fn notify(&self) {
+        fence(Ordering::SeqCst);
+        if self.waiters.load(Ordering::Relaxed) != 0 {
+            self.epoch.fetch_add(1, Ordering::Release);
+            wake_all_u32(&self.epoch);
+        }
+    }
+}
@@ -94,6 +94,157 @@ fn test_ghost_barrier() {
     });
 }
 
+#[test]
+fn test_ghost_barrier_reuse_elects_one_leader_per_generation() {
+    GhostToken::new(|token| {
+        const THREADS: usize = 4;
+        const ROUNDS: usize = 200;
+        let barrier = GhostBarrier::new(THREADS);
+        let leaders = AtomicUsize::new(0);
+        let phase = AtomicUsize::new(0);
+        let (read, _) = token.split_immutable();
+
+        thread::scope(|s| {
+            for _ in 0..THREADS {
+                let (barrier, leaders, phase) = (&barrier, &leaders, &phase);
+                s.spawn(move || {
+                    for round in 0..ROUNDS {
+                        assert_eq!(phase.load(Ordering::SeqCst) / THREADS, round);
+                        phase.fetch_add(1, Ordering::SeqCst);
+                        if barrier.wait(&read).is_leader() {
+                            leaders.fetch_add(1, Ordering::SeqCst);
+                        }
+                        // No thread starts the next round before all finished this one.
+                        assert!(phase.load(Ordering::SeqCst) >= (round + 1) * THREADS);
+                        barrier.wait(&read);
+                    }
+                });
+            }
+        });
+        assert_eq!(leaders.load(Ordering::SeqCst), ROUNDS);
+    });
+}
+
+#[test]
+fn test_ghost_latch_releases_waiters_at_zero() {
+    GhostToken::new(|token| {
+        let latch = GhostLatch::new(3);
+        let done = AtomicUsize::new(0);
+        let (read, _) = token.split_immutable();
+        assert!(!latch.wait_timeout(&read, Duration::from_millis(10)));
+
+        thread::scope(|s| {
+            for _ in 0..2 {
+                let (latch, done) = (&latch, &done);
+                s.spawn(move || {
+                    latch.wait(&read);
+                    assert_eq!(done.load(Ordering::SeqCst), 3);
+                });
+            }
+            for _ in 0..3 {
+                let (latch, done) = (&latch, &done);
+                s.spawn(move || {
+                    done.fetch_add(1, Ordering::SeqCst);
+                    latch.count_down();
+                });
+            }
+        });
+        assert!(latch.try_wait());
+        assert_eq!(latch.count(), 0);
+        assert!(latch.wait_timeout(&read, Duration::ZERO));
+
+        let latch = GhostLatch::new(2);
+        latch.count_down_by(2);
+        latch.wait(&token);
+        let over = std::panic::catch_unwind(|| latch.count_down());
+        assert!(over.is_err());
+    });
+}
+
+#[test]
+fn test_ghost_event_set_wait_and_reset() {
+    GhostToken::new(|token| {
+        let event = GhostEvent::new();
+        let ready = AtomicUsize::new(0);
+        let (read, _) = token.split_immutable();
+        assert!(!event.is_set());
+        assert!(!event.wait_timeout(&read, Duration::from_millis(10)));
+
+        thread::scope(|s| {
+            for _ in 0..3 {
+                let (event, ready) = (&event, &ready);
+                s.spawn(move || {
+                    event.wait(&read);
+                    assert_eq!(ready.load(Ordering::Relaxed), 42);
+                });
+            }
+            thread::sleep(Duration::from_millis(10));
+            ready.store(42, Ordering::Relaxed);
+            event.set();
+        });
+        event.set();
+        assert!(event.is_set());
+        event.wait(&token);
+
+        // Once reset, waits block again until the next `set`.
+        event.reset();
+        assert!(!event.wait_timeout(&read, Duration::from_millis(10)));
+        thread::scope(|s| {
+            let waiter = s.spawn(|| event.wait_timeout(&read, Duration::from_secs(10)));
+            thread::sleep(Duration::from_millis(10));
+            event.set();
+            assert!(waiter.join().unwrap());
+        });
+    });
+}
+
+#[test]
+fn test_ghost_semaphore_bounds_concurrency() {
+    GhostToken::new(|token| {
+        let semaphore = GhostSemaphore::new(3);
+        let (active, peak) = (AtomicUsize::new(0), AtomicUsize::new(0));
+        let (read, _) = token.split_immutable();
+
+        thread::scope(|s| {
+            for i in 0..8 {
+                let (semaphore, active, peak) = (&semaphore, &active, &peak);
+                s.spawn(move || {
+                    for _ in 0..50 {
+                        // Every other thread takes two permits at once.
+                        let permit = semaphore.acquire_many(&read, 1 + i % 2);
+                        let n = permit.count() as usize;
+                        let now = active.fetch_add(n, Ordering::SeqCst) + n;
+                        peak.fetch_max(now, Ordering::SeqCst);
+                        thread::yield_now();
+                        active.fetch_sub(n, Ordering::SeqCst);
+                    }
+                });
+            }
+        });
+        assert!(peak.load(Ordering::SeqCst) <= 3);
+        assert_eq!(semaphore.available_permits(), 3);
+
+        let held = semaphore.acquire_many(&token, 2);
+        assert!(semaphore.try_acquire_many(&token, 2).is_none());
+        let one = semaphore.try_acquire(&token).expect("one permit left");
+        assert!(semaphore.try_acquire(&token).is_none());
+        one.forget();
+        drop(held);
+        assert_eq!(semaphore.available_permits(), 2);
+
+        // A parked acquirer is woken by a release.
+        let all = semaphore.acquire_many(&token, 2);
+        thread::scope(|s| {
+            let waiter = s.spawn(|| semaphore.acquire_many(&read, 3).count());
+            thread::sleep(Duration::from_millis(10));
+            semaphore.release(1);
+            drop(all);
+            assert_eq!(waiter.join().unwrap(), 3);
+        });
+        assert_eq!(semaphore.available_permits(), 3);
+    });
+}
+
 #[test]
 fn test_wait_on_u32_wake_existing() {
     // Porting the existing test from mod.rs
@@ -116,3 +267,355 @@ fn test_wait_on_u32_wake_existing() {
     let value = handle.join().unwrap();
     assert_eq!(value, 1);
 }
+
+#[test]
+fn test_wait_on_bool_and_usize_sleep_until_woken() {
+    let flag = AtomicBool::new(false);
+    let count = AtomicUsize::new(0);
+
+    thread::scope(|s| {
+        let waiter = s.spawn(|| {
+            while !flag.load(Ordering::SeqCst) {
+                wait_on_bool(&flag, false);
+            }
+            while count.load(Ordering::SeqCst) == 0 {
+                wait_on_usize(&count, 0);
+            }
+            count.load(Ordering::SeqCst)
+        });
+
+        thread::sleep(Duration::from_millis(20));
+        flag.store(true, Ordering::SeqCst);
+        wake_one_bool(&flag);
+        thread::sleep(Duration::from_millis(20));
+        count.store(1 << 40, Ordering::SeqCst);
+        wake_all_usize(&count);
+
+        assert_eq!(waiter.join().unwrap(), 1 << 40);
+    });
+}
+
+#[test]
+fn test_timed_waits_time_out_and_wake() {
+    let word = AtomicU32::new(0);
+    let flag = AtomicBool::new(false);
+    let count = AtomicUsize::new(0);
+    let timeout = Duration::from_millis(10);
+
+    let start = std::time::Instant::now();
+    while wait_on_u32_timeout(&word, 0, timeout) == WaitResult::Woken {}
+    assert!(start.elapsed() >= timeout);
+    assert!(wait_on_bool_timeout(&flag, false, Duration::ZERO).timed_out());
+    while !wait_on_usize_timeout(&count, 0, timeout).timed_out() {}
+
+    thread::scope(|s| {
+        let waiter = s.spawn(|| {
+            while word.load(Ordering::SeqCst) == 0 {
+                wait_on_u32_timeout(&word, 0, Duration::from_secs(10));
+            }
+        });
+        thread::sleep(Duration::from_millis(20));
+        word.store(1, Ordering::SeqCst);
+        wake_all_u32(&word);
+        waiter.join().unwrap();
+    });
+    assert!(start.elapsed() < Duration::from_secs(5));
+}
+
+#[test]
+fn test_ghost_rwlock_readers_share_and_writer_excludes() {
+    use crate::GhostCell;
+
+    GhostToken::new(|token| {
+        let lock = GhostRwLock::new(token);
+        let cell = GhostCell::new(0u64);
+
+        let r1 = lock.read();
+        let r2 = lock.read();
+        assert_eq!(*cell.borrow(&*r1) + *cell.borrow(&*r2), 0);
+        assert!(lock.try_write().is_none());
+        let up = lock.try_upgradable_read().unwrap();
+        assert!(lock.try_upgradable_read().is_none());
+        drop(r1);
+        let Err(up) = up.try_upgrade() else {
+            panic!("a reader is still present");
+        };
+        drop(r2);
+        let mut w = up.try_upgrade().ok().unwrap();
+        *cell.borrow_mut(&mut *w) = 5;
+        assert!(lock.try_read().is_none());
+        drop(w);
+
+        thread::scope(|s| {
+            for _ in 0..4 {
+                s.spawn(|| {
+                    for _ in 0..200 {
+                        *cell.borrow_mut(&mut *lock.write()) += 1;
+                        let r = lock.read();
+                        assert!(*cell.borrow(&*r) >= 5);
+                    }
+                });
+            }
+            s.spawn(|| {
+                for _ in 0..100 {
+                    let up = lock.upgradable_read();
+                    let before = *cell.borrow(&*up);
+                    let mut w = up.upgrade();
+                    assert_eq!(*cell.borrow(&*w), before);
+                    *cell.borrow_mut(&mut *w) += 1;
+                }
+            });
+        });
+        assert_eq!(*cell.borrow(&*lock.read()), 5 + 4 * 200 + 100);
+    });
+}
+
+#[test]
+fn test_ghost_rwlock_policies() {
+    GhostToken::new(|token| {
+        let lock = GhostRwLock::with_policy(token, RwLockPolicy::ReaderPreferred);
+        assert_eq!(lock.policy(), RwLockPolicy::ReaderPreferred);
+        let _r = lock.read();
+
+        thread::scope(|s| {
+            let writer = s.spawn(|| drop(lock.write()));
+            thread::sleep(Duration::from_millis(20));
+            // A waiting writer does not hold back new readers under this policy.
+            assert!(lock.try_read().is_some());
+            drop(_r);
+            writer.join().unwrap();
+        });
+    });
+
+    GhostToken::new(|token| {
+        let lock = GhostRwLock::new(token);
+        assert_eq!(lock.policy(), RwLockPolicy::WriterPreferred);
+        let r = lock.read();
+
+        thread::scope(|s| {
+            let writer = s.spawn(|| drop(lock.write()));
+            thread::sleep(Duration::from_millis(20));
+            assert!(lock.try_read().is_none());
+            drop(r);
+            writer.join().unwrap();
+            assert!(lock.try_read().is_some());
+        });
+    });
+}
+
+#[test]
+fn test_ghost_seqlock_reads_are_never_torn() {
+    #[derive(Clone, Copy)]
+    struct Snapshot {
+        generation: u64,
+        doubled: u64,
+        tag: [u8; 16],
+    }
+
+    let lock: GhostSeqLock<'_, Snapshot> = GhostSeqLock::new(Snapshot {
+        generation: 0,
+        doubled: 0,
+        tag: [0; 16],
+    });
+    let done = AtomicBool::new(false);
+
+    thread::scope(|s| {
+        for _ in 0..3 {
+            s.spawn(|| {
+                let mut last = 0;
+                while !done.load(Ordering::Relaxed) {
+                    let snap = lock.read();
+                    assert_eq!(snap.doubled, snap.generation * 2);
+                    let tag = snap.generation.to_le_bytes()[0];
+                    assert!(snap.tag.iter().all(|&b| b == tag));
+                    assert!(snap.generation >= last);
+                    last = snap.generation;
+                }
+            });
+        }
+        let writers: Vec<_> = (0..2)
+            .map(|_| {
+                s.spawn(|| {
+                    for _ in 0..5_000 {
+                        lock.update(|snap| {
+                            snap.generation += 1;
+                            snap.doubled = snap.generation * 2;
+                            snap.tag = [snap.generation.to_le_bytes()[0]; 16];
+                        });
+                    }
+                })
+            })
+            .collect();
+        for w in writers {
+            w.join().unwrap();
+        }
+        done.store(true, Ordering::Relaxed);
+    });
+
+    assert_eq!(lock.read().generation, 10_000);
+    assert_eq!(lock.version(), 20_000);
+}
+
+#[test]
+fn test_ghost_seqlock_write_and_panicking_update() {
+    let mut lock = GhostSeqLock::new((1u32, 2u32));
+    let v0 = lock.version();
+    lock.write((3, 4));
+    assert_eq!(lock.try_read(), Some((3, 4)));
+    assert_ne!(lock.version(), v0);
+
+    let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+        lock.update(|pair| {
+            pair.0 = 99;
+            panic!("abandoned update");
+        })
+    }));
+    assert!(result.is_err());
+    // The write ended on unwind and the value is unchanged.
+    assert_eq!(lock.try_read(), Some((3, 4)));
+
+    lock.get_mut().1 = 40;
+    assert_eq!(lock.update(|pair| pair.0 + pair.1), 43);
+    assert_eq!(lock.into_inner(), (3, 40));
+}
+
+#[test]
+fn test_ghost_rcu_cell_readers_keep_snapshots() {
+    let routes: GhostRcuCell<'_, Vec<u32>> = GhostRcuCell::new(vec![1, 2, 3]);
+    let before = routes.load();
+    thread::scope(|s| {
+        let writer = s.spawn(|| routes.store(vec![4, 5]));
+        // The writer waits for this snapshot, which stays valid meanwhile.
+        thread::sleep(Duration::from_millis(20));
+        assert!(!writer.is_finished());
+        assert_eq!(*before, [1, 2, 3]);
+        drop(before);
+        writer.join().unwrap();
+    });
+    assert_eq!(*routes.load(), [4, 5]);
+
+    let old_len = routes.update(|v| (v.iter().map(|x| x * 10).collect(), v.len()));
+    assert_eq!(old_len, 2);
+    assert_eq!(routes.load_cloned(), [40, 50]);
+
+    let mut routes = routes;
+    routes.get_mut().push(60);
+    assert_eq!(routes.into_inner(), [40, 50, 60]);
+}
+
+#[test]
+fn test_ghost_rcu_cell_concurrent_updates_and_drops() {
+    struct Counted<'a>(u64, &'a AtomicUsize);
+    impl Drop for Counted<'_> {
+        fn drop(&mut self) {
+            self.1.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    let drops = AtomicUsize::new(0);
+    let cell = GhostRcuCell::new(Counted(0, &drops));
+    let done = AtomicBool::new(false);
+    thread::scope(|s| {
+        for _ in 0..2 {
+            s.spawn(|| {
+                let mut last = 0;
+                while !done.load(Ordering::Relaxed) {
+                    let snap = cell.load();
+                    assert!(snap.0 >= last);
+                    last = snap.0;
+                }
+            });
+        }
+        let writers: Vec<_> = (0..3)
+            .map(|_| {
+                s.spawn(|| {
+                    for _ in 0..1000 {
+                        cell.update(|c| (Counted(c.0 + 1, c.1), ()));
+                    }
+                })
+            })
+            .collect();
+        for w in writers {
+            w.join().unwrap();
+        }
+        done.store(true, Ordering::Relaxed);
+    });
+    assert_eq!(cell.load().0, 3000);
+    // Replaced values are dropped by their writers, before `update` returns.
+    assert_eq!(drops.load(Ordering::Relaxed), 3000);
+    drop(cell);
+    assert_eq!(drops.load(Ordering::Relaxed), 3001);
+}
+
+#[test]
+fn test_ghost_flat_combiner_serializes_operations() {
+    GhostToken::new(|token| {
+        let hits = crate::GhostCell::new(0usize);
+        let combiner = GhostFlatCombiner::new(Vec::new(), token);
+        thread::scope(|s| {
+            for t in 0..8 {
+                let (combiner, hits) = (&combiner, &hits);
+                s.spawn(move || {
+                    for i in 0..500 {
+                        let index = combiner.apply(|log: &mut Vec<usize>, token| {
+                            *hits.borrow_mut(token) += 1;
+                            log.push(t * 500 + i);
+                            log.len() - 1
+                        });
+                        assert!(index < 4000);
+                    }
+                });
+            }
+        });
+        let (mut log, token) = combiner.into_inner();
+        assert_eq!(*hits.borrow(&token), 4000);
+        log.sort_unstable();
+        assert_eq!(log, (0..4000).collect::<Vec<_>>());
+    });
+}
+
+#[test]
+fn test_ghost_flat_combiner_reports_panics_to_the_caller() {
+    GhostToken::new(|token| {
+        let mut combiner = GhostFlatCombiner::new(0u32, token);
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            combiner.apply(|_: &mut u32, _| -> u32 { panic!("boom") })
+        }));
+        assert!(result.is_err());
+        assert_eq!(
+            combiner.apply(|n, _| {
+                *n += 1;
+                *n
+            }),
+            1
+        );
+        *combiner.get_mut() += 1;
+        assert_eq!(combiner.into_inner().0, 2);
+    });
+}
+
+#[test]
+fn test_retire_list_frees_a_batch_once_its_readers_leave() {
+    use core::sync::atomic::{AtomicUsize, Ordering};
+
+    struct Counted<'a>(&'a AtomicUsize);
+    impl Reclaim for Counted<'_> {
+        unsafe fn reclaim(self) {
+            self.0.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    let freed = AtomicUsize::new(0);
+    let grace = GracePeriod::new();
+    let retired = RetireList::with_batch(1);
+    let section = grace.read();
+    retired.retire(&grace, [Counted(&freed)]);
+    retired.try_reclaim(&grace);
+    assert_eq!(freed.load(Ordering::Relaxed), 0);
+    assert!(!retired.is_empty());
+
+    drop(section);
+    retired.try_reclaim(&grace);
+    assert_eq!(freed.load(Ordering::Relaxed), 1);
+    assert!(retired.is_empty());
+}
@@ -0,0 +1,269 @@
+//! `TokenRegistry` — branded per-thread scratch state.
+//!
+//! Parallel graph kernels keep per-worker state across tasks: a local frontier, a
+//! visited buffer, partial sums. A registry stores one value per thread, indexed by
+//! [`current_thread_slot`], so fetching it in a task is an index into a table that is
+//! only ever appended to: no hashing and no locks after a thread's first access.
+//!
+//! Access takes a token of the registry's brand, so the state stays tied to the
+//! branded data it describes; once the parallel phase is over, `&mut self` methods
+//! visit every thread's value to merge the results.
+//!
+//! ```
+//! use halo::concurrency::pool::GhostThreadPool;
+//! use halo::concurrency::TokenRegistry;
+//! use halo::GhostToken;
+//!
+//! GhostToken::new(|token| {
+//!     let mut frontiers = TokenRegistry::new();
+//!     GhostThreadPool::new(4).scope(&token, |s| {
+//!         for v in 0..100usize {
+//!             let frontiers = &frontiers;
+//!             s.spawn(move |ctx| {
+//!                 frontiers.with(&ctx.token(), Vec::new, |local: &mut Vec<usize>| local.push(v));
+//!             });
+//!         }
+//!     });
+//!     let mut all: Vec<usize> = frontiers.iter_mut().flat_map(core::mem::take).collect();
+//!     all.sort_unstable();
+//!     assert_eq!(all, (0..100).collect::<Vec<_>>());
+//! });
+//! ```
+
+use core::cell::{Cell, UnsafeCell};
+use core::marker::PhantomData;
+use core::ptr;
+use core::sync::atomic::{AtomicPtr, Ordering};
+
+use crate::concurrency::{current_thread_slot, CachePadded};
+use crate::token::traits::GhostBorrow;
+
+/// Bucket `b` holds slots `2^b - 1 .. 2^(b+1) - 1`, so the table grows by doubling
+/// without ever moving a value.
+const BUCKETS: usize = usize::BITS as usize;
+
+struct Entry<T> {
+    value: UnsafeCell<Option<T>>,
+    /// Set while the owning thread is inside [`TokenRegistry::with`].
+    borrowed: Cell<bool>,
+}
+
+/// Clears an entry's borrow flag, also when `f` unwinds.
+struct Release<'a>(&'a Cell<bool>);
+
+impl Drop for Release<'_> {
+    fn drop(&mut self) {
+        self.0.set(false);
+    }
+}
+
+/// One value of type `T` per thread, for branded per-worker scratch state.
+///
+/// A thread's value is created on its first [`with`](Self::with) and outlives the
+/// thread: a thread that later takes over the same slot finds it again, and
+/// [`iter_mut`](Self::iter_mut) and [`into_values`](Self::into_values) see it.
+pub struct TokenRegistry<'brand, T> {
+    buckets: [AtomicPtr<CachePadded<Entry<T>>>; BUCKETS],
+    _brand: PhantomData<fn(&'brand ()) -> &'brand ()>,
+}
+
+// SAFETY: an entry is only accessed by the thread holding its slot, or through
+// `&mut self`; values move between threads when slots are recycled, hence `T: Send`.
+unsafe impl<'brand, T: Send> Sync for TokenRegistry<'brand, T> {}
+unsafe impl<'brand, T: Send> Send for TokenRegistry<'brand, T> {}
+
+#[inline]
+fn locate(slot: usize) -> (usize, usize) {
+    let n = slot + 1;
+    let bucket = n.ilog2() as usize;
+    (bucket, n - (1 << bucket))
+}
+
+impl<'brand, T> TokenRegistry<'brand, T> {
+    /// Creates an empty registry. Nothing is allocated until a thread first uses it.
+    pub const fn new() -> Self {
+        Self {
+            buckets: [const { AtomicPtr::new(ptr::null_mut()) }; BUCKETS],
+            _brand: PhantomData,
+        }
+    }
+
+    /// Runs `f` on the calling thread's value, creating it with `init` on first use.
+    ///
+    /// # Panics
+    /// Panics if called from inside `f` on the same registry.
+    pub fn with<R>(
+        &self,
+        _token: &impl GhostBorrow<'brand>,
+        init: impl FnOnce() -> T,
+        f: impl FnOnce(&mut T) -> R,
+    ) -> R {
+        let entry = self.entry(current_thread_slot());
+        assert!(
+            !entry.borrowed.replace(true),
+            "TokenRegistry::with called re-entrantly"
+        );
+        let _release = Release(&entry.borrowed);
+        // SAFETY: the slot is held by this thread alone, and the flag rules out a
+        // second borrow from `f`.
+        let value = unsafe { &mut *entry.value.get() };
+        f(value.get_or_insert_with(init))
+    }
+
+    /// Returns the entry for `slot`, allocating its bucket if needed.
+    fn entry(&self, slot: usize) -> &Entry<T> {
+        let (bucket, index) = locate(slot);
+        let mut entries = self.buckets[bucket].load(Ordering::Acquire);
+        if entries.is_null() {
+            let fresh = Self::alloc_bucket(bucket);
+            entries = match self.buckets[bucket].compare_exchange(
+                ptr::null_mut(),
+                fresh,
+                Ordering::AcqRel,
+                Ordering::Acquire,
+            ) {
+                Ok(_) => fresh,
+                Err(current) => {
+                    // SAFETY: `fresh` was never published.
+                    unsafe { Self::free_bucket(fresh, bucket) };
+                    current
+                }
+            };
+        }
+        // SAFETY: bucket `b` holds `2^b` entries and `index < 2^b`.
+        unsafe { &*entries.add(index) }
+    }
+
+    fn alloc_bucket(bucket: usize) -> *mut CachePadded<Entry<T>> {
+        let entries: Box<[CachePadded<Entry<T>>]> = (0..1usize << bucket)
+            .map(|_| {
+                CachePadded::new(Entry {
+                    value: UnsafeCell::new(None),
+                    borrowed: Cell::new(false),
+                })
+            })
+            .collect();
+        Box::into_raw(entries).cast()
+    }
+
+    /// # Safety
+    /// `entries` must come from `alloc_bucket(bucket)` and be unreachable otherwise.
+    unsafe fn free_bucket(entries: *mut CachePadded<Entry<T>>, bucket: usize) {
+        let slice = ptr::slice_from_raw_parts_mut(entries, 1 << bucket);
+        drop(unsafe { Box::from_raw(slice) });
+    }
+
+    /// Iterates over the values of every thread that has used the registry.
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = &mut T> + '_ {
+        self.entries_mut()
+            .filter_map(|entry| entry.value.get_mut().as_mut())
+    }
+
+    /// Consumes the registry, returning every thread's value.
+    pub fn into_values(mut self) -> Vec<T> {
+        self.entries_mut()
+            .filter_map(|entry| entry.value.get_mut().take())
+            .collect()
+    }
+
+    fn entries_mut(&mut self) -> impl Iterator<Item = &mut Entry<T>> + '_ {
+        self.buckets
+            .iter_mut()
+            .enumerate()
+            .map(|(bucket, entries)| (bucket, *entries.get_mut()))
+            .filter(|(_, entries)| !entries.is_null())
+            .flat_map(|(bucket, entries)| {
+                // SAFETY: non-null buckets hold `2^bucket` entries, and `&mut self`
+                // excludes every other access.
+                unsafe { core::slice::from_raw_parts_mut(entries, 1 << bucket) }
+            })
+            .map(|entry| &mut **entry)
+    }
+}
+
+impl<'brand, T> Default for TokenRegistry<'brand, T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<'brand, T> Drop for TokenRegistry<'brand, T> {
+    fn drop(&mut self) {
+        for (bucket, entries) in self.buckets.iter_mut().enumerate() {
+            let entries = *entries.get_mut();
+            if !entries.is_null() {
+                // SAFETY: `&mut self` excludes every other access.
+                unsafe { Self::free_bucket(entries, bucket) };
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::GhostToken;
+
+    #[test]
+    fn test_token_registry_per_thread_values() {
+        GhostToken::new(|token| {
+            let mut registry = TokenRegistry::new();
+            let (read, _) = token.split_immutable();
+            std::thread::scope(|s| {
+                for t in 0..8u64 {
+                    let registry = &registry;
+                    s.spawn(move || {
+                        for i in 0..100 {
+                            registry.with(
+                                &read,
+                                || (0, 0),
+                                |(sum, n): &mut (u64, u32)| {
+                                    *sum += t * 100 + i;
+                                    *n += 1;
+                                },
+                            );
+                        }
+                    });
+                }
+            });
+            // Slots of exited threads are reused, so threads may share a value, but
+            // nothing is lost.
+            let total: (u64, u32) = registry
+                .iter_mut()
+                .fold((0, 0), |acc, v| (acc.0 + v.0, acc.1 + v.1));
+            assert_eq!(total, ((0..800).sum(), 800));
+            registry.with(&token, || (0, 0), |v| v.1 += 1);
+            assert_eq!(registry.into_values().iter().map(|v| v.1).sum::<u32>(), 801);
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "re-entrantly")]
+    fn test_token_registry_rejects_reentrant_with() {
+        GhostToken::new(|token| {
+            let registry = TokenRegistry::new();
+            registry.with(&token, || 0, |_| registry.with(&token, || 0, |v| *v += 1));
+        });
+    }
+
+    #[test]
+    fn test_thread_slots_are_unique_among_live_threads() {
+        let barrier = std::sync::Barrier::new(6);
+        let mut slots: Vec<usize> = std::thread::scope(|s| {
+            let handles: Vec<_> = (0..6)
+                .map(|_| {
+                    s.spawn(|| {
+                        let slot = current_thread_slot();
+                        barrier.wait();
+                        assert_eq!(current_thread_slot(), slot);
+                        slot
+                    })
+                })
+                .collect();
+            handles.into_iter().map(|h| h.join().unwrap()).collect()
+        });
+        slots.sort_unstable();
+        slots.dedup();
+        assert_eq!(slots.len(), 6);
+    }
+}
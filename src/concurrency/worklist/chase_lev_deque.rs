@@ -1,41 +1,160 @@
-//! A fixed-capacity Chase–Lev work-stealing deque (indices-only).
+//! A growable Chase–Lev work-stealing deque (indices-only).
 //!
 //! Properties:
 //! - Single owner: `push_bottom` / `pop_bottom`
-//! - Multiple stealers: `steal`
-//! - Fixed capacity, power-of-two ring buffer
+//! - Multiple stealers: `steal`, and `steal_batch` / `steal_batch_and_pop` to move up
+//!   to half of a victim's items into the stealer's own deque at once
+//! - Power-of-two ring buffer that doubles when a push finds it full
 //!
 //! This implementation stores only `usize` items and uses atomics for the buffer
 //! as well as `top`/`bottom` to avoid UB from concurrent reads.
+//!
+//! Growth copies the live items into a buffer twice the size and swaps the buffer
+//! pointer. Stealers read the buffer inside a read section of the deque's grace
+//! period, so the old buffer is retired rather than freed, and the owner frees it on a
+//! later push or pop once every stealer that could still be reading it has left.
 
-use core::sync::atomic::{fence, Ordering};
+use core::ptr;
+use core::sync::atomic::Ordering;
 
+use crate::concurrency::atomic::primitive::{fence, AtomicBool, AtomicPtr};
 use crate::concurrency::atomic::GhostAtomicUsize;
+use crate::concurrency::sync::{GracePeriod, Reclaim, RetireList};
 use crate::token::{GhostBorrowMut, ImmutableChild};
 
 use super::treiber_stack::NONE;
 
-/// A fixed-capacity Chase–Lev deque for indices.
+/// A power-of-two ring of slots, indexed modulo its length.
+struct Buffer<'brand> {
+    slots: Box<[GhostAtomicUsize<'brand>]>,
+}
+
+impl<'brand> Buffer<'brand> {
+    fn new(capacity: usize) -> Self {
+        Self {
+            slots: (0..capacity).map(|_| GhostAtomicUsize::new(NONE)).collect(),
+        }
+    }
+
+    #[inline]
+    fn capacity(&self) -> usize {
+        self.slots.len()
+    }
+
+    #[inline]
+    fn slot(&self, i: usize) -> &GhostAtomicUsize<'brand> {
+        // SAFETY: the length is a power of two, so the masked index is in bounds.
+        unsafe { self.slots.get_unchecked(i & (self.slots.len() - 1)) }
+    }
+}
+
+/// A growable Chase–Lev deque for indices.
 pub struct GhostChaseLevDeque<'brand> {
     top: GhostAtomicUsize<'brand>,
     bottom: GhostAtomicUsize<'brand>,
-    buf: Vec<GhostAtomicUsize<'brand>>,
-    mask: usize,
+    /// The current buffer, owned by the deque; replaced only by the owner.
+    buf: AtomicPtr<Buffer<'brand>>,
+    grace: GracePeriod,
+    /// Buffers replaced by growth, kept alive for stealers still reading them.
+    retired: RetireList<Retired<'brand>>,
+    /// Set by the owner while `retired` holds buffers it has not freed yet.
+    retiring: AtomicBool,
+}
+
+/// A buffer replaced by growth, freed once no stealer can be reading it.
+struct Retired<'brand>(*mut Buffer<'brand>);
+
+// SAFETY: the buffer holds only atomics, and is freed by whichever thread reclaims it.
+unsafe impl Send for Retired<'_> {}
+
+impl Reclaim for Retired<'_> {
+    unsafe fn reclaim(self) {
+        // SAFETY: the pointer came from `Box::into_raw`, and the caller guarantees no
+        // stealer still reads the buffer.
+        drop(unsafe { Box::from_raw(self.0) });
+    }
 }
 
 impl<'brand> GhostChaseLevDeque<'brand> {
-    /// Creates a new deque with `capacity` entries.
+    /// Creates a new deque with an initial `capacity` entries.
     ///
-    /// `capacity` must be a power of two.
+    /// `capacity` must be a power of two. The buffer doubles whenever a push finds it
+    /// full.
     pub fn new(capacity: usize) -> Self {
         assert!(capacity.is_power_of_two());
         assert!(capacity != 0);
-        let buf = (0..capacity).map(|_| GhostAtomicUsize::new(NONE)).collect();
         Self {
             top: GhostAtomicUsize::new(0),
             bottom: GhostAtomicUsize::new(0),
-            buf,
-            mask: capacity - 1,
+            buf: AtomicPtr::new(Box::into_raw(Box::new(Buffer::new(capacity)))),
+            grace: GracePeriod::new(),
+            // Growth is rare, so each old buffer gets its own grace period.
+            retired: RetireList::with_batch(1),
+            retiring: AtomicBool::new(false),
+        }
+    }
+
+    /// Returns the current buffer capacity.
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        let _section = self.grace.read();
+        // Pairs with the fence in `grow`, as in `steal`.
+        fence(Ordering::SeqCst);
+        self.buffer().capacity()
+    }
+
+    #[inline]
+    fn buffer(&self) -> &Buffer<'brand> {
+        // SAFETY: only the owner retires buffers, so one it loaded stays alive until it
+        // grows again, and stealers load it inside a read section of `grace`.
+        unsafe { &*self.buf.load(Ordering::Acquire) }
+    }
+
+    /// Returns a buffer with room for `len` items, growing it if needed. Owner-only;
+    /// `t..b` are the live indices to carry over.
+    #[inline]
+    fn reserve(&self, t: usize, b: usize, len: usize) -> &Buffer<'brand> {
+        let buf = self.buffer();
+        if len <= buf.capacity() {
+            buf
+        } else {
+            self.grow(t, b, len)
+        }
+    }
+
+    #[cold]
+    fn grow(&self, t: usize, b: usize, len: usize) -> &Buffer<'brand> {
+        let old = self.buffer();
+        let new = Buffer::new(len.next_power_of_two().max(old.capacity() * 2));
+        for i in t..b {
+            new.slot(i).store(old.slot(i).load(Ordering::Relaxed), Ordering::Relaxed);
+        }
+        let new = Box::into_raw(Box::new(new));
+        // Release: stealers that load the new pointer see the copied items.
+        let old = self.buf.swap(new, Ordering::Release);
+        // A stealer registers as a reader and issues a SeqCst fence before it loads the
+        // buffer. Pairing with that fence, either it loads `new` or the grace period
+        // started below sees its registration, so `old` is only freed once it is gone.
+        fence(Ordering::SeqCst);
+        self.retired.retire(&self.grace, [Retired(old)]);
+        self.retiring.store(true, Ordering::Relaxed);
+        // SAFETY: `new` stays alive until the owner grows the deque again.
+        unsafe { &*new }
+    }
+
+    /// Frees retired buffers whose stealers have left. Owner-only.
+    #[inline]
+    fn reclaim_retired(&self) {
+        if self.retiring.load(Ordering::Relaxed) {
+            self.reclaim_retired_slow();
+        }
+    }
+
+    #[cold]
+    fn reclaim_retired_slow(&self) {
+        self.retired.try_reclaim(&self.grace);
+        if self.retired.is_empty() {
+            self.retiring.store(false, Ordering::Relaxed);
         }
     }
 
@@ -47,31 +166,25 @@ impl<'brand> GhostChaseLevDeque<'brand> {
         self.bottom.store(0, Ordering::Relaxed);
     }
 
-    /// Attempts to push `x` to the bottom. Owner-only.
-    ///
-    /// Returns `false` if the deque is full.
-    pub fn push_bottom<T: GhostBorrowMut<'brand>>(&self, token: &T, x: usize) -> bool {
+    /// Pushes `x` to the bottom, growing the buffer if it is full. Owner-only.
+    pub fn push_bottom<T: GhostBorrowMut<'brand>>(&self, token: &T, x: usize) {
         let _ = token;
         debug_assert!(x != NONE);
         let b = self.bottom.load(Ordering::Relaxed);
         let t = self.top.load(Ordering::Acquire);
-        if b < t {
-            // Should be impossible for monotone counters; treat as full/invalid.
-            return false;
-        }
-        if b - t >= self.buf.len() {
-            return false;
-        }
-        self.buf[b & self.mask].store(x, Ordering::Relaxed);
+        debug_assert!(b >= t, "top passed bottom");
+        self.reclaim_retired();
+        let buf = self.reserve(t, b, b - t + 1);
+        buf.slot(b).store(x, Ordering::Relaxed);
         // Publish the element before making it stealable via `bottom`.
         fence(Ordering::Release);
         self.bottom.store(b + 1, Ordering::Release);
-        true
     }
 
     /// Attempts to pop from the bottom. Owner-only.
     pub fn pop_bottom<T: GhostBorrowMut<'brand>>(&self, token: &T) -> Option<usize> {
         let _ = token;
+        self.reclaim_retired();
         // Load bottom first; if empty, avoid underflow.
         let b = self.bottom.load(Ordering::Relaxed);
         let t0 = self.top.load(Ordering::Acquire);
@@ -89,7 +202,7 @@ impl<'brand> GhostChaseLevDeque<'brand> {
             return None;
         }
 
-        let x = self.buf[b1 & self.mask].load(Ordering::Relaxed);
+        let x = self.buffer().slot(b1).load(Ordering::Relaxed);
         if t == b1 {
             // Last element: race with stealers.
             if self
@@ -109,6 +222,8 @@ impl<'brand> GhostChaseLevDeque<'brand> {
     /// Attempts to steal from the top. Multi-stealer.
     pub fn steal<'a>(&self, token: &ImmutableChild<'a, 'brand>) -> Option<usize> {
         let _ = token;
+        // Registered before the fence, see `grow`.
+        let _section = self.grace.read();
         loop {
             let t = self.top.load(Ordering::Acquire);
             fence(Ordering::SeqCst);
@@ -116,7 +231,9 @@ impl<'brand> GhostChaseLevDeque<'brand> {
             if t >= b {
                 return None;
             }
-            let x = self.buf[t & self.mask].load(Ordering::Relaxed);
+            // Load the buffer after `bottom`, so it is the one item `t` was pushed into
+            // or a newer copy.
+            let x = self.buffer().slot(t).load(Ordering::Relaxed);
             if self
                 .top
                 .compare_exchange(t, t + 1, Ordering::SeqCst, Ordering::Relaxed)
@@ -126,4 +243,123 @@ impl<'brand> GhostChaseLevDeque<'brand> {
             }
         }
     }
+
+    /// Steals items in parallel on rayon's pool until the deque is observed empty.
+    ///
+    /// This drains the deque from the top, so the owner may keep pushing and popping
+    /// meanwhile; whatever it pops is simply not yielded here.
+    #[cfg(feature = "rayon")]
+    pub fn par_steal<'a>(
+        &'a self,
+        token: &ImmutableChild<'a, 'brand>,
+    ) -> impl rayon::iter::ParallelIterator<Item = usize> + use<'a, 'brand> {
+        use rayon::prelude::*;
+        let token = *token;
+        rayon::iter::repeat(()).map(move |()| self.steal(&token)).while_some()
+    }
+
+    /// Steals up to half of the items (rounded up) from the top and pushes them onto
+    /// the bottom of `dst`, oldest first. Returns how many were moved.
+    ///
+    /// `dst` is the stealer's own deque, so `dst_token` must authorize its owner
+    /// operations; it may carry a different brand. `dst` grows to fit the batch up
+    /// front; fewer items move if other threads take them first.
+    ///
+    /// The owner pops from the bottom without a CAS while at least two items remain, so
+    /// a range of items cannot be claimed safely with a single CAS on `top`: the owner
+    /// could pop into it after the range was sized. Each item is therefore still claimed
+    /// with its own CAS, but the victim's size is read once, the items are written
+    /// straight into `dst`'s buffer, and they are published to `dst`'s stealers with a
+    /// single store of its `bottom`, instead of a `steal` + `push_bottom` pair per item.
+    pub fn steal_batch<'a, 'd, T: GhostBorrowMut<'d>>(
+        &self,
+        token: &ImmutableChild<'a, 'brand>,
+        dst: &GhostChaseLevDeque<'d>,
+        dst_token: &T,
+    ) -> usize {
+        let (_, moved) = self.steal_into(token, dst, dst_token, false);
+        moved
+    }
+
+    /// Like [`steal_batch`](Self::steal_batch), but returns the first stolen item
+    /// instead of pushing it onto `dst`.
+    pub fn steal_batch_and_pop<'a, 'd, T: GhostBorrowMut<'d>>(
+        &self,
+        token: &ImmutableChild<'a, 'brand>,
+        dst: &GhostChaseLevDeque<'d>,
+        dst_token: &T,
+    ) -> Option<usize> {
+        self.steal_into(token, dst, dst_token, true).0
+    }
+
+    fn steal_into<'a, 'd, T: GhostBorrowMut<'d>>(
+        &self,
+        token: &ImmutableChild<'a, 'brand>,
+        dst: &GhostChaseLevDeque<'d>,
+        dst_token: &T,
+        pop_first: bool,
+    ) -> (Option<usize>, usize) {
+        let _ = (token, dst_token);
+        // Only we push to `dst`; its stealers only raise `top`, which adds room.
+        let dst_b = dst.bottom.load(Ordering::Relaxed);
+        let dst_t = dst.top.load(Ordering::Acquire);
+        // Registered before the fences, see `grow`.
+        let _section = self.grace.read();
+
+        'retry: loop {
+            let mut t = self.top.load(Ordering::Acquire);
+            fence(Ordering::SeqCst);
+            let b = self.bottom.load(Ordering::Acquire);
+            if t >= b {
+                return (None, 0);
+            }
+            let limit = (b - t).div_ceil(2);
+            let dst_buf = dst.reserve(dst_t, dst_b, dst_b - dst_t + limit);
+            let mut first = None;
+            let mut moved = 0;
+            for claimed in 0..limit {
+                if claimed > 0 {
+                    // Re-check as `steal` does that item `t` still exists.
+                    fence(Ordering::SeqCst);
+                    if self.bottom.load(Ordering::Acquire) <= t {
+                        break;
+                    }
+                }
+                // Reload the buffer: the owner may have grown it since the last item.
+                let x = self.buffer().slot(t).load(Ordering::Relaxed);
+                if self
+                    .top
+                    .compare_exchange(t, t + 1, Ordering::SeqCst, Ordering::Relaxed)
+                    .is_err()
+                {
+                    if claimed == 0 {
+                        continue 'retry;
+                    }
+                    break;
+                }
+                t += 1;
+                if pop_first && claimed == 0 {
+                    first = Some(x);
+                } else {
+                    dst_buf.slot(dst_b + moved).store(x, Ordering::Relaxed);
+                    moved += 1;
+                }
+            }
+            if moved > 0 {
+                // Publish the items before making them stealable via `bottom`.
+                fence(Ordering::Release);
+                dst.bottom.store(dst_b + moved, Ordering::Release);
+            }
+            return (first, moved);
+        }
+    }
+}
+
+impl<'brand> Drop for GhostChaseLevDeque<'brand> {
+    fn drop(&mut self) {
+        let buf = self.buf.swap(ptr::null_mut(), Ordering::Relaxed);
+        // SAFETY: the current buffer came from `Box::into_raw` and nothing borrows it.
+        // Retired buffers are freed when `retired` is dropped after this.
+        drop(unsafe { Box::from_raw(buf) });
+    }
 }
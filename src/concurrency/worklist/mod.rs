@@ -7,4 +7,4 @@ pub mod chase_lev_deque;
 pub mod treiber_stack;
 
 pub use chase_lev_deque::GhostChaseLevDeque;
-pub use treiber_stack::GhostTreiberStack;
+pub use treiber_stack::{EliminationStats, GhostTreiberStack};
@@ -9,31 +9,101 @@
 //! - Correctness relies on the caller ensuring each index is pushed at most once
 //!   concurrently, or otherwise providing a safe reclamation strategy. For our
 //!   intended graph traversal use (visited bitmap ensures single push), that holds.
+//!
+//! Elimination backoff:
+//! - A stack built with [`GhostTreiberStack::with_elimination`] has a small array of
+//!   exchange slots. A push whose head CAS fails offers its index in a slot for a short
+//!   spin; a pop whose CAS fails scans the slots and takes any offer. Such a pair
+//!   completes without touching `head`, which relieves the hot spot under contention.
+//! - [`GhostTreiberStack::elimination_stats`] reports how often this happens, to size
+//!   the array: many expired offers suggest fewer slots (or fewer concurrent pops to
+//!   meet), while high head contention with few eliminations suggests more.
 
 use core::sync::atomic::Ordering;
 
 use crate::concurrency::atomic::GhostAtomicUsize;
+use crate::concurrency::{current_shard_index, CachePadded};
 use crate::token::GhostBorrow;
 
 /// Sentinel for an empty stack / null next pointer.
 pub const NONE: usize = usize::MAX;
 
+/// Elimination slot state: an offered index was taken by a pop.
+const TAKEN: usize = usize::MAX - 1;
+
+/// How long a push waits in an elimination slot for a pop to take its offer.
+const ELIMINATION_SPINS: usize = 64;
+
+/// Contention counters of a [`GhostTreiberStack`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct EliminationStats {
+    /// Push or pop attempts whose CAS on `head` failed.
+    pub head_contention: usize,
+    /// Push/pop pairs that exchanged an index through the elimination array.
+    pub eliminated: usize,
+    /// Push offers that expired without a pop taking them.
+    pub expired_offers: usize,
+}
+
 /// A branded lock-free stack of indices `0..capacity`.
 pub struct GhostTreiberStack<'brand> {
     head: GhostAtomicUsize<'brand>,
     next: Vec<GhostAtomicUsize<'brand>>,
+    /// Exchange slots holding `NONE`, `TAKEN` or an offered index.
+    slots: Box<[CachePadded<GhostAtomicUsize<'brand>>]>,
+    head_contention: GhostAtomicUsize<'brand>,
+    eliminated: GhostAtomicUsize<'brand>,
+    expired_offers: GhostAtomicUsize<'brand>,
 }
 
 impl<'brand> GhostTreiberStack<'brand> {
     /// Creates an empty stack with a fixed `capacity`.
     pub fn new(capacity: usize) -> Self {
+        Self::with_elimination(capacity, 0)
+    }
+
+    /// Creates an empty stack with a fixed `capacity` and `slots` elimination slots.
+    ///
+    /// With `slots == 0` this is [`new`](Self::new). A few slots (up to about the
+    /// number of contending threads) are usually enough.
+    pub fn with_elimination(capacity: usize, slots: usize) -> Self {
         let next = (0..capacity).map(|_| GhostAtomicUsize::new(NONE)).collect();
+        let slots = (0..slots)
+            .map(|_| CachePadded::new(GhostAtomicUsize::new(NONE)))
+            .collect();
         Self {
             head: GhostAtomicUsize::new(NONE),
             next,
+            slots,
+            head_contention: GhostAtomicUsize::new(0),
+            eliminated: GhostAtomicUsize::new(0),
+            expired_offers: GhostAtomicUsize::new(0),
         }
     }
 
+    /// Returns the number of elimination slots.
+    #[inline]
+    pub fn elimination_slots(&self) -> usize {
+        self.slots.len()
+    }
+
+    /// Returns the contention counters accumulated since creation or the last
+    /// [`reset_elimination_stats`](Self::reset_elimination_stats).
+    pub fn elimination_stats(&self) -> EliminationStats {
+        EliminationStats {
+            head_contention: self.head_contention.load(Ordering::Relaxed),
+            eliminated: self.eliminated.load(Ordering::Relaxed),
+            expired_offers: self.expired_offers.load(Ordering::Relaxed),
+        }
+    }
+
+    /// Resets the contention counters to zero.
+    pub fn reset_elimination_stats(&self) {
+        self.head_contention.store(0, Ordering::Relaxed);
+        self.eliminated.store(0, Ordering::Relaxed);
+        self.expired_offers.store(0, Ordering::Relaxed);
+    }
+
     /// Clears the stack (does not clear `next` for all nodes; push overwrites it).
     #[inline]
     pub fn clear<T: GhostBorrow<'brand>>(&self, token: &T) {
@@ -59,6 +129,9 @@ impl<'brand> GhostTreiberStack<'brand> {
             {
                 return;
             }
+            if self.offer(idx) {
+                return;
+            }
         }
     }
 
@@ -121,6 +194,77 @@ impl<'brand> GhostTreiberStack<'brand> {
             {
                 return Some(h);
             }
+            if let Some(idx) = self.take_offer() {
+                return Some(idx);
+            }
         }
     }
+
+    /// Pops indices in parallel on rayon's pool until the stack is observed empty.
+    ///
+    /// Indices pushed while the iterator runs are picked up until some worker finds the
+    /// stack empty; after that every worker stops, so call it again to drain work that
+    /// arrives later.
+    #[cfg(feature = "rayon")]
+    pub fn par_drain<'a, T>(
+        &'a self,
+        token: &'a T,
+    ) -> impl rayon::iter::ParallelIterator<Item = usize> + use<'a, 'brand, T>
+    where
+        T: GhostBorrow<'brand> + Sync,
+    {
+        use rayon::prelude::*;
+        rayon::iter::repeat(()).map(move |()| self.pop(token)).while_some()
+    }
+
+    /// After a failed push CAS: offers `idx` in an elimination slot and returns `true`
+    /// if a pop took it.
+    #[cold]
+    fn offer(&self, idx: usize) -> bool {
+        self.head_contention.fetch_add(1, Ordering::Relaxed);
+        if self.slots.is_empty() {
+            return false;
+        }
+        let slot = &self.slots[current_shard_index() % self.slots.len()];
+        if slot
+            .compare_exchange(NONE, idx, Ordering::Release, Ordering::Relaxed)
+            .is_err()
+        {
+            return false;
+        }
+        for _ in 0..ELIMINATION_SPINS {
+            if slot.load(Ordering::Acquire) == TAKEN {
+                break;
+            }
+            core::hint::spin_loop();
+        }
+        // Withdraw the offer unless a pop has taken it meanwhile.
+        if slot
+            .compare_exchange(idx, NONE, Ordering::Relaxed, Ordering::Acquire)
+            .is_ok()
+        {
+            self.expired_offers.fetch_add(1, Ordering::Relaxed);
+            return false;
+        }
+        slot.store(NONE, Ordering::Release);
+        self.eliminated.fetch_add(1, Ordering::Relaxed);
+        true
+    }
+
+    /// After a failed pop CAS: takes an index offered by a concurrent push, if any.
+    #[cold]
+    fn take_offer(&self) -> Option<usize> {
+        self.head_contention.fetch_add(1, Ordering::Relaxed);
+        let len = self.slots.len();
+        let start = if len == 0 { 0 } else { current_shard_index() };
+        (0..len).find_map(|k| {
+            let slot = &self.slots[(start + k) % len];
+            let offered = slot.load(Ordering::Acquire);
+            (offered < TAKEN
+                && slot
+                    .compare_exchange(offered, TAKEN, Ordering::Acquire, Ordering::Relaxed)
+                    .is_ok())
+            .then_some(offered)
+        })
+    }
 }
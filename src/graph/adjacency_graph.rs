@@ -305,7 +305,7 @@ impl<'brand> GhostAdjacencyGraph<'brand> {
 
         self.reset_visited();
         self.visited.mark(start, Ordering::Relaxed);
-        assert!(deque.push_bottom(token, start), "deque capacity too small");
+        deque.push_bottom(token, start);
         let steal_token = token.split_immutable().0;
 
         let mut count = 1;
@@ -313,7 +313,7 @@ impl<'brand> GhostAdjacencyGraph<'brand> {
         while let Some(vertex) = deque.steal(&steal_token) {
             for neighbor in self.out_neighbors(token, vertex) {
                 if self.visited.try_visit(neighbor, Ordering::Relaxed) {
-                    assert!(deque.push_bottom(token, neighbor), "deque capacity too small");
+                    deque.push_bottom(token, neighbor);
                     count += 1;
                 }
             }
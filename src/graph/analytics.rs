@@ -0,0 +1,552 @@
+//! Incrementally maintained analytics over a [`GhostAdjacencyGraph`].
+//!
+//! Streaming workloads change a few edges at a time and re-read results in between;
+//! recomputing PageRank or components from scratch after every batch wastes almost all
+//! of the work. The trackers here own the edge updates: insertions and deletions go
+//! through them, so they can patch their state locally before touching the graph.
+//!
+//! - [`IncrementalPageRank`] keeps a rank estimate `x` and residual
+//!   `r = b + d·Pᵀx − x` (`b = (1 − d)/n`, dangling nodes spread uniformly). An edge
+//!   change at `u` only perturbs `r` at `u`'s neighbors, and
+//!   [`refresh`](IncrementalPageRank::refresh) pushes residual mass until every
+//!   `|r[v]|` is below `tolerance / n`, so work is proportional to the perturbation.
+//! - [`IncrementalComponents`] keeps weakly connected component labels. Insertions
+//!   merge by relabeling the smaller component; deletions run two interleaved BFS
+//!   searches from the endpoints and, if they do not meet, relabel the side that ran
+//!   out first.
+//!
+//! Both assume a fixed vertex set; adding or removing vertices requires rebuilding.
+
+use core::marker::PhantomData;
+use std::collections::VecDeque;
+
+use crate::{graph::GhostAdjacencyGraph, GhostToken};
+
+/// PageRank scores kept up to date across edge insertions and deletions.
+pub struct IncrementalPageRank<'brand> {
+    damping: f64,
+    tolerance: f64,
+    rank: Vec<f64>,
+    residual: Vec<f64>,
+    queue: VecDeque<usize>,
+    queued: Vec<bool>,
+    _brand: PhantomData<fn(&'brand ()) -> &'brand ()>,
+}
+
+impl<'brand> IncrementalPageRank<'brand> {
+    /// Computes PageRank for `graph` from scratch.
+    ///
+    /// `tolerance` bounds the total residual, so the L1 error of the ranks stays below
+    /// `tolerance / (1 - damping)` after every [`refresh`](Self::refresh).
+    ///
+    /// # Panics
+    /// Panics if `damping` is not in `[0, 1)` or `tolerance` is not positive.
+    pub fn new(
+        graph: &GhostAdjacencyGraph<'brand>,
+        token: &GhostToken<'brand>,
+        damping: f64,
+        tolerance: f64,
+    ) -> Self {
+        assert!((0.0..1.0).contains(&damping), "damping must be in [0, 1)");
+        assert!(tolerance > 0.0, "tolerance must be positive");
+        let n = graph.vertex_count();
+        let base = if n == 0 {
+            0.0
+        } else {
+            (1.0 - damping) / to_f64(n)
+        };
+        let mut pr = Self {
+            damping,
+            tolerance,
+            rank: vec![0.0; n],
+            residual: vec![base; n],
+            queue: (0..n).collect(),
+            queued: vec![true; n],
+            _brand: PhantomData,
+        };
+        pr.refresh(graph, token);
+        pr
+    }
+
+    /// Returns the current rank of every vertex.
+    #[inline]
+    pub fn ranks(&self) -> &[f64] {
+        &self.rank
+    }
+
+    /// Returns the current rank of `vertex`.
+    #[inline]
+    pub fn rank(&self, vertex: usize) -> f64 {
+        self.rank[vertex]
+    }
+
+    /// Returns `true` if edge updates are waiting for a [`refresh`](Self::refresh).
+    #[inline]
+    pub fn is_dirty(&self) -> bool {
+        !self.queue.is_empty()
+    }
+
+    #[inline]
+    fn threshold(&self) -> f64 {
+        self.tolerance / to_f64(self.rank.len().max(1))
+    }
+
+    fn add_residual(&mut self, vertex: usize, delta: f64) {
+        self.residual[vertex] += delta;
+        if !self.queued[vertex] && self.residual[vertex].abs() > self.threshold() {
+            self.queued[vertex] = true;
+            self.queue.push_back(vertex);
+        }
+    }
+
+    fn add_residual_everywhere(&mut self, delta: f64) {
+        for v in 0..self.rank.len() {
+            self.add_residual(v, delta);
+        }
+    }
+
+    /// Adjusts residuals for `from`'s out-degree changing from `old_degree` to
+    /// `old_degree ± 1` by `to` entering or leaving its neighbor list.
+    fn rebalance(
+        &mut self,
+        graph: &GhostAdjacencyGraph<'brand>,
+        token: &GhostToken<'brand>,
+        from: usize,
+        to: usize,
+        old_degree: usize,
+        new_degree: usize,
+    ) {
+        let mass = self.damping * self.rank[from];
+        if mass == 0.0 {
+            return;
+        }
+        let n = to_f64(self.rank.len());
+        let old_share = if old_degree == 0 {
+            0.0
+        } else {
+            mass / to_f64(old_degree)
+        };
+        let new_share = if new_degree == 0 {
+            0.0
+        } else {
+            mass / to_f64(new_degree)
+        };
+
+        // Dangling vertices spread their mass over every vertex.
+        if old_degree == 0 {
+            self.add_residual_everywhere(-mass / n);
+        }
+        if new_degree == 0 {
+            self.add_residual_everywhere(mass / n);
+        }
+        // `graph` already reflects the change here.
+        for w in graph.out_neighbors(token, from).collect::<Vec<_>>() {
+            if w != to {
+                self.add_residual(w, new_share - old_share);
+            }
+        }
+        if new_degree > old_degree {
+            self.add_residual(to, new_share);
+        } else {
+            self.add_residual(to, -old_share);
+        }
+    }
+
+    /// Inserts `from -> to` into `graph`, returning `false` if it was already present.
+    ///
+    /// Ranks are not updated until the next [`refresh`](Self::refresh).
+    pub fn insert_edge(
+        &mut self,
+        graph: &GhostAdjacencyGraph<'brand>,
+        token: &mut GhostToken<'brand>,
+        from: usize,
+        to: usize,
+    ) -> bool {
+        if graph.has_edge(token, from, to) {
+            return false;
+        }
+        let old_degree = graph.out_degree(token, from);
+        graph.add_edge(token, from, to);
+        self.rebalance(graph, token, from, to, old_degree, old_degree + 1);
+        true
+    }
+
+    /// Removes `from -> to` from `graph`, returning whether it was present.
+    ///
+    /// Ranks are not updated until the next [`refresh`](Self::refresh).
+    pub fn remove_edge(
+        &mut self,
+        graph: &GhostAdjacencyGraph<'brand>,
+        token: &mut GhostToken<'brand>,
+        from: usize,
+        to: usize,
+    ) -> bool {
+        let old_degree = graph.out_degree(token, from);
+        if !graph.remove_edge(token, from, to) {
+            return false;
+        }
+        self.rebalance(graph, token, from, to, old_degree, old_degree - 1);
+        true
+    }
+
+    /// Pushes pending residual mass until the ranks are within tolerance again.
+    ///
+    /// Returns the number of push operations performed.
+    ///
+    /// # Panics
+    /// Panics if the vertex count of `graph` changed since construction.
+    pub fn refresh(
+        &mut self,
+        graph: &GhostAdjacencyGraph<'brand>,
+        token: &GhostToken<'brand>,
+    ) -> usize {
+        let n = self.rank.len();
+        assert_eq!(
+            graph.vertex_count(),
+            n,
+            "vertex set changed; rebuild instead"
+        );
+        let mut pushes = 0;
+        while let Some(v) = self.queue.pop_front() {
+            self.queued[v] = false;
+            let r = core::mem::take(&mut self.residual[v]);
+            if r.abs() <= self.threshold() {
+                self.residual[v] = r;
+                continue;
+            }
+            pushes += 1;
+            self.rank[v] += r;
+            let degree = graph.out_degree(token, v);
+            if degree == 0 {
+                self.add_residual_everywhere(self.damping * r / to_f64(n));
+            } else {
+                let share = self.damping * r / to_f64(degree);
+                for w in graph.out_neighbors(token, v) {
+                    self.add_residual(w, share);
+                }
+            }
+        }
+        pushes
+    }
+}
+
+/// Weakly connected components kept up to date across edge insertions and deletions.
+pub struct IncrementalComponents<'brand> {
+    /// Undirected adjacency with one entry per directed edge endpoint.
+    undirected: Vec<Vec<usize>>,
+    label: Vec<usize>,
+    /// Size per label; labels are never reused, so retired ones stay at zero.
+    sizes: Vec<usize>,
+    count: usize,
+    /// Search stamps: `epoch` marks the `from` side, `epoch + 1` the `to` side.
+    mark: Vec<u64>,
+    epoch: u64,
+    _brand: PhantomData<fn(&'brand ()) -> &'brand ()>,
+}
+
+impl<'brand> IncrementalComponents<'brand> {
+    /// Computes the weakly connected components of `graph` from scratch.
+    pub fn new(graph: &GhostAdjacencyGraph<'brand>, token: &GhostToken<'brand>) -> Self {
+        let n = graph.vertex_count();
+        let mut undirected = vec![Vec::new(); n];
+        for u in 0..n {
+            for v in graph.out_neighbors(token, u) {
+                undirected[u].push(v);
+                undirected[v].push(u);
+            }
+        }
+
+        let mut label = vec![usize::MAX; n];
+        let mut sizes = Vec::new();
+        let mut stack = Vec::new();
+        for s in 0..n {
+            if label[s] != usize::MAX {
+                continue;
+            }
+            let id = sizes.len();
+            label[s] = id;
+            stack.push(s);
+            let mut size = 0;
+            while let Some(u) = stack.pop() {
+                size += 1;
+                for &v in &undirected[u] {
+                    if label[v] == usize::MAX {
+                        label[v] = id;
+                        stack.push(v);
+                    }
+                }
+            }
+            sizes.push(size);
+        }
+
+        Self {
+            undirected,
+            count: sizes.len(),
+            label,
+            sizes,
+            mark: vec![0; n],
+            epoch: 0,
+            _brand: PhantomData,
+        }
+    }
+
+    /// Returns the number of components.
+    #[inline]
+    pub fn component_count(&self) -> usize {
+        self.count
+    }
+
+    /// Returns the component label of `vertex`.
+    ///
+    /// Labels are stable between changes but not dense: compare them, don't index by them.
+    #[inline]
+    pub fn component(&self, vertex: usize) -> usize {
+        self.label[vertex]
+    }
+
+    /// Returns `true` if `a` and `b` are in the same component.
+    #[inline]
+    pub fn connected(&self, a: usize, b: usize) -> bool {
+        self.label[a] == self.label[b]
+    }
+
+    /// Returns the size of the component containing `vertex`.
+    #[inline]
+    pub fn component_size(&self, vertex: usize) -> usize {
+        self.sizes[self.label[vertex]]
+    }
+
+    /// Inserts `from -> to` into `graph`, returning `false` if it was already present.
+    pub fn insert_edge(
+        &mut self,
+        graph: &GhostAdjacencyGraph<'brand>,
+        token: &mut GhostToken<'brand>,
+        from: usize,
+        to: usize,
+    ) -> bool {
+        if graph.has_edge(token, from, to) {
+            return false;
+        }
+        graph.add_edge(token, from, to);
+        self.undirected[from].push(to);
+        self.undirected[to].push(from);
+
+        let (a, b) = (self.label[from], self.label[to]);
+        if a != b {
+            let (keep, retire, start) = if self.sizes[a] >= self.sizes[b] {
+                (a, b, to)
+            } else {
+                (b, a, from)
+            };
+            self.relabel(start, keep);
+            self.sizes[keep] += core::mem::take(&mut self.sizes[retire]);
+            self.count -= 1;
+        }
+        true
+    }
+
+    /// Removes `from -> to` from `graph`, returning whether it was present.
+    pub fn remove_edge(
+        &mut self,
+        graph: &GhostAdjacencyGraph<'brand>,
+        token: &mut GhostToken<'brand>,
+        from: usize,
+        to: usize,
+    ) -> bool {
+        if !graph.remove_edge(token, from, to) {
+            return false;
+        }
+        Self::unlink(&mut self.undirected[from], to);
+        Self::unlink(&mut self.undirected[to], from);
+
+        if let Some(side) = self.split_side(from, to) {
+            let old = self.label[from];
+            let id = self.sizes.len();
+            for &v in &side {
+                self.label[v] = id;
+            }
+            self.sizes[old] -= side.len();
+            self.sizes.push(side.len());
+            self.count += 1;
+        }
+        true
+    }
+
+    fn unlink(list: &mut Vec<usize>, target: usize) {
+        let pos = list
+            .iter()
+            .position(|&v| v == target)
+            .expect("undirected adjacency out of sync");
+        list.swap_remove(pos);
+    }
+
+    /// Relabels the component containing `start` to `id`.
+    fn relabel(&mut self, start: usize, id: usize) {
+        let old = self.label[start];
+        self.label[start] = id;
+        let mut stack = vec![start];
+        while let Some(u) = stack.pop() {
+            for &v in &self.undirected[u] {
+                if self.label[v] == old {
+                    self.label[v] = id;
+                    stack.push(v);
+                }
+            }
+        }
+    }
+
+    /// Searches from both endpoints in lockstep. Returns the vertices of the side that
+    /// was exhausted first if the two are no longer connected.
+    fn split_side(&mut self, from: usize, to: usize) -> Option<Vec<usize>> {
+        if from == to {
+            return None;
+        }
+        self.epoch += 2;
+        let marks = [self.epoch, self.epoch + 1];
+        let mut sides = [vec![from], vec![to]];
+        let mut heads = [0usize; 2];
+        self.mark[from] = marks[0];
+        self.mark[to] = marks[1];
+
+        loop {
+            for s in 0..2 {
+                let Some(&u) = sides[s].get(heads[s]) else {
+                    return Some(core::mem::take(&mut sides[s]));
+                };
+                heads[s] += 1;
+                for &v in &self.undirected[u] {
+                    if self.mark[v] == marks[1 - s] {
+                        return None;
+                    }
+                    if self.mark[v] != marks[s] {
+                        self.mark[v] = marks[s];
+                        sides[s].push(v);
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Converts a vertex count or degree for the rank arithmetic. These stay far below
+/// 2^53, so the conversion is exact.
+#[allow(clippy::cast_precision_loss)]
+#[inline]
+fn to_f64(n: usize) -> f64 {
+    n as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pagerank_from_scratch<'brand>(
+        graph: &GhostAdjacencyGraph<'brand>,
+        token: &GhostToken<'brand>,
+        damping: f64,
+    ) -> Vec<f64> {
+        let n = graph.vertex_count();
+        let mut x = vec![1.0 / to_f64(n); n];
+        for _ in 0..200 {
+            let dangling: f64 = (0..n)
+                .filter(|&u| graph.out_degree(token, u) == 0)
+                .map(|u| x[u])
+                .sum();
+            let mut next = vec![(1.0 - damping + damping * dangling) / to_f64(n); n];
+            for u in 0..n {
+                let deg = graph.out_degree(token, u);
+                for v in graph.out_neighbors(token, u) {
+                    next[v] += damping * x[u] / to_f64(deg);
+                }
+            }
+            x = next;
+        }
+        x
+    }
+
+    fn assert_close(a: &[f64], b: &[f64], eps: f64) {
+        let err: f64 = a.iter().zip(b).map(|(x, y)| (x - y).abs()).sum();
+        assert!(err < eps, "L1 error {err} exceeds {eps}: {a:?} vs {b:?}");
+    }
+
+    #[test]
+    fn incremental_pagerank_tracks_edge_changes() {
+        GhostToken::new(|mut token| {
+            let graph = GhostAdjacencyGraph::from_adjacency(vec![
+                vec![1, 2],
+                vec![2],
+                vec![0],
+                vec![2],
+                vec![],
+            ]);
+            let mut pr = IncrementalPageRank::new(&graph, &token, 0.85, 1e-10);
+            assert_close(
+                pr.ranks(),
+                &pagerank_from_scratch(&graph, &token, 0.85),
+                1e-8,
+            );
+            assert!((pr.ranks().iter().sum::<f64>() - 1.0).abs() < 1e-8);
+
+            // One batch: a dangling vertex gains an edge, another loses its only one.
+            assert!(pr.insert_edge(&graph, &mut token, 4, 3));
+            assert!(!pr.insert_edge(&graph, &mut token, 4, 3));
+            assert!(pr.remove_edge(&graph, &mut token, 1, 2));
+            assert!(pr.insert_edge(&graph, &mut token, 0, 4));
+            assert!(pr.is_dirty());
+            pr.refresh(&graph, &token);
+            assert!(!pr.is_dirty());
+            assert_close(
+                pr.ranks(),
+                &pagerank_from_scratch(&graph, &token, 0.85),
+                1e-8,
+            );
+
+            assert!(pr.remove_edge(&graph, &mut token, 0, 1));
+            assert!(!pr.remove_edge(&graph, &mut token, 0, 1));
+            pr.refresh(&graph, &token);
+            assert_close(
+                pr.ranks(),
+                &pagerank_from_scratch(&graph, &token, 0.85),
+                1e-8,
+            );
+        });
+    }
+
+    #[test]
+    fn incremental_components_merge_and_split() {
+        GhostToken::new(|mut token| {
+            let graph =
+                GhostAdjacencyGraph::from_adjacency(vec![vec![1], vec![], vec![3], vec![], vec![]]);
+            let mut cc = IncrementalComponents::new(&graph, &token);
+            assert_eq!(cc.component_count(), 3);
+            assert!(cc.connected(0, 1));
+            assert!(!cc.connected(1, 2));
+
+            assert!(cc.insert_edge(&graph, &mut token, 3, 1));
+            assert_eq!(cc.component_count(), 2);
+            assert_eq!(cc.component_size(0), 4);
+
+            // A second path keeps 0 and 1 together when one edge goes away.
+            assert!(cc.insert_edge(&graph, &mut token, 2, 0));
+            assert!(cc.remove_edge(&graph, &mut token, 0, 1));
+            assert_eq!(cc.component_count(), 2);
+            assert!(cc.connected(0, 1));
+
+            assert!(cc.remove_edge(&graph, &mut token, 3, 1));
+            assert_eq!(cc.component_count(), 3);
+            assert!(!cc.connected(0, 1));
+            assert!(cc.connected(0, 3));
+            assert_eq!(cc.component_size(1), 1);
+            assert_eq!(cc.component_size(2), 3);
+            assert!(!cc.remove_edge(&graph, &mut token, 3, 1));
+
+            // Labels agree with a fresh computation.
+            let fresh = IncrementalComponents::new(&graph, &token);
+            for a in 0..5 {
+                for b in 0..5 {
+                    assert_eq!(cc.connected(a, b), fresh.connected(a, b));
+                }
+            }
+        });
+    }
+}
@@ -14,8 +14,10 @@
 use core::sync::atomic::Ordering;
 
 use crate::{
-    collections::ChunkedVec, concurrency::worklist::GhostChaseLevDeque,
-    graph::access::visited::VisitedSet, GhostToken,
+    collections::{BrandedFrontier, ChunkedVec},
+    concurrency::worklist::GhostChaseLevDeque,
+    graph::access::visited::VisitedSet,
+    GhostToken,
 };
 
 /// A bipartite graph whose visited bitmaps are branded.
@@ -205,8 +207,6 @@ impl<'brand, const EDGE_CHUNK: usize> GhostBipartiteGraph<'brand, EDGE_CHUNK> {
     /// - for left vertices `u` in `[0, left_count)`, `mate[u] = Some(left_count + v)` if matched to right `v`
     /// - for right vertices `left_count + v`, `mate[left_count + v] = Some(u)` if matched
     pub fn maximum_matching(&self) -> Vec<Option<usize>> {
-        use std::collections::VecDeque;
-
         const INF: i32 = i32::MAX / 4;
 
         let mut pair_u: Vec<Option<usize>> = vec![None; self.left_count];
@@ -219,30 +219,33 @@ impl<'brand, const EDGE_CHUNK: usize> GhostBipartiteGraph<'brand, EDGE_CHUNK> {
             pair_v: &[Option<usize>],
             dist: &mut [i32],
             inf: i32,
+            frontier: &mut BrandedFrontier<'_, usize>,
         ) -> bool {
-            let mut q = VecDeque::new();
+            frontier.clear();
             for u in 0..g.left_count {
                 if pair_u[u].is_none() {
                     dist[u] = 0;
-                    q.push_back(u);
+                    frontier.push_current(u);
                 } else {
                     dist[u] = inf;
                 }
             }
 
             let mut found_free = false;
-            while let Some(u) = q.pop_front() {
-                let du = dist[u];
-                for v in g.left_neighbors(u) {
-                    if let Some(u2) = pair_v[v] {
-                        if dist[u2] == inf {
-                            dist[u2] = du + 1;
-                            q.push_back(u2);
+            while !frontier.is_empty() {
+                frontier.process_level(|u, next| {
+                    let du = dist[u];
+                    for v in g.left_neighbors(u) {
+                        if let Some(u2) = pair_v[v] {
+                            if dist[u2] == inf {
+                                dist[u2] = du + 1;
+                                next.push_back(u2);
+                            }
+                        } else {
+                            found_free = true;
                         }
-                    } else {
-                        found_free = true;
                     }
-                }
+                });
             }
             found_free
         }
@@ -275,7 +278,9 @@ impl<'brand, const EDGE_CHUNK: usize> GhostBipartiteGraph<'brand, EDGE_CHUNK> {
             false
         }
 
-        while bfs(self, &pair_u, &pair_v, &mut dist, INF) {
+        // Reused by every phase's layering.
+        let mut frontier = BrandedFrontier::new();
+        while bfs(self, &pair_u, &pair_v, &mut dist, INF, &mut frontier) {
             for u in 0..self.left_count {
                 if pair_u[u].is_none() {
                     let _ = dfs(self, u, &mut pair_u, &mut pair_v, &mut dist, INF);
@@ -313,10 +318,7 @@ impl<'brand, const EDGE_CHUNK: usize> GhostBipartiteGraph<'brand, EDGE_CHUNK> {
 
         self.reset_visited();
         debug_assert!(self.visited_left.try_visit(start_left, Ordering::Relaxed));
-        assert!(
-            deque.push_bottom(token, start_left),
-            "deque capacity too small"
-        );
+        deque.push_bottom(token, start_left);
 
         let steal_token = token.split_immutable().0;
         let mut count = 1;
@@ -326,10 +328,7 @@ impl<'brand, const EDGE_CHUNK: usize> GhostBipartiteGraph<'brand, EDGE_CHUNK> {
                 // Left vertex - visit right neighbors
                 for right in self.left_neighbors(vertex) {
                     if self.visited_right.try_visit(right, Ordering::Relaxed) {
-                        assert!(
-                            deque.push_bottom(token, self.left_count + right),
-                            "deque capacity too small"
-                        );
+                        deque.push_bottom(token, self.left_count + right);
                         count += 1;
                     }
                 }
@@ -338,7 +337,7 @@ impl<'brand, const EDGE_CHUNK: usize> GhostBipartiteGraph<'brand, EDGE_CHUNK> {
                 let right = vertex - self.left_count;
                 for left in self.right_neighbors(right) {
                     if self.visited_left.try_visit(left, Ordering::Relaxed) {
-                        assert!(deque.push_bottom(token, left), "deque capacity too small");
+                        deque.push_bottom(token, left);
                         count += 1;
                     }
                 }
@@ -362,10 +361,7 @@ impl<'brand, const EDGE_CHUNK: usize> GhostBipartiteGraph<'brand, EDGE_CHUNK> {
 
         self.reset_visited();
         debug_assert!(self.visited_right.try_visit(start_right, Ordering::Relaxed));
-        assert!(
-            deque.push_bottom(token, self.left_count + start_right),
-            "deque capacity too small"
-        );
+        deque.push_bottom(token, self.left_count + start_right);
 
         let steal_token = token.split_immutable().0;
         let mut count = 1;
@@ -375,10 +371,7 @@ impl<'brand, const EDGE_CHUNK: usize> GhostBipartiteGraph<'brand, EDGE_CHUNK> {
                 // Left vertex - visit right neighbors
                 for right in self.left_neighbors(vertex) {
                     if self.visited_right.try_visit(right, Ordering::Relaxed) {
-                        assert!(
-                            deque.push_bottom(token, self.left_count + right),
-                            "deque capacity too small"
-                        );
+                        deque.push_bottom(token, self.left_count + right);
                         count += 1;
                     }
                 }
@@ -387,7 +380,7 @@ impl<'brand, const EDGE_CHUNK: usize> GhostBipartiteGraph<'brand, EDGE_CHUNK> {
                 let right = vertex - self.left_count;
                 for left in self.right_neighbors(right) {
                     if self.visited_left.try_visit(left, Ordering::Relaxed) {
-                        assert!(deque.push_bottom(token, left), "deque capacity too small");
+                        deque.push_bottom(token, left);
                         count += 1;
                     }
                 }
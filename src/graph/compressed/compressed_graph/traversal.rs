@@ -1,5 +1,7 @@
 //! Compressed graph traversal algorithms.
 
+use crate::collections::BrandedFrontier;
+
 /// Breadth-first traversal optimized for compressed format.
 ///
 /// Uses the compressed representation efficiently while maintaining
@@ -12,23 +14,25 @@ pub fn bfs<'brand, const EDGE_CHUNK: usize>(
     assert!(start < graph.node_count(), "start out of bounds");
 
     let mut out = Vec::with_capacity(graph.node_count());
-    let mut q = std::collections::VecDeque::with_capacity(64);
+    let mut frontier = BrandedFrontier::with_capacity(64);
 
     if graph.try_visit(start) {
-        q.push_back(start);
+        frontier.push_current(start);
     } else {
         return out;
     }
 
-    while let Some(u) = q.pop_front() {
-        out.push(u);
+    while !frontier.is_empty() {
+        frontier.process_level(|u, next| {
+            out.push(u);
 
-        // Process neighbors from compressed format
-        for v in graph.neighbors(u) {
-            if graph.try_visit(v) {
-                q.push_back(v);
+            // Process neighbors from compressed format
+            for v in graph.neighbors(u) {
+                if graph.try_visit(v) {
+                    next.push_back(v);
+                }
             }
-        }
+        });
     }
 
     out
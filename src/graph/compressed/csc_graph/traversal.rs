@@ -3,7 +3,10 @@
 use core::sync::atomic::Ordering;
 
 use crate::{
-    concurrency::worklist::{GhostChaseLevDeque, GhostTreiberStack},
+    concurrency::{
+        scoped::broadcast,
+        worklist::{GhostChaseLevDeque, GhostTreiberStack},
+    },
     graph::compressed::csc_graph::GhostCscGraph,
     GhostToken,
 };
@@ -60,7 +63,7 @@ impl<'brand, const EDGE_CHUNK: usize> GhostCscGraph<'brand, EDGE_CHUNK> {
 
         // Mark start as visited
         debug_assert!(self.visited.try_visit(start, Ordering::Relaxed));
-        assert!(deque.push_bottom(token, start), "deque capacity too small");
+        deque.push_bottom(token, start);
         let steal_token = token.split_immutable().0;
 
         let mut count = 1;
@@ -69,7 +72,7 @@ impl<'brand, const EDGE_CHUNK: usize> GhostCscGraph<'brand, EDGE_CHUNK> {
             // Visit all incoming neighbors (transpose traversal)
             for neighbor in self.in_neighbors(node) {
                 if self.visited.try_visit(neighbor, Ordering::Relaxed) {
-                    assert!(deque.push_bottom(token, neighbor), "deque capacity too small");
+                    deque.push_bottom(token, neighbor);
                     count += 1;
                 }
             }
@@ -87,7 +90,6 @@ impl<'brand, const EDGE_CHUNK: usize> GhostCscGraph<'brand, EDGE_CHUNK> {
         start: usize,
         threads: usize,
     ) -> usize {
-        use core::sync::atomic::AtomicUsize;
         assert!(threads != 0, "threads must be > 0");
         assert!(start < self.node_count(), "start {start} out of bounds");
 
@@ -96,24 +98,20 @@ impl<'brand, const EDGE_CHUNK: usize> GhostCscGraph<'brand, EDGE_CHUNK> {
         debug_assert!(self.visited.try_visit(start, Ordering::Relaxed));
         stack.push(token, start);
 
-        let count = AtomicUsize::new(0);
-        std::thread::scope(|scope| {
-            for _ in 0..threads {
-                let token = token;
-                scope.spawn(|| {
-                    while let Some(u) = stack.pop(token) {
-                        count.fetch_add(1, Ordering::Relaxed);
-                        for p in self.in_neighbors(u) {
-                            if self.visited.try_visit(p, Ordering::AcqRel) {
-                                stack.push(token, p);
-                            }
-                        }
+        broadcast(token, threads, |token, _| {
+            let mut count = 0;
+            while let Some(u) = stack.pop(token) {
+                count += 1;
+                for p in self.in_neighbors(u) {
+                    if self.visited.try_visit(p, Ordering::AcqRel) {
+                        stack.push(token, p);
                     }
-                });
+                }
             }
-        });
-
-        count.load(Ordering::Relaxed)
+            count
+        })
+        .into_iter()
+        .sum()
     }
 
     /// Computes the transpose of this CSC graph (returns a CSR graph).
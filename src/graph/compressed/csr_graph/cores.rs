@@ -0,0 +1,98 @@
+//! k-core decomposition by bucket peeling.
+//!
+//! The k-core of an undirected graph is its largest subgraph in which every node has
+//! degree at least `k`; the core number of a node is the largest `k` whose k-core
+//! contains it. The graph is read as undirected through its out-edges, so every
+//! undirected edge must be stored in both directions. Self-loops are ignored.
+
+use crate::{collections::BrandedVec, graph::compressed::csr_graph::GhostCsrGraph, GhostToken};
+
+impl<const EDGE_CHUNK: usize> GhostCsrGraph<'_, EDGE_CHUNK> {
+    /// Computes the core number of every node with the Batagelj–Zaversnik bucket
+    /// algorithm.
+    ///
+    /// Nodes are kept sorted by current degree in one array, with the start of each
+    /// degree's bucket recorded, and are peeled in that order: removing a node moves
+    /// each neighbor of higher degree one bucket down with a single swap. The degree
+    /// of a node when it is peeled is its core number.
+    ///
+    /// **Time complexity**: \(O(n + m)\)
+    pub fn k_core_decomposition(&self) -> Vec<usize> {
+        let n = self.node_count();
+        GhostToken::new(|mut token| {
+            let degrees: BrandedVec<'_, usize> = (0..n)
+                .map(|v| self.neighbors(v).filter(|&u| u != v).count())
+                .collect();
+            let deg = degrees.as_mut_slice(&mut token);
+            let max_degree = deg.iter().copied().max().unwrap_or(0);
+
+            // `bin[d]` is the start of the bucket of degree `d` in `vert`, and `pos` the
+            // inverse of `vert`.
+            let mut bin = vec![0; max_degree + 1];
+            for &d in deg.iter() {
+                bin[d] += 1;
+            }
+            let mut start = 0;
+            for count in &mut bin {
+                let size = *count;
+                *count = start;
+                start += size;
+            }
+            let mut pos = vec![0; n];
+            let mut vert = vec![0; n];
+            for v in 0..n {
+                pos[v] = bin[deg[v]];
+                vert[pos[v]] = v;
+                bin[deg[v]] += 1;
+            }
+            for d in (1..=max_degree).rev() {
+                bin[d] = bin[d - 1];
+            }
+            if let Some(first) = bin.first_mut() {
+                *first = 0;
+            }
+
+            for i in 0..n {
+                let v = vert[i];
+                for u in self.neighbors(v) {
+                    if deg[u] > deg[v] {
+                        // Swap `u` with the first node of its bucket, then shrink the
+                        // bucket past it.
+                        let du = deg[u];
+                        let (pu, pw) = (pos[u], bin[du]);
+                        let w = vert[pw];
+                        vert.swap(pu, pw);
+                        pos[u] = pw;
+                        pos[w] = pu;
+                        bin[du] += 1;
+                        deg[u] -= 1;
+                    }
+                }
+            }
+            degrees.into_vec()
+        })
+    }
+
+    /// Extracts the k-core: the subgraph induced by the nodes with core number at least
+    /// `k`, renumbered `0..len` in their original order.
+    ///
+    /// Returns the subgraph and, for each of its nodes, the node's id in `self`.
+    pub fn k_core_subgraph(&self, k: usize) -> (Self, Vec<usize>) {
+        let core = self.k_core_decomposition();
+        let nodes: Vec<usize> = (0..self.node_count()).filter(|&v| core[v] >= k).collect();
+        let mut new_id = vec![usize::MAX; self.node_count()];
+        for (i, &v) in nodes.iter().enumerate() {
+            new_id[v] = i;
+        }
+        let adjacency: Vec<Vec<usize>> = nodes
+            .iter()
+            .map(|&v| {
+                self.neighbors(v)
+                    .filter(|&u| new_id[u] != usize::MAX)
+                    .map(|u| new_id[u])
+                    .collect()
+            })
+            .collect();
+        (Self::from_adjacency(&adjacency), nodes)
+    }
+}
@@ -31,6 +31,7 @@ use std::sync::atomic::Ordering;
 /// | `degree` | \(O(1)\) | Returns out-degree |
 /// | `has_edge` | \(O(\text{out-degree})\) | Linear scan of neighbors |
 /// | `in_neighbors` | \(O(m)\) | Scans all edges |
+/// | `k_core_decomposition` | \(O(n + m)\) | Bucket peeling of core numbers |
 /// | `SIMD-friendly visited array` | Contiguous atomic booleans for potential vectorization |
 #[repr(C)]
 pub struct GhostCsrGraph<'brand, const EDGE_CHUNK: usize> {
@@ -274,6 +275,11 @@ impl<'brand, const EDGE_CHUNK: usize> GhostCsrGraph<'brand, EDGE_CHUNK> {
     }
 }
 
+mod cores;
+mod snapshot;
 #[cfg(test)]
 mod tests;
 mod traversal;
+
+pub use snapshot::SNAPSHOT_MAGIC;
+pub use traversal::CycleError;
@@ -0,0 +1,88 @@
+//! Binary snapshot format for `GhostCsrGraph`.
+//!
+//! Layout (all integers little-endian `u64`):
+//! - the 8-byte magic `SNAPSHOT_MAGIC`;
+//! - node count `n` and edge count `m`;
+//! - `n + 1` row offsets;
+//! - `m` edge targets.
+//!
+//! Only the outgoing CSR is stored; the incoming (CSC) index and the visited bitmap
+//! are rebuilt on load.
+
+use super::GhostCsrGraph;
+use std::io::{self, Read, Write};
+
+/// Magic bytes identifying a CSR snapshot, including a format version.
+pub const SNAPSHOT_MAGIC: [u8; 8] = *b"HALOCSR1";
+
+fn invalid(msg: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, msg)
+}
+
+fn read_u64(reader: &mut impl Read) -> io::Result<u64> {
+    let mut buf = [0u8; 8];
+    reader.read_exact(&mut buf)?;
+    Ok(u64::from_le_bytes(buf))
+}
+
+fn read_usize(reader: &mut impl Read) -> io::Result<usize> {
+    usize::try_from(read_u64(reader)?).map_err(|_| invalid("value does not fit in usize"))
+}
+
+impl<'brand, const EDGE_CHUNK: usize> GhostCsrGraph<'brand, EDGE_CHUNK> {
+    /// Writes the graph in the snapshot format.
+    ///
+    /// # Errors
+    /// Propagates any I/O error from `writer`.
+    pub fn write_snapshot<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        writer.write_all(&SNAPSHOT_MAGIC)?;
+        writer.write_all(&(self.node_count() as u64).to_le_bytes())?;
+        writer.write_all(&(self.edge_count() as u64).to_le_bytes())?;
+        for &offset in &self.offsets {
+            writer.write_all(&(offset as u64).to_le_bytes())?;
+        }
+        for &target in self.edges.iter() {
+            writer.write_all(&(target as u64).to_le_bytes())?;
+        }
+        writer.flush()
+    }
+
+    /// Reads a graph written by [`write_snapshot`](Self::write_snapshot).
+    ///
+    /// # Errors
+    /// Returns `InvalidData` if the magic is wrong or the CSR arrays are inconsistent,
+    /// and propagates I/O errors (including `UnexpectedEof` for truncated input).
+    pub fn read_snapshot<R: Read>(mut reader: R) -> io::Result<Self> {
+        let mut magic = [0u8; 8];
+        reader.read_exact(&mut magic)?;
+        if magic != SNAPSHOT_MAGIC {
+            return Err(invalid("not a CSR graph snapshot"));
+        }
+
+        let n = read_usize(&mut reader)?;
+        let m = read_usize(&mut reader)?;
+
+        // Grow as data arrives rather than trusting the header for the allocation size.
+        let mut offsets = Vec::new();
+        for _ in 0..=n {
+            offsets.push(read_usize(&mut reader)?);
+        }
+        if offsets[0] != 0 || offsets[n] != m || offsets.windows(2).any(|w| w[0] > w[1]) {
+            return Err(invalid("offsets must be monotone from 0 to the edge count"));
+        }
+
+        let mut edges = Vec::new();
+        for _ in 0..m {
+            let target = read_usize(&mut reader)?;
+            if target >= n {
+                return Err(invalid("edge target out of bounds"));
+            }
+            edges.push(target);
+        }
+
+        if n == 0 {
+            return Ok(Self::from_adjacency(&[]));
+        }
+        Ok(Self::from_csr_parts(offsets, edges))
+    }
+}
@@ -1,6 +1,8 @@
 //! Tests for CSR graph implementation.
 
 use super::*;
+use crate::graph::compressed::strategies;
+use proptest::prelude::*;
 
 #[test]
 fn test_csr_in_neighbors_basic() {
@@ -94,3 +96,179 @@ fn test_disconnected_graph() {
         assert!(graph.in_neighbors(i).is_empty());
     }
 }
+
+#[test]
+fn test_csr_bfs_distances() {
+    // 0 -> 1, 2 ; 1 -> 3 ; 2 -> 3 ; 3 -> 4 ; 5 isolated
+    let adjacency = vec![vec![1, 2], vec![3], vec![3], vec![4], vec![], vec![]];
+    let graph = GhostCsrGraph::<4>::from_adjacency(&adjacency);
+
+    graph.reset_visited();
+    assert_eq!(graph.bfs_distances(0), vec![0, 1, 1, 2, 3, usize::MAX]);
+}
+
+#[test]
+fn test_csr_topological_sort() {
+    // 0 -> 1, 2 ; 1 -> 3 ; 2 -> 3 ; 3 -> 4 ; 5 isolated
+    let adjacency = vec![vec![1, 2], vec![3], vec![3], vec![4], vec![], vec![]];
+    let graph = GhostCsrGraph::<4>::from_adjacency(&adjacency);
+    let order = graph.topological_sort().unwrap();
+    let mut position = vec![0; order.len()];
+    for (i, &v) in order.iter().enumerate() {
+        position[v] = i;
+    }
+    assert_eq!(order.len(), 6);
+    for (u, nbrs) in adjacency.iter().enumerate() {
+        for &v in nbrs {
+            assert!(position[u] < position[v]);
+        }
+    }
+
+    // 0 -> 1 -> 2 -> 3 -> 1 ; 3 -> 4 ; 4 -> 4
+    let adjacency = vec![vec![1], vec![2], vec![3], vec![1, 4], vec![4]];
+    let graph = GhostCsrGraph::<4>::from_adjacency(&adjacency);
+    let CycleError(cycle) = graph.topological_sort().unwrap_err();
+    // Either cycle may be reported; each listed edge must exist.
+    assert!(cycle.len() == 3 || cycle == [4]);
+    for (i, &u) in cycle.iter().enumerate() {
+        assert!(graph.has_edge(u, cycle[(i + 1) % cycle.len()]));
+    }
+}
+
+#[test]
+fn test_csr_snapshot_roundtrip() {
+    let adjacency = vec![vec![1, 2], vec![2], vec![], vec![0, 1]];
+    let graph = GhostCsrGraph::<4>::from_adjacency(&adjacency);
+
+    let mut bytes = Vec::new();
+    graph.write_snapshot(&mut bytes).unwrap();
+    assert_eq!(bytes[..8], SNAPSHOT_MAGIC);
+
+    let loaded = GhostCsrGraph::<4>::read_snapshot(bytes.as_slice()).unwrap();
+    assert_eq!(loaded.node_count(), 4);
+    assert_eq!(loaded.edge_count(), 5);
+    for u in 0..4 {
+        assert_eq!(loaded.neighbors(u).collect::<Vec<_>>(), adjacency[u]);
+    }
+    assert_eq!(loaded.in_neighbors(1), vec![0, 3]);
+
+    // Truncated and corrupted inputs are errors, not panics.
+    assert!(GhostCsrGraph::<4>::read_snapshot(&bytes[..bytes.len() - 1]).is_err());
+    let mut bad_edge = bytes.clone();
+    let last = bad_edge.len() - 8;
+    bad_edge[last..].copy_from_slice(&9u64.to_le_bytes());
+    let err = GhostCsrGraph::<4>::read_snapshot(bad_edge.as_slice()).err().unwrap();
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+
+    let mut empty = Vec::new();
+    GhostCsrGraph::<4>::from_adjacency(&[]).write_snapshot(&mut empty).unwrap();
+    assert_eq!(GhostCsrGraph::<4>::read_snapshot(empty.as_slice()).unwrap().node_count(), 0);
+}
+
+#[test]
+fn test_csr_k_core_decomposition() {
+    // A 4-clique {0, 1, 2, 3}, a triangle {3, 4, 5} hanging off it, a path 5-6-7, an
+    // isolated node 8, and a self-loop on 7.
+    let undirected = [
+        (0, 1),
+        (0, 2),
+        (0, 3),
+        (1, 2),
+        (1, 3),
+        (2, 3),
+        (3, 4),
+        (3, 5),
+        (4, 5),
+        (5, 6),
+        (6, 7),
+        (7, 7),
+    ];
+    let mut adjacency = vec![Vec::new(); 9];
+    for &(u, v) in &undirected {
+        adjacency[u].push(v);
+        if u != v {
+            adjacency[v].push(u);
+        }
+    }
+    let graph = GhostCsrGraph::<4>::from_adjacency(&adjacency);
+    assert_eq!(graph.k_core_decomposition(), [3, 3, 3, 3, 2, 2, 1, 1, 0]);
+
+    let (core, nodes) = graph.k_core_subgraph(2);
+    assert_eq!(nodes, [0, 1, 2, 3, 4, 5]);
+    assert_eq!(core.edge_count(), 18);
+    assert_eq!(core.neighbors(5).collect::<Vec<_>>(), [3, 4]);
+    let (core, nodes) = graph.k_core_subgraph(4);
+    assert_eq!((core.node_count(), nodes.len()), (0, 0));
+}
+
+proptest! {
+    #[test]
+    fn test_csr_k_core_matches_naive_peeling(
+        (n, edges) in strategies::edges(60, 200, Just(())),
+    ) {
+        // Simple undirected graph: each edge stored both ways, no loops or repeats.
+        let mut adjacency = vec![Vec::new(); n];
+        for (u, v, ()) in edges {
+            if u != v && !adjacency[u].contains(&v) {
+                adjacency[u].push(v);
+                adjacency[v].push(u);
+            }
+        }
+        let graph = GhostCsrGraph::<8>::from_adjacency(&adjacency);
+        let core = graph.k_core_decomposition();
+
+        // Peel nodes of degree < k until none is left; the survivors form the k-core.
+        for k in 0..=core.iter().copied().max().unwrap() + 1 {
+            let mut alive = vec![true; n];
+            loop {
+                let dead: Vec<usize> = (0..n)
+                    .filter(|&v| alive[v] && adjacency[v].iter().filter(|&&u| alive[u]).count() < k)
+                    .collect();
+                if dead.is_empty() {
+                    break;
+                }
+                for v in dead {
+                    alive[v] = false;
+                }
+            }
+            for v in 0..n {
+                prop_assert_eq!(alive[v], core[v] >= k, "node {} at k={}", v, k);
+            }
+        }
+    }
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(64))]
+    #[test]
+    fn test_csr_parallel_reachable_from_roots(
+        (adjacency, roots) in strategies::adjacency(400, 2).prop_flat_map(|adjacency| {
+            let n = adjacency.len();
+            (Just(adjacency), proptest::collection::vec(0..n, 1..6))
+        }),
+    ) {
+        let n = adjacency.len();
+        let graph = GhostCsrGraph::<64>::from_adjacency(&adjacency);
+
+        let mut expected = vec![false; n];
+        let mut stack: Vec<usize> = roots.clone();
+        while let Some(u) = stack.pop() {
+            if !std::mem::replace(&mut expected[u], true) {
+                stack.extend(&adjacency[u]);
+            }
+        }
+        let expected: Vec<usize> = (0..n).filter(|&v| expected[v]).collect();
+
+        crate::GhostToken::new(|token| {
+            let marked = graph.parallel_reachable_from_roots(&token, &roots, 4);
+            prop_assert_eq!(&marked, &expected);
+            prop_assert!(marked.iter().all(|&v| graph.is_visited(v)));
+
+            // Marked nodes are not expanded again; a reset starts over.
+            prop_assert!(graph.parallel_reachable_from_roots(&token, &roots, 2).is_empty());
+            graph.reset_visited();
+            prop_assert_eq!(graph.parallel_reachable_from_roots(&token, &roots, 1), expected);
+            Ok(())
+        })?;
+    }
+}
@@ -3,6 +3,7 @@
 use core::sync::atomic::{AtomicUsize, Ordering};
 
 use crate::{
+    collections::BrandedFrontier,
     concurrency::atomic::GhostAtomicBitset,
     concurrency::worklist::{GhostChaseLevDeque, GhostTreiberStack},
     graph::compressed::csr_graph::GhostCsrGraph,
@@ -181,7 +182,7 @@ impl<'brand, const EDGE_CHUNK: usize> GhostCsrGraph<'brand, EDGE_CHUNK> {
 
         self.reset_visited();
         debug_assert!(self.try_visit(start));
-        assert!(deque.push_bottom(token, start), "deque capacity too small");
+        deque.push_bottom(token, start);
         let steal_token = token.split_immutable().0;
 
         let mut count = 1;
@@ -189,7 +190,7 @@ impl<'brand, const EDGE_CHUNK: usize> GhostCsrGraph<'brand, EDGE_CHUNK> {
         while let Some(node) = deque.steal(&steal_token) {
             for neighbor in self.neighbors(node) {
                 if self.try_visit(neighbor) {
-                    assert!(deque.push_bottom(token, neighbor), "deque capacity too small");
+                    deque.push_bottom(token, neighbor);
                     count += 1;
                 }
             }
@@ -323,35 +324,38 @@ impl<'brand, const EDGE_CHUNK: usize> GhostCsrGraph<'brand, EDGE_CHUNK> {
         count
     }
 
-    /// Breadth-first traversal using a queue, guarded by an atomic visited bitmap.
+    /// Breadth-first traversal, level by level through a [`BrandedFrontier`], guarded by
+    /// an atomic visited bitmap.
     ///
     /// **Time complexity**: \(O(n + m)\)
-    /// **Space complexity**: \(O(n)\) for queue and result
+    /// **Space complexity**: \(O(n)\) for the frontier and result
     pub fn bfs(&self, start: usize) -> Vec<usize> {
         assert!(start < self.node_count(), "start out of bounds");
 
         let mut out = Vec::with_capacity(self.node_count());
-        let mut q = std::collections::VecDeque::with_capacity(64);
+        let mut frontier = BrandedFrontier::with_capacity(64);
 
         if self.try_visit(start) {
-            q.push_back(start);
+            frontier.push_current(start);
         } else {
             return out;
         }
 
-        while let Some(u) = q.pop_front() {
-            out.push(u);
-
-            let start_i = unsafe { *self.offsets.get_unchecked(u) };
-            let end_i = unsafe { *self.offsets.get_unchecked(u + 1) };
-            let mut i = start_i;
-            while i < end_i {
-                let v = unsafe { *self.edges.get_unchecked(i) };
-                if unsafe { self.try_visit_unchecked(v) } {
-                    q.push_back(v);
+        while !frontier.is_empty() {
+            frontier.process_level(|u, next| {
+                out.push(u);
+
+                let start_i = unsafe { *self.offsets.get_unchecked(u) };
+                let end_i = unsafe { *self.offsets.get_unchecked(u + 1) };
+                let mut i = start_i;
+                while i < end_i {
+                    let v = unsafe { *self.edges.get_unchecked(i) };
+                    if unsafe { self.try_visit_unchecked(v) } {
+                        next.push_back(v);
+                    }
+                    i += 1;
                 }
-                i += 1;
-            }
+            });
         }
 
         out
@@ -369,30 +373,78 @@ impl<'brand, const EDGE_CHUNK: usize> GhostCsrGraph<'brand, EDGE_CHUNK> {
         assert!(start < self.node_count(), "start out of bounds");
 
         let mut out = Vec::with_capacity(self.node_count());
-        let mut q = std::collections::VecDeque::with_capacity(64);
+        let mut frontier = BrandedFrontier::with_capacity(64);
 
         if self.try_visit(start) {
-            q.push_back(start);
+            frontier.push_current(start);
         } else {
             return out;
         }
 
-        while let Some(u) = q.pop_front() {
-            out.push(u);
+        while !frontier.is_empty() {
+            frontier.process_level(|u, next| {
+                out.push(u);
+
+                let start_i = unsafe { *self.offsets.get_unchecked(u) };
+                let end_i = unsafe { *self.offsets.get_unchecked(u + 1) };
+                let mut i = start_i;
+                while i < end_i {
+                    let v = unsafe { *self.edges.get_unchecked(i) };
+                    if unsafe { self.try_visit_unchecked(v) } {
+                        next.push_back(v);
+                    }
+                    i += 1;
+                }
+            });
+        }
 
-            let start_i = unsafe { *self.offsets.get_unchecked(u) };
-            let end_i = unsafe { *self.offsets.get_unchecked(u + 1) };
-            let mut i = start_i;
-            while i < end_i {
-                let v = unsafe { *self.edges.get_unchecked(i) };
-                if unsafe { self.try_visit_unchecked(v) } {
-                    q.push_back(v);
+        out
+    }
+
+    /// Level-synchronous breadth-first search returning the hop distance of every node.
+    ///
+    /// Unreachable nodes are reported as `usize::MAX`. Levels are processed through a
+    /// [`BrandedFrontier`], so the per-level buffers are allocated once and swapped
+    /// rather than reallocated at each level.
+    ///
+    /// **Time complexity**: \(O(n + m)\)
+    /// **Space complexity**: \(O(n)\) for the frontier and result
+    ///
+    /// # Panics
+    ///
+    /// Panics if `start` is out of bounds.
+    pub fn bfs_distances(&self, start: usize) -> Vec<usize> {
+        assert!(start < self.node_count(), "start out of bounds");
+
+        let mut dist = vec![usize::MAX; self.node_count()];
+        let mut frontier = BrandedFrontier::with_capacity(64);
+
+        if self.try_visit(start) {
+            frontier.push_current(start);
+        } else {
+            return dist;
+        }
+
+        loop {
+            let level = frontier.level();
+            let more = frontier.process_level(|u, next| {
+                dist[u] = level;
+                let start_i = unsafe { *self.offsets.get_unchecked(u) };
+                let end_i = unsafe { *self.offsets.get_unchecked(u + 1) };
+                for i in start_i..end_i {
+                    let v = unsafe { *self.edges.get_unchecked(i) };
+                    // SAFETY: `from_*` constructors ensure all `v < node_count()`.
+                    if unsafe { self.try_visit_unchecked(v) } {
+                        next.push_back(v);
+                    }
                 }
-                i += 1;
+            });
+            if !more {
+                break;
             }
         }
 
-        out
+        dist
     }
 
     /// Parallel BFS traversal using work-stealing with caller-provided deques.
@@ -413,7 +465,7 @@ impl<'brand, const EDGE_CHUNK: usize> GhostCsrGraph<'brand, EDGE_CHUNK> {
         let count = AtomicUsize::new(0);
 
         if self.try_visit(start) {
-            assert!(deques[0].push_bottom(token, start), "deque capacity too small");
+            deques[0].push_bottom(token, start);
             outstanding.store(1, core::sync::atomic::Ordering::Relaxed);
         } else {
             return 0;
@@ -460,8 +512,7 @@ impl<'brand, const EDGE_CHUNK: usize> GhostCsrGraph<'brand, EDGE_CHUNK> {
                             if unsafe { self.try_visit_unchecked(v) } {
                                 // Account for new work first, then push.
                                 outstanding.fetch_add(1, core::sync::atomic::Ordering::Relaxed);
-                                let ok = me.push_bottom(token, v);
-                                assert!(ok, "deque capacity too small");
+                                me.push_bottom(token, v);
                             }
                         }
 
@@ -487,4 +538,165 @@ impl<'brand, const EDGE_CHUNK: usize> GhostCsrGraph<'brand, EDGE_CHUNK> {
             (0..threads).map(|_| GhostChaseLevDeque::new(cap)).collect();
         self.parallel_reachable_count_workstealing_with_deques(token, start, &deques)
     }
+
+    /// Parallel reachability from a set of roots, using work-stealing with
+    /// caller-provided deques. Returns the nodes this call marked visited, in increasing
+    /// order.
+    ///
+    /// The roots are dealt round-robin onto the deques, one per worker; each worker then
+    /// runs a DFS off its own deque and steals from the others when it runs dry.
+    /// `try_visit` decides which worker claims a node, so every node is expanded once.
+    ///
+    /// Nodes already marked in the visited bitmap count as visited and are not
+    /// expanded, so marking can be resumed from new roots, as in incremental
+    /// garbage-collection marking; call [`reset_visited`](Self::reset_visited) first
+    /// for a fresh traversal.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `deques` is empty or a root is out of bounds.
+    pub fn parallel_reachable_from_roots_with_deques(
+        &self,
+        token: &GhostToken<'brand>,
+        roots: &[usize],
+        deques: &[GhostChaseLevDeque<'brand>],
+    ) -> Vec<usize> {
+        let threads = deques.len();
+        assert!(threads != 0, "threads must be > 0");
+        for deque in deques {
+            deque.clear(token);
+        }
+        let mut seeded = 0;
+        for &root in roots {
+            assert!(root < self.node_count(), "root {root} out of bounds");
+            if self.try_visit(root) {
+                deques[seeded % threads].push_bottom(token, root);
+                seeded += 1;
+            }
+        }
+        if seeded == 0 {
+            return Vec::new();
+        }
+        let outstanding = AtomicUsize::new(seeded);
+
+        let mut marked: Vec<usize> = std::thread::scope(|scope| {
+            let outstanding = &outstanding;
+            let steal_token = token.split_immutable().0;
+            let workers: Vec<_> = (0..threads)
+                .map(|tid| {
+                    scope.spawn(move || {
+                        let me = &deques[tid];
+                        let mut local = Vec::new();
+                        loop {
+                            let task = me.pop_bottom(token).or_else(|| {
+                                (1..threads)
+                                    .find_map(|k| deques[(tid + k) % threads].steal(&steal_token))
+                            });
+                            let Some(u) = task else {
+                                if outstanding.load(Ordering::Acquire) == 0 {
+                                    break;
+                                }
+                                core::hint::spin_loop();
+                                continue;
+                            };
+
+                            local.push(u);
+                            for i in (self.offsets[u]..self.offsets[u + 1]).rev() {
+                                // SAFETY: constructors ensure all edges are in-bounds.
+                                let v = unsafe { *self.edges.get_unchecked(i) };
+                                if unsafe { self.try_visit_unchecked(v) } {
+                                    // Account for new work first, then push.
+                                    outstanding.fetch_add(1, Ordering::Relaxed);
+                                    me.push_bottom(token, v);
+                                }
+                            }
+                            outstanding.fetch_sub(1, Ordering::Release);
+                        }
+                        local
+                    })
+                })
+                .collect();
+            workers
+                .into_iter()
+                .flat_map(|worker| worker.join().unwrap())
+                .collect()
+        });
+        marked.sort_unstable();
+        marked
+    }
+
+    /// Convenience wrapper that allocates `threads` deques and runs
+    /// [`Self::parallel_reachable_from_roots_with_deques`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if `threads` is zero or a root is out of bounds.
+    pub fn parallel_reachable_from_roots(
+        &self,
+        token: &GhostToken<'brand>,
+        roots: &[usize],
+        threads: usize,
+    ) -> Vec<usize> {
+        assert!(threads != 0, "threads must be > 0");
+        let cap = self.node_count().next_power_of_two().max(64);
+        let deques: Vec<GhostChaseLevDeque<'brand>> =
+            (0..threads).map(|_| GhostChaseLevDeque::new(cap)).collect();
+        self.parallel_reachable_from_roots_with_deques(token, roots, &deques)
+    }
+
+    /// Orders the nodes so that every edge goes from an earlier node to a later one,
+    /// with Kahn's algorithm.
+    ///
+    /// **Time complexity**: \(O(n + m)\)
+    ///
+    /// # Errors
+    ///
+    /// If the graph has a cycle, no such order exists and one of the cycles is
+    /// returned instead, in the form of [`CycleError`].
+    pub fn topological_sort(&self) -> Result<Vec<usize>, CycleError> {
+        let n = self.node_count();
+        let mut in_degree: Vec<usize> = (0..n).map(|v| self.in_degree(v)).collect();
+        let mut order: Vec<usize> = (0..n).filter(|&v| in_degree[v] == 0).collect();
+        let mut head = 0;
+        while let Some(&u) = order.get(head) {
+            head += 1;
+            for v in self.neighbors(u) {
+                in_degree[v] -= 1;
+                if in_degree[v] == 0 {
+                    order.push(v);
+                }
+            }
+        }
+        if order.len() == n {
+            return Ok(order);
+        }
+
+        // Every node left over has an in-edge from another left-over node, so walking
+        // those edges backwards must end up going around a cycle.
+        let pred = |v: usize| {
+            self.in_neighbors(v)
+                .into_iter()
+                .find(|&u| in_degree[u] != 0)
+                .unwrap_or_else(|| unreachable!("left-over nodes have left-over predecessors"))
+        };
+        let Some(mut start) = (0..n).find(|&v| in_degree[v] != 0) else {
+            unreachable!("nodes left over");
+        };
+        for _ in 0..n {
+            start = pred(start);
+        }
+        let mut cycle = vec![start];
+        let mut v = pred(start);
+        while v != start {
+            cycle.push(v);
+            v = pred(v);
+        }
+        cycle.reverse();
+        Err(CycleError(cycle))
+    }
 }
+
+/// Error returned by [`GhostCsrGraph::topological_sort`] when the graph has a cycle:
+/// the cycle's nodes, in edge order.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CycleError(pub Vec<usize>);
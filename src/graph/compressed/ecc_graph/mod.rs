@@ -21,6 +21,7 @@
 
 use core::sync::atomic::Ordering;
 
+use crate::collections::BrandedFrontier;
 use crate::graph::access::visited::VisitedSet;
 
 pub use storage::{EccEdge, EdgeCentricStorage};
@@ -116,23 +117,25 @@ impl<'brand> GhostEccGraph<'brand> {
         assert!(start < self.node_count, "start out of bounds");
 
         let mut out = Vec::with_capacity(self.node_count);
-        let mut q = std::collections::VecDeque::with_capacity(64);
+        let mut frontier = BrandedFrontier::with_capacity(64);
 
         if self.try_visit(start) {
-            q.push_back(start);
+            frontier.push_current(start);
         } else {
             return out;
         }
 
-        while let Some(u) = q.pop_front() {
-            out.push(u);
+        while !frontier.is_empty() {
+            frontier.process_level(|u, next| {
+                out.push(u);
 
-            // Use edge-centric neighbor access
-            for v in self.neighbors(u) {
-                if self.try_visit(v) {
-                    q.push_back(v);
+                // Use edge-centric neighbor access
+                for v in self.neighbors(u) {
+                    if self.try_visit(v) {
+                        next.push_back(v);
+                    }
                 }
-            }
+            });
         }
 
         out
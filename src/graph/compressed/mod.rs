@@ -7,8 +7,16 @@ pub mod compressed_graph;
 pub mod csc_graph;
 pub mod csr_graph;
 pub mod ecc_graph;
+pub mod flow_network;
+pub mod scc;
+#[cfg(test)]
+mod strategies;
+pub mod weighted_csr_graph;
 
 pub use compressed_graph::GhostCompressedGraph;
 pub use csc_graph::GhostCscGraph;
-pub use csr_graph::GhostCsrGraph;
+pub use csr_graph::{CycleError, GhostCsrGraph};
 pub use ecc_graph::GhostEccGraph;
+pub use flow_network::GhostFlowNetwork;
+pub use scc::scc_kosaraju;
+pub use weighted_csr_graph::{GhostWeightedCsrGraph, NegativeCycle};
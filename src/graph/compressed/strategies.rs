@@ -0,0 +1,40 @@
+//! Proptest strategies for random graphs, shared by the compressed-graph tests.
+
+use proptest::collection::vec;
+use proptest::prelude::*;
+
+/// Directed graphs of `1..=max_nodes` nodes as adjacency lists, with up to
+/// `max_degree` out-edges per node. Self-loops and parallel edges occur.
+pub(crate) fn adjacency(
+    max_nodes: usize,
+    max_degree: usize,
+) -> impl Strategy<Value = Vec<Vec<usize>>> {
+    (1..=max_nodes).prop_flat_map(move |n| vec(vec(0..n, 0..=max_degree), n))
+}
+
+/// Directed graphs of `1..=max_nodes` nodes as adjacency lists of `(target, weight)`,
+/// with up to `max_degree` out-edges per node. Self-loops and parallel edges occur.
+pub(crate) fn weighted_adjacency<W>(
+    max_nodes: usize,
+    max_degree: usize,
+    weight: W,
+) -> impl Strategy<Value = Vec<Vec<(usize, W::Value)>>>
+where
+    W: Strategy + Clone,
+{
+    (1..=max_nodes).prop_flat_map(move |n| vec(vec((0..n, weight.clone()), 0..=max_degree), n))
+}
+
+/// Edge lists `(n, edges)` over `1..=max_nodes` nodes with up to `max_edges` edges
+/// `(from, to, weight)`. Self-loops and parallel edges occur.
+pub(crate) fn edges<W>(
+    max_nodes: usize,
+    max_edges: usize,
+    weight: W,
+) -> impl Strategy<Value = (usize, Vec<(usize, usize, W::Value)>)>
+where
+    W: Strategy + Clone,
+{
+    (1..=max_nodes)
+        .prop_flat_map(move |n| (Just(n), vec((0..n, 0..n, weight.clone()), 0..=max_edges)))
+}
@@ -0,0 +1,225 @@
+//! All-pairs shortest paths: cache-blocked Floyd–Warshall.
+//!
+//! The distance matrix is processed in `BLOCK`-sized pivot rounds. In each round the
+//! pivot band (the rows of the round's pivot vertices) is finished first; every other
+//! band of rows then only reads the pivot band and writes itself, so the bands are
+//! independent and the parallel variant hands them out to workers. Its workers start
+//! once and meet at a barrier between the phases of every round.
+
+use super::GhostWeightedCsrGraph;
+use crate::collections::BrandedMatrix;
+use crate::concurrency::scoped::broadcast;
+use crate::concurrency::sync::GhostBarrier;
+use crate::GhostToken;
+use core::ops::{Add, Range};
+use core::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::any::Any;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::RwLock;
+
+/// Side of a tile: three 64×64 tiles of 16-byte entries fit in a typical L2 cache.
+const BLOCK: usize = 64;
+
+impl<'brand, W, const EDGE_CHUNK: usize> GhostWeightedCsrGraph<'brand, W, EDGE_CHUNK> {
+    /// Computes the distances between all pairs of nodes with a cache-blocked
+    /// Floyd–Warshall, in \(O(n^3)\) time and \(O(n^2)\) space.
+    ///
+    /// Entry `(u, v)` of the returned matrix is the length of a shortest path from `u`
+    /// to `v`, or `None` if `v` is unreachable from `u`; the diagonal is
+    /// `W::default()`. Suited to small and medium graphs, dense ones in particular;
+    /// for a few sources on a sparse graph, run [`dijkstra`](Self::dijkstra) per source.
+    ///
+    /// Negative weights are allowed but negative cycles are not: with one, distances
+    /// through it are meaningless (and integer weights may overflow). Check with
+    /// [`bellman_ford`](Self::bellman_ford) first if the graph may contain one.
+    pub fn all_pairs_shortest_paths(&self) -> BrandedMatrix<'brand, Option<W>>
+    where
+        W: Copy + Ord + Add<Output = W> + Default,
+    {
+        let n = self.node_count();
+        let mut dist = self.initial_distances();
+        for pivots in blocks(n) {
+            let (pivot, bands) = split_pivot_band(&mut dist, n, &pivots);
+            finish_pivot_band(pivot, n, &pivots);
+            for band in bands {
+                relax_band(band, pivot, n, &pivots);
+            }
+        }
+        BrandedMatrix::from_vec(dist.into_iter().collect(), n, n)
+    }
+
+    /// Like [`all_pairs_shortest_paths`](Self::all_pairs_shortest_paths), but relaxes
+    /// the bands of each round on `threads` workers sharing `token`.
+    ///
+    /// The workers are started once for the whole computation. In every round worker
+    /// `0` finishes the pivot band, and after a barrier all workers claim and relax the
+    /// other bands; a second barrier closes the round.
+    ///
+    /// # Panics
+    /// Panics if `threads` is zero.
+    pub fn parallel_all_pairs_shortest_paths(
+        &self,
+        token: &GhostToken<'brand>,
+        threads: usize,
+    ) -> BrandedMatrix<'brand, Option<W>>
+    where
+        W: Copy + Ord + Add<Output = W> + Default + Send + Sync,
+    {
+        assert!(threads != 0, "threads must be > 0");
+        let n = self.node_count();
+        // One lock per band of `BLOCK` rows; round `r` reads band `r` as its pivot band.
+        let bands: Vec<RwLock<Vec<Option<W>>>> = self
+            .initial_distances()
+            .chunks(BLOCK * n.max(1))
+            .map(|band| RwLock::new(band.to_vec()))
+            .collect();
+        let barrier = GhostBarrier::new(threads);
+        let next = AtomicUsize::new(0);
+        let failed = AtomicBool::new(false);
+
+        broadcast(token, threads, |token, worker| {
+            let mut payload = None;
+            for (round, pivots) in blocks(n).enumerate() {
+                if worker == 0 {
+                    guarded(&failed, &mut payload, || {
+                        finish_pivot_band(&mut bands[round].write().unwrap(), n, &pivots);
+                        next.store(0, Ordering::Relaxed);
+                    });
+                }
+                barrier.wait(token);
+                guarded(&failed, &mut payload, || loop {
+                    let band = next.fetch_add(1, Ordering::Relaxed);
+                    if band >= bands.len() {
+                        break;
+                    }
+                    if band != round {
+                        let pivot = bands[round].read().unwrap();
+                        relax_band(&mut bands[band].write().unwrap(), &pivot, n, &pivots);
+                    }
+                });
+                barrier.wait(token);
+            }
+            if let Some(payload) = payload {
+                panic::resume_unwind(payload);
+            }
+        });
+        let dist = bands
+            .into_iter()
+            .flat_map(|band| band.into_inner().unwrap())
+            .collect();
+        BrandedMatrix::from_vec(dist, n, n)
+    }
+
+    /// Returns the row-major `n × n` matrix of direct edge weights, with zeros on the
+    /// diagonal and the lightest of parallel edges.
+    fn initial_distances(&self) -> Vec<Option<W>>
+    where
+        W: Copy + Ord + Default,
+    {
+        let n = self.node_count();
+        let mut dist = vec![None; n * n];
+        for u in 0..n {
+            dist[u * n + u] = Some(W::default());
+            for (v, &w) in self.neighbors_weighted(u) {
+                let d = &mut dist[u * n + v];
+                *d = Some(d.map_or(w, |cur| cur.min(w)));
+            }
+        }
+        dist
+    }
+}
+
+/// Runs `work` unless a worker has already panicked, and records its panic otherwise.
+///
+/// A panicking worker must still reach every barrier of the remaining rounds, or the
+/// others would wait for it forever; it keeps its payload to resume once they are done.
+fn guarded(failed: &AtomicBool, payload: &mut Option<Box<dyn Any + Send>>, work: impl FnOnce()) {
+    if failed.load(Ordering::Relaxed) {
+        return;
+    }
+    if let Err(p) = panic::catch_unwind(AssertUnwindSafe(work)) {
+        failed.store(true, Ordering::Relaxed);
+        *payload = Some(p);
+    }
+}
+
+/// Splits `0..n` into consecutive ranges of `BLOCK`.
+fn blocks(n: usize) -> impl Iterator<Item = Range<usize>> {
+    (0..n)
+        .step_by(BLOCK)
+        .map(move |start| start..n.min(start + BLOCK))
+}
+
+/// Splits the matrix into the pivot band and the other bands of `BLOCK` rows.
+fn split_pivot_band<'a, W>(
+    dist: &'a mut [Option<W>],
+    n: usize,
+    pivots: &Range<usize>,
+) -> (
+    &'a mut [Option<W>],
+    impl Iterator<Item = &'a mut [Option<W>]>,
+) {
+    let (above, rest) = dist.split_at_mut(pivots.start * n);
+    let (pivot, below) = rest.split_at_mut(pivots.len() * n);
+    let bands = above
+        .chunks_mut(BLOCK * n)
+        .chain(below.chunks_mut(BLOCK * n));
+    (pivot, bands)
+}
+
+/// Runs the round's pivots over the pivot band itself: the diagonal tile first, then
+/// the rest of the band, tile by tile.
+fn finish_pivot_band<W>(pivot: &mut [Option<W>], n: usize, pivots: &Range<usize>)
+where
+    W: Copy + Ord + Add<Output = W>,
+{
+    let tiles = core::iter::once(pivots.clone()).chain(blocks(n).filter(|t| t != pivots));
+    for cols in tiles {
+        for k in pivots.clone() {
+            let (before, rest) = pivot.split_at_mut((k - pivots.start) * n);
+            let (row_k, after) = rest.split_at_mut(n);
+            // Row `k` itself could only improve through a negative cycle.
+            relax_rows(before, n, k, row_k, &cols);
+            relax_rows(after, n, k, row_k, &cols);
+        }
+    }
+}
+
+/// Runs the round's pivots over a band outside the pivot band: the tile in the pivot
+/// columns first, since the other tiles read it, then the others.
+fn relax_band<W>(band: &mut [Option<W>], pivot: &[Option<W>], n: usize, pivots: &Range<usize>)
+where
+    W: Copy + Ord + Add<Output = W>,
+{
+    let tiles = core::iter::once(pivots.clone()).chain(blocks(n).filter(|t| t != pivots));
+    for cols in tiles {
+        for k in pivots.clone() {
+            let row_k = &pivot[(k - pivots.start) * n..][..n];
+            relax_rows(band, n, k, row_k, &cols);
+        }
+    }
+}
+
+/// Relaxes `rows[i][j]` through pivot `k` for every row and every `j` in `cols`.
+#[inline]
+fn relax_rows<W>(
+    rows: &mut [Option<W>],
+    n: usize,
+    k: usize,
+    row_k: &[Option<W>],
+    cols: &Range<usize>,
+) where
+    W: Copy + Ord + Add<Output = W>,
+{
+    for row in rows.chunks_exact_mut(n) {
+        let Some(dik) = row[k] else { continue };
+        for (d, &dkj) in row[cols.clone()].iter_mut().zip(&row_k[cols.clone()]) {
+            if let Some(dkj) = dkj {
+                let nd = dik + dkj;
+                if d.is_none_or(|cur| nd < cur) {
+                    *d = Some(nd);
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,275 @@
+//! A CSR graph with a weight on every edge, and shortest paths over it.
+//!
+//! [`dijkstra`](GhostWeightedCsrGraph::dijkstra) handles non-negative weights;
+//! [`bellman_ford`](GhostWeightedCsrGraph::bellman_ford) also negative ones, and
+//! reports a [`NegativeCycle`] when shortest paths do not exist.
+//! [`all_pairs_shortest_paths`](GhostWeightedCsrGraph::all_pairs_shortest_paths) fills
+//! a dense distance matrix with a cache-blocked Floyd–Warshall.
+//! [`minimum_spanning_tree`](GhostWeightedCsrGraph::minimum_spanning_tree) reads the
+//! graph as undirected, with [`kruskal`](GhostWeightedCsrGraph::kruskal) and
+//! [`prim`](GhostWeightedCsrGraph::prim) both available.
+//!
+//! Memory layout:
+//! - `offsets`: `Vec<usize>` of length `n + 1` (row offsets)
+//! - `edges`: chunked contiguous `usize` targets for each row
+//! - `weights`: chunked weights, parallel to `edges`: `weights[i]` belongs to `edges[i]`
+
+use crate::collections::{BrandedIndexedHeap, ChunkedVec};
+use crate::GhostToken;
+use core::marker::PhantomData;
+use core::ops::Add;
+
+/// A CSR graph whose edges carry weights of type `W`.
+///
+/// Laid out like [`GhostCsrGraph`](super::GhostCsrGraph), with the weights in a second
+/// chunked array aligned with the edge array, so the weighted and unweighted views of
+/// a row are both a contiguous scan. The brand ties the graph to a token scope; the
+/// structure itself is immutable once built.
+///
+/// ### Performance Characteristics
+/// | Operation | Complexity | Notes |
+/// |-----------|------------|-------|
+/// | `from_adjacency` | \(O(n + m)\) | Builds CSR from a weighted adjacency list |
+/// | `neighbors` | \(O(1)\) | Returns iterator over outgoing neighbors |
+/// | `neighbors_weighted` | \(O(1)\) | Returns iterator over `(target, &weight)` pairs |
+/// | `degree` | \(O(1)\) | Returns out-degree |
+/// | `dijkstra` | \(O((n + m) \log n)\) | Indexed heap with `decrease_key` |
+/// | `bellman_ford` | \(O(n m)\) | Negative weights, reports negative cycles |
+/// | `all_pairs_shortest_paths` | \(O(n^3)\) | Blocked Floyd–Warshall, optionally parallel |
+/// | `kruskal` | \(O(m \log m)\) | Minimum spanning forest with a union-find |
+/// | `prim` | \(O((n + m) \log n)\) | Minimum spanning forest with an indexed heap |
+pub struct GhostWeightedCsrGraph<'brand, W, const EDGE_CHUNK: usize> {
+    offsets: Vec<usize>,
+    edges: ChunkedVec<usize, EDGE_CHUNK>,
+    weights: ChunkedVec<W, EDGE_CHUNK>,
+    _brand: PhantomData<fn(&'brand ()) -> &'brand ()>,
+}
+
+/// Error returned by [`GhostWeightedCsrGraph::bellman_ford`] when a negative cycle is
+/// reachable from the source: the cycle's vertices, in edge order.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NegativeCycle(pub Vec<usize>);
+
+impl<W, const EDGE_CHUNK: usize> GhostWeightedCsrGraph<'_, W, EDGE_CHUNK> {
+    /// Builds a weighted CSR graph from an adjacency list of `(target, weight)` pairs.
+    ///
+    /// # Panics
+    ///
+    /// Panics if any edge references a node index out of bounds.
+    pub fn from_adjacency(adjacency: Vec<Vec<(usize, W)>>) -> Self {
+        let n = adjacency.len();
+        let m = adjacency.iter().map(Vec::len).sum();
+        let mut offsets = Vec::with_capacity(n + 1);
+        let mut edges: ChunkedVec<usize, EDGE_CHUNK> = ChunkedVec::new();
+        let mut weights: ChunkedVec<W, EDGE_CHUNK> = ChunkedVec::new();
+        edges.reserve(m);
+        weights.reserve(m);
+        offsets.push(0);
+        for (u, nbrs) in adjacency.into_iter().enumerate() {
+            for (v, w) in nbrs {
+                assert!(v < n, "edge {u}->{v} is out of bounds for n={n}");
+                edges.push(v);
+                weights.push(w);
+            }
+            offsets.push(edges.len());
+        }
+        Self {
+            offsets,
+            edges,
+            weights,
+            _brand: PhantomData,
+        }
+    }
+
+    /// Builds a weighted CSR graph directly from CSR parts.
+    ///
+    /// # Panics
+    /// - if `offsets.len() < 2`
+    /// - if offsets are not monotone
+    /// - if `offsets.last() != edges.len()` or `weights.len() != edges.len()`
+    /// - if an edge target is out of bounds
+    pub fn from_csr_parts(offsets: Vec<usize>, edges: Vec<usize>, weights: Vec<W>) -> Self {
+        assert!(offsets.len() >= 2, "offsets must have length n+1");
+        let n = offsets.len() - 1;
+        for w in offsets.windows(2) {
+            assert!(w[0] <= w[1], "offsets must be monotone");
+        }
+        let m = *offsets.last().expect("offsets non-empty");
+        assert!(m == edges.len(), "offsets last must equal edges length");
+        assert!(m == weights.len(), "weights length must equal edges length");
+        for &v in &edges {
+            assert!(v < n, "edge to {v} out of bounds for n={n}");
+        }
+        Self {
+            offsets,
+            edges: edges.into(),
+            weights: weights.into(),
+            _brand: PhantomData,
+        }
+    }
+
+    /// Number of nodes.
+    pub fn node_count(&self) -> usize {
+        self.offsets.len().saturating_sub(1)
+    }
+
+    /// Number of edges.
+    pub fn edge_count(&self) -> usize {
+        self.edges.len()
+    }
+
+    /// Returns the range of edge slots of `node`.
+    fn row(&self, node: usize) -> core::ops::Range<usize> {
+        assert!(node < self.node_count(), "node {node} out of bounds");
+        self.offsets[node]..self.offsets[node + 1]
+    }
+
+    /// Returns the out-neighbors of `node`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `node` is out of bounds.
+    pub fn neighbors(&self, node: usize) -> impl Iterator<Item = usize> + '_ {
+        let edges = &self.edges;
+        self.row(node).map(move |i| unsafe {
+            // SAFETY: CSR construction ensures `i < edge_count()`.
+            *edges.get_unchecked(i)
+        })
+    }
+
+    /// Returns the out-neighbors of `node` with the weights of the edges to them.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `node` is out of bounds.
+    pub fn neighbors_weighted(&self, node: usize) -> impl Iterator<Item = (usize, &W)> + '_ {
+        let (edges, weights) = (&self.edges, &self.weights);
+        self.row(node).map(move |i| unsafe {
+            // SAFETY: CSR construction ensures `i < edge_count()`, and `weights` has
+            // one entry per edge.
+            (*edges.get_unchecked(i), weights.get_unchecked(i))
+        })
+    }
+
+    /// Returns the out-degree of a node.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `node` is out of bounds.
+    pub fn degree(&self, node: usize) -> usize {
+        self.row(node).len()
+    }
+
+    /// Computes shortest paths from `source` with Dijkstra's algorithm.
+    ///
+    /// Returns `(dist, parents)`: `dist[v]` is the length of a shortest path from
+    /// `source` to `v`, and `parents[v]` the node before `v` on it, so `parents` is a
+    /// shortest-path tree rooted at `source`. Both are `None` for unreachable nodes,
+    /// and `parents[source]` is `None`.
+    ///
+    /// Weights must be non-negative, with `W::default()` as zero; with negative weights
+    /// the results are meaningless, though the search still terminates.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `source` is out of bounds.
+    pub fn dijkstra(&self, source: usize) -> (Vec<Option<W>>, Vec<Option<usize>>)
+    where
+        W: Copy + Ord + Add<Output = W> + Default,
+    {
+        let n = self.node_count();
+        assert!(source < n, "source {source} out of bounds");
+        let mut dist = vec![None; n];
+        let mut parents = vec![None; n];
+        // A popped node's distance is final. Skipping it afterwards also keeps a
+        // negative cycle from looping forever.
+        let mut settled = vec![false; n];
+        GhostToken::new(|mut token| {
+            let mut heap = BrandedIndexedHeap::with_capacity(n);
+            dist[source] = Some(W::default());
+            heap.push(&mut token, source, W::default());
+            while let Some((u, d)) = heap.pop_min(&mut token) {
+                settled[u] = true;
+                for (v, &w) in self.neighbors_weighted(u) {
+                    let nd = d + w;
+                    if !settled[v] && dist[v].is_none_or(|cur| nd < cur) {
+                        dist[v] = Some(nd);
+                        parents[v] = Some(u);
+                        heap.push_or_decrease(&mut token, v, nd);
+                    }
+                }
+            }
+        });
+        (dist, parents)
+    }
+
+    /// Computes shortest paths from `source` with the Bellman-Ford algorithm, which,
+    /// unlike [`dijkstra`](Self::dijkstra), accepts negative weights.
+    ///
+    /// Returns `(dist, parents)` in the same form as `dijkstra`.
+    ///
+    /// # Errors
+    ///
+    /// If a cycle of negative total weight is reachable from `source`, shortest paths
+    /// are undefined and the cycle is returned instead: `NegativeCycle(c)` has an edge
+    /// from `c[i]` to `c[i + 1]` and from the last vertex back to `c[0]`. Negative
+    /// cycles that `source` cannot reach do not matter and are not reported.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `source` is out of bounds.
+    #[allow(clippy::type_complexity)]
+    pub fn bellman_ford(
+        &self,
+        source: usize,
+    ) -> Result<(Vec<Option<W>>, Vec<Option<usize>>), NegativeCycle>
+    where
+        W: Copy + Ord + Add<Output = W> + Default,
+    {
+        let n = self.node_count();
+        assert!(source < n, "source {source} out of bounds");
+        let mut dist = vec![None; n];
+        let mut parents = vec![None; n];
+        dist[source] = Some(W::default());
+        // Without negative cycles every shortest path has at most `n - 1` edges, so
+        // the distances settle within `n - 1` rounds; a change in round `n` means
+        // there is a cycle.
+        let mut relaxed = None;
+        for _ in 0..n {
+            relaxed = None;
+            for u in 0..n {
+                let Some(du) = dist[u] else { continue };
+                for (v, &w) in self.neighbors_weighted(u) {
+                    let nd = du + w;
+                    if dist[v].is_none_or(|cur| nd < cur) {
+                        dist[v] = Some(nd);
+                        parents[v] = Some(u);
+                        relaxed = Some(v);
+                    }
+                }
+            }
+            if relaxed.is_none() {
+                return Ok((dist, parents));
+            }
+        }
+        // A vertex relaxed in round `n` has a negative cycle on its parent chain;
+        // `n` steps back along the chain are certain to land on the cycle.
+        let mut start = relaxed.expect("relaxed in the last round");
+        for _ in 0..n {
+            start = parents[start].expect("relaxed vertices have parents");
+        }
+        let mut cycle = vec![start];
+        let mut v = parents[start].expect("cycle vertices have parents");
+        while v != start {
+            cycle.push(v);
+            v = parents[v].expect("cycle vertices have parents");
+        }
+        cycle.reverse();
+        Err(NegativeCycle(cycle))
+    }
+}
+
+mod all_pairs;
+mod mst;
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,103 @@
+//! Minimum spanning trees: Kruskal's and Prim's algorithms.
+//!
+//! Both read the graph as undirected. Kruskal's looks at every stored edge once, so
+//! storing an undirected edge in one direction is enough; Prim's grows trees along
+//! out-edges, so it needs each edge stored in both directions, which is how a CSR
+//! graph holds an undirected graph.
+
+use super::GhostWeightedCsrGraph;
+use crate::collections::{BrandedIndexedHeap, BrandedUnionFind};
+use crate::GhostToken;
+use core::ops::Add;
+
+impl<W, const EDGE_CHUNK: usize> GhostWeightedCsrGraph<'_, W, EDGE_CHUNK> {
+    /// Computes a minimum spanning tree with [`kruskal`](Self::kruskal).
+    ///
+    /// Returns the tree's edges as `(u, v, weight)` and their total weight. On a
+    /// disconnected graph, the result is a minimum spanning forest: one tree per
+    /// connected component.
+    pub fn minimum_spanning_tree(&self) -> (Vec<(usize, usize, W)>, W)
+    where
+        W: Copy + Ord + Add<Output = W> + Default,
+    {
+        self.kruskal()
+    }
+
+    /// Computes a minimum spanning forest with Kruskal's algorithm: edges in order of
+    /// weight, each kept if it joins two trees, as told by a union-find.
+    ///
+    /// Returns the kept edges as `(u, v, weight)`, with `u -> v` as stored, in order
+    /// of weight, and their total weight (`W::default()` for no edges).
+    ///
+    /// **Time complexity**: \(O(m \log m)\)
+    pub fn kruskal(&self) -> (Vec<(usize, usize, W)>, W)
+    where
+        W: Copy + Ord + Add<Output = W> + Default,
+    {
+        let n = self.node_count();
+        let mut edges: Vec<(usize, usize, W)> = (0..n)
+            .flat_map(|u| self.neighbors_weighted(u).map(move |(v, &w)| (u, v, w)))
+            .collect();
+        // Stable, so equal weights keep their storage order and the tree is
+        // deterministic.
+        edges.sort_by_key(|&(_, _, w)| w);
+
+        let mut tree = Vec::with_capacity(n.saturating_sub(1));
+        GhostToken::new(|mut token| {
+            let mut sets = BrandedUnionFind::with_singletons(n);
+            for (u, v, w) in edges {
+                if sets.union(&mut token, u, v) {
+                    tree.push((u, v, w));
+                    if tree.len() + 1 == n {
+                        break;
+                    }
+                }
+            }
+        });
+        let total = tree.iter().fold(W::default(), |acc, &(_, _, w)| acc + w);
+        (tree, total)
+    }
+
+    /// Computes a minimum spanning forest with Prim's algorithm: from each node not yet
+    /// reached, a tree grows by its lightest edge to a node outside it, found with an
+    /// indexed heap of the cheapest known edge into every outside node.
+    ///
+    /// Returns the tree's edges as `(parent, child, weight)`, in the order the
+    /// children joined, and their total weight. Requires every undirected edge to be
+    /// stored in both directions.
+    ///
+    /// **Time complexity**: \(O((n + m) \log n)\)
+    pub fn prim(&self) -> (Vec<(usize, usize, W)>, W)
+    where
+        W: Copy + Ord + Add<Output = W> + Default,
+    {
+        let n = self.node_count();
+        let mut tree = Vec::with_capacity(n.saturating_sub(1));
+        let mut in_tree = vec![false; n];
+        // The cheapest known edge `(parent, weight)` into each node outside the tree.
+        let mut best: Vec<Option<(usize, W)>> = vec![None; n];
+        GhostToken::new(|mut token| {
+            let mut heap = BrandedIndexedHeap::with_capacity(n);
+            for root in 0..n {
+                if in_tree[root] {
+                    continue;
+                }
+                heap.push(&mut token, root, W::default());
+                while let Some((u, _)) = heap.pop_min(&mut token) {
+                    in_tree[u] = true;
+                    if let Some((p, w)) = best[u] {
+                        tree.push((p, u, w));
+                    }
+                    for (v, &w) in self.neighbors_weighted(u) {
+                        if !in_tree[v] && best[v].is_none_or(|(_, cur)| w < cur) {
+                            best[v] = Some((u, w));
+                            heap.push_or_decrease(&mut token, v, w);
+                        }
+                    }
+                }
+            }
+        });
+        let total = tree.iter().fold(W::default(), |acc, &(_, _, w)| acc + w);
+        (tree, total)
+    }
+}
@@ -0,0 +1,319 @@
+//! Tests for the weighted CSR graph.
+
+use super::*;
+use crate::graph::compressed::strategies;
+use crate::GhostToken;
+use proptest::prelude::*;
+
+#[test]
+fn test_weighted_csr_dijkstra_distances_and_tree() {
+    // 0 -4-> 1, 0 -1-> 2, 2 -2-> 1, 1 -5-> 3, 2 -8-> 3, 3 -0-> 4; 5 is isolated.
+    let graph = GhostWeightedCsrGraph::<_, 4>::from_adjacency(vec![
+        vec![(1, 4u32), (2, 1)],
+        vec![(3, 5)],
+        vec![(1, 2), (3, 8)],
+        vec![(4, 0)],
+        vec![],
+        vec![(0, 1)],
+    ]);
+    assert_eq!(graph.node_count(), 6);
+    assert_eq!(graph.edge_count(), 7);
+    assert_eq!(graph.degree(2), 2);
+    assert_eq!(graph.neighbors(2).collect::<Vec<_>>(), [1, 3]);
+    assert_eq!(
+        graph.neighbors_weighted(2).collect::<Vec<_>>(),
+        [(1, &2), (3, &8)]
+    );
+
+    let (dist, parents) = graph.dijkstra(0);
+    assert_eq!(dist, [Some(0), Some(3), Some(1), Some(8), Some(8), None]);
+    assert_eq!(parents, [None, Some(2), Some(0), Some(1), Some(3), None]);
+
+    // Walking the tree back from a node retraces a shortest path.
+    let mut path = vec![4];
+    while let Some(p) = parents[*path.last().unwrap()] {
+        path.push(p);
+    }
+    assert_eq!(path, [4, 3, 1, 2, 0]);
+}
+
+proptest! {
+    #[test]
+    fn test_weighted_csr_dijkstra_matches_bellman_ford(
+        adjacency in strategies::weighted_adjacency(40, 4, 0..20u64),
+    ) {
+        let n = adjacency.len();
+        let graph = GhostWeightedCsrGraph::<_, 8>::from_adjacency(adjacency.clone());
+        let (dist, parents) = graph.dijkstra(0);
+
+        // Naive Bellman-Ford: n rounds of relaxing every edge.
+        let mut expected = vec![None; n];
+        expected[0] = Some(0u64);
+        for _ in 0..n {
+            for (u, nbrs) in adjacency.iter().enumerate() {
+                let Some(du) = expected[u] else { continue };
+                for &(v, w) in nbrs {
+                    if expected[v].map_or(true, |dv| du + w < dv) {
+                        expected[v] = Some(du + w);
+                    }
+                }
+            }
+        }
+        prop_assert_eq!(&dist, &expected);
+        // Every tree edge is tight.
+        for v in 0..n {
+            if let Some(p) = parents[v] {
+                let w = adjacency[p]
+                    .iter()
+                    .filter(|&&(t, _)| t == v)
+                    .map(|&(_, w)| w)
+                    .min()
+                    .unwrap();
+                prop_assert_eq!(dist[p].unwrap() + w, dist[v].unwrap());
+            }
+        }
+    }
+}
+
+#[test]
+fn test_weighted_csr_from_parts() {
+    let graph =
+        GhostWeightedCsrGraph::<_, 4>::from_csr_parts(vec![0, 1, 2, 2], vec![1, 2], vec![3i64, 4]);
+    assert_eq!(graph.dijkstra(0).0, [Some(0), Some(3), Some(7)]);
+    assert_eq!(graph.dijkstra(2).0, [None, None, Some(0)]);
+}
+
+#[test]
+fn test_weighted_csr_weights_stay_aligned_across_chunks() {
+    // A single row of 10 edges spans several chunks.
+    let row: Vec<(usize, String)> = (0..10).map(|v| (v, format!("w{v}"))).collect();
+    let graph = GhostWeightedCsrGraph::<_, 2>::from_adjacency(
+        std::iter::once(row)
+            .chain((1..10).map(|_| Vec::new()))
+            .collect(),
+    );
+    assert_eq!(graph.degree(0), 10);
+    for (v, w) in graph.neighbors_weighted(0) {
+        assert_eq!(*w, format!("w{v}"));
+    }
+    assert_eq!(graph.neighbors_weighted(9).count(), 0);
+}
+
+#[test]
+fn test_weighted_csr_bellman_ford_negative_weights() {
+    // 0 -4-> 1, 0 -2-> 2, 2 -(-3)-> 1, 1 -1-> 3; 4 is unreachable.
+    let graph = GhostWeightedCsrGraph::<_, 4>::from_adjacency(vec![
+        vec![(1, 4i32), (2, 2)],
+        vec![(3, 1)],
+        vec![(1, -3)],
+        vec![],
+        vec![(0, -10)],
+    ]);
+    let (dist, parents) = graph.bellman_ford(0).unwrap();
+    assert_eq!(dist, [Some(0), Some(-1), Some(2), Some(0), None]);
+    assert_eq!(parents, [None, Some(2), Some(0), Some(1), None]);
+
+    // Without negative weights it agrees with Dijkstra.
+    let graph = GhostWeightedCsrGraph::<_, 4>::from_adjacency(vec![
+        vec![(1, 4u32), (2, 1)],
+        vec![(3, 5)],
+        vec![(1, 2), (3, 8)],
+        vec![],
+    ]);
+    assert_eq!(graph.bellman_ford(0).unwrap(), graph.dijkstra(0));
+}
+
+#[test]
+fn test_weighted_csr_bellman_ford_reports_negative_cycle() {
+    // 1 -> 2 -> 3 -> 1 weighs -1 in total; 4 -> 5 -> 4 is negative but unreachable.
+    let graph = GhostWeightedCsrGraph::<_, 4>::from_adjacency(vec![
+        vec![(1, 1i64)],
+        vec![(2, 2)],
+        vec![(3, -4), (6, 1)],
+        vec![(1, 1)],
+        vec![(5, -1)],
+        vec![(4, -1)],
+        vec![],
+    ]);
+    let NegativeCycle(mut cycle) = graph.bellman_ford(0).unwrap_err();
+    // The cycle may be reported from any of its vertices.
+    let first = cycle.iter().position(|&v| v == 1).unwrap();
+    cycle.rotate_left(first);
+    assert_eq!(cycle, [1, 2, 3]);
+
+    assert!(graph.bellman_ford(6).is_ok());
+    assert!(graph.bellman_ford(4).is_err());
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(48))]
+
+    #[test]
+    fn test_weighted_csr_all_pairs_matches_naive_floyd_warshall(
+        // Up to 150 nodes span three blocks, the last one partial.
+        (adjacency, potential) in strategies::weighted_adjacency(150, 5, 0..30i64)
+            .prop_flat_map(|adjacency| {
+                let n = adjacency.len();
+                (Just(adjacency), proptest::collection::vec(0..50i64, n))
+            }),
+    ) {
+        // Potentials turn non-negative weights into negative ones without creating a
+        // negative cycle: `w + p[u] - p[v]` sums to `w` plus a constant along any path.
+        let adjacency: Vec<Vec<(usize, i64)>> = adjacency
+            .into_iter()
+            .enumerate()
+            .map(|(u, row)| {
+                row.into_iter()
+                    .map(|(v, w)| (v, w + potential[u] - potential[v]))
+                    .collect()
+            })
+            .collect();
+        let n = adjacency.len();
+
+        // Textbook Floyd-Warshall over a dense matrix.
+        let mut expected = vec![vec![None; n]; n];
+        for (u, row) in adjacency.iter().enumerate() {
+            expected[u][u] = Some(0);
+            for &(v, w) in row {
+                if expected[u][v].map_or(true, |d| w < d) {
+                    expected[u][v] = Some(w);
+                }
+            }
+        }
+        for k in 0..n {
+            for u in 0..n {
+                for v in 0..n {
+                    if let (Some(a), Some(b)) = (expected[u][k], expected[k][v]) {
+                        if expected[u][v].map_or(true, |d| a + b < d) {
+                            expected[u][v] = Some(a + b);
+                        }
+                    }
+                }
+            }
+        }
+
+        GhostToken::new(|token| {
+            let graph = GhostWeightedCsrGraph::<_, 16>::from_adjacency(adjacency);
+            let sequential = graph.all_pairs_shortest_paths();
+            let parallel = graph.parallel_all_pairs_shortest_paths(&token, 4);
+            prop_assert_eq!((sequential.rows(), sequential.cols()), (n, n));
+            for (u, row) in expected.iter().enumerate() {
+                for (v, expected) in row.iter().enumerate() {
+                    prop_assert_eq!(sequential.get(&token, u, v), Some(expected));
+                    prop_assert_eq!(parallel.get(&token, u, v), Some(expected));
+                }
+            }
+            Ok(())
+        })?;
+    }
+}
+
+#[test]
+fn test_weighted_csr_all_pairs_empty() {
+    GhostToken::new(|token| {
+        let empty = GhostWeightedCsrGraph::<u32, 4>::from_adjacency(Vec::new());
+        assert_eq!(empty.all_pairs_shortest_paths().rows(), 0);
+        assert_eq!(empty.parallel_all_pairs_shortest_paths(&token, 3).rows(), 0);
+    });
+}
+
+#[test]
+#[should_panic(expected = "path too long")]
+fn test_weighted_csr_parallel_all_pairs_resumes_a_worker_panic() {
+    /// A weight whose sums panic past 1, so that relaxing a band outside the pivot band
+    /// panics on whichever worker claims it.
+    #[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default)]
+    struct Short(u8);
+    impl core::ops::Add for Short {
+        type Output = Self;
+        fn add(self, other: Self) -> Self {
+            assert!(self.0 + other.0 <= 1, "path too long");
+            Short(self.0 + other.0)
+        }
+    }
+
+    // 100 -> 0 -> 1 is relaxed in round 0 from band 1 (rows 64..128).
+    let mut adjacency = vec![Vec::new(); 130];
+    adjacency[0].push((1, Short(1)));
+    adjacency[100].push((0, Short(1)));
+    GhostToken::new(|token| {
+        let graph = GhostWeightedCsrGraph::<_, 16>::from_adjacency(adjacency);
+        graph.parallel_all_pairs_shortest_paths(&token, 4);
+    });
+}
+
+#[test]
+fn test_weighted_csr_minimum_spanning_tree() {
+    // Undirected edges, stored both ways: 0-1 (4), 0-2 (1), 1-2 (2), 1-3 (5), 2-3 (8);
+    // 4-5 (3) is a second component and 6 is isolated.
+    let undirected = [
+        (0, 1, 4u32),
+        (0, 2, 1),
+        (1, 2, 2),
+        (1, 3, 5),
+        (2, 3, 8),
+        (4, 5, 3),
+    ];
+    let mut adjacency = vec![Vec::new(); 7];
+    for &(u, v, w) in &undirected {
+        adjacency[u].push((v, w));
+        adjacency[v].push((u, w));
+    }
+    let graph = GhostWeightedCsrGraph::<_, 4>::from_adjacency(adjacency);
+
+    let (tree, total) = graph.minimum_spanning_tree();
+    assert_eq!(tree, [(0, 2, 1), (1, 2, 2), (4, 5, 3), (1, 3, 5)]);
+    assert_eq!(total, 11);
+
+    let (tree, total) = graph.prim();
+    assert_eq!(tree, [(0, 2, 1), (2, 1, 2), (1, 3, 5), (4, 5, 3)]);
+    assert_eq!(total, 11);
+
+    let empty = GhostWeightedCsrGraph::<u32, 4>::from_adjacency(Vec::new());
+    assert_eq!(empty.kruskal(), (Vec::new(), 0));
+    assert_eq!(empty.prim(), (Vec::new(), 0));
+}
+
+proptest! {
+    #[test]
+    fn test_weighted_csr_kruskal_and_prim_match_naive_forest(
+        (n, edges) in strategies::edges(60, 150, -20..80i64),
+    ) {
+        let mut adjacency: Vec<Vec<(usize, i64)>> = vec![Vec::new(); n];
+        for &(u, v, w) in &edges {
+            adjacency[u].push((v, w));
+            adjacency[v].push((u, w));
+        }
+        let graph = GhostWeightedCsrGraph::<_, 16>::from_adjacency(adjacency);
+
+        // Naive Kruskal: scan edges by weight, relabelling a whole component on
+        // every merge.
+        let mut sorted = edges.clone();
+        sorted.sort_by_key(|&(_, _, w)| w);
+        let mut label: Vec<usize> = (0..n).collect();
+        let (mut expected_total, mut expected_edges) = (0, 0);
+        for (u, v, w) in sorted {
+            let (from, to) = (label[v], label[u]);
+            if from != to {
+                label.iter_mut().filter(|l| **l == from).for_each(|l| *l = to);
+                expected_total += w;
+                expected_edges += 1;
+            }
+        }
+
+        for (tree, total) in [graph.kruskal(), graph.prim()] {
+            prop_assert_eq!(total, expected_total);
+            prop_assert_eq!(tree.len(), expected_edges);
+            prop_assert_eq!(tree.iter().map(|&(_, _, w)| w).sum::<i64>(), total);
+            // A forest of that many edges with no cycle spans every component.
+            GhostToken::new(|mut token| {
+                let mut sets = crate::collections::BrandedUnionFind::with_singletons(n);
+                for &(u, v, _) in &tree {
+                    prop_assert!(label[u] == label[v], "tree edge {}-{} crosses components", u, v);
+                    prop_assert!(sets.union(&mut token, u, v), "tree edge closes a cycle");
+                }
+                Ok(())
+            })?;
+        }
+    }
+}
@@ -6,23 +6,37 @@
 //! - `GhostAdjacencyGraph`
 //! - `GhostBipartiteGraph`
 //! - `GhostDag`
-//! - Compressed formats (`compressed` module)
+//! - `GhostStaticGraph`, a compile-time-sized graph with no heap storage
+//! - Compressed formats (`compressed` module), including the weighted
+//!   `GhostWeightedCsrGraph` with Dijkstra shortest paths and the `GhostFlowNetwork`
+//!   with Dinic's maximum flow
 //! - Specialized formats (`specialized` module)
+//! - `GraphRegistry` for lazily loaded CSR snapshots
+//! - Incrementally maintained PageRank and components (`analytics` module)
 
 pub(crate) mod access;
+pub mod analytics;
 pub mod adj_list;
 pub mod adjacency_graph;
 pub mod bipartite_graph;
 pub mod compressed;
 pub mod dag;
 pub mod pool_graph;
+pub mod registry;
 pub mod specialized;
+pub mod static_graph;
 pub mod traversal;
 
 // Re-export commonly used types from submodules
 pub use adj_list::AdjListGraph;
+pub use analytics::{IncrementalComponents, IncrementalPageRank};
 pub use adjacency_graph::GhostAdjacencyGraph;
 pub use bipartite_graph::GhostBipartiteGraph;
-pub use compressed::{GhostCscGraph, GhostCsrGraph};
+pub use compressed::{
+    CycleError, GhostCscGraph, GhostCsrGraph, GhostFlowNetwork, GhostWeightedCsrGraph,
+    NegativeCycle,
+};
 pub use dag::GhostDag;
 pub use pool_graph::BrandedPoolGraph;
+pub use registry::{GraphRegistry, RegistryStats};
+pub use static_graph::{GhostStaticGraph, StaticNodeList};
@@ -0,0 +1,198 @@
+//! `GraphRegistry` — named CSR graphs loaded lazily from snapshot files.
+//!
+//! A server hosting many datasets registers every graph up front (name → snapshot
+//! path) but only pays for the ones that are actually queried. Each entry owns a
+//! [`GhostOnceLock`]; the first [`get`](GraphRegistry::get) for a name reads the
+//! snapshot (see [`GhostCsrGraph::read_snapshot`]) and every later call returns the
+//! same frozen graph by shared reference.
+//!
+//! Registration is a structural change and takes `&mut self`; lookups take `&self` and
+//! a shared token, so a registry can be shared across request handlers once built.
+
+use crate::concurrency::sync::GhostOnceLock;
+use crate::graph::GhostCsrGraph;
+use crate::token::traits::GhostBorrow;
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{self, BufReader};
+use std::path::{Path, PathBuf};
+
+struct RegistryEntry<'brand, const EDGE_CHUNK: usize> {
+    path: PathBuf,
+    graph: GhostOnceLock<'brand, GhostCsrGraph<'brand, EDGE_CHUNK>>,
+}
+
+/// Aggregate statistics over the graphs a [`GraphRegistry`] has loaded.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct RegistryStats {
+    /// Number of registered names.
+    pub registered: usize,
+    /// Number of graphs loaded so far.
+    pub loaded: usize,
+    /// Total nodes across loaded graphs.
+    pub nodes: usize,
+    /// Total edges across loaded graphs.
+    pub edges: usize,
+}
+
+/// A registry of named graphs, each loaded from its snapshot file on first use.
+pub struct GraphRegistry<'brand, const EDGE_CHUNK: usize> {
+    entries: HashMap<String, RegistryEntry<'brand, EDGE_CHUNK>>,
+}
+
+impl<'brand, const EDGE_CHUNK: usize> GraphRegistry<'brand, EDGE_CHUNK> {
+    /// Creates an empty registry.
+    pub fn new() -> Self {
+        Self {
+            entries: HashMap::new(),
+        }
+    }
+
+    /// Registers `name` as backed by the snapshot at `path`. Nothing is read yet.
+    ///
+    /// Re-registering a name replaces its path and discards any loaded graph; the old
+    /// path is returned.
+    pub fn register(&mut self, name: impl Into<String>, path: impl AsRef<Path>) -> Option<PathBuf> {
+        let entry = RegistryEntry {
+            path: path.as_ref().to_path_buf(),
+            graph: GhostOnceLock::new(),
+        };
+        self.entries.insert(name.into(), entry).map(|old| old.path)
+    }
+
+    /// Removes `name`, returning its loaded graph if there was one.
+    pub fn unregister(&mut self, name: &str) -> Option<GhostCsrGraph<'brand, EDGE_CHUNK>> {
+        self.entries.remove(name)?.graph.into_inner()
+    }
+
+    /// Returns `true` if `name` is registered.
+    pub fn contains(&self, name: &str) -> bool {
+        self.entries.contains_key(name)
+    }
+
+    /// Returns `true` if `name` is registered and already loaded.
+    pub fn is_loaded(&self, token: &impl GhostBorrow<'brand>, name: &str) -> bool {
+        self.entries
+            .get(name)
+            .is_some_and(|e| e.graph.is_initialized(token))
+    }
+
+    /// Returns the graph registered as `name`, loading its snapshot on first use.
+    ///
+    /// If two threads race on the first load, one reads the file while the other
+    /// waits for it; callers always observe a single graph per name.
+    ///
+    /// # Errors
+    /// Returns `NotFound` for an unregistered name, and any error from opening or
+    /// parsing the snapshot. A failed load is not cached; the next call retries.
+    pub fn get<'a>(
+        &'a self,
+        token: &'a impl GhostBorrow<'brand>,
+        name: &str,
+    ) -> io::Result<&'a GhostCsrGraph<'brand, EDGE_CHUNK>> {
+        let entry = self.entries.get(name).ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::NotFound,
+                format!("graph `{name}` is not registered"),
+            )
+        })?;
+        entry.graph.get_or_try_init(token, || {
+            GhostCsrGraph::read_snapshot(BufReader::new(File::open(&entry.path)?))
+        })
+    }
+
+    /// Iterates over the names of loaded graphs, in arbitrary order.
+    pub fn loaded_names<'a, Token>(
+        &'a self,
+        token: &'a Token,
+    ) -> impl Iterator<Item = &'a str> + use<'a, 'brand, Token, EDGE_CHUNK>
+    where
+        Token: GhostBorrow<'brand>,
+    {
+        self.entries
+            .iter()
+            .filter(move |(_, e)| e.graph.is_initialized(token))
+            .map(|(name, _)| name.as_str())
+    }
+
+    /// Reports how many graphs are registered and loaded, and their total size.
+    pub fn stats(&self, token: &impl GhostBorrow<'brand>) -> RegistryStats {
+        let mut stats = RegistryStats {
+            registered: self.entries.len(),
+            ..RegistryStats::default()
+        };
+        for graph in self.entries.values().filter_map(|e| e.graph.get(token)) {
+            stats.loaded += 1;
+            stats.nodes += graph.node_count();
+            stats.edges += graph.edge_count();
+        }
+        stats
+    }
+}
+
+impl<'brand, const EDGE_CHUNK: usize> Default for GraphRegistry<'brand, EDGE_CHUNK> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::GhostToken;
+
+    fn snapshot_path(tag: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("halo-registry-{}-{tag}.csr", std::process::id()))
+    }
+
+    #[test]
+    fn graph_registry_loads_lazily_and_reports_stats() {
+        let small = snapshot_path("small");
+        let ring = snapshot_path("ring");
+        GhostToken::new(|token| {
+            GhostCsrGraph::<'_, 64>::from_adjacency(&[vec![1], vec![]])
+                .write_snapshot(File::create(&small).unwrap())
+                .unwrap();
+            GhostCsrGraph::<'_, 64>::from_adjacency(&[vec![1], vec![2], vec![0]])
+                .write_snapshot(File::create(&ring).unwrap())
+                .unwrap();
+
+            let mut registry = GraphRegistry::<'_, 64>::new();
+            registry.register("small", &small);
+            registry.register("ring", &ring);
+            registry.register("missing", snapshot_path("missing"));
+            assert_eq!(registry.stats(&token).loaded, 0);
+
+            let g = registry.get(&token, "ring").unwrap();
+            assert_eq!(g.neighbors(2).collect::<Vec<_>>(), vec![0]);
+            assert_eq!(g.in_neighbors(0), vec![2]);
+            assert!(core::ptr::eq(g, registry.get(&token, "ring").unwrap()));
+            assert!(registry.is_loaded(&token, "ring"));
+            assert!(!registry.is_loaded(&token, "small"));
+            assert_eq!(
+                registry.loaded_names(&token).collect::<Vec<_>>(),
+                vec!["ring"]
+            );
+
+            registry.get(&token, "small").unwrap();
+            assert_eq!(
+                registry.stats(&token),
+                RegistryStats {
+                    registered: 3,
+                    loaded: 2,
+                    nodes: 5,
+                    edges: 4,
+                }
+            );
+
+            let err = registry.get(&token, "missing").err().unwrap();
+            assert_eq!(err.kind(), io::ErrorKind::NotFound);
+            let err = registry.get(&token, "unknown").err().unwrap();
+            assert_eq!(err.kind(), io::ErrorKind::NotFound);
+            assert!(registry.unregister("small").is_some());
+            assert_eq!(registry.stats(&token).loaded, 1);
+        });
+        let _ = std::fs::remove_file(small);
+        let _ = std::fs::remove_file(ring);
+    }
+}
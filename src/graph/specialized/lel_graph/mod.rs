@@ -8,6 +8,7 @@
 use core::sync::atomic::Ordering;
 
 use crate::collections::vec::BrandedVec;
+use crate::collections::BrandedFrontier;
 use crate::graph::access::visited::VisitedSet;
 use crate::graph::compressed::ecc_graph::EccEdge;
 use crate::token::traits::GhostBorrow;
@@ -134,21 +135,23 @@ impl<'brand> GhostLelGraph<'brand> {
         assert!(start < self.node_count, "start out of bounds");
 
         let mut out = Vec::with_capacity(self.node_count);
-        let mut q = std::collections::VecDeque::with_capacity(64);
+        let mut frontier = BrandedFrontier::with_capacity(64);
 
         if self.try_visit(start) {
-            q.push_back(start);
+            frontier.push_current(start);
         } else {
             return out;
         }
 
-        while let Some(u) = q.pop_front() {
-            out.push(u);
-            for v in self.neighbors(u) {
-                if self.try_visit(v) {
-                    q.push_back(v);
+        while !frontier.is_empty() {
+            frontier.process_level(|u, next| {
+                out.push(u);
+                for v in self.neighbors(u) {
+                    if self.try_visit(v) {
+                        next.push_back(v);
+                    }
                 }
-            }
+            });
         }
 
         out
@@ -0,0 +1,359 @@
+//! `GhostStaticGraph` — a directed graph whose size is fixed at compile time.
+//!
+//! Both the node count `N` and the maximum out-degree `D` are const generics, so the
+//! whole graph is a pair of inline arrays:
+//! - `adjacency`: `[[usize; D]; N]`, the first `degrees[u]` slots of row `u` are live;
+//! - `degrees`: `[usize; N]` out-degree per node.
+//!
+//! Nothing here touches the heap. Traversals keep their visited flags and queue/stack
+//! on the stack as `[_; N]` arrays and return a fixed-capacity [`StaticNodeList`], so the
+//! graph suits embedded targets and hot loops whose topology is known at build time.
+//! Construction is `const`, so a graph can live in a `static`.
+
+use core::marker::PhantomData;
+use core::ops::Deref;
+
+/// A directed graph with `N` nodes and at most `D` outgoing edges per node.
+///
+/// Rows keep insertion order (until an edge is removed); duplicate edges are ignored.
+///
+/// ### Performance Characteristics
+/// | Operation | Complexity | Notes |
+/// |-----------|------------|-------|
+/// | `from_edges` | \(O(m \cdot D)\) | `const`, duplicate check per row |
+/// | `neighbors` | \(O(1)\) | Returns a slice of the row |
+/// | `degree` | \(O(1)\) | Reads `degrees[u]` |
+/// | `has_edge` | \(O(D)\) | Linear scan of the row |
+/// | `bfs` / `dfs` | \(O(N + m)\) | No allocation |
+#[derive(Clone, Copy)]
+pub struct GhostStaticGraph<'brand, const N: usize, const D: usize> {
+    adjacency: [[usize; D]; N],
+    degrees: [usize; N],
+    edge_count: usize,
+    _brand: PhantomData<fn(&'brand ()) -> &'brand ()>,
+}
+
+impl<'brand, const N: usize, const D: usize> GhostStaticGraph<'brand, N, D> {
+    /// Creates a graph with `N` nodes and no edges.
+    pub const fn new() -> Self {
+        Self {
+            adjacency: [[0; D]; N],
+            degrees: [0; N],
+            edge_count: 0,
+            _brand: PhantomData,
+        }
+    }
+
+    /// Builds a graph from a list of `(from, to)` edges.
+    ///
+    /// # Panics
+    /// Panics if an endpoint is out of bounds or a node would exceed `D` out-edges.
+    /// In a `const` context these are compile-time errors.
+    pub const fn from_edges(edges: &[(usize, usize)]) -> Self {
+        let mut graph = Self::new();
+        let mut i = 0;
+        while i < edges.len() {
+            let (from, to) = edges[i];
+            graph.add_edge(from, to);
+            i += 1;
+        }
+        graph
+    }
+
+    /// Adds the edge `from -> to`, returning `false` if it was already present.
+    ///
+    /// # Panics
+    /// Panics if an endpoint is out of bounds or `from` already has `D` out-edges.
+    pub const fn add_edge(&mut self, from: usize, to: usize) -> bool {
+        assert!(from < N && to < N, "edge endpoint out of bounds");
+        if self.has_edge(from, to) {
+            return false;
+        }
+        let degree = self.degrees[from];
+        assert!(degree < D, "out-degree exceeds D");
+        self.adjacency[from][degree] = to;
+        self.degrees[from] = degree + 1;
+        self.edge_count += 1;
+        true
+    }
+
+    /// Removes the edge `from -> to`, returning whether it was present.
+    ///
+    /// The last edge of the row takes the removed slot, so row order is not preserved.
+    ///
+    /// # Panics
+    /// Panics if `from` is out of bounds.
+    pub fn remove_edge(&mut self, from: usize, to: usize) -> bool {
+        let degree = self.degrees[from];
+        let row = &mut self.adjacency[from][..degree];
+        match row.iter().position(|&v| v == to) {
+            Some(pos) => {
+                row.swap(pos, degree - 1);
+                self.degrees[from] = degree - 1;
+                self.edge_count -= 1;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Number of nodes.
+    #[inline]
+    pub const fn node_count(&self) -> usize {
+        N
+    }
+
+    /// Number of edges.
+    #[inline]
+    pub const fn edge_count(&self) -> usize {
+        self.edge_count
+    }
+
+    /// Out-degree of `node`.
+    ///
+    /// # Panics
+    /// Panics if `node >= N`.
+    #[inline]
+    pub const fn degree(&self, node: usize) -> usize {
+        self.degrees[node]
+    }
+
+    /// Returns the outgoing neighbors of `node`.
+    ///
+    /// # Panics
+    /// Panics if `node >= N`.
+    #[inline]
+    pub fn neighbors(&self, node: usize) -> &[usize] {
+        &self.adjacency[node][..self.degrees[node]]
+    }
+
+    /// Returns `true` if the edge `from -> to` exists.
+    ///
+    /// # Panics
+    /// Panics if `from >= N`.
+    #[inline]
+    pub const fn has_edge(&self, from: usize, to: usize) -> bool {
+        let row = &self.adjacency[from];
+        let mut i = 0;
+        while i < self.degrees[from] {
+            if row[i] == to {
+                return true;
+            }
+            i += 1;
+        }
+        false
+    }
+
+    /// Breadth-first traversal from `start`, in visit order.
+    ///
+    /// **Time complexity**: \(O(N + m)\)
+    /// **Space complexity**: \(O(N)\) on the stack
+    ///
+    /// # Panics
+    /// Panics if `start >= N`.
+    pub fn bfs(&self, start: usize) -> StaticNodeList<N> {
+        assert!(start < N, "start out of bounds");
+        let mut visited = [false; N];
+        // Each node is enqueued at most once, so the output doubles as the queue.
+        let mut out = StaticNodeList::new();
+        visited[start] = true;
+        out.push(start);
+
+        let mut head = 0;
+        while head < out.len {
+            let u = out.nodes[head];
+            head += 1;
+            for &v in self.neighbors(u) {
+                if !visited[v] {
+                    visited[v] = true;
+                    out.push(v);
+                }
+            }
+        }
+        out
+    }
+
+    /// Depth-first (preorder) traversal from `start`.
+    ///
+    /// Neighbors are explored in row order.
+    ///
+    /// **Time complexity**: \(O(N + m)\)
+    /// **Space complexity**: \(O(N)\) on the stack
+    ///
+    /// # Panics
+    /// Panics if `start >= N`.
+    pub fn dfs(&self, start: usize) -> StaticNodeList<N> {
+        assert!(start < N, "start out of bounds");
+        let mut visited = [false; N];
+        let mut out = StaticNodeList::new();
+        // `(node, next row slot)` frames; the path never repeats a node.
+        let mut stack = [(0usize, 0usize); N];
+        let mut depth = 1;
+        stack[0] = (start, 0);
+        visited[start] = true;
+        out.push(start);
+
+        while depth > 0 {
+            let (u, slot) = stack[depth - 1];
+            if let Some(&v) = self.neighbors(u).get(slot) {
+                stack[depth - 1].1 += 1;
+                if !visited[v] {
+                    visited[v] = true;
+                    out.push(v);
+                    stack[depth] = (v, 0);
+                    depth += 1;
+                }
+            } else {
+                depth -= 1;
+            }
+        }
+        out
+    }
+
+    /// Hop distance from `start` to every node; unreachable nodes are `usize::MAX`.
+    ///
+    /// # Panics
+    /// Panics if `start >= N`.
+    pub fn bfs_distances(&self, start: usize) -> [usize; N] {
+        assert!(start < N, "start out of bounds");
+        let mut dist = [usize::MAX; N];
+        let mut queue = [0usize; N];
+        let (mut head, mut tail) = (0, 1);
+        queue[0] = start;
+        dist[start] = 0;
+
+        while head < tail {
+            let u = queue[head];
+            head += 1;
+            for &v in self.neighbors(u) {
+                if dist[v] == usize::MAX {
+                    dist[v] = dist[u] + 1;
+                    queue[tail] = v;
+                    tail += 1;
+                }
+            }
+        }
+        dist
+    }
+
+    /// Number of nodes reachable from `start`, including `start` itself.
+    ///
+    /// # Panics
+    /// Panics if `start >= N`.
+    pub fn reachable_count(&self, start: usize) -> usize {
+        self.bfs(start).len()
+    }
+}
+
+impl<'brand, const N: usize, const D: usize> Default for GhostStaticGraph<'brand, N, D> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A list of at most `N` node indices, stored inline.
+///
+/// Returned by the traversals of [`GhostStaticGraph`]; dereferences to `[usize]`.
+#[derive(Clone, Copy)]
+pub struct StaticNodeList<const N: usize> {
+    nodes: [usize; N],
+    len: usize,
+}
+
+impl<const N: usize> StaticNodeList<N> {
+    #[inline]
+    const fn new() -> Self {
+        Self {
+            nodes: [0; N],
+            len: 0,
+        }
+    }
+
+    #[inline]
+    fn push(&mut self, node: usize) {
+        self.nodes[self.len] = node;
+        self.len += 1;
+    }
+
+    /// Returns the nodes as a slice.
+    #[inline]
+    pub fn as_slice(&self) -> &[usize] {
+        &self.nodes[..self.len]
+    }
+}
+
+impl<const N: usize> Deref for StaticNodeList<N> {
+    type Target = [usize];
+
+    #[inline]
+    fn deref(&self) -> &[usize] {
+        self.as_slice()
+    }
+}
+
+impl<const N: usize> core::fmt::Debug for StaticNodeList<N> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_list().entries(self.as_slice()).finish()
+    }
+}
+
+impl<const N: usize> PartialEq<[usize]> for StaticNodeList<N> {
+    fn eq(&self, other: &[usize]) -> bool {
+        self.as_slice() == other
+    }
+}
+
+impl<'a, const N: usize> IntoIterator for &'a StaticNodeList<N> {
+    type Item = &'a usize;
+    type IntoIter = core::slice::Iter<'a, usize>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.as_slice().iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Built entirely at compile time.
+    static DIAMOND: GhostStaticGraph<'static, 5, 2> =
+        GhostStaticGraph::from_edges(&[(0, 1), (0, 2), (1, 3), (2, 3), (3, 0)]);
+
+    #[test]
+    fn static_graph_traversals() {
+        assert_eq!(DIAMOND.node_count(), 5);
+        assert_eq!(DIAMOND.edge_count(), 5);
+        assert_eq!(DIAMOND.neighbors(0), &[1, 2]);
+        assert!(DIAMOND.has_edge(3, 0));
+        assert!(!DIAMOND.has_edge(0, 3));
+
+        assert_eq!(DIAMOND.bfs(0).as_slice(), &[0, 1, 2, 3]);
+        assert_eq!(DIAMOND.dfs(0).as_slice(), &[0, 1, 3, 2]);
+        assert_eq!(DIAMOND.bfs_distances(1), [2, 0, 3, 1, usize::MAX]);
+        assert_eq!(DIAMOND.reachable_count(4), 1);
+    }
+
+    #[test]
+    fn static_graph_edits() {
+        let mut g = GhostStaticGraph::<'_, 3, 2>::new();
+        assert!(g.add_edge(0, 1));
+        assert!(!g.add_edge(0, 1));
+        assert!(g.add_edge(0, 2));
+        assert_eq!(g.degree(0), 2);
+
+        assert!(g.remove_edge(0, 1));
+        assert!(!g.remove_edge(0, 1));
+        assert_eq!(g.neighbors(0), &[2]);
+        assert_eq!(g.edge_count(), 1);
+        assert_eq!(g.dfs(0).as_slice(), &[0, 2]);
+    }
+
+    #[test]
+    #[should_panic(expected = "out-degree exceeds D")]
+    fn static_graph_rejects_degree_overflow() {
+        let mut g = GhostStaticGraph::<'_, 3, 1>::new();
+        g.add_edge(0, 1);
+        g.add_edge(0, 2);
+    }
+}
@@ -0,0 +1,296 @@
+//! `halo::kv` — a small durable in-memory key-value store.
+//!
+//! This is a reference for how the crate's pieces fit together in a real subsystem
+//! rather than a production database:
+//!
+//! - **Tokens and collections**: entries live in a
+//!   [`ShardedBrandedHashMap`], which owns the brand's `GhostToken`, so readers on
+//!   different shards never contend and no caller ever handles the token.
+//! - **Concurrency**: every method takes `&self`; a write holds its shard's lock while
+//!   it appends to the log and updates the map, so the log order of any one key
+//!   always matches the order its updates were applied.
+//! - **Durability**: mutations are appended to a [write-ahead log](wal) before they
+//!   become visible, and [`KvStore::checkpoint`] writes a [snapshot] and starts a
+//!   fresh log. [`KvStore::recover`] rebuilds a store from the latest snapshot plus
+//!   the log written after it.
+//!
+//! Values are reference-counted byte slices, so reads hand out cheap clones instead
+//! of holding shard locks. See `examples/kv_store.rs` for the store running on
+//! `HaloAllocator` with several writer threads.
+//!
+//! ```
+//! use halo::kv::KvStore;
+//! use halo::GhostToken;
+//!
+//! GhostToken::new(|token| {
+//!     let store = KvStore::new(token, Vec::new());
+//!     store.put(&b"user:1"[..], &b"ada"[..]).unwrap();
+//!     store.put(&b"user:2"[..], &b"grace"[..]).unwrap();
+//!     store.delete(b"user:1").unwrap();
+//!
+//!     let (_token, log) = store.into_parts();
+//!     GhostToken::new(|token| {
+//!         let recovered = KvStore::recover(token, None::<&[u8]>, &log[..], Vec::new()).unwrap();
+//!         assert_eq!(recovered.get(b"user:2").as_deref(), Some(&b"grace"[..]));
+//!         assert!(recovered.get(b"user:1").is_none());
+//!     });
+//! });
+//! ```
+
+pub mod snapshot;
+pub mod wal;
+
+pub use snapshot::{read_snapshot, SnapshotWriter};
+pub use wal::{OwnedRecord, Record, WalReader, WalWriter};
+
+use crate::collections::hash::hash_map::RawEntryMut;
+use crate::collections::ShardedBrandedHashMap;
+use crate::GhostToken;
+use std::io::{self, Read, Write};
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+
+/// Shared, immutable bytes used for keys and values.
+pub type Bytes = Arc<[u8]>;
+
+/// A concurrent in-memory key-value store backed by a write-ahead log.
+pub struct KvStore<'brand, W: Write> {
+    map: ShardedBrandedHashMap<'brand, Bytes, Bytes>,
+    wal: Mutex<WalWriter<W>>,
+}
+
+impl<'brand, W: Write> KvStore<'brand, W> {
+    /// Creates an empty store owning `token` and logging to `wal`.
+    pub fn new(token: GhostToken<'brand>, wal: W) -> Self {
+        Self {
+            map: ShardedBrandedHashMap::new(token),
+            wal: Mutex::new(WalWriter::new(wal)),
+        }
+    }
+
+    /// Rebuilds a store from an optional snapshot and the log written after it, then
+    /// continues logging to `wal`.
+    ///
+    /// A partial record at the end of `log` (from a crash mid-write) is ignored.
+    ///
+    /// # Errors
+    /// Fails with `InvalidData` if the snapshot or a complete log record is corrupt,
+    /// or with any I/O error from the readers.
+    pub fn recover<S: Read, L: Read>(
+        token: GhostToken<'brand>,
+        snapshot: Option<S>,
+        log: L,
+        wal: W,
+    ) -> io::Result<Self> {
+        let store = Self::new(token, wal);
+        if let Some(snapshot) = snapshot {
+            for (key, value) in read_snapshot(snapshot)? {
+                store.map.insert(key.into(), value.into());
+            }
+        }
+        // Replaying on top of the snapshot is safe even if it already contains some
+        // of these updates: each record sets or clears its key outright.
+        for record in WalReader::new(log) {
+            match record? {
+                OwnedRecord::Put(key, value) => {
+                    store.map.insert(key.into(), value.into());
+                }
+                OwnedRecord::Delete(key) => {
+                    store.map.remove(&key[..]);
+                }
+            }
+        }
+        Ok(store)
+    }
+
+    fn wal(&self) -> MutexGuard<'_, WalWriter<W>> {
+        self.wal.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Returns the number of entries.
+    pub fn len(&self) -> usize {
+        self.map.len()
+    }
+
+    /// Returns `true` if the store is empty.
+    pub fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+
+    /// Returns the value for `key`.
+    pub fn get(&self, key: &[u8]) -> Option<Bytes> {
+        self.map.get(key)
+    }
+
+    /// Returns `true` if `key` is present.
+    pub fn contains_key(&self, key: &[u8]) -> bool {
+        self.map.contains_key(key)
+    }
+
+    /// Sets `key` to `value`, returning the previous value.
+    ///
+    /// # Errors
+    /// Returns the log's error, in which case the store is unchanged. After a write
+    /// fails partway through a record, every later write fails too, until a
+    /// [`checkpoint`](Self::checkpoint) moves on to a fresh log.
+    pub fn put(&self, key: impl Into<Bytes>, value: impl Into<Bytes>) -> io::Result<Option<Bytes>> {
+        let key: Bytes = key.into();
+        let value: Bytes = value.into();
+        let lookup = Arc::clone(&key);
+        self.map.raw_entry_with(&lookup[..], |entry, hash| {
+            self.wal().append(&Record::Put {
+                key: &key,
+                value: &value,
+            })?;
+            Ok(match entry {
+                RawEntryMut::Occupied(mut entry) => Some(entry.insert(value)),
+                RawEntryMut::Vacant(entry) => {
+                    entry.insert_hashed_nocheck(hash, key, value);
+                    None
+                }
+            })
+        })
+    }
+
+    /// Removes `key`, returning its value. Nothing is logged if `key` is absent.
+    ///
+    /// # Errors
+    /// Returns the log's error, in which case the store is unchanged. After a write
+    /// fails partway through a record, every later write fails too, until a
+    /// [`checkpoint`](Self::checkpoint) moves on to a fresh log.
+    pub fn delete(&self, key: &[u8]) -> io::Result<Option<Bytes>> {
+        self.map.raw_entry_with(key, |entry, _| match entry {
+            RawEntryMut::Occupied(entry) => {
+                self.wal().append(&Record::Delete { key })?;
+                Ok(Some(entry.remove()))
+            }
+            RawEntryMut::Vacant(_) => Ok(None),
+        })
+    }
+
+    /// Flushes the write-ahead log.
+    ///
+    /// # Errors
+    /// Returns any error from the log writer.
+    pub fn flush(&self) -> io::Result<()> {
+        self.wal().flush()
+    }
+
+    /// Writes the current contents as a snapshot, returning the entry count.
+    ///
+    /// Shards are read one at a time, so concurrent writes may or may not be
+    /// included; pair with [`checkpoint`](Self::checkpoint) for recovery.
+    ///
+    /// # Errors
+    /// Returns any error from `out`.
+    pub fn write_snapshot<S: Write>(&self, out: S) -> io::Result<u64> {
+        let mut writer = SnapshotWriter::new(out)?;
+        let mut result = Ok(());
+        self.map.for_each(|key, value| {
+            if result.is_ok() {
+                result = writer.entry(key, value);
+            }
+        });
+        result?;
+        Ok(writer.finish()?.1)
+    }
+
+    /// Starts logging to `new_wal`, then writes a snapshot to `snapshot`, returning
+    /// the previous log writer.
+    ///
+    /// Once this returns, the old log is no longer needed: recovering from
+    /// `snapshot` plus `new_wal` reproduces the store. Writes can continue
+    /// throughout the checkpoint.
+    ///
+    /// # Errors
+    /// Returns any error from flushing the old log or writing the snapshot. If the
+    /// snapshot fails, the old log plus `new_wal` are still needed for recovery.
+    pub fn checkpoint<S: Write>(&self, snapshot: S, new_wal: W) -> io::Result<W> {
+        let old = {
+            let mut wal = self.wal();
+            wal.flush()?;
+            std::mem::replace(&mut *wal, WalWriter::new(new_wal))
+        };
+        // Every write logged to the old log completed under its shard lock before
+        // the swap, so the snapshot sees it.
+        self.write_snapshot(snapshot)?;
+        Ok(old.into_inner())
+    }
+
+    /// Consumes the store, returning the token and the log writer.
+    pub fn into_parts(self) -> (GhostToken<'brand>, W) {
+        let wal = self
+            .wal
+            .into_inner()
+            .unwrap_or_else(PoisonError::into_inner)
+            .into_inner();
+        (self.map.into_parts().0, wal)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_kv_put_get_delete() {
+        GhostToken::new(|token| {
+            let store = KvStore::new(token, Vec::new());
+            assert_eq!(store.put(&b"a"[..], &b"1"[..]).unwrap(), None);
+            assert_eq!(
+                store.put(&b"a"[..], &b"2"[..]).unwrap().as_deref(),
+                Some(&b"1"[..])
+            );
+            assert_eq!(store.get(b"a").as_deref(), Some(&b"2"[..]));
+            assert_eq!(store.delete(b"missing").unwrap(), None);
+            assert_eq!(store.delete(b"a").unwrap().as_deref(), Some(&b"2"[..]));
+            assert!(store.is_empty());
+
+            // Three records: two puts and the delete of a present key.
+            let (_token, log) = store.into_parts();
+            assert_eq!(WalReader::new(&log[..]).count(), 3);
+        });
+    }
+
+    #[test]
+    fn test_kv_checkpoint_and_recover_under_concurrent_writes() {
+        GhostToken::new(|token| {
+            let store = KvStore::new(token, Vec::new());
+            let mut snapshot = Vec::new();
+            let mut old_log = Vec::new();
+            std::thread::scope(|s| {
+                for t in 0..4u32 {
+                    let store = &store;
+                    s.spawn(move || {
+                        for i in 0..500u32 {
+                            let key = format!("{t}:{}", i % 50).into_bytes();
+                            store.put(key.clone(), i.to_le_bytes().to_vec()).unwrap();
+                            if i % 7 == 0 {
+                                store.delete(&key).unwrap();
+                            }
+                        }
+                    });
+                }
+                old_log = store.checkpoint(&mut snapshot, Vec::new()).unwrap();
+            });
+            assert!(!old_log.is_empty() || !snapshot.is_empty());
+
+            let mut expected: Vec<(Vec<u8>, Vec<u8>)> = Vec::new();
+            store
+                .map
+                .for_each(|k, v| expected.push((k.to_vec(), v.to_vec())));
+            expected.sort();
+
+            let (_token, new_log) = store.into_parts();
+            GhostToken::new(|token| {
+                let recovered =
+                    KvStore::recover(token, Some(&snapshot[..]), &new_log[..], Vec::new()).unwrap();
+                let mut actual = Vec::new();
+                recovered
+                    .map
+                    .for_each(|k, v| actual.push((k.to_vec(), v.to_vec())));
+                actual.sort();
+                assert_eq!(actual, expected);
+            });
+        });
+    }
+}
@@ -0,0 +1,194 @@
+//! Point-in-time snapshots of a store's contents.
+//!
+//! Layout, integers in little endian:
+//!
+//! ```text
+//! magic "HALOKVS1"
+//! { 1u8 | key_len: u32 | value_len: u32 | key | value }*
+//! 0u8 | crc32: u32
+//! ```
+//!
+//! The CRC-32 covers everything before it. Entries are streamed, so a snapshot can be
+//! written without knowing the entry count up front.
+
+use super::wal::{corrupt, len_u32, Crc32};
+use std::io::{self, Read, Write};
+
+const MAGIC: &[u8; 8] = b"HALOKVS1";
+const TAG_ENTRY: u8 = 1;
+const TAG_END: u8 = 0;
+
+/// Streams entries into a snapshot.
+pub struct SnapshotWriter<W: Write> {
+    out: W,
+    crc: Crc32,
+    entries: u64,
+}
+
+impl<W: Write> SnapshotWriter<W> {
+    /// Starts a snapshot on `out`, writing the header.
+    ///
+    /// # Errors
+    /// Returns any error from the underlying writer.
+    pub fn new(out: W) -> io::Result<Self> {
+        let mut writer = Self {
+            out,
+            crc: Crc32::new(),
+            entries: 0,
+        };
+        writer.write(MAGIC)?;
+        Ok(writer)
+    }
+
+    fn write(&mut self, bytes: &[u8]) -> io::Result<()> {
+        self.crc.update(bytes);
+        self.out.write_all(bytes)
+    }
+
+    /// Appends one entry.
+    ///
+    /// # Errors
+    /// Fails with `InvalidInput` if the key or value is longer than `u32::MAX`, or
+    /// with any error from the underlying writer.
+    pub fn entry(&mut self, key: &[u8], value: &[u8]) -> io::Result<()> {
+        let mut header = [0u8; 9];
+        header[0] = TAG_ENTRY;
+        header[1..5].copy_from_slice(&len_u32(key)?.to_le_bytes());
+        header[5..9].copy_from_slice(&len_u32(value)?.to_le_bytes());
+        self.write(&header)?;
+        self.write(key)?;
+        self.write(value)?;
+        self.entries += 1;
+        Ok(())
+    }
+
+    /// Writes the trailer and flushes, returning the writer and the entry count.
+    ///
+    /// # Errors
+    /// Returns any error from the underlying writer.
+    pub fn finish(mut self) -> io::Result<(W, u64)> {
+        self.write(&[TAG_END])?;
+        let crc = self.crc.finish();
+        self.out.write_all(&crc.to_le_bytes())?;
+        self.out.flush()?;
+        Ok((self.out, self.entries))
+    }
+}
+
+/// Fills `buf` from `input` and adds it to `crc`.
+fn read<R: Read>(input: &mut R, buf: &mut [u8], crc: &mut Crc32) -> io::Result<()> {
+    input.read_exact(buf).map_err(|e| {
+        if e.kind() == io::ErrorKind::UnexpectedEof {
+            corrupt("snapshot is truncated")
+        } else {
+            e
+        }
+    })?;
+    crc.update(buf);
+    Ok(())
+}
+
+/// Reads a field of `len` bytes and adds it to `crc`.
+///
+/// `len` is untrusted until the trailing checksum is verified, so the buffer grows
+/// only as far as the input actually goes rather than being allocated up front.
+fn read_field<R: Read>(input: &mut R, len: u32, crc: &mut Crc32) -> io::Result<Vec<u8>> {
+    let mut buf = Vec::new();
+    input.by_ref().take(u64::from(len)).read_to_end(&mut buf)?;
+    if (buf.len() as u64) < u64::from(len) {
+        return Err(corrupt("snapshot is truncated"));
+    }
+    crc.update(&buf);
+    Ok(buf)
+}
+
+/// Reads every entry of a snapshot, verifying its checksum.
+///
+/// # Errors
+/// Fails with `InvalidData` if the snapshot is truncated, has a bad header or fails
+/// its checksum, or with any error from `input`.
+pub fn read_snapshot<R: Read>(mut input: R) -> io::Result<Vec<(Vec<u8>, Vec<u8>)>> {
+    let input = &mut input;
+    let mut crc = Crc32::new();
+
+    let mut magic = [0u8; 8];
+    read(input, &mut magic, &mut crc)?;
+    if &magic != MAGIC {
+        return Err(corrupt("not a halo kv snapshot"));
+    }
+
+    let mut entries = Vec::new();
+    loop {
+        let mut tag = [0u8];
+        read(input, &mut tag, &mut crc)?;
+        match tag[0] {
+            TAG_ENTRY => {
+                let mut lens = [0u8; 8];
+                read(input, &mut lens, &mut crc)?;
+                let [k0, k1, k2, k3, v0, v1, v2, v3] = lens;
+                let key_len = u32::from_le_bytes([k0, k1, k2, k3]);
+                let value_len = u32::from_le_bytes([v0, v1, v2, v3]);
+                let key = read_field(input, key_len, &mut crc)?;
+                let value = read_field(input, value_len, &mut crc)?;
+                entries.push((key, value));
+            }
+            TAG_END => break,
+            _ => return Err(corrupt("unknown snapshot entry tag")),
+        }
+    }
+
+    let expected = crc.finish();
+    let mut stored = [0u8; 4];
+    read(input, &mut stored, &mut Crc32::new())?;
+    if u32::from_le_bytes(stored) != expected {
+        return Err(corrupt("snapshot checksum mismatch"));
+    }
+    Ok(entries)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn snapshot_round_trip_and_corruption() {
+        let mut writer = SnapshotWriter::new(Vec::new()).unwrap();
+        writer.entry(b"k1", b"v1").unwrap();
+        writer.entry(b"", b"empty key").unwrap();
+        let (bytes, count) = writer.finish().unwrap();
+        assert_eq!(count, 2);
+
+        let entries = read_snapshot(&bytes[..]).unwrap();
+        assert_eq!(
+            entries,
+            [
+                (b"k1".to_vec(), b"v1".to_vec()),
+                (Vec::new(), b"empty key".to_vec())
+            ]
+        );
+
+        let mut flipped = bytes.clone();
+        flipped[20] ^= 0x40;
+        assert_eq!(
+            read_snapshot(&flipped[..]).unwrap_err().kind(),
+            io::ErrorKind::InvalidData
+        );
+        assert_eq!(
+            read_snapshot(&bytes[..bytes.len() - 2]).unwrap_err().kind(),
+            io::ErrorKind::InvalidData
+        );
+    }
+
+    #[test]
+    fn snapshot_garbage_length_is_truncation_not_allocation() {
+        let mut bytes = MAGIC.to_vec();
+        bytes.push(TAG_ENTRY);
+        bytes.extend_from_slice(&u32::MAX.to_le_bytes());
+        bytes.extend_from_slice(&u32::MAX.to_le_bytes());
+        bytes.extend_from_slice(b"stray");
+        assert_eq!(
+            read_snapshot(&bytes[..]).unwrap_err().kind(),
+            io::ErrorKind::InvalidData
+        );
+    }
+}
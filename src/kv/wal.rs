@@ -0,0 +1,416 @@
+//! Write-ahead log records and their on-disk encoding.
+//!
+//! Each record is laid out as
+//!
+//! ```text
+//! crc32: u32 | tag: u8 | key_len: u32 | value_len: u32 | key | value
+//! ```
+//!
+//! with integers in little endian and the CRC-32 covering everything after it. A
+//! record cut short at the end of the log (a torn write) ends replay cleanly; a
+//! complete record with a bad checksum is reported as corruption. The lengths are only
+//! trusted once the checksum matches, so a garbage length ends replay at the end of
+//! the input instead of allocating up to 8 GiB.
+
+use std::io::{self, Read, Write};
+
+const TAG_PUT: u8 = 1;
+const TAG_DELETE: u8 = 2;
+const HEADER_LEN: usize = 13;
+
+const CRC_TABLE: [u32; 256] = {
+    let mut table = [0u32; 256];
+    let mut i = 0u32;
+    while i < 256 {
+        let mut crc = i;
+        let mut bit = 0;
+        while bit < 8 {
+            crc = if crc & 1 != 0 {
+                (crc >> 1) ^ 0xEDB8_8320
+            } else {
+                crc >> 1
+            };
+            bit += 1;
+        }
+        table[i as usize] = crc;
+        i += 1;
+    }
+    table
+};
+
+/// Incremental CRC-32 (IEEE).
+#[derive(Clone, Copy)]
+pub(crate) struct Crc32(u32);
+
+impl Crc32 {
+    pub(crate) fn new() -> Self {
+        Self(!0)
+    }
+
+    pub(crate) fn update(&mut self, bytes: &[u8]) {
+        for &b in bytes {
+            self.0 = CRC_TABLE[((self.0 ^ u32::from(b)) & 0xFF) as usize] ^ (self.0 >> 8);
+        }
+    }
+
+    pub(crate) fn finish(self) -> u32 {
+        !self.0
+    }
+}
+
+pub(crate) fn corrupt(what: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, what.to_string())
+}
+
+pub(crate) fn len_u32(bytes: &[u8]) -> io::Result<u32> {
+    u32::try_from(bytes.len())
+        .map_err(|_| io::Error::new(io::ErrorKind::InvalidInput, "key or value exceeds 4 GiB"))
+}
+
+/// One logged mutation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Record<'a> {
+    /// `key` was set to `value`.
+    Put {
+        /// The key.
+        key: &'a [u8],
+        /// The new value.
+        value: &'a [u8],
+    },
+    /// `key` was removed.
+    Delete {
+        /// The key.
+        key: &'a [u8],
+    },
+}
+
+impl Record<'_> {
+    /// Appends the encoded record to `buf`.
+    ///
+    /// # Errors
+    /// Fails with `InvalidInput` if the key or value is longer than `u32::MAX`.
+    pub fn encode(&self, buf: &mut Vec<u8>) -> io::Result<()> {
+        let (tag, key, value): (u8, &[u8], &[u8]) = match *self {
+            Record::Put { key, value } => (TAG_PUT, key, value),
+            Record::Delete { key } => (TAG_DELETE, key, &[]),
+        };
+        let start = buf.len();
+        buf.extend_from_slice(&[0; 4]);
+        buf.push(tag);
+        buf.extend_from_slice(&len_u32(key)?.to_le_bytes());
+        buf.extend_from_slice(&len_u32(value)?.to_le_bytes());
+        buf.extend_from_slice(key);
+        buf.extend_from_slice(value);
+        let mut crc = Crc32::new();
+        crc.update(&buf[start + 4..]);
+        buf[start..start + 4].copy_from_slice(&crc.finish().to_le_bytes());
+        Ok(())
+    }
+}
+
+/// An owned [`Record`], as returned by [`WalReader`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum OwnedRecord {
+    /// `key` was set to `value`.
+    Put(Vec<u8>, Vec<u8>),
+    /// `key` was removed.
+    Delete(Vec<u8>),
+}
+
+/// Appends records to a log.
+///
+/// If an append fails partway through, the log may end in a partial record, and
+/// appending after it would bury that record mid-log where replay reports corruption.
+/// The writer therefore refuses further appends once one has failed: truncate the log
+/// to [`valid_len`](Self::valid_len) bytes past where this writer started, then
+/// continue with a new writer.
+pub struct WalWriter<W: Write> {
+    out: W,
+    buf: Vec<u8>,
+    records: u64,
+    valid_len: u64,
+    torn: bool,
+}
+
+impl<W: Write> WalWriter<W> {
+    /// Creates a writer appending to `out`.
+    pub fn new(out: W) -> Self {
+        Self {
+            out,
+            buf: Vec::new(),
+            records: 0,
+            valid_len: 0,
+            torn: false,
+        }
+    }
+
+    /// Encodes `record` and writes it with a single `write_all`.
+    ///
+    /// # Errors
+    /// Returns any error from encoding or from the underlying writer. If the write
+    /// itself failed, this and every later append fail until the log is repaired (see
+    /// [`is_torn`](Self::is_torn)).
+    pub fn append(&mut self, record: &Record<'_>) -> io::Result<()> {
+        if self.torn {
+            return Err(io::Error::other(
+                "write-ahead log may end in a partial record; truncate it to valid_len",
+            ));
+        }
+        self.buf.clear();
+        record.encode(&mut self.buf)?;
+        if let Err(e) = self.out.write_all(&self.buf) {
+            self.torn = true;
+            return Err(e);
+        }
+        self.records += 1;
+        self.valid_len += self.buf.len() as u64;
+        Ok(())
+    }
+
+    /// Returns the number of records appended through this writer.
+    pub fn records(&self) -> u64 {
+        self.records
+    }
+
+    /// Returns the length in bytes of the complete records appended through this
+    /// writer.
+    pub fn valid_len(&self) -> u64 {
+        self.valid_len
+    }
+
+    /// Returns `true` if a write failed partway through a record.
+    ///
+    /// Some of that record's bytes may have reached the log after the first
+    /// [`valid_len`](Self::valid_len) bytes; they must be cut off before the log is
+    /// appended to again.
+    pub fn is_torn(&self) -> bool {
+        self.torn
+    }
+
+    /// Flushes the underlying writer.
+    ///
+    /// # Errors
+    /// Returns any error from the underlying writer.
+    pub fn flush(&mut self) -> io::Result<()> {
+        self.out.flush()
+    }
+
+    /// Returns a reference to the underlying writer.
+    pub fn get_ref(&self) -> &W {
+        &self.out
+    }
+
+    /// Returns the underlying writer.
+    pub fn into_inner(self) -> W {
+        self.out
+    }
+}
+
+/// Reads records back from a log, in order.
+pub struct WalReader<R: Read> {
+    input: R,
+    valid_len: u64,
+    torn: bool,
+}
+
+impl<R: Read> WalReader<R> {
+    /// Creates a reader over `input`.
+    pub fn new(input: R) -> Self {
+        Self {
+            input,
+            valid_len: 0,
+            torn: false,
+        }
+    }
+
+    /// Returns the length in bytes of the complete records read so far.
+    ///
+    /// After a torn tail, truncating the log to this length removes the partial record.
+    pub fn valid_len(&self) -> u64 {
+        self.valid_len
+    }
+
+    /// Returns `true` if reading stopped at a partial record.
+    pub fn is_torn(&self) -> bool {
+        self.torn
+    }
+
+    /// Fills `buf`, returning `false` if the input ends first.
+    fn read_full(&mut self, buf: &mut [u8]) -> io::Result<bool> {
+        let mut filled = 0;
+        while filled < buf.len() {
+            match self.input.read(&mut buf[filled..]) {
+                Ok(0) => {
+                    self.torn |= filled > 0;
+                    return Ok(false);
+                }
+                Ok(n) => filled += n,
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(true)
+    }
+
+    fn read_record(&mut self) -> io::Result<Option<OwnedRecord>> {
+        let mut header = [0u8; HEADER_LEN];
+        if !self.read_full(&mut header)? {
+            return Ok(None);
+        }
+        let crc = u32::from_le_bytes(header[0..4].try_into().unwrap());
+        let tag = header[4];
+        let key_len = u32::from_le_bytes(header[5..9].try_into().unwrap());
+        let value_len = u32::from_le_bytes(header[9..13].try_into().unwrap());
+
+        // The lengths are unchecked until the CRC is, so the body grows only as far as
+        // the input actually goes rather than being allocated up front.
+        let len = u64::from(key_len) + u64::from(value_len);
+        let mut body = Vec::new();
+        (&mut self.input).take(len).read_to_end(&mut body)?;
+        if (body.len() as u64) < len {
+            self.torn = true;
+            return Ok(None);
+        }
+        let mut check = Crc32::new();
+        check.update(&header[4..]);
+        check.update(&body);
+        if check.finish() != crc {
+            return Err(corrupt("write-ahead log record checksum mismatch"));
+        }
+
+        let value = body.split_off(key_len as usize);
+        let record = match tag {
+            TAG_PUT => OwnedRecord::Put(body, value),
+            TAG_DELETE if value.is_empty() => OwnedRecord::Delete(body),
+            _ => return Err(corrupt("unknown write-ahead log record tag")),
+        };
+        self.valid_len += HEADER_LEN as u64 + len;
+        Ok(Some(record))
+    }
+}
+
+impl<R: Read> Iterator for WalReader<R> {
+    type Item = io::Result<OwnedRecord>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.torn {
+            return None;
+        }
+        self.read_record().transpose()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn crc32_matches_reference() {
+        let mut crc = Crc32::new();
+        crc.update(b"123456789");
+        assert_eq!(crc.finish(), 0xCBF4_3926);
+    }
+
+    #[test]
+    fn wal_round_trip_and_torn_tail() {
+        let mut wal = WalWriter::new(Vec::new());
+        wal.append(&Record::Put {
+            key: b"a",
+            value: b"1",
+        })
+        .unwrap();
+        wal.append(&Record::Delete { key: b"a" }).unwrap();
+        wal.append(&Record::Put {
+            key: b"b",
+            value: b"22",
+        })
+        .unwrap();
+        assert_eq!(wal.records(), 3);
+        let mut log = wal.into_inner();
+        let full_len = log.len() as u64;
+
+        // Cut the last record short.
+        log.truncate(log.len() - 1);
+        let mut reader = WalReader::new(&log[..]);
+        let records: Vec<OwnedRecord> = reader.by_ref().map(Result::unwrap).collect();
+        assert_eq!(
+            records,
+            [
+                OwnedRecord::Put(b"a".to_vec(), b"1".to_vec()),
+                OwnedRecord::Delete(b"a".to_vec()),
+            ]
+        );
+        assert!(reader.is_torn());
+        assert_eq!(reader.valid_len(), full_len - 16);
+
+        // A flipped bit in a complete record is corruption.
+        log[14] ^= 1;
+        let err = WalReader::new(&log[..]).next().unwrap().unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn wal_garbage_length_ends_replay_without_allocating_it() {
+        let mut log = Vec::new();
+        Record::Delete { key: b"a" }.encode(&mut log).unwrap();
+        let first_len = log.len() as u64;
+        // A header claiming two 4 GiB fields, followed by a few stray bytes.
+        log.extend_from_slice(&[0, 0, 0, 0, TAG_PUT]);
+        log.extend_from_slice(&u32::MAX.to_le_bytes());
+        log.extend_from_slice(&u32::MAX.to_le_bytes());
+        log.extend_from_slice(b"stray");
+
+        let mut reader = WalReader::new(&log[..]);
+        assert_eq!(
+            reader.next().unwrap().unwrap(),
+            OwnedRecord::Delete(b"a".to_vec())
+        );
+        assert!(reader.next().is_none());
+        assert!(reader.is_torn());
+        assert_eq!(reader.valid_len(), first_len);
+    }
+
+    #[test]
+    fn wal_writer_refuses_appends_after_a_partial_write() {
+        /// Accepts `room` bytes, then fails every write.
+        struct Full {
+            data: Vec<u8>,
+            room: usize,
+        }
+        impl Write for Full {
+            fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+                let n = buf.len().min(self.room - self.data.len());
+                if n == 0 {
+                    return Err(io::ErrorKind::StorageFull.into());
+                }
+                self.data.extend_from_slice(&buf[..n]);
+                Ok(n)
+            }
+            fn flush(&mut self) -> io::Result<()> {
+                Ok(())
+            }
+        }
+
+        let put = Record::Put {
+            key: b"key",
+            value: b"value",
+        };
+        let mut wal = WalWriter::new(Full {
+            data: Vec::new(),
+            room: 30,
+        });
+        wal.append(&put).unwrap();
+        assert!(wal.append(&put).is_err());
+        assert!(wal.is_torn());
+        assert!(wal.append(&Record::Delete { key: b"k" }).is_err());
+        assert_eq!((wal.records(), wal.valid_len()), (1, 21));
+
+        // Truncating to the valid length leaves a log that replays cleanly.
+        let mut log = wal.into_inner().data;
+        assert_eq!(log.len(), 30);
+        log.truncate(21);
+        let mut reader = WalReader::new(&log[..]);
+        assert_eq!(reader.by_ref().count(), 1);
+        assert!(!reader.is_torn());
+    }
+}
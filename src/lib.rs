@@ -99,22 +99,36 @@
 //! });
 //! ```
 
+#![cfg_attr(not(feature = "std"), no_std)]
 #![warn(missing_docs, clippy::all, clippy::pedantic)]
 #![allow(clippy::module_name_repetitions)]
 #![allow(clippy::must_use_candidate)]
 
+// The crate-level `alloc` module shadows the `alloc` crate, so it is renamed here.
+#[cfg(feature = "alloc")]
+extern crate alloc as alloc_crate;
+
+#[cfg(feature = "alloc")]
 pub mod alloc;
+#[cfg(feature = "bench-support")]
+pub mod bench_support;
 pub mod cell;
+#[cfg(feature = "alloc")]
 pub mod collections;
 pub mod concurrency;
+#[cfg(feature = "std")]
 pub mod graph;
+#[cfg(feature = "std")]
+pub mod kv;
 pub mod token;
 
+#[cfg(feature = "std")]
 pub use alloc::BrandedArena;
 pub use cell::{
     GhostCell, GhostLazyCell, GhostLazyLock, GhostOnceCell, GhostRefCell, GhostUnsafeCell,
     RawGhostCell,
 };
+#[cfg(feature = "std")]
 pub use collections::{
     ActivateVec, ActiveDisjointSet, ActiveVec, BrandedArray, BrandedChain, BrandedCow,
     BrandedCowStrings, BrandedDisjointSet, BrandedDoublyLinkedList, BrandedHashMap, BrandedHashSet,
@@ -122,12 +136,19 @@ pub use collections::{
     BrandedPathBuf, BrandedSegmentTree, BrandedSegmentTreeViewMut, BrandedSlice, BrandedSliceMut,
     BrandedSlotMap, BrandedString, BrandedVec, BrandedVecDeque, InternId, SlotKey,
 };
+#[cfg(feature = "std")]
 pub use alloc::{BrandedRc, StaticRc};
-pub use graph::{GhostAdjacencyGraph, GhostBipartiteGraph, GhostCscGraph, GhostCsrGraph, GhostDag};
+#[cfg(feature = "std")]
+pub use graph::{
+    GhostAdjacencyGraph, GhostBipartiteGraph, GhostCscGraph, GhostCsrGraph, GhostDag,
+    GhostWeightedCsrGraph,
+};
 pub use token::{
-    GhostBorrow, GhostBorrowMut, GhostToken, HierarchicalGhostToken, ImmutableChild,
-    SharedGhostToken,
+    GhostBorrow, GhostBorrowMut, GhostIterator, GhostToken, HierarchicalGhostToken, ImmutableChild,
 };
+#[cfg(feature = "std")]
+pub use token::SharedGhostToken;
+#[cfg(feature = "std")]
 pub use concurrency::sync::GhostOnceLock;
 
 // TODO(ghost-stdlib): BrandedArc with hierarchical permissions and zero-sized brand markers.
@@ -174,5 +195,6 @@ const _: () = {
     assert!(mem::size_of::<GhostOnceCell<'static, u64>>() <= mem::size_of::<usize>() * 4);
     assert!(mem::size_of::<GhostLazyCell<'static, u64>>() <= mem::size_of::<usize>() * 6);
     assert!(mem::size_of::<GhostLazyLock<'static, u64>>() <= mem::size_of::<usize>() * 6);
+    #[cfg(feature = "std")]
     assert!(mem::size_of::<GhostOnceLock<'static, u64>>() <= mem::size_of::<usize>() * 4);
 };
@@ -0,0 +1,327 @@
+//! Iterator adapters that thread a ghost token through each step.
+//!
+//! A closure that captures `&mut token` holds that borrow for as long as the iterator
+//! lives, so nothing else in the pipeline (or after it, until the iterator is dropped)
+//! can use the token. The adapters here take the token once and lend it to the closure
+//! on every call instead, which keeps token-gated pipelines over branded cells short:
+//!
+//! ```rust
+//! use halo::token::GhostIterator;
+//! use halo::{GhostCell, GhostToken};
+//!
+//! GhostToken::new(|mut token| {
+//!     let cells: Vec<GhostCell<'_, i32>> = (1..=4).map(GhostCell::new).collect();
+//!
+//!     cells
+//!         .iter()
+//!         .for_each_with_token(&mut token, |token, cell| *cell.borrow_mut(token) *= 10);
+//!
+//!     let big: Vec<i32> = cells
+//!         .iter()
+//!         .filter_with_token(&token, |token, cell| *cell.borrow(token) > 15)
+//!         .map_with_token(&token, |token, cell| *cell.borrow(token))
+//!         .collect();
+//!     assert_eq!(big, [20, 30, 40]);
+//! });
+//! ```
+
+use crate::token::traits::{GhostBorrow, GhostBorrowMut};
+use core::fmt;
+use core::iter::FusedIterator;
+
+/// Token-threading extensions, implemented for every [`Iterator`].
+pub trait GhostIterator: Iterator + Sized {
+    /// Maps each item with a shared token.
+    ///
+    /// Results may borrow from the token for `'t`, so the closure can return
+    /// references obtained through [`GhostCell::borrow`](crate::GhostCell::borrow).
+    #[inline]
+    fn map_with_token<'t, 'brand, Token, B, F>(
+        self,
+        token: &'t Token,
+        f: F,
+    ) -> MapWithToken<'t, Self, Token, F>
+    where
+        Token: GhostBorrow<'brand>,
+        F: FnMut(&'t Token, Self::Item) -> B,
+    {
+        MapWithToken {
+            iter: self,
+            token,
+            f,
+        }
+    }
+
+    /// Maps each item with an exclusive token, lent afresh for every call.
+    ///
+    /// Results cannot borrow from the token; use this to update cells as they pass.
+    #[inline]
+    fn map_with_token_mut<'t, 'brand, Token, B, F>(
+        self,
+        token: &'t mut Token,
+        f: F,
+    ) -> MapWithTokenMut<'t, Self, Token, F>
+    where
+        Token: GhostBorrowMut<'brand>,
+        F: FnMut(&mut Token, Self::Item) -> B,
+    {
+        MapWithTokenMut {
+            iter: self,
+            token,
+            f,
+        }
+    }
+
+    /// Keeps the items for which `predicate`, given a shared token, returns `true`.
+    #[inline]
+    fn filter_with_token<'t, 'brand, Token, P>(
+        self,
+        token: &'t Token,
+        predicate: P,
+    ) -> FilterWithToken<'t, Self, Token, P>
+    where
+        Token: GhostBorrow<'brand>,
+        P: FnMut(&'t Token, &Self::Item) -> bool,
+    {
+        FilterWithToken {
+            iter: self,
+            token,
+            predicate,
+        }
+    }
+
+    /// Consumes the iterator, calling `f` on each item with an exclusive token.
+    #[inline]
+    fn for_each_with_token<'brand, Token, F>(self, token: &mut Token, mut f: F)
+    where
+        Token: GhostBorrowMut<'brand>,
+        F: FnMut(&mut Token, Self::Item),
+    {
+        for item in self {
+            f(token, item);
+        }
+    }
+}
+
+impl<I: Iterator> GhostIterator for I {}
+
+/// Iterator returned by [`GhostIterator::map_with_token`].
+#[must_use = "iterators are lazy and do nothing unless consumed"]
+pub struct MapWithToken<'t, I, Token, F> {
+    iter: I,
+    token: &'t Token,
+    f: F,
+}
+
+impl<'t, I, Token, F, B> Iterator for MapWithToken<'t, I, Token, F>
+where
+    I: Iterator,
+    F: FnMut(&'t Token, I::Item) -> B,
+{
+    type Item = B;
+
+    #[inline]
+    fn next(&mut self) -> Option<B> {
+        let item = self.iter.next()?;
+        Some((self.f)(self.token, item))
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl<'t, I, Token, F, B> DoubleEndedIterator for MapWithToken<'t, I, Token, F>
+where
+    I: DoubleEndedIterator,
+    F: FnMut(&'t Token, I::Item) -> B,
+{
+    #[inline]
+    fn next_back(&mut self) -> Option<B> {
+        let item = self.iter.next_back()?;
+        Some((self.f)(self.token, item))
+    }
+}
+
+impl<'t, I, Token, F, B> ExactSizeIterator for MapWithToken<'t, I, Token, F>
+where
+    I: ExactSizeIterator,
+    F: FnMut(&'t Token, I::Item) -> B,
+{
+}
+
+impl<'t, I, Token, F, B> FusedIterator for MapWithToken<'t, I, Token, F>
+where
+    I: FusedIterator,
+    F: FnMut(&'t Token, I::Item) -> B,
+{
+}
+
+impl<I: fmt::Debug, Token, F> fmt::Debug for MapWithToken<'_, I, Token, F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("MapWithToken")
+            .field("iter", &self.iter)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Iterator returned by [`GhostIterator::map_with_token_mut`].
+#[must_use = "iterators are lazy and do nothing unless consumed"]
+pub struct MapWithTokenMut<'t, I, Token, F> {
+    iter: I,
+    token: &'t mut Token,
+    f: F,
+}
+
+impl<I, Token, F, B> Iterator for MapWithTokenMut<'_, I, Token, F>
+where
+    I: Iterator,
+    F: FnMut(&mut Token, I::Item) -> B,
+{
+    type Item = B;
+
+    #[inline]
+    fn next(&mut self) -> Option<B> {
+        let item = self.iter.next()?;
+        Some((self.f)(self.token, item))
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl<I, Token, F, B> DoubleEndedIterator for MapWithTokenMut<'_, I, Token, F>
+where
+    I: DoubleEndedIterator,
+    F: FnMut(&mut Token, I::Item) -> B,
+{
+    #[inline]
+    fn next_back(&mut self) -> Option<B> {
+        let item = self.iter.next_back()?;
+        Some((self.f)(self.token, item))
+    }
+}
+
+impl<I, Token, F, B> ExactSizeIterator for MapWithTokenMut<'_, I, Token, F>
+where
+    I: ExactSizeIterator,
+    F: FnMut(&mut Token, I::Item) -> B,
+{
+}
+
+impl<I, Token, F, B> FusedIterator for MapWithTokenMut<'_, I, Token, F>
+where
+    I: FusedIterator,
+    F: FnMut(&mut Token, I::Item) -> B,
+{
+}
+
+impl<I: fmt::Debug, Token, F> fmt::Debug for MapWithTokenMut<'_, I, Token, F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("MapWithTokenMut")
+            .field("iter", &self.iter)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Iterator returned by [`GhostIterator::filter_with_token`].
+#[must_use = "iterators are lazy and do nothing unless consumed"]
+pub struct FilterWithToken<'t, I, Token, P> {
+    iter: I,
+    token: &'t Token,
+    predicate: P,
+}
+
+impl<'t, I, Token, P> Iterator for FilterWithToken<'t, I, Token, P>
+where
+    I: Iterator,
+    P: FnMut(&'t Token, &I::Item) -> bool,
+{
+    type Item = I::Item;
+
+    #[inline]
+    fn next(&mut self) -> Option<I::Item> {
+        let token = self.token;
+        let predicate = &mut self.predicate;
+        self.iter.find(|item| predicate(token, item))
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, self.iter.size_hint().1)
+    }
+}
+
+impl<'t, I, Token, P> DoubleEndedIterator for FilterWithToken<'t, I, Token, P>
+where
+    I: DoubleEndedIterator,
+    P: FnMut(&'t Token, &I::Item) -> bool,
+{
+    #[inline]
+    fn next_back(&mut self) -> Option<I::Item> {
+        let token = self.token;
+        let predicate = &mut self.predicate;
+        self.iter.rfind(|item| predicate(token, item))
+    }
+}
+
+impl<'t, I, Token, P> FusedIterator for FilterWithToken<'t, I, Token, P>
+where
+    I: FusedIterator,
+    P: FnMut(&'t Token, &I::Item) -> bool,
+{
+}
+
+impl<I: fmt::Debug, Token, P> fmt::Debug for FilterWithToken<'_, I, Token, P> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("FilterWithToken")
+            .field("iter", &self.iter)
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::collections::BrandedVec;
+    use crate::{GhostCell, GhostToken};
+
+    #[test]
+    fn test_map_and_filter_with_token() {
+        GhostToken::new(|token| {
+            let cells: Vec<GhostCell<'_, String>> = ["a", "bb", "ccc"]
+                .map(|s| GhostCell::new(s.to_string()))
+                .into();
+            let long: Vec<&str> = cells
+                .iter()
+                .filter_with_token(&token, |token, cell| cell.borrow(token).len() > 1)
+                .map_with_token(&token, |token, cell| cell.borrow(token).as_str())
+                .rev()
+                .collect();
+            assert_eq!(long, ["ccc", "bb"]);
+        });
+    }
+
+    #[test]
+    fn test_mutating_adapters() {
+        GhostToken::new(|mut token| {
+            let vec: BrandedVec<'_, u32> = (0..5).collect();
+            let doubled: Vec<u32> = (0..vec.len())
+                .map_with_token_mut(&mut token, |token, i| {
+                    let value = vec.get_mut(token, i).unwrap();
+                    *value *= 2;
+                    *value
+                })
+                .collect();
+            assert_eq!(doubled, [0, 2, 4, 6, 8]);
+
+            (0..vec.len())
+                .filter(|i| i % 2 == 1)
+                .for_each_with_token(&mut token, |token, i| *vec.get_mut(token, i).unwrap() = 0);
+            assert_eq!(vec.as_slice(&token), [0, 0, 4, 0, 8]);
+        });
+    }
+}
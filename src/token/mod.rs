@@ -12,21 +12,28 @@
 //! live mutable borrows of the same token simultaneously.
 
 /// Global singleton tokens for static lifetime branding.
+#[cfg(feature = "std")]
 pub mod global;
 /// Hierarchical tokens allowing splitting and restricted views.
 pub mod hierarchy;
 /// Invariant lifetime definitions for branding.
 pub mod invariant;
+/// Iterator adapters that thread a token through each step.
+pub mod iter;
 /// Macros for convenient token generation.
 pub mod macros;
 /// Shared tokens for reference-counted access.
+#[cfg(feature = "std")]
 pub mod shared;
 /// Traits defining token capabilities (GhostBorrow/GhostBorrowMut).
 pub mod traits;
 
+#[cfg(feature = "std")]
 pub use global::{static_token, with_static_token, with_static_token_mut, StaticBrand};
 pub use hierarchy::{HierarchicalGhostToken, ImmutableChild};
 pub use invariant::InvariantLifetime;
+pub use iter::GhostIterator;
+#[cfg(feature = "std")]
 pub use shared::SharedGhostToken;
 pub use traits::{GhostBorrow, GhostBorrowMut};
 
@@ -63,9 +70,8 @@ impl<'brand> GhostToken<'brand> {
         f(GhostToken(InvariantLifetime::default()))
     }
 
-    // NOTE: we intentionally keep the public surface small. If you need a
-    // `&mut GhostToken<'brand>` for iterator pipelines, just take a mutable
-    // borrow of the token inside the `new` closure.
+    // NOTE: we intentionally keep the public surface small. To pass the token
+    // through iterator pipelines, use the adapters on `GhostIterator`.
 
     /// Creates a GhostToken from a raw invariant lifetime.
     ///
@@ -73,6 +79,7 @@ impl<'brand> GhostToken<'brand> {
     ///
     /// This is an internal API. The caller must ensure that the lifetime `'brand`
     /// is used correctly to enforce linearity and uniqueness where required.
+    #[cfg_attr(not(feature = "std"), allow(dead_code))]
     #[inline(always)]
     pub(crate) const fn from_invariant(invariant: InvariantLifetime<'brand>) -> Self {
         GhostToken(invariant)
@@ -25,9 +25,9 @@ use crate::concurrency::{CachePadded, SHARD_COUNT, current_shard_index};
 use crate::concurrency::sync::{wait_on_u32, wake_all_u32};
 use crate::token::traits::{GhostBorrow, GhostBorrowMut};
 use crate::GhostToken;
-use std::cell::UnsafeCell;
-use std::ops::{Deref, DerefMut};
-use std::sync::atomic::{AtomicU32, Ordering};
+use core::cell::UnsafeCell;
+use core::ops::{Deref, DerefMut};
+use core::sync::atomic::{AtomicU32, Ordering};
 
 const WRITER_ACTIVE: u32 = 1;
 const WRITER_INACTIVE: u32 = 0;
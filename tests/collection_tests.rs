@@ -1,3 +1,4 @@
+use halo::cell::raw::cells::ref_cell::{Ref, RefMut};
 use halo::collections::{
     BrandedArena, BrandedChunkedVec, BrandedDeque, BrandedHashMap, BrandedHashSet, BrandedVecDeque,
 };
@@ -489,3 +490,65 @@ fn test_raw_ghost_ref_cell_runtime_borrow_checking() {
         // We can't easily test panics across token boundaries, so we'll skip this
     });
 }
+
+#[test]
+fn test_raw_ghost_ref_cell_reader_count_and_metrics() {
+    GhostToken::new(|mut token| {
+        let cell = GhostRefCell::new(1);
+        assert_eq!(cell.reader_count(&token), 0);
+        {
+            let a = cell.borrow(&token);
+            let _b = cell.borrow(&token);
+            assert_eq!(cell.reader_count(&token), 2);
+            drop(a);
+            assert_eq!(cell.reader_count(&token), 1);
+        }
+
+        let guard = cell.borrow_mut(&mut token);
+        drop(guard);
+        assert_eq!(cell.reader_count(&token), 0);
+
+        #[cfg(feature = "metrics")]
+        {
+            use halo::cell::raw::cells::ref_cell::BorrowMetrics;
+
+            let leaked = RefMut::forget_release(cell.borrow_mut(&mut token));
+            *leaked += 1;
+            assert!(cell.try_borrow(&token).is_none());
+            assert!(cell.try_borrow(&token).is_none());
+            assert!(cell.try_borrow_mut(&mut token).is_none());
+            assert_eq!(
+                cell.metrics(),
+                BorrowMetrics {
+                    failed_borrows: 2,
+                    failed_borrow_muts: 1,
+                }
+            );
+            cell.reset_metrics();
+            assert_eq!(cell.metrics(), BorrowMetrics::default());
+        }
+    });
+}
+
+#[test]
+fn test_raw_ghost_ref_cell_leak_and_unpoison() {
+    GhostToken::new(|mut token| {
+        let mut cell = GhostRefCell::new(vec![1, 2]);
+        *cell.get_mut() = vec![1, 2, 3];
+
+        let leaked: &Vec<i32> = Ref::leak(cell.borrow(&token));
+        assert_eq!(leaked, &vec![1, 2, 3]);
+        assert!(cell.is_borrowed(&token));
+        assert!(cell.try_borrow_mut(&mut token).is_none());
+
+        cell.unpoison().push(4);
+        assert!(!cell.is_borrowed(&token));
+
+        let v = RefMut::forget_release(cell.borrow_mut(&mut token));
+        v.push(5);
+        assert!(cell.try_borrow(&token).is_none());
+
+        assert_eq!(cell.unpoison(), &vec![1, 2, 3, 4, 5]);
+        assert_eq!(*cell.borrow(&token), vec![1, 2, 3, 4, 5]);
+    });
+}
@@ -46,6 +46,22 @@ fn test_branded_vec_drain() {
     });
 }
 
+#[test]
+fn test_branded_vec_splice_and_retain() {
+    GhostToken::new(|mut token| {
+        let mut vec: BrandedVec<_> = (0..5).collect();
+        let removed: Vec<_> = vec.splice(1..3, [10, 11, 12]).collect();
+        assert_eq!(removed, vec![1, 2]);
+        assert_eq!(vec.as_slice(&token), &[0, 10, 11, 12, 3, 4]);
+
+        vec.retain(&mut token, |x| {
+            *x += 1;
+            *x % 2 == 0
+        });
+        assert_eq!(vec.as_slice(&token), &[12, 4]);
+    });
+}
+
 #[test]
 fn test_branded_vec_deque_from_iter() {
     GhostToken::new(|token| {
@@ -21,3 +21,107 @@ fn atomic_bitset_basic() {
         assert!(!b.is_set(129));
     });
 }
+
+#[test]
+fn atomic_bitset_set_bit_iteration() {
+    GhostToken::new(|_token| {
+        let b: GhostAtomicBitset<'_> = GhostAtomicBitset::new(200);
+        let set = [0, 3, 63, 64, 65, 127, 128, 199];
+        for &bit in &set {
+            b.test_and_set(bit, Ordering::Relaxed);
+        }
+
+        assert_eq!(b.iter_ones().collect::<Vec<_>>(), set);
+        let zeros: Vec<usize> = b.iter_zeros().collect();
+        assert_eq!(zeros.len(), 200 - set.len());
+        assert!(zeros.iter().all(|z| !set.contains(z)));
+        assert_eq!(zeros.last(), Some(&198));
+
+        assert_eq!(b.first_set_from(0), Some(0));
+        assert_eq!(b.first_set_from(1), Some(3));
+        assert_eq!(b.first_set_from(4), Some(63));
+        assert_eq!(b.first_set_from(66), Some(127));
+        assert_eq!(b.first_set_from(129), Some(199));
+        assert_eq!(b.first_set_from(200), None);
+
+        b.clear_all();
+        assert_eq!(b.first_set_from(0), None);
+        assert_eq!(b.iter_ones().next(), None);
+        assert_eq!(b.iter_zeros().count(), 200);
+
+        let empty: GhostAtomicBitset<'_> = GhostAtomicBitset::new(0);
+        assert_eq!(empty.iter_zeros().next(), None);
+        assert_eq!(empty.first_set_from(0), None);
+    });
+}
+
+#[test]
+fn atomic_bitset_bulk_word_operations() {
+    GhostToken::new(|_token| {
+        let b: GhostAtomicBitset<'_> = GhostAtomicBitset::new(300);
+        b.set_range(10..200);
+        assert_eq!(b.count_ones(), 190);
+        assert_eq!(b.first_set_from(0), Some(10));
+        assert!(b.is_set(199) && !b.is_set(200));
+
+        b.clear_range(60..130);
+        assert_eq!(b.count_ones(), 120);
+        assert_eq!(b.first_set_from(60), Some(130));
+        b.set_range(5..5);
+        b.clear_range(300..300);
+        assert_eq!(b.count_ones(), 120);
+
+        let frontier: GhostAtomicBitset<'_> = GhostAtomicBitset::new(300);
+        for bit in [0, 10, 64, 250, 299] {
+            frontier.test_and_set(bit, Ordering::Relaxed);
+        }
+        // 10 is already set in `b`.
+        assert_eq!(b.union_with(&frontier, Ordering::Relaxed), 4);
+        assert_eq!(b.union_with(&frontier, Ordering::Relaxed), 0);
+        assert_eq!(b.count_ones(), 124);
+
+        b.intersect_with(&frontier, Ordering::Relaxed);
+        assert_eq!(b.iter_ones().collect::<Vec<_>>(), [0, 10, 64, 250, 299]);
+
+        b.set_range(0..300);
+        assert_eq!(b.count_ones(), 300);
+        b.clear_range(0..300);
+        assert_eq!(b.count_ones(), 0);
+    });
+}
+
+#[test]
+#[should_panic(expected = "out of bounds")]
+fn atomic_bitset_set_range_out_of_bounds() {
+    GhostToken::new(|_token| {
+        let b: GhostAtomicBitset<'_> = GhostAtomicBitset::new(64);
+        b.set_range(60..65);
+    });
+}
+
+#[test]
+fn atomic_bitset_parallel_frontier_merge() {
+    GhostToken::new(|_token| {
+        let visited: GhostAtomicBitset<'_> = GhostAtomicBitset::new(4096);
+        let frontiers: Vec<GhostAtomicBitset<'_>> =
+            (0..4).map(|_| GhostAtomicBitset::new(4096)).collect();
+        for (t, frontier) in frontiers.iter().enumerate() {
+            // Overlapping ranges, so some bits are found by several threads.
+            frontier.set_range(t * 800..t * 800 + 1600);
+        }
+
+        let added: usize = std::thread::scope(|s| {
+            let handles: Vec<_> = frontiers
+                .iter()
+                .map(|f| {
+                    let visited = &visited;
+                    s.spawn(move || visited.union_with(f, Ordering::Relaxed))
+                })
+                .collect();
+            handles.into_iter().map(|h| h.join().unwrap()).sum()
+        });
+
+        assert_eq!(added, 4000);
+        assert_eq!(visited.count_ones(), 4000);
+    });
+}
@@ -1,5 +1,8 @@
 use core::sync::atomic::Ordering;
-use halo::concurrency::atomic::{GhostAtomicBool, GhostAtomicU64, GhostAtomicUsize};
+use halo::concurrency::atomic::cell::NoUninit;
+use halo::concurrency::atomic::{
+    GhostAtomicBool, GhostAtomicCell, GhostAtomicU64, GhostAtomicUsize, GhostShardedCounter,
+};
 
 fn assert_send_sync<T: Send + Sync>() {}
 
@@ -28,3 +31,135 @@ fn branded_atomics_are_send_sync_and_work() {
     );
     assert_eq!(u.load(Ordering::Relaxed), 9);
 }
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, NoUninit)]
+#[bytemuck(crate = "halo::concurrency::atomic::cell")]
+#[repr(u8)]
+enum Phase {
+    Idle,
+    Running,
+    Done,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, NoUninit)]
+#[bytemuck(crate = "halo::concurrency::atomic::cell")]
+#[repr(transparent)]
+struct NodeId(u32);
+
+#[test]
+fn atomic_cell_holds_small_enums_and_newtypes() {
+    assert_send_sync::<GhostAtomicCell<'static, Phase>>();
+    assert_eq!(core::mem::size_of::<GhostAtomicCell<'static, Phase>>(), 1);
+
+    let phase = GhostAtomicCell::new(Phase::Idle);
+    assert_eq!(phase.load(Ordering::Acquire), Phase::Idle);
+    assert_eq!(
+        phase.compare_exchange(
+            Phase::Done,
+            Phase::Running,
+            Ordering::AcqRel,
+            Ordering::Acquire
+        ),
+        Err(Phase::Idle)
+    );
+    assert_eq!(
+        phase.compare_exchange(
+            Phase::Idle,
+            Phase::Running,
+            Ordering::AcqRel,
+            Ordering::Acquire
+        ),
+        Ok(Phase::Idle)
+    );
+    assert_eq!(phase.swap(Phase::Done, Ordering::AcqRel), Phase::Running);
+    assert_eq!(phase.into_inner(), Phase::Done);
+
+    let mut id = GhostAtomicCell::new(NodeId(7));
+    id.store(NodeId(8), Ordering::Release);
+    let bumped = id.fetch_update(Ordering::AcqRel, Ordering::Acquire, |NodeId(n)| {
+        Some(NodeId(n + 1))
+    });
+    assert_eq!(bumped, Ok(NodeId(8)));
+    assert_eq!(
+        id.fetch_update(Ordering::AcqRel, Ordering::Acquire, |_| None),
+        Err(NodeId(9))
+    );
+    id.get_mut().0 = 100;
+    assert_eq!(id.load(Ordering::Relaxed), NodeId(100));
+
+    let x = GhostAtomicCell::new(1.5f64);
+    assert_eq!(x.swap(-0.0, Ordering::Relaxed), 1.5);
+    // Bitwise comparison: 0.0 does not match the stored -0.0.
+    assert!(x
+        .compare_exchange(0.0, 2.0, Ordering::Relaxed, Ordering::Relaxed)
+        .is_err());
+    let flag: GhostAtomicCell<'_, bool> = GhostAtomicCell::default();
+    assert!(!flag.load(Ordering::Relaxed));
+}
+
+#[test]
+fn atomic_cell_takes_primitives_without_an_impl() {
+    use core::num::NonZeroU32;
+
+    let byte = GhostAtomicCell::new(-3i8);
+    assert_eq!(byte.swap(4, Ordering::Relaxed), -3);
+    let half = GhostAtomicCell::new(0u16);
+    half.store(u16::MAX, Ordering::Relaxed);
+    assert_eq!(half.load(Ordering::Relaxed), u16::MAX);
+    let letter = GhostAtomicCell::new('a');
+    assert_eq!(
+        letter.compare_exchange('a', 'é', Ordering::Relaxed, Ordering::Relaxed),
+        Ok('a')
+    );
+    let id = GhostAtomicCell::new(NonZeroU32::new(1));
+    assert_eq!(id.swap(None, Ordering::Relaxed), NonZeroU32::new(1));
+    assert_eq!(id.load(Ordering::Relaxed), None);
+}
+
+#[test]
+fn atomic_cell_concurrent_fetch_update() {
+    let counter = GhostAtomicCell::new(NodeId(0));
+    std::thread::scope(|s| {
+        for _ in 0..4 {
+            s.spawn(|| {
+                for _ in 0..1000 {
+                    let _ = counter.fetch_update(Ordering::AcqRel, Ordering::Acquire, |n| {
+                        Some(NodeId(n.0 + 1))
+                    });
+                }
+            });
+        }
+    });
+    assert_eq!(counter.load(Ordering::Relaxed), NodeId(4000));
+}
+
+#[test]
+fn sharded_counter_sums_across_threads() {
+    assert_send_sync::<GhostShardedCounter<'static>>();
+
+    static EVENTS: GhostShardedCounter<'static> = GhostShardedCounter::new();
+    let bytes = GhostShardedCounter::default();
+    std::thread::scope(|s| {
+        for t in 0..8u64 {
+            let bytes = &bytes;
+            s.spawn(move || {
+                for _ in 0..1000 {
+                    EVENTS.increment();
+                    bytes.add(t + 1);
+                }
+                // Net zero, even if this thread's slot goes below zero.
+                bytes.sub(5);
+                bytes.add(5);
+            });
+        }
+    });
+    assert_eq!(EVENTS.sum(), 8000);
+    assert_eq!(bytes.sum(), 1000 * (1..=8).sum::<u64>());
+
+    bytes.sub(36_000);
+    assert_eq!(bytes.sum(), 0);
+    bytes.add(3);
+    assert_eq!(format!("{bytes:?}"), "GhostShardedCounter(3)");
+    bytes.reset();
+    assert_eq!(bytes.sum(), 0);
+}
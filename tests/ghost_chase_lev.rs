@@ -4,9 +4,9 @@ use halo::{concurrency::worklist::GhostChaseLevDeque, GhostToken};
 fn chase_lev_single_thread_push_pop() {
     GhostToken::new(|token| {
         let d: GhostChaseLevDeque<'_> = GhostChaseLevDeque::new(64);
-        assert!(d.push_bottom(&token, 1));
-        assert!(d.push_bottom(&token, 2));
-        assert!(d.push_bottom(&token, 3));
+        d.push_bottom(&token, 1);
+        d.push_bottom(&token, 2);
+        d.push_bottom(&token, 3);
         assert_eq!(d.pop_bottom(&token), Some(3));
         assert_eq!(d.pop_bottom(&token), Some(2));
         assert_eq!(d.pop_bottom(&token), Some(1));
@@ -19,7 +19,7 @@ fn chase_lev_steal_from_other_thread() {
     GhostToken::new(|token| {
         let d: GhostChaseLevDeque<'_> = GhostChaseLevDeque::new(64);
         for i in 0..16usize {
-            assert!(d.push_bottom(&token, i));
+            d.push_bottom(&token, i);
         }
         let steal_token = token.split_immutable().0;
 
@@ -53,3 +53,112 @@ fn chase_lev_steal_from_other_thread() {
         });
     });
 }
+
+#[test]
+fn chase_lev_steal_batch_moves_half_into_own_deque() {
+    GhostToken::new(|token| {
+        let victim: GhostChaseLevDeque<'_> = GhostChaseLevDeque::new(64);
+        for i in 0..9usize {
+            victim.push_bottom(&token, i);
+        }
+        let steal_token = token.split_immutable().0;
+
+        GhostToken::new(|own_token| {
+            let own: GhostChaseLevDeque<'_> = GhostChaseLevDeque::new(4);
+            // Half of 9, rounded up; `own` grows to fit them.
+            assert_eq!(victim.steal_batch(&steal_token, &own, &own_token), 5);
+            assert_eq!(own.capacity(), 8);
+            assert_eq!(own.pop_bottom(&own_token), Some(4));
+            assert_eq!(own.pop_bottom(&own_token), Some(3));
+
+            // Half of the remaining 4 is 2: one returned, one pushed behind 0..=2.
+            assert_eq!(
+                victim.steal_batch_and_pop(&steal_token, &own, &own_token),
+                Some(5)
+            );
+            let mut rest = Vec::new();
+            while let Some(x) = own.pop_bottom(&own_token) {
+                rest.push(x);
+            }
+            assert_eq!(rest, [6, 2, 1, 0]);
+        });
+
+        assert_eq!(victim.pop_bottom(&token), Some(8));
+        assert_eq!(victim.pop_bottom(&token), Some(7));
+        assert_eq!(victim.pop_bottom(&token), None);
+    });
+}
+
+#[test]
+fn chase_lev_steal_batch_races_with_owner() {
+    const N: usize = 4096;
+    GhostToken::new(|token| {
+        // Start small so the owner grows the buffer while the thief steals.
+        let d: GhostChaseLevDeque<'_> = GhostChaseLevDeque::new(16);
+        let steal_token = token.split_immutable().0;
+        let done = std::sync::atomic::AtomicBool::new(false);
+
+        let (mine, stolen) = std::thread::scope(|s| {
+            let (d, done, steal_token) = (&d, &done, &steal_token);
+            let thief = s.spawn(move || {
+                GhostToken::new(|own_token| {
+                    let own: GhostChaseLevDeque<'_> = GhostChaseLevDeque::new(2);
+                    let mut got = Vec::new();
+                    loop {
+                        let finished = done.load(std::sync::atomic::Ordering::Acquire);
+                        got.extend(d.steal_batch_and_pop(steal_token, &own, &own_token));
+                        while let Some(x) = own.pop_bottom(&own_token) {
+                            got.push(x);
+                        }
+                        if finished {
+                            break;
+                        }
+                    }
+                    got
+                })
+            });
+
+            let mut mine = Vec::new();
+            for i in 0..N {
+                d.push_bottom(&token, i);
+                if i % 3 == 0 {
+                    mine.extend(d.pop_bottom(&token));
+                }
+            }
+            while let Some(x) = d.pop_bottom(&token) {
+                mine.push(x);
+            }
+            done.store(true, std::sync::atomic::Ordering::Release);
+            (mine, thief.join().unwrap())
+        });
+
+        let mut seen = vec![false; N];
+        for x in mine.into_iter().chain(stolen) {
+            assert!(!seen[x], "duplicate item {x}");
+            seen[x] = true;
+        }
+        assert!(seen.into_iter().all(|b| b));
+    });
+}
+
+#[test]
+fn chase_lev_grows_instead_of_failing() {
+    GhostToken::new(|token| {
+        let d: GhostChaseLevDeque<'_> = GhostChaseLevDeque::new(2);
+        let steal_token = token.split_immutable().0;
+        d.push_bottom(&token, 0);
+        assert_eq!(d.steal(&steal_token), Some(0));
+        // Wrap around the ring before growing so the copy must follow `top`.
+        for i in 1..=100usize {
+            d.push_bottom(&token, i);
+        }
+        assert_eq!(d.capacity(), 128);
+        assert_eq!(d.steal(&steal_token), Some(1));
+        assert_eq!(d.pop_bottom(&token), Some(100));
+        let mut rest = Vec::new();
+        while let Some(x) = d.steal(&steal_token) {
+            rest.push(x);
+        }
+        assert_eq!(rest, (2..100).collect::<Vec<_>>());
+    });
+}
@@ -0,0 +1,110 @@
+use halo::concurrency::sync::{
+    ghost_mpmc_channel, RecvError, SendError, TryRecvError, TrySendError,
+};
+use halo::{ghost_select, GhostToken};
+use std::thread;
+use std::time::Duration;
+
+#[test]
+fn test_mpmc_bounded_and_disconnect() {
+    GhostToken::new(|token| {
+        let (tx, rx) = ghost_mpmc_channel(2);
+        assert_eq!(tx.capacity(), 2);
+
+        tx.send(1, &token).unwrap();
+        tx.try_send(2, &token).unwrap();
+        assert_eq!(tx.try_send(3, &token), Err(TrySendError::Full(3)));
+        assert_eq!(rx.try_recv(&token), Ok(1));
+
+        let rx2 = rx.clone();
+        drop(rx);
+        tx.send(3, &token).unwrap();
+        drop(tx);
+        assert!(rx2.is_disconnected());
+        assert_eq!(rx2.recv(&token), Ok(2));
+        assert_eq!(rx2.recv(&token), Ok(3));
+        assert_eq!(rx2.recv(&token), Err(RecvError));
+        assert_eq!(rx2.try_recv(&token), Err(TryRecvError::Disconnected));
+
+        let (tx, rx) = ghost_mpmc_channel::<u8>(2);
+        drop(rx);
+        assert!(tx.is_disconnected());
+        assert_eq!(tx.send(7, &token), Err(SendError(7)));
+        assert_eq!(
+            tx.try_send(8, &token).map_err(TrySendError::into_inner),
+            Err(8)
+        );
+    });
+}
+
+#[test]
+fn test_mpmc_many_producers_and_consumers() {
+    GhostToken::new(|token| {
+        let (tx, rx) = ghost_mpmc_channel::<u64>(4);
+        let token = &token;
+
+        let total: u64 = thread::scope(|s| {
+            for p in 0..4u64 {
+                let tx = tx.clone();
+                s.spawn(move || {
+                    for i in 0..500 {
+                        tx.send(p * 1000 + i, token).unwrap();
+                    }
+                });
+            }
+            drop(tx);
+
+            let consumers: Vec<_> = (0..3)
+                .map(|_| {
+                    let rx = rx.clone();
+                    s.spawn(move || {
+                        let mut sum = 0;
+                        while let Ok(v) = rx.recv(token) {
+                            sum += v;
+                        }
+                        sum
+                    })
+                })
+                .collect();
+            consumers.into_iter().map(|c| c.join().unwrap()).sum()
+        });
+
+        let expected: u64 = (0..4u64)
+            .flat_map(|p| (0..500).map(move |i| p * 1000 + i))
+            .sum();
+        assert_eq!(total, expected);
+    });
+}
+
+#[test]
+fn test_ghost_select_blocks_until_any_channel_is_ready() {
+    GhostToken::new(|token| {
+        let (num_tx, nums) = ghost_mpmc_channel::<u32>(4);
+        let (word_tx, words) = ghost_mpmc_channel::<&str>(4);
+        let token = &token;
+
+        let empty = ghost_select! {
+            recv(nums, token) -> n => n.ok(),
+            default => None,
+        };
+        assert_eq!(empty, None);
+
+        thread::scope(|s| {
+            s.spawn(move || {
+                thread::sleep(Duration::from_millis(20));
+                word_tx.send("late", token).unwrap();
+            });
+            let got = ghost_select! {
+                recv(nums, token) -> n => format!("{n:?}"),
+                recv(words, token) -> w => w.unwrap().to_string(),
+            };
+            assert_eq!(got, "late");
+        });
+
+        drop(num_tx);
+        let disconnected = ghost_select! {
+            recv(nums, token) -> n => n,
+        };
+        assert_eq!(disconnected, Err(RecvError));
+    });
+}
@@ -1,4 +1,4 @@
-use halo::{GhostToken, GhostOnceLock, SharedGhostToken};
+use halo::{GhostOnceLock, GhostToken, SharedGhostToken};
 use std::sync::Arc;
 use std::thread;
 
@@ -92,3 +92,64 @@ fn test_ghost_once_lock_concurrent() {
         });
     });
 }
+
+#[test]
+fn test_ghost_once_lock_get_or_try_init() {
+    GhostToken::new(|token| {
+        let lock = GhostOnceLock::new();
+        assert_eq!(lock.get_or_try_init(&token, || Err("io")), Err("io"));
+        assert!(!lock.is_initialized(&token));
+        assert_eq!(lock.get_or_try_init(&token, || Ok::<_, &str>(7)), Ok(&7));
+        assert_eq!(lock.get_or_try_init(&token, || Err("unused")), Ok(&7));
+
+        let caught = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            GhostOnceLock::<u8>::new().get_or_init(&token, || panic!("init failed"));
+        }));
+        assert!(caught.is_err());
+        assert_eq!(lock.into_inner(), Some(7));
+    });
+}
+
+#[test]
+fn test_ghost_once_lock_wait_and_single_initializer() {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    GhostToken::new(|token| {
+        let (a, b) = token.split_immutable();
+        let lock = GhostOnceLock::new();
+        let runs = AtomicUsize::new(0);
+        thread::scope(|s| {
+            let waiters: Vec<_> = (0..4).map(|_| s.spawn(|| *lock.wait(&a))).collect();
+            let initializers: Vec<_> = (0..4)
+                .map(|i| {
+                    let (lock, runs) = (&lock, &runs);
+                    s.spawn(move || {
+                        lock.get_or_try_init(&b, || {
+                            runs.fetch_add(1, Ordering::Relaxed);
+                            thread::sleep(std::time::Duration::from_millis(5));
+                            // The first attempt fails; a later one must take over.
+                            if i == 0 {
+                                Err(())
+                            } else {
+                                Ok(i)
+                            }
+                        })
+                        .ok()
+                        .copied()
+                    })
+                })
+                .collect();
+            let inits: Vec<_> = initializers
+                .into_iter()
+                .map(|h| h.join().unwrap())
+                .collect();
+            let value = lock.wait(&a);
+            assert!(inits.iter().flatten().all(|v| v == value));
+            for w in waiters {
+                assert_eq!(w.join().unwrap(), *value);
+            }
+        });
+        // One success, plus at most one failed attempt before it.
+        assert!(runs.load(Ordering::Relaxed) <= 2);
+    });
+}
@@ -24,3 +24,65 @@ fn parallel_read_then_commit_applies_updates() {
         assert_eq!(*b.borrow(&token), 10);
     });
 }
+
+#[test]
+fn wait_group_tracks_dynamically_spawned_subtasks() {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    // Sums a range of cells, handing one half of larger ranges to a subtask.
+    fn visit<'scope, 'env, 'brand>(
+        scope: scoped::GhostReadScope<'scope, 'env, 'brand>,
+        wg: scoped::GhostWaitGroup<'brand>,
+        cells: &'env [GhostCell<'brand, usize>],
+        sum: &'env AtomicUsize,
+        token: &'env GhostToken<'brand>,
+    ) {
+        if cells.len() <= 4 {
+            let local: usize = cells.iter().map(|c| *c.borrow(token)).sum();
+            sum.fetch_add(local, Ordering::SeqCst);
+            return;
+        }
+        let (left, right) = cells.split_at(cells.len() / 2);
+        let sub = wg.clone();
+        scope.spawn(move |t| visit(scope, sub, left, sum, t));
+        visit(scope, wg, right, sum, token);
+    }
+
+    GhostToken::new(|token| {
+        let cells: Vec<GhostCell<'_, usize>> = (0..64).map(GhostCell::new).collect();
+        let sum = AtomicUsize::new(0);
+
+        scoped::with_read_scope(&token, |scope| {
+            let wg = scoped::GhostWaitGroup::new();
+            let task = wg.clone();
+            let (cells, sum) = (&cells, &sum);
+            scope.spawn(move |t| visit(scope, task, cells, sum, t));
+            wg.wait(&token);
+            // Every subtask has finished, though its thread may not have been joined yet.
+            assert_eq!(sum.load(Ordering::SeqCst), (0..64).sum::<usize>());
+        });
+    });
+}
+
+#[test]
+fn broadcast_runs_once_per_worker() {
+    GhostToken::new(|token| {
+        let cells: Vec<GhostCell<'_, u64>> = (0..1000).map(GhostCell::new).collect();
+        let parts = scoped::broadcast(&token, 4, |t, worker| {
+            cells
+                .iter()
+                .skip(worker)
+                .step_by(4)
+                .map(|c| *c.borrow(t))
+                .sum::<u64>()
+        });
+        assert_eq!(parts.len(), 4);
+        assert_eq!(parts.iter().sum::<u64>(), 999 * 1000 / 2);
+        assert_eq!(parts[1], (0..1000).skip(1).step_by(4).sum());
+
+        let caught = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            scoped::broadcast(&token, 3, |_, worker| assert_ne!(worker, 2));
+        }));
+        assert!(caught.is_err());
+    });
+}
@@ -0,0 +1,125 @@
+use halo::collections::BrandedVec;
+use halo::concurrency::pool::{GhostTaskContext, GhostThreadPool};
+use halo::{GhostCell, GhostToken};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+
+#[test]
+fn thread_pool_runs_nested_spawns_to_completion() {
+    GhostToken::new(|token| {
+        // A complete binary tree in heap order, traversed by spawning one task per child.
+        let depth = 12;
+        let nodes: Vec<GhostCell<'_, usize>> = (0..(1 << depth) - 1).map(GhostCell::new).collect();
+        let visited = AtomicUsize::new(0);
+        let sum = AtomicUsize::new(0);
+        let workers = Mutex::new(vec![false; 4]);
+
+        fn visit<'env, 'brand>(
+            ctx: &GhostTaskContext<'_, 'env, 'brand>,
+            nodes: &'env [GhostCell<'brand, usize>],
+            i: usize,
+            visited: &'env AtomicUsize,
+            sum: &'env AtomicUsize,
+            workers: &'env Mutex<Vec<bool>>,
+        ) {
+            visited.fetch_add(1, Ordering::Relaxed);
+            sum.fetch_add(*nodes[i].borrow(&ctx.token()), Ordering::Relaxed);
+            workers.lock().unwrap()[ctx.worker_index()] = true;
+            for child in [2 * i + 1, 2 * i + 2] {
+                if child < nodes.len() {
+                    ctx.spawn(move |ctx| visit(ctx, nodes, child, visited, sum, workers));
+                }
+            }
+        }
+
+        let pool = GhostThreadPool::new(4);
+        let returned = pool.scope(&token, |s| {
+            assert_eq!(*nodes[0].borrow(&s.token()), 0);
+            let (nodes, visited, sum, workers) = (&nodes[..], &visited, &sum, &workers);
+            s.spawn(move |ctx| {
+                assert_eq!(ctx.threads(), 4);
+                visit(ctx, nodes, 0, visited, sum, workers);
+            });
+            "done"
+        });
+
+        let n = nodes.len();
+        assert_eq!(returned, "done");
+        assert_eq!(visited.into_inner(), n);
+        assert_eq!(sum.into_inner(), n * (n - 1) / 2);
+        assert!(workers.into_inner().unwrap().iter().any(|&w| w));
+    });
+}
+
+#[test]
+fn thread_pool_scopes_can_be_reused() {
+    GhostToken::new(|token| {
+        let pool = GhostThreadPool::new(2);
+        assert_eq!(pool.threads(), 2);
+        for round in 0..20 {
+            let count = AtomicUsize::new(0);
+            pool.scope(&token, |s| {
+                for _ in 0..round {
+                    s.spawn(|_| {
+                        count.fetch_add(1, Ordering::Relaxed);
+                    });
+                }
+            });
+            assert_eq!(count.into_inner(), round);
+        }
+    });
+}
+
+#[test]
+fn thread_pool_writes_disjoint_regions() {
+    GhostToken::new(|token| {
+        let input: Vec<GhostCell<'_, u64>> = (0..1000).map(GhostCell::new).collect();
+
+        GhostToken::new(|mut out_token| {
+            let mut out = BrandedVec::new();
+            for _ in 0..input.len() {
+                out.push(0u64);
+            }
+            let ranges = (0..10).map(|k| k * 100..(k + 1) * 100);
+            let views = out.split_views(&mut out_token, ranges);
+
+            let input = &input[..];
+            GhostThreadPool::new(3).scope(&token, |s| {
+                s.spawn_regions(views, move |ctx, view| {
+                    let offset = view.offset();
+                    let read = ctx.token();
+                    view.with_region(|cells, mut region| {
+                        for (i, cell) in cells.iter().enumerate() {
+                            *cell.borrow_mut(&mut region) = input[offset + i].borrow(&read) * 2;
+                        }
+                    });
+                });
+            });
+
+            for i in 0..input.len() {
+                assert_eq!(out.get(&out_token, i), Some(&(2 * i as u64)));
+            }
+        });
+    });
+}
+
+#[test]
+fn thread_pool_resumes_task_panics_after_draining() {
+    GhostToken::new(|token| {
+        let finished = AtomicUsize::new(0);
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            GhostThreadPool::new(2).scope(&token, |s| {
+                s.spawn(|_| panic!("task failed"));
+                for _ in 0..50 {
+                    s.spawn(|_| {
+                        finished.fetch_add(1, Ordering::Relaxed);
+                    });
+                }
+            })
+        }));
+
+        let payload = result.unwrap_err();
+        assert_eq!(payload.downcast_ref::<&str>(), Some(&"task failed"));
+        assert_eq!(finished.into_inner(), 50);
+    });
+}
@@ -1,6 +1,9 @@
 use core::sync::atomic::{AtomicUsize, Ordering};
 
-use halo::{concurrency::worklist::GhostTreiberStack, GhostToken};
+use halo::{
+    concurrency::worklist::{EliminationStats, GhostTreiberStack},
+    GhostToken,
+};
 
 #[test]
 fn treiber_stack_single_thread_lifo() {
@@ -67,3 +70,37 @@ fn treiber_stack_multi_thread_unique_push_pop() {
         assert_eq!(s.pop(&token), None);
     });
 }
+
+#[test]
+fn treiber_stack_with_elimination_keeps_every_index() {
+    const N: usize = 4096;
+    GhostToken::new(|token| {
+        let s: GhostTreiberStack<'_> = GhostTreiberStack::with_elimination(N, 4);
+        assert_eq!(s.elimination_slots(), 4);
+        let popped: Vec<AtomicUsize> = (0..N).map(|_| AtomicUsize::new(0)).collect();
+
+        std::thread::scope(|scope| {
+            let (s, token, popped) = (&s, &token, &popped);
+            for t in 0..4 {
+                scope.spawn(move || {
+                    // Interleave pushes and pops so they contend and can eliminate.
+                    for i in (t * N / 4)..((t + 1) * N / 4) {
+                        s.push(token, i);
+                        if let Some(j) = s.pop(token) {
+                            popped[j].fetch_add(1, Ordering::Relaxed);
+                        }
+                    }
+                });
+            }
+        });
+        while let Some(j) = s.pop(&token) {
+            popped[j].fetch_add(1, Ordering::Relaxed);
+        }
+
+        assert!(popped.iter().all(|c| c.load(Ordering::Relaxed) == 1));
+        let stats = s.elimination_stats();
+        assert!(stats.eliminated + stats.expired_offers <= stats.head_contention);
+        s.reset_elimination_stats();
+        assert_eq!(s.elimination_stats(), EliminationStats::default());
+    });
+}
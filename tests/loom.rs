@@ -0,0 +1,88 @@
+//! Model checks of the lock-free structures under every interleaving loom explores.
+//!
+//! ```text
+//! RUSTFLAGS="--cfg loom" cargo test --release --test loom
+//! ```
+#![cfg(loom)]
+
+use halo::concurrency::sync::GhostRingBuffer;
+use halo::concurrency::worklist::{GhostChaseLevDeque, GhostTreiberStack};
+use halo::token::global::static_child_token;
+use halo::token::with_static_token_mut;
+use loom::sync::Arc;
+use loom::thread;
+
+#[test]
+fn treiber_stack_push_pop() {
+    loom::model(|| {
+        let stack = Arc::new(GhostTreiberStack::new(2));
+        let token = static_child_token();
+        stack.push(&token, 0);
+
+        let other = Arc::clone(&stack);
+        let handle = thread::spawn(move || {
+            other.push(&token, 1);
+            other.pop(&token)
+        });
+        let mine = stack.pop(&token);
+        let theirs = handle.join().unwrap();
+        let rest = stack.pop(&token);
+
+        // Each index is popped exactly once.
+        let mut popped: Vec<_> = [mine, theirs, rest].into_iter().flatten().collect();
+        popped.sort_unstable();
+        assert_eq!(popped, [0, 1]);
+        assert_eq!(stack.pop(&token), None);
+    });
+}
+
+#[test]
+fn chase_lev_owner_races_stealer() {
+    loom::model(|| {
+        let deque = Arc::new(GhostChaseLevDeque::new(2));
+        let thief = Arc::clone(&deque);
+        // SAFETY: the static brand brands nothing but this deque; the mutable token
+        // only marks this thread as its owner, and the stealer uses a read token.
+        unsafe {
+            with_static_token_mut(|owner| {
+                deque.push_bottom(owner, 1);
+                deque.push_bottom(owner, 2);
+                let handle = thread::spawn(move || thief.steal(&static_child_token()));
+                // Pushing a third item grows the buffer under the stealer.
+                deque.push_bottom(owner, 3);
+                let mut taken: Vec<_> = core::iter::from_fn(|| deque.pop_bottom(owner)).collect();
+                taken.extend(handle.join().unwrap());
+
+                // Every item is taken exactly once, by the owner or the stealer.
+                taken.sort_unstable();
+                assert_eq!(taken, [1, 2, 3]);
+            });
+        }
+    });
+}
+
+#[test]
+fn ring_buffer_producer_consumer() {
+    loom::model(|| {
+        let ring = Arc::new(GhostRingBuffer::<usize>::new(2));
+        let producer = Arc::clone(&ring);
+        let handle = thread::spawn(move || {
+            for i in 0..3 {
+                while producer.try_push(i).is_err() {
+                    thread::yield_now();
+                }
+            }
+        });
+        let mut received = Vec::new();
+        while received.len() < 3 {
+            match ring.try_pop() {
+                Some(i) => received.push(i),
+                None => thread::yield_now(),
+            }
+        }
+        handle.join().unwrap();
+
+        // A single producer's items arrive in order.
+        assert_eq!(received, [0, 1, 2]);
+    });
+}
@@ -0,0 +1,404 @@
+//! Development tasks for `halo`, run as `cargo xtask <task>`.
+//!
+//! Tasks:
+//! - `profile --workload <bench>`: builds one of the Criterion benchmarks in
+//!   `benches/`, samples it under `perf` (Linux) or `dtrace` (macOS), and renders a
+//!   flamegraph SVG into `benchmark_results/`.
+//! - `bench-gate --baseline <name>`: compares the latest Criterion results against a
+//!   baseline saved with `cargo bench -- --save-baseline <name>` and exits non-zero on
+//!   regressions (runs the `bench_report` example).
+//!
+//! Stack folding and rendering use `inferno` (`cargo install inferno`) when it is on
+//! `PATH`, falling back to Brendan Gregg's `stackcollapse-*.pl` / `flamegraph.pl`.
+
+use std::{
+    env,
+    ffi::OsString,
+    fs,
+    path::{Path, PathBuf},
+    process::{self, Command, Stdio},
+};
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+enum Tool {
+    Perf,
+    Dtrace,
+}
+
+impl Tool {
+    fn name(self) -> &'static str {
+        match self {
+            Tool::Perf => "perf",
+            Tool::Dtrace => "dtrace",
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+struct ProfileArgs {
+    workload: String,
+    tool: Tool,
+    /// Sampling frequency in Hz.
+    frequency: u32,
+    /// Seconds Criterion spends iterating each benchmark (`--profile-time`).
+    seconds: u32,
+    /// Criterion benchmark-name filter.
+    filter: Option<String>,
+    output: Option<PathBuf>,
+}
+
+fn main() {
+    let mut args = env::args().skip(1);
+    match args.next().as_deref() {
+        Some("profile") => {
+            let args = parse_profile_args(args).unwrap_or_else(|msg| usage_exit(&msg));
+            if let Err(msg) = profile(&args) {
+                eprintln!("error: {msg}");
+                process::exit(1);
+            }
+        }
+        Some("bench-gate") => process::exit(bench_gate(args)),
+        Some("-h" | "--help") => usage(),
+        Some(other) => usage_exit(&format!("unknown task `{other}`")),
+        None => usage_exit("missing task"),
+    }
+}
+
+fn usage() {
+    eprintln!(
+        "usage: cargo xtask profile --workload <bench> [options]
+       cargo xtask bench-gate --baseline <name> [--threshold <ratio>] [--stat mean|median]
+
+profile options:
+  --workload <bench>   benchmark in benches/ to profile, e.g. alloc_benchmark
+  --tool <perf|dtrace> sampler (default: perf on Linux, dtrace elsewhere)
+  --frequency <hz>     sampling frequency (default: 997)
+  --seconds <n>        time spent iterating each benchmark (default: 5)
+  --filter <name>      only run Criterion benchmarks matching <name>
+  --output <path>      SVG path (default: benchmark_results/flamegraph-<bench>.svg)"
+    );
+}
+
+fn usage_exit(msg: &str) -> ! {
+    eprintln!("error: {msg}\n");
+    usage();
+    process::exit(2)
+}
+
+fn parse_profile_args(mut args: impl Iterator<Item = String>) -> Result<ProfileArgs, String> {
+    let mut workload = None;
+    let mut tool = if cfg!(target_os = "linux") {
+        Tool::Perf
+    } else {
+        Tool::Dtrace
+    };
+    let mut frequency = 997;
+    let mut seconds = 5;
+    let mut filter = None;
+    let mut output = None;
+
+    while let Some(arg) = args.next() {
+        let mut value = || args.next().ok_or(format!("missing value for {arg}"));
+        match arg.as_str() {
+            "--workload" => workload = Some(value()?),
+            "--tool" => {
+                tool = match value()?.as_str() {
+                    "perf" => Tool::Perf,
+                    "dtrace" => Tool::Dtrace,
+                    _ => return Err("invalid value for --tool (expected: perf|dtrace)".into()),
+                }
+            }
+            "--frequency" => {
+                frequency = value()?
+                    .parse()
+                    .map_err(|_| "invalid integer for --frequency")?
+            }
+            "--seconds" => {
+                seconds = value()?
+                    .parse()
+                    .map_err(|_| "invalid integer for --seconds")?
+            }
+            "--filter" => filter = Some(value()?),
+            "--output" => output = Some(PathBuf::from(value()?)),
+            _ => return Err(format!("unknown option `{arg}`")),
+        }
+    }
+
+    Ok(ProfileArgs {
+        workload: workload.ok_or("missing --workload")?,
+        tool,
+        frequency,
+        seconds,
+        filter,
+        output,
+    })
+}
+
+fn workspace_root() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .parent()
+        .expect("xtask lives one level below the workspace root")
+        .to_path_buf()
+}
+
+fn available_workloads(root: &Path) -> Vec<String> {
+    let mut names: Vec<String> = fs::read_dir(root.join("benches"))
+        .into_iter()
+        .flatten()
+        .flatten()
+        .filter_map(|entry| {
+            let path = entry.path();
+            (path.extension()? == "rs")
+                .then(|| path.file_stem()?.to_str().map(str::to_owned))
+                .flatten()
+        })
+        .collect();
+    names.sort();
+    names
+}
+
+fn profile(args: &ProfileArgs) -> Result<(), String> {
+    let root = workspace_root();
+    let workloads = available_workloads(&root);
+    if !workloads.contains(&args.workload) {
+        return Err(format!(
+            "unknown workload `{}`; available: {}",
+            args.workload,
+            workloads.join(", ")
+        ));
+    }
+    let (collapse, render) = flamegraph_tools(args.tool)?;
+    if !on_path(args.tool.name()) {
+        return Err(format!("`{}` was not found on PATH", args.tool.name()));
+    }
+
+    let out_dir = root.join("benchmark_results");
+    fs::create_dir_all(&out_dir).map_err(|e| format!("creating {}: {e}", out_dir.display()))?;
+    let svg = args
+        .output
+        .clone()
+        .unwrap_or_else(|| out_dir.join(format!("flamegraph-{}.svg", args.workload)));
+
+    let bench = build_bench(&root, &args.workload)?;
+    eprintln!("profiling {} with {}", bench.display(), args.tool.name());
+
+    let mut bench_args: Vec<OsString> = vec![
+        "--bench".into(),
+        "--profile-time".into(),
+        args.seconds.to_string().into(),
+    ];
+    bench_args.extend(args.filter.iter().map(OsString::from));
+
+    let stacks = out_dir.join(format!("{}.stacks", args.workload));
+    match args.tool {
+        Tool::Perf => {
+            let data = out_dir.join(format!("{}.perf.data", args.workload));
+            run(Command::new("perf")
+                .args(["record", "-g", "--call-graph", "dwarf", "-F"])
+                .arg(args.frequency.to_string())
+                .arg("-o")
+                .arg(&data)
+                .arg("--")
+                .arg(&bench)
+                .args(&bench_args)
+                .current_dir(&root))?;
+            let script = Command::new("perf")
+                .arg("script")
+                .arg("-i")
+                .arg(&data)
+                .stderr(Stdio::inherit())
+                .output()
+                .map_err(|e| format!("running perf script: {e}"))?;
+            if !script.status.success() {
+                return Err(format!("perf script failed with {}", script.status));
+            }
+            fs::write(&stacks, script.stdout)
+                .map_err(|e| format!("writing {}: {e}", stacks.display()))?;
+        }
+        Tool::Dtrace => {
+            let mut command = bench.clone().into_os_string();
+            for arg in &bench_args {
+                command.push(" ");
+                command.push(arg);
+            }
+            run(Command::new("dtrace")
+                .args(["-x", "ustackframes=100", "-n"])
+                .arg(format!(
+                    "profile-{} /pid == $target/ {{ @[ustack()] = count(); }}",
+                    args.frequency
+                ))
+                .arg("-c")
+                .arg(command)
+                .arg("-o")
+                .arg(&stacks)
+                .current_dir(&root))?;
+        }
+    }
+
+    let folded = pipe(&collapse, &stacks)?;
+    let folded_path = out_dir.join(format!("{}.folded", args.workload));
+    fs::write(&folded_path, folded)
+        .map_err(|e| format!("writing {}: {e}", folded_path.display()))?;
+    let image = pipe(&render, &folded_path)?;
+    fs::write(&svg, image).map_err(|e| format!("writing {}: {e}", svg.display()))?;
+
+    eprintln!("wrote {}", svg.display());
+    Ok(())
+}
+
+/// Forwards `args` to the `bench_report` example and returns its exit code.
+fn bench_gate(args: impl Iterator<Item = String>) -> i32 {
+    let args: Vec<String> = args.collect();
+    if !args.iter().any(|a| a == "--baseline") {
+        usage_exit("bench-gate needs --baseline <name>");
+    }
+    let cargo = env::var_os("CARGO").unwrap_or_else(|| "cargo".into());
+    let criterion_dir = workspace_root().join("target").join("criterion");
+    let status = Command::new(cargo)
+        .args(["run", "--quiet", "--example", "bench_report", "--"])
+        .arg("--criterion-dir")
+        .arg(criterion_dir)
+        .args(&args)
+        .current_dir(workspace_root())
+        .status();
+    match status {
+        Ok(status) => status.code().unwrap_or(1),
+        Err(e) => {
+            eprintln!("error: running bench_report: {e}");
+            1
+        }
+    }
+}
+
+/// Builds the benchmark with debug info and returns the path of its executable.
+fn build_bench(root: &Path, workload: &str) -> Result<PathBuf, String> {
+    let cargo = env::var_os("CARGO").unwrap_or_else(|| "cargo".into());
+    let output = Command::new(cargo)
+        .args(["bench", "--no-run", "--message-format=json", "--bench", workload])
+        .env("CARGO_PROFILE_BENCH_DEBUG", "true")
+        .current_dir(root)
+        .stderr(Stdio::inherit())
+        .output()
+        .map_err(|e| format!("running cargo bench: {e}"))?;
+    if !output.status.success() {
+        return Err(format!("building `{workload}` failed with {}", output.status));
+    }
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter(|line| line.contains(&format!("\"name\":\"{workload}\"")))
+        .find_map(executable_of)
+        .ok_or_else(|| format!("cargo did not report an executable for `{workload}`"))
+}
+
+/// Extracts the `"executable"` path from one line of cargo's JSON messages.
+fn executable_of(line: &str) -> Option<PathBuf> {
+    let start = line.find("\"executable\":\"")? + "\"executable\":\"".len();
+    let rest = &line[start..];
+    let mut path = String::new();
+    let mut chars = rest.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' => return Some(PathBuf::from(path)),
+            '\\' => path.push(chars.next()?),
+            c => path.push(c),
+        }
+    }
+    None
+}
+
+/// Picks the stack-collapsing and rendering commands for `tool`.
+fn flamegraph_tools(tool: Tool) -> Result<(String, String), String> {
+    let inferno_collapse = format!("inferno-collapse-{}", tool.name());
+    if on_path(&inferno_collapse) && on_path("inferno-flamegraph") {
+        return Ok((inferno_collapse, "inferno-flamegraph".into()));
+    }
+    let perl_collapse = format!("stackcollapse-{}.pl", tool.name());
+    if on_path(&perl_collapse) && on_path("flamegraph.pl") {
+        return Ok((perl_collapse, "flamegraph.pl".into()));
+    }
+    Err(format!(
+        "no flamegraph renderer found; install one with `cargo install inferno` \
+         (needs `{inferno_collapse}` and `inferno-flamegraph` on PATH)"
+    ))
+}
+
+fn on_path(program: &str) -> bool {
+    env::var_os("PATH")
+        .map(|paths| env::split_paths(&paths).any(|dir| dir.join(program).is_file()))
+        .unwrap_or(false)
+}
+
+fn run(command: &mut Command) -> Result<(), String> {
+    let status = command
+        .status()
+        .map_err(|e| format!("running {command:?}: {e}"))?;
+    if status.success() {
+        Ok(())
+    } else {
+        Err(format!("{command:?} failed with {status}"))
+    }
+}
+
+/// Runs `program` on the file `input` and returns its standard output.
+fn pipe(program: &str, input: &Path) -> Result<Vec<u8>, String> {
+    let output = Command::new(program)
+        .arg(input)
+        .stderr(Stdio::inherit())
+        .output()
+        .map_err(|e| format!("running {program}: {e}"))?;
+    if output.status.success() {
+        Ok(output.stdout)
+    } else {
+        Err(format!("{program} failed with {}", output.status))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Result<ProfileArgs, String> {
+        parse_profile_args(args.iter().map(|s| s.to_string()))
+    }
+
+    #[test]
+    fn parses_profile_args() {
+        let args = parse(&[
+            "--workload",
+            "alloc_benchmark",
+            "--tool",
+            "dtrace",
+            "--seconds",
+            "2",
+            "--filter",
+            "slab",
+        ])
+        .unwrap();
+        assert_eq!(args.workload, "alloc_benchmark");
+        assert_eq!(args.tool, Tool::Dtrace);
+        assert_eq!(args.seconds, 2);
+        assert_eq!(args.frequency, 997);
+        assert_eq!(args.filter.as_deref(), Some("slab"));
+
+        assert!(parse(&[]).unwrap_err().contains("--workload"));
+        assert!(parse(&["--workload"]).unwrap_err().contains("missing value"));
+        assert!(parse(&["--workload", "x", "--tool", "vtune"]).is_err());
+    }
+
+    #[test]
+    fn extracts_executable_from_cargo_json() {
+        let line = r#"{"reason":"compiler-artifact","target":{"name":"graph_benchmark"},"executable":"C:\\t\\graph-1a2b.exe","fresh":true}"#;
+        assert_eq!(
+            executable_of(line),
+            Some(PathBuf::from(r"C:\t\graph-1a2b.exe"))
+        );
+        assert_eq!(executable_of(r#"{"executable":null}"#), None);
+    }
+
+    #[test]
+    fn lists_bench_workloads() {
+        let workloads = available_workloads(&workspace_root());
+        assert!(workloads.iter().any(|w| w == "alloc_benchmark"));
+        assert!(workloads.iter().any(|w| w == "graph_benchmark"));
+    }
+}